@@ -1,4 +1,4 @@
-use mcje_downloader::RootManifest;
+use mcje_downloader::{CACHE_DIR_VAR, Cache, ENV_VARS, Error, OFFLINE_VAR, RuleContext};
 use std::{path::PathBuf, time::Duration};
 use tokio::{fs, time};
 
@@ -6,33 +6,36 @@ use tokio::{fs, time};
 async fn main() {
     let manifest_dir = env!("CARGO_MANIFEST_DIR");
     let manifest_dir = PathBuf::from(manifest_dir);
-    let root_manifest = RootManifest::fetch().await.unwrap();
+    println!("cargo:rerun-if-changed=../../mc-version");
+    for var in ENV_VARS {
+        println!("cargo:rerun-if-env-changed={var}");
+    }
     let target_mc_version = fs::read_to_string(manifest_dir.join("../../mc-version"))
         .await
         .unwrap()
         .trim()
         .to_string();
-    let version_release = root_manifest
-        .versions
-        .into_iter()
-        .find(|ver| ver.id == target_mc_version)
-        .unwrap();
-
-    let version_manifest = version_release.fetch_manifest().await.unwrap();
-
-    let root_path = manifest_dir.join("../../target/").join("downloads");
-    let main_path = root_path.join("mc.jar");
 
-    if !root_path.exists() {
-        fs::create_dir_all(&root_path).await.unwrap();
-    }
-
-    if !main_path.exists() {
-        let client = version_manifest.downloads.client.download().await.unwrap();
-        fs::write(&main_path, client).await.unwrap();
-    }
+    let cache = Cache::from_env();
+    let version_manifest = cache
+        .version_manifest(&target_mc_version)
+        .await
+        .unwrap_or_else(|err| {
+            fail(
+                &cache,
+                &format!("resolve minecraft {target_mc_version}"),
+                err,
+            )
+        });
 
-    let libs_root = root_path.join("libs");
+    let main_path = version_manifest
+        .downloads
+        .as_ref()
+        .expect("the version has no downloads")
+        .client
+        .fetch(&cache)
+        .await
+        .unwrap_or_else(|err| fail(&cache, "fetch the client jar", err));
 
     let mut classpath = main_path.to_str().unwrap().to_string();
     #[cfg(target_os = "windows")]
@@ -40,28 +43,33 @@ async fn main() {
     #[cfg(not(target_os = "windows"))]
     let sep = ":";
 
-    for lib in version_manifest.libraries {
-        if lib.rules.iter().all(|rule| rule.allow()) {
-            let name = lib.name.replace([';', ':'], "-");
-            let lib_path = libs_root.join(if lib.name.ends_with(".jar") {
-                name
-            } else {
-                format!("{}.jar", name)
-            });
-            classpath += &format!("{}{}", sep, lib_path.to_str().unwrap());
-            if !lib_path.exists() {
-                // avoid rate limit
-                time::sleep(Duration::from_millis(100)).await;
-                let lib_source = lib.downloads.artifact.download().await.unwrap();
-                if let Some(parent) = lib_path.parent()
-                    && !parent.exists()
-                {
-                    fs::create_dir_all(parent).await.unwrap();
-                }
-                fs::write(&lib_path, lib_source).await.unwrap();
-            }
+    let context = RuleContext::current();
+    for lib in version_manifest.libraries_for(&context) {
+        // natives-only libraries have nothing for the classpath
+        let Some(artifact) = lib.artifact() else {
+            continue;
+        };
+        if !cache.object_path(&artifact.sha1).exists() {
+            // avoid rate limit
+            time::sleep(Duration::from_millis(100)).await;
         }
+        let lib_path = artifact
+            .fetch(&cache)
+            .await
+            .unwrap_or_else(|err| fail(&cache, &format!("fetch {}", lib.name), err));
+        classpath += &format!("{}{}", sep, lib_path.to_str().unwrap());
     }
 
     println!("cargo:rustc-env=MCJE_JVM_CLASSPATH={classpath}");
 }
+
+fn fail(cache: &Cache, what: &str, err: Error) -> ! {
+    match err {
+        Error::NotCached(missing) => panic!(
+            "failed to {what}: {missing} is not in the cache at `{}` and offline mode is on; \
+             build once with network access, or point {CACHE_DIR_VAR} at a cache that has it",
+            cache.dir().display()
+        ),
+        err => panic!("failed to {what}: {err}; set {OFFLINE_VAR} to build from the cache alone"),
+    }
+}
@@ -0,0 +1,444 @@
+//! Wrappers around the vanilla and JDK classes the generators and parity
+//! tests read.
+
+use jni::objects::JObject;
+
+use crate::{Iterable, java_class};
+
+java_class! {
+    #[class = "java/lang/Class"]
+    pub struct JClassObject {
+        #[method = "getSimpleName"]
+        fn simple_name(&self) -> String;
+    }
+
+    #[class = "java/lang/Boolean"]
+    pub struct JBoolean {
+        #[method]
+        fn boolean_value(&self) -> bool;
+    }
+
+    #[class = "java/lang/Integer"]
+    pub struct JInteger {
+        #[method]
+        fn int_value(&self) -> i32;
+    }
+
+    #[class = "java/lang/Enum"]
+    pub struct JEnum {
+        #[method]
+        fn ordinal(&self) -> i32;
+    }
+
+    #[class = "java/lang/ClassLoader"]
+    pub struct JClassLoader {
+        /// A file on the classpath, `None` if there is none.
+        #[method]
+        #[java = "java/io/InputStream"]
+        fn get_system_resource_as_stream(
+            #[java = "java/lang/String"] name: &JObject<'local>,
+        ) -> Option<JObject<'local>>;
+    }
+
+    #[class = "java/io/InputStreamReader"]
+    pub struct JInputStreamReader {
+        #[constructor]
+        fn new(#[java = "java/io/InputStream"] stream: &JObject<'local>) -> Self;
+    }
+
+    #[class = "java/util/Iterator"]
+    pub struct JIterator {
+        #[method]
+        fn has_next(&self) -> bool;
+        #[method]
+        fn next(&self) -> JObject<'local>;
+    }
+
+    #[class = "java/util/Collection"]
+    pub struct JCollection {
+        #[method]
+        fn size(&self) -> i32;
+    }
+
+    #[class = "java/util/List"]
+    pub struct JList {
+        #[method]
+        fn size(&self) -> i32;
+        #[method]
+        fn get(&self, index: i32) -> JObject<'local>;
+    }
+
+    #[class = "java/util/Map"]
+    pub struct JMap {
+        #[method]
+        #[java = "java/util/Set"]
+        fn entry_set(&self) -> JCollection<'local>;
+    }
+
+    #[class = "java/util/Map$Entry"]
+    pub struct JMapEntry {
+        #[method = "getKey"]
+        fn key(&self) -> JObject<'local>;
+        #[method = "getValue"]
+        fn value(&self) -> JObject<'local>;
+    }
+
+    #[class = "com/google/gson/JsonParser"]
+    pub struct JJsonParser {
+        #[method]
+        fn parse_reader(
+            #[java = "java/io/Reader"] reader: &JInputStreamReader<'local>,
+        ) -> JJsonElement<'local>;
+    }
+
+    #[class = "com/google/gson/JsonElement"]
+    pub struct JJsonElement {
+        #[method]
+        fn is_json_object(&self) -> bool;
+        #[method]
+        fn get_as_json_object(&self) -> JJsonObject<'local>;
+        #[method]
+        fn get_as_json_array(&self) -> JJsonArray<'local>;
+        #[method]
+        fn get_as_string(&self) -> String;
+    }
+
+    #[class = "com/google/gson/JsonObject"]
+    pub struct JJsonObject {
+        /// The member `name`, `None` if there is none.
+        #[method]
+        fn get(
+            &self,
+            #[java = "java/lang/String"] name: &JObject<'local>,
+        ) -> Option<JJsonElement<'local>>;
+    }
+
+    #[class = "com/google/gson/JsonArray"]
+    pub struct JJsonArray {}
+
+    #[class = "net/minecraft/resources/Identifier"]
+    pub struct JIdentifier {
+        #[field]
+        fn namespace(&self) -> String;
+        #[field]
+        fn path(&self) -> String;
+    }
+
+    #[class = "net/minecraft/network/chat/Component"]
+    pub struct JComponent {
+        #[method = "getString"]
+        fn string(&self) -> String;
+    }
+
+    #[class = "net/minecraft/core/registries/BuiltInRegistries"]
+    pub struct JBuiltInRegistries {
+        #[field = "BLOCK"]
+        #[java = "net/minecraft/core/DefaultedRegistry"]
+        fn block() -> JRegistry<'local>;
+        #[field = "FLUID"]
+        #[java = "net/minecraft/core/DefaultedRegistry"]
+        fn fluid() -> JRegistry<'local>;
+        #[field = "ITEM"]
+        #[java = "net/minecraft/core/DefaultedRegistry"]
+        fn item() -> JRegistry<'local>;
+        #[field = "DATA_COMPONENT_TYPE"]
+        fn data_component_type() -> JRegistry<'local>;
+    }
+
+    #[class = "net/minecraft/core/Registry"]
+    pub struct JRegistry {
+        #[method = "getId"]
+        fn id(&self, value: &JObject<'local>) -> i32;
+        #[method = "getKey"]
+        fn key(&self, value: &JObject<'local>) -> JIdentifier<'local>;
+        #[method]
+        #[java = "java/lang/Object"]
+        fn by_id(&self, id: i32) -> Option<JObject<'local>>;
+    }
+
+    #[class = "net/minecraft/core/IdMapper"]
+    pub struct JIdMapper {
+        #[method = "getId"]
+        fn id(&self, value: &JObject<'local>) -> i32;
+        #[method]
+        #[java = "java/lang/Object"]
+        fn by_id(&self, id: i32) -> Option<JObject<'local>>;
+        #[method]
+        fn size(&self) -> i32;
+    }
+
+    #[class = "net/minecraft/core/BlockPos"]
+    pub struct JBlockPos {
+        #[field = "ZERO"]
+        fn zero() -> Self;
+        #[constructor]
+        fn new(x: i32, y: i32, z: i32) -> Self;
+    }
+
+    /// A level with nothing but air, to read shapes without a world.
+    #[class = "net/minecraft/world/level/EmptyBlockGetter"]
+    pub struct JEmptyBlockGetter {
+        #[field = "INSTANCE"]
+        fn instance() -> Self;
+    }
+
+    #[class = "net/minecraft/world/phys/AABB"]
+    pub struct JAabb {
+        #[field]
+        fn min_x(&self) -> f64;
+        #[field]
+        fn min_y(&self) -> f64;
+        #[field]
+        fn min_z(&self) -> f64;
+        #[field]
+        fn max_x(&self) -> f64;
+        #[field]
+        fn max_y(&self) -> f64;
+        #[field]
+        fn max_z(&self) -> f64;
+    }
+
+    #[class = "net/minecraft/world/phys/shapes/VoxelShape"]
+    pub struct JVoxelShape {
+        /// The boxes making up the shape, as `AABB`s.
+        #[method]
+        fn to_aabbs(&self) -> JList<'local>;
+    }
+
+    #[class = "net/minecraft/world/phys/Vec3"]
+    pub struct JVec3 {
+        #[field]
+        fn x(&self) -> f64;
+        #[field]
+        fn y(&self) -> f64;
+        #[field]
+        fn z(&self) -> f64;
+    }
+
+    #[class = "net/minecraft/world/level/block/Block"]
+    pub struct JBlock {
+        /// Every block state by its id.
+        #[field = "BLOCK_STATE_REGISTRY"]
+        fn state_registry() -> JIdMapper<'local>;
+        /// The id of `state` in [`JBlock::state_registry`].
+        #[method = "getId"]
+        fn state_id(state: &JBlockState<'local>) -> i32;
+        #[field]
+        fn default_block_state(&self) -> JBlockState<'local>;
+        #[field]
+        fn state_definition(&self) -> JStateDefinition<'local>;
+        #[method = "getName"]
+        #[java = "net/minecraft/network/chat/MutableComponent"]
+        fn name(&self) -> JComponent<'local>;
+        #[method = "getDescriptionId"]
+        fn description_id(&self) -> String;
+        #[method = "getMaxHorizontalOffset"]
+        fn max_horizontal_offset(&self) -> f32;
+        #[method = "getMaxVerticalOffset"]
+        fn max_vertical_offset(&self) -> f32;
+        #[method = "getFriction"]
+        fn friction(&self) -> f32;
+        #[method = "getSpeedFactor"]
+        fn speed_factor(&self) -> f32;
+        #[method = "getJumpFactor"]
+        fn jump_factor(&self) -> f32;
+        /// The item standing for the block, air if it has none.
+        #[method]
+        fn as_item(&self) -> JItem<'local>;
+    }
+
+    #[class = "net/minecraft/world/level/block/state/BlockState"]
+    pub struct JBlockState {
+        #[field]
+        #[java = "java/lang/Object"]
+        fn owner(&self) -> JBlock<'local>;
+        #[field]
+        fn light_emission(&self) -> i32;
+        #[field]
+        fn use_shape_for_light_occlusion(&self) -> bool;
+        #[field]
+        fn propagates_skylight_down(&self) -> bool;
+        #[field]
+        fn light_block(&self) -> i32;
+        #[field]
+        fn solid_render(&self) -> bool;
+        #[field]
+        fn is_air(&self) -> bool;
+        #[field]
+        fn ignited_by_lava(&self) -> bool;
+        #[field]
+        fn can_occlude(&self) -> bool;
+        #[field]
+        fn is_randomly_ticking(&self) -> bool;
+        #[field]
+        fn replaceable(&self) -> bool;
+        #[field]
+        fn spawn_terrain_particles(&self) -> bool;
+        #[field]
+        fn requires_correct_tool_for_drops(&self) -> bool;
+        #[field]
+        fn destroy_speed(&self) -> f32;
+        /// `None` for blocks drawn where they are placed.
+        #[field]
+        fn offset_function(&self) -> Option<JOffsetFunction<'local>>;
+        #[method = "getFluidState"]
+        fn fluid_state(&self) -> JFluidState<'local>;
+        /// Whether the state stops entities, which heightmaps and fluids
+        /// go by.
+        #[method]
+        fn blocks_motion(&self) -> bool;
+        /// The outline shape at `pos`, including the random offset.
+        #[method = "getShape"]
+        fn shape(
+            &self,
+            #[java = "net/minecraft/world/level/BlockGetter"] level: &JEmptyBlockGetter<'local>,
+            pos: &JBlockPos<'local>,
+        ) -> JVoxelShape<'local>;
+        /// The collision shape at `pos`, including the random offset.
+        #[method = "getCollisionShape"]
+        fn collision_shape(
+            &self,
+            #[java = "net/minecraft/world/level/BlockGetter"] level: &JEmptyBlockGetter<'local>,
+            pos: &JBlockPos<'local>,
+        ) -> JVoxelShape<'local>;
+        /// The shape that blocks light on its faces, empty for states that
+        /// do not occlude.
+        #[method = "getOcclusionShape"]
+        fn occlusion_shape(&self) -> JVoxelShape<'local>;
+        #[method = "getProperties"]
+        fn properties(&self) -> JCollection<'local>;
+        #[method = "getValue"]
+        #[java = "java/lang/Comparable"]
+        fn value(&self, property: &JProperty<'local>) -> JObject<'local>;
+    }
+
+    #[class = "net/minecraft/world/level/block/state/BlockBehaviour$OffsetFunction"]
+    pub struct JOffsetFunction {
+        #[method]
+        fn evaluate(&self, state: &JBlockState<'local>, pos: &JBlockPos<'local>) -> JVec3<'local>;
+    }
+
+    #[class = "net/minecraft/world/level/block/state/StateDefinition"]
+    pub struct JStateDefinition {
+        #[field]
+        #[java = "com/google/common/collect/ImmutableSortedMap"]
+        fn properties_by_name(&self) -> JMap<'local>;
+    }
+
+    #[class = "net/minecraft/world/level/block/state/properties/Property"]
+    pub struct JProperty {
+        #[field]
+        fn name(&self) -> String;
+        /// The class of the values.
+        #[field]
+        fn clazz(&self) -> JClassObject<'local>;
+        /// How `value` is written in block state strings.
+        #[method = "getName"]
+        fn value_name(&self, #[java = "java/lang/Comparable"] value: &JObject<'local>) -> String;
+        #[method = "getPossibleValues"]
+        fn possible_values(&self) -> JList<'local>;
+    }
+
+    #[class = "net/minecraft/world/level/block/state/properties/IntegerProperty"]
+    pub struct JIntegerProperty {
+        #[field]
+        fn min(&self) -> i32;
+        #[field]
+        fn max(&self) -> i32;
+    }
+
+    #[class = "net/minecraft/world/item/Item"]
+    pub struct JItem {
+        #[method = "getName"]
+        fn name(&self) -> JComponent<'local>;
+        #[method = "getDescriptionId"]
+        fn description_id(&self) -> String;
+        /// The default components of stacks of the item.
+        #[method]
+        fn components(&self) -> JDataComponentMap<'local>;
+    }
+
+    #[class = "net/minecraft/world/item/BlockItem"]
+    pub struct JBlockItem {
+        #[method = "getBlock"]
+        fn block(&self) -> JBlock<'local>;
+    }
+
+    #[class = "net/minecraft/core/component/DataComponentMap"]
+    pub struct JDataComponentMap {
+        /// The value of a component, `None` if the map does not have it.
+        #[method]
+        #[java = "java/lang/Object"]
+        fn get(&self, component: &JDataComponentType<'local>) -> Option<JObject<'local>>;
+    }
+
+    #[class = "net/minecraft/core/component/DataComponentType"]
+    pub struct JDataComponentType {
+        /// Whether the component has no codec, so it is never saved.
+        #[method]
+        fn is_transient(&self) -> bool;
+    }
+
+    #[class = "net/minecraft/core/component/DataComponents"]
+    pub struct JDataComponents {
+        #[field = "MAX_STACK_SIZE"]
+        fn max_stack_size() -> JDataComponentType<'local>;
+        #[field = "MAX_DAMAGE"]
+        fn max_damage() -> JDataComponentType<'local>;
+        #[field = "FOOD"]
+        fn food() -> JDataComponentType<'local>;
+        #[field = "TOOL"]
+        fn tool() -> JDataComponentType<'local>;
+        #[field = "RARITY"]
+        fn rarity() -> JDataComponentType<'local>;
+    }
+
+    #[class = "net/minecraft/world/food/FoodProperties"]
+    pub struct JFoodProperties {
+        #[method]
+        fn nutrition(&self) -> i32;
+        #[method]
+        fn saturation(&self) -> f32;
+        #[method]
+        fn can_always_eat(&self) -> bool;
+    }
+
+    #[class = "net/minecraft/world/item/component/Tool"]
+    pub struct JTool {
+        #[method]
+        fn default_mining_speed(&self) -> f32;
+        #[method]
+        fn damage_per_block(&self) -> i32;
+        #[method]
+        fn can_destroy_blocks_in_creative(&self) -> bool;
+    }
+
+    #[class = "net/minecraft/world/level/material/Fluid"]
+    pub struct JFluid {}
+
+    #[class = "net/minecraft/world/level/material/FlowingFluid"]
+    pub struct JFlowingFluid {
+        #[field = "FALLING"]
+        #[java = "net/minecraft/world/level/block/state/properties/BooleanProperty"]
+        fn falling() -> JProperty<'local>;
+    }
+
+    #[class = "net/minecraft/world/level/material/FluidState"]
+    pub struct JFluidState {
+        #[method = "getType"]
+        fn fluid(&self) -> JFluid<'local>;
+        #[method = "getAmount"]
+        fn amount(&self) -> i32;
+        #[method = "getValue"]
+        #[java = "java/lang/Comparable"]
+        fn value(&self, property: &JProperty<'local>) -> JObject<'local>;
+    }
+}
+
+impl<'local> Iterable<'local> for JCollection<'local> {}
+
+impl<'local> Iterable<'local> for JList<'local> {}
+
+impl<'local> Iterable<'local> for JJsonArray<'local> {}
+
+impl<'local> Iterable<'local> for JRegistry<'local> {}
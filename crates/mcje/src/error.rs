@@ -0,0 +1,142 @@
+use std::fmt;
+
+use jni::{JNIEnv, objects::JThrowable};
+
+#[derive(Debug)]
+pub enum Error {
+    /// A JNI call that failed without Java throwing, such as reading a value
+    /// as another type than the one it has.
+    Jni(jni::errors::Error),
+    /// An exception Java threw.
+    Exception(JavaException),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// An exception thrown by Java, taken from the JVM so that the calls after
+/// it can go on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JavaException {
+    /// The binary name of its class, such as `java.lang.NoSuchFieldError`.
+    pub class: String,
+    pub message: Option<String>,
+    /// What `printStackTrace` prints, causes included.
+    pub stack_trace: String,
+}
+
+impl JavaException {
+    /// Takes the exception pending in `env`, if there is one.
+    pub fn take(env: &mut JNIEnv) -> Option<Self> {
+        if !env.exception_check().unwrap_or(false) {
+            return None;
+        }
+        let throwable = env.exception_occurred().ok()?;
+        env.exception_clear().ok()?;
+        let exception = Self::describe(env, &throwable).unwrap_or_else(|_| Self {
+            class: "java.lang.Throwable".into(),
+            message: None,
+            stack_trace: String::new(),
+        });
+        // describing it may have thrown as well
+        let _ = env.exception_clear();
+        Some(exception)
+    }
+
+    fn describe(env: &mut JNIEnv, throwable: &JThrowable) -> jni::errors::Result<Self> {
+        let class = env
+            .call_method(throwable, "getClass", "()Ljava/lang/Class;", &[])?
+            .l()?;
+        let class = env
+            .call_method(class, "getName", "()Ljava/lang/String;", &[])?
+            .l()?;
+        let class = env.get_string(&class.into())?.into();
+
+        let message = env
+            .call_method(throwable, "getMessage", "()Ljava/lang/String;", &[])?
+            .l()?;
+        let message = if message.is_null() {
+            None
+        } else {
+            Some(env.get_string(&message.into())?.into())
+        };
+
+        let writer = env.new_object("java/io/StringWriter", "()V", &[])?;
+        let print_writer = env.new_object(
+            "java/io/PrintWriter",
+            "(Ljava/io/Writer;)V",
+            &[(&writer).into()],
+        )?;
+        env.call_method(
+            throwable,
+            "printStackTrace",
+            "(Ljava/io/PrintWriter;)V",
+            &[(&print_writer).into()],
+        )?;
+        let stack_trace = env
+            .call_method(&writer, "toString", "()Ljava/lang/String;", &[])?
+            .l()?;
+        let stack_trace = env.get_string(&stack_trace.into())?.into();
+
+        Ok(Self {
+            class,
+            message,
+            stack_trace,
+        })
+    }
+}
+
+/// Turns the result of a JNI call into a [`Result`], taking the exception
+/// the call left pending, if any.
+pub fn catch<T>(env: &mut JNIEnv, result: jni::errors::Result<T>) -> Result<T> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(error) => Err(match JavaException::take(env) {
+            Some(exception) => Error::Exception(exception),
+            None => Error::Jni(error),
+        }),
+    }
+}
+
+impl From<jni::errors::Error> for Error {
+    fn from(error: jni::errors::Error) -> Self {
+        Self::Jni(error)
+    }
+}
+
+impl From<JavaException> for Error {
+    fn from(exception: JavaException) -> Self {
+        Self::Exception(exception)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Jni(error) => write!(f, "JNI error: {error}"),
+            Self::Exception(exception) => write!(f, "{exception}"),
+        }
+    }
+}
+
+impl fmt::Display for JavaException {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.stack_trace.is_empty() {
+            return f.write_str(self.stack_trace.trim_end());
+        }
+        match &self.message {
+            Some(message) => write!(f, "{}: {message}", self.class),
+            None => f.write_str(&self.class),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Jni(error) => Some(error),
+            Self::Exception(exception) => Some(exception),
+        }
+    }
+}
+
+impl std::error::Error for JavaException {}
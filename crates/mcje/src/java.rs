@@ -0,0 +1,244 @@
+//! The runtime side of [`java_class!`](crate::java_class): how Rust types
+//! stand for Java ones and the JNI calls the generated wrappers make.
+
+use std::marker::PhantomData;
+
+use jni::{
+    JNIEnv,
+    objects::{JObject, JString, JValue, JValueGen, JValueOwned},
+};
+
+use crate::{JIterator, Result, error::catch};
+
+/// A Rust type standing for a Java type in the members of a wrapper.
+pub trait JavaType {
+    /// The JNI type descriptor, such as `I` or `Ljava/lang/String;`.
+    const DESCRIPTOR: &'static str;
+}
+
+/// A [`JavaType`] Java can return.
+pub trait FromJava<'local>: JavaType + Sized {
+    fn from_value(env: &mut JNIEnv<'local>, value: JValueOwned<'local>) -> Result<Self>;
+}
+
+/// A [`JavaType`] that can be passed to Java.
+pub trait ToJava<'local>: JavaType {
+    fn as_arg(&self) -> JValue<'local, '_>;
+}
+
+/// A wrapper around an object of one Java class, as generated by
+/// [`java_class!`](crate::java_class).
+pub trait JavaClass<'local>: FromJava<'local> + ToJava<'local> {
+    /// The binary name of the class with `/` separators.
+    const CLASS: &'static str;
+
+    /// Wraps `object` without checking its class.
+    fn from_object(object: JObject<'local>) -> Self;
+
+    fn as_object(&self) -> &JObject<'local>;
+
+    fn into_object(self) -> JObject<'local>;
+
+    /// The same object as another class, such as the element type of a
+    /// generic collection. The class is not checked.
+    fn cast<T: JavaClass<'local>>(self) -> T {
+        T::from_object(self.into_object())
+    }
+
+    /// Whether the object is an instance of `T`, to check a
+    /// [`JavaClass::cast`] first.
+    fn is<T: JavaClass<'local>>(&self, env: &mut JNIEnv<'local>) -> Result<bool> {
+        let is = env.is_instance_of(self.as_object(), T::CLASS);
+        catch(env, is)
+    }
+}
+
+/// A class implementing `java.lang.Iterable`.
+pub trait Iterable<'local>: JavaClass<'local> {
+    /// The elements as `T`, which Java erases.
+    fn iter<T: JavaClass<'local>>(&self, env: &mut JNIEnv<'local>) -> Result<Iter<'local, T>> {
+        let iterator = call_method(
+            env,
+            self.as_object(),
+            "iterator",
+            "()Ljava/util/Iterator;",
+            &[],
+        )?;
+        Ok(Iter {
+            iterator,
+            element: PhantomData,
+        })
+    }
+}
+
+/// The elements of an [`Iterable`]. Each step calls Java, so this takes the
+/// environment in [`Iter::next`] rather than being an [`Iterator`].
+pub struct Iter<'local, T> {
+    iterator: JIterator<'local>,
+    element: PhantomData<T>,
+}
+
+impl<'local, T: JavaClass<'local>> Iter<'local, T> {
+    pub fn next(&mut self, env: &mut JNIEnv<'local>) -> Result<Option<T>> {
+        if !self.iterator.has_next(env)? {
+            return Ok(None);
+        }
+        Ok(Some(self.iterator.next(env)?.cast()))
+    }
+}
+
+pub fn get_field<'local, T: FromJava<'local>>(
+    env: &mut JNIEnv<'local>,
+    object: &JObject,
+    name: &str,
+    descriptor: &str,
+) -> Result<T> {
+    let value = env.get_field(object, name, descriptor);
+    let value = catch(env, value)?;
+    T::from_value(env, value)
+}
+
+pub fn get_static_field<'local, T: FromJava<'local>>(
+    env: &mut JNIEnv<'local>,
+    class: &str,
+    name: &str,
+    descriptor: &str,
+) -> Result<T> {
+    let value = env.get_static_field(class, name, descriptor);
+    let value = catch(env, value)?;
+    T::from_value(env, value)
+}
+
+pub fn call_method<'local, T: FromJava<'local>>(
+    env: &mut JNIEnv<'local>,
+    object: &JObject,
+    name: &str,
+    signature: &str,
+    args: &[JValue],
+) -> Result<T> {
+    let value = env.call_method(object, name, signature, args);
+    let value = catch(env, value)?;
+    T::from_value(env, value)
+}
+
+pub fn call_static_method<'local, T: FromJava<'local>>(
+    env: &mut JNIEnv<'local>,
+    class: &str,
+    name: &str,
+    signature: &str,
+    args: &[JValue],
+) -> Result<T> {
+    let value = env.call_static_method(class, name, signature, args);
+    let value = catch(env, value)?;
+    T::from_value(env, value)
+}
+
+pub fn new_object<'local, T: JavaClass<'local>>(
+    env: &mut JNIEnv<'local>,
+    signature: &str,
+    args: &[JValue],
+) -> Result<T> {
+    let object = env.new_object(T::CLASS, signature, args);
+    Ok(T::from_object(catch(env, object)?))
+}
+
+macro_rules! primitive {
+    ($($ty:ty => $descriptor:literal, $variant:ident, $get:ident;)*) => {$(
+        impl JavaType for $ty {
+            const DESCRIPTOR: &'static str = $descriptor;
+        }
+
+        impl<'local> FromJava<'local> for $ty {
+            fn from_value(_env: &mut JNIEnv<'local>, value: JValueOwned<'local>) -> Result<Self> {
+                Ok(value.$get()?)
+            }
+        }
+
+        impl<'local> ToJava<'local> for $ty {
+            fn as_arg(&self) -> JValue<'local, '_> {
+                JValueGen::$variant((*self).into())
+            }
+        }
+    )*};
+}
+
+primitive! {
+    bool => "Z", Bool, z;
+    i8 => "B", Byte, b;
+    u16 => "C", Char, c;
+    i16 => "S", Short, s;
+    i32 => "I", Int, i;
+    i64 => "J", Long, j;
+    f32 => "F", Float, f;
+    f64 => "D", Double, d;
+}
+
+impl JavaType for () {
+    const DESCRIPTOR: &'static str = "V";
+}
+
+impl<'local> FromJava<'local> for () {
+    fn from_value(_env: &mut JNIEnv<'local>, value: JValueOwned<'local>) -> Result<Self> {
+        Ok(value.v()?)
+    }
+}
+
+/// Java strings are copied into Rust ones when returned.
+impl JavaType for String {
+    const DESCRIPTOR: &'static str = "Ljava/lang/String;";
+}
+
+impl<'local> FromJava<'local> for String {
+    fn from_value(env: &mut JNIEnv<'local>, value: JValueOwned<'local>) -> Result<Self> {
+        let string = JString::from(value.l()?);
+        Ok(env.get_string(&string)?.into())
+    }
+}
+
+/// `null` is `None`; the other types take it as is.
+impl<T: JavaType> JavaType for Option<T> {
+    const DESCRIPTOR: &'static str = T::DESCRIPTOR;
+}
+
+impl<'local, T: FromJava<'local>> FromJava<'local> for Option<T> {
+    fn from_value(env: &mut JNIEnv<'local>, value: JValueOwned<'local>) -> Result<Self> {
+        if let JValueGen::Object(object) = &value
+            && object.is_null()
+        {
+            return Ok(None);
+        }
+        T::from_value(env, value).map(Some)
+    }
+}
+
+impl JavaType for JObject<'_> {
+    const DESCRIPTOR: &'static str = "Ljava/lang/Object;";
+}
+
+impl<'local> FromJava<'local> for JObject<'local> {
+    fn from_value(_env: &mut JNIEnv<'local>, value: JValueOwned<'local>) -> Result<Self> {
+        Ok(value.l()?)
+    }
+}
+
+impl<'local> ToJava<'local> for JObject<'local> {
+    fn as_arg(&self) -> JValue<'local, '_> {
+        JValueGen::Object(self)
+    }
+}
+
+impl<'local> JavaClass<'local> for JObject<'local> {
+    const CLASS: &'static str = "java/lang/Object";
+
+    fn from_object(object: JObject<'local>) -> Self {
+        object
+    }
+
+    fn as_object(&self) -> &JObject<'local> {
+        self
+    }
+
+    fn into_object(self) -> JObject<'local> {
+        self
+    }
+}
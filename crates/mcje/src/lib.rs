@@ -1,9 +1,21 @@
+extern crate self as mcje;
+
 use jni::{
     InitArgsBuilder, JNIEnv, JavaVM,
     objects::{JObject, JValueGen},
 };
 
+pub mod classes;
+mod error;
+pub mod java;
+mod tags;
+
+pub use classes::*;
+pub use error::{Error, JavaException, Result, catch};
+pub use java::{FromJava, Iter, Iterable, JavaClass, JavaType, ToJava};
+pub use jni;
 pub use mcje_macros::*;
+pub use tags::block_tag;
 
 pub async fn init() -> JavaVM {
     let classpath = env!("MCJE_JVM_CLASSPATH");
@@ -0,0 +1,44 @@
+//! Block tags, read from the data pack inside the game jar, since the
+//! registries only know their tags once a server has loaded data packs.
+
+use jni::JNIEnv;
+
+use crate::{Iterable, JClassLoader, JInputStreamReader, JJsonElement, JJsonParser, Result};
+
+/// The names of the blocks in the block tag `name`, with the tags it
+/// includes expanded, or `None` if the game has no such tag.
+pub fn block_tag<'local>(env: &mut JNIEnv<'local>, name: &str) -> Result<Option<Vec<String>>> {
+    let path = env.new_string(format!("data/minecraft/tags/block/{name}.json"))?;
+    let Some(stream) = JClassLoader::get_system_resource_as_stream(env, &path)? else {
+        return Ok(None);
+    };
+    let reader = JInputStreamReader::new(env, &stream)?;
+    let tag = JJsonParser::parse_reader(env, &reader)?.get_as_json_object(env)?;
+    let values_key = env.new_string("values")?;
+    let id_key = env.new_string("id")?;
+    let Some(values) = tag.get(env, &values_key)? else {
+        return Ok(Some(Vec::new()));
+    };
+
+    let mut blocks = Vec::new();
+    let mut entries = values.get_as_json_array(env)?.iter::<JJsonElement>(env)?;
+    while let Some(entry) = entries.next(env)? {
+        // optional entries are objects with the id in `id`
+        let id = if entry.is_json_object(env)? {
+            match entry.get_as_json_object(env)?.get(env, &id_key)? {
+                Some(id) => id.get_as_string(env)?,
+                None => continue,
+            }
+        } else {
+            entry.get_as_string(env)?
+        };
+        match id.strip_prefix('#') {
+            Some(tag) => {
+                let tag = tag.strip_prefix("minecraft:").unwrap_or(tag);
+                blocks.extend(block_tag(env, tag)?.unwrap_or_default());
+            }
+            None => blocks.push(id.strip_prefix("minecraft:").unwrap_or(&id).to_string()),
+        }
+    }
+    Ok(Some(blocks))
+}
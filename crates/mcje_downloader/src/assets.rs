@@ -0,0 +1,262 @@
+//! The asset index of a version and the objects it lists: sounds, the
+//! languages other than `en_us` and the icons, which are not in the client
+//! jar but in a store of files named by their SHA-1.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    ops::Bound,
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+use url::Url;
+
+use crate::{AssetIndexInfo, Cache, Result};
+
+/// Where objects are downloaded from, as `<RESOURCES_URL><ab>/<ab12…>`.
+pub const RESOURCES_URL: &str = "https://resources.download.minecraft.net/";
+
+/// The objects of one asset index by the paths the game knows them by, such
+/// as `minecraft/sounds/ambient/cave/cave1.ogg`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct AssetIndex {
+    pub objects: BTreeMap<String, AssetObject>,
+    /// Set by the indexes of old versions, whose objects were copied out to
+    /// these paths rather than read from the store.
+    #[serde(rename = "virtual", skip_serializing_if = "is_false", default)]
+    pub is_virtual: bool,
+    #[serde(skip_serializing_if = "is_false", default)]
+    pub map_to_resources: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AssetObject {
+    pub hash: String,
+    pub size: u64,
+}
+
+impl AssetIndexInfo {
+    /// The index, read through `cache`.
+    pub async fn fetch(&self, cache: &Cache) -> Result<AssetIndex> {
+        let bytes = cache
+            .fetch_bytes(&self.url, &self.sha1, Some(self.size))
+            .await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+impl AssetIndex {
+    pub fn get(&self, path: &str) -> Option<&AssetObject> {
+        self.objects.get(path)
+    }
+
+    /// Puts every object into `cache`, downloading those it lacks from
+    /// `base`, which must end in `/`, at most `concurrency` at a time.
+    ///
+    /// Objects several paths share are downloaded once. The first failure
+    /// stops the downloads still going.
+    pub async fn fetch_objects(&self, cache: &Cache, base: &Url, concurrency: usize) -> Result<()> {
+        let hashes: BTreeMap<&str, &AssetObject> = self
+            .objects
+            .values()
+            .map(|object| (object.hash.as_str(), object))
+            .collect();
+        let mut tasks = JoinSet::new();
+        for object in hashes.into_values() {
+            if tasks.len() >= concurrency.max(1)
+                && let Some(done) = tasks.join_next().await
+            {
+                done.expect("object download panicked")?;
+            }
+            let cache = cache.clone();
+            let url = object.url(base)?;
+            let object = object.clone();
+            tasks.spawn(async move { cache.fetch(&url, &object.hash, Some(object.size)).await });
+        }
+        while let Some(done) = tasks.join_next().await {
+            done.expect("object download panicked")?;
+        }
+        Ok(())
+    }
+}
+
+impl AssetObject {
+    /// The path of the object under the store at `base`.
+    pub fn url(&self, base: &Url) -> Result<Url> {
+        let prefix = self.hash.get(..2).unwrap_or(&self.hash);
+        Ok(base.join(&format!("{prefix}/{}", self.hash))?)
+    }
+}
+
+/// An [`AssetIndex`] whose objects are in a [`Cache`], read as files by
+/// their paths in the index, the way the game lays them over the client
+/// jar's `assets` directory.
+#[derive(Debug, Clone)]
+pub struct AssetObjects {
+    index: AssetIndex,
+    cache: Cache,
+}
+
+impl AssetObjects {
+    /// Objects missing from `cache` read as absent; fetch them first with
+    /// [`AssetIndex::fetch_objects`].
+    pub fn new(index: AssetIndex, cache: Cache) -> Self {
+        Self { index, cache }
+    }
+
+    pub fn index(&self) -> &AssetIndex {
+        &self.index
+    }
+
+    /// The top level directories of the index, `minecraft` and `icons` for
+    /// current versions.
+    pub fn namespaces(&self) -> Vec<String> {
+        let namespaces: BTreeSet<&str> = self
+            .index
+            .objects
+            .keys()
+            .filter_map(|path| path.split_once('/').map(|(namespace, _)| namespace))
+            .collect();
+        namespaces.into_iter().map(str::to_string).collect()
+    }
+
+    /// The paths in the index under `dir`, such as every sound with
+    /// `minecraft/sounds/`.
+    pub fn list<'a>(&'a self, dir: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.index
+            .objects
+            .range::<str, _>((Bound::Included(dir), Bound::Unbounded))
+            .map(|(path, _)| path.as_str())
+            .take_while(move |path| path.starts_with(dir))
+    }
+
+    /// Where the object at `path` is kept, if it has been downloaded.
+    pub fn file(&self, path: &str) -> Option<PathBuf> {
+        let object = self.index.get(path)?;
+        let file = self.cache.object_path(&object.hash);
+        file.exists().then_some(file)
+    }
+
+    /// The contents of the object at `path`, if it has been downloaded.
+    pub fn read(&self, path: &str) -> Option<Vec<u8>> {
+        fs::read(self.file(path)?).ok()
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, time::Duration};
+
+    use super::*;
+    use crate::{Error, sha1_hex, test_server::TestServer};
+
+    fn cache(name: &str, offline: bool) -> Cache {
+        let dir = env::temp_dir().join(format!("mcje-assets-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        Cache::new(dir, offline)
+    }
+
+    fn object(data: &[u8]) -> (String, AssetObject) {
+        let hash = sha1_hex(data);
+        let path = format!("{}/{hash}", &hash[..2]);
+        (
+            path,
+            AssetObject {
+                hash,
+                size: data.len() as u64,
+            },
+        )
+    }
+
+    #[test]
+    fn test_parse_index() {
+        let index: AssetIndex = serde_json::from_str(
+            r#"{"objects": {
+                "icons/icon_16x16.png": {"hash": "bdf48ef6b5d0d23bbb02e17d04865216179f510a", "size": 3665},
+                "minecraft/sounds/ambient/cave/cave1.ogg": {"hash": "dcb6a4f6d3c4d0d0de4fdc0a5e19e9b4e8c7f3f8", "size": 62939}
+            }}"#,
+        )
+        .unwrap();
+        assert!(!index.is_virtual);
+        let cave = index
+            .get("minecraft/sounds/ambient/cave/cave1.ogg")
+            .unwrap();
+        assert_eq!(cave.size, 62939);
+        let base: Url = RESOURCES_URL.parse().unwrap();
+        assert_eq!(
+            cave.url(&base).unwrap().as_str(),
+            "https://resources.download.minecraft.net/dc/dcb6a4f6d3c4d0d0de4fdc0a5e19e9b4e8c7f3f8"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_objects() {
+        let files: Vec<&[u8]> = vec![b"cave", b"door", b"lang", b"icon", b"step"];
+        let mut index = AssetIndex::default();
+        let mut served = Vec::new();
+        for data in &files {
+            let (path, object) = object(data);
+            served.push((path, data.to_vec()));
+            let name = String::from_utf8(data.to_vec()).unwrap();
+            index
+                .objects
+                .insert(format!("minecraft/sounds/{name}.ogg"), object);
+        }
+        // two paths sharing one object
+        let (_, shared) = object(b"cave");
+        index
+            .objects
+            .insert("minecraft/sounds/cave_again.ogg".into(), shared);
+        let server = TestServer::start(
+            served
+                .iter()
+                .map(|(path, data)| (path.as_str(), data.clone()))
+                .collect(),
+            Duration::from_millis(20),
+        );
+
+        let cache = cache("fetch", false);
+        index.fetch_objects(&cache, &server.url, 2).await.unwrap();
+        assert_eq!(server.requests().len(), 5);
+        assert!(server.max_in_flight() <= 2);
+        // everything is cached now
+        index.fetch_objects(&cache, &server.url, 2).await.unwrap();
+        assert_eq!(server.requests().len(), 5);
+
+        let objects = AssetObjects::new(index.clone(), cache.clone());
+        assert_eq!(objects.namespaces(), ["minecraft"]);
+        assert_eq!(
+            objects.read("minecraft/sounds/cave_again.ogg").unwrap(),
+            b"cave"
+        );
+        assert_eq!(objects.read("minecraft/sounds/missing.ogg"), None);
+        let sounds: Vec<&str> = objects.list("minecraft/sounds/d").collect();
+        assert_eq!(sounds, ["minecraft/sounds/door.ogg"]);
+        std::fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_objects_checks_hashes() {
+        let (path, mut listed) = object(b"door");
+        listed.size = 4;
+        let server = TestServer::start(vec![(&path, b"dorr".to_vec())], Duration::ZERO);
+        let mut index = AssetIndex::default();
+        index
+            .objects
+            .insert("minecraft/sounds/door.ogg".into(), listed);
+        let cache = cache("hashes", false);
+        assert!(matches!(
+            index.fetch_objects(&cache, &server.url, 4).await,
+            Err(Error::HashMismatch { .. })
+        ));
+        let objects = AssetObjects::new(index, cache.clone());
+        assert_eq!(objects.read("minecraft/sounds/door.ogg"), None);
+        std::fs::remove_dir_all(cache.dir()).unwrap();
+    }
+}
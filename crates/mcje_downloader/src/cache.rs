@@ -0,0 +1,535 @@
+//! A content-addressed store for the files the launcher manifests list, so
+//! each is downloaded once, checked against its SHA-1, and can be found
+//! again without the network.
+//!
+//! ```text
+//! <dir>/objects/ab/ab12…             files, named by their SHA-1
+//! <dir>/versions/<id>.json           the launcher's entry for each version used
+//! <dir>/version_manifest_v2.json     the launcher's list of versions last seen
+//! <dir>/tmp/ab12….part               downloads in progress
+//! ```
+
+use std::{
+    env, io,
+    path::{Path, PathBuf},
+};
+
+use reqwest::{Client, StatusCode, header};
+use sha1::{Digest, Sha1};
+use tokio::{
+    fs::{self, File, OpenOptions},
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+};
+use url::Url;
+
+use crate::{Error, MANIFEST_URL, Result, RootManifest, VersionManifest, VersionRelease};
+
+/// The variable that moves the cache away from the user's cache directory.
+pub const CACHE_DIR_VAR: &str = "MCRE_CACHE_DIR";
+/// The variable that, set to anything but `0` or `false`, keeps the cache
+/// off the network.
+pub const OFFLINE_VAR: &str = "MCRE_OFFLINE";
+/// Every variable [`Cache::from_env`] reads, for build scripts to rerun when
+/// one changes.
+pub const ENV_VARS: [&str; 6] = [
+    CACHE_DIR_VAR,
+    OFFLINE_VAR,
+    "CARGO_NET_OFFLINE",
+    "XDG_CACHE_HOME",
+    "LOCALAPPDATA",
+    "HOME",
+];
+
+const ROOT_MANIFEST_FILE: &str = "version_manifest_v2.json";
+
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    offline: bool,
+    client: Client,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>, offline: bool) -> Self {
+        Self {
+            dir: dir.into(),
+            offline,
+            client: Client::new(),
+        }
+    }
+
+    /// The cache in [`CACHE_DIR_VAR`], or in `mcre` under the user's cache
+    /// directory, offline if [`OFFLINE_VAR`] or cargo's `CARGO_NET_OFFLINE`
+    /// is set.
+    pub fn from_env() -> Self {
+        let dir = env::var_os(CACHE_DIR_VAR)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(default_dir);
+        Self::new(dir, env_flag(OFFLINE_VAR) || env_flag("CARGO_NET_OFFLINE"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Where the file with the given SHA-1 is kept, whether or not it is
+    /// there yet.
+    pub fn object_path(&self, sha1: &str) -> PathBuf {
+        let sha1 = sha1.to_ascii_lowercase();
+        let prefix = sha1.get(..2).unwrap_or(&sha1);
+        self.dir.join("objects").join(prefix).join(&sha1)
+    }
+
+    /// The path of the cached copy of the file at `url`, downloading it
+    /// first if there is none.
+    ///
+    /// A cached copy that no longer matches `sha1` and `size` is thrown away
+    /// and downloaded again. A download interrupted part way through is
+    /// carried on from where it stopped, if the size is known.
+    pub async fn fetch(&self, url: &Url, sha1: &str, size: Option<u64>) -> Result<PathBuf> {
+        let path = self.object_path(sha1);
+        if fs::try_exists(&path).await? {
+            match verify_file(&path, sha1, size).await {
+                Ok(()) => return Ok(path),
+                Err(Error::HashMismatch { .. } | Error::SizeMismatch { .. }) => {
+                    fs::remove_file(&path).await?;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        if self.offline {
+            return Err(Error::NotCached(url.to_string()));
+        }
+        let part = self.download(url, sha1, size).await?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::rename(&part, &path).await?;
+        Ok(path)
+    }
+
+    /// The contents of the file at `url`, as [`Cache::fetch`] finds it.
+    pub async fn fetch_bytes(&self, url: &Url, sha1: &str, size: Option<u64>) -> Result<Vec<u8>> {
+        Ok(fs::read(self.fetch(url, sha1, size).await?).await?)
+    }
+
+    /// The launcher's entry for version `id`.
+    ///
+    /// The launcher's list of versions carries no hash and changes with every
+    /// release, so it is only downloaded the first time a version is asked
+    /// for; the entry is then kept in `versions/<id>.json`. Offline, the list
+    /// saved by the last download is looked in instead.
+    pub async fn version(&self, id: &str) -> Result<VersionRelease> {
+        let path = self.dir.join("versions").join(format!("{id}.json"));
+        if let Ok(bytes) = fs::read(&path).await {
+            return Ok(serde_json::from_slice(&bytes)?);
+        }
+        let release = self
+            .root_manifest()
+            .await?
+            .versions
+            .into_iter()
+            .find(|version| version.id == id);
+        let release = match release {
+            Some(release) => release,
+            // the saved list may be older than the version
+            None if self.offline => return Err(Error::NotCached(format!("version {id}"))),
+            None => return Err(Error::UnknownVersion(id.to_string())),
+        };
+        self.write_atomic(&path, &serde_json::to_vec_pretty(&release)?)
+            .await?;
+        Ok(release)
+    }
+
+    /// The launcher's list of versions, downloaded and saved, or the saved
+    /// copy when offline.
+    async fn root_manifest(&self) -> Result<RootManifest> {
+        let path = self.dir.join(ROOT_MANIFEST_FILE);
+        if self.offline {
+            return match fs::read(&path).await {
+                Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                    Err(Error::NotCached("the version manifest".to_string()))
+                }
+                Err(error) => Err(error.into()),
+            };
+        }
+        let bytes = self
+            .client
+            .get(MANIFEST_URL)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let root = serde_json::from_slice(&bytes)?;
+        self.write_atomic(&path, &bytes).await?;
+        Ok(root)
+    }
+
+    /// The manifest of version `id`, see [`Cache::version`].
+    pub async fn version_manifest(&self, id: &str) -> Result<VersionManifest> {
+        let release = self.version(id).await?;
+        let bytes = self.fetch_bytes(&release.url, &release.sha1, None).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// `manifest` merged with the version it inherits from, fetched through
+    /// the cache, so that a profile such as Fabric's has everything needed
+    /// to launch it.
+    pub async fn resolve(&self, mut manifest: VersionManifest) -> Result<VersionManifest> {
+        while let Some(parent) = manifest.inherits_from.clone() {
+            manifest = manifest.merge(self.version_manifest(&parent).await?);
+        }
+        Ok(manifest)
+    }
+
+    /// Streams `url` into `tmp/<sha1>.part` and checks it, returning the
+    /// part file.
+    async fn download(&self, url: &Url, sha1: &str, size: Option<u64>) -> Result<PathBuf> {
+        let tmp = self.dir.join("tmp");
+        fs::create_dir_all(&tmp).await?;
+        let part = tmp.join(format!("{}.part", sha1.to_ascii_lowercase()));
+
+        let mut hasher = Sha1::new();
+        let mut written = 0;
+        // what an earlier download left behind can only be carried on from
+        // when it is known how much is missing
+        if let Some(size) = size
+            && let Ok(mut file) = File::open(&part).await
+        {
+            written = hash_reader(&mut file, &mut hasher).await?;
+            if written > size {
+                hasher = Sha1::new();
+                written = 0;
+            }
+        }
+
+        if size.is_none_or(|size| written < size) {
+            let mut request = self.client.get(url.clone());
+            if written > 0 {
+                request = request.header(header::RANGE, format!("bytes={written}-"));
+            }
+            let mut response = request.send().await?.error_for_status()?;
+            if written > 0 && response.status() != StatusCode::PARTIAL_CONTENT {
+                // the server ignored the range and sends everything
+                hasher = Sha1::new();
+                written = 0;
+            }
+            let mut options = OpenOptions::new();
+            if written > 0 {
+                options.append(true);
+            } else {
+                options.create(true).write(true).truncate(true);
+            }
+            let mut file = options.open(&part).await?;
+            while let Some(chunk) = response.chunk().await? {
+                hasher.update(&chunk);
+                file.write_all(&chunk).await?;
+                written += chunk.len() as u64;
+            }
+            file.flush().await?;
+        }
+
+        // a short download is kept to be carried on from next time
+        if let Some(size) = size
+            && written < size
+        {
+            return Err(Error::SizeMismatch {
+                expected: size,
+                actual: written,
+            });
+        }
+        let checked = check(sha1, size, &hex(&hasher.finalize()), written);
+        if checked.is_err() {
+            fs::remove_file(&part).await?;
+        }
+        checked.map(|()| part)
+    }
+
+    /// Writes `path` through a temporary file, so it is never seen half
+    /// written.
+    async fn write_atomic(&self, path: &Path, contents: &[u8]) -> Result<()> {
+        let tmp = self.dir.join("tmp");
+        fs::create_dir_all(&tmp).await?;
+        let Some(name) = path.file_name() else {
+            return Err(Error::Io(io::ErrorKind::InvalidInput.into()));
+        };
+        let staged = tmp.join(name);
+        fs::write(&staged, contents).await?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::rename(&staged, path).await?;
+        Ok(())
+    }
+}
+
+/// The SHA-1 of `data` as lowercase hex, as the manifests write it.
+pub fn sha1_hex(data: &[u8]) -> String {
+    hex(&Sha1::digest(data))
+}
+
+/// Checks downloaded data against the SHA-1 and size a manifest lists.
+pub fn verify(data: &[u8], sha1: &str, size: Option<u64>) -> Result<()> {
+    check(sha1, size, &sha1_hex(data), data.len() as u64)
+}
+
+async fn verify_file(path: &Path, sha1: &str, size: Option<u64>) -> Result<()> {
+    let mut file = File::open(path).await?;
+    let length = file.metadata().await?.len();
+    if let Some(size) = size
+        && length != size
+    {
+        return Err(Error::SizeMismatch {
+            expected: size,
+            actual: length,
+        });
+    }
+    let mut hasher = Sha1::new();
+    let length = hash_reader(&mut file, &mut hasher).await?;
+    check(sha1, size, &hex(&hasher.finalize()), length)
+}
+
+fn check(sha1: &str, size: Option<u64>, actual_sha1: &str, actual_size: u64) -> Result<()> {
+    if let Some(size) = size
+        && actual_size != size
+    {
+        return Err(Error::SizeMismatch {
+            expected: size,
+            actual: actual_size,
+        });
+    }
+    if !actual_sha1.eq_ignore_ascii_case(sha1) {
+        return Err(Error::HashMismatch {
+            expected: sha1.to_string(),
+            actual: actual_sha1.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Feeds everything left in `reader` to `hasher`, returning how many bytes
+/// that was.
+async fn hash_reader(reader: &mut (impl AsyncRead + Unpin), hasher: &mut Sha1) -> Result<u64> {
+    let mut buf = vec![0; 64 * 1024];
+    let mut total = 0;
+    loop {
+        let read = reader.read(&mut buf).await?;
+        if read == 0 {
+            return Ok(total);
+        }
+        hasher.update(&buf[..read]);
+        total += read as u64;
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn env_flag(name: &str) -> bool {
+    env::var(name).is_ok_and(|value| {
+        !matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "" | "0" | "false" | "no" | "off"
+        )
+    })
+}
+
+/// `mcre` in the platform's per-user cache directory.
+fn default_dir() -> PathBuf {
+    let home = || env::var_os("HOME").map(PathBuf::from);
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|home| home.join("Library").join("Caches"))
+    } else {
+        env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| home().map(|home| home.join(".cache")))
+    };
+    base.unwrap_or_else(env::temp_dir).join("mcre")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::test_server::TestServer;
+
+    const ABC_SHA1: &str = "a9993e364706816aba3e25717850c26c9cd0d89d";
+
+    /// An empty cache directory of its own for each test.
+    fn cache(name: &str, offline: bool) -> Cache {
+        let dir = env::temp_dir().join(format!("mcje-cache-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        Cache::new(dir, offline)
+    }
+
+    fn put(cache: &Cache, sha1: &str, contents: &[u8]) -> PathBuf {
+        let path = cache.object_path(sha1);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn url() -> Url {
+        "https://example.invalid/abc".parse().unwrap()
+    }
+
+    #[test]
+    fn test_verify() {
+        assert_eq!(sha1_hex(b"abc"), ABC_SHA1);
+        assert!(verify(b"abc", ABC_SHA1, Some(3)).is_ok());
+        assert!(verify(b"abc", &ABC_SHA1.to_uppercase(), None).is_ok());
+        assert!(matches!(
+            verify(b"abd", ABC_SHA1, Some(3)),
+            Err(Error::HashMismatch { .. })
+        ));
+        assert!(matches!(
+            verify(b"abc", ABC_SHA1, Some(4)),
+            Err(Error::SizeMismatch {
+                expected: 4,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_object_path() {
+        let cache = Cache::new("cache", false);
+        assert_eq!(
+            cache.object_path(&ABC_SHA1.to_uppercase()),
+            Path::new("cache/objects/a9").join(ABC_SHA1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_offline_fetch() {
+        let cache = cache("offline-fetch", true);
+        assert!(matches!(
+            cache.fetch(&url(), ABC_SHA1, Some(3)).await,
+            Err(Error::NotCached(_))
+        ));
+        let path = put(&cache, ABC_SHA1, b"abc");
+        assert_eq!(cache.fetch(&url(), ABC_SHA1, Some(3)).await.unwrap(), path);
+        assert_eq!(
+            cache.fetch_bytes(&url(), ABC_SHA1, None).await.unwrap(),
+            b"abc"
+        );
+        // a damaged copy is not handed out
+        put(&cache, ABC_SHA1, b"abd");
+        assert!(matches!(
+            cache.fetch(&url(), ABC_SHA1, Some(3)).await,
+            Err(Error::NotCached(_))
+        ));
+        assert!(!path.exists());
+        std::fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resume_download() {
+        let data: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+        let sha1 = sha1_hex(&data);
+        let server = TestServer::start(vec![("client.jar", data.clone())], Duration::ZERO);
+        let url = server.url.join("client.jar").unwrap();
+        let cache = cache("resume", false);
+        // an earlier download that stopped part way
+        let part = cache.dir().join("tmp").join(format!("{sha1}.part"));
+        std::fs::create_dir_all(part.parent().unwrap()).unwrap();
+        std::fs::write(&part, &data[..4000]).unwrap();
+
+        let size = Some(data.len() as u64);
+        let path = cache.fetch(&url, &sha1, size).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+        assert!(!part.exists());
+        assert_eq!(server.requests(), [("/client.jar".to_string(), Some(4000))]);
+
+        // a download that does not match its hash is not kept
+        let mut wrong = sha1.clone();
+        wrong.replace_range(..1, if wrong.starts_with('0') { "1" } else { "0" });
+        assert!(matches!(
+            cache.fetch(&url, &wrong, size).await,
+            Err(Error::HashMismatch { .. })
+        ));
+        assert!(!cache.object_path(&wrong).exists());
+        assert!(
+            !cache
+                .dir()
+                .join("tmp")
+                .join(format!("{wrong}.part"))
+                .exists()
+        );
+        std::fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_offline_version_manifest() {
+        let cache = cache("offline-version", true);
+        assert!(matches!(
+            cache.version_manifest("1.21.10").await,
+            Err(Error::NotCached(_))
+        ));
+
+        let manifest = br#"{
+            "assetIndex": {"id": "27", "sha1": "a9993e364706816aba3e25717850c26c9cd0d89d", "size": 3, "totalSize": 0, "url": "https://example.invalid/27.json"},
+            "assets": "27",
+            "downloads": {
+                "client": {"sha1": "a9993e364706816aba3e25717850c26c9cd0d89d", "size": 3, "url": "https://example.invalid/client.jar"},
+                "server": {"sha1": "a9993e364706816aba3e25717850c26c9cd0d89d", "size": 3, "url": "https://example.invalid/server.jar"}
+            },
+            "id": "1.21.10",
+            "libraries": []
+        }"#;
+        let sha1 = sha1_hex(manifest);
+        put(&cache, &sha1, manifest);
+        let release = format!(
+            r#"{{"id": "1.21.10", "type": "release", "url": "https://example.invalid/1.21.10.json",
+                "time": "2025-10-07T09:17:23+00:00", "releaseTime": "2025-10-07T09:17:23+00:00",
+                "sha1": "{sha1}", "complianceLevel": 1}}"#
+        );
+        std::fs::create_dir_all(cache.dir().join("versions")).unwrap();
+        std::fs::write(cache.dir().join("versions/1.21.10.json"), &release).unwrap();
+
+        let manifest = cache.version_manifest("1.21.10").await.unwrap();
+        assert_eq!(manifest.id, "1.21.10");
+
+        // without its entry, the version is looked up in the saved list
+        std::fs::remove_dir_all(cache.dir().join("versions")).unwrap();
+        assert!(matches!(
+            cache.version("1.21.10").await,
+            Err(Error::NotCached(what)) if what == "the version manifest"
+        ));
+        let root = format!(
+            r#"{{"latest": {{"release": "1.21.10", "snapshot": "1.21.10"}}, "versions": [{release}]}}"#
+        );
+        std::fs::write(cache.dir().join(ROOT_MANIFEST_FILE), root).unwrap();
+        assert_eq!(
+            cache.version_manifest("1.21.10").await.unwrap().id,
+            "1.21.10"
+        );
+        assert!(cache.dir().join("versions/1.21.10.json").exists());
+        assert!(matches!(
+            cache.version("1.21.11").await,
+            Err(Error::NotCached(what)) if what == "version 1.21.11"
+        ));
+        put(&cache, ABC_SHA1, b"abc");
+        let client = manifest
+            .downloads
+            .unwrap()
+            .client
+            .fetch(&cache)
+            .await
+            .unwrap();
+        assert_eq!(client, cache.object_path(ABC_SHA1));
+        std::fs::remove_dir_all(cache.dir()).unwrap();
+    }
+}
@@ -0,0 +1,88 @@
+use std::{fmt, io};
+
+#[derive(Debug)]
+pub enum Error {
+    Http(reqwest::Error),
+    Io(io::Error),
+    Json(serde_json::Error),
+    Url(url::ParseError),
+    /// A download whose SHA-1 is not the one the manifest lists for it.
+    HashMismatch {
+        expected: String,
+        actual: String,
+    },
+    /// A download whose length is not the one the manifest lists for it.
+    SizeMismatch {
+        expected: u64,
+        actual: u64,
+    },
+    /// A version id missing from the launcher's version manifest.
+    UnknownVersion(String),
+    /// ProGuard mappings that do not parse, with the line, counted from 1.
+    InvalidMappings {
+        line: usize,
+        reason: &'static str,
+    },
+    /// Something that is not in the cache while the network may not be used.
+    NotCached(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(error)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+impl From<url::ParseError> for Error {
+    fn from(error: url::ParseError) -> Self {
+        Self::Url(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(error) => write!(f, "HTTP error: {error}"),
+            Self::Io(error) => write!(f, "I/O error: {error}"),
+            Self::Json(error) => write!(f, "invalid JSON: {error}"),
+            Self::Url(error) => write!(f, "invalid URL: {error}"),
+            Self::HashMismatch { expected, actual } => {
+                write!(f, "SHA-1 mismatch: expected {expected}, got {actual}")
+            }
+            Self::SizeMismatch { expected, actual } => {
+                write!(f, "size mismatch: expected {expected} bytes, got {actual}")
+            }
+            Self::UnknownVersion(id) => write!(f, "unknown minecraft version {id}"),
+            Self::InvalidMappings { line, reason } => {
+                write!(f, "invalid mappings on line {line}: {reason}")
+            }
+            Self::NotCached(what) => write!(f, "{what} is not cached and offline mode is on"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(error) => Some(error),
+            Self::Io(error) => Some(error),
+            Self::Json(error) => Some(error),
+            Self::Url(error) => Some(error),
+            _ => None,
+        }
+    }
+}
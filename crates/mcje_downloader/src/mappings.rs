@@ -0,0 +1,510 @@
+//! Mojang's obfuscation mappings, published in ProGuard's format next to
+//! each client and server jar, and the Tiny v2, SRG and TSRG formats other
+//! tools read them in.
+//!
+//! Names are kept in the JVM's internal form, `net/minecraft/Util$Foo`, and
+//! member types as descriptors in the named namespace.
+
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{Cache, DownloadInfo, Error, Result};
+
+/// Every class the mappings cover, with its fields and methods.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mappings {
+    classes: Vec<ClassMapping>,
+    by_named: HashMap<String, usize>,
+    by_obfuscated: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassMapping {
+    pub named: String,
+    pub obfuscated: String,
+    pub fields: Vec<FieldMapping>,
+    pub methods: Vec<MethodMapping>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldMapping {
+    pub named: String,
+    pub obfuscated: String,
+    /// The field's type descriptor, with named class names.
+    pub descriptor: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodMapping {
+    pub named: String,
+    pub obfuscated: String,
+    /// The method's descriptor, with named class names.
+    pub descriptor: String,
+    /// The lines of the obfuscated method, for methods that have code.
+    pub lines: Option<(u32, u32)>,
+    /// The lines the method had in the source, where they differ.
+    pub original_lines: Option<(u32, u32)>,
+}
+
+impl Mappings {
+    /// Reads ProGuard mappings as Mojang publishes them.
+    ///
+    /// Lines for code inlined from other methods, whose names are qualified
+    /// with their class, only matter to stack traces and are left out.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut classes = Vec::new();
+        let mut current: Option<ClassMapping> = None;
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let invalid = |reason| Error::InvalidMappings {
+                line: number,
+                reason,
+            };
+            let (left, obfuscated) = line.split_once(" -> ").ok_or(invalid("expected `->`"))?;
+            if !line.starts_with(char::is_whitespace) {
+                let obfuscated = obfuscated
+                    .strip_suffix(':')
+                    .ok_or(invalid("expected `:` after a class"))?;
+                classes.extend(current.take());
+                current = Some(ClassMapping {
+                    named: internal_name(left.trim()),
+                    obfuscated: internal_name(obfuscated.trim()),
+                    fields: Vec::new(),
+                    methods: Vec::new(),
+                });
+                continue;
+            }
+            let class = current
+                .as_mut()
+                .ok_or(invalid("member outside of a class"))?;
+            let obfuscated = obfuscated.trim().to_string();
+            let member = left.trim();
+            match member.find('(') {
+                None => {
+                    let (ty, named) = member
+                        .rsplit_once(' ')
+                        .ok_or(invalid("expected a field type and name"))?;
+                    class.fields.push(FieldMapping {
+                        named: named.to_string(),
+                        obfuscated,
+                        descriptor: type_descriptor(ty),
+                    });
+                }
+                Some(_) => {
+                    let Some(method) =
+                        parse_method(member, obfuscated).ok_or(invalid("malformed method"))?
+                    else {
+                        continue;
+                    };
+                    // a method split into several line ranges is listed once per range
+                    let known = class.methods.iter().any(|known| {
+                        known.named == method.named && known.descriptor == method.descriptor
+                    });
+                    if !known {
+                        class.methods.push(method);
+                    }
+                }
+            }
+        }
+        classes.extend(current);
+        Ok(Self::from_classes(classes))
+    }
+
+    /// The mappings a version lists for its client or server, read through
+    /// `cache`.
+    pub async fn fetch(download: &DownloadInfo, cache: &Cache) -> Result<Self> {
+        let bytes = cache
+            .fetch_bytes(&download.url, &download.sha1, Some(download.size))
+            .await?;
+        let text = String::from_utf8(bytes).map_err(|_| Error::InvalidMappings {
+            line: 0,
+            reason: "not UTF-8",
+        })?;
+        Self::parse(&text)
+    }
+
+    pub fn from_classes(classes: Vec<ClassMapping>) -> Self {
+        let by_named = classes
+            .iter()
+            .enumerate()
+            .map(|(index, class)| (class.named.clone(), index))
+            .collect();
+        let by_obfuscated = classes
+            .iter()
+            .enumerate()
+            .map(|(index, class)| (class.obfuscated.clone(), index))
+            .collect();
+        Self {
+            classes,
+            by_named,
+            by_obfuscated,
+        }
+    }
+
+    pub fn classes(&self) -> &[ClassMapping] {
+        &self.classes
+    }
+
+    pub fn class(&self, named: &str) -> Option<&ClassMapping> {
+        self.by_named.get(named).map(|index| &self.classes[*index])
+    }
+
+    pub fn class_by_obfuscated(&self, obfuscated: &str) -> Option<&ClassMapping> {
+        self.by_obfuscated
+            .get(obfuscated)
+            .map(|index| &self.classes[*index])
+    }
+
+    /// The name a class has at runtime in the obfuscated jar. Classes the
+    /// mappings do not cover, such as the JDK's, keep their name.
+    pub fn obfuscate_class<'a>(&'a self, named: &'a str) -> &'a str {
+        self.class(named).map_or(named, |class| &class.obfuscated)
+    }
+
+    pub fn deobfuscate_class<'a>(&'a self, obfuscated: &'a str) -> &'a str {
+        self.class_by_obfuscated(obfuscated)
+            .map_or(obfuscated, |class| &class.named)
+    }
+
+    /// `descriptor` with its named classes replaced by their obfuscated
+    /// names.
+    pub fn obfuscate_descriptor(&self, descriptor: &str) -> String {
+        map_descriptor(descriptor, |class| self.obfuscate_class(class))
+    }
+
+    pub fn deobfuscate_descriptor(&self, descriptor: &str) -> String {
+        map_descriptor(descriptor, |class| self.deobfuscate_class(class))
+    }
+
+    /// The runtime name and descriptor of a field, from its named class and
+    /// name.
+    pub fn obfuscate_field(&self, class: &str, name: &str) -> Option<(&str, String)> {
+        let field = self.class(class)?.field(name)?;
+        Some((
+            &field.obfuscated,
+            self.obfuscate_descriptor(&field.descriptor),
+        ))
+    }
+
+    /// The runtime name and descriptor of a method, from its named class,
+    /// name and descriptor.
+    pub fn obfuscate_method(
+        &self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+    ) -> Option<(&str, String)> {
+        let method = self.class(class)?.method(name, descriptor)?;
+        Some((&method.obfuscated, self.obfuscate_descriptor(descriptor)))
+    }
+
+    /// The named field behind an obfuscated class and field name.
+    pub fn deobfuscate_field(&self, class: &str, name: &str) -> Option<&FieldMapping> {
+        self.class_by_obfuscated(class)?
+            .fields
+            .iter()
+            .find(|field| field.obfuscated == name)
+    }
+
+    /// The named method behind an obfuscated class, method name and
+    /// descriptor.
+    pub fn deobfuscate_method(
+        &self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+    ) -> Option<&MethodMapping> {
+        let descriptor = self.deobfuscate_descriptor(descriptor);
+        self.class_by_obfuscated(class)?
+            .methods
+            .iter()
+            .find(|method| method.obfuscated == name && method.descriptor == descriptor)
+    }
+
+    /// The mappings as Tiny v2, from the `official` namespace to `named`,
+    /// as Fabric's tools read them.
+    pub fn to_tiny_v2(&self) -> String {
+        let mut out = String::from("tiny\t2\t0\tofficial\tnamed\n");
+        for class in &self.classes {
+            let _ = writeln!(out, "c\t{}\t{}", class.obfuscated, class.named);
+            for field in &class.fields {
+                let descriptor = self.obfuscate_descriptor(&field.descriptor);
+                let _ = writeln!(
+                    out,
+                    "\tf\t{descriptor}\t{}\t{}",
+                    field.obfuscated, field.named
+                );
+            }
+            for method in &class.methods {
+                let descriptor = self.obfuscate_descriptor(&method.descriptor);
+                let _ = writeln!(
+                    out,
+                    "\tm\t{descriptor}\t{}\t{}",
+                    method.obfuscated, method.named
+                );
+            }
+        }
+        out
+    }
+
+    /// The mappings as SRG, from obfuscated to named.
+    pub fn to_srg(&self) -> String {
+        let mut out = String::new();
+        for class in &self.classes {
+            let _ = writeln!(out, "CL: {} {}", class.obfuscated, class.named);
+        }
+        for class in &self.classes {
+            for field in &class.fields {
+                let _ = writeln!(
+                    out,
+                    "FD: {}/{} {}/{}",
+                    class.obfuscated, field.obfuscated, class.named, field.named
+                );
+            }
+            for method in &class.methods {
+                let _ = writeln!(
+                    out,
+                    "MD: {}/{} {} {}/{} {}",
+                    class.obfuscated,
+                    method.obfuscated,
+                    self.obfuscate_descriptor(&method.descriptor),
+                    class.named,
+                    method.named,
+                    method.descriptor
+                );
+            }
+        }
+        out
+    }
+
+    /// The mappings as TSRG, from obfuscated to named.
+    pub fn to_tsrg(&self) -> String {
+        let mut out = String::new();
+        for class in &self.classes {
+            let _ = writeln!(out, "{} {}", class.obfuscated, class.named);
+            for field in &class.fields {
+                let _ = writeln!(out, "\t{} {}", field.obfuscated, field.named);
+            }
+            for method in &class.methods {
+                let _ = writeln!(
+                    out,
+                    "\t{} {} {}",
+                    method.obfuscated,
+                    self.obfuscate_descriptor(&method.descriptor),
+                    method.named
+                );
+            }
+        }
+        out
+    }
+}
+
+impl ClassMapping {
+    pub fn field(&self, named: &str) -> Option<&FieldMapping> {
+        self.fields.iter().find(|field| field.named == named)
+    }
+
+    /// The method with the given name and named descriptor.
+    pub fn method(&self, named: &str, descriptor: &str) -> Option<&MethodMapping> {
+        self.methods
+            .iter()
+            .find(|method| method.named == named && method.descriptor == descriptor)
+    }
+}
+
+/// Reads `[start:end:]type name(params)[:start:end]`, `None` in the outer
+/// option for a malformed line and in the inner one for an inlined frame.
+fn parse_method(member: &str, obfuscated: String) -> Option<Option<MethodMapping>> {
+    let mut rest = member;
+    let mut lines = None;
+    if rest.starts_with(|c: char| c.is_ascii_digit()) {
+        let (start, after) = rest.split_once(':')?;
+        let (end, after) = after.split_once(':')?;
+        lines = Some((start.parse().ok()?, end.parse().ok()?));
+        rest = after;
+    }
+    let open = rest.find('(')?;
+    let close = rest.find(')')?;
+    let original_lines = match rest[close + 1..].strip_prefix(':') {
+        Some(original) => {
+            let (start, end) = original.split_once(':')?;
+            Some((start.parse().ok()?, end.parse().ok()?))
+        }
+        None => None,
+    };
+    let (return_type, named) = rest[..open].rsplit_once(' ')?;
+    if named.contains('.') {
+        return Some(None);
+    }
+    let params = &rest[open + 1..close];
+    let mut descriptor = String::from("(");
+    for param in params.split(',').filter(|param| !param.is_empty()) {
+        descriptor.push_str(&type_descriptor(param));
+    }
+    descriptor.push(')');
+    descriptor.push_str(&type_descriptor(return_type));
+    Some(Some(MethodMapping {
+        named: named.to_string(),
+        obfuscated,
+        descriptor,
+        lines,
+        original_lines,
+    }))
+}
+
+fn internal_name(name: &str) -> String {
+    name.replace('.', "/")
+}
+
+/// The descriptor of a Java type as ProGuard writes it, such as
+/// `java.lang.String[]`.
+fn type_descriptor(ty: &str) -> String {
+    let mut ty = ty.trim();
+    let mut descriptor = String::new();
+    while let Some(element) = ty.strip_suffix("[]") {
+        descriptor.push('[');
+        ty = element;
+    }
+    let primitive = match ty {
+        "boolean" => "Z",
+        "byte" => "B",
+        "char" => "C",
+        "short" => "S",
+        "int" => "I",
+        "long" => "J",
+        "float" => "F",
+        "double" => "D",
+        "void" => "V",
+        _ => {
+            let _ = write!(descriptor, "L{};", internal_name(ty));
+            return descriptor;
+        }
+    };
+    descriptor.push_str(primitive);
+    descriptor
+}
+
+fn map_descriptor<'a>(descriptor: &'a str, mut map: impl FnMut(&'a str) -> &'a str) -> String {
+    let mut out = String::with_capacity(descriptor.len());
+    let mut rest = descriptor;
+    while let Some(start) = rest.find('L') {
+        let Some(end) = rest[start..].find(';') else {
+            break;
+        };
+        out.push_str(&rest[..=start]);
+        out.push_str(map(&rest[start + 1..start + end]));
+        out.push(';');
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGUARD: &str = "\
+# {\"fileName\":\"client.txt\",\"id\":\"sourceFile\"}
+net.minecraft.Util -> ad:
+# {\"fileName\":\"Util.java\",\"id\":\"sourceFile\"}
+    java.lang.String NAME -> a
+    net.minecraft.Util$Holder[] holders -> b
+    12:14:void <init>() -> <init>
+    20:25:net.minecraft.Util$Holder get(int,java.lang.String[]) -> a
+    21:21:int net.minecraft.Util$Holder.size():40:40 -> a
+    30:30:long millis():33:33 -> b
+    void abstractThing(net.minecraft.Util) -> c
+net.minecraft.Util$Holder -> ad$a:
+    int size -> a
+";
+
+    #[test]
+    fn test_parse_proguard() {
+        let mappings = Mappings::parse(PROGUARD).unwrap();
+        assert_eq!(mappings.classes().len(), 2);
+        let util = mappings.class("net/minecraft/Util").unwrap();
+        assert_eq!(util.obfuscated, "ad");
+        assert_eq!(
+            util.field("holders").unwrap().descriptor,
+            "[Lnet/minecraft/Util$Holder;"
+        );
+        // the inlined frame from Holder is not one of Util's methods
+        assert_eq!(util.methods.len(), 4);
+        let get = &util.methods[1];
+        assert_eq!(get.named, "get");
+        assert_eq!(
+            get.descriptor,
+            "(I[Ljava/lang/String;)Lnet/minecraft/Util$Holder;"
+        );
+        assert_eq!(get.lines, Some((20, 25)));
+        assert_eq!(util.methods[2].original_lines, Some((33, 33)));
+        assert_eq!(util.methods[3].lines, None);
+
+        assert!(matches!(
+            Mappings::parse("a.B -> c\n"),
+            Err(Error::InvalidMappings { line: 1, .. })
+        ));
+        assert!(matches!(
+            Mappings::parse("# header\n    int a -> b\n"),
+            Err(Error::InvalidMappings { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_lookups() {
+        let mappings = Mappings::parse(PROGUARD).unwrap();
+        assert_eq!(
+            mappings.obfuscate_class("net/minecraft/Util$Holder"),
+            "ad$a"
+        );
+        assert_eq!(mappings.deobfuscate_class("ad"), "net/minecraft/Util");
+        assert_eq!(
+            mappings.obfuscate_class("java/lang/String"),
+            "java/lang/String"
+        );
+        assert_eq!(
+            mappings.obfuscate_method(
+                "net/minecraft/Util",
+                "get",
+                "(I[Ljava/lang/String;)Lnet/minecraft/Util$Holder;"
+            ),
+            Some(("a", "(I[Ljava/lang/String;)Lad$a;".to_string()))
+        );
+        assert_eq!(
+            mappings.obfuscate_field("net/minecraft/Util", "holders"),
+            Some(("b", "[Lad$a;".to_string()))
+        );
+        let method = mappings.deobfuscate_method("ad", "c", "(Lad;)V").unwrap();
+        assert_eq!(method.named, "abstractThing");
+        assert_eq!(
+            mappings.deobfuscate_field("ad$a", "a").unwrap().named,
+            "size"
+        );
+    }
+
+    #[test]
+    fn test_emitters() {
+        let mappings = Mappings::parse(PROGUARD).unwrap();
+        let tiny = mappings.to_tiny_v2();
+        let mut lines = tiny.lines();
+        assert_eq!(lines.next(), Some("tiny\t2\t0\tofficial\tnamed"));
+        assert_eq!(lines.next(), Some("c\tad\tnet/minecraft/Util"));
+        assert_eq!(lines.next(), Some("\tf\tLjava/lang/String;\ta\tNAME"));
+        assert!(tiny.contains("\tm\t(I[Ljava/lang/String;)Lad$a;\ta\tget\n"));
+
+        let srg = mappings.to_srg();
+        assert!(srg.starts_with("CL: ad net/minecraft/Util\nCL: ad$a net/minecraft/Util$Holder\n"));
+        assert!(srg.contains("FD: ad$a/a net/minecraft/Util$Holder/size\n"));
+        assert!(srg.contains(
+            "MD: ad/c (Lad;)V net/minecraft/Util/abstractThing (Lnet/minecraft/Util;)V\n"
+        ));
+
+        let tsrg = mappings.to_tsrg();
+        assert!(tsrg.starts_with("ad net/minecraft/Util\n\ta NAME\n\tb holders\n"));
+        assert!(tsrg.contains("\tb ()J millis\n"));
+        assert!(tsrg.ends_with("ad$a net/minecraft/Util$Holder\n\ta size\n"));
+    }
+}
@@ -0,0 +1,211 @@
+//! The rules that make libraries and arguments of a version JSON apply only
+//! on some machines or with some launcher features, evaluated as the
+//! vanilla launcher does.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+};
+
+use regex_lite::Regex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum OsName {
+    Linux,
+    Windows,
+    Osx,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub action: RuleAction,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub os: Option<OsRule>,
+    /// Launcher features that must be on (`true`) or off (`false`).
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub features: BTreeMap<String, bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    Allow,
+    Disallow,
+}
+
+/// The machines a [`Rule`] is about; every part given has to match.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct OsRule {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub name: Option<OsName>,
+    /// A regular expression found in Java's `os.version`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub version: Option<String>,
+    /// Java's `os.arch`, such as `x86` for a 32-bit JVM.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub arch: Option<String>,
+}
+
+/// What rules are evaluated against: the machine the game is launched on
+/// and the launcher features in use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleContext {
+    pub os: OsName,
+    /// Java's `os.version`, if known. Rules about the OS version never apply
+    /// without it.
+    pub os_version: Option<String>,
+    /// Java's `os.arch`.
+    pub arch: String,
+    /// The features that are on, such as `is_demo_user` or
+    /// `has_custom_resolution`.
+    pub features: BTreeSet<String>,
+}
+
+impl OsName {
+    pub fn current() -> Self {
+        #[cfg(target_os = "windows")]
+        let current = Self::Windows;
+        #[cfg(target_os = "linux")]
+        let current = Self::Linux;
+        #[cfg(target_os = "macos")]
+        let current = Self::Osx;
+
+        current
+    }
+
+    pub fn is_current(&self) -> bool {
+        self == &Self::current()
+    }
+}
+
+impl Rule {
+    /// Whether the rule is about `context`, whatever its action.
+    pub fn applies(&self, context: &RuleContext) -> bool {
+        self.os.as_ref().is_none_or(|os| os.matches(context))
+            && self
+                .features
+                .iter()
+                .all(|(feature, on)| context.features.contains(feature) == *on)
+    }
+}
+
+/// Whether `rules` let something be used in `context`: nothing is allowed
+/// unless a rule allows it, and the last rule that applies decides. An empty
+/// list allows everything.
+pub fn rules_allow(rules: &[Rule], context: &RuleContext) -> bool {
+    if rules.is_empty() {
+        return true;
+    }
+    rules
+        .iter()
+        .rfind(|rule| rule.applies(context))
+        .is_some_and(|rule| rule.action == RuleAction::Allow)
+}
+
+impl OsRule {
+    pub fn matches(&self, context: &RuleContext) -> bool {
+        self.name.is_none_or(|name| name == context.os)
+            && self.arch.as_ref().is_none_or(|arch| *arch == context.arch)
+            && self.version.as_ref().is_none_or(|version| {
+                // a pattern the launcher could not compile never matches
+                let pattern = Regex::new(version).ok();
+                context
+                    .os_version
+                    .as_ref()
+                    .zip(pattern)
+                    .is_some_and(|(os_version, pattern)| pattern.is_match(os_version))
+            })
+    }
+}
+
+impl RuleContext {
+    /// The machine this runs on with no features on, its OS version unknown.
+    pub fn current() -> Self {
+        Self {
+            os: OsName::current(),
+            os_version: None,
+            arch: java_arch(env::consts::ARCH).to_string(),
+            features: BTreeSet::new(),
+        }
+    }
+
+    pub fn with_feature(mut self, feature: impl Into<String>) -> Self {
+        self.features.insert(feature.into());
+        self
+    }
+
+    /// `32` or `64`, what `${arch}` stands for in native classifiers.
+    pub fn arch_bits(&self) -> &'static str {
+        match self.arch.as_str() {
+            "x86" | "arm" => "32",
+            _ => "64",
+        }
+    }
+}
+
+/// The `os.arch` a JVM reports for one of Rust's target architectures.
+fn java_arch(arch: &str) -> &str {
+    match arch {
+        "x86_64" if cfg!(target_os = "macos") => "x86_64",
+        "x86_64" => "amd64",
+        arch => arch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(json: &str) -> Vec<Rule> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn context(os: OsName, arch: &str) -> RuleContext {
+        RuleContext {
+            os,
+            os_version: None,
+            arch: arch.to_string(),
+            features: BTreeSet::new(),
+        }
+    }
+
+    #[test]
+    fn test_last_matching_rule_wins() {
+        let linux = context(OsName::Linux, "amd64");
+        let osx = context(OsName::Osx, "aarch64");
+        // a library on everything but macOS
+        let all_but_osx =
+            rules(r#"[{"action": "allow"}, {"action": "disallow", "os": {"name": "osx"}}]"#);
+        assert!(rules_allow(&all_but_osx, &linux));
+        assert!(!rules_allow(&all_but_osx, &osx));
+        // and only on macOS
+        let only_osx = rules(r#"[{"action": "allow", "os": {"name": "osx"}}]"#);
+        assert!(!rules_allow(&only_osx, &linux));
+        assert!(rules_allow(&only_osx, &osx));
+        assert!(rules_allow(&[], &linux));
+    }
+
+    #[test]
+    fn test_os_version_arch_and_features() {
+        let mut windows = context(OsName::Windows, "x86");
+        let windows_10 =
+            rules(r#"[{"action": "allow", "os": {"name": "windows", "version": "^10\\."}}]"#);
+        assert!(!rules_allow(&windows_10, &windows));
+        windows.os_version = Some("10.0".into());
+        assert!(rules_allow(&windows_10, &windows));
+        windows.os_version = Some("6.1".into());
+        assert!(!rules_allow(&windows_10, &windows));
+
+        let x86 = rules(r#"[{"action": "allow", "os": {"arch": "x86"}}]"#);
+        assert!(rules_allow(&x86, &windows));
+        assert!(!rules_allow(&x86, &context(OsName::Windows, "amd64")));
+        assert_eq!(windows.arch_bits(), "32");
+
+        let demo = rules(r#"[{"action": "allow", "features": {"is_demo_user": true}}]"#);
+        let linux = context(OsName::Linux, "amd64");
+        assert!(!rules_allow(&demo, &linux));
+        assert!(rules_allow(&demo, &linux.with_feature("is_demo_user")));
+    }
+}
@@ -0,0 +1,112 @@
+//! A tiny HTTP/1.1 server standing in for Mojang's in tests, serving fixed
+//! files and honouring `Range: bytes=<start>-`.
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use url::Url;
+
+#[derive(Debug, Default)]
+struct State {
+    /// Each request's path and the start of its range, if it had one.
+    requests: Mutex<Vec<(String, Option<usize>)>>,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+pub(crate) struct TestServer {
+    pub(crate) url: Url,
+    state: Arc<State>,
+}
+
+impl TestServer {
+    /// Serves `files` by path, each response held back for `delay` so
+    /// overlapping requests can be seen.
+    pub(crate) fn start(files: Vec<(&str, Vec<u8>)>, delay: Duration) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap())
+            .parse()
+            .unwrap();
+        let files: Arc<HashMap<String, Vec<u8>>> = Arc::new(
+            files
+                .into_iter()
+                .map(|(path, body)| (format!("/{path}"), body))
+                .collect(),
+        );
+        let state = Arc::new(State::default());
+        let server_state = state.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let files = files.clone();
+                let state = server_state.clone();
+                thread::spawn(move || serve(stream, &files, &state, delay));
+            }
+        });
+        Self { url, state }
+    }
+
+    pub(crate) fn requests(&self) -> Vec<(String, Option<usize>)> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    /// The most requests that were answered at the same time.
+    pub(crate) fn max_in_flight(&self) -> usize {
+        self.state.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+fn serve(mut stream: TcpStream, files: &HashMap<String, Vec<u8>>, state: &State, delay: Duration) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let path = request_line
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or("/")
+        .to_string();
+    let mut range = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap() == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("range")
+        {
+            range = value
+                .trim()
+                .strip_prefix("bytes=")
+                .and_then(|value| value.strip_suffix('-'))
+                .and_then(|start| start.parse().ok());
+        }
+    }
+    state.requests.lock().unwrap().push((path.clone(), range));
+
+    let in_flight = state.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+    state.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+    thread::sleep(delay);
+    state.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+    let (status, body) = match files.get(&path) {
+        Some(body) => match range {
+            Some(start) if start <= body.len() => ("206 Partial Content", &body[start..]),
+            _ => ("200 OK", &body[..]),
+        },
+        None => ("404 Not Found", &[][..]),
+    };
+    let head = format!(
+        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(body);
+}
@@ -0,0 +1,655 @@
+//! A version JSON, as the launcher reads it from `versions/<id>/<id>.json`:
+//! what to download, how to launch it, and, for modded profiles such as
+//! Fabric's, the version it builds on.
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures_core::Stream;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    Cache, Error, OsName, ReleaseKind, Result, Rule, RuleContext, download_verified, rules_allow,
+};
+
+/// Where libraries without download information come from.
+pub const LIBRARIES_URL: &str = "https://libraries.minecraft.net/";
+
+/// The JVM arguments the launcher uses for versions that only have
+/// `minecraftArguments`.
+const LEGACY_JVM_ARGUMENTS: [&str; 3] = [
+    "-Djava.library.path=${natives_directory}",
+    "-cp",
+    "${classpath}",
+];
+
+/// A version JSON. A profile that `inherits_from` another lacks most of
+/// these until it is [merged](VersionManifest::merge) with its parent.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionManifest {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub inherits_from: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none", default)]
+    pub kind: Option<ReleaseKind>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub time: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub release_time: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub main_class: Option<String>,
+    /// The arguments of versions since 1.13.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub arguments: Option<Arguments>,
+    /// The game arguments of versions before 1.13, separated by spaces.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub minecraft_arguments: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub asset_index: Option<AssetIndexInfo>,
+    /// The id of the asset index, the same as `asset_index.id`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub assets: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub downloads: Option<VersionDownloads>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub java_version: Option<JavaVersion>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub libraries: Vec<Library>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub logging: Option<Logging>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub compliance_level: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub minimum_launcher_version: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Arguments {
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub game: Vec<Argument>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub jvm: Vec<Argument>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Argument {
+    Plain(String),
+    Conditional {
+        rules: Vec<Rule>,
+        value: ArgumentValue,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ArgumentValue {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JavaVersion {
+    /// The launcher's name for the Java runtime, such as `java-runtime-delta`.
+    pub component: String,
+    pub major_version: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Logging {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub client: Option<LoggingConfig>,
+}
+
+/// A logging configuration file and the JVM argument that points the game
+/// at it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LoggingConfig {
+    /// The argument, with `${path}` standing for the downloaded file.
+    pub argument: String,
+    pub file: LoggingFile,
+    /// The format of the file, `log4j2-xml`.
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LoggingFile {
+    pub id: String,
+    pub sha1: String,
+    pub size: u64,
+    pub url: Url,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct VersionDownloads {
+    pub client: DownloadInfo,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub client_mappings: Option<DownloadInfo>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub server: Option<DownloadInfo>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub server_mappings: Option<DownloadInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct DownloadInfo {
+    pub sha1: String,
+    pub size: u64,
+    pub url: Url,
+}
+
+/// Where a version's [`AssetIndex`](crate::AssetIndex) is downloaded from.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetIndexInfo {
+    pub id: String,
+    pub sha1: String,
+    pub size: u64,
+    /// The size of every object the index lists, added up.
+    pub total_size: u64,
+    pub url: Url,
+}
+
+/// A library, either with its downloads listed, as in vanilla versions, or
+/// found by its Maven coordinates in the repository at `url`, as in Fabric
+/// profiles.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Library {
+    /// Maven coordinates, `group:artifact:version[:classifier]`.
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub downloads: Option<LibraryDownloads>,
+    /// The Maven repository of a library without `downloads`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub url: Option<Url>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub sha1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub size: Option<u64>,
+    #[serde(skip_serializing_if = "LibraryExtractInstructions::is_empty", default)]
+    pub extract: LibraryExtractInstructions,
+    /// The classifier of the natives for each OS, where `${arch}` stands for
+    /// `32` or `64`.
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub natives: HashMap<OsName, String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LibraryExtractInstructions {
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LibraryDownloads {
+    /// Missing for libraries that only hold natives.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub artifact: Option<LibraryDownload>,
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub classifiers: HashMap<String, LibraryDownload>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LibraryDownload {
+    pub path: String,
+    pub sha1: String,
+    pub size: u64,
+    pub url: Url,
+}
+
+impl VersionManifest {
+    /// This profile laid over `parent`, the version it `inherits_from`, as
+    /// the launcher does: its own values win, its libraries come first and
+    /// replace the parent's of the same group and artifact, and its
+    /// arguments follow the parent's.
+    pub fn merge(self, parent: VersionManifest) -> VersionManifest {
+        let own: HashSet<String> = self
+            .libraries
+            .iter()
+            .map(|library| library_key(&library.name))
+            .collect();
+        let libraries = self
+            .libraries
+            .into_iter()
+            .chain(
+                parent
+                    .libraries
+                    .into_iter()
+                    .filter(|library| !own.contains(&library_key(&library.name))),
+            )
+            .collect();
+        let arguments = match (parent.arguments, self.arguments) {
+            (Some(mut parent), Some(own)) => {
+                parent.game.extend(own.game);
+                parent.jvm.extend(own.jvm);
+                Some(parent)
+            }
+            (parent, own) => own.or(parent),
+        };
+        VersionManifest {
+            id: self.id,
+            inherits_from: parent.inherits_from,
+            kind: self.kind.or(parent.kind),
+            time: self.time.or(parent.time),
+            release_time: self.release_time.or(parent.release_time),
+            main_class: self.main_class.or(parent.main_class),
+            arguments,
+            minecraft_arguments: self.minecraft_arguments.or(parent.minecraft_arguments),
+            asset_index: self.asset_index.or(parent.asset_index),
+            assets: self.assets.or(parent.assets),
+            downloads: self.downloads.or(parent.downloads),
+            java_version: self.java_version.or(parent.java_version),
+            libraries,
+            logging: self.logging.or(parent.logging),
+            compliance_level: self.compliance_level.or(parent.compliance_level),
+            minimum_launcher_version: self
+                .minimum_launcher_version
+                .max(parent.minimum_launcher_version),
+        }
+    }
+
+    /// The game arguments that apply in `context`, with their `${…}`
+    /// placeholders left in.
+    pub fn game_arguments(&self, context: &RuleContext) -> Vec<String> {
+        let legacy = self
+            .minecraft_arguments
+            .iter()
+            .flat_map(|arguments| arguments.split_whitespace());
+        let modern = self
+            .arguments
+            .iter()
+            .flat_map(|arguments| resolve(&arguments.game, context));
+        legacy.chain(modern).map(str::to_string).collect()
+    }
+
+    /// The JVM arguments that apply in `context`, with their `${…}`
+    /// placeholders left in.
+    pub fn jvm_arguments(&self, context: &RuleContext) -> Vec<String> {
+        let legacy = self
+            .minecraft_arguments
+            .iter()
+            .flat_map(|_| LEGACY_JVM_ARGUMENTS);
+        let modern = self
+            .arguments
+            .iter()
+            .flat_map(|arguments| resolve(&arguments.jvm, context));
+        legacy.chain(modern).map(str::to_string).collect()
+    }
+
+    /// The libraries used in `context`.
+    pub fn libraries_for<'a>(
+        &'a self,
+        context: &'a RuleContext,
+    ) -> impl Iterator<Item = &'a Library> + 'a {
+        self.libraries
+            .iter()
+            .filter(|library| library.is_allowed(context))
+    }
+}
+
+fn resolve<'a>(arguments: &'a [Argument], context: &RuleContext) -> Vec<&'a str> {
+    let mut resolved = Vec::new();
+    for argument in arguments {
+        match argument {
+            Argument::Plain(value) => resolved.push(value.as_str()),
+            Argument::Conditional { rules, value } if rules_allow(rules, context) => match value {
+                ArgumentValue::One(value) => resolved.push(value.as_str()),
+                ArgumentValue::Many(values) => resolved.extend(values.iter().map(String::as_str)),
+            },
+            Argument::Conditional { .. } => {}
+        }
+    }
+    resolved
+}
+
+impl DownloadInfo {
+    /// Downloads the file into memory, checking its hash and size.
+    pub async fn download(&self) -> Result<Bytes> {
+        download_verified(&self.url, &self.sha1, Some(self.size)).await
+    }
+
+    /// The path of the file in `cache`, downloading it if it is not there.
+    pub async fn fetch(&self, cache: &Cache) -> Result<PathBuf> {
+        cache.fetch(&self.url, &self.sha1, Some(self.size)).await
+    }
+
+    /// The unchecked body of the download, for callers that verify it
+    /// themselves.
+    pub async fn download_as_stream(
+        &self,
+    ) -> reqwest::Result<impl Stream<Item = reqwest::Result<Bytes>> + use<>> {
+        Ok(reqwest::get(self.url.clone())
+            .await?
+            .error_for_status()?
+            .bytes_stream())
+    }
+
+    pub async fn download_as_string(&self) -> Result<String> {
+        let bytes = self.download().await?;
+        String::from_utf8(bytes.into())
+            .map_err(|error| Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, error)))
+    }
+}
+
+impl Library {
+    pub fn is_allowed(&self, context: &RuleContext) -> bool {
+        rules_allow(&self.rules, context)
+    }
+
+    /// Downloads the jar and the natives of the library used on this
+    /// machine, each with its path under the libraries directory.
+    pub async fn download(&self) -> Result<Vec<(String, Bytes)>> {
+        let mut files = Vec::new();
+        for file in self.files(&RuleContext::current()) {
+            files.push((file.path.clone(), file.download().await?));
+        }
+        Ok(files)
+    }
+
+    /// [`Library::download`] as unchecked streams.
+    pub async fn download_as_stream(
+        &self,
+    ) -> reqwest::Result<Vec<(String, impl Stream<Item = reqwest::Result<Bytes>>)>> {
+        let mut files = Vec::new();
+        for file in self.files(&RuleContext::current()) {
+            files.push((file.path.clone(), file.download_as_stream().await?));
+        }
+        Ok(files)
+    }
+
+    /// The jar and the natives of the library used in `context`, nothing if
+    /// its rules leave it out.
+    pub fn files(&self, context: &RuleContext) -> Vec<Cow<'_, LibraryDownload>> {
+        if !self.is_allowed(context) {
+            return Vec::new();
+        }
+        let native = self.native_for(context).map(Cow::Borrowed);
+        self.artifact().into_iter().chain(native).collect()
+    }
+
+    /// The library's jar. For a library found by its coordinates, that is
+    /// only known if the profile lists its SHA-1 and size, as Fabric's do.
+    pub fn artifact(&self) -> Option<Cow<'_, LibraryDownload>> {
+        if let Some(downloads) = &self.downloads {
+            return downloads.artifact.as_ref().map(Cow::Borrowed);
+        }
+        let path = maven_path(&self.name)?;
+        let repository = match &self.url {
+            Some(url) => url.clone(),
+            None => LIBRARIES_URL.parse().ok()?,
+        };
+        Some(Cow::Owned(LibraryDownload {
+            url: repository.join(&path).ok()?,
+            path,
+            sha1: self.sha1.clone()?,
+            size: self.size?,
+        }))
+    }
+
+    /// The natives for this machine.
+    pub fn native(&self) -> Option<&LibraryDownload> {
+        self.native_for(&RuleContext::current())
+    }
+
+    pub fn native_for(&self, context: &RuleContext) -> Option<&LibraryDownload> {
+        let classifier = self
+            .natives
+            .get(&context.os)?
+            .replace("${arch}", context.arch_bits());
+        self.downloads.as_ref()?.classifiers.get(&classifier)
+    }
+}
+
+impl LibraryDownload {
+    /// Downloads the file into memory, checking its hash and size.
+    pub async fn download(&self) -> Result<Bytes> {
+        download_verified(&self.url, &self.sha1, Some(self.size)).await
+    }
+
+    /// The path of the file in `cache`, downloading it if it is not there.
+    pub async fn fetch(&self, cache: &Cache) -> Result<PathBuf> {
+        cache.fetch(&self.url, &self.sha1, Some(self.size)).await
+    }
+
+    /// The unchecked body of the download, for callers that verify it
+    /// themselves.
+    pub async fn download_as_stream(
+        &self,
+    ) -> reqwest::Result<impl Stream<Item = reqwest::Result<Bytes>> + use<>> {
+        Ok(reqwest::get(self.url.clone())
+            .await?
+            .error_for_status()?
+            .bytes_stream())
+    }
+}
+
+impl LibraryExtractInstructions {
+    pub fn is_empty(&self) -> bool {
+        self.exclude.is_empty()
+    }
+}
+
+/// The path of a library in a Maven repository, from
+/// `group:artifact:version[:classifier][@extension]`.
+fn maven_path(name: &str) -> Option<String> {
+    let (coordinates, extension) = name.split_once('@').unwrap_or((name, "jar"));
+    let mut parts = coordinates.split(':');
+    let group = parts.next()?;
+    let artifact = parts.next()?;
+    let version = parts.next()?;
+    let file = match parts.next() {
+        Some(classifier) => format!("{artifact}-{version}-{classifier}.{extension}"),
+        None => format!("{artifact}-{version}.{extension}"),
+    };
+    Some(format!(
+        "{}/{artifact}/{version}/{file}",
+        group.replace('.', "/")
+    ))
+}
+
+/// A library's coordinates without its version, which a profile's library
+/// shares with the one of its parent it replaces.
+fn library_key(name: &str) -> String {
+    let mut parts: Vec<&str> = name.split('@').next().unwrap_or(name).split(':').collect();
+    if parts.len() > 2 {
+        parts.remove(2);
+    }
+    parts.join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    const VANILLA: &str = r#"{
+        "arguments": {
+            "game": [
+                "--username", "${auth_player_name}",
+                {"rules": [{"action": "allow", "features": {"is_demo_user": true}}], "value": "--demo"},
+                {"rules": [{"action": "allow", "features": {"has_custom_resolution": true}}],
+                 "value": ["--width", "${resolution_width}", "--height", "${resolution_height}"]}
+            ],
+            "jvm": [
+                {"rules": [{"action": "allow", "os": {"name": "osx"}}], "value": ["-XstartOnFirstThread"]},
+                {"rules": [{"action": "allow", "os": {"arch": "x86"}}], "value": "-Xss1M"},
+                "-Djava.library.path=${natives_directory}",
+                "-cp", "${classpath}"
+            ]
+        },
+        "assetIndex": {"id": "27", "sha1": "3f59ee2d1ee1e9e1f0e2df3e2e6c6d6f6c0e1b1a", "size": 460000, "totalSize": 800000000,
+                       "url": "https://piston-meta.mojang.com/v1/packages/3f59ee2d1ee1e9e1f0e2df3e2e6c6d6f6c0e1b1a/27.json"},
+        "assets": "27",
+        "complianceLevel": 1,
+        "downloads": {
+            "client": {"sha1": "a9993e364706816aba3e25717850c26c9cd0d89d", "size": 3, "url": "https://example.invalid/client.jar"},
+            "client_mappings": {"sha1": "a9993e364706816aba3e25717850c26c9cd0d89d", "size": 3, "url": "https://example.invalid/client.txt"}
+        },
+        "id": "1.21.10",
+        "javaVersion": {"component": "java-runtime-delta", "majorVersion": 21},
+        "libraries": [
+            {"downloads": {"artifact": {"path": "org/ow2/asm/asm/9.3/asm-9.3.jar", "sha1": "8e6300ef51c1d801a7ed62d07cd221aca3a90640",
+                                        "size": 122004, "url": "https://libraries.minecraft.net/org/ow2/asm/asm/9.3/asm-9.3.jar"}},
+             "name": "org.ow2.asm:asm:9.3"},
+            {"downloads": {"artifact": {"path": "org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3-natives-macos.jar", "sha1": "33a6efa288390490ce6eb6c3df47ac21ecf648cf",
+                                        "size": 1, "url": "https://libraries.minecraft.net/org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3-natives-macos.jar"}},
+             "name": "org.lwjgl:lwjgl:3.3.3:natives-macos",
+             "rules": [{"action": "allow", "os": {"name": "osx"}}]}
+        ],
+        "logging": {"client": {"argument": "-Dlog4j.configurationFile=${path}",
+                               "file": {"id": "client-1.21.2.xml", "sha1": "a9993e364706816aba3e25717850c26c9cd0d89d", "size": 3,
+                                        "url": "https://example.invalid/client-1.21.2.xml"},
+                               "type": "log4j2-xml"}},
+        "mainClass": "net.minecraft.client.main.Main",
+        "minimumLauncherVersion": 21,
+        "releaseTime": "2025-10-07T09:17:23+00:00",
+        "time": "2025-10-07T09:17:23+00:00",
+        "type": "release"
+    }"#;
+
+    const FABRIC: &str = r#"{
+        "id": "fabric-loader-0.17.2-1.21.10",
+        "inheritsFrom": "1.21.10",
+        "releaseTime": "2025-10-08T12:00:00+00:00",
+        "time": "2025-10-08T12:00:00+00:00",
+        "type": "release",
+        "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+        "arguments": {"game": [], "jvm": ["-DFabricMcEmu= net.minecraft.client.main.Main "]},
+        "libraries": [
+            {"name": "org.ow2.asm:asm:9.8", "url": "https://maven.fabricmc.net/",
+             "sha1": "dc19ecb3f7889b7860697215cae99c0f9b6f6b4b", "size": 126113},
+            {"name": "net.fabricmc:fabric-loader:0.17.2", "url": "https://maven.fabricmc.net/"}
+        ]
+    }"#;
+
+    fn context(os: OsName) -> RuleContext {
+        RuleContext {
+            os,
+            os_version: None,
+            arch: "amd64".into(),
+            features: BTreeSet::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_vanilla() {
+        let manifest: VersionManifest = serde_json::from_str(VANILLA).unwrap();
+        assert_eq!(manifest.kind, Some(ReleaseKind::Release));
+        assert_eq!(
+            manifest.main_class.as_deref(),
+            Some("net.minecraft.client.main.Main")
+        );
+        assert_eq!(manifest.java_version.as_ref().unwrap().major_version, 21);
+        assert_eq!(manifest.asset_index.as_ref().unwrap().id, "27");
+        let logging = manifest.logging.as_ref().unwrap().client.as_ref().unwrap();
+        assert_eq!(logging.file.id, "client-1.21.2.xml");
+        assert!(manifest.downloads.as_ref().unwrap().server.is_none());
+
+        let linux = context(OsName::Linux);
+        assert_eq!(
+            manifest.game_arguments(&linux),
+            ["--username", "${auth_player_name}"]
+        );
+        let custom = linux.clone().with_feature("has_custom_resolution");
+        assert_eq!(manifest.game_arguments(&custom).len(), 6);
+        assert_eq!(
+            manifest.jvm_arguments(&context(OsName::Osx))[0],
+            "-XstartOnFirstThread"
+        );
+        assert_eq!(manifest.jvm_arguments(&linux).len(), 3);
+        assert_eq!(manifest.libraries_for(&linux).count(), 1);
+        assert_eq!(manifest.libraries_for(&context(OsName::Osx)).count(), 2);
+
+        // and back to the same JSON
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert_eq!(
+            serde_json::from_str::<VersionManifest>(&json).unwrap(),
+            manifest
+        );
+    }
+
+    #[test]
+    fn test_legacy_arguments() {
+        let manifest: VersionManifest = serde_json::from_str(
+            r#"{"id": "1.12.2", "minecraftArguments": "--username ${auth_player_name} --version ${version_name}"}"#,
+        )
+        .unwrap();
+        let linux = context(OsName::Linux);
+        assert_eq!(manifest.game_arguments(&linux).len(), 4);
+        assert_eq!(manifest.jvm_arguments(&linux), LEGACY_JVM_ARGUMENTS);
+    }
+
+    #[test]
+    fn test_merge_fabric() {
+        let vanilla: VersionManifest = serde_json::from_str(VANILLA).unwrap();
+        let fabric: VersionManifest = serde_json::from_str(FABRIC).unwrap();
+        assert_eq!(fabric.inherits_from.as_deref(), Some("1.21.10"));
+        let merged = fabric.merge(vanilla);
+        assert_eq!(merged.id, "fabric-loader-0.17.2-1.21.10");
+        assert_eq!(merged.inherits_from, None);
+        assert_eq!(
+            merged.main_class.as_deref(),
+            Some("net.fabricmc.loader.impl.launch.knot.KnotClient")
+        );
+        assert_eq!(merged.assets.as_deref(), Some("27"));
+        assert!(merged.downloads.is_some());
+
+        // fabric's asm replaces vanilla's
+        let names: Vec<&str> = merged
+            .libraries
+            .iter()
+            .map(|library| library.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "org.ow2.asm:asm:9.8",
+                "net.fabricmc:fabric-loader:0.17.2",
+                "org.lwjgl:lwjgl:3.3.3:natives-macos"
+            ]
+        );
+        let asm = merged.libraries[0].artifact().unwrap();
+        assert_eq!(
+            asm.url.as_str(),
+            "https://maven.fabricmc.net/org/ow2/asm/asm/9.8/asm-9.8.jar"
+        );
+        assert_eq!(asm.size, 126113);
+        // no checksum to download it by
+        assert!(merged.libraries[1].artifact().is_none());
+
+        let jvm = merged.jvm_arguments(&context(OsName::Linux));
+        assert_eq!(
+            jvm.last().unwrap(),
+            "-DFabricMcEmu= net.minecraft.client.main.Main "
+        );
+        assert_eq!(jvm.len(), 4);
+    }
+
+    #[test]
+    fn test_maven_path() {
+        assert_eq!(
+            maven_path("org.lwjgl:lwjgl:3.3.3:natives-linux").unwrap(),
+            "org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3-natives-linux.jar"
+        );
+        assert_eq!(
+            maven_path("com.mojang:text2speech:1.17.9@zip").unwrap(),
+            "com/mojang/text2speech/1.17.9/text2speech-1.17.9.zip"
+        );
+        assert_eq!(maven_path("broken"), None);
+        assert_eq!(
+            library_key("org.lwjgl:lwjgl:3.3.3:natives-linux"),
+            "org.lwjgl:lwjgl:natives-linux"
+        );
+    }
+}
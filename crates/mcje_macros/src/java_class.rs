@@ -0,0 +1,457 @@
+//! `java_class!`: typed wrappers around Java classes, declared by their
+//! members.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    Attribute, Error, Expr, FnArg, Ident, Lit, LitStr, Meta, Pat, Result, ReturnType, Token,
+    TraitItemFn, Type, Visibility, braced,
+    parse::{Parse, ParseStream},
+};
+
+/// Every class of one `java_class!`.
+pub struct Classes(Vec<Class>);
+
+/// `#[class = "java/lang/Iterator"] pub struct JIterator { members }`
+struct Class {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    path: LitStr,
+    members: Vec<Member>,
+}
+
+struct Member {
+    attrs: Vec<Attribute>,
+    kind: MemberKind,
+    /// The Java name, which defaults to the Rust one in camel case.
+    java_name: String,
+    /// Whether the member is on objects rather than the class.
+    instance: bool,
+    name: Ident,
+    params: Vec<Param>,
+    output: Type,
+    /// The Java type the member declares when it is not the one of the Rust
+    /// type, such as `java/lang/Object` for an erased generic.
+    java_type: Option<LitStr>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MemberKind {
+    Field,
+    Method,
+    Constructor,
+}
+
+struct Param {
+    pat: Pat,
+    ty: Type,
+    java_type: Option<LitStr>,
+}
+
+impl Parse for Classes {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut classes = Vec::new();
+        while !input.is_empty() {
+            classes.push(input.parse()?);
+        }
+        Ok(Self(classes))
+    }
+}
+
+impl Parse for Class {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![struct]>()?;
+        let name: Ident = input.parse()?;
+        let path = take_string(&mut attrs, "class")?
+            .flatten()
+            .ok_or_else(|| Error::new(name.span(), "missing `#[class = \"...\"]`"))?;
+
+        let content;
+        braced!(content in input);
+        let mut members = Vec::new();
+        while !content.is_empty() {
+            members.push(Member::new(content.parse()?)?);
+        }
+
+        Ok(Self {
+            attrs,
+            vis,
+            name,
+            path,
+            members,
+        })
+    }
+}
+
+impl Member {
+    fn new(item: TraitItemFn) -> Result<Self> {
+        let span = item.sig.ident.span();
+        if let Some(default) = &item.default {
+            return Err(Error::new_spanned(default, "members have no body"));
+        }
+        let mut attrs = item.attrs;
+        let java_type = take_string(&mut attrs, "java")?.flatten();
+        let mut kinds = [
+            (MemberKind::Field, take_string(&mut attrs, "field")?),
+            (MemberKind::Method, take_string(&mut attrs, "method")?),
+            (
+                MemberKind::Constructor,
+                take_string(&mut attrs, "constructor")?,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(kind, name)| name.map(|name| (kind, name)));
+        let (kind, java_name) = kinds.next().ok_or_else(|| {
+            Error::new(span, "expected `#[field]`, `#[method]` or `#[constructor]`")
+        })?;
+        if kinds.next().is_some() {
+            return Err(Error::new(
+                span,
+                "a member is one of a field, a method or a constructor",
+            ));
+        }
+
+        let mut instance = false;
+        let mut params = Vec::new();
+        for input in item.sig.inputs {
+            match input {
+                FnArg::Receiver(receiver) => {
+                    if receiver.reference.is_none() || receiver.mutability.is_some() {
+                        return Err(Error::new_spanned(receiver, "members take `&self`"));
+                    }
+                    instance = true;
+                }
+                FnArg::Typed(mut param) => {
+                    let java_type = take_string(&mut param.attrs, "java")?.flatten();
+                    params.push(Param {
+                        pat: *param.pat,
+                        ty: *param.ty,
+                        java_type,
+                    });
+                }
+            }
+        }
+        let output = match item.sig.output {
+            ReturnType::Default => syn::parse_quote!(()),
+            ReturnType::Type(_, ty) => *ty,
+        };
+
+        match kind {
+            MemberKind::Field if !params.is_empty() => {
+                return Err(Error::new(span, "fields take no parameters"));
+            }
+            MemberKind::Constructor if instance => {
+                return Err(Error::new(span, "constructors do not take `&self`"));
+            }
+            MemberKind::Constructor if !matches!(&output, Type::Path(path) if path.path.is_ident("Self")) =>
+            {
+                return Err(Error::new_spanned(output, "constructors return `Self`"));
+            }
+            _ => {}
+        }
+
+        let name = item.sig.ident;
+        let java_name = match java_name {
+            Some(java_name) => java_name.value(),
+            None if kind == MemberKind::Constructor => "<init>".into(),
+            None => camel_case(&name.to_string()),
+        };
+        Ok(Self {
+            attrs,
+            kind,
+            java_name,
+            instance,
+            name,
+            params,
+            output,
+            java_type,
+        })
+    }
+}
+
+/// Removes the `#[name]` or `#[name = "..."]` attribute from `attrs`, giving
+/// `Some(None)` for the first form.
+fn take_string(attrs: &mut Vec<Attribute>, name: &str) -> Result<Option<Option<LitStr>>> {
+    let Some(index) = attrs.iter().position(|attr| attr.path().is_ident(name)) else {
+        return Ok(None);
+    };
+    let attr = attrs.remove(index);
+    match attr.meta {
+        Meta::Path(_) => Ok(Some(None)),
+        Meta::NameValue(meta) => match meta.value {
+            Expr::Lit(syn::ExprLit {
+                lit: Lit::Str(value),
+                ..
+            }) => Ok(Some(Some(value))),
+            value => Err(Error::new_spanned(value, "expected a string")),
+        },
+        Meta::List(list) => Err(Error::new_spanned(list, "expected `#[name = \"...\"]`")),
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+/// The descriptor of `ty`, written as `java_type` if given.
+fn descriptor(ty: &Type, java_type: Option<&LitStr>) -> TokenStream {
+    if let Some(java_type) = java_type {
+        let descriptor = LitStr::new(&format!("L{};", java_type.value()), java_type.span());
+        return quote!(#descriptor);
+    }
+    let mut ty = ty;
+    while let Type::Reference(reference) = ty {
+        ty = &reference.elem;
+    }
+    quote!(<#ty as ::mcje::JavaType>::DESCRIPTOR)
+}
+
+pub fn expand(classes: Classes) -> TokenStream {
+    classes.0.iter().map(Class::expand).collect()
+}
+
+impl Class {
+    fn expand(&self) -> TokenStream {
+        let Self {
+            attrs,
+            vis,
+            name,
+            path,
+            members,
+        } = self;
+        let descriptor = LitStr::new(&format!("L{};", path.value()), path.span());
+        let members = members.iter().map(|member| member.expand(vis));
+
+        quote! {
+            #(#attrs)*
+            #[derive(Debug)]
+            #[repr(transparent)]
+            #vis struct #name<'local>(::mcje::jni::objects::JObject<'local>);
+
+            impl<'local> #name<'local> {
+                #(#members)*
+            }
+
+            impl ::mcje::JavaType for #name<'_> {
+                const DESCRIPTOR: &'static str = #descriptor;
+            }
+
+            impl<'local> ::mcje::FromJava<'local> for #name<'local> {
+                fn from_value(
+                    _env: &mut ::mcje::jni::JNIEnv<'local>,
+                    value: ::mcje::jni::objects::JValueOwned<'local>,
+                ) -> ::mcje::Result<Self> {
+                    Ok(Self(value.l()?))
+                }
+            }
+
+            impl<'local> ::mcje::ToJava<'local> for #name<'local> {
+                fn as_arg(&self) -> ::mcje::jni::objects::JValue<'local, '_> {
+                    ::mcje::jni::objects::JValueGen::Object(&self.0)
+                }
+            }
+
+            impl<'local> ::mcje::JavaClass<'local> for #name<'local> {
+                const CLASS: &'static str = #path;
+
+                fn from_object(object: ::mcje::jni::objects::JObject<'local>) -> Self {
+                    Self(object)
+                }
+
+                fn as_object(&self) -> &::mcje::jni::objects::JObject<'local> {
+                    &self.0
+                }
+
+                fn into_object(self) -> ::mcje::jni::objects::JObject<'local> {
+                    self.0
+                }
+            }
+
+            impl<'local> ::core::ops::Deref for #name<'local> {
+                type Target = ::mcje::jni::objects::JObject<'local>;
+
+                fn deref(&self) -> &Self::Target {
+                    &self.0
+                }
+            }
+
+            impl<'local> ::core::convert::AsRef<::mcje::jni::objects::JObject<'local>>
+                for #name<'local>
+            {
+                fn as_ref(&self) -> &::mcje::jni::objects::JObject<'local> {
+                    &self.0
+                }
+            }
+        }
+    }
+}
+
+impl Member {
+    fn expand(&self, vis: &Visibility) -> TokenStream {
+        let Self {
+            attrs,
+            kind,
+            java_name,
+            instance,
+            name,
+            params,
+            output,
+            java_type,
+        } = self;
+        let receiver = instance.then(|| quote!(&self,));
+        let env = Ident::new("env", Span::call_site());
+        let pats = params.iter().map(|param| &param.pat);
+        let types = params.iter().map(|param| &param.ty);
+        let args = params.iter().map(|param| {
+            let pat = &param.pat;
+            match param.ty {
+                Type::Reference(_) => quote!(::mcje::ToJava::as_arg(#pat)),
+                _ => quote!(::mcje::ToJava::as_arg(&#pat)),
+            }
+        });
+        let args = quote!(&[#(#args),*]);
+        let params_descriptor = params
+            .iter()
+            .map(|param| descriptor(&param.ty, param.java_type.as_ref()));
+        let output_descriptor = match kind {
+            MemberKind::Constructor => quote!("V"),
+            _ => descriptor(output, java_type.as_ref()),
+        };
+        let signature = quote! {
+            &<[&str]>::concat(&["(", #(#params_descriptor,)* ")", #output_descriptor])
+        };
+        let class = quote!(<Self as ::mcje::JavaClass>::CLASS);
+
+        let body = match (kind, instance) {
+            (MemberKind::Field, true) => quote! {
+                ::mcje::java::get_field(#env, &self.0, #java_name, #output_descriptor)
+            },
+            (MemberKind::Field, false) => quote! {
+                ::mcje::java::get_static_field(#env, #class, #java_name, #output_descriptor)
+            },
+            (MemberKind::Method, true) => quote! {
+                ::mcje::java::call_method(#env, &self.0, #java_name, #signature, #args)
+            },
+            (MemberKind::Method, false) => quote! {
+                ::mcje::java::call_static_method(#env, #class, #java_name, #signature, #args)
+            },
+            (MemberKind::Constructor, _) => quote! {
+                ::mcje::java::new_object(#env, #signature, #args)
+            },
+        };
+        quote! {
+            #(#attrs)*
+            #[inline]
+            #vis fn #name(
+                #receiver
+                #env: &mut ::mcje::jni::JNIEnv<'local>,
+                #(#pats: #types),*
+            ) -> ::mcje::Result<#output> {
+                #body
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_str(input: TokenStream) -> String {
+        expand(syn::parse2(input).unwrap()).to_string()
+    }
+
+    #[test]
+    fn test_camel_case() {
+        assert_eq!(camel_case("light_emission"), "lightEmission");
+        assert_eq!(camel_case("is_air"), "isAir");
+        assert_eq!(camel_case("x"), "x");
+    }
+
+    #[test]
+    fn test_expand_members() {
+        let expanded = expand_str(quote! {
+            #[class = "net/minecraft/world/level/block/state/BlockState"]
+            pub struct JBlockState {
+                #[field]
+                fn light_emission(&self) -> i32;
+                #[field]
+                #[java = "java/lang/Object"]
+                fn owner(&self) -> JBlock<'local>;
+                #[method = "getValue"]
+                #[java = "java/lang/Comparable"]
+                fn value(&self, property: &JProperty<'local>) -> JObject<'local>;
+            }
+        });
+        assert!(expanded.contains("struct JBlockState < 'local >"));
+        assert!(expanded.contains(
+            "const DESCRIPTOR : & 'static str = \"Lnet/minecraft/world/level/block/state/BlockState;\""
+        ));
+        assert!(expanded.contains(
+            "get_field (env , & self . 0 , \"lightEmission\" , < i32 as :: mcje :: JavaType > :: DESCRIPTOR)"
+        ));
+        assert!(expanded.contains("\"owner\" , \"Ljava/lang/Object;\""));
+        assert!(expanded.contains(
+            "[\"(\" , < JProperty < 'local > as :: mcje :: JavaType > :: DESCRIPTOR , \")\" , \"Ljava/lang/Comparable;\"]"
+        ));
+        assert!(expanded.contains(":: mcje :: ToJava :: as_arg (property)"));
+    }
+
+    #[test]
+    fn test_expand_statics_and_constructors() {
+        let expanded = expand_str(quote! {
+            #[class = "net/minecraft/core/BlockPos"]
+            pub struct JBlockPos {
+                #[constructor]
+                fn new(x: i32, y: i32, z: i32) -> Self;
+                #[field = "ZERO"]
+                fn zero() -> JBlockPos<'local>;
+            }
+        });
+        assert!(expanded.contains("new_object (env"));
+        assert!(expanded.contains(":: mcje :: ToJava :: as_arg (& x)"));
+        assert!(expanded.contains(
+            "get_static_field (env , < Self as :: mcje :: JavaClass > :: CLASS , \"ZERO\""
+        ));
+    }
+
+    #[test]
+    fn test_invalid_members() {
+        let error = |input: TokenStream| syn::parse2::<Classes>(input).err().unwrap().to_string();
+        assert!(
+            error(quote!(
+                pub struct JFoo {}
+            ))
+            .contains("#[class")
+        );
+        assert!(
+            error(quote! {
+                #[class = "Foo"]
+                struct JFoo { fn bar(&self) -> i32; }
+            })
+            .contains("expected `#[field]`")
+        );
+        assert!(
+            error(quote! {
+                #[class = "Foo"]
+                struct JFoo { #[field] fn bar(&self, x: i32) -> i32; }
+            })
+            .contains("no parameters")
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{ItemFn, parse_macro_input};
+use syn::{ItemFn, ReturnType, parse_macro_input};
+
+mod java_class;
 
 /// Generates the setup code for the main entry point.
 #[proc_macro_attribute]
@@ -14,6 +16,41 @@ pub fn test(_attr: TokenStream, item: TokenStream) -> TokenStream {
     generate_runner(item, true)
 }
 
+/// Declares typed wrappers around Java classes.
+///
+/// Each class is a struct holding the object, with a method per member that
+/// takes the environment and returns a `mcje::Result`, so that what Java
+/// throws comes back as a `JavaException` with its stack trace. The JNI
+/// descriptors are derived from the Rust types.
+///
+/// ```ignore
+/// java_class! {
+///     #[class = "net/minecraft/world/level/block/state/BlockState"]
+///     pub struct JBlockState {
+///         // the field `lightEmission`
+///         #[field]
+///         fn light_emission(&self) -> i32;
+///         // a generic field, erased to `Object`
+///         #[field]
+///         #[java = "java/lang/Object"]
+///         fn owner(&self) -> JBlock<'local>;
+///         #[method = "getFluidState"]
+///         fn fluid_state(&self) -> JFluidState<'local>;
+///     }
+/// }
+/// ```
+///
+/// Members are `#[field]`s, `#[method]`s or `#[constructor]`s, static when
+/// they do not take `&self`. Their Java name is the Rust one in camel case
+/// unless given as `#[method = "getValue"]`. `#[java = "..."]` on a member
+/// or a parameter declares its Java type when it is not the one of the Rust
+/// type. `Option` return types read `null` as `None`.
+#[proc_macro]
+pub fn java_class(input: TokenStream) -> TokenStream {
+    let classes = parse_macro_input!(input as java_class::Classes);
+    java_class::expand(classes).into()
+}
+
 fn generate_runner(item: TokenStream, is_test: bool) -> TokenStream {
     // 1. Parse the input function (the user's code)
     let input_fn = parse_macro_input!(item as ItemFn);
@@ -51,7 +88,18 @@ fn generate_runner(item: TokenStream, is_test: bool) -> TokenStream {
         quote! {}
     };
 
-    // 7. Generate the final code
+    // 7. Fail with what a function returning a `Result` gave, such as the
+    // stack trace of a Java exception
+    let call = match input_fn.sig.output {
+        ReturnType::Default => quote! { #inner_name(&mut env)#await_call; },
+        ReturnType::Type(..) => quote! {
+            if let Err(error) = #inner_name(&mut env)#await_call {
+                panic!("{error}");
+            }
+        },
+    };
+
+    // 8. Generate the final code
     let output = quote! {
         #macro_attr
         #fn_vis async fn #wrapper_name() {
@@ -62,7 +110,7 @@ fn generate_runner(item: TokenStream, is_test: bool) -> TokenStream {
             let mut env = jvm.attach_current_thread().unwrap();
             ::mcje::bootstrap(&mut env);
             // Call the user's function with the prepared environment
-            #inner_name(&mut env)#await_call;
+            #call
         }
     };
 
@@ -0,0 +1,653 @@
+//! Decoding and encoding of the chunk NBT written by `SerializableChunkData`.
+
+use hashbrown::HashMap;
+use mcre_core::{BlockPos, ChunkPos};
+use mcre_nbt::{Compound, Tag};
+use mcre_world::{
+    BiomeId, BitStorage, Block, BlockEntity, BlockEntityKind, BlockState, Chunk, ChunkData,
+    ChunkSection, HeightmapKind, LightUpdate, NibbleArray, PalettedContainer, PropVal,
+    SECTION_BIOMES, SECTION_BLOCKS, WorldHeight,
+};
+use rustc_hash::FxBuildHasher;
+
+use crate::{Error, Result};
+
+/// The first data version with the 1.18 chunk layout (21w43a), where
+/// sections carry their own `block_states` and `biomes` containers.
+pub const MIN_DATA_VERSION: i32 = 2844;
+
+/// The biome vanilla fills sections with when they are missing from a save.
+const DEFAULT_BIOME: &str = "minecraft:plains";
+
+/// A chunk as stored in a region file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedChunk {
+    pub pos: ChunkPos,
+    pub data_version: i32,
+    /// The generation status, such as `minecraft:full`.
+    pub status: String,
+    pub data: ChunkData,
+}
+
+impl SavedChunk {
+    /// A loaded chunk as a finished chunk of the given data version.
+    pub fn from_chunk(pos: ChunkPos, chunk: &Chunk, data_version: i32) -> Self {
+        Self {
+            pos,
+            data_version,
+            status: "minecraft:full".to_string(),
+            data: chunk.to_data(),
+        }
+    }
+
+    /// Whether generation finished. Only full chunks are sent to clients.
+    pub fn is_full(&self) -> bool {
+        self.status
+            .strip_prefix("minecraft:")
+            .unwrap_or(&self.status)
+            == "full"
+    }
+}
+
+/// Decodes chunk NBT into [`ChunkData`].
+///
+/// Saves name their block states and biomes, so the decoder resolves them to
+/// this build's [`BlockState`] ids and to [`BiomeId`]s it hands out in order
+/// of first appearance. Seed it with a registry's names to get matching ids.
+/// Unknown blocks decode as air and unknown properties are ignored; a value
+/// the block does not allow gives its default state.
+#[derive(Debug, Clone)]
+pub struct ChunkDecoder {
+    height: WorldHeight,
+    states: HashMap<String, BlockState, FxBuildHasher>,
+    blocks: HashMap<String, Option<Block>, FxBuildHasher>,
+    block_states: HashMap<Block, HashMap<Vec<PropVal>, BlockState, FxBuildHasher>, FxBuildHasher>,
+    biome_names: Vec<String>,
+    biome_ids: HashMap<String, BiomeId, FxBuildHasher>,
+}
+
+impl ChunkDecoder {
+    pub fn new(height: WorldHeight) -> Self {
+        Self {
+            height,
+            states: HashMap::default(),
+            blocks: HashMap::default(),
+            block_states: HashMap::default(),
+            biome_names: Vec::new(),
+            biome_ids: HashMap::default(),
+        }
+    }
+
+    /// A decoder whose biome ids start out as the indices of `names`.
+    pub fn with_biomes(height: WorldHeight, names: impl IntoIterator<Item = String>) -> Self {
+        let mut decoder = Self::new(height);
+        for name in names {
+            decoder.biome_id(&name);
+        }
+        decoder
+    }
+
+    pub fn height(&self) -> WorldHeight {
+        self.height
+    }
+
+    /// Biome names indexed by the [`BiomeId`]s handed out so far.
+    pub fn biome_names(&self) -> &[String] {
+        &self.biome_names
+    }
+
+    pub fn biome_name(&self, id: BiomeId) -> Option<&str> {
+        self.biome_names.get(id.0 as usize).map(String::as_str)
+    }
+
+    /// The id of a biome, assigning the next free one to new names.
+    pub fn biome_id(&mut self, name: &str) -> BiomeId {
+        if let Some(id) = self.biome_ids.get(name) {
+            return *id;
+        }
+        let id = BiomeId(self.biome_names.len() as u16);
+        self.biome_names.push(name.to_string());
+        self.biome_ids.insert(name.to_string(), id);
+        id
+    }
+
+    /// Resolves a palette entry such as `{Name:"minecraft:oak_log",
+    /// Properties:{axis:"y"}}`.
+    pub fn block_state(&mut self, entry: &Compound) -> Result<BlockState> {
+        let name = entry
+            .get_str("Name")
+            .ok_or(Error::Invalid("block state without a name"))?;
+        let props = entry.get_compound("Properties");
+
+        let mut key = name.to_string();
+        if let Some(props) = props {
+            for (prop, value) in props.iter() {
+                key.push_str(&format!(",{prop}={}", value.as_str().unwrap_or_default()));
+            }
+        }
+        if let Some(state) = self.states.get(&key) {
+            return Ok(*state);
+        }
+
+        let block = match self.blocks.get(name) {
+            Some(block) => *block,
+            None => {
+                let block = Block::from_name(name);
+                self.blocks.insert(name.to_string(), block);
+                block
+            }
+        };
+        let state = match block {
+            Some(block) => {
+                let default = block.default_state();
+                let mut wanted: Vec<PropVal> = default.props().collect();
+                for prop in
+                    props
+                        .into_iter()
+                        .flat_map(Compound::iter)
+                        .filter_map(|(prop, value)| {
+                            PropVal::parse_with_key(prop.parse().ok()?, value.as_str()?)
+                        })
+                {
+                    if let Some(slot) = wanted.iter_mut().find(|val| val.key() == prop.key()) {
+                        *slot = prop;
+                    }
+                }
+                // Indexing every state of the block at once is much cheaper
+                // than a `BlockState::from_props` scan per palette entry.
+                let states = self.block_states.entry(block).or_insert_with(|| {
+                    block
+                        .states()
+                        .map(|state| (state.props().collect(), state))
+                        .collect()
+                });
+                states.get(&wanted).copied().unwrap_or(default)
+            }
+            None => Block::AIR.default_state(),
+        };
+        self.states.insert(key, state);
+        Ok(state)
+    }
+
+    pub fn decode(&mut self, nbt: &Compound) -> Result<SavedChunk> {
+        let data_version = nbt
+            .get_int("DataVersion")
+            .ok_or(Error::Invalid("missing DataVersion"))?;
+        if data_version < MIN_DATA_VERSION {
+            return Err(Error::UnsupportedDataVersion(data_version));
+        }
+        let pos = ChunkPos::new(
+            nbt.get_int("xPos").ok_or(Error::Invalid("missing xPos"))?,
+            nbt.get_int("zPos").ok_or(Error::Invalid("missing zPos"))?,
+        );
+        let status = nbt
+            .get_str("Status")
+            .unwrap_or("minecraft:empty")
+            .to_string();
+
+        let height = self.height;
+        let mut sections: Vec<Option<ChunkSection>> = vec![None; height.section_count()];
+        let light_sections = height.section_count() + 2;
+        let mut light = LightUpdate {
+            sky: vec![None; light_sections],
+            block: vec![None; light_sections],
+        };
+        let light_on = nbt.get_bool("isLightOn").unwrap_or(false);
+
+        for section in nbt.get_list("sections").unwrap_or_default() {
+            let section = section
+                .as_compound()
+                .ok_or(Error::Invalid("section is not a compound"))?;
+            let y = i32::from(
+                section
+                    .get_byte("Y")
+                    .ok_or(Error::Invalid("section without Y"))?,
+            );
+
+            let index = y - height.min_section();
+            if index >= 0
+                && (index as usize) < sections.len()
+                && let Some(decoded) = self.decode_section(section)?
+            {
+                sections[index as usize] = Some(decoded);
+            }
+
+            let light_index = index + 1;
+            if light_on && light_index >= 0 && (light_index as usize) < light_sections {
+                for (key, layers) in [
+                    ("SkyLight", &mut light.sky),
+                    ("BlockLight", &mut light.block),
+                ] {
+                    if let Some(bytes) = section.get_byte_array(key) {
+                        let bytes: Vec<u8> = bytes.iter().map(|b| *b as u8).collect();
+                        layers[light_index as usize] = Some(
+                            NibbleArray::from_bytes(&bytes)
+                                .ok_or(Error::Invalid("light array has the wrong length"))?,
+                        );
+                    }
+                }
+            }
+        }
+
+        let sections = sections
+            .into_iter()
+            .map(|section| match section {
+                Some(section) => section,
+                None => ChunkSection::empty(self.biome_id(DEFAULT_BIOME)),
+            })
+            .collect();
+
+        let mut heightmaps = Vec::new();
+        if let Some(maps) = nbt.get_compound("Heightmaps") {
+            for kind in HeightmapKind::ALL {
+                if let Some(longs) = maps.get_long_array(kind.as_str()) {
+                    heightmaps.push((kind, longs.iter().map(|l| *l as u64).collect()));
+                }
+            }
+        }
+
+        let block_entities = nbt
+            .get_list("block_entities")
+            .unwrap_or_default()
+            .iter()
+            .filter_map(Tag::as_compound)
+            .map(decode_block_entity)
+            .collect::<Result<_>>()?;
+
+        Ok(SavedChunk {
+            pos,
+            data_version,
+            status,
+            data: ChunkData {
+                height,
+                sections,
+                heightmaps,
+                block_entities,
+                light,
+            },
+        })
+    }
+
+    /// Decodes a section's containers. Sections holding only light have
+    /// neither and decode to `None`.
+    fn decode_section(&mut self, section: &Compound) -> Result<Option<ChunkSection>> {
+        let (Some(states), Some(biomes)) = (
+            section.get_compound("block_states"),
+            section.get_compound("biomes"),
+        ) else {
+            return Ok(None);
+        };
+
+        let palette = states
+            .get_list("palette")
+            .ok_or(Error::Invalid("block_states without a palette"))?
+            .iter()
+            .map(|entry| {
+                let entry = entry
+                    .as_compound()
+                    .ok_or(Error::Invalid("block state is not a compound"))?;
+                self.block_state(entry)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let bits = block_state_bits(palette.len());
+        let states = unpack(palette, bits, states.get_long_array("data"), SECTION_BLOCKS).ok_or(
+            Error::Invalid("block_states data does not match its palette"),
+        )?;
+
+        let palette = biomes
+            .get_list("palette")
+            .ok_or(Error::Invalid("biomes without a palette"))?
+            .iter()
+            .map(|name| {
+                name.as_str()
+                    .map(|name| self.biome_id(name))
+                    .ok_or(Error::Invalid("biome is not a string"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let bits = ceil_log2(palette.len());
+        let biomes = unpack(palette, bits, biomes.get_long_array("data"), SECTION_BIOMES)
+            .ok_or(Error::Invalid("biomes data does not match its palette"))?;
+
+        Ok(ChunkSection::new(states, biomes))
+    }
+}
+
+fn ceil_log2(len: usize) -> u8 {
+    if len <= 1 {
+        0
+    } else {
+        (usize::BITS - (len - 1).leading_zeros()) as u8
+    }
+}
+
+/// Block states use at least 4 bits once there is more than one, like the
+/// linear and hash map palettes vanilla serializes through.
+fn block_state_bits(palette_len: usize) -> u8 {
+    match ceil_log2(palette_len) {
+        0 => 0,
+        bits => bits.max(4),
+    }
+}
+
+fn unpack<T: Copy + Eq>(
+    palette: Vec<T>,
+    bits: u8,
+    data: Option<&[i64]>,
+    size: usize,
+) -> Option<PalettedContainer<T>> {
+    if bits == 0 {
+        return (palette.len() == 1).then(|| PalettedContainer::single(palette[0], size));
+    }
+    let data = data?.iter().map(|l| *l as u64).collect();
+    PalettedContainer::from_palette(palette, BitStorage::from_raw(bits, size, data)?)
+}
+
+fn decode_block_entity(nbt: &Compound) -> Result<BlockEntity> {
+    let mut data = nbt.clone();
+    let mut coord = |key| {
+        data.remove(key)
+            .and_then(|tag| tag.as_i64())
+            .ok_or(Error::Invalid("block entity without a position"))
+    };
+    let pos = BlockPos::new(coord("x")? as i32, coord("y")? as i32, coord("z")? as i32);
+    let id = match data.remove("id") {
+        Some(Tag::String(id)) => id,
+        _ => return Err(Error::Invalid("block entity without an id")),
+    };
+    Ok(BlockEntity {
+        pos,
+        kind: BlockEntityKind::Name(id),
+        data: Some(data),
+    })
+}
+
+impl SavedChunk {
+    /// Encodes the chunk as vanilla saves it. `biome_names` is indexed by
+    /// [`BiomeId`], like [`ChunkDecoder::biome_names`].
+    ///
+    /// Block entities identified by a network id cannot be saved and make
+    /// this fail, as do biomes without a name.
+    pub fn to_nbt(&self, biome_names: &[String]) -> Result<Compound> {
+        let data = &self.data;
+        let height = data.height;
+        let mut nbt = Compound::new();
+        nbt.insert("DataVersion", self.data_version);
+        nbt.insert("xPos", self.pos.x);
+        nbt.insert("zPos", self.pos.z);
+        nbt.insert("yPos", height.min_section());
+        nbt.insert("Status", self.status.as_str());
+        nbt.insert("LastUpdate", 0i64);
+        nbt.insert("InhabitedTime", 0i64);
+        let light_on = data
+            .light
+            .sky
+            .iter()
+            .chain(&data.light.block)
+            .any(Option::is_some);
+        nbt.insert("isLightOn", light_on);
+
+        // One entry per light section, which extend one past the world at
+        // either end, skipping those with nothing to store.
+        let mut sections = Vec::new();
+        for light_index in 0..height.section_count() + 2 {
+            let mut section = Compound::new();
+            section.insert("Y", (height.min_section() - 1 + light_index as i32) as i8);
+            if let Some(blocks) = light_index
+                .checked_sub(1)
+                .and_then(|index| data.sections.get(index))
+            {
+                section.insert("block_states", encode_block_states(blocks));
+                section.insert("biomes", encode_biomes(blocks, biome_names)?);
+            }
+            for (key, layers) in [
+                ("BlockLight", &data.light.block),
+                ("SkyLight", &data.light.sky),
+            ] {
+                if let Some(Some(layer)) = layers.get(light_index) {
+                    let bytes: Vec<i8> = layer.as_bytes().iter().map(|b| *b as i8).collect();
+                    section.insert(key, bytes);
+                }
+            }
+            if section.len() > 1 {
+                sections.push(Tag::Compound(section));
+            }
+        }
+        nbt.insert("sections", sections);
+
+        let mut heightmaps = Compound::new();
+        for (kind, longs) in &data.heightmaps {
+            let longs: Vec<i64> = longs.iter().map(|l| *l as i64).collect();
+            heightmaps.insert(kind.as_str(), longs);
+        }
+        nbt.insert("Heightmaps", heightmaps);
+
+        let block_entities = data
+            .block_entities
+            .iter()
+            .map(|block_entity| encode_block_entity(block_entity).map(Tag::Compound))
+            .collect::<Result<Vec<_>>>()?;
+        nbt.insert("block_entities", block_entities);
+        Ok(nbt)
+    }
+}
+
+/// The palette of the values in use, in order of first appearance, and the
+/// packed indices into it, as `PalettedContainer.pack` produces them.
+fn pack<T: Copy + Eq>(
+    container: &PalettedContainer<T>,
+    bits_for: fn(usize) -> u8,
+) -> (Vec<T>, Option<Vec<i64>>) {
+    let old_palette = container.palette();
+    let mut remap = vec![u32::MAX; old_palette.len()];
+    let mut palette = Vec::new();
+    let indices: Vec<u32> = container
+        .storage()
+        .iter()
+        .map(|old| {
+            let new = &mut remap[old as usize];
+            if *new == u32::MAX {
+                *new = palette.len() as u32;
+                palette.push(old_palette[old as usize]);
+            }
+            *new
+        })
+        .collect();
+
+    let bits = bits_for(palette.len());
+    if bits == 0 {
+        return (palette, None);
+    }
+    let mut storage = BitStorage::new(bits, indices.len());
+    for (i, index) in indices.into_iter().enumerate() {
+        storage.set(i, index);
+    }
+    let data = storage.raw().iter().map(|l| *l as i64).collect();
+    (palette, Some(data))
+}
+
+fn encode_block_states(section: &ChunkSection) -> Compound {
+    let (palette, data) = pack(section.states(), block_state_bits);
+    let mut states = Compound::new();
+    let palette: Vec<Tag> = palette
+        .into_iter()
+        .map(|state| Tag::Compound(encode_block_state(state)))
+        .collect();
+    states.insert("palette", palette);
+    if let Some(data) = data {
+        states.insert("data", data);
+    }
+    states
+}
+
+/// The palette entry for a state, such as `{Name:"minecraft:oak_log",
+/// Properties:{axis:"y"}}`.
+pub fn encode_block_state(state: BlockState) -> Compound {
+    let mut entry = Compound::new();
+    entry.insert("Name", format!("minecraft:{}", state.block().name()));
+    let mut props = Compound::new();
+    for prop in state.props() {
+        let prop = prop.to_string();
+        if let Some((key, value)) = prop.split_once('=') {
+            props.insert(key, value);
+        }
+    }
+    if !props.is_empty() {
+        entry.insert("Properties", props);
+    }
+    entry
+}
+
+fn encode_biomes(section: &ChunkSection, biome_names: &[String]) -> Result<Compound> {
+    let (palette, data) = pack(section.biomes(), ceil_log2);
+    let mut biomes = Compound::new();
+    let palette = palette
+        .into_iter()
+        .map(|id| {
+            biome_names
+                .get(id.0 as usize)
+                .map(|name| Tag::from(name.as_str()))
+                .ok_or(Error::Invalid("biome id without a name"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    biomes.insert("palette", palette);
+    if let Some(data) = data {
+        biomes.insert("data", data);
+    }
+    Ok(biomes)
+}
+
+fn encode_block_entity(block_entity: &BlockEntity) -> Result<Compound> {
+    let BlockEntityKind::Name(id) = &block_entity.kind else {
+        return Err(Error::Invalid("block entity type has no name"));
+    };
+    let mut nbt = block_entity.data.clone().unwrap_or_default();
+    nbt.insert("id", id.as_str());
+    nbt.insert("x", block_entity.pos.x);
+    nbt.insert("y", block_entity.pos.y);
+    nbt.insert("z", block_entity.pos.z);
+    Ok(nbt)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use mcre_core::Axis;
+    use mcre_nbt::from_snbt;
+
+    use super::*;
+
+    /// A full chunk with stone below y=0, an oak log and a chest, in plains
+    /// and a desert top section.
+    pub(crate) fn sample_chunk_nbt(x: i32, z: i32) -> Compound {
+        let mut data = vec![0i64; 256];
+        // index 1 is the log at (1, 0, 0) of the section
+        data[0] = 1 << 4;
+        let mut nbt = from_snbt(&format!(
+            r#"{{
+                DataVersion: 4189, xPos: {x}, zPos: {z}, yPos: -4, Status: "minecraft:full",
+                isLightOn: 1b,
+                Heightmaps: {{MOTION_BLOCKING: [L; 1L, 2L]}},
+                block_entities: [{{id: "minecraft:chest", x: {bx}, y: 1, z: {bz}, Items: []}}],
+                sections: [
+                    {{Y: -5b, SkyLight: [B; {light}]}},
+                    {{
+                        Y: -4b,
+                        block_states: {{palette: [{{Name: "minecraft:stone"}}]}},
+                        biomes: {{palette: ["minecraft:plains"]}}
+                    }},
+                    {{
+                        Y: 19b,
+                        block_states: {{palette: [{{Name: "minecraft:air"}}]}},
+                        biomes: {{palette: ["minecraft:desert"]}}
+                    }}
+                ]
+            }}"#,
+            bx = x * 16,
+            bz = z * 16,
+            light = vec!["-1b"; 2048].join(","),
+        ))
+        .unwrap();
+
+        let mut section = from_snbt(
+            r#"{
+                Y: 0b,
+                block_states: {palette: [
+                    {Name: "minecraft:air"},
+                    {Name: "minecraft:oak_log", Properties: {axis: "x"}},
+                    {Name: "minecraft:chest", Properties: {facing: "west", type: "single", waterlogged: "false"}}
+                ]},
+                biomes: {palette: ["minecraft:plains", "minecraft:desert"], data: [L; 0L]}
+            }"#,
+        )
+        .unwrap();
+        section
+            .get_mut("block_states")
+            .and_then(Tag::as_compound_mut)
+            .unwrap()
+            .insert("data", data);
+        if let Some(Tag::List(sections)) = nbt.get_mut("sections") {
+            sections.push(section.into());
+        }
+        nbt
+    }
+
+    #[test]
+    fn test_decode_chunk() {
+        let mut decoder = ChunkDecoder::new(WorldHeight::OVERWORLD);
+        let chunk = decoder.decode(&sample_chunk_nbt(2, -3)).unwrap();
+        assert_eq!(chunk.pos, ChunkPos::new(2, -3));
+        assert!(chunk.is_full());
+
+        let data = &chunk.data;
+        assert_eq!(data.sections.len(), 24);
+        let stone = Block::STONE.default_state();
+        assert_eq!(data.sections[0].get_block(3, 3, 3), stone);
+        let log = data.sections[4].get_block(1, 0, 0);
+        assert_eq!(log.block(), Block::from_name("oak_log").unwrap());
+        assert_eq!(log.axis(), Axis::X);
+        assert!(data.sections[4].get_block(0, 0, 0).is_air());
+        // a section missing from the save
+        assert!(data.sections[1].is_empty());
+        assert_eq!(
+            decoder.biome_name(data.sections[23].get_biome(0, 0, 0)),
+            Some("minecraft:desert")
+        );
+
+        assert_eq!(data.light.sky[0], Some(NibbleArray::filled(15)));
+        assert_eq!(data.light.sky[1], None);
+        assert_eq!(
+            data.heightmaps,
+            [(HeightmapKind::MotionBlocking, vec![1, 2])]
+        );
+        assert_eq!(data.block_entities[0].pos, BlockPos::new(32, 1, -48));
+        assert_eq!(
+            data.block_entities[0].kind,
+            BlockEntityKind::Name("minecraft:chest".into())
+        );
+        assert!(
+            data.block_entities[0]
+                .data
+                .as_ref()
+                .unwrap()
+                .contains_key("Items")
+        );
+    }
+
+    #[test]
+    fn test_unknown_names_and_old_versions() {
+        let mut decoder =
+            ChunkDecoder::with_biomes(WorldHeight::OVERWORLD, ["minecraft:ocean".to_string()]);
+        let unknown = from_snbt(r#"{Name: "mod:machine", Properties: {on: "true"}}"#).unwrap();
+        assert!(decoder.block_state(&unknown).unwrap().is_air());
+        let log = from_snbt(r#"{Name: "minecraft:oak_log", Properties: {axis: "z", on: "true"}}"#)
+            .unwrap();
+        assert_eq!(decoder.block_state(&log).unwrap().axis(), Axis::Z);
+        assert_eq!(decoder.biome_id("minecraft:ocean"), BiomeId(0));
+        assert_eq!(decoder.biome_id("minecraft:plains"), BiomeId(1));
+
+        let old = from_snbt("{DataVersion: 2586, Level: {}}").unwrap();
+        assert!(matches!(
+            decoder.decode(&old),
+            Err(Error::UnsupportedDataVersion(2586))
+        ));
+    }
+}
@@ -0,0 +1,172 @@
+//! The per-chunk compression schemes of `RegionFileVersion`.
+
+use mcre_nbt::Compression;
+use xxhash_rust::xxh32::xxh32;
+
+use crate::{Error, Result};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChunkCompression {
+    Gzip = 1,
+    /// Vanilla's default, `region-file-compression=deflate`.
+    #[default]
+    Zlib = 2,
+    None = 3,
+    Lz4 = 4,
+}
+
+impl ChunkCompression {
+    /// Id 127 (custom, named compressors) is not supported.
+    pub fn from_id(id: u8) -> Result<Self> {
+        match id {
+            1 => Ok(Self::Gzip),
+            2 => Ok(Self::Zlib),
+            3 => Ok(Self::None),
+            4 => Ok(Self::Lz4),
+            _ => Err(Error::UnknownCompression(id)),
+        }
+    }
+
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    pub fn decompress(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::Gzip => Ok(Compression::Gzip.decompress(data)?),
+            Self::Zlib => Ok(Compression::Zlib.decompress(data)?),
+            Self::None => Ok(data.to_vec()),
+            Self::Lz4 => lz4_block_decompress(data),
+        }
+    }
+
+    pub fn compress(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Gzip => Compression::Gzip.compress(data),
+            Self::Zlib => Compression::Zlib.compress(data),
+            Self::None => data.to_vec(),
+            Self::Lz4 => lz4_block_compress(data),
+        }
+    }
+}
+
+// Constants of lz4-java's `LZ4BlockOutputStream`, which vanilla wraps chunks in.
+const LZ4_MAGIC: &[u8; 8] = b"LZ4Block";
+const LZ4_HEADER_LEN: usize = LZ4_MAGIC.len() + 13;
+const LZ4_METHOD_RAW: u8 = 0x10;
+const LZ4_METHOD_LZ4: u8 = 0x20;
+const LZ4_BLOCK_SIZE: usize = 1 << 16;
+/// `compressionLevel` for 64 KiB blocks, stored in the token's low nibble.
+const LZ4_LEVEL: u8 = 6;
+const LZ4_CHECKSUM_SEED: u32 = 0x9747_b28c;
+
+fn lz4_checksum(data: &[u8]) -> u32 {
+    xxh32(data, LZ4_CHECKSUM_SEED) & 0x0FFF_FFFF
+}
+
+fn lz4_block_decompress(mut data: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    while !data.is_empty() {
+        if data.len() < LZ4_HEADER_LEN || &data[..8] != LZ4_MAGIC {
+            return Err(Error::InvalidLz4);
+        }
+        let token = data[8];
+        let int_at = |i: usize| u32::from_le_bytes(data[i..i + 4].try_into().unwrap());
+        let compressed_len = int_at(9) as usize;
+        let original_len = int_at(13) as usize;
+        let checksum = int_at(17);
+        let payload = data
+            .get(LZ4_HEADER_LEN..LZ4_HEADER_LEN + compressed_len)
+            .ok_or(Error::InvalidLz4)?;
+        data = &data[LZ4_HEADER_LEN + compressed_len..];
+        if original_len == 0 {
+            // the end-of-stream marker
+            break;
+        }
+
+        let start = out.len();
+        match token & 0xF0 {
+            LZ4_METHOD_RAW if compressed_len == original_len => out.extend_from_slice(payload),
+            LZ4_METHOD_LZ4 => {
+                out.resize(start + original_len, 0);
+                let written = lz4_flex::block::decompress_into(payload, &mut out[start..])
+                    .map_err(|_| Error::InvalidLz4)?;
+                if written != original_len {
+                    return Err(Error::InvalidLz4);
+                }
+            }
+            _ => return Err(Error::InvalidLz4),
+        }
+        if lz4_checksum(&out[start..]) != checksum {
+            return Err(Error::InvalidLz4);
+        }
+    }
+    Ok(out)
+}
+
+fn lz4_block_compress(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() / 2 + LZ4_HEADER_LEN * 2);
+    let mut write_block = |method: u8, payload: &[u8], original: &[u8]| {
+        out.extend_from_slice(LZ4_MAGIC);
+        out.push(method | LZ4_LEVEL);
+        out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(original.len() as u32).to_le_bytes());
+        let checksum = if original.is_empty() {
+            0
+        } else {
+            lz4_checksum(original)
+        };
+        out.extend_from_slice(&checksum.to_le_bytes());
+        out.extend_from_slice(payload);
+    };
+    for block in data.chunks(LZ4_BLOCK_SIZE) {
+        let compressed = lz4_flex::block::compress(block);
+        if compressed.len() < block.len() {
+            write_block(LZ4_METHOD_LZ4, &compressed, block);
+        } else {
+            write_block(LZ4_METHOD_RAW, block, block);
+        }
+    }
+    write_block(LZ4_METHOD_RAW, &[], &[]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trips() {
+        let mut data = Vec::new();
+        for i in 0..200_000u32 {
+            data.push((i % 251) as u8 ^ (i >> 11) as u8);
+        }
+        for compression in [
+            ChunkCompression::Gzip,
+            ChunkCompression::Zlib,
+            ChunkCompression::None,
+            ChunkCompression::Lz4,
+        ] {
+            let compressed = compression.compress(&data);
+            assert_eq!(compression.decompress(&compressed).unwrap(), data);
+            assert_eq!(
+                ChunkCompression::from_id(compression.id()).unwrap(),
+                compression
+            );
+        }
+    }
+
+    #[test]
+    fn test_lz4_rejects_bad_checksums() {
+        let mut compressed = lz4_block_compress(b"hello hello hello hello");
+        compressed[17] ^= 1;
+        assert!(matches!(
+            lz4_block_decompress(&compressed),
+            Err(Error::InvalidLz4)
+        ));
+        assert!(matches!(
+            ChunkCompression::from_id(127),
+            Err(Error::UnknownCompression(127))
+        ));
+    }
+}
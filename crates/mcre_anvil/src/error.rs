@@ -0,0 +1,68 @@
+use std::{fmt, io};
+
+use mcre_core::ChunkPos;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Nbt(mcre_nbt::Error),
+    /// A region file shorter than its 8 KiB header.
+    TruncatedHeader,
+    /// A chunk whose sectors point outside the file or whose length does not
+    /// fit in them.
+    InvalidSectors,
+    UnknownCompression(u8),
+    /// An LZ4 stream that is not in `LZ4BlockOutputStream` format or fails
+    /// its checksum.
+    InvalidLz4,
+    /// A chunk saved before 1.18's format, which this crate does not read.
+    UnsupportedDataVersion(i32),
+    /// A chunk written to a region file that does not cover it.
+    OutsideRegion(ChunkPos),
+    /// Chunk NBT that is well-formed but not laid out as vanilla writes it.
+    Invalid(&'static str),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<mcre_nbt::Error> for Error {
+    fn from(error: mcre_nbt::Error) -> Self {
+        Self::Nbt(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "I/O error: {error}"),
+            Self::Nbt(error) => write!(f, "invalid NBT: {error}"),
+            Self::TruncatedHeader => f.write_str("region file is shorter than its header"),
+            Self::InvalidSectors => f.write_str("chunk sectors are out of bounds"),
+            Self::UnknownCompression(id) => write!(f, "unknown chunk compression type {id}"),
+            Self::InvalidLz4 => f.write_str("invalid LZ4 block stream"),
+            Self::UnsupportedDataVersion(version) => {
+                write!(f, "chunks with DataVersion {version} are not supported")
+            }
+            Self::OutsideRegion(pos) => {
+                write!(f, "chunk {}, {} is outside the region", pos.x, pos.z)
+            }
+            Self::Invalid(what) => write!(f, "invalid chunk: {what}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Nbt(error) => Some(error),
+            _ => None,
+        }
+    }
+}
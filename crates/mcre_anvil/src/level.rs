@@ -0,0 +1,615 @@
+//! `level.dat`, the gzipped root of a save, and the per-player
+//! `playerdata/<uuid>.dat` files.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use mcre_core::BlockPos;
+use mcre_nbt::{Compound, Tag};
+
+use crate::{Error, MIN_DATA_VERSION, Result};
+
+/// The Minecraft version this build targets, from the repository's
+/// `mc-version` file.
+pub fn mc_version() -> &'static str {
+    include_str!("../../../mc-version").trim()
+}
+
+/// The directory holding the region files of a dimension, following the
+/// layout of `DimensionType.getStorageFolder`.
+pub fn region_dir(world_dir: impl AsRef<Path>, dimension: &str) -> PathBuf {
+    let world_dir = world_dir.as_ref();
+    let dimension = dimension.strip_prefix("minecraft:").map_or_else(
+        || dimension.replacen(':', "/", 1),
+        |path| format!("minecraft/{path}"),
+    );
+    match dimension.as_str() {
+        "minecraft/overworld" => world_dir.join("region"),
+        "minecraft/the_nether" => world_dir.join("DIM-1").join("region"),
+        "minecraft/the_end" => world_dir.join("DIM1").join("region"),
+        path => world_dir.join("dimensions").join(path).join("region"),
+    }
+}
+
+pub fn player_data_path(world_dir: impl AsRef<Path>, uuid: &str) -> PathBuf {
+    world_dir
+        .as_ref()
+        .join("playerdata")
+        .join(format!("{uuid}.dat"))
+}
+
+fn read_root(path: &Path) -> Result<Compound> {
+    let (_, root) = mcre_nbt::from_compressed_bytes(&fs::read(path)?)?;
+    Ok(root)
+}
+
+/// The `Version` compound, present since 1.9.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveVersion {
+    /// The data version, the same as `DataVersion`.
+    pub id: i32,
+    pub name: String,
+    /// `main` unless the save comes from an experimental branch.
+    pub series: String,
+    pub snapshot: bool,
+}
+
+/// Where new players appear.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spawn {
+    pub dimension: String,
+    pub pos: BlockPos,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+/// The game rules as saved: string values before 1.21.11, typed values
+/// under namespaced names since.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameRules(pub Compound);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameRuleValue {
+    Bool(bool),
+    Int(i32),
+}
+
+impl GameRules {
+    /// Looks a rule up by its saved name, also trying it with the
+    /// `minecraft:` namespace.
+    pub fn get(&self, name: &str) -> Option<GameRuleValue> {
+        let tag = self
+            .0
+            .get(name)
+            .or_else(|| self.0.get(&format!("minecraft:{name}")))?;
+        match tag {
+            Tag::String(value) => match value.as_str() {
+                "true" => Some(GameRuleValue::Bool(true)),
+                "false" => Some(GameRuleValue::Bool(false)),
+                value => value.parse().ok().map(GameRuleValue::Int),
+            },
+            Tag::Byte(value) => Some(GameRuleValue::Bool(*value != 0)),
+            tag => tag.as_i64().map(|value| GameRuleValue::Int(value as i32)),
+        }
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        match self.get(name)? {
+            GameRuleValue::Bool(value) => Some(value),
+            GameRuleValue::Int(_) => None,
+        }
+    }
+
+    pub fn get_int(&self, name: &str) -> Option<i32> {
+        match self.get(name)? {
+            GameRuleValue::Int(value) => Some(value),
+            GameRuleValue::Bool(_) => None,
+        }
+    }
+}
+
+/// One entry of `WorldGenSettings.dimensions`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelDimension {
+    pub name: String,
+    pub dimension_type: String,
+    /// The generator's `type`, such as `minecraft:noise` or `minecraft:flat`.
+    pub generator_type: String,
+    pub generator: Compound,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldGenSettings {
+    pub seed: i64,
+    pub generate_features: bool,
+    pub bonus_chest: bool,
+    pub dimensions: Vec<LevelDimension>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataPacks {
+    pub enabled: Vec<String>,
+    pub disabled: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Weather {
+    pub raining: bool,
+    pub rain_time: i32,
+    pub thundering: bool,
+    pub thunder_time: i32,
+    /// Ticks of clear weather left after `/weather clear`.
+    pub clear_weather_time: i32,
+}
+
+/// How a save's version relates to [`mc_version`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionCheck {
+    Match,
+    /// Saved by another version whose chunks this crate can still read,
+    /// though blocks and biomes it does not know decode as defaults.
+    Mismatch {
+        saved_name: Option<String>,
+        saved_data_version: i32,
+    },
+    /// Saved before the 1.18 chunk format.
+    Unsupported {
+        saved_data_version: i32,
+    },
+}
+
+impl fmt::Display for VersionCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Match => write!(f, "saved by {}", mc_version()),
+            Self::Mismatch {
+                saved_name,
+                saved_data_version,
+            } => write!(
+                f,
+                "saved by {} (data version {saved_data_version}) but built for {}; \
+                 unknown blocks and biomes will fall back to defaults",
+                saved_name.as_deref().unwrap_or("an unknown version"),
+                mc_version(),
+            ),
+            Self::Unsupported { saved_data_version } => write!(
+                f,
+                "data version {saved_data_version} predates the 1.18 chunk format and \
+                 cannot be read; open and save the world in a newer version first"
+            ),
+        }
+    }
+}
+
+/// The `Data` compound of `level.dat`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelData {
+    pub data_version: i32,
+    pub version: Option<SaveVersion>,
+    pub level_name: String,
+    pub spawn: Spawn,
+    pub game_rules: GameRules,
+    /// Missing from saves that keep it in a separate file.
+    pub world_gen: Option<WorldGenSettings>,
+    pub data_packs: DataPacks,
+    /// Ticks since the world was created.
+    pub time: i64,
+    /// The time of day in ticks, which `/time set` changes.
+    pub day_time: i64,
+    pub weather: Weather,
+    pub game_type: i32,
+    pub hardcore: bool,
+    pub difficulty: i8,
+    pub allow_commands: bool,
+    /// Milliseconds since the Unix epoch.
+    pub last_played: i64,
+    /// The player of a singleplayer world.
+    pub player: Option<PlayerData>,
+    /// The whole compound, for everything not decoded above.
+    pub raw: Compound,
+}
+
+impl LevelData {
+    /// Reads `level.dat` from a world directory.
+    pub fn read(world_dir: impl AsRef<Path>) -> Result<Self> {
+        Self::from_root(&read_root(&world_dir.as_ref().join("level.dat"))?)
+    }
+
+    /// Decodes the root compound of `level.dat`, which wraps everything in
+    /// `Data`.
+    pub fn from_root(root: &Compound) -> Result<Self> {
+        let data = root
+            .get_compound("Data")
+            .ok_or(Error::Invalid("level.dat without Data"))?;
+        Self::from_nbt(data)
+    }
+
+    pub fn from_nbt(data: &Compound) -> Result<Self> {
+        let version = data.get_compound("Version").map(|version| SaveVersion {
+            id: version.get_int("Id").unwrap_or_default(),
+            name: version.get_str("Name").unwrap_or_default().to_string(),
+            series: version.get_str("Series").unwrap_or("main").to_string(),
+            snapshot: version.get_bool("Snapshot").unwrap_or_default(),
+        });
+        let data_version = data
+            .get_int("DataVersion")
+            .or(version.as_ref().map(|version| version.id))
+            .ok_or(Error::Invalid("level.dat without DataVersion"))?;
+
+        Ok(Self {
+            data_version,
+            version,
+            level_name: data.get_str("LevelName").unwrap_or_default().to_string(),
+            spawn: decode_spawn(data),
+            game_rules: GameRules(data.get_compound("GameRules").cloned().unwrap_or_default()),
+            world_gen: data.get_compound("WorldGenSettings").map(decode_world_gen),
+            data_packs: data
+                .get_compound("DataPacks")
+                .map(|packs| DataPacks {
+                    enabled: strings(packs.get_list("Enabled")),
+                    disabled: strings(packs.get_list("Disabled")),
+                })
+                .unwrap_or_default(),
+            time: data.get_long("Time").unwrap_or_default(),
+            day_time: data.get_long("DayTime").unwrap_or_default(),
+            weather: Weather {
+                raining: data.get_bool("raining").unwrap_or_default(),
+                rain_time: data.get_int("rainTime").unwrap_or_default(),
+                thundering: data.get_bool("thundering").unwrap_or_default(),
+                thunder_time: data.get_int("thunderTime").unwrap_or_default(),
+                clear_weather_time: data.get_int("clearWeatherTime").unwrap_or_default(),
+            },
+            game_type: data.get_int("GameType").unwrap_or_default(),
+            hardcore: data.get_bool("hardcore").unwrap_or_default(),
+            difficulty: data.get_byte("Difficulty").unwrap_or(2),
+            allow_commands: data.get_bool("allowCommands").unwrap_or_default(),
+            last_played: data.get_long("LastPlayed").unwrap_or_default(),
+            player: data
+                .get_compound("Player")
+                .map(PlayerData::from_nbt)
+                .transpose()?,
+            raw: data.clone(),
+        })
+    }
+
+    /// Compares the save's version with [`mc_version`].
+    pub fn version_check(&self) -> VersionCheck {
+        let saved_name = self.version.as_ref().map(|version| version.name.clone());
+        if self.data_version < MIN_DATA_VERSION {
+            VersionCheck::Unsupported {
+                saved_data_version: self.data_version,
+            }
+        } else if saved_name.as_deref() == Some(mc_version()) {
+            VersionCheck::Match
+        } else {
+            VersionCheck::Mismatch {
+                saved_name,
+                saved_data_version: self.data_version,
+            }
+        }
+    }
+}
+
+/// Reads the `spawn` compound of 1.21.9 and later, or the separate
+/// `SpawnX`/`SpawnY`/`SpawnZ`/`SpawnAngle` fields before it.
+fn decode_spawn(data: &Compound) -> Spawn {
+    if let Some(spawn) = data.get_compound("spawn") {
+        let pos = match spawn.get_int_array("pos") {
+            Some([x, y, z]) => BlockPos::new(*x, *y, *z),
+            _ => BlockPos::new(0, 0, 0),
+        };
+        return Spawn {
+            dimension: spawn
+                .get_str("dimension")
+                .unwrap_or("minecraft:overworld")
+                .to_string(),
+            pos,
+            yaw: spawn.get_float("yaw").unwrap_or_default(),
+            pitch: spawn.get_float("pitch").unwrap_or_default(),
+        };
+    }
+    Spawn {
+        dimension: "minecraft:overworld".to_string(),
+        pos: BlockPos::new(
+            data.get_int("SpawnX").unwrap_or_default(),
+            data.get_int("SpawnY").unwrap_or_default(),
+            data.get_int("SpawnZ").unwrap_or_default(),
+        ),
+        yaw: data.get_float("SpawnAngle").unwrap_or_default(),
+        pitch: 0.0,
+    }
+}
+
+fn decode_world_gen(settings: &Compound) -> WorldGenSettings {
+    let dimensions = settings
+        .get_compound("dimensions")
+        .into_iter()
+        .flat_map(Compound::iter)
+        .filter_map(|(name, dimension)| {
+            let dimension = dimension.as_compound()?;
+            let generator = dimension
+                .get_compound("generator")
+                .cloned()
+                .unwrap_or_default();
+            Some(LevelDimension {
+                name: name.clone(),
+                dimension_type: dimension.get_str("type").unwrap_or_default().to_string(),
+                generator_type: generator.get_str("type").unwrap_or_default().to_string(),
+                generator,
+            })
+        })
+        .collect();
+    WorldGenSettings {
+        seed: settings.get_long("seed").unwrap_or_default(),
+        generate_features: settings.get_bool("generate_features").unwrap_or(true),
+        bonus_chest: settings.get_bool("bonus_chest").unwrap_or_default(),
+        dimensions,
+    }
+}
+
+fn strings(list: Option<&[Tag]>) -> Vec<String> {
+    list.unwrap_or_default()
+        .iter()
+        .filter_map(|tag| tag.as_str().map(str::to_string))
+        .collect()
+}
+
+/// An item stack as saved in an inventory, with its components left as NBT.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedItem {
+    /// The inventory slot, absent for equipment.
+    pub slot: Option<i8>,
+    pub id: String,
+    pub count: i32,
+    pub components: Option<Compound>,
+}
+
+impl SavedItem {
+    /// Reads the 1.20.5 layout, falling back to the older `Count` byte and
+    /// `tag` compound.
+    pub fn from_nbt(nbt: &Compound) -> Result<Self> {
+        Ok(Self {
+            slot: nbt.get_byte("Slot"),
+            id: nbt
+                .get_str("id")
+                .ok_or(Error::Invalid("item without an id"))?
+                .to_string(),
+            count: nbt
+                .get("count")
+                .or_else(|| nbt.get("Count"))
+                .and_then(Tag::as_i64)
+                .unwrap_or(1) as i32,
+            components: nbt
+                .get_compound("components")
+                .or_else(|| nbt.get_compound("tag"))
+                .cloned(),
+        })
+    }
+}
+
+/// A player as saved in `playerdata/<uuid>.dat` or `level.dat`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerData {
+    /// Feet position.
+    pub pos: [f64; 3],
+    pub yaw: f32,
+    pub pitch: f32,
+    pub dimension: String,
+    pub game_type: Option<i32>,
+    /// The hotbar slot in hand, 0 to 8.
+    pub selected_slot: i32,
+    pub inventory: Vec<SavedItem>,
+    /// Worn and held items by equipment slot name, such as `head` or
+    /// `offhand`. Before 1.21.5 these were inventory slots 100 to 103 and -106.
+    pub equipment: Vec<(String, SavedItem)>,
+    /// The whole compound, for everything not decoded above.
+    pub raw: Compound,
+}
+
+impl PlayerData {
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_nbt(&read_root(path.as_ref())?)
+    }
+
+    pub fn from_nbt(nbt: &Compound) -> Result<Self> {
+        let pos = match nbt.get_list("Pos") {
+            Some([x, y, z]) => [x, y, z].map(|tag| tag.as_f64().unwrap_or_default()),
+            _ => return Err(Error::Invalid("player without a position")),
+        };
+        let (yaw, pitch) = match nbt.get_list("Rotation") {
+            Some([yaw, pitch]) => (
+                yaw.as_f64().unwrap_or_default() as f32,
+                pitch.as_f64().unwrap_or_default() as f32,
+            ),
+            _ => (0.0, 0.0),
+        };
+        // an int before 1.16
+        let dimension = match nbt.get("Dimension") {
+            Some(Tag::String(dimension)) => dimension.clone(),
+            Some(tag) => match tag.as_i64() {
+                Some(-1) => "minecraft:the_nether".to_string(),
+                Some(1) => "minecraft:the_end".to_string(),
+                _ => "minecraft:overworld".to_string(),
+            },
+            None => "minecraft:overworld".to_string(),
+        };
+        let inventory = nbt
+            .get_list("Inventory")
+            .unwrap_or_default()
+            .iter()
+            .filter_map(Tag::as_compound)
+            .map(SavedItem::from_nbt)
+            .collect::<Result<_>>()?;
+        let equipment = nbt
+            .get_compound("equipment")
+            .into_iter()
+            .flat_map(Compound::iter)
+            .filter_map(|(slot, item)| Some((slot, item.as_compound()?)))
+            .map(|(slot, item)| Ok((slot.clone(), SavedItem::from_nbt(item)?)))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            pos,
+            yaw,
+            pitch,
+            dimension,
+            game_type: nbt.get_int("playerGameType"),
+            selected_slot: nbt.get_int("SelectedItemSlot").unwrap_or_default(),
+            inventory,
+            equipment,
+            raw: nbt.clone(),
+        })
+    }
+
+    /// The item in the selected hotbar slot.
+    pub fn selected_item(&self) -> Option<&SavedItem> {
+        self.inventory
+            .iter()
+            .find(|item| item.slot == Some(self.selected_slot as i8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_nbt::from_snbt;
+
+    use super::*;
+
+    const PLAYER: &str = r#"{
+        Pos: [12.5d, 70.0d, -3.25d], Rotation: [90.0f, -15.0f], Dimension: "minecraft:the_nether",
+        playerGameType: 1, SelectedItemSlot: 2,
+        Inventory: [
+            {Slot: 0b, id: "minecraft:stone", count: 64},
+            {Slot: 2b, id: "minecraft:diamond_sword", count: 1, components: {"minecraft:damage": 5}}
+        ],
+        equipment: {head: {id: "minecraft:iron_helmet", count: 1}}
+    }"#;
+
+    #[test]
+    fn test_level_data() {
+        let root = from_snbt(&format!(
+            r#"{{Data: {{
+                DataVersion: 4440, LevelName: "Test World",
+                Version: {{Id: 4440, Name: "{version}", Series: "main", Snapshot: 1b}},
+                spawn: {{dimension: "minecraft:overworld", pos: [I; 8, 64, -8], yaw: 45.0f, pitch: 0.0f}},
+                GameRules: {{"minecraft:advance_time": 0b, "minecraft:random_tick_speed": 3}},
+                WorldGenSettings: {{seed: 42L, generate_features: 1b, bonus_chest: 0b, dimensions: {{
+                    "minecraft:overworld": {{type: "minecraft:overworld", generator: {{type: "minecraft:noise", settings: "minecraft:overworld"}}}}
+                }}}},
+                DataPacks: {{Enabled: ["vanilla"], Disabled: ["minecart_improvements"]}},
+                Time: 1200L, DayTime: 6000L, raining: 1b, rainTime: 300, thundering: 0b, thunderTime: 0,
+                GameType: 0, hardcore: 0b, Difficulty: 3b, allowCommands: 1b,
+                Player: {PLAYER}
+            }}}}"#,
+            version = mc_version(),
+        ))
+        .unwrap();
+        let level = LevelData::from_root(&root).unwrap();
+
+        assert_eq!(level.level_name, "Test World");
+        assert_eq!(level.spawn.pos, BlockPos::new(8, 64, -8));
+        assert_eq!(level.spawn.yaw, 45.0);
+        assert_eq!(level.game_rules.get_bool("advance_time"), Some(false));
+        assert_eq!(level.game_rules.get_int("random_tick_speed"), Some(3));
+        let world_gen = level.world_gen.as_ref().unwrap();
+        assert_eq!(world_gen.seed, 42);
+        assert_eq!(world_gen.dimensions[0].generator_type, "minecraft:noise");
+        assert_eq!(level.data_packs.enabled, ["vanilla"]);
+        assert_eq!(level.day_time, 6000);
+        assert!(level.weather.raining);
+        assert_eq!(level.difficulty, 3);
+        assert_eq!(
+            level.player.as_ref().unwrap().dimension,
+            "minecraft:the_nether"
+        );
+        assert_eq!(level.version_check(), VersionCheck::Match);
+    }
+
+    #[test]
+    fn test_legacy_fields_and_version_reports() {
+        let data = from_snbt(
+            r#"{
+                DataVersion: 3465, Version: {Id: 3465, Name: "1.20.1"},
+                SpawnX: 1, SpawnY: 2, SpawnZ: 3, SpawnAngle: 90.0f,
+                GameRules: {doDaylightCycle: "false", randomTickSpeed: "3"}
+            }"#,
+        )
+        .unwrap();
+        let level = LevelData::from_nbt(&data).unwrap();
+        assert_eq!(level.spawn.pos, BlockPos::new(1, 2, 3));
+        assert_eq!(level.game_rules.get_bool("doDaylightCycle"), Some(false));
+        assert_eq!(level.game_rules.get_int("randomTickSpeed"), Some(3));
+        let check = level.version_check();
+        assert_eq!(
+            check,
+            VersionCheck::Mismatch {
+                saved_name: Some("1.20.1".to_string()),
+                saved_data_version: 3465
+            }
+        );
+        assert!(check.to_string().contains("built for"));
+
+        let old = from_snbt("{DataVersion: 2586}").unwrap();
+        assert_eq!(
+            LevelData::from_nbt(&old).unwrap().version_check(),
+            VersionCheck::Unsupported {
+                saved_data_version: 2586
+            }
+        );
+    }
+
+    #[test]
+    fn test_player_data() {
+        let player = PlayerData::from_nbt(&from_snbt(PLAYER).unwrap()).unwrap();
+        assert_eq!(player.pos, [12.5, 70.0, -3.25]);
+        assert_eq!((player.yaw, player.pitch), (90.0, -15.0));
+        assert_eq!(player.game_type, Some(1));
+        assert_eq!(
+            player.selected_item().unwrap().id,
+            "minecraft:diamond_sword"
+        );
+        assert_eq!(player.inventory[0].count, 64);
+        assert_eq!(player.equipment[0].0, "head");
+
+        let dir = std::env::temp_dir().join(format!("mcre_anvil_player_{}", std::process::id()));
+        let path = player_data_path(&dir, "abc");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            mcre_nbt::to_compressed_bytes("", &player.raw).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(PlayerData::read(&path).unwrap(), player);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let legacy = from_snbt(
+            r#"{Pos: [0.0d, 0.0d, 0.0d], Dimension: 1, Inventory: [{Slot: 0b, id: "minecraft:dirt", Count: 3b}]}"#,
+        )
+        .unwrap();
+        let legacy = PlayerData::from_nbt(&legacy).unwrap();
+        assert_eq!(legacy.dimension, "minecraft:the_end");
+        assert_eq!(legacy.inventory[0].count, 3);
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(
+            region_dir("w", "minecraft:overworld"),
+            Path::new("w/region")
+        );
+        assert_eq!(
+            region_dir("w", "minecraft:the_nether"),
+            Path::new("w/DIM-1/region")
+        );
+        assert_eq!(
+            region_dir("w", "mymod:mining"),
+            Path::new("w/dimensions/mymod/mining/region")
+        );
+        assert_eq!(
+            player_data_path("w", "abc"),
+            Path::new("w/playerdata/abc.dat")
+        );
+    }
+}
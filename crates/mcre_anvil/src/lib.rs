@@ -0,0 +1,27 @@
+//! Anvil worlds: `.mca` region files, the chunk NBT inside them, a
+//! [`mcre_world::WorldSource`] that streams chunks from a save and a writer
+//! that produces saves vanilla can open, plus the `level.dat` and player
+//! files that describe the save.
+
+mod chunk;
+mod compression;
+mod error;
+mod level;
+mod region;
+mod source;
+mod writer;
+
+pub use chunk::{ChunkDecoder, MIN_DATA_VERSION, SavedChunk, encode_block_state};
+pub use compression::ChunkCompression;
+pub use error::{Error, Result};
+pub use level::{
+    DataPacks, GameRuleValue, GameRules, LevelData, LevelDimension, PlayerData, SaveVersion,
+    SavedItem, Spawn, VersionCheck, Weather, WorldGenSettings, mc_version, player_data_path,
+    region_dir,
+};
+pub use region::{
+    EXTERNAL_FLAG, HEADER_BYTES, MAX_CHUNK_SECTORS, REGION_CHUNKS, RegionFile, SECTOR_BYTES,
+    external_file_name, region_file_name, region_of,
+};
+pub use source::RegionSource;
+pub use writer::{RegionWriter, export_world};
@@ -0,0 +1,404 @@
+//! The `.mca` region file container: a 32×32 grid of chunks stored in 4 KiB
+//! sectors behind a location and timestamp header.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use hashbrown::HashMap;
+use mcre_core::ChunkPos;
+use mcre_nbt::Compound;
+use rustc_hash::FxBuildHasher;
+
+use crate::{ChunkCompression, Error, Result};
+
+pub const SECTOR_BYTES: usize = 4096;
+/// Chunks along each side of a region.
+pub const REGION_CHUNKS: i32 = 32;
+/// The location table and the timestamp table, one sector each.
+pub const HEADER_BYTES: usize = 2 * SECTOR_BYTES;
+/// Set on the compression byte when the chunk lives in its own `.mcc` file.
+pub const EXTERNAL_FLAG: u8 = 0x80;
+/// Chunks needing this many sectors or more are stored externally, since
+/// the location table only has a byte for the sector count.
+pub const MAX_CHUNK_SECTORS: usize = 256;
+/// The length prefix and the compression byte in front of each chunk.
+const CHUNK_HEADER_BYTES: usize = 5;
+
+/// The region containing `chunk`.
+pub fn region_of(chunk: ChunkPos) -> (i32, i32) {
+    (chunk.x >> 5, chunk.z >> 5)
+}
+
+pub fn region_file_name(region_x: i32, region_z: i32) -> String {
+    format!("r.{region_x}.{region_z}.mca")
+}
+
+/// The file an oversized chunk is moved to, next to its region file.
+pub fn external_file_name(chunk: ChunkPos) -> String {
+    format!("c.{}.{}.mcc", chunk.x, chunk.z)
+}
+
+/// Index of `chunk` in the header tables.
+pub(crate) fn header_index(chunk: ChunkPos) -> usize {
+    ((chunk.x & 31) + (chunk.z & 31) * REGION_CHUNKS) as usize
+}
+
+/// A region file held fully in memory.
+///
+/// Writes go to memory and reach the disk on [`RegionFile::save`]. Like
+/// vanilla's `RegionFile`, a rewritten chunk is placed in the first free run
+/// of sectors while its old sectors are still taken, so a save interrupted
+/// half way never loses the previous copy.
+#[derive(Debug, Clone)]
+pub struct RegionFile {
+    dir: PathBuf,
+    x: i32,
+    z: i32,
+    bytes: Vec<u8>,
+    compression: ChunkCompression,
+    /// External chunk files to write on save, or to delete when `None`.
+    external: HashMap<ChunkPos, Option<Vec<u8>>, FxBuildHasher>,
+}
+
+impl RegionFile {
+    /// Opens `r.<x>.<z>.mca` in `dir`, or returns `None` if there is no such
+    /// file.
+    pub fn open(dir: impl Into<PathBuf>, region_x: i32, region_z: i32) -> Result<Option<Self>> {
+        let dir = dir.into();
+        match fs::read(dir.join(region_file_name(region_x, region_z))) {
+            Ok(bytes) => Self::from_bytes(dir, region_x, region_z, bytes).map(Some),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// A region without chunks, saved as `r.<x>.<z>.mca` in `dir`.
+    pub fn new(dir: impl Into<PathBuf>, region_x: i32, region_z: i32) -> Self {
+        Self {
+            dir: dir.into(),
+            x: region_x,
+            z: region_z,
+            bytes: vec![0; HEADER_BYTES],
+            compression: ChunkCompression::default(),
+            external: HashMap::default(),
+        }
+    }
+
+    /// Wraps the contents of a region file. `dir` is where external chunk
+    /// files are looked up. An empty file is a region without chunks, as
+    /// vanilla leaves behind after creating one.
+    pub fn from_bytes(dir: impl Into<PathBuf>, x: i32, z: i32, bytes: Vec<u8>) -> Result<Self> {
+        if !bytes.is_empty() && bytes.len() < HEADER_BYTES {
+            return Err(Error::TruncatedHeader);
+        }
+        let mut region = Self::new(dir, x, z);
+        if !bytes.is_empty() {
+            region.bytes = bytes;
+        }
+        Ok(region)
+    }
+
+    /// The file contents as they would be saved.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The compression used for chunks written from now on.
+    pub fn compression(&self) -> ChunkCompression {
+        self.compression
+    }
+
+    pub fn set_compression(&mut self, compression: ChunkCompression) {
+        self.compression = compression;
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The region coordinates, in units of 32 chunks.
+    pub fn pos(&self) -> (i32, i32) {
+        (self.x, self.z)
+    }
+
+    pub fn contains(&self, chunk: ChunkPos) -> bool {
+        region_of(chunk) == (self.x, self.z)
+    }
+
+    fn header_int(&self, offset: usize) -> u32 {
+        self.bytes
+            .get(offset..offset + 4)
+            .map_or(0, |b| u32::from_be_bytes(b.try_into().unwrap()))
+    }
+
+    fn set_header_int(&mut self, offset: usize, value: u32) {
+        self.bytes[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+    }
+
+    /// The first sector and sector count of a chunk, `(0, 0)` if absent.
+    pub fn location(&self, chunk: ChunkPos) -> (u32, u32) {
+        let packed = self.header_int(header_index(chunk) * 4);
+        (packed >> 8, packed & 0xFF)
+    }
+
+    pub fn has_chunk(&self, chunk: ChunkPos) -> bool {
+        self.contains(chunk) && self.location(chunk) != (0, 0)
+    }
+
+    /// When the chunk was last saved, in seconds since the Unix epoch.
+    pub fn timestamp(&self, chunk: ChunkPos) -> u32 {
+        self.header_int(SECTOR_BYTES + header_index(chunk) * 4)
+    }
+
+    /// The chunks present in this region.
+    pub fn chunks(&self) -> impl Iterator<Item = ChunkPos> + '_ {
+        (0..REGION_CHUNKS * REGION_CHUNKS)
+            .map(|i| {
+                ChunkPos::new(
+                    self.x * REGION_CHUNKS + i % REGION_CHUNKS,
+                    self.z * REGION_CHUNKS + i / REGION_CHUNKS,
+                )
+            })
+            .filter(|chunk| self.has_chunk(*chunk))
+    }
+
+    /// The stored payload of a chunk and how it is compressed, reading
+    /// external chunks from their `.mcc` file.
+    pub fn read_raw(&self, chunk: ChunkPos) -> Result<Option<(ChunkCompression, Vec<u8>)>> {
+        if !self.has_chunk(chunk) {
+            return Ok(None);
+        }
+        let (sector, count) = self.location(chunk);
+        let start = sector as usize * SECTOR_BYTES;
+        let end = start + count as usize * SECTOR_BYTES;
+        if start < HEADER_BYTES || start + 5 > self.bytes.len() {
+            return Err(Error::InvalidSectors);
+        }
+        // The last sector of the file may be short when it was not padded.
+        let sectors = &self.bytes[start..end.min(self.bytes.len())];
+        let length = u32::from_be_bytes(sectors[..4].try_into().unwrap()) as usize;
+        if length == 0 {
+            // vanilla logs "Chunk has no data" and treats the chunk as missing
+            return Ok(None);
+        }
+        let payload = sectors.get(5..4 + length).ok_or(Error::InvalidSectors)?;
+
+        let id = sectors[4];
+        let compression = ChunkCompression::from_id(id & !EXTERNAL_FLAG)?;
+        if id & EXTERNAL_FLAG != 0 {
+            let data = match self.external.get(&chunk) {
+                Some(Some(data)) => data.clone(),
+                _ => fs::read(self.dir.join(external_file_name(chunk)))?,
+            };
+            return Ok(Some((compression, data)));
+        }
+        Ok(Some((compression, payload.to_vec())))
+    }
+
+    /// Reads and decompresses the NBT of a chunk.
+    pub fn read_chunk(&self, chunk: ChunkPos) -> Result<Option<Compound>> {
+        let Some((compression, data)) = self.read_raw(chunk)? else {
+            return Ok(None);
+        };
+        let (_, nbt) = mcre_nbt::from_bytes(&compression.decompress(&data)?)?;
+        Ok(Some(nbt))
+    }
+
+    /// Compresses and stores a chunk, stamping it with the current time.
+    pub fn write_chunk(&mut self, chunk: ChunkPos, nbt: &Compound) -> Result<()> {
+        let raw = mcre_nbt::to_bytes("", nbt)?;
+        let data = self.compression.compress(&raw);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs() as u32);
+        self.write_raw(chunk, self.compression, data, timestamp)
+    }
+
+    /// Stores an already compressed chunk payload.
+    pub fn write_raw(
+        &mut self,
+        chunk: ChunkPos,
+        compression: ChunkCompression,
+        data: Vec<u8>,
+        timestamp: u32,
+    ) -> Result<()> {
+        if !self.contains(chunk) {
+            return Err(Error::OutsideRegion(chunk));
+        }
+        let external =
+            (CHUNK_HEADER_BYTES + data.len()).div_ceil(SECTOR_BYTES) >= MAX_CHUNK_SECTORS;
+        let (id, payload, external) = if external {
+            (
+                compression.id() | EXTERNAL_FLAG,
+                &[][..],
+                Some(data.clone()),
+            )
+        } else {
+            (compression.id(), &data[..], None)
+        };
+
+        let length = CHUNK_HEADER_BYTES + payload.len();
+        let count = length.div_ceil(SECTOR_BYTES);
+        let sector = self.allocate(count);
+        let start = sector * SECTOR_BYTES;
+        let end = start + count * SECTOR_BYTES;
+        if self.bytes.len() < end {
+            self.bytes.resize(end, 0);
+        }
+        let sectors = &mut self.bytes[start..end];
+        sectors[..4].copy_from_slice(&(payload.len() as u32 + 1).to_be_bytes());
+        sectors[4] = id;
+        sectors[CHUNK_HEADER_BYTES..length].copy_from_slice(payload);
+        sectors[length..].fill(0);
+
+        let index = header_index(chunk) * 4;
+        self.set_header_int(index, (sector as u32) << 8 | count as u32);
+        self.set_header_int(SECTOR_BYTES + index, timestamp);
+        self.external.insert(chunk, external);
+        Ok(())
+    }
+
+    pub fn remove_chunk(&mut self, chunk: ChunkPos) {
+        if self.has_chunk(chunk) {
+            let index = header_index(chunk) * 4;
+            self.set_header_int(index, 0);
+            self.set_header_int(SECTOR_BYTES + index, 0);
+            self.external.insert(chunk, None);
+        }
+    }
+
+    /// Overrides the save time of a chunk, in seconds since the Unix epoch.
+    pub fn set_timestamp(&mut self, chunk: ChunkPos, timestamp: u32) {
+        if self.has_chunk(chunk) {
+            self.set_header_int(SECTOR_BYTES + header_index(chunk) * 4, timestamp);
+        }
+    }
+
+    /// The first run of `count` sectors no chunk is using, `RegionBitmap`
+    /// style, which may extend past the end of the file.
+    fn allocate(&self, count: usize) -> usize {
+        let mut used = vec![false; self.bytes.len().div_ceil(SECTOR_BYTES)];
+        used[..HEADER_BYTES / SECTOR_BYTES].fill(true);
+        for i in 0..(REGION_CHUNKS * REGION_CHUNKS) as usize {
+            let packed = self.header_int(i * 4);
+            let (sector, sectors) = ((packed >> 8) as usize, (packed & 0xFF) as usize);
+            for used in used.iter_mut().skip(sector).take(sectors) {
+                *used = true;
+            }
+        }
+
+        let mut start = 0;
+        for (sector, used) in used.iter().enumerate() {
+            if *used {
+                start = sector + 1;
+            } else if sector + 1 - start == count {
+                return start;
+            }
+        }
+        start
+    }
+
+    /// Writes the region file, padded to whole sectors, along with any
+    /// external chunk files, creating `dir` if needed.
+    pub fn save(&mut self) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        // external files first, so the region never points at a missing one
+        for (chunk, data) in self.external.drain() {
+            let path = self.dir.join(external_file_name(chunk));
+            match data {
+                Some(data) => fs::write(path, data)?,
+                None => match fs::remove_file(path) {
+                    Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                        return Err(error.into());
+                    }
+                    _ => {}
+                },
+            }
+        }
+        self.bytes
+            .resize(self.bytes.len().next_multiple_of(SECTOR_BYTES), 0);
+        fs::write(self.dir.join(region_file_name(self.x, self.z)), &self.bytes)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Lays chunks out the way vanilla does, one after another from sector 2.
+    pub(crate) fn region_bytes(chunks: &[(ChunkPos, ChunkCompression, Vec<u8>)]) -> Vec<u8> {
+        let mut bytes = vec![0; HEADER_BYTES];
+        for (chunk, compression, payload) in chunks {
+            let sector = bytes.len() / SECTOR_BYTES;
+            bytes.extend_from_slice(&(payload.len() as u32 + 1).to_be_bytes());
+            bytes.push(compression.id());
+            bytes.extend_from_slice(payload);
+            bytes.resize(bytes.len().next_multiple_of(SECTOR_BYTES), 0);
+            let count = bytes.len() / SECTOR_BYTES - sector;
+            let index = header_index(*chunk) * 4;
+            bytes[index..index + 4].copy_from_slice(&((sector << 8 | count) as u32).to_be_bytes());
+            bytes[SECTOR_BYTES + index..SECTOR_BYTES + index + 4]
+                .copy_from_slice(&1_700_000_000u32.to_be_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_read_chunks() {
+        let mut nbt = Compound::new();
+        nbt.insert("DataVersion", 4189);
+        let raw = mcre_nbt::to_bytes("", &nbt).unwrap();
+        let region = region_bytes(&[
+            (
+                ChunkPos::new(-1, 0),
+                ChunkCompression::Zlib,
+                ChunkCompression::Zlib.compress(&raw),
+            ),
+            (
+                ChunkPos::new(-32, 31),
+                ChunkCompression::Lz4,
+                ChunkCompression::Lz4.compress(&raw),
+            ),
+        ]);
+        let region = RegionFile::from_bytes(".", -1, 0, region).unwrap();
+
+        assert_eq!(
+            region.chunks().collect::<Vec<_>>(),
+            [ChunkPos::new(-1, 0), ChunkPos::new(-32, 31)]
+        );
+        assert_eq!(region.timestamp(ChunkPos::new(-1, 0)), 1_700_000_000);
+        assert_eq!(
+            region.read_chunk(ChunkPos::new(-1, 0)).unwrap(),
+            Some(nbt.clone())
+        );
+        assert_eq!(
+            region.read_chunk(ChunkPos::new(-32, 31)).unwrap(),
+            Some(nbt)
+        );
+        assert_eq!(region.read_chunk(ChunkPos::new(-2, 0)).unwrap(), None);
+        // outside the region
+        assert_eq!(region.read_chunk(ChunkPos::new(0, 0)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_invalid_headers() {
+        assert!(matches!(
+            RegionFile::from_bytes(".", 0, 0, vec![0; 100]),
+            Err(Error::TruncatedHeader)
+        ));
+        let empty = RegionFile::from_bytes(".", 0, 0, Vec::new()).unwrap();
+        assert_eq!(empty.chunks().count(), 0);
+
+        let mut bytes = vec![0; HEADER_BYTES];
+        bytes[..4].copy_from_slice(&(9 << 8 | 1u32).to_be_bytes());
+        let region = RegionFile::from_bytes(".", 0, 0, bytes).unwrap();
+        assert!(matches!(
+            region.read_chunk(ChunkPos::new(0, 0)),
+            Err(Error::InvalidSectors)
+        ));
+    }
+}
@@ -0,0 +1,242 @@
+//! A [`WorldSource`] that streams chunks from a dimension's `region`
+//! directory around a moving view centre.
+
+use std::{collections::VecDeque, path::PathBuf};
+
+use hashbrown::HashMap;
+use mcre_core::ChunkPos;
+use mcre_world::{WorldEvent, WorldHeight, WorldSource};
+use rustc_hash::FxBuildHasher;
+
+use crate::{ChunkDecoder, Error, RegionFile, region_of};
+
+/// Loads the full chunks within a square view distance of a centre chunk,
+/// nearest first, and unloads them once they fall out of view.
+///
+/// Chunks that fail to read are skipped; their errors are collected for
+/// [`RegionSource::take_errors`].
+#[derive(Debug)]
+pub struct RegionSource {
+    dir: PathBuf,
+    decoder: ChunkDecoder,
+    regions: HashMap<(i32, i32), Option<RegionFile>, FxBuildHasher>,
+    /// Chunks in view that were already read, and whether they were loaded.
+    visited: HashMap<ChunkPos, bool, FxBuildHasher>,
+    unloads: VecDeque<ChunkPos>,
+    loads: VecDeque<ChunkPos>,
+    errors: Vec<(ChunkPos, Error)>,
+}
+
+impl RegionSource {
+    /// A source reading `r.<x>.<z>.mca` files from `dir`. Nothing loads until
+    /// [`RegionSource::set_view`] is called.
+    pub fn new(dir: impl Into<PathBuf>, height: WorldHeight) -> Self {
+        Self::with_decoder(dir, ChunkDecoder::new(height))
+    }
+
+    pub fn with_decoder(dir: impl Into<PathBuf>, decoder: ChunkDecoder) -> Self {
+        Self {
+            dir: dir.into(),
+            decoder,
+            regions: HashMap::default(),
+            visited: HashMap::default(),
+            unloads: VecDeque::new(),
+            loads: VecDeque::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// The decoder, for mapping the emitted [`mcre_world::BiomeId`]s back to
+    /// biome names.
+    pub fn decoder(&self) -> &ChunkDecoder {
+        &self.decoder
+    }
+
+    /// Moves the view. Chunks outside it are unloaded before any new ones
+    /// load, and new ones load in order of distance from `center`.
+    pub fn set_view(&mut self, center: ChunkPos, view_distance: u32) {
+        let radius = view_distance as i32;
+        let in_view = |pos: ChunkPos| {
+            (pos.x - center.x).abs() <= radius && (pos.z - center.z).abs() <= radius
+        };
+
+        self.unloads.retain(|pos| !in_view(*pos));
+        self.visited.retain(|pos, loaded| {
+            if in_view(*pos) {
+                return true;
+            }
+            if *loaded {
+                self.unloads.push_back(*pos);
+            }
+            false
+        });
+
+        let mut loads = Vec::new();
+        for x in center.x - radius..=center.x + radius {
+            for z in center.z - radius..=center.z + radius {
+                let pos = ChunkPos::new(x, z);
+                if !self.visited.contains_key(&pos) {
+                    loads.push(pos);
+                }
+            }
+        }
+        loads.sort_by_key(|pos| {
+            let (dx, dz) = ((pos.x - center.x) as i64, (pos.z - center.z) as i64);
+            dx * dx + dz * dz
+        });
+        self.loads = loads.into();
+
+        let (min_x, min_z) = region_of(ChunkPos::new(center.x - radius, center.z - radius));
+        let (max_x, max_z) = region_of(ChunkPos::new(center.x + radius, center.z + radius));
+        self.regions
+            .retain(|(x, z), _| (min_x..=max_x).contains(x) && (min_z..=max_z).contains(z));
+    }
+
+    /// Errors from chunks that could not be read since the last call.
+    pub fn take_errors(&mut self) -> Vec<(ChunkPos, Error)> {
+        std::mem::take(&mut self.errors)
+    }
+
+    fn load(&mut self, pos: ChunkPos) -> Option<WorldEvent> {
+        let region = region_of(pos);
+        if !self.regions.contains_key(&region) {
+            let file = RegionFile::open(&self.dir, region.0, region.1).unwrap_or_else(|error| {
+                self.errors.push((pos, error));
+                None
+            });
+            self.regions.insert(region, file);
+        }
+        let nbt = match self.regions[&region].as_ref()?.read_chunk(pos) {
+            Ok(nbt) => nbt?,
+            Err(error) => {
+                self.errors.push((pos, error));
+                return None;
+            }
+        };
+        match self.decoder.decode(&nbt) {
+            Ok(chunk) if chunk.is_full() => Some(WorldEvent::ChunkLoaded {
+                pos,
+                chunk: chunk.data,
+            }),
+            Ok(_) => None,
+            Err(error) => {
+                self.errors.push((pos, error));
+                None
+            }
+        }
+    }
+}
+
+impl WorldSource for RegionSource {
+    fn poll_event(&mut self) -> Option<WorldEvent> {
+        if let Some(pos) = self.unloads.pop_front() {
+            return Some(WorldEvent::ChunkUnloaded { pos });
+        }
+        while let Some(pos) = self.loads.pop_front() {
+            let event = self.load(pos);
+            self.visited.insert(pos, event.is_some());
+            if event.is_some() {
+                return event;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use mcre_world::Block;
+
+    use super::*;
+    use crate::{
+        ChunkCompression, chunk::tests::sample_chunk_nbt, region::tests::region_bytes,
+        region_file_name,
+    };
+
+    fn write_region(dir: &std::path::Path, region: (i32, i32), chunks: &[ChunkPos]) {
+        let chunks: Vec<_> = chunks
+            .iter()
+            .map(|pos| {
+                let raw = mcre_nbt::to_bytes("", &sample_chunk_nbt(pos.x, pos.z)).unwrap();
+                (
+                    *pos,
+                    ChunkCompression::Zlib,
+                    ChunkCompression::Zlib.compress(&raw),
+                )
+            })
+            .collect();
+        fs::write(
+            dir.join(region_file_name(region.0, region.1)),
+            region_bytes(&chunks),
+        )
+        .unwrap();
+    }
+
+    fn drain(source: &mut RegionSource) -> Vec<WorldEvent> {
+        std::iter::from_fn(|| source.poll_event()).collect()
+    }
+
+    #[test]
+    fn test_moving_view() {
+        let dir = std::env::temp_dir().join(format!("mcre_anvil_source_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        write_region(
+            &dir,
+            (0, 0),
+            &[
+                ChunkPos::new(0, 0),
+                ChunkPos::new(1, 0),
+                ChunkPos::new(2, 0),
+            ],
+        );
+        write_region(&dir, (-1, 0), &[ChunkPos::new(-1, 0)]);
+
+        let mut source = RegionSource::new(&dir, WorldHeight::OVERWORLD);
+        source.set_view(ChunkPos::new(0, 0), 1);
+        let loaded: Vec<_> = drain(&mut source)
+            .into_iter()
+            .map(|event| match event {
+                WorldEvent::ChunkLoaded { pos, chunk } => {
+                    assert_eq!(
+                        chunk.sections[0].get_block(0, 0, 0),
+                        Block::STONE.default_state()
+                    );
+                    pos
+                }
+                event => panic!("unexpected {event:?}"),
+            })
+            .collect();
+        assert_eq!(
+            loaded,
+            [
+                ChunkPos::new(0, 0),
+                ChunkPos::new(-1, 0),
+                ChunkPos::new(1, 0)
+            ]
+        );
+
+        // (-1, 0) and (0, 0) leave the view, (2, 0) enters it and (3, 0) is
+        // not in the save
+        source.set_view(ChunkPos::new(2, 0), 1);
+        let events = drain(&mut source);
+        assert_eq!(events.len(), 3);
+        let mut unloaded: Vec<_> = events[..2]
+            .iter()
+            .map(|event| match event {
+                WorldEvent::ChunkUnloaded { pos } => (pos.x, pos.z),
+                event => panic!("unexpected {event:?}"),
+            })
+            .collect();
+        unloaded.sort();
+        assert_eq!(unloaded, [(-1, 0), (0, 0)]);
+        assert!(matches!(
+            events[2],
+            WorldEvent::ChunkLoaded { pos, .. } if pos == ChunkPos::new(2, 0)
+        ));
+        assert!(source.take_errors().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,311 @@
+//! Saving chunks into a dimension's `region` directory.
+
+use std::path::PathBuf;
+
+use hashbrown::HashMap;
+use mcre_world::WorldView;
+use rustc_hash::FxBuildHasher;
+
+use crate::{ChunkCompression, RegionFile, Result, SavedChunk, region_of};
+
+/// Writes chunks into the region files of a directory, merging them into the
+/// files already there. Nothing reaches the disk until
+/// [`RegionWriter::flush`].
+#[derive(Debug)]
+pub struct RegionWriter {
+    dir: PathBuf,
+    compression: ChunkCompression,
+    regions: HashMap<(i32, i32), RegionFile, FxBuildHasher>,
+}
+
+impl RegionWriter {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            compression: ChunkCompression::default(),
+            regions: HashMap::default(),
+        }
+    }
+
+    /// The compression for chunks written from now on, zlib by default as
+    /// in vanilla.
+    pub fn set_compression(&mut self, compression: ChunkCompression) {
+        self.compression = compression;
+        for region in self.regions.values_mut() {
+            region.set_compression(compression);
+        }
+    }
+
+    /// Encodes and stores a chunk. `biome_names` is indexed by biome id, see
+    /// [`SavedChunk::to_nbt`].
+    pub fn write(&mut self, chunk: &SavedChunk, biome_names: &[String]) -> Result<()> {
+        let nbt = chunk.to_nbt(biome_names)?;
+        self.region(region_of(chunk.pos))?
+            .write_chunk(chunk.pos, &nbt)
+    }
+
+    fn region(&mut self, (x, z): (i32, i32)) -> Result<&mut RegionFile> {
+        if !self.regions.contains_key(&(x, z)) {
+            let mut region = RegionFile::open(&self.dir, x, z)?
+                .unwrap_or_else(|| RegionFile::new(&self.dir, x, z));
+            region.set_compression(self.compression);
+            self.regions.insert((x, z), region);
+        }
+        Ok(self.regions.get_mut(&(x, z)).unwrap())
+    }
+
+    /// Saves every region written to.
+    pub fn flush(&mut self) -> Result<()> {
+        for region in self.regions.values_mut() {
+            region.save()?;
+        }
+        Ok(())
+    }
+}
+
+/// Saves every loaded chunk of a world as a finished chunk of `data_version`
+/// and returns how many were written.
+pub fn export_world(
+    view: WorldView,
+    dir: impl Into<PathBuf>,
+    biome_names: &[String],
+    data_version: i32,
+) -> Result<usize> {
+    let mut writer = RegionWriter::new(dir);
+    let mut count = 0;
+    for (pos, chunk) in view.chunks() {
+        writer.write(
+            &SavedChunk::from_chunk(pos, chunk, data_version),
+            biome_names,
+        )?;
+        count += 1;
+    }
+    writer.flush()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use mcre_core::{BlockPos, ChunkPos};
+    use mcre_nbt::Compound;
+    use mcre_world::{
+        BiomeId, BitStorage, BlockEntity, BlockEntityKind, BlockState, ChunkData, ChunkSection,
+        HeightmapKind, LightUpdate, NibbleArray, PalettedContainer, World, WorldEvent, WorldHeight,
+    };
+
+    use super::*;
+    use crate::{
+        ChunkDecoder, HEADER_BYTES, MAX_CHUNK_SECTORS, RegionSource, SECTOR_BYTES, region_file_name,
+    };
+
+    const BIOMES: [&str; 3] = ["minecraft:plains", "minecraft:desert", "minecraft:ocean"];
+
+    fn biome_names() -> Vec<String> {
+        BIOMES.iter().map(|name| name.to_string()).collect()
+    }
+
+    /// A chunk whose sections each hold `palette_len` consecutive states from
+    /// `offset` on, except every fifth which is uniform, and a mix of biomes.
+    fn sample_chunk(pos: ChunkPos, palette_len: usize, offset: usize) -> SavedChunk {
+        let height = WorldHeight::OVERWORLD;
+        let states: Vec<BlockState> = BlockState::all().collect();
+        let sections = (0..height.section_count())
+            .map(|y| {
+                let first = offset + y * palette_len;
+                let blocks = if y % 5 == 0 {
+                    PalettedContainer::single(states[first % states.len()], 4096)
+                } else {
+                    let palette = (0..palette_len)
+                        .map(|i| states[(first + i) % states.len()])
+                        .collect();
+                    let mut storage = BitStorage::new(12, 4096);
+                    for i in 0..4096 {
+                        storage.set(i, ((i * 7 + y) % palette_len) as u32);
+                    }
+                    PalettedContainer::from_palette(palette, storage).unwrap()
+                };
+                let biomes = PalettedContainer::from_values(
+                    (0..64).map(|i| BiomeId(((i / (y + 1)) % BIOMES.len()) as u16)),
+                )
+                .unwrap();
+                ChunkSection::new(blocks, biomes).unwrap()
+            })
+            .collect();
+
+        let light_sections = height.section_count() + 2;
+        let mut light = LightUpdate {
+            sky: vec![None; light_sections],
+            block: vec![None; light_sections],
+        };
+        light.sky[0] = Some(NibbleArray::filled(15));
+        light.block[3] = Some(NibbleArray::filled(7));
+
+        let mut data = Compound::new();
+        data.insert("CustomName", "\"Box\"");
+        SavedChunk {
+            pos,
+            data_version: 4440,
+            status: "minecraft:full".to_string(),
+            data: ChunkData {
+                height,
+                sections,
+                heightmaps: vec![(HeightmapKind::MotionBlocking, vec![7; 37])],
+                block_entities: vec![BlockEntity {
+                    pos: BlockPos::new(pos.x * 16 + 3, 70, pos.z * 16 + 4),
+                    kind: BlockEntityKind::Name("minecraft:chest".to_string()),
+                    data: Some(data),
+                }],
+                light,
+            },
+        }
+    }
+
+    fn assert_same_chunk(left: &SavedChunk, right: &SavedChunk) {
+        assert_eq!(left.pos, right.pos);
+        assert_eq!(left.data_version, right.data_version);
+        for (left, right) in left.data.sections.iter().zip(&right.data.sections) {
+            assert!(left.states().iter().eq(right.states().iter()));
+            assert!(left.biomes().iter().eq(right.biomes().iter()));
+        }
+        assert_eq!(left.data.heightmaps, right.data.heightmaps);
+        assert_eq!(left.data.block_entities, right.data.block_entities);
+        assert_eq!(left.data.light, right.data.light);
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mcre_anvil_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_nbt_round_trip() {
+        let chunk = sample_chunk(ChunkPos::new(3, -2), 300, 0);
+        let nbt = chunk.to_nbt(&biome_names()).unwrap();
+        let mut decoder = ChunkDecoder::with_biomes(WorldHeight::OVERWORLD, biome_names());
+        assert_same_chunk(&decoder.decode(&nbt).unwrap(), &chunk);
+
+        for state in BlockState::all() {
+            let entry = crate::encode_block_state(state);
+            assert_eq!(decoder.block_state(&entry).unwrap(), state, "{entry:?}");
+        }
+    }
+
+    #[test]
+    fn test_region_round_trip() {
+        let dir = temp_dir("writer");
+        let names = biome_names();
+        for compression in [
+            ChunkCompression::Zlib,
+            ChunkCompression::Gzip,
+            ChunkCompression::Lz4,
+            ChunkCompression::None,
+        ] {
+            let mut writer = RegionWriter::new(&dir);
+            writer.set_compression(compression);
+            let chunks: Vec<_> = [(0, 0), (31, 31), (-1, 5), (40, -33)]
+                .into_iter()
+                .enumerate()
+                .map(|(i, (x, z))| sample_chunk(ChunkPos::new(x, z), 20, i * 7000))
+                .collect();
+            for chunk in &chunks {
+                writer.write(chunk, &names).unwrap();
+            }
+            writer.flush().unwrap();
+
+            let mut decoder = ChunkDecoder::with_biomes(WorldHeight::OVERWORLD, names.clone());
+            for chunk in &chunks {
+                let (x, z) = region_of(chunk.pos);
+                let region = RegionFile::open(&dir, x, z).unwrap().unwrap();
+                assert_eq!(region.as_bytes().len() % SECTOR_BYTES, 0);
+                let nbt = region.read_chunk(chunk.pos).unwrap().unwrap();
+                assert_same_chunk(&decoder.decode(&nbt).unwrap(), chunk);
+            }
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sector_allocation() {
+        let mut region = RegionFile::new("unused", 0, 0);
+        region.set_compression(ChunkCompression::None);
+        let small = ChunkPos::new(1, 2);
+        let mut nbt = Compound::new();
+        nbt.insert("Payload", vec![1i64; 1000]);
+        region.write_chunk(small, &nbt).unwrap();
+        assert_eq!(region.location(small), (2, 2));
+
+        // the old copy stays until the new one is written
+        region.write_chunk(small, &nbt).unwrap();
+        assert_eq!(region.location(small), (4, 2));
+        region.write_chunk(small, &nbt).unwrap();
+        assert_eq!(region.location(small), (2, 2));
+        region.set_timestamp(small, 1234);
+        assert_eq!(region.timestamp(small), 1234);
+
+        region.remove_chunk(small);
+        assert!(!region.has_chunk(small));
+        assert!(matches!(
+            region.write_chunk(ChunkPos::new(32, 0), &nbt),
+            Err(crate::Error::OutsideRegion(_))
+        ));
+        assert_eq!(region.as_bytes().len(), HEADER_BYTES + 4 * SECTOR_BYTES);
+    }
+
+    #[test]
+    fn test_external_chunks() {
+        let dir = temp_dir("external");
+        let pos = ChunkPos::new(-5, 7);
+        let mut region = RegionFile::new(&dir, -1, 0);
+        region.set_compression(ChunkCompression::None);
+        let mut nbt = Compound::new();
+        nbt.insert("Payload", vec![3i8; MAX_CHUNK_SECTORS * SECTOR_BYTES]);
+        region.write_chunk(pos, &nbt).unwrap();
+        assert_eq!(region.location(pos).1, 1);
+        assert_eq!(region.read_chunk(pos).unwrap(), Some(nbt.clone()));
+        region.save().unwrap();
+        assert!(dir.join("c.-5.7.mcc").exists());
+
+        let mut region = RegionFile::open(&dir, -1, 0).unwrap().unwrap();
+        assert_eq!(region.read_chunk(pos).unwrap(), Some(nbt));
+        let mut small = Compound::new();
+        small.insert("Payload", 1);
+        region.write_chunk(pos, &small).unwrap();
+        region.save().unwrap();
+        assert!(!dir.join("c.-5.7.mcc").exists());
+        assert_eq!(region.read_chunk(pos).unwrap(), Some(small));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_export_world() {
+        let dir = temp_dir("export");
+        let mut world = World::new();
+        let pos = ChunkPos::new(2, 2);
+        world.apply(WorldEvent::ChunkLoaded {
+            pos,
+            chunk: sample_chunk(pos, 50, 5).data,
+        });
+        let names = biome_names();
+        assert_eq!(export_world(world.view(), &dir, &names, 4440).unwrap(), 1);
+        assert!(dir.join(region_file_name(0, 0)).exists());
+
+        let decoder = ChunkDecoder::with_biomes(WorldHeight::OVERWORLD, names);
+        let mut source = RegionSource::with_decoder(&dir, decoder);
+        source.set_view(pos, 0);
+        let mut reloaded = World::new();
+        while let Some(event) = mcre_world::WorldSource::poll_event(&mut source) {
+            reloaded.apply(event);
+        }
+        let expected = world.view().get_chunk(pos).unwrap().to_data();
+        let actual = reloaded.view().get_chunk(pos).unwrap().to_data();
+        for (left, right) in expected.sections.iter().zip(&actual.sections) {
+            assert!(left.states().iter().eq(right.states().iter()));
+            assert!(left.biomes().iter().eq(right.biomes().iter()));
+        }
+        assert_eq!(expected.block_entities, actual.block_entities);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,11 +1,6 @@
-use std::{
-    env,
-    fs::File,
-    io::{self, Cursor},
-    path::PathBuf,
-};
-
-use mcje_downloader::RootManifest;
+use std::{fs::File, io, path::PathBuf};
+
+use mcje_downloader::{CACHE_DIR_VAR, Cache, ENV_VARS, Error, OFFLINE_VAR};
 use tokio::fs;
 use zip::ZipArchive;
 
@@ -13,27 +8,40 @@ use zip::ZipArchive;
 async fn main() {
     let manifest_dir = env!("CARGO_MANIFEST_DIR");
     let manifest_dir = PathBuf::from(manifest_dir);
+    println!("cargo:rerun-if-changed=../../mc-version");
+    for var in ENV_VARS {
+        println!("cargo:rerun-if-env-changed={var}");
+    }
     if manifest_dir.join("assets").exists() {
         return;
     }
-    let root_manifest = RootManifest::fetch().await.unwrap();
     let target_mc_version = fs::read_to_string(manifest_dir.join("../../mc-version"))
         .await
         .unwrap()
         .trim()
         .to_string();
-    let version_release = root_manifest
-        .versions
-        .into_iter()
-        .find(|ver| ver.id == target_mc_version)
-        .unwrap();
-
-    let version_manifest = version_release.fetch_manifest().await.unwrap();
+    let cache = Cache::from_env();
+    let version_manifest = cache
+        .version_manifest(&target_mc_version)
+        .await
+        .unwrap_or_else(|err| {
+            fail(
+                &cache,
+                &format!("resolve minecraft {target_mc_version}"),
+                err,
+            )
+        });
 
-    let jar = version_manifest.downloads.client.download().await.unwrap();
-    let jar_cursor = Cursor::new(jar);
+    let jar = version_manifest
+        .downloads
+        .as_ref()
+        .expect("the version has no downloads")
+        .client
+        .fetch(&cache)
+        .await
+        .unwrap_or_else(|err| fail(&cache, "fetch the client jar", err));
 
-    let mut jar_archive = ZipArchive::new(jar_cursor).unwrap();
+    let mut jar_archive = ZipArchive::new(File::open(jar).unwrap()).unwrap();
 
     for i in 0..jar_archive.len() {
         let mut entry = jar_archive.by_index(i).unwrap();
@@ -55,3 +63,14 @@ async fn main() {
         }
     }
 }
+
+fn fail(cache: &Cache, what: &str, err: Error) -> ! {
+    match err {
+        Error::NotCached(missing) => panic!(
+            "failed to {what}: {missing} is not in the cache at `{}` and offline mode is on; \
+             build once with network access, or point {CACHE_DIR_VAR} at a cache that has it",
+            cache.dir().display()
+        ),
+        err => panic!("failed to {what}: {err}; set {OFFLINE_VAR} to build from the cache alone"),
+    }
+}
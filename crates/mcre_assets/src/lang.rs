@@ -0,0 +1,214 @@
+//! Translations loaded from `assets/<namespace>/lang/<code>.json`.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use mcre_world::Block;
+
+use crate::FxHashMap;
+
+/// The language every other one falls back to.
+pub const DEFAULT_LANGUAGE: &str = "en_us";
+
+/// A source of asset files, such as the client jar or a resource pack.
+pub trait ResourcePack {
+    /// The namespaces this pack has an `assets/<namespace>` directory for.
+    fn namespaces(&self) -> Vec<String>;
+
+    /// Reads `assets/<namespace>/<path>`, if the pack has it.
+    fn read(&self, namespace: &str, path: &str) -> Option<Vec<u8>>;
+}
+
+/// Translation keys mapped to format strings for one selected language.
+#[derive(Debug, Clone, Default)]
+pub struct LanguageTable {
+    entries: FxHashMap<String, String>,
+}
+
+impl LanguageTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads `code` on top of [`DEFAULT_LANGUAGE`] from `packs`, ordered from
+    /// lowest to highest priority, like `ClientLanguage.loadFrom`. Keys the
+    /// selected language lacks keep their English text.
+    pub fn load(packs: &[&dyn ResourcePack], code: &str) -> Result<Self, serde_json::Error> {
+        let mut namespaces: Vec<String> = packs.iter().flat_map(|pack| pack.namespaces()).collect();
+        namespaces.sort_unstable();
+        namespaces.dedup();
+
+        let mut table = Self::new();
+        let codes = if code == DEFAULT_LANGUAGE {
+            &[DEFAULT_LANGUAGE][..]
+        } else {
+            &[DEFAULT_LANGUAGE, code][..]
+        };
+        for code in codes {
+            let path = format!("lang/{code}.json");
+            for namespace in &namespaces {
+                for pack in packs {
+                    if let Some(json) = pack.read(namespace, &path) {
+                        table.append_json(&json)?;
+                    }
+                }
+            }
+        }
+        Ok(table)
+    }
+
+    /// Adds the entries of one lang file, replacing existing keys.
+    pub fn append_json(&mut self, json: &[u8]) -> Result<(), serde_json::Error> {
+        let entries: FxHashMap<String, String> = serde_json::from_slice(json)?;
+        self.entries.extend(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key, normalize_format(&value))),
+        );
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The localised name of `block`, or its English name if no pack has one.
+    pub fn block_name(&self, block: Block) -> &str {
+        self.get(block.translation_key())
+            .unwrap_or(block.display_name())
+    }
+}
+
+impl mcre_text::Language for LanguageTable {
+    fn get(&self, key: &str) -> Option<&str> {
+        LanguageTable::get(self, key)
+    }
+}
+
+/// Rewrites `%d` and `%f` style conversions to `%s`, keeping explicit
+/// argument indices, as vanilla does when loading lang files.
+fn normalize_format(value: &str) -> String {
+    if !value.contains('%') {
+        return value.to_string();
+    }
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        out.push_str(&rest[..start]);
+        let spec = &rest[start + 1..];
+        match numeric_conversion_len(spec) {
+            Some((index_len, len)) => {
+                out.push('%');
+                out.push_str(&spec[..index_len]);
+                out.push('s');
+                rest = &spec[len..];
+            }
+            None => {
+                out.push('%');
+                rest = spec;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Matches `(\d+\$)?[\d.]*[df]`, returning the length of the index group and
+/// of the whole match.
+fn numeric_conversion_len(spec: &str) -> Option<(usize, usize)> {
+    let bytes = spec.as_bytes();
+    let digits = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+    let index_len = if digits > 0 && bytes.get(digits) == Some(&b'$') {
+        digits + 1
+    } else {
+        0
+    };
+    let precision = bytes[index_len..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit() || **b == b'.')
+        .count();
+    let end = index_len + precision;
+    match bytes.get(end) {
+        Some(b'd' | b'f') => Some((index_len, end + 1)),
+        // Without an index, the digits may be a width instead.
+        _ if index_len > 0 => {
+            let precision = bytes
+                .iter()
+                .take_while(|b| b.is_ascii_digit() || **b == b'.')
+                .count();
+            matches!(bytes.get(precision), Some(b'd' | b'f')).then_some((0, precision + 1))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{borrow::ToOwned, vec};
+
+    use mcre_text::Component;
+
+    use super::*;
+
+    struct MemoryPack(Vec<(&'static str, &'static str, &'static str)>);
+
+    impl ResourcePack for MemoryPack {
+        fn namespaces(&self) -> Vec<String> {
+            self.0.iter().map(|(ns, _, _)| ns.to_string()).collect()
+        }
+
+        fn read(&self, namespace: &str, path: &str) -> Option<Vec<u8>> {
+            self.0
+                .iter()
+                .find(|(ns, p, _)| *ns == namespace && *p == path)
+                .map(|(_, _, json)| json.as_bytes().to_owned())
+        }
+    }
+
+    #[test]
+    fn test_load_with_fallback_and_priority() {
+        let vanilla = MemoryPack(vec![
+            (
+                "minecraft",
+                "lang/en_us.json",
+                r#"{"block.minecraft.stone":"Stone","block.minecraft.dirt":"Dirt","greet":"Hi %s"}"#,
+            ),
+            (
+                "minecraft",
+                "lang/de_de.json",
+                r#"{"block.minecraft.stone":"Stein"}"#,
+            ),
+        ]);
+        let pack = MemoryPack(vec![(
+            "minecraft",
+            "lang/de_de.json",
+            r#"{"block.minecraft.stone":"Fels","greet":"Hallo %1$d"}"#,
+        )]);
+
+        let table = LanguageTable::load(&[&vanilla, &pack], "de_de").unwrap();
+        assert_eq!(table.block_name(Block::STONE), "Fels");
+        assert_eq!(table.block_name(Block::DIRT), "Dirt");
+        assert_eq!(table.block_name(Block::GRANITE), "Granite");
+
+        let greeting = Component::translatable("greet", vec!["Alex".into()]);
+        assert_eq!(greeting.to_plain_string(&table), "Hallo Alex");
+    }
+
+    #[test]
+    fn test_normalize_format() {
+        assert_eq!(normalize_format("%d%%"), "%s%%");
+        assert_eq!(normalize_format("%2$d of %1$.1f"), "%2$s of %1$s");
+        assert_eq!(normalize_format("%5d and %s"), "%s and %s");
+        assert_eq!(normalize_format("100% done"), "100% done");
+    }
+}
@@ -1,13 +1,19 @@
 #![cfg_attr(not(test), no_std)]
 
 extern crate alloc;
+#[cfg(feature = "objects")]
+extern crate std;
 
 mod blockstates;
 mod id;
+mod lang;
 mod models;
+#[cfg(feature = "objects")]
+mod objects;
 
 pub use blockstates::*;
 pub use id::*;
+pub use lang::*;
 pub use models::*;
 
 use hashbrown::HashMap;
@@ -0,0 +1,52 @@
+//! The hashed objects store of a version as a [`ResourcePack`]. It holds
+//! the sounds and the languages the client jar leaves out, and sits below
+//! the jar, as the game's vanilla pack reads both.
+
+use alloc::{format, string::String, vec::Vec};
+use mcje_downloader::AssetObjects;
+
+use crate::ResourcePack;
+
+impl ResourcePack for AssetObjects {
+    fn namespaces(&self) -> Vec<String> {
+        AssetObjects::namespaces(self)
+    }
+
+    fn read(&self, namespace: &str, path: &str) -> Option<Vec<u8>> {
+        AssetObjects::read(self, &format!("{namespace}/{path}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcje_downloader::{AssetIndex, AssetObject, Cache, sha1_hex};
+    use mcre_world::Block;
+
+    use super::*;
+    use crate::LanguageTable;
+
+    #[test]
+    fn test_language_from_objects() {
+        let dir = std::env::temp_dir().join(format!("mcre-assets-objects-{}", std::process::id()));
+        let cache = Cache::new(&dir, true);
+        let lang = br#"{"block.minecraft.stone":"Stein"}"#;
+        let hash = sha1_hex(lang);
+        let file = cache.object_path(&hash);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, lang).unwrap();
+
+        let mut index = AssetIndex::default();
+        index.objects.insert(
+            "minecraft/lang/de_de.json".into(),
+            AssetObject {
+                hash,
+                size: lang.len() as u64,
+            },
+        );
+        let objects = AssetObjects::new(index, cache);
+        let table = LanguageTable::load(&[&objects], "de_de").unwrap();
+        assert_eq!(table.block_name(Block::STONE), "Stein");
+        assert_eq!(table.block_name(Block::DIRT), "Dirt");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{Axis, BlockPos, Direction, Vec3d};
+
+/// An axis-aligned bounding box, vanilla's `AABB`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3d,
+    pub max: Vec3d,
+}
+
+impl Aabb {
+    /// The box between two opposite corners, in any order.
+    pub fn new(a: Vec3d, b: Vec3d) -> Self {
+        Self {
+            min: Vec3d::new(a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2])),
+            max: Vec3d::new(a[0].max(b[0]), a[1].max(b[1]), a[2].max(b[2])),
+        }
+    }
+
+    /// The unit cube of a block.
+    pub fn of_block(pos: BlockPos) -> Self {
+        let min = Vec3d::new(pos.x as f64, pos.y as f64, pos.z as f64);
+        Self {
+            min,
+            max: Vec3d::new(min[0] + 1.0, min[1] + 1.0, min[2] + 1.0),
+        }
+    }
+
+    pub fn size(&self, axis: Axis) -> f64 {
+        axis.select(self.max) - axis.select(self.min)
+    }
+
+    pub fn center(&self) -> Vec3d {
+        Vec3d::new(
+            (self.min[0] + self.max[0]) / 2.0,
+            (self.min[1] + self.max[1]) / 2.0,
+            (self.min[2] + self.max[2]) / 2.0,
+        )
+    }
+
+    pub fn moved(self, offset: Vec3d) -> Self {
+        Self {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+
+    /// Stretches the box by `offset`, on the side it points to, to cover
+    /// everything the box passes through when moving that far.
+    pub fn expand_towards(mut self, offset: Vec3d) -> Self {
+        for i in 0..3 {
+            if offset[i] < 0.0 {
+                self.min[i] += offset[i];
+            } else {
+                self.max[i] += offset[i];
+            }
+        }
+        self
+    }
+
+    /// Grows the box by the given amount on both sides of each axis.
+    pub fn inflate(self, x: f64, y: f64, z: f64) -> Self {
+        let amount = Vec3d::new(x, y, z);
+        Self::new(self.min - amount, self.max + amount)
+    }
+
+    pub fn deflate(self, x: f64, y: f64, z: f64) -> Self {
+        self.inflate(-x, -y, -z)
+    }
+
+    /// Whether the boxes overlap; touching faces do not count.
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        (0..3).all(|i| self.min[i] < other.max[i] && self.max[i] > other.min[i])
+    }
+
+    /// Whether a point lies within the box, counting its low faces but not
+    /// its high ones.
+    pub fn contains(&self, point: Vec3d) -> bool {
+        (0..3).all(|i| self.min[i] <= point[i] && point[i] < self.max[i])
+    }
+
+    /// The point where the segment from `from` to `to` enters the box.
+    pub fn clip(&self, from: Vec3d, to: Vec3d) -> Option<Vec3d> {
+        let (_, fraction) = self.clip_face(from, to)?;
+        let delta = to - from;
+        Some(Vec3d::new(
+            from[0] + delta[0] * fraction,
+            from[1] + delta[1] * fraction,
+            from[2] + delta[2] * fraction,
+        ))
+    }
+
+    /// The face the segment from `from` to `to` enters the box through, and
+    /// how far along the segment, from 0 to 1, after `AABB.getDirection`.
+    ///
+    /// A segment starting inside the box, or on the face it leaves through,
+    /// does not enter it. Faces are tested in x, y, z order, so a segment
+    /// through an edge enters through the face of the earlier axis.
+    pub fn clip_face(&self, from: Vec3d, to: Vec3d) -> Option<(Direction, f64)> {
+        let delta = to - from;
+        let faces = [
+            (Direction::West, Direction::East),
+            (Direction::Down, Direction::Up),
+            (Direction::North, Direction::South),
+        ];
+        let mut hit = None;
+        let mut scale = 1.0;
+        for (a, (low, high)) in faces.into_iter().enumerate() {
+            let (plane, face) = if delta[a] > 1e-7 {
+                (self.min[a], low)
+            } else if delta[a] < -1e-7 {
+                (self.max[a], high)
+            } else {
+                continue;
+            };
+            let t = (plane - from[a]) / delta[a];
+            if !(0.0 < t && t < scale) {
+                continue;
+            }
+            let within = [(a + 1) % 3, (a + 2) % 3].into_iter().all(|b| {
+                let at = from[b] + t * delta[b];
+                self.min[b] - 1e-7 < at && at < self.max[b] + 1e-7
+            });
+            if within {
+                scale = t;
+                hit = Some((face, t));
+            }
+        }
+        hit
+    }
+
+    /// How far `moving` can go along `axis`, up to `distance`, before it
+    /// runs into this box. Only boxes that overlap `moving` on the other two
+    /// axes can be run into.
+    pub fn collide(&self, axis: Axis, moving: &Aabb, distance: f64) -> f64 {
+        let a = axis as usize;
+        let overlaps = axis.complementary_axes().into_iter().all(|other| {
+            let b = other as usize;
+            self.min[b] < moving.max[b] - 1e-7 && self.max[b] > moving.min[b] + 1e-7
+        });
+        if !overlaps {
+            return distance;
+        }
+        if distance > 0.0 && self.min[a] > moving.max[a] - 1e-7 {
+            distance.min(self.min[a] - moving.max[a])
+        } else if distance < 0.0 && self.max[a] < moving.min[a] + 1e-7 {
+            distance.max(self.max[a] - moving.min[a])
+        } else {
+            distance
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aabb(min: [f64; 3], max: [f64; 3]) -> Aabb {
+        Aabb::new(
+            Vec3d::new(min[0], min[1], min[2]),
+            Vec3d::new(max[0], max[1], max[2]),
+        )
+    }
+
+    #[test]
+    fn test_aabb_operations() {
+        let unit = Aabb::of_block(BlockPos::ZERO);
+        assert_eq!(aabb([1.0, 1.0, 1.0], [0.0, 0.0, 0.0]), unit);
+        assert_eq!(
+            unit.expand_towards(Vec3d::new(-1.0, 0.5, 0.0)),
+            aabb([-1.0, 0.0, 0.0], [1.0, 1.5, 1.0])
+        );
+        assert_eq!(
+            unit.deflate(0.25, 0.0, 0.0),
+            aabb([0.25, 0.0, 0.0], [0.75, 1.0, 1.0])
+        );
+        assert!(unit.intersects(&aabb([0.5, 0.5, 0.5], [2.0, 2.0, 2.0])));
+        assert!(!unit.intersects(&aabb([1.0, 0.0, 0.0], [2.0, 1.0, 1.0])));
+        assert!(unit.contains(Vec3d::new(0.0, 0.5, 0.5)));
+        assert!(!unit.contains(Vec3d::new(1.0, 0.5, 0.5)));
+    }
+
+    #[test]
+    fn test_aabb_clip() {
+        let unit = Aabb::of_block(BlockPos::ZERO);
+        let hit = unit.clip_face(Vec3d::new(-1.0, 0.5, 0.5), Vec3d::new(1.0, 0.5, 0.5));
+        assert_eq!(hit, Some((Direction::West, 0.5)));
+        assert_eq!(
+            unit.clip(Vec3d::new(0.5, 2.0, 0.5), Vec3d::new(0.5, -2.0, 0.5)),
+            Some(Vec3d::new(0.5, 1.0, 0.5))
+        );
+        // too short, and starting inside
+        assert_eq!(
+            unit.clip(Vec3d::new(-1.0, 0.5, 0.5), Vec3d::new(-0.5, 0.5, 0.5)),
+            None
+        );
+        assert_eq!(
+            unit.clip(Vec3d::new(0.5, 0.5, 0.5), Vec3d::new(2.0, 0.5, 0.5)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_aabb_collide() {
+        let wall = aabb([2.0, 0.0, 0.0], [3.0, 1.0, 1.0]);
+        let player = aabb([0.2, 0.0, 0.2], [0.8, 1.8, 0.8]);
+        assert!((wall.collide(Axis::X, &player, 5.0) - 1.2).abs() < 1e-12);
+        assert_eq!(wall.collide(Axis::X, &player, -5.0), -5.0);
+        assert_eq!(wall.collide(Axis::X, &player, 1.0), 1.0);
+        // beside the wall rather than in front of it
+        let beside = player.moved(Vec3d::new(0.0, 0.0, 1.0));
+        assert_eq!(wall.collide(Axis::X, &beside, 5.0), 5.0);
+    }
+}
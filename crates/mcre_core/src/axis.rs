@@ -2,7 +2,7 @@ use core::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
-use crate::VecN;
+use crate::{Vec3i, VecN};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
 #[serde(rename_all = "lowercase")]
@@ -62,6 +62,18 @@ impl Direction {
         }
     }
 
+    /// The unit offset towards the neighbour in this direction.
+    pub fn step(self) -> Vec3i {
+        match self {
+            Direction::Down => Vec3i::new(0, -1, 0),
+            Direction::Up => Vec3i::new(0, 1, 0),
+            Direction::North => Vec3i::new(0, 0, -1),
+            Direction::South => Vec3i::new(0, 0, 1),
+            Direction::West => Vec3i::new(-1, 0, 0),
+            Direction::East => Vec3i::new(1, 0, 0),
+        }
+    }
+
     pub fn axis(self) -> Axis {
         match self {
             Direction::Down | Direction::Up => Axis::Y,
@@ -1,6 +1,6 @@
-use crate::Vec3i;
+use crate::{Direction, Vec3i};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub struct BlockPos {
     pub x: i32,
     pub y: i32,
@@ -15,6 +15,19 @@ impl BlockPos {
         Self { x, y, z }
     }
 
+    /// The neighbouring position in `direction`.
+    #[inline]
+    pub const fn relative(self, direction: Direction) -> Self {
+        match direction {
+            Direction::Down => Self::new(self.x, self.y - 1, self.z),
+            Direction::Up => Self::new(self.x, self.y + 1, self.z),
+            Direction::North => Self::new(self.x, self.y, self.z - 1),
+            Direction::South => Self::new(self.x, self.y, self.z + 1),
+            Direction::West => Self::new(self.x - 1, self.y, self.z),
+            Direction::East => Self::new(self.x + 1, self.y, self.z),
+        }
+    }
+
     #[inline]
     pub fn seed(self) -> i64 {
         let mut i = (self.x as i64).wrapping_mul(3_129_871)
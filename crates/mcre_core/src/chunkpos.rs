@@ -0,0 +1,54 @@
+use crate::BlockPos;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChunkPos {
+    pub x: i32,
+    pub z: i32,
+}
+
+impl ChunkPos {
+    #[inline]
+    pub const fn new(x: i32, z: i32) -> Self {
+        Self { x, z }
+    }
+
+    /// Packs the position the way `ChunkPos.toLong` does: `x` in the low half,
+    /// `z` in the high half.
+    #[inline]
+    pub const fn to_long(self) -> i64 {
+        (self.x as u32 as i64) | ((self.z as u32 as i64) << 32)
+    }
+
+    #[inline]
+    pub const fn from_long(packed: i64) -> Self {
+        Self {
+            x: packed as i32,
+            z: (packed >> 32) as i32,
+        }
+    }
+
+    #[inline]
+    pub const fn min_block_x(self) -> i32 {
+        self.x << 4
+    }
+
+    #[inline]
+    pub const fn min_block_z(self) -> i32 {
+        self.z << 4
+    }
+}
+
+impl BlockPos {
+    #[inline]
+    pub const fn chunk(self) -> ChunkPos {
+        ChunkPos {
+            x: self.x >> 4,
+            z: self.z >> 4,
+        }
+    }
+
+    #[inline]
+    pub const fn section_y(self) -> i32 {
+        self.y >> 4
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// How rare an item is, which colors its name.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+}
+
+impl Rarity {
+    /// The name of the text color of items of this rarity.
+    pub fn color(self) -> &'static str {
+        match self {
+            Rarity::Common => "white",
+            Rarity::Uncommon => "yellow",
+            Rarity::Rare => "aqua",
+            Rarity::Epic => "light_purple",
+        }
+    }
+}
+
+/// What eating an item restores, vanilla's `FoodProperties` component.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FoodProperties {
+    pub nutrition: u8,
+    pub saturation: f32,
+    pub can_always_eat: bool,
+}
+
+/// The `tool` component, without its per-block rules, which refer to block
+/// tags.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Tool {
+    pub default_mining_speed: f32,
+    pub damage_per_block: u8,
+    pub can_destroy_blocks_in_creative: bool,
+}
@@ -2,12 +2,22 @@
 
 extern crate alloc;
 
+mod aabb;
 mod axis;
 mod blockpos;
+mod chunkpos;
+mod fluid;
+mod item;
 mod random_offset;
+mod transform;
 mod vec;
 
+pub use aabb::Aabb;
 pub use axis::{Axis, Direction, SignedAxis};
 pub use blockpos::BlockPos;
+pub use chunkpos::ChunkPos;
+pub use fluid::{Fluid, FluidState};
+pub use item::{FoodProperties, Rarity, Tool};
 pub use random_offset::OffsetType;
+pub use transform::{Mirror, Rotation};
 pub use vec::*;
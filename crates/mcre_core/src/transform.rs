@@ -0,0 +1,152 @@
+use crate::{Axis, BlockPos, Direction};
+
+/// A turn about the Y axis, as used when placing structures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise90,
+    Clockwise180,
+    Counterclockwise90,
+}
+
+impl Rotation {
+    pub const ALL: [Rotation; 4] = [
+        Rotation::None,
+        Rotation::Clockwise90,
+        Rotation::Clockwise180,
+        Rotation::Counterclockwise90,
+    ];
+
+    /// The number of clockwise quarter turns.
+    pub fn quarter_turns(self) -> u8 {
+        match self {
+            Rotation::None => 0,
+            Rotation::Clockwise90 => 1,
+            Rotation::Clockwise180 => 2,
+            Rotation::Counterclockwise90 => 3,
+        }
+    }
+
+    pub fn then(self, other: Rotation) -> Rotation {
+        Self::ALL[((self.quarter_turns() + other.quarter_turns()) % 4) as usize]
+    }
+
+    pub fn rotate(self, direction: Direction) -> Direction {
+        if direction.axis() == Axis::Y {
+            return direction;
+        }
+        let mut direction = direction;
+        for _ in 0..self.quarter_turns() {
+            direction = match direction {
+                Direction::North => Direction::East,
+                Direction::East => Direction::South,
+                Direction::South => Direction::West,
+                _ => Direction::North,
+            };
+        }
+        direction
+    }
+
+    /// Rotates one of `count` evenly spaced headings, such as the 16 of a
+    /// standing sign's `rotation`.
+    pub fn rotate_heading(self, heading: u8, count: u8) -> u8 {
+        ((heading as u32 + self.quarter_turns() as u32 * count as u32 / 4) % count as u32) as u8
+    }
+
+    /// Rotates a position about `pivot`, like `StructureTemplate.transform`.
+    pub fn rotate_pos(self, pos: BlockPos, pivot: BlockPos) -> BlockPos {
+        let (x, z) = (pos.x, pos.z);
+        match self {
+            Rotation::None => pos,
+            Rotation::Clockwise90 => {
+                BlockPos::new(pivot.x + pivot.z - z, pos.y, pivot.z - pivot.x + x)
+            }
+            Rotation::Clockwise180 => BlockPos::new(2 * pivot.x - x, pos.y, 2 * pivot.z - z),
+            Rotation::Counterclockwise90 => {
+                BlockPos::new(pivot.x - pivot.z + z, pos.y, pivot.x + pivot.z - x)
+            }
+        }
+    }
+}
+
+/// A reflection in a vertical plane, as used when placing structures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Mirror {
+    #[default]
+    None,
+    /// Swaps north and south.
+    LeftRight,
+    /// Swaps east and west.
+    FrontBack,
+}
+
+impl Mirror {
+    pub fn mirror(self, direction: Direction) -> Direction {
+        match (self, direction) {
+            (Mirror::LeftRight, Direction::North | Direction::South)
+            | (Mirror::FrontBack, Direction::East | Direction::West) => direction.opposite(),
+            _ => direction,
+        }
+    }
+
+    /// Mirrors one of `count` evenly spaced headings, heading 0 being south.
+    pub fn mirror_heading(self, heading: u8, count: u8) -> u8 {
+        let (heading, count) = (heading as u32, count as u32);
+        match self {
+            Mirror::None => heading as u8,
+            Mirror::LeftRight => ((count + count / 2 - heading) % count) as u8,
+            Mirror::FrontBack => ((count - heading) % count) as u8,
+        }
+    }
+
+    /// Mirrors a position through the origin's axis planes.
+    pub fn mirror_pos(self, pos: BlockPos) -> BlockPos {
+        match self {
+            Mirror::None => pos,
+            Mirror::LeftRight => BlockPos::new(pos.x, pos.y, -pos.z),
+            Mirror::FrontBack => BlockPos::new(-pos.x, pos.y, pos.z),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation() {
+        assert_eq!(
+            Rotation::Clockwise90.rotate(Direction::North),
+            Direction::East
+        );
+        assert_eq!(
+            Rotation::Counterclockwise90.rotate(Direction::North),
+            Direction::West
+        );
+        assert_eq!(Rotation::Clockwise180.rotate(Direction::Up), Direction::Up);
+        assert_eq!(Rotation::Clockwise90.rotate_heading(14, 16), 2);
+        assert_eq!(
+            Rotation::Clockwise90.then(Rotation::Counterclockwise90),
+            Rotation::None
+        );
+        // south of the pivot ends up west of it
+        assert_eq!(
+            Rotation::Clockwise90.rotate_pos(BlockPos::new(0, 5, 1), BlockPos::ZERO),
+            BlockPos::new(-1, 5, 0)
+        );
+    }
+
+    #[test]
+    fn test_mirror() {
+        assert_eq!(Mirror::LeftRight.mirror(Direction::North), Direction::South);
+        assert_eq!(Mirror::LeftRight.mirror(Direction::East), Direction::East);
+        assert_eq!(Mirror::LeftRight.mirror_heading(0, 16), 8);
+        assert_eq!(Mirror::FrontBack.mirror_heading(4, 16), 12);
+        assert_eq!(Mirror::FrontBack.mirror_heading(0, 16), 0);
+        assert_eq!(
+            Mirror::FrontBack.mirror_pos(BlockPos::new(3, 1, 2)),
+            BlockPos::new(-3, 1, 2)
+        );
+    }
+}
@@ -11,6 +11,7 @@ pub struct VecN<T, const LEN: usize>([T; LEN]);
 pub type Vec3i = VecN<i32, 3>;
 pub type Vec3u = VecN<u32, 3>;
 pub type Vec3f = VecN<f32, 3>;
+pub type Vec3d = VecN<f64, 3>;
 pub type Vec4i = VecN<i32, 4>;
 pub type Vec4u = VecN<u32, 4>;
 pub type Vec4f = VecN<f32, 4>;
@@ -82,6 +83,12 @@ impl<T, const LEN: usize> IndexMut<usize> for VecN<T, LEN> {
     }
 }
 
+impl<T, const LEN: usize> From<[T; LEN]> for VecN<T, LEN> {
+    fn from(array: [T; LEN]) -> Self {
+        Self(array)
+    }
+}
+
 impl<T> VecN<T, 3> {
     pub fn new(x: T, y: T, z: T) -> Self {
         Self([x, y, z])
@@ -6,22 +6,32 @@ use tokio::fs;
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Block {
     pub id: u16,
-    pub name: String,         // "oak_planks"
-    pub display_name: String, // "Oak Planks",
+    pub name: String,            // "oak_planks"
+    pub display_name: String,    // "Oak Planks",
+    pub translation_key: String, // "block.minecraft.oak_planks"
     pub default_state_id: u16,
     pub min_state_id: u16,
     pub max_state_id: u16,
     pub states: Vec<BlockStateField>,
+    pub friction: f32,
+    pub speed_factor: f32,
+    pub jump_factor: f32,
+    // The item standing for it, `Block.asItem`; missing from data
+    // generated before items were.
+    #[serde(default)]
+    pub item: Option<String>,
+    // The tags of `BLOCK_TAGS` in data_gen that the block is in.
+    pub tags: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct BlockStateField {
     pub name: String,
     #[serde(flatten)]
     pub values: BlockStateFieldValues,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum BlockStateFieldValues {
     Bool,
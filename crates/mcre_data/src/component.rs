@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::PathBuf;
+use tokio::fs;
+
+/// An entry of the data component type registry.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DataComponentType {
+    pub id: u16,
+    pub name: String, // "custom_name"
+    // Whether stacks save the component; transient ones only live in memory.
+    pub persistent: bool,
+}
+
+impl DataComponentType {
+    pub fn all_sync() -> io::Result<Box<[Self]>> {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let component_data_path = root.join("data_components.json");
+        let component_data_json = std::fs::read_to_string(component_data_path)?;
+        Ok(serde_json::from_str(&component_data_json)?)
+    }
+
+    pub async fn all() -> io::Result<Vec<Self>> {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let component_data_path = root.join("data_components.json");
+        let component_data_json = fs::read_to_string(component_data_path).await?;
+        let component_data: Vec<Self> = serde_json::from_str(&component_data_json)?;
+
+        Ok(component_data)
+    }
+}
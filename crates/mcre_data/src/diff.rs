@@ -0,0 +1,474 @@
+//! What changed in the block data between two versions of the game.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, io,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    block::{Block, BlockStateField},
+    remap::StateIdRemap,
+    state::BlockState,
+};
+
+/// The `blocks.json` and `block_states.json` generated for one version.
+#[derive(Debug)]
+pub struct DataSet {
+    pub blocks: Vec<Block>,
+    pub states: Vec<BlockState>,
+}
+
+impl DataSet {
+    /// Reads the data generated into `dir`.
+    pub fn load(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        let blocks = std::fs::read_to_string(dir.join("blocks.json"))?;
+        let states = std::fs::read_to_string(dir.join("block_states.json"))?;
+        Ok(Self {
+            blocks: serde_json::from_str(&blocks)?,
+            states: serde_json::from_str(&states)?,
+        })
+    }
+
+    /// The data of the current version.
+    pub fn current() -> io::Result<Self> {
+        Self::load(PathBuf::from(env!("CARGO_MANIFEST_DIR")))
+    }
+
+    pub fn block(&self, name: &str) -> Option<&Block> {
+        self.blocks.iter().find(|block| block.name == name)
+    }
+}
+
+/// The blocks added, removed and renamed from one version to another, the
+/// property changes of the others and where their states went.
+#[derive(Debug, Default, PartialEq)]
+pub struct VersionDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Old and new names.
+    pub renamed: Vec<(String, String)>,
+    pub schemas: Vec<SchemaChange>,
+    /// Every kept block whose states have other ids, in the new order.
+    pub moved: Vec<MovedRange>,
+}
+
+/// The properties of a block that were added, removed, or kept with other
+/// values.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SchemaChange {
+    pub block: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct MovedRange {
+    pub block: String,
+    pub old: RangeInclusive<u16>,
+    pub new: RangeInclusive<u16>,
+}
+
+impl MovedRange {
+    /// How far the first state moved.
+    pub fn shift(&self) -> i32 {
+        i32::from(*self.new.start()) - i32::from(*self.old.start())
+    }
+}
+
+impl VersionDiff {
+    /// Compares `old` to `new`. A block is renamed if `renames` says so, or if
+    /// it was removed and an added block with the same properties follows
+    /// the same block it did, not counting the other added ones.
+    pub fn new(old: &DataSet, new: &DataSet, renames: &[(String, String)]) -> Self {
+        let old_names: HashSet<&str> = old.blocks.iter().map(|block| block.name.as_str()).collect();
+        let new_names: HashSet<&str> = new.blocks.iter().map(|block| block.name.as_str()).collect();
+        let mut renamed: Vec<(String, String)> = renames
+            .iter()
+            .filter(|(from, to)| {
+                old_names.contains(from.as_str()) && new_names.contains(to.as_str())
+            })
+            .cloned()
+            .collect();
+
+        let is_renamed_from =
+            |renamed: &[(String, String)], name: &str| renamed.iter().any(|(from, _)| from == name);
+        let is_renamed_to =
+            |renamed: &[(String, String)], name: &str| renamed.iter().any(|(_, to)| to == name);
+
+        for (i, block) in old.blocks.iter().enumerate() {
+            if new_names.contains(block.name.as_str()) || is_renamed_from(&renamed, &block.name) {
+                continue;
+            }
+            let predecessor = i
+                .checked_sub(1)
+                .map(|i| new_name(&renamed, &old.blocks[i].name).to_owned());
+            let mut candidates = new.blocks.iter().enumerate().filter(|(j, candidate)| {
+                !old_names.contains(candidate.name.as_str())
+                    && !is_renamed_to(&renamed, &candidate.name)
+                    && candidate.states == block.states
+                    && new.blocks[..*j]
+                        .iter()
+                        .rev()
+                        .map(|block| block.name.as_str())
+                        .find(|name| old_names.contains(name) || is_renamed_to(&renamed, name))
+                        == predecessor.as_deref()
+            });
+            if let (Some((_, candidate)), None) = (candidates.next(), candidates.next()) {
+                renamed.push((block.name.clone(), candidate.name.clone()));
+            }
+        }
+
+        let removed = old
+            .blocks
+            .iter()
+            .filter(|block| {
+                !new_names.contains(block.name.as_str()) && !is_renamed_from(&renamed, &block.name)
+            })
+            .map(|block| block.name.clone())
+            .collect();
+        let added = new
+            .blocks
+            .iter()
+            .filter(|block| {
+                !old_names.contains(block.name.as_str()) && !is_renamed_to(&renamed, &block.name)
+            })
+            .map(|block| block.name.clone())
+            .collect();
+
+        let old_blocks: HashMap<&str, &Block> = old
+            .blocks
+            .iter()
+            .map(|block| (new_name(&renamed, &block.name), block))
+            .collect();
+        let mut schemas = Vec::new();
+        let mut moved = Vec::new();
+        for block in &new.blocks {
+            let Some(old_block) = old_blocks.get(block.name.as_str()) else {
+                continue;
+            };
+            if let Some(change) = SchemaChange::new(&block.name, &old_block.states, &block.states) {
+                schemas.push(change);
+            }
+            let old_range = old_block.min_state_id..=old_block.max_state_id;
+            let new_range = block.min_state_id..=block.max_state_id;
+            if old_range != new_range {
+                moved.push(MovedRange {
+                    block: block.name.clone(),
+                    old: old_range,
+                    new: new_range,
+                });
+            }
+        }
+
+        Self {
+            added,
+            removed,
+            renamed,
+            schemas,
+            moved,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The remap of the state ids of `old` to those of `new`, with the
+    /// renames found by this diff.
+    pub fn remap(&self, old: &DataSet, new: &DataSet) -> StateIdRemap {
+        StateIdRemap::new(old, new, &self.renamed)
+    }
+}
+
+fn new_name<'a>(renamed: &'a [(String, String)], name: &'a str) -> &'a str {
+    renamed
+        .iter()
+        .find(|(from, _)| from == name)
+        .map_or(name, |(_, to)| to)
+}
+
+impl SchemaChange {
+    fn new(block: &str, old: &[BlockStateField], new: &[BlockStateField]) -> Option<Self> {
+        let mut change = Self {
+            block: block.to_owned(),
+            ..Self::default()
+        };
+        for field in new {
+            match old.iter().find(|old| old.name == field.name) {
+                None => change.added.push(field.name.clone()),
+                Some(old) if old.values != field.values => change.changed.push(field.name.clone()),
+                Some(_) => {}
+            }
+        }
+        change.removed = old
+            .iter()
+            .filter(|old| !new.iter().any(|field| field.name == old.name))
+            .map(|old| old.name.clone())
+            .collect();
+        let unchanged =
+            change.added.is_empty() && change.removed.is_empty() && change.changed.is_empty();
+        (!unchanged).then_some(change)
+    }
+}
+
+impl fmt::Display for VersionDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no changes");
+        }
+        if !self.added.is_empty() {
+            writeln!(f, "added ({}):", self.added.len())?;
+            for name in &self.added {
+                writeln!(f, "  minecraft:{name}")?;
+            }
+        }
+        if !self.removed.is_empty() {
+            writeln!(f, "removed ({}):", self.removed.len())?;
+            for name in &self.removed {
+                writeln!(f, "  minecraft:{name}")?;
+            }
+        }
+        if !self.renamed.is_empty() {
+            writeln!(f, "renamed ({}):", self.renamed.len())?;
+            for (from, to) in &self.renamed {
+                writeln!(f, "  minecraft:{from} -> minecraft:{to}")?;
+            }
+        }
+        if !self.schemas.is_empty() {
+            writeln!(f, "properties changed ({}):", self.schemas.len())?;
+            for change in &self.schemas {
+                write!(f, "  minecraft:{}:", change.block)?;
+                for name in &change.added {
+                    write!(f, " +{name}")?;
+                }
+                for name in &change.removed {
+                    write!(f, " -{name}")?;
+                }
+                for name in &change.changed {
+                    write!(f, " ~{name}")?;
+                }
+                writeln!(f)?;
+            }
+        }
+        if !self.moved.is_empty() {
+            writeln!(f, "state ids moved ({} blocks):", self.moved.len())?;
+            // blocks that moved as one are listed together
+            for run in self.moved.chunk_by(|a, b| {
+                a.shift() == b.shift()
+                    && a.new.end() + 1 == *b.new.start()
+                    && a.old.end() + 1 == *b.old.start()
+                    && b.new.len() == b.old.len()
+            }) {
+                let (first, last) = (&run[0], &run[run.len() - 1]);
+                if run.len() == 1 {
+                    write!(f, "  minecraft:{}", first.block)?;
+                } else {
+                    write!(
+                        f,
+                        "  minecraft:{}..minecraft:{} ({} blocks)",
+                        first.block,
+                        last.block,
+                        run.len()
+                    )?;
+                }
+                writeln!(
+                    f,
+                    ": {}..={} -> {}..={}",
+                    first.old.start(),
+                    last.old.end(),
+                    first.new.start(),
+                    last.new.end()
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use indexmap::IndexMap;
+    use mcre_core::{FluidState, OffsetType};
+
+    use super::*;
+    use crate::{block::BlockStateFieldValues, state::StateValue};
+
+    pub(crate) fn field(name: &str, values: &[&str]) -> BlockStateField {
+        let values = if values == ["false", "true"] {
+            BlockStateFieldValues::Bool
+        } else if let Ok(min) = values[0].parse()
+            && let Ok(max) = values[values.len() - 1].parse()
+        {
+            BlockStateFieldValues::Int { min, max }
+        } else {
+            BlockStateFieldValues::Enum {
+                enum_name: name.to_owned(),
+                values: values.iter().map(|value| (*value).to_owned()).collect(),
+            }
+        };
+        BlockStateField {
+            name: name.to_owned(),
+            values,
+        }
+    }
+
+    fn field_values(field: &BlockStateField) -> Vec<StateValue> {
+        match &field.values {
+            BlockStateFieldValues::Bool => vec![StateValue::Bool(false), StateValue::Bool(true)],
+            BlockStateFieldValues::Int { min, max } => (*min..=*max).map(StateValue::Int).collect(),
+            BlockStateFieldValues::Enum { values, .. } => {
+                values.iter().cloned().map(StateValue::String).collect()
+            }
+        }
+    }
+
+    /// Blocks with every combination of the values of their properties, the
+    /// last one changing first, and the first combination as default.
+    pub(crate) fn data_set(blocks: &[(&str, Vec<BlockStateField>)]) -> DataSet {
+        let mut data = DataSet {
+            blocks: Vec::new(),
+            states: Vec::new(),
+        };
+        for (name, fields) in blocks {
+            let block_id = data.blocks.len() as u16;
+            let min_state_id = data.states.len() as u16;
+            let mut combinations = vec![IndexMap::new()];
+            for field in fields {
+                combinations = combinations
+                    .into_iter()
+                    .flat_map(|values| {
+                        field_values(field).into_iter().map(move |value| {
+                            let mut values = values.clone();
+                            values.insert(field.name.clone(), value);
+                            values
+                        })
+                    })
+                    .collect();
+            }
+            for state_values in combinations {
+                data.states.push(BlockState {
+                    id: data.states.len() as u16,
+                    block_id,
+                    block_name: (*name).to_owned(),
+                    light_emission: 0,
+                    use_shape_for_light_occlusion: false,
+                    propagates_skylight_down: false,
+                    light_block: 0,
+                    solid_render: true,
+                    is_air: false,
+                    ignited_by_lava: false,
+                    can_occlude: true,
+                    is_randomly_ticking: false,
+                    replaceable: false,
+                    spawn_terrain_particles: true,
+                    requires_correct_tool_for_drops: false,
+                    destroy_speed: 1.0,
+                    blocks_motion: true,
+                    offset_type: OffsetType::None,
+                    max_horizontal_offset: 0.0,
+                    max_vertical_offset: 0.0,
+                    fluid_state: FluidState::EMPTY,
+                    outline_shape: vec![[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]],
+                    collision_shape: vec![[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]],
+                    occlusion_shape: vec![[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]],
+                    state_values,
+                });
+            }
+            data.blocks.push(Block {
+                id: block_id,
+                name: (*name).to_owned(),
+                display_name: (*name).to_owned(),
+                translation_key: format!("block.minecraft.{name}"),
+                default_state_id: min_state_id,
+                min_state_id,
+                max_state_id: data.states.len() as u16 - 1,
+                states: fields.clone(),
+                friction: 0.6,
+                speed_factor: 1.0,
+                jump_factor: 1.0,
+                item: None,
+                tags: Vec::new(),
+            });
+        }
+        data
+    }
+
+    #[test]
+    fn test_version_diff() {
+        let old = data_set(&[
+            ("air", vec![]),
+            ("stone", vec![]),
+            ("grass", vec![field("snowy", &["false", "true"])]),
+            ("fence", vec![field("north", &["false", "true"])]),
+            ("gear", vec![]),
+        ]);
+        let new = data_set(&[
+            ("air", vec![]),
+            ("stone", vec![]),
+            ("granite", vec![]),
+            ("short_grass", vec![field("snowy", &["false", "true"])]),
+            (
+                "fence",
+                vec![
+                    field("north", &["false", "true"]),
+                    field("waterlogged", &["false", "true"]),
+                ],
+            ),
+        ]);
+        let diff = VersionDiff::new(&old, &new, &[]);
+        assert_eq!(diff.added, ["granite"]);
+        assert_eq!(diff.removed, ["gear"]);
+        assert_eq!(diff.renamed, [("grass".into(), "short_grass".into())]);
+        assert_eq!(
+            diff.schemas,
+            [SchemaChange {
+                block: "fence".into(),
+                added: vec!["waterlogged".into()],
+                ..SchemaChange::default()
+            }]
+        );
+        assert_eq!(
+            diff.moved,
+            [
+                MovedRange {
+                    block: "short_grass".into(),
+                    old: 2..=3,
+                    new: 3..=4,
+                },
+                MovedRange {
+                    block: "fence".into(),
+                    old: 4..=5,
+                    new: 5..=8,
+                },
+            ]
+        );
+
+        let text = diff.to_string();
+        assert!(text.contains("renamed (1):\n  minecraft:grass -> minecraft:short_grass\n"));
+        assert!(text.contains("  minecraft:fence: +waterlogged\n"));
+        assert!(text.contains("  minecraft:short_grass: 2..=3 -> 3..=4\n"));
+        assert!(text.contains("  minecraft:fence: 4..=5 -> 5..=8\n"));
+
+        let remap = diff.remap(&old, &new);
+        assert_eq!(remap.get(3), Some(4));
+        assert_eq!(remap.get(5), Some(7));
+        assert_eq!(remap.get(6), None);
+    }
+
+    #[test]
+    fn test_rename_hint() {
+        let old = data_set(&[("air", vec![]), ("chain", vec![])]);
+        let new = data_set(&[("iron_chain", vec![]), ("air", vec![])]);
+        assert_eq!(
+            VersionDiff::new(&old, &new, &[]).renamed,
+            Vec::<(String, String)>::new()
+        );
+        let diff = VersionDiff::new(&old, &new, &[("chain".into(), "iron_chain".into())]);
+        assert_eq!(diff.renamed, [("chain".into(), "iron_chain".into())]);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+use mcre_core::{FoodProperties, Rarity, Tool};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::PathBuf;
+use tokio::fs;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Item {
+    pub id: u16,
+    pub name: String,            // "oak_planks"
+    pub display_name: String,    // "Oak Planks"
+    pub translation_key: String, // "block.minecraft.oak_planks"
+    pub max_stack_size: u8,
+    // The block it places, for block items.
+    pub block: Option<String>,
+    pub components: ItemComponents,
+}
+
+/// The default data components of an item that the client needs.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ItemComponents {
+    pub max_damage: Option<u16>,
+    pub food: Option<FoodProperties>,
+    pub tool: Option<Tool>,
+    pub rarity: Rarity,
+}
+
+impl Item {
+    pub fn all_sync() -> io::Result<Box<[Self]>> {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let item_data_path = root.join("items.json");
+        let item_data_json = std::fs::read_to_string(item_data_path)?;
+        Ok(serde_json::from_str(&item_data_json)?)
+    }
+
+    pub async fn all() -> io::Result<Vec<Self>> {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let item_data_path = root.join("items.json");
+        let item_data_json = fs::read_to_string(item_data_path).await?;
+        let item_data: Vec<Self> = serde_json::from_str(&item_data_json)?;
+
+        Ok(item_data)
+    }
+}
@@ -1,2 +1,7 @@
 pub mod block;
+pub mod component;
+pub mod diff;
+pub mod item;
+pub mod remap;
 pub mod state;
+pub mod versions;
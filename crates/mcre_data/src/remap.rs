@@ -0,0 +1,222 @@
+//! Converting the block state ids of one version into those of another, for
+//! data saved or recorded by an older game.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::{Block, BlockStateFieldValues},
+    diff::DataSet,
+    state::StateValue,
+};
+
+/// Where each state id of one version went in another, as runs of ids that
+/// moved together. States of removed blocks map to nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateIdRemap {
+    runs: Vec<RemapRun>,
+}
+
+/// `len` consecutive ids from `from` on, mapped to as many from `to` on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemapRun {
+    pub from: u16,
+    pub to: u16,
+    pub len: u16,
+}
+
+impl StateIdRemap {
+    /// Maps every state of `old` to the state of `new` with the same block
+    /// and property values. Blocks are matched by name, or by `renames` of
+    /// old names to new ones. Properties the new block lacks are dropped and
+    /// those it added, or whose old value it no longer allows, take their
+    /// values from its default state.
+    pub fn new(old: &DataSet, new: &DataSet, renames: &[(String, String)]) -> Self {
+        let renames: HashMap<&str, &str> = renames
+            .iter()
+            .map(|(old, new)| (old.as_str(), new.as_str()))
+            .collect();
+        let new_blocks: HashMap<&str, &Block> = new
+            .blocks
+            .iter()
+            .map(|block| (block.name.as_str(), block))
+            .collect();
+
+        let mut table = vec![None; old.states.len()];
+        for old_block in &old.blocks {
+            let name = renames
+                .get(old_block.name.as_str())
+                .copied()
+                .unwrap_or(&old_block.name);
+            let Some(new_block) = new_blocks.get(name) else {
+                continue;
+            };
+            let new_states = new_states_by_values(new, new_block);
+            let default_values = values_key(new, new_block, new_block.default_state_id);
+            for old_id in old_block.min_state_id..=old_block.max_state_id {
+                let Some(old_state) = old.states.get(old_id as usize) else {
+                    continue;
+                };
+                let key: Vec<String> = new_block
+                    .states
+                    .iter()
+                    .zip(&default_values)
+                    .map(|(field, default)| {
+                        old_state
+                            .state_values
+                            .get(&field.name)
+                            .map(value_text)
+                            .filter(|value| allows(&field.values, value))
+                            .unwrap_or_else(|| default.clone())
+                    })
+                    .collect();
+                table[old_id as usize] = new_states.get(&key).copied();
+            }
+        }
+        Self::from_table(table)
+    }
+
+    /// The remap giving `table[old]` for every old id.
+    pub fn from_table(table: impl IntoIterator<Item = Option<u16>>) -> Self {
+        let mut runs: Vec<RemapRun> = Vec::new();
+        for (from, to) in table.into_iter().enumerate() {
+            let Some(to) = to else { continue };
+            let from = from as u16;
+            match runs.last_mut() {
+                Some(run) if run.from + run.len == from && run.to + run.len == to => run.len += 1,
+                _ => runs.push(RemapRun { from, to, len: 1 }),
+            }
+        }
+        Self { runs }
+    }
+
+    /// The id `old` has in the newer version, if its block is still there.
+    pub fn get(&self, old: u16) -> Option<u16> {
+        let index = self
+            .runs
+            .partition_point(|run| run.from <= old)
+            .checked_sub(1)?;
+        let run = self.runs[index];
+        (old - run.from < run.len).then(|| run.to + (old - run.from))
+    }
+
+    pub fn runs(&self) -> &[RemapRun] {
+        &self.runs
+    }
+
+    /// How many old ids map to a new one.
+    pub fn mapped(&self) -> usize {
+        self.runs.iter().map(|run| run.len as usize).sum()
+    }
+}
+
+/// The states of `block` in `data` by their values, in the order of its
+/// properties.
+fn new_states_by_values(data: &DataSet, block: &Block) -> HashMap<Vec<String>, u16> {
+    (block.min_state_id..=block.max_state_id)
+        .map(|id| (values_key(data, block, id), id))
+        .collect()
+}
+
+fn values_key(data: &DataSet, block: &Block, id: u16) -> Vec<String> {
+    let state = data.states.get(id as usize);
+    block
+        .states
+        .iter()
+        .map(|field| {
+            state
+                .and_then(|state| state.state_values.get(&field.name))
+                .map(value_text)
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn value_text(value: &StateValue) -> String {
+    match value {
+        StateValue::Bool(value) => value.to_string(),
+        StateValue::Int(value) => value.to_string(),
+        StateValue::String(value) => value.clone(),
+    }
+}
+
+fn allows(values: &BlockStateFieldValues, value: &str) -> bool {
+    match values {
+        BlockStateFieldValues::Bool => value == "true" || value == "false",
+        BlockStateFieldValues::Int { min, max } => value
+            .parse::<u8>()
+            .is_ok_and(|value| (*min..=*max).contains(&value)),
+        BlockStateFieldValues::Enum { values, .. } => values.iter().any(|allowed| allowed == value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::tests::{data_set, field};
+
+    #[test]
+    fn test_runs() {
+        let remap = StateIdRemap::from_table([Some(0), Some(1), None, Some(5), Some(6), Some(2)]);
+        assert_eq!(
+            remap.runs(),
+            [
+                RemapRun {
+                    from: 0,
+                    to: 0,
+                    len: 2
+                },
+                RemapRun {
+                    from: 3,
+                    to: 5,
+                    len: 2
+                },
+                RemapRun {
+                    from: 5,
+                    to: 2,
+                    len: 1
+                },
+            ]
+        );
+        assert_eq!(remap.get(1), Some(1));
+        assert_eq!(remap.get(2), None);
+        assert_eq!(remap.get(4), Some(6));
+        assert_eq!(remap.get(5), Some(2));
+        assert_eq!(remap.get(6), None);
+        assert_eq!(remap.mapped(), 5);
+    }
+
+    #[test]
+    fn test_remap_values() {
+        let old = data_set(&[
+            ("air", vec![]),
+            ("fence", vec![field("north", &["false", "true"])]),
+            ("repeater", vec![field("delay", &["1", "2", "3", "4"])]),
+            ("grass", vec![]),
+        ]);
+        let new = data_set(&[
+            ("air", vec![]),
+            ("granite", vec![]),
+            (
+                "fence",
+                vec![
+                    field("north", &["false", "true"]),
+                    field("waterlogged", &["false", "true"]),
+                ],
+            ),
+            ("repeater", vec![field("delay", &["1", "2"])]),
+        ]);
+        let remap = StateIdRemap::new(&old, &new, &[]);
+        assert_eq!(remap.get(0), Some(0));
+        // fence[north=true] gains waterlogged=false
+        assert_eq!(remap.get(2), Some(4));
+        // repeater[delay=4] is no more, so it takes the default delay=1
+        assert_eq!(remap.get(6), Some(6));
+        assert_eq!(remap.get(4), Some(7));
+        assert_eq!(remap.get(7), None);
+
+        let renamed = StateIdRemap::new(&old, &new, &[("grass".into(), "granite".into())]);
+        assert_eq!(renamed.get(7), Some(1));
+    }
+}
@@ -1,5 +1,5 @@
 use indexmap::IndexMap;
-use mcre_core::{BlockPos, OffsetType};
+use mcre_core::{BlockPos, FluidState, OffsetType};
 use serde::{Deserialize, Serialize};
 use std::io;
 use std::path::PathBuf;
@@ -36,9 +36,19 @@ pub struct BlockState {
     pub spawn_terrain_particles: bool,
     pub requires_correct_tool_for_drops: bool,
     pub destroy_speed: f32,
+    // Whether entities are stopped by it, `blocksMotion`.
+    pub blocks_motion: bool,
     pub offset_type: OffsetType,
     pub max_horizontal_offset: f32,
     pub max_vertical_offset: f32,
+    // The water or lava in the block, including waterlogging.
+    pub fluid_state: FluidState,
+    // The boxes of `getShape` and `getCollisionShape` in an empty level at
+    // the origin, without the random offset, as `[x0, y0, z0, x1, y1, z1]`.
+    pub outline_shape: Vec<[f64; 6]>,
+    pub collision_shape: Vec<[f64; 6]>,
+    // The boxes of `getOcclusionShape`, which light checks faces against.
+    pub occlusion_shape: Vec<[f64; 6]>,
     pub state_values: IndexMap<String, StateValue>,
 }
 
@@ -70,7 +80,8 @@ impl BlockState {
 
 #[cfg(test)]
 mod tests {
-    use jni::{JNIEnv, objects::JValueGen};
+    use jni::JNIEnv;
+    use mcje::{JBlock, JBlockPos, JBlockState, JavaClass};
     use mcre_core::BlockPos;
 
     use crate::state::{BlockState, OffsetType};
@@ -82,127 +93,41 @@ mod tests {
     }
 
     #[mcje::test]
-    async fn test_random_offset(env: &mut JNIEnv<'_>) {
+    async fn test_random_offset(env: &mut JNIEnv<'_>) -> mcje::Result<()> {
         let block_states = BlockState::all().await.unwrap();
-
-        let block_state_registry = env
-            .get_static_field(
-                "net/minecraft/world/level/block/Block",
-                "BLOCK_STATE_REGISTRY",
-                "Lnet/minecraft/core/IdMapper;",
-            )
-            .unwrap()
-            .l()
-            .unwrap();
-
-        let mut block_state = env
-            .call_method(
-                &block_state_registry,
-                "byId",
-                "(I)Ljava/lang/Object;",
-                &[JValueGen::Int(0)],
-            )
-            .unwrap()
-            .l()
-            .unwrap();
+        let block_state_registry = JBlock::state_registry(env)?;
 
         let mut block_state_id = 0u16;
 
-        while !block_state.is_null() {
-            let offset_function = env
-                .get_field(
-                    &block_state,
-                    "offsetFunction",
-                    "Lnet/minecraft/world/level/block/state/BlockBehaviour$OffsetFunction;",
-                )
-                .unwrap()
-                .l()
-                .unwrap();
-            if offset_function.is_null() {
-                assert_eq!(
+        while let Some(block_state) = block_state_registry.by_id(env, block_state_id.into())? {
+            let block_state = block_state.cast::<JBlockState>();
+            match block_state.offset_function(env)? {
+                None => assert_eq!(
                     block_states[block_state_id as usize].offset_type,
                     OffsetType::None
-                );
-            } else {
-                let block_pos_class = "net/minecraft/core/BlockPos";
-
-                for i in 0..10 {
-                    // BlockPos(i, i, i)
-                    let pos_obj = env
-                        .new_object(
-                            block_pos_class,
-                            "(III)V",
-                            &[JValueGen::Int(i), JValueGen::Int(i), JValueGen::Int(i)],
-                        )
-                        .unwrap();
-
-                    // Call offsetFunction.evaluate(state, pos)
-                    // Signature: (LBlockState;LBlockPos;)LVec3;
-                    let vec3_obj = env.call_method(
-                        &offset_function,
-                        "evaluate",
-                        "(Lnet/minecraft/world/level/block/state/BlockState;Lnet/minecraft/core/BlockPos;)Lnet/minecraft/world/phys/Vec3;",
-                        &[JValueGen::Object(&block_state), JValueGen::Object(&pos_obj)]
-                    ).unwrap().l().unwrap();
-
-                    let java_x = env.get_field(&vec3_obj, "x", "D").unwrap().d().unwrap();
-                    let java_y = env.get_field(&vec3_obj, "y", "D").unwrap().d().unwrap();
-                    let java_z = env.get_field(&vec3_obj, "z", "D").unwrap().d().unwrap();
-
-                    let java_value = (java_x, java_y, java_z);
-
-                    let block_state = &block_states[block_state_id as usize];
-
-                    let rust_value = block_state.random_offset(BlockPos::new(i, i, i));
-
-                    assert!(
-                        (rust_value.0 - java_value.0).abs() < 1e-6,
-                        "State: {:#?}\nBlockPos({i}, {i}, {i})\nOffset:\n  Rust: ({}, {}, {})\n  Java: ({}, {}, {})",
-                        block_state,
-                        rust_value.0,
-                        rust_value.1,
-                        rust_value.2,
-                        java_value.0,
-                        java_value.1,
-                        java_value.2
-                    );
-
-                    assert!(
-                        (rust_value.1 - java_value.1).abs() < 1e-6,
-                        "State: {:#?}\nBlockPos({i}, {i}, {i})\nOffset:\n  Rust: ({}, {}, {})\n  Java: ({}, {}, {})",
-                        block_state,
-                        rust_value.0,
-                        rust_value.1,
-                        rust_value.2,
-                        java_value.0,
-                        java_value.1,
-                        java_value.2
-                    );
-
-                    assert!(
-                        (rust_value.2 - java_value.2).abs() < 1e-6,
-                        "State: {:#?}\nBlockPos({i}, {i}, {i})\nOffset:\n  Rust: ({}, {}, {})\n  Java: ({}, {}, {})",
-                        block_state,
-                        rust_value.0,
-                        rust_value.1,
-                        rust_value.2,
-                        java_value.0,
-                        java_value.1,
-                        java_value.2
-                    );
+                ),
+                Some(offset_function) => {
+                    for i in 0..10 {
+                        let pos = JBlockPos::new(env, i, i, i)?;
+                        let offset = offset_function.evaluate(env, &block_state, &pos)?;
+                        let java_value = (offset.x(env)?, offset.y(env)?, offset.z(env)?);
+
+                        let block_state = &block_states[block_state_id as usize];
+
+                        let rust_value = block_state.random_offset(BlockPos::new(i, i, i));
+
+                        assert!(
+                            (rust_value.0 - java_value.0).abs() < 1e-6
+                                && (rust_value.1 - java_value.1).abs() < 1e-6
+                                && (rust_value.2 - java_value.2).abs() < 1e-6,
+                            "State: {:#?}\nBlockPos({i}, {i}, {i})\nOffset:\n  Rust: {rust_value:?}\n  Java: {java_value:?}",
+                            block_state,
+                        );
+                    }
                 }
             }
             block_state_id += 1;
-            block_state = env
-                .call_method(
-                    &block_state_registry,
-                    "byId",
-                    "(I)Ljava/lang/Object;",
-                    &[JValueGen::Int(block_state_id.into())],
-                )
-                .unwrap()
-                .l()
-                .unwrap();
         }
+        Ok(())
     }
 }
@@ -0,0 +1,74 @@
+//! The data of other versions of the game, kept under `versions/` so their
+//! state ids can be mapped onto those of the current one.
+//!
+//! Each version is a directory holding its `blocks.json` and
+//! `block_states.json` and a `version.json` like
+//! `{ "name": "1.21.11", "protocol": 774, "renames": { "chain": "iron_chain" } }`,
+//! where `renames` is optional and only needed for renames the diff does not
+//! find by itself.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{
+    diff::{DataSet, VersionDiff},
+    remap::StateIdRemap,
+};
+
+#[derive(Debug)]
+pub struct OtherVersion {
+    pub name: String,
+    pub protocol: i32,
+    /// Old and new names of blocks renamed since.
+    pub renames: Vec<(String, String)>,
+    pub data: DataSet,
+}
+
+#[derive(Deserialize)]
+struct VersionInfo {
+    name: String,
+    protocol: i32,
+    #[serde(default)]
+    renames: IndexMap<String, String>,
+}
+
+impl OtherVersion {
+    pub fn load(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        let info: VersionInfo =
+            serde_json::from_str(&fs::read_to_string(dir.join("version.json"))?)?;
+        Ok(Self {
+            name: info.name,
+            protocol: info.protocol,
+            renames: info.renames.into_iter().collect(),
+            data: DataSet::load(dir)?,
+        })
+    }
+
+    /// Every version under `versions/`, by protocol version.
+    pub fn all() -> io::Result<Vec<Self>> {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("versions");
+        if !root.try_exists()? {
+            return Ok(Vec::new());
+        }
+        let mut versions = Vec::new();
+        for entry in fs::read_dir(root)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                versions.push(Self::load(entry.path())?);
+            }
+        }
+        versions.sort_by_key(|version| version.protocol);
+        Ok(versions)
+    }
+
+    /// The remap of this version's state ids onto those of `current`.
+    pub fn remap(&self, current: &DataSet) -> StateIdRemap {
+        VersionDiff::new(&self.data, current, &self.renames).remap(&self.data, current)
+    }
+}
@@ -0,0 +1,616 @@
+//! NBT and JSON codecs, following vanilla's `ItemStack.CODEC` and the codecs
+//! of each component.
+//!
+//! As in `mcre_text`, decoding goes through an NBT [`Tag`] tree and encoding
+//! goes through `serde`, so booleans come out as `true`/`false` in JSON and
+//! as bytes in NBT.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::ops::RangeInclusive;
+use mcre_core::Rarity;
+use mcre_nbt::{Compound, IntArray, Tag};
+use mcre_text::{Component, HoverEvent};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, ser::SerializeMap};
+
+use crate::{
+    ComponentPatch, DataComponent, Enchantments, Error, Filterable, ItemStack, Profile,
+    ProfileProperty, Result, SkinPatch, WrittenBookContent, names, parse_identifier,
+};
+
+/// The most slots a container item holds.
+pub(crate) const MAX_CONTAINER_SLOTS: usize = 256;
+
+impl ItemStack {
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("item stacks always serialize to JSON")
+    }
+
+    pub fn from_nbt(tag: &Tag) -> Result<Self> {
+        parse_stack(tag)
+    }
+
+    pub fn to_nbt(&self) -> Tag {
+        mcre_nbt::to_tag(self).expect("item stacks always serialize to NBT")
+    }
+
+    /// The stack a `show_item` hover event shows, `None` for other events.
+    pub fn from_hover_event(event: &HoverEvent) -> Option<Result<Self>> {
+        let HoverEvent::ShowItem {
+            id,
+            count,
+            components,
+        } = event
+        else {
+            return None;
+        };
+        let components = match components {
+            Some(components) => parse_patch(components),
+            None => Ok(ComponentPatch::default()),
+        };
+        Some(components.and_then(|components| {
+            Ok(ItemStack {
+                item: parse_identifier(id)?,
+                count: *count,
+                components,
+            })
+        }))
+    }
+}
+
+impl DataComponent {
+    /// Reads the value of the component `name` from its NBT form. Components
+    /// without a model here are kept as [`DataComponent::Other`].
+    pub fn from_nbt(name: &str, tag: &Tag) -> Result<Self> {
+        let name = parse_identifier(name)?;
+        Ok(match name.as_str() {
+            names::CUSTOM_NAME => Self::CustomName(Component::from_nbt(tag)?),
+            names::ITEM_NAME => Self::ItemName(Component::from_nbt(tag)?),
+            names::LORE => Self::Lore(
+                list(tag, "lore")?
+                    .iter()
+                    .map(Component::from_nbt)
+                    .collect::<mcre_text::Result<_>>()?,
+            ),
+            names::DAMAGE => Self::Damage(int(tag, "damage", 0..=i32::MAX)?),
+            names::MAX_DAMAGE => Self::MaxDamage(int(tag, "max damage", 1..=i32::MAX)?),
+            names::MAX_STACK_SIZE => Self::MaxStackSize(int(tag, "max stack size", 1..=99)?),
+            names::REPAIR_COST => Self::RepairCost(int(tag, "repair cost", 0..=i32::MAX)?),
+            names::UNBREAKABLE => Self::Unbreakable,
+            names::ENCHANTMENTS => Self::Enchantments(parse_enchantments(tag)?),
+            names::STORED_ENCHANTMENTS => Self::StoredEnchantments(parse_enchantments(tag)?),
+            names::DYED_COLOR => Self::DyedColor(parse_color(tag)?),
+            names::RARITY => Self::Rarity(
+                mcre_nbt::from_tag::<Rarity>(tag).map_err(|_| invalid("unknown rarity"))?,
+            ),
+            names::ENCHANTMENT_GLINT_OVERRIDE => Self::EnchantmentGlintOverride(
+                tag.as_bool()
+                    .ok_or_else(|| invalid("glint override must be a boolean"))?,
+            ),
+            names::PROFILE => Self::Profile(parse_profile(tag)?),
+            names::CONTAINER => Self::Container(parse_container(tag)?),
+            names::WRITTEN_BOOK_CONTENT => Self::WrittenBookContent(parse_book(tag)?),
+            _ => Self::Other(name, tag.clone()),
+        })
+    }
+
+    pub fn to_nbt(&self) -> Tag {
+        mcre_nbt::to_tag(self).expect("data components always serialize to NBT")
+    }
+}
+
+pub(crate) fn invalid(message: impl Into<String>) -> Error {
+    Error::Invalid(message.into())
+}
+
+fn compound<'a>(tag: &'a Tag, what: &str) -> Result<&'a Compound> {
+    tag.as_compound()
+        .ok_or_else(|| invalid(format!("{what} must be a compound")))
+}
+
+fn list<'a>(tag: &'a Tag, what: &str) -> Result<&'a [Tag]> {
+    tag.as_list()
+        .ok_or_else(|| invalid(format!("{what} must be a list")))
+}
+
+fn string<'a>(tag: &'a Tag, what: &str) -> Result<&'a str> {
+    tag.as_str()
+        .ok_or_else(|| invalid(format!("{what} must be a string")))
+}
+
+fn int(tag: &Tag, what: &str, range: RangeInclusive<i32>) -> Result<i32> {
+    tag.as_i64()
+        .and_then(|value| i32::try_from(value).ok())
+        .filter(|value| range.contains(value))
+        .ok_or_else(|| {
+            invalid(format!(
+                "{what} must be an integer from {} to {}",
+                range.start(),
+                range.end()
+            ))
+        })
+}
+
+fn require<'a>(compound: &'a Compound, key: &str) -> Result<&'a Tag> {
+    compound
+        .get(key)
+        .ok_or_else(|| invalid(format!("missing `{key}`")))
+}
+
+fn parse_stack(tag: &Tag) -> Result<ItemStack> {
+    let stack = compound(tag, "an item stack")?;
+    let item = string(require(stack, "id")?, "`id`")?;
+    let count = match stack.get("count") {
+        Some(count) => int(count, "count", 1..=99)?,
+        None => 1,
+    };
+    let components = match stack.get("components") {
+        Some(components) => parse_patch(components)?,
+        None => ComponentPatch::default(),
+    };
+    Ok(ItemStack {
+        item: parse_identifier(item)?,
+        count,
+        components,
+    })
+}
+
+/// Removed components are listed with a `!` before their name.
+fn parse_patch(tag: &Tag) -> Result<ComponentPatch> {
+    let mut patch = ComponentPatch::new();
+    for (key, value) in compound(tag, "components")? {
+        match key.strip_prefix('!') {
+            Some(name) => patch.remove(&parse_identifier(name)?),
+            None => patch.set(DataComponent::from_nbt(key, value)?),
+        }
+    }
+    Ok(patch)
+}
+
+fn parse_enchantments(tag: &Tag) -> Result<Enchantments> {
+    let levels = compound(tag, "enchantments")?
+        .iter()
+        .map(|(name, level)| Ok((parse_identifier(name)?, int(level, "level", 1..=255)?)))
+        .collect::<Result<_>>()?;
+    Ok(Enchantments { levels })
+}
+
+/// An RGB int, or a list of three floats from 0 to 1.
+fn parse_color(tag: &Tag) -> Result<u32> {
+    if let Some(color) = tag.as_i64() {
+        return Ok(color as u32);
+    }
+    match tag.as_list().map(|list| {
+        list.iter()
+            .map(|channel| channel.as_f64().map(|c| (c.clamp(0.0, 1.0) * 255.0) as u32))
+            .collect::<Option<Vec<_>>>()
+    }) {
+        Some(Some(rgb)) if rgb.len() == 3 => Ok(rgb[0] << 16 | rgb[1] << 8 | rgb[2]),
+        _ => Err(invalid("a color must be an int or three floats")),
+    }
+}
+
+fn parse_uuid(tag: &Tag) -> Result<[i32; 4]> {
+    let uuid = match tag {
+        Tag::IntArray(ints) => ints.as_slice().try_into().ok(),
+        Tag::List(list) => list
+            .iter()
+            .map(|tag| tag.as_i64().map(|v| v as i32))
+            .collect::<Option<Vec<_>>>()
+            .and_then(|ints| ints.try_into().ok()),
+        _ => None,
+    };
+    uuid.ok_or_else(|| invalid("a uuid must be four ints"))
+}
+
+/// A bare name, or a compound with any of `name`, `id` and `properties` and
+/// the skin patch's `texture`, `cape`, `elytra` and `model`.
+fn parse_profile(tag: &Tag) -> Result<Profile> {
+    if let Tag::String(name) = tag {
+        return Ok(Profile {
+            name: Some(name.clone()),
+            ..Profile::default()
+        });
+    }
+    let profile = compound(tag, "a profile")?;
+    let properties = match profile.get("properties") {
+        Some(properties) => list(properties, "properties")?
+            .iter()
+            .map(|property| {
+                let property = compound(property, "a property")?;
+                Ok(ProfileProperty {
+                    name: string(require(property, "name")?, "`name`")?.into(),
+                    value: string(require(property, "value")?, "`value`")?.into(),
+                    signature: property
+                        .get("signature")
+                        .map(|signature| string(signature, "`signature`").map(String::from))
+                        .transpose()?,
+                })
+            })
+            .collect::<Result<_>>()?,
+        None => Vec::new(),
+    };
+    Ok(Profile {
+        name: profile
+            .get("name")
+            .map(|name| string(name, "`name`").map(String::from))
+            .transpose()?,
+        id: profile.get("id").map(parse_uuid).transpose()?,
+        properties,
+        skin: SkinPatch {
+            texture: optional_identifier(profile, "texture")?,
+            cape: optional_identifier(profile, "cape")?,
+            elytra: optional_identifier(profile, "elytra")?,
+            slim: match profile.get("model").map(|model| string(model, "`model`")) {
+                Some(Ok("slim")) => Some(true),
+                Some(Ok("wide")) => Some(false),
+                Some(Ok(_)) => return Err(invalid("unknown player model")),
+                Some(Err(error)) => return Err(error),
+                None => None,
+            },
+        },
+    })
+}
+
+fn optional_identifier(compound: &Compound, key: &str) -> Result<Option<String>> {
+    compound
+        .get(key)
+        .map(|value| parse_identifier(string(value, key)?))
+        .transpose()
+}
+
+/// Non-empty slots as `{slot, item}` compounds.
+fn parse_container(tag: &Tag) -> Result<Vec<Option<ItemStack>>> {
+    let mut slots: Vec<Option<ItemStack>> = Vec::new();
+    for entry in list(tag, "container")? {
+        let entry = compound(entry, "a container slot")?;
+        let slot = int(
+            require(entry, "slot")?,
+            "slot",
+            0..=MAX_CONTAINER_SLOTS as i32 - 1,
+        )? as usize;
+        if slots.len() <= slot {
+            slots.resize(slot + 1, None);
+        }
+        slots[slot] = Some(parse_stack(require(entry, "item")?)?);
+    }
+    Ok(slots)
+}
+
+/// Either a bare value or `{raw, filtered}`.
+fn parse_filterable<T>(tag: &Tag, parse: impl Fn(&Tag) -> Result<T>) -> Result<Filterable<T>> {
+    match tag {
+        Tag::Compound(compound) if compound.contains_key("raw") => Ok(Filterable {
+            raw: parse(&compound["raw"])?,
+            filtered: compound.get("filtered").map(&parse).transpose()?,
+        }),
+        tag => Ok(Filterable::new(parse(tag)?)),
+    }
+}
+
+fn parse_book(tag: &Tag) -> Result<WrittenBookContent> {
+    let book = compound(tag, "book content")?;
+    let title = parse_filterable(require(book, "title")?, |title| {
+        Ok(string(title, "title")?.to_string())
+    })?;
+    let pages = match book.get("pages") {
+        Some(pages) => list(pages, "pages")?
+            .iter()
+            .map(|page| parse_filterable(page, |page| Ok(Component::from_nbt(page)?)))
+            .collect::<Result<_>>()?,
+        None => Vec::new(),
+    };
+    Ok(WrittenBookContent {
+        title,
+        author: string(require(book, "author")?, "`author`")?.into(),
+        generation: match book.get("generation") {
+            Some(generation) => int(generation, "generation", 0..=3)?,
+            None => 0,
+        },
+        pages,
+        resolved: book.get("resolved").and_then(Tag::as_bool).unwrap_or(false),
+    })
+}
+
+impl<'de> Deserialize<'de> for ItemStack {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+        let tag = Tag::deserialize(deserializer)?;
+        parse_stack(&tag).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for ItemStack {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("id", &self.item)?;
+        map.serialize_entry("count", &self.count)?;
+        if !self.components.is_empty() {
+            map.serialize_entry("components", &self.components)?;
+        }
+        map.end()
+    }
+}
+
+/// The `{}` written for unit values and removed components.
+struct Empty;
+
+impl Serialize for Empty {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        serializer.serialize_map(Some(0))?.end()
+    }
+}
+
+impl Serialize for ComponentPatch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.added().len() + self.removed().len()))?;
+        for component in self.added() {
+            map.serialize_entry(component.name(), component)?;
+        }
+        for name in self.removed() {
+            map.serialize_entry(&format!("!{name}"), &Empty)?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for DataComponent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        match self {
+            Self::CustomName(name) | Self::ItemName(name) => name.serialize(serializer),
+            Self::Lore(lines) => lines.serialize(serializer),
+            Self::Damage(value)
+            | Self::MaxDamage(value)
+            | Self::MaxStackSize(value)
+            | Self::RepairCost(value) => value.serialize(serializer),
+            Self::Unbreakable => Empty.serialize(serializer),
+            Self::Enchantments(enchantments) | Self::StoredEnchantments(enchantments) => {
+                let mut map = serializer.serialize_map(Some(enchantments.levels.len()))?;
+                for (name, level) in &enchantments.levels {
+                    map.serialize_entry(name, level)?;
+                }
+                map.end()
+            }
+            Self::DyedColor(color) => (*color as i32).serialize(serializer),
+            Self::Rarity(rarity) => rarity.serialize(serializer),
+            Self::EnchantmentGlintOverride(glint) => glint.serialize(serializer),
+            Self::Profile(profile) => profile.serialize(serializer),
+            Self::Container(slots) => {
+                let slots: Vec<_> = slots
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(slot, stack)| Some(ContainerSlot(slot as i32, stack.as_ref()?)))
+                    .collect();
+                slots.serialize(serializer)
+            }
+            Self::WrittenBookContent(book) => book.serialize(serializer),
+            Self::Other(_, tag) => tag.serialize(serializer),
+        }
+    }
+}
+
+struct ContainerSlot<'a>(i32, &'a ItemStack);
+
+impl Serialize for ContainerSlot<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("slot", &self.0)?;
+        map.serialize_entry("item", self.1)?;
+        map.end()
+    }
+}
+
+impl Serialize for Profile {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        if let (Some(name), None, true, true) = (
+            &self.name,
+            self.id,
+            self.properties.is_empty(),
+            self.skin.is_empty(),
+        ) {
+            return serializer.serialize_str(name);
+        }
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(name) = &self.name {
+            map.serialize_entry("name", name)?;
+        }
+        if let Some(id) = self.id {
+            map.serialize_entry("id", &IntArray(vec![id[0], id[1], id[2], id[3]]))?;
+        }
+        if !self.properties.is_empty() {
+            map.serialize_entry("properties", &self.properties)?;
+        }
+        let textures = [
+            ("texture", &self.skin.texture),
+            ("cape", &self.skin.cape),
+            ("elytra", &self.skin.elytra),
+        ];
+        for (key, texture) in textures {
+            if let Some(texture) = texture {
+                map.serialize_entry(key, texture)?;
+            }
+        }
+        if let Some(slim) = self.skin.slim {
+            map.serialize_entry("model", if slim { "slim" } else { "wide" })?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for ProfileProperty {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("name", &self.name)?;
+        map.serialize_entry("value", &self.value)?;
+        if let Some(signature) = &self.signature {
+            map.serialize_entry("signature", signature)?;
+        }
+        map.end()
+    }
+}
+
+impl<T: Serialize> Serialize for Filterable<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("raw", &self.raw)?;
+        if let Some(filtered) = &self.filtered {
+            map.serialize_entry("filtered", filtered)?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for WrittenBookContent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("title", &self.title)?;
+        map.serialize_entry("author", &self.author)?;
+        if self.generation != 0 {
+            map.serialize_entry("generation", &self.generation)?;
+        }
+        if !self.pages.is_empty() {
+            map.serialize_entry("pages", &self.pages)?;
+        }
+        if self.resolved {
+            map.serialize_entry("resolved", &true)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nbt_round_trip() {
+        let snbt = r#"{components:{"!minecraft:food":{},"minecraft:custom_name":"Excalibur","minecraft:damage":5,"minecraft:enchantments":{"minecraft:sharpness":5},"minecraft:unbreakable":{},"minecraft:weapon":{}},count:1,id:"minecraft:diamond_sword"}"#;
+        let tag = mcre_nbt::snbt::parse(snbt).unwrap();
+        let stack = ItemStack::from_nbt(&tag).unwrap();
+        assert_eq!(stack.item, "minecraft:diamond_sword");
+        assert_eq!(
+            stack.components.get(names::DAMAGE),
+            Some(&DataComponent::Damage(5))
+        );
+        let Some(DataComponent::Enchantments(enchantments)) =
+            stack.components.get(names::ENCHANTMENTS)
+        else {
+            panic!("missing enchantments");
+        };
+        assert_eq!(enchantments.level("minecraft:sharpness"), Some(5));
+        assert_eq!(
+            stack.components.get("minecraft:weapon"),
+            Some(&DataComponent::Other(
+                "minecraft:weapon".into(),
+                Tag::Compound(Compound::new())
+            ))
+        );
+        assert!(stack.components.is_removed("minecraft:food"));
+        assert_eq!(mcre_nbt::to_snbt(&stack.to_nbt()), snbt);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let json = r#"{"id":"minecraft:player_head","count":2,"components":{"minecraft:profile":{"name":"Steve","id":[1,2,3,4],"texture":"minecraft:entity/player/slim/alex","model":"slim"},"minecraft:lore":["a",{"text":"b","color":"red"}],"minecraft:enchantment_glint_override":true,"minecraft:rarity":"epic","minecraft:dyed_color":16711680}}"#;
+        let stack = ItemStack::from_json(json).unwrap();
+        assert_eq!(stack.count, 2);
+        assert_eq!(
+            stack.components.get(names::PROFILE),
+            Some(&DataComponent::Profile(Profile {
+                name: Some("Steve".into()),
+                id: Some([1, 2, 3, 4]),
+                properties: Vec::new(),
+                skin: SkinPatch {
+                    texture: Some("minecraft:entity/player/slim/alex".into()),
+                    slim: Some(true),
+                    ..SkinPatch::default()
+                },
+            }))
+        );
+        assert_eq!(
+            stack.components.get(names::RARITY),
+            Some(&DataComponent::Rarity(Rarity::Epic))
+        );
+        assert_eq!(stack.to_json(), json);
+        assert_eq!(ItemStack::from_nbt(&stack.to_nbt()).unwrap(), stack);
+    }
+
+    #[test]
+    fn test_containers_and_books() {
+        let book = ItemStack::new("written_book", 1).with(DataComponent::WrittenBookContent(
+            WrittenBookContent {
+                title: Filterable::new("Notes".into()),
+                author: "Alex".into(),
+                generation: 1,
+                pages: vec![Filterable {
+                    raw: Component::text("damn"),
+                    filtered: Some(Component::text("****")),
+                }],
+                resolved: true,
+            },
+        ));
+        let shulker = ItemStack::new("shulker_box", 1)
+            .with(DataComponent::Container(vec![None, Some(book.clone())]));
+        let tag = shulker.to_nbt();
+        let slots = tag.as_compound().unwrap()["components"]
+            .as_compound()
+            .unwrap()[names::CONTAINER]
+            .as_list()
+            .unwrap();
+        assert_eq!(slots.len(), 1);
+        assert_eq!(slots[0].as_compound().unwrap().get_int("slot"), Some(1));
+        assert_eq!(ItemStack::from_nbt(&tag).unwrap(), shulker);
+
+        // A bare title is read like `{raw: title}`.
+        let content = mcre_nbt::snbt::parse(r#"{title:"Notes",author:"Alex"}"#).unwrap();
+        let Ok(DataComponent::WrittenBookContent(content)) =
+            DataComponent::from_nbt("written_book_content", &content)
+        else {
+            panic!("invalid book content");
+        };
+        assert_eq!(content.title, Filterable::new("Notes".into()));
+        assert!(content.pages.is_empty());
+    }
+
+    #[test]
+    fn test_hover_event() {
+        let component = Component::from_json(
+            r#"{"text":"a","hover_event":{"action":"show_item","id":"diamond","count":3,"components":{"minecraft:rarity":"rare"}}}"#,
+        )
+        .unwrap();
+        let event = component.style.hover_event.as_deref().unwrap();
+        let stack = ItemStack::from_hover_event(event).unwrap().unwrap();
+        assert_eq!(
+            stack,
+            ItemStack::new("diamond", 3).with(DataComponent::Rarity(Rarity::Rare))
+        );
+        assert!(ItemStack::from_hover_event(&HoverEvent::ShowText("a".into())).is_none());
+    }
+
+    #[test]
+    fn test_invalid_stacks() {
+        for snbt in [
+            r#"{count:1}"#,
+            r#"{id:"stone",count:0}"#,
+            r#"{id:"stone",components:{"minecraft:damage":-1}}"#,
+            r#"{id:"stone",components:{"minecraft:max_stack_size":100}}"#,
+            r#"{id:"Stone"}"#,
+            r#"{id:"minecraft:stone:slab"}"#,
+            r#"{id:"stone",components:{"minecraft:Damage":1}}"#,
+            r#"{id:"stone",components:{"!food ":{}}}"#,
+            r#"{id:"stone",components:{"minecraft:enchantments":{"sharp ness":1}}}"#,
+        ] {
+            let tag = mcre_nbt::snbt::parse(snbt).unwrap();
+            assert!(ItemStack::from_nbt(&tag).is_err(), "{snbt}");
+        }
+        let tag = mcre_nbt::snbt::parse(r#"{id:":stone"}"#).unwrap();
+        assert_eq!(ItemStack::from_nbt(&tag).unwrap().item, "minecraft:stone");
+    }
+}
@@ -0,0 +1,158 @@
+use alloc::{string::String, vec::Vec};
+use mcre_core::Rarity;
+use mcre_nbt::Tag;
+use mcre_text::Component;
+
+use crate::ItemStack;
+
+/// The registry names of the components modelled by [`DataComponent`].
+pub mod names {
+    pub const CUSTOM_NAME: &str = "minecraft:custom_name";
+    pub const ITEM_NAME: &str = "minecraft:item_name";
+    pub const LORE: &str = "minecraft:lore";
+    pub const DAMAGE: &str = "minecraft:damage";
+    pub const MAX_DAMAGE: &str = "minecraft:max_damage";
+    pub const MAX_STACK_SIZE: &str = "minecraft:max_stack_size";
+    pub const REPAIR_COST: &str = "minecraft:repair_cost";
+    pub const UNBREAKABLE: &str = "minecraft:unbreakable";
+    pub const ENCHANTMENTS: &str = "minecraft:enchantments";
+    pub const STORED_ENCHANTMENTS: &str = "minecraft:stored_enchantments";
+    pub const DYED_COLOR: &str = "minecraft:dyed_color";
+    pub const RARITY: &str = "minecraft:rarity";
+    pub const ENCHANTMENT_GLINT_OVERRIDE: &str = "minecraft:enchantment_glint_override";
+    pub const PROFILE: &str = "minecraft:profile";
+    pub const CONTAINER: &str = "minecraft:container";
+    pub const WRITTEN_BOOK_CONTENT: &str = "minecraft:written_book_content";
+}
+
+/// The value of one data component.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataComponent {
+    CustomName(Component),
+    ItemName(Component),
+    Lore(Vec<Component>),
+    Damage(i32),
+    MaxDamage(i32),
+    MaxStackSize(i32),
+    /// Extra experience levels anvils charge for working the stack.
+    RepairCost(i32),
+    Unbreakable,
+    Enchantments(Enchantments),
+    /// The enchantments an enchanted book applies.
+    StoredEnchantments(Enchantments),
+    /// `0xRRGGBB`.
+    DyedColor(u32),
+    Rarity(Rarity),
+    EnchantmentGlintOverride(bool),
+    /// The player a head shows.
+    Profile(Profile),
+    /// The slots of a shulker box or other container item, `None` for empty
+    /// ones.
+    Container(Vec<Option<ItemStack>>),
+    WrittenBookContent(WrittenBookContent),
+    /// Any other component, by name, with its NBT value.
+    Other(String, Tag),
+}
+
+impl DataComponent {
+    /// The component's namespaced registry name.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::CustomName(_) => names::CUSTOM_NAME,
+            Self::ItemName(_) => names::ITEM_NAME,
+            Self::Lore(_) => names::LORE,
+            Self::Damage(_) => names::DAMAGE,
+            Self::MaxDamage(_) => names::MAX_DAMAGE,
+            Self::MaxStackSize(_) => names::MAX_STACK_SIZE,
+            Self::RepairCost(_) => names::REPAIR_COST,
+            Self::Unbreakable => names::UNBREAKABLE,
+            Self::Enchantments(_) => names::ENCHANTMENTS,
+            Self::StoredEnchantments(_) => names::STORED_ENCHANTMENTS,
+            Self::DyedColor(_) => names::DYED_COLOR,
+            Self::Rarity(_) => names::RARITY,
+            Self::EnchantmentGlintOverride(_) => names::ENCHANTMENT_GLINT_OVERRIDE,
+            Self::Profile(_) => names::PROFILE,
+            Self::Container(_) => names::CONTAINER,
+            Self::WrittenBookContent(_) => names::WRITTEN_BOOK_CONTENT,
+            Self::Other(name, _) => name,
+        }
+    }
+}
+
+/// Enchantment levels by enchantment name, vanilla's `ItemEnchantments`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Enchantments {
+    pub levels: Vec<(String, i32)>,
+}
+
+impl Enchantments {
+    pub fn level(&self, enchantment: &str) -> Option<i32> {
+        self.levels
+            .iter()
+            .find(|(name, _)| name == enchantment)
+            .map(|(_, level)| *level)
+    }
+}
+
+/// A game profile that may still need resolving, vanilla's
+/// `ResolvableProfile`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    pub name: Option<String>,
+    pub id: Option<[i32; 4]>,
+    pub properties: Vec<ProfileProperty>,
+    pub skin: SkinPatch,
+}
+
+/// A signed profile property, such as the `textures` of a skin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileProperty {
+    pub name: String,
+    pub value: String,
+    pub signature: Option<String>,
+}
+
+/// Textures and a model shown instead of those of the profile's skin,
+/// vanilla's `PlayerSkin.Patch`. Textures are resource locations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SkinPatch {
+    pub texture: Option<String>,
+    pub cape: Option<String>,
+    pub elytra: Option<String>,
+    /// Whether the slim arm model is used, if overridden.
+    pub slim: Option<bool>,
+}
+
+impl SkinPatch {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A value with an optional version for players who filter text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filterable<T> {
+    pub raw: T,
+    pub filtered: Option<T>,
+}
+
+impl<T> Filterable<T> {
+    pub fn new(raw: T) -> Self {
+        Self {
+            raw,
+            filtered: None,
+        }
+    }
+}
+
+/// The contents of a signed book.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WrittenBookContent {
+    pub title: Filterable<String>,
+    pub author: String,
+    /// 0 for originals, up to 3 for copies of copies.
+    pub generation: i32,
+    pub pages: Vec<Filterable<Component>>,
+    /// Whether selectors and scores in the pages have been resolved.
+    pub resolved: bool,
+}
@@ -0,0 +1,45 @@
+use core::fmt::{self, Display};
+
+use alloc::string::String;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Well-formed input that does not describe an item stack.
+    Invalid(String),
+    Json(serde_json::Error),
+    Nbt(mcre_nbt::Error),
+    Text(mcre_text::Error),
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(message) => write!(f, "invalid item stack: {message}"),
+            Self::Json(err) => write!(f, "invalid JSON: {err}"),
+            Self::Nbt(err) => write!(f, "invalid NBT: {err}"),
+            Self::Text(err) => err.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<mcre_nbt::Error> for Error {
+    fn from(err: mcre_nbt::Error) -> Self {
+        Self::Nbt(err)
+    }
+}
+
+impl From<mcre_text::Error> for Error {
+    fn from(err: mcre_text::Error) -> Self {
+        Self::Text(err)
+    }
+}
@@ -0,0 +1,161 @@
+//! The item argument of commands such as `/give`, e.g.
+//! `minecraft:diamond_sword[damage=5,!food]`, following vanilla's
+//! `ItemParser`. Component values are SNBT.
+
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{ComponentPatch, DataComponent, ItemStack, Result, codec::invalid, parse_identifier};
+
+impl ItemStack {
+    /// Parses an item argument into a stack of one; the count is a separate
+    /// argument of `/give`.
+    pub fn from_give_syntax(input: &str) -> Result<Self> {
+        let input = input.trim();
+        let (item, mut rest) = split_name(input);
+        if item.is_empty() {
+            return Err(invalid("expected an item name"));
+        }
+        let mut components = ComponentPatch::new();
+        if let Some(list) = rest.strip_prefix('[') {
+            rest = parse_components(list, &mut components)?;
+        }
+        if !rest.trim().is_empty() {
+            return Err(invalid(format!("unexpected `{rest}` after the item")));
+        }
+        Ok(Self {
+            item: parse_identifier(item)?,
+            count: 1,
+            components,
+        })
+    }
+
+    /// The stack as an item argument, leaving out the count.
+    pub fn to_give_syntax(&self) -> String {
+        if self.components.is_empty() {
+            return self.item.clone();
+        }
+        let added = self.components.added().iter().map(|component| {
+            format!(
+                "{}={}",
+                component.name(),
+                mcre_nbt::to_snbt(&component.to_nbt())
+            )
+        });
+        let removed = self
+            .components
+            .removed()
+            .iter()
+            .map(|name| format!("!{name}"));
+        let components: Vec<String> = added.chain(removed).collect();
+        format!("{}[{}]", self.item, components.join(","))
+    }
+}
+
+/// Splits a resource location off the start of `input`.
+fn split_name(input: &str) -> (&str, &str) {
+    let end = input
+        .find(|c: char| {
+            !(c.is_ascii_lowercase()
+                || c.is_ascii_digit()
+                || matches!(c, '_' | '-' | '.' | ':' | '/'))
+        })
+        .unwrap_or(input.len());
+    input.split_at(end)
+}
+
+/// Parses the components up to the closing `]`, returning what follows it.
+fn parse_components<'a>(mut input: &'a str, patch: &mut ComponentPatch) -> Result<&'a str> {
+    input = input.trim_start();
+    if let Some(rest) = input.strip_prefix(']') {
+        return Ok(rest);
+    }
+    loop {
+        let removed = input.starts_with('!');
+        let (name, rest) = split_name(input.trim_start_matches('!').trim_start());
+        if name.is_empty() {
+            return Err(invalid("expected a component name"));
+        }
+        let name = parse_identifier(name)?;
+        if patch.get(&name).is_some() || patch.is_removed(&name) {
+            return Err(invalid(format!("component `{name}` given twice")));
+        }
+        input = rest.trim_start();
+        if removed {
+            patch.remove(&name);
+        } else {
+            input = input
+                .strip_prefix('=')
+                .ok_or_else(|| invalid(format!("expected `=` after `{name}`")))?;
+            let (value, len) = mcre_nbt::snbt::parse_prefix(input)?;
+            patch.set(DataComponent::from_nbt(&name, &value)?);
+            input = &input[len..];
+        }
+        input = input.trim_start();
+        if let Some(rest) = input.strip_prefix(',') {
+            input = rest.trim_start();
+        } else if let Some(rest) = input.strip_prefix(']') {
+            return Ok(rest);
+        } else {
+            return Err(invalid("expected `,` or `]`"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_text::Component;
+
+    use super::*;
+    use crate::names;
+
+    #[test]
+    fn test_give_syntax() {
+        let stack = ItemStack::from_give_syntax(
+            "diamond_sword[damage=5, custom_name={text:\"Sword, [1]\",color:\"gold\"}, !food]",
+        )
+        .unwrap();
+        assert_eq!(stack.item, "minecraft:diamond_sword");
+        assert_eq!(stack.count, 1);
+        assert_eq!(
+            stack.components.get(names::DAMAGE),
+            Some(&DataComponent::Damage(5))
+        );
+        let Some(DataComponent::CustomName(name)) = stack.components.get(names::CUSTOM_NAME) else {
+            panic!("missing custom name");
+        };
+        assert_eq!(name.to_plain_string(&()), "Sword, [1]");
+        assert!(stack.components.is_removed("minecraft:food"));
+        assert_eq!(
+            stack.to_give_syntax(),
+            "minecraft:diamond_sword[minecraft:damage=5,minecraft:custom_name={color:\"gold\",text:\"Sword, [1]\"},!minecraft:food]"
+        );
+        assert_eq!(
+            ItemStack::from_give_syntax(&stack.to_give_syntax()).unwrap(),
+            stack
+        );
+
+        let plain = ItemStack::new("stone", 1);
+        assert_eq!(plain.to_give_syntax(), "minecraft:stone");
+        assert_eq!(ItemStack::from_give_syntax("stone[]").unwrap(), plain);
+        let named = plain.with(DataComponent::ItemName(Component::text("Rock")));
+        assert_eq!(
+            named.to_give_syntax(),
+            "minecraft:stone[minecraft:item_name=\"Rock\"]"
+        );
+    }
+
+    #[test]
+    fn test_invalid_give_syntax() {
+        for input in [
+            "",
+            "[damage=1]",
+            "stone[damage=1",
+            "stone[damage]",
+            "stone[damage=1,damage=2]",
+            "stone[damage=1] extra",
+            "stone[damage=\"five\"]",
+        ] {
+            assert!(ItemStack::from_give_syntax(input).is_err(), "{input}");
+        }
+    }
+}
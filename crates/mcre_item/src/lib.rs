@@ -0,0 +1,56 @@
+//! Item stacks and the data components they carry, as saved in worlds,
+//! shown in JSON and written in commands like
+//! `/give @s minecraft:diamond_sword[damage=5]`.
+//!
+//! Names are namespaced identifiers throughout. Parsing checks their syntax
+//! only; [`ItemStack::check_names`] looks them up in the registries. The
+//! protocol codec, which works with registry ids, lives in `mcre_protocol`.
+
+#![no_std]
+
+extern crate alloc;
+
+mod codec;
+mod component;
+mod error;
+mod give;
+mod stack;
+
+use alloc::{format, string::String};
+
+use crate::codec::invalid;
+
+pub use component::{
+    DataComponent, Enchantments, Filterable, Profile, ProfileProperty, SkinPatch,
+    WrittenBookContent, names,
+};
+pub use error::{Error, Result};
+pub use stack::{ComponentPatch, ItemStack};
+
+/// `name` with the `minecraft:` namespace added if it has none, as
+/// `ResourceLocation.parse` reads it.
+pub fn identifier(name: &str) -> String {
+    let (namespace, path) = split_identifier(name);
+    format!("{namespace}:{path}")
+}
+
+/// Like [`identifier`], but fails on names `ResourceLocation` rejects: the
+/// namespace may only hold `a-z0-9_.-`, and the path `/` as well.
+pub fn parse_identifier(name: &str) -> Result<String> {
+    let (namespace, path) = split_identifier(name);
+    let allowed = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || "_.-".contains(c);
+    if namespace.chars().all(allowed) && path.chars().all(|c| allowed(c) || c == '/') {
+        Ok(format!("{namespace}:{path}"))
+    } else {
+        Err(invalid(format!("invalid resource location `{name}`")))
+    }
+}
+
+/// An empty namespace is `minecraft` too.
+fn split_identifier(name: &str) -> (&str, &str) {
+    match name.split_once(':') {
+        Some(("", path)) => ("minecraft", path),
+        Some(split) => split,
+        None => ("minecraft", name),
+    }
+}
@@ -0,0 +1,143 @@
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{DataComponent, Result, codec::invalid, identifier};
+
+/// A non-empty stack of items, vanilla's `ItemStack`. Empty stacks are
+/// `None` wherever they can appear.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemStack {
+    /// The item's namespaced name, e.g. `minecraft:diamond_sword`.
+    pub item: String,
+    pub count: i32,
+    /// How the stack's components differ from the item's defaults.
+    pub components: ComponentPatch,
+}
+
+impl ItemStack {
+    pub fn new(item: &str, count: i32) -> Self {
+        Self {
+            item: identifier(item),
+            count,
+            components: ComponentPatch::default(),
+        }
+    }
+
+    pub fn with(mut self, component: DataComponent) -> Self {
+        self.components.set(component);
+        self
+    }
+
+    /// Checks the item and component names against the item and data
+    /// component type registries, here and in the stacks of containers.
+    /// Parsing only checks that names are valid resource locations.
+    pub fn check_names(
+        &self,
+        is_item: &dyn Fn(&str) -> bool,
+        is_component: &dyn Fn(&str) -> bool,
+    ) -> Result<()> {
+        if !is_item(&self.item) {
+            return Err(invalid(format!("unknown item `{}`", self.item)));
+        }
+        let added = self.components.added.iter().map(DataComponent::name);
+        let removed = self.components.removed.iter().map(String::as_str);
+        if let Some(name) = added.chain(removed).find(|name| !is_component(name)) {
+            return Err(invalid(format!("unknown data component `{name}`")));
+        }
+        for component in &self.components.added {
+            if let DataComponent::Container(slots) = component {
+                for stack in slots.iter().flatten() {
+                    stack.check_names(is_item, is_component)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Components added to or removed from an item's defaults, vanilla's
+/// `DataComponentPatch`. A component is never both.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ComponentPatch {
+    added: Vec<DataComponent>,
+    removed: Vec<String>,
+}
+
+impl ComponentPatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&DataComponent> {
+        self.added.iter().find(|component| component.name() == name)
+    }
+
+    /// Adds `component`, replacing any value or removal of it.
+    pub fn set(&mut self, component: DataComponent) {
+        self.removed.retain(|removed| removed != component.name());
+        match self
+            .added
+            .iter_mut()
+            .find(|added| added.name() == component.name())
+        {
+            Some(added) => *added = component,
+            None => self.added.push(component),
+        }
+    }
+
+    /// Removes the item's default value of the component `name`.
+    pub fn remove(&mut self, name: &str) {
+        self.added.retain(|added| added.name() != name);
+        if !self.is_removed(name) {
+            self.removed.push(name.into());
+        }
+    }
+
+    pub fn is_removed(&self, name: &str) -> bool {
+        self.removed.iter().any(|removed| removed == name)
+    }
+
+    pub fn added(&self) -> &[DataComponent] {
+        &self.added
+    }
+
+    pub fn removed(&self) -> &[String] {
+        &self.removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn test_check_names() {
+        let items = ["minecraft:shulker_box", "minecraft:stone"];
+        let components = ["minecraft:container", "minecraft:damage"];
+        let is_item = |name: &str| items.contains(&name);
+        let is_component = |name: &str| components.contains(&name);
+
+        let mut stone = ItemStack::new("stone", 1).with(DataComponent::Damage(1));
+        let shulker = ItemStack::new("shulker_box", 1)
+            .with(DataComponent::Container(vec![None, Some(stone.clone())]));
+        assert!(shulker.check_names(&is_item, &is_component).is_ok());
+
+        stone.components.remove("minecraft:food");
+        let shulker = shulker.with(DataComponent::Container(vec![Some(stone)]));
+        assert!(matches!(
+            shulker.check_names(&is_item, &is_component),
+            Err(Error::Invalid(message)) if message == "unknown data component `minecraft:food`"
+        ));
+        assert!(
+            ItemStack::new("dirt", 1)
+                .check_names(&is_item, &is_component)
+                .is_err()
+        );
+    }
+}
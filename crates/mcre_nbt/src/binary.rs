@@ -0,0 +1,378 @@
+//! The binary NBT encoding, used by files and the network protocol.
+//!
+//! Files (`level.dat`, region chunks, structures) use a *named* root: a tag
+//! type byte, a string name and then the payload. Since 1.20.2 the network
+//! protocol drops the name and writes just the type byte and payload.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{Compound, Error, Result, Tag, TagType, mutf8};
+
+/// Same limit as vanilla's `NbtAccounter`.
+pub const MAX_DEPTH: usize = 512;
+
+/// Reads a named root compound, the format of files on disk.
+pub fn from_bytes(mut bytes: &[u8]) -> Result<(String, Compound)> {
+    let (name, tag) = read_named(&mut bytes)?;
+    match tag {
+        Tag::Compound(compound) => Ok((name, compound)),
+        tag => Err(Error::UnexpectedTagType {
+            expected: TagType::Compound,
+            found: tag.tag_type(),
+        }),
+    }
+}
+
+/// Reads a nameless root compound, the format of the network protocol.
+pub fn from_network_bytes(mut bytes: &[u8]) -> Result<Compound> {
+    match read_nameless(&mut bytes)? {
+        Some(Tag::Compound(compound)) => Ok(compound),
+        Some(tag) => Err(Error::UnexpectedTagType {
+            expected: TagType::Compound,
+            found: tag.tag_type(),
+        }),
+        None => Err(Error::UnexpectedTagType {
+            expected: TagType::Compound,
+            found: TagType::End,
+        }),
+    }
+}
+
+pub fn to_bytes(name: &str, compound: &Compound) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    out.push(TagType::Compound.id());
+    write_string(&mut out, name)?;
+    write_compound(&mut out, compound)?;
+    Ok(out)
+}
+
+pub fn to_network_bytes(compound: &Compound) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    out.push(TagType::Compound.id());
+    write_compound(&mut out, compound)?;
+    Ok(out)
+}
+
+/// Reads a named root tag and advances `buf` past it.
+pub fn read_named(buf: &mut &[u8]) -> Result<(String, Tag)> {
+    let tag_type = TagType::from_id(read_u8(buf)?)?;
+    if tag_type == TagType::End {
+        return Err(Error::UnexpectedTagType {
+            expected: TagType::Compound,
+            found: TagType::End,
+        });
+    }
+    let name = read_string(buf)?;
+    let tag = read_payload(buf, tag_type, 0)?;
+    Ok((name, tag))
+}
+
+/// Reads a nameless root tag and advances `buf` past it.
+///
+/// Returns `None` for a lone `TAG_End`, which the protocol uses for "no data".
+pub fn read_nameless(buf: &mut &[u8]) -> Result<Option<Tag>> {
+    let tag_type = TagType::from_id(read_u8(buf)?)?;
+    if tag_type == TagType::End {
+        return Ok(None);
+    }
+    read_payload(buf, tag_type, 0).map(Some)
+}
+
+pub fn write_named(out: &mut Vec<u8>, name: &str, tag: &Tag) -> Result<()> {
+    out.push(tag.tag_type().id());
+    write_string(out, name)?;
+    write_payload(out, tag)
+}
+
+/// Writes a nameless root tag; `None` is written as a lone `TAG_End`.
+pub fn write_nameless(out: &mut Vec<u8>, tag: Option<&Tag>) -> Result<()> {
+    match tag {
+        Some(tag) => {
+            out.push(tag.tag_type().id());
+            write_payload(out, tag)
+        }
+        None => {
+            out.push(TagType::End.id());
+            Ok(())
+        }
+    }
+}
+
+pub(crate) fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if buf.len() < len {
+        return Err(Error::UnexpectedEof);
+    }
+    let (head, tail) = buf.split_at(len);
+    *buf = tail;
+    Ok(head)
+}
+
+pub(crate) fn read_array<const N: usize>(buf: &mut &[u8]) -> Result<[u8; N]> {
+    Ok(take(buf, N)?.try_into().unwrap())
+}
+
+pub(crate) fn read_u8(buf: &mut &[u8]) -> Result<u8> {
+    Ok(read_array::<1>(buf)?[0])
+}
+
+pub(crate) fn read_u16(buf: &mut &[u8]) -> Result<u16> {
+    Ok(u16::from_be_bytes(read_array(buf)?))
+}
+
+pub(crate) fn read_i32(buf: &mut &[u8]) -> Result<i32> {
+    Ok(i32::from_be_bytes(read_array(buf)?))
+}
+
+pub(crate) fn read_len(buf: &mut &[u8]) -> Result<usize> {
+    let len = read_i32(buf)?;
+    usize::try_from(len).map_err(|_| Error::NegativeLength(len))
+}
+
+pub(crate) fn read_string_bytes<'a>(buf: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let len = read_u16(buf)? as usize;
+    take(buf, len)
+}
+
+fn read_string(buf: &mut &[u8]) -> Result<String> {
+    let bytes = read_string_bytes(buf)?;
+    Ok(mutf8::decode(bytes)?.into_owned())
+}
+
+fn read_payload(buf: &mut &[u8], tag_type: TagType, depth: usize) -> Result<Tag> {
+    if depth > MAX_DEPTH {
+        return Err(Error::DepthLimitExceeded);
+    }
+    Ok(match tag_type {
+        TagType::End => return Err(Error::InvalidTagType(0)),
+        TagType::Byte => Tag::Byte(read_u8(buf)? as i8),
+        TagType::Short => Tag::Short(i16::from_be_bytes(read_array(buf)?)),
+        TagType::Int => Tag::Int(read_i32(buf)?),
+        TagType::Long => Tag::Long(i64::from_be_bytes(read_array(buf)?)),
+        TagType::Float => Tag::Float(f32::from_be_bytes(read_array(buf)?)),
+        TagType::Double => Tag::Double(f64::from_be_bytes(read_array(buf)?)),
+        TagType::ByteArray => {
+            let len = read_len(buf)?;
+            Tag::ByteArray(take(buf, len)?.iter().map(|b| *b as i8).collect())
+        }
+        TagType::String => Tag::String(read_string(buf)?),
+        TagType::List => {
+            let element_type = TagType::from_id(read_u8(buf)?)?;
+            let len = read_len(buf)?;
+            if element_type == TagType::End && len > 0 {
+                return Err(Error::InvalidTagType(0));
+            }
+            // Each element takes at least one byte, so a bogus length fails on
+            // EOF instead of reserving a huge buffer up front.
+            let mut list = Vec::with_capacity(len.min(buf.len()));
+            for _ in 0..len {
+                list.push(read_payload(buf, element_type, depth + 1)?);
+            }
+            Tag::List(unwrap_heterogeneous(list))
+        }
+        TagType::Compound => {
+            let mut compound = Compound::new();
+            loop {
+                let tag_type = TagType::from_id(read_u8(buf)?)?;
+                if tag_type == TagType::End {
+                    break;
+                }
+                let name = read_string(buf)?;
+                let tag = read_payload(buf, tag_type, depth + 1)?;
+                compound.insert(name, tag);
+            }
+            Tag::Compound(compound)
+        }
+        TagType::IntArray => {
+            let len = read_len(buf)?;
+            let bytes = take(buf, len.checked_mul(4).ok_or(Error::UnexpectedEof)?)?;
+            Tag::IntArray(
+                bytes
+                    .chunks_exact(4)
+                    .map(|c| i32::from_be_bytes(c.try_into().unwrap()))
+                    .collect(),
+            )
+        }
+        TagType::LongArray => {
+            let len = read_len(buf)?;
+            let bytes = take(buf, len.checked_mul(8).ok_or(Error::UnexpectedEof)?)?;
+            Tag::LongArray(
+                bytes
+                    .chunks_exact(8)
+                    .map(|c| i64::from_be_bytes(c.try_into().unwrap()))
+                    .collect(),
+            )
+        }
+    })
+}
+
+/// Undoes the wrapping applied by [`write_payload`] to heterogeneous lists.
+pub(crate) fn unwrap_heterogeneous(list: Vec<Tag>) -> Vec<Tag> {
+    let wrapped = !list.is_empty()
+        && list.iter().all(|tag| {
+            tag.as_compound()
+                .is_some_and(|c| c.len() == 1 && c.contains_key(""))
+        });
+    if !wrapped {
+        return list;
+    }
+    list.into_iter()
+        .map(|tag| tag.into_compound().unwrap().remove("").unwrap())
+        .collect()
+}
+
+fn write_string(out: &mut Vec<u8>, s: &str) -> Result<()> {
+    let bytes = mutf8::encode(s);
+    let len = u16::try_from(bytes.len()).map_err(|_| Error::StringTooLong(bytes.len()))?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(&bytes);
+    Ok(())
+}
+
+fn write_len(out: &mut Vec<u8>, len: usize) {
+    out.extend_from_slice(&(len as i32).to_be_bytes());
+}
+
+fn write_compound(out: &mut Vec<u8>, compound: &Compound) -> Result<()> {
+    for (name, tag) in compound {
+        out.push(tag.tag_type().id());
+        write_string(out, name)?;
+        write_payload(out, tag)?;
+    }
+    out.push(TagType::End.id());
+    Ok(())
+}
+
+fn write_payload(out: &mut Vec<u8>, tag: &Tag) -> Result<()> {
+    match tag {
+        Tag::Byte(v) => out.push(*v as u8),
+        Tag::Short(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Int(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Long(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Float(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Double(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::ByteArray(array) => {
+            write_len(out, array.len());
+            out.extend(array.iter().map(|b| *b as u8));
+        }
+        Tag::String(s) => write_string(out, s)?,
+        Tag::List(list) => {
+            let element_type = list.first().map_or(TagType::End, Tag::tag_type);
+            if list.iter().all(|tag| tag.tag_type() == element_type) {
+                out.push(element_type.id());
+                write_len(out, list.len());
+                for tag in list {
+                    write_payload(out, tag)?;
+                }
+            } else {
+                out.push(TagType::Compound.id());
+                write_len(out, list.len());
+                for tag in list {
+                    out.push(tag.tag_type().id());
+                    write_string(out, "")?;
+                    write_payload(out, tag)?;
+                    out.push(TagType::End.id());
+                }
+            }
+        }
+        Tag::Compound(compound) => write_compound(out, compound)?,
+        Tag::IntArray(array) => {
+            write_len(out, array.len());
+            for v in array {
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+        Tag::LongArray(array) => {
+            write_len(out, array.len());
+            for v in array {
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    fn sample() -> Compound {
+        let mut level = Compound::new();
+        level.insert("byte", 1i8);
+        level.insert("short", -2i16);
+        level.insert("long", i64::MIN);
+        level.insert("double", 0.5f64);
+        level.insert("name", "Bananrama\0");
+        level.insert("bytes", vec![1i8, -1]);
+        level.insert("ints", vec![1i32, 2, 3]);
+        level.insert("longs", vec![-1i64]);
+        level.insert("list", vec![Tag::Float(1.0), Tag::Float(2.5)]);
+        level.insert("empty", Vec::<Tag>::new());
+        level.insert("mixed", vec![Tag::Int(1), Tag::String("two".into())]);
+        let mut nested = Compound::new();
+        nested.insert("egg", Tag::Compound(Compound::new()));
+        level.insert("nested", nested);
+        level
+    }
+
+    #[test]
+    fn test_named_round_trip() {
+        let compound = sample();
+        let bytes = to_bytes("Level", &compound).unwrap();
+        let (name, decoded) = from_bytes(&bytes).unwrap();
+        assert_eq!(name, "Level");
+        assert_eq!(decoded, compound);
+    }
+
+    #[test]
+    fn test_network_round_trip() {
+        let compound = sample();
+        let bytes = to_network_bytes(&compound).unwrap();
+        assert_eq!(bytes[0], TagType::Compound.id());
+        // no name follows the type byte
+        assert_eq!(bytes[1], TagType::Byte.id());
+        assert_eq!(from_network_bytes(&bytes).unwrap(), compound);
+    }
+
+    #[test]
+    fn test_hello_world() {
+        // the classic `hello_world.nbt` test file
+        let bytes = [
+            0x0a, 0x00, 0x0b, b'h', b'e', b'l', b'l', b'o', b' ', b'w', b'o', b'r', b'l', b'd',
+            0x08, 0x00, 0x04, b'n', b'a', b'm', b'e', 0x00, 0x09, b'B', b'a', b'n', b'a', b'n',
+            b'r', b'a', b'm', b'a', 0x00,
+        ];
+        let (name, compound) = from_bytes(&bytes).unwrap();
+        assert_eq!(name, "hello world");
+        assert_eq!(compound.get_str("name"), Some("Bananrama"));
+    }
+
+    #[test]
+    fn test_nameless_end_and_trailing_data() {
+        let mut buf: &[u8] = &[0x00, 0x01, 0x05];
+        assert_eq!(read_nameless(&mut buf).unwrap(), None);
+        assert_eq!(read_nameless(&mut buf).unwrap(), Some(Tag::Byte(5)));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_truncated_input() {
+        let bytes = to_bytes("", &sample()).unwrap();
+        for len in 0..bytes.len() {
+            assert!(from_bytes(&bytes[..len]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let mut bytes = vec![0x09, 0x00, 0x00];
+        for _ in 0..=MAX_DEPTH + 1 {
+            bytes.extend_from_slice(&[0x09, 0x00, 0x00, 0x00, 0x01]);
+        }
+        assert_eq!(
+            read_named(&mut bytes.as_slice()),
+            Err(Error::DepthLimitExceeded)
+        );
+    }
+}
@@ -0,0 +1,450 @@
+//! A zero-copy view over binary NBT.
+//!
+//! The input is validated once while reading; compounds and lists keep a slice
+//! of their payload and decode entries lazily while being iterated, so nothing
+//! is allocated unless a value is converted to an owned [`Tag`].
+
+use core::marker::PhantomData;
+
+use alloc::{borrow::Cow, string::String};
+
+use crate::{
+    Compound, Error, Result, Tag, TagType,
+    binary::{
+        MAX_DEPTH, read_array, read_len, read_string_bytes, read_u8, take, unwrap_heterogeneous,
+    },
+    mutf8,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BorrowedTag<'a> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(&'a [u8]),
+    String(Mutf8Str<'a>),
+    List(BorrowedList<'a>),
+    Compound(BorrowedCompound<'a>),
+    IntArray(BeArray<'a, i32>),
+    LongArray(BeArray<'a, i64>),
+}
+
+/// A modified UTF-8 string that has already been validated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mutf8Str<'a>(&'a [u8]);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BorrowedCompound<'a> {
+    data: &'a [u8],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BorrowedList<'a> {
+    element_type: TagType,
+    len: usize,
+    data: &'a [u8],
+}
+
+/// A big-endian array of numbers, decoded on access.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BeArray<'a, T> {
+    data: &'a [u8],
+    _marker: PhantomData<T>,
+}
+
+/// Reads a named root tag and advances `buf` past it.
+pub fn read_named<'a>(buf: &mut &'a [u8]) -> Result<(Mutf8Str<'a>, BorrowedTag<'a>)> {
+    let tag_type = TagType::from_id(read_u8(buf)?)?;
+    if tag_type == TagType::End {
+        return Err(Error::UnexpectedTagType {
+            expected: TagType::Compound,
+            found: TagType::End,
+        });
+    }
+    let name = read_str(buf)?;
+    let tag = read_payload(buf, tag_type, 0)?;
+    Ok((name, tag))
+}
+
+/// Reads a nameless root tag and advances `buf` past it.
+pub fn read_nameless<'a>(buf: &mut &'a [u8]) -> Result<Option<BorrowedTag<'a>>> {
+    let tag_type = TagType::from_id(read_u8(buf)?)?;
+    if tag_type == TagType::End {
+        return Ok(None);
+    }
+    read_payload(buf, tag_type, 0).map(Some)
+}
+
+fn read_str<'a>(buf: &mut &'a [u8]) -> Result<Mutf8Str<'a>> {
+    let bytes = read_string_bytes(buf)?;
+    mutf8::decode(bytes)?;
+    Ok(Mutf8Str(bytes))
+}
+
+fn read_payload<'a>(
+    buf: &mut &'a [u8],
+    tag_type: TagType,
+    depth: usize,
+) -> Result<BorrowedTag<'a>> {
+    if depth > MAX_DEPTH {
+        return Err(Error::DepthLimitExceeded);
+    }
+    Ok(match tag_type {
+        TagType::End => return Err(Error::InvalidTagType(0)),
+        TagType::Byte => BorrowedTag::Byte(read_u8(buf)? as i8),
+        TagType::Short => BorrowedTag::Short(i16::from_be_bytes(read_array(buf)?)),
+        TagType::Int => BorrowedTag::Int(i32::from_be_bytes(read_array(buf)?)),
+        TagType::Long => BorrowedTag::Long(i64::from_be_bytes(read_array(buf)?)),
+        TagType::Float => BorrowedTag::Float(f32::from_be_bytes(read_array(buf)?)),
+        TagType::Double => BorrowedTag::Double(f64::from_be_bytes(read_array(buf)?)),
+        TagType::ByteArray => {
+            let len = read_len(buf)?;
+            BorrowedTag::ByteArray(take(buf, len)?)
+        }
+        TagType::String => BorrowedTag::String(read_str(buf)?),
+        TagType::List => {
+            let element_type = TagType::from_id(read_u8(buf)?)?;
+            let len = read_len(buf)?;
+            if element_type == TagType::End && len > 0 {
+                return Err(Error::InvalidTagType(0));
+            }
+            let start = *buf;
+            for _ in 0..len {
+                read_payload(buf, element_type, depth + 1)?;
+            }
+            let data = &start[..start.len() - buf.len()];
+            BorrowedTag::List(BorrowedList {
+                element_type,
+                len,
+                data,
+            })
+        }
+        TagType::Compound => {
+            let start = *buf;
+            loop {
+                let tag_type = TagType::from_id(read_u8(buf)?)?;
+                if tag_type == TagType::End {
+                    break;
+                }
+                read_str(buf)?;
+                read_payload(buf, tag_type, depth + 1)?;
+            }
+            // excludes the closing TAG_End
+            let data = &start[..start.len() - buf.len() - 1];
+            BorrowedTag::Compound(BorrowedCompound { data })
+        }
+        TagType::IntArray => {
+            let len = read_len(buf)?;
+            let data = take(buf, len.checked_mul(4).ok_or(Error::UnexpectedEof)?)?;
+            BorrowedTag::IntArray(BeArray::new(data))
+        }
+        TagType::LongArray => {
+            let len = read_len(buf)?;
+            let data = take(buf, len.checked_mul(8).ok_or(Error::UnexpectedEof)?)?;
+            BorrowedTag::LongArray(BeArray::new(data))
+        }
+    })
+}
+
+/// Re-reads a payload that [`read_payload`] already validated.
+fn reread<'a>(buf: &mut &'a [u8], tag_type: TagType) -> BorrowedTag<'a> {
+    read_payload(buf, tag_type, 0).expect("payload was validated when it was first read")
+}
+
+impl<'a> BorrowedTag<'a> {
+    pub fn tag_type(&self) -> TagType {
+        match self {
+            Self::Byte(_) => TagType::Byte,
+            Self::Short(_) => TagType::Short,
+            Self::Int(_) => TagType::Int,
+            Self::Long(_) => TagType::Long,
+            Self::Float(_) => TagType::Float,
+            Self::Double(_) => TagType::Double,
+            Self::ByteArray(_) => TagType::ByteArray,
+            Self::String(_) => TagType::String,
+            Self::List(_) => TagType::List,
+            Self::Compound(_) => TagType::Compound,
+            Self::IntArray(_) => TagType::IntArray,
+            Self::LongArray(_) => TagType::LongArray,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Byte(v) => Some(*v as i64),
+            Self::Short(v) => Some(*v as i64),
+            Self::Int(v) => Some(*v as i64),
+            Self::Long(v) => Some(*v),
+            Self::Float(v) => Some(*v as i64),
+            Self::Double(v) => Some(*v as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<Cow<'a, str>> {
+        if let Self::String(s) = self {
+            Some(s.to_str())
+        } else {
+            None
+        }
+    }
+
+    pub fn as_compound(&self) -> Option<BorrowedCompound<'a>> {
+        if let Self::Compound(compound) = self {
+            Some(*compound)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_list(&self) -> Option<BorrowedList<'a>> {
+        if let Self::List(list) = self {
+            Some(*list)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_long_array(&self) -> Option<BeArray<'a, i64>> {
+        if let Self::LongArray(array) = self {
+            Some(*array)
+        } else {
+            None
+        }
+    }
+
+    pub fn to_owned(&self) -> Tag {
+        match self {
+            Self::Byte(v) => Tag::Byte(*v),
+            Self::Short(v) => Tag::Short(*v),
+            Self::Int(v) => Tag::Int(*v),
+            Self::Long(v) => Tag::Long(*v),
+            Self::Float(v) => Tag::Float(*v),
+            Self::Double(v) => Tag::Double(*v),
+            Self::ByteArray(bytes) => Tag::ByteArray(bytes.iter().map(|b| *b as i8).collect()),
+            Self::String(s) => Tag::String(s.to_str().into_owned()),
+            Self::List(list) => list.to_owned(),
+            Self::Compound(compound) => Tag::Compound(compound.to_owned()),
+            Self::IntArray(array) => Tag::IntArray(array.iter().collect()),
+            Self::LongArray(array) => Tag::LongArray(array.iter().collect()),
+        }
+    }
+}
+
+impl<'a> Mutf8Str<'a> {
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    pub fn to_str(&self) -> Cow<'a, str> {
+        mutf8::decode(self.0).expect("string was validated when it was read")
+    }
+}
+
+impl PartialEq<str> for Mutf8Str<'_> {
+    fn eq(&self, other: &str) -> bool {
+        *mutf8::encode(other) == *self.0
+    }
+}
+
+impl<'a> BorrowedCompound<'a> {
+    pub fn iter(&self) -> BorrowedCompoundIter<'a> {
+        BorrowedCompoundIter { data: self.data }
+    }
+
+    pub fn get(&self, key: &str) -> Option<BorrowedTag<'a>> {
+        let key = mutf8::encode(key);
+        self.iter()
+            .find(|(name, _)| name.as_bytes() == &*key)
+            .map(|(_, tag)| tag)
+    }
+
+    pub fn get_compound(&self, key: &str) -> Option<BorrowedCompound<'a>> {
+        self.get(key)?.as_compound()
+    }
+
+    pub fn get_list(&self, key: &str) -> Option<BorrowedList<'a>> {
+        self.get(key)?.as_list()
+    }
+
+    pub fn to_owned(&self) -> Compound {
+        self.iter()
+            .map(|(name, tag)| (String::from(name.to_str()), tag.to_owned()))
+            .collect()
+    }
+}
+
+pub struct BorrowedCompoundIter<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for BorrowedCompoundIter<'a> {
+    type Item = (Mutf8Str<'a>, BorrowedTag<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let tag_type = TagType::from_id(read_u8(&mut self.data).ok()?).ok()?;
+        let name = Mutf8Str(read_string_bytes(&mut self.data).ok()?);
+        Some((name, reread(&mut self.data, tag_type)))
+    }
+}
+
+impl<'a> BorrowedList<'a> {
+    pub fn element_type(&self) -> TagType {
+        self.element_type
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> BorrowedListIter<'a> {
+        BorrowedListIter {
+            element_type: self.element_type,
+            remaining: self.len,
+            data: self.data,
+        }
+    }
+
+    pub fn to_owned(&self) -> Tag {
+        Tag::List(unwrap_heterogeneous(
+            self.iter().map(|tag| tag.to_owned()).collect(),
+        ))
+    }
+}
+
+pub struct BorrowedListIter<'a> {
+    element_type: TagType,
+    remaining: usize,
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for BorrowedListIter<'a> {
+    type Item = BorrowedTag<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(reread(&mut self.data, self.element_type))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for BorrowedListIter<'_> {}
+
+pub trait BeNumber: Copy + 'static {
+    const SIZE: usize;
+
+    fn from_be_slice(bytes: &[u8]) -> Self;
+}
+
+impl BeNumber for i32 {
+    const SIZE: usize = 4;
+
+    fn from_be_slice(bytes: &[u8]) -> Self {
+        i32::from_be_bytes(bytes.try_into().unwrap())
+    }
+}
+
+impl BeNumber for i64 {
+    const SIZE: usize = 8;
+
+    fn from_be_slice(bytes: &[u8]) -> Self {
+        i64::from_be_bytes(bytes.try_into().unwrap())
+    }
+}
+
+impl<'a, T: BeNumber> BeArray<'a, T> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len() / T::SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<T> {
+        let start = index.checked_mul(T::SIZE)?;
+        self.data.get(start..start + T::SIZE).map(T::from_be_slice)
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = T> + 'a {
+        self.data.chunks_exact(T::SIZE).map(T::from_be_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::binary;
+
+    #[test]
+    fn test_borrowed_matches_owned() {
+        let mut compound = Compound::new();
+        compound.insert("name", "minecraft:stone");
+        compound.insert("heights", vec![1i64, -2, 3]);
+        compound.insert(
+            "palette",
+            vec![
+                Tag::String("minecraft:air".into()),
+                Tag::String("minecraft:stone".into()),
+            ],
+        );
+        let mut inner = Compound::new();
+        inner.insert("y", -4i8);
+        compound.insert("inner", inner);
+
+        let bytes = binary::to_bytes("root", &compound).unwrap();
+        let mut buf = bytes.as_slice();
+        let (name, tag) = read_named(&mut buf).unwrap();
+        assert!(buf.is_empty());
+        assert_eq!(name, *"root");
+
+        let root = tag.as_compound().unwrap();
+        assert_eq!(
+            root.get("name").unwrap().as_str().unwrap(),
+            "minecraft:stone"
+        );
+        let heights = root.get("heights").unwrap().as_long_array().unwrap();
+        assert_eq!(heights.len(), 3);
+        assert_eq!(heights.get(1), Some(-2));
+        assert_eq!(heights.get(3), None);
+        assert_eq!(root.get_list("palette").unwrap().len(), 2);
+        assert_eq!(
+            root.get_compound("inner").unwrap().get("y"),
+            Some(BorrowedTag::Byte(-4))
+        );
+        assert_eq!(root.to_owned(), compound);
+    }
+
+    #[test]
+    fn test_invalid_input_is_rejected_up_front() {
+        let bytes = [
+            0x0a, 0x00, 0x00, 0x08, 0x00, 0x01, b'a', 0x00, 0x02, 0xE0, b'x',
+        ];
+        assert_eq!(read_named(&mut bytes.as_slice()), Err(Error::InvalidMutf8));
+    }
+}
@@ -0,0 +1,212 @@
+//! The gzip and zlib wrappers that NBT files and region chunks come in.
+
+use alloc::vec::Vec;
+
+use miniz_oxide::{deflate, inflate};
+
+use crate::{Compound, Error, Result, binary};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const GZIP_DEFLATE: u8 = 8;
+const GZIP_FHCRC: u8 = 1 << 1;
+const GZIP_FEXTRA: u8 = 1 << 2;
+const GZIP_FNAME: u8 = 1 << 3;
+const GZIP_FCOMMENT: u8 = 1 << 4;
+
+/// Level used by `java.util.zip.Deflater.DEFAULT_COMPRESSION`.
+const DEFAULT_LEVEL: u8 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zlib,
+}
+
+impl Compression {
+    /// Guesses the wrapper from the first bytes of `data`.
+    pub fn detect(data: &[u8]) -> Self {
+        match data {
+            [0x1f, 0x8b, ..] => Self::Gzip,
+            // CMF (deflate, window ≤ 32K) followed by a FLG that makes the
+            // pair a multiple of 31, as RFC 1950 requires
+            [cmf, flg, ..]
+                if cmf & 0x0f == 8 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0 =>
+            {
+                Self::Zlib
+            }
+            _ => Self::None,
+        }
+    }
+
+    pub fn decompress(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(data.to_vec()),
+            Self::Gzip => gunzip(data),
+            Self::Zlib => inflate::decompress_to_vec_zlib(data).map_err(|_| Error::Decompression),
+        }
+    }
+
+    pub fn compress(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::None => data.to_vec(),
+            Self::Gzip => gzip(data),
+            Self::Zlib => deflate::compress_to_vec_zlib(data, DEFAULT_LEVEL),
+        }
+    }
+}
+
+/// Reads a named root compound from a file that may or may not be compressed.
+pub fn from_compressed_bytes(data: &[u8]) -> Result<(alloc::string::String, Compound)> {
+    let compression = Compression::detect(data);
+    if compression == Compression::None {
+        return binary::from_bytes(data);
+    }
+    binary::from_bytes(&compression.decompress(data)?)
+}
+
+/// Writes a named root compound gzipped, the way `NbtIo::writeCompressed` does.
+pub fn to_compressed_bytes(name: &str, compound: &Compound) -> Result<Vec<u8>> {
+    Ok(gzip(&binary::to_bytes(name, compound)?))
+}
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let body = deflate::compress_to_vec(data, DEFAULT_LEVEL);
+    let mut out = Vec::with_capacity(body.len() + 18);
+    // magic, method, flags, mtime, extra flags, OS (unknown)
+    out.extend_from_slice(&GZIP_MAGIC);
+    out.extend_from_slice(&[GZIP_DEFLATE, 0, 0, 0, 0, 0, 0, 0xff]);
+    out.extend_from_slice(&body);
+    out.extend_from_slice(&crc32(data).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out
+}
+
+fn gunzip(data: &[u8]) -> Result<Vec<u8>> {
+    let mut buf = data;
+    let header = binary::take(&mut buf, 10).map_err(|_| Error::InvalidGzipHeader)?;
+    if header[..2] != GZIP_MAGIC || header[2] != GZIP_DEFLATE {
+        return Err(Error::InvalidGzipHeader);
+    }
+    let flags = header[3];
+    if flags & GZIP_FEXTRA != 0 {
+        let len = binary::read_array::<2>(&mut buf).map_err(|_| Error::InvalidGzipHeader)?;
+        binary::take(&mut buf, u16::from_le_bytes(len) as usize)
+            .map_err(|_| Error::InvalidGzipHeader)?;
+    }
+    for flag in [GZIP_FNAME, GZIP_FCOMMENT] {
+        if flags & flag != 0 {
+            let end = buf
+                .iter()
+                .position(|b| *b == 0)
+                .ok_or(Error::InvalidGzipHeader)?;
+            buf = &buf[end + 1..];
+        }
+    }
+    if flags & GZIP_FHCRC != 0 {
+        binary::take(&mut buf, 2).map_err(|_| Error::InvalidGzipHeader)?;
+    }
+
+    let (out, consumed) = inflate_raw(buf)?;
+    let mut trailer = &buf[consumed..];
+    let crc = u32::from_le_bytes(binary::read_array(&mut trailer)?);
+    let size = u32::from_le_bytes(binary::read_array(&mut trailer)?);
+    if crc != crc32(&out) || size != out.len() as u32 {
+        return Err(Error::ChecksumMismatch);
+    }
+    Ok(out)
+}
+
+/// Inflates a raw deflate stream, returning the output and the input consumed.
+fn inflate_raw(data: &[u8]) -> Result<(Vec<u8>, usize)> {
+    use inflate::{
+        TINFLStatus,
+        core::{
+            DecompressorOxide, decompress, inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+        },
+    };
+
+    let mut decompressor = DecompressorOxide::new();
+    let mut out = alloc::vec![0; data.len().saturating_mul(2).max(64)];
+    let mut in_pos = 0;
+    let mut out_pos = 0;
+    loop {
+        let (status, read, written) = decompress(
+            &mut decompressor,
+            &data[in_pos..],
+            &mut out,
+            out_pos,
+            TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+        );
+        in_pos += read;
+        out_pos += written;
+        match status {
+            TINFLStatus::Done => {
+                out.truncate(out_pos);
+                return Ok((out, in_pos));
+            }
+            TINFLStatus::HasMoreOutput => out.resize(out.len() * 2, 0),
+            _ => return Err(Error::Decompression),
+        }
+    }
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut c = i as u32;
+            let mut k = 0;
+            while k < 8 {
+                c = if c & 1 != 0 {
+                    0xEDB8_8320 ^ (c >> 1)
+                } else {
+                    c >> 1
+                };
+                k += 1;
+            }
+            table[i] = c;
+            i += 1;
+        }
+        table
+    };
+
+    !data.iter().fold(!0u32, |crc, b| {
+        TABLE[((crc ^ *b as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut compound = Compound::new();
+        compound.insert("DataVersion", 4440);
+        compound.insert("LevelName", "New World");
+
+        for compression in [Compression::None, Compression::Gzip, Compression::Zlib] {
+            let bytes = compression.compress(&binary::to_bytes("", &compound).unwrap());
+            assert_eq!(Compression::detect(&bytes), compression);
+            assert_eq!(from_compressed_bytes(&bytes).unwrap().1, compound);
+        }
+    }
+
+    #[test]
+    fn test_corrupt_gzip_is_rejected() {
+        let mut bytes = to_compressed_bytes("", &Compound::new()).unwrap();
+        let len = bytes.len();
+        bytes[len - 5] ^= 0xff;
+        assert_eq!(
+            Compression::Gzip.decompress(&bytes),
+            Err(Error::ChecksumMismatch)
+        );
+    }
+}
@@ -0,0 +1,493 @@
+//! Deserializing Rust values from a borrowed [`Tag`].
+//!
+//! This is the inverse of [`crate::ser`]: unsigned integers are read back from
+//! the signed tag of the same width, booleans accept any numeric tag, and
+//! missing compound entries become `None` for `Option` fields.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use serde::{
+    Deserialize,
+    de::{
+        self, DeserializeSeed, IntoDeserializer, MapAccess, Visitor,
+        value::{BorrowedStrDeserializer, SeqDeserializer},
+    },
+    forward_to_deserialize_any,
+};
+
+use crate::{
+    ByteArray, Compound, Error, IntArray, LongArray, Result, Tag,
+    ser::{BYTE_ARRAY_TOKEN, INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN},
+};
+
+pub fn from_tag<'de, T: Deserialize<'de>>(tag: &'de Tag) -> Result<T> {
+    T::deserialize(Deserializer(tag))
+}
+
+pub fn from_compound<'de, T: Deserialize<'de>>(compound: &'de Compound) -> Result<T> {
+    T::deserialize(CompoundDeserializer(compound))
+}
+
+#[derive(Clone, Copy)]
+pub struct Deserializer<'de>(pub &'de Tag);
+
+impl<'de> Deserializer<'de> {
+    fn array_entry(self) -> Option<&'static str> {
+        match self.0 {
+            Tag::ByteArray(_) => Some(BYTE_ARRAY_TOKEN),
+            Tag::IntArray(_) => Some(INT_ARRAY_TOKEN),
+            Tag::LongArray(_) => Some(LONG_ARRAY_TOKEN),
+            _ => None,
+        }
+    }
+
+    fn invalid_type(self, expected: &dyn de::Expected) -> Error {
+        let unexpected = match self.0 {
+            Tag::String(s) => de::Unexpected::Str(s),
+            Tag::List(_) => de::Unexpected::Seq,
+            Tag::Compound(_) => de::Unexpected::Map,
+            tag => match tag.as_i64() {
+                Some(v) if tag.as_f64().is_some_and(|f| f == v as f64) => de::Unexpected::Signed(v),
+                _ => de::Unexpected::Other("array tag"),
+            },
+        };
+        de::Error::invalid_type(unexpected, expected)
+    }
+}
+
+macro_rules! deserialize_unsigned {
+    ($($method:ident => $variant:ident as $ty:ty => $visit:ident),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                match self.0 {
+                    Tag::$variant(v) => visitor.$visit(*v as $ty),
+                    _ => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Tag::Byte(v) => visitor.visit_i8(*v),
+            Tag::Short(v) => visitor.visit_i16(*v),
+            Tag::Int(v) => visitor.visit_i32(*v),
+            Tag::Long(v) => visitor.visit_i64(*v),
+            Tag::Float(v) => visitor.visit_f32(*v),
+            Tag::Double(v) => visitor.visit_f64(*v),
+            Tag::String(s) => visitor.visit_borrowed_str(s),
+            Tag::List(list) => {
+                visitor.visit_seq(SeqDeserializer::new(list.iter().map(Deserializer)))
+            }
+            Tag::Compound(compound) => CompoundDeserializer(compound).deserialize_any(visitor),
+            // Arrays show up as a single-entry map keyed by a marker, so that
+            // `Tag`'s own visitor can tell them apart from lists
+            Tag::ByteArray(_) | Tag::IntArray(_) | Tag::LongArray(_) => {
+                visitor.visit_map(Entries {
+                    iter: core::iter::once((self.array_entry().unwrap(), self.0)),
+                    value: None,
+                })
+            }
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0.as_bool() {
+            Some(v) if !matches!(self.0, Tag::Float(_) | Tag::Double(_)) => visitor.visit_bool(v),
+            _ => Err(self.invalid_type(&visitor)),
+        }
+    }
+
+    deserialize_unsigned! {
+        deserialize_u8 => Byte as u8 => visit_u8,
+        deserialize_u16 => Short as u16 => visit_u16,
+        deserialize_u32 => Int as u32 => visit_u32,
+        deserialize_u64 => Long as u64 => visit_u64
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Tag::ByteArray(array) => visitor.visit_seq(SeqDeserializer::new(array.iter().copied())),
+            Tag::IntArray(array) => visitor.visit_seq(SeqDeserializer::new(array.iter().copied())),
+            Tag::LongArray(array) => visitor.visit_seq(SeqDeserializer::new(array.iter().copied())),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Tag::ByteArray(array) => {
+                visitor.visit_byte_buf(array.iter().map(|b| *b as u8).collect())
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.0 {
+            Tag::String(s) => visitor.visit_enum(BorrowedStrDeserializer::<Error>::new(s)),
+            Tag::Compound(compound) if compound.len() == 1 => {
+                let (variant, value) = compound.iter().next().unwrap();
+                visitor.visit_enum(Enum { variant, value })
+            }
+            _ => Err(self.invalid_type(&visitor)),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u128 f32 f64 char str string map struct identifier
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Deserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct CompoundDeserializer<'de>(pub &'de Compound);
+
+impl<'de> de::Deserializer<'de> for CompoundDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_map(Entries {
+            iter: self.0.iter().map(|(k, v)| (k.as_str(), v)),
+            value: None,
+        })
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct Entries<'de, I> {
+    iter: I,
+    value: Option<&'de Tag>,
+}
+
+impl<'de, I: Iterator<Item = (&'de str, &'de Tag)>> MapAccess<'de> for Entries<'de, I> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        let Some((key, value)) = self.iter.next() else {
+            return Ok(None);
+        };
+        self.value = Some(value);
+        seed.deserialize(BorrowedStrDeserializer::new(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(Deserializer(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.iter.size_hint().1
+    }
+}
+
+struct Enum<'de> {
+    variant: &'de str,
+    value: &'de Tag,
+}
+
+impl<'de> de::EnumAccess<'de> for Enum<'de> {
+    type Error = Error;
+    type Variant = Deserializer<'de>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant)> {
+        let variant = seed.deserialize(BorrowedStrDeserializer::<Error>::new(self.variant))?;
+        Ok((variant, Deserializer(self.value)))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+macro_rules! impl_array_deserialize {
+    ($($ty:ident),*) => {
+        $(
+            impl<'de> Deserialize<'de> for $ty {
+                fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+                    Deserialize::deserialize(deserializer).map($ty)
+                }
+            }
+        )*
+    };
+}
+
+impl_array_deserialize!(ByteArray, IntArray, LongArray);
+
+struct TagVisitor;
+
+impl<'de> Visitor<'de> for TagVisitor {
+    type Value = Tag;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an NBT tag")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> core::result::Result<Tag, E> {
+        Ok(v.into())
+    }
+
+    fn visit_i8<E>(self, v: i8) -> core::result::Result<Tag, E> {
+        Ok(Tag::Byte(v))
+    }
+
+    fn visit_i16<E>(self, v: i16) -> core::result::Result<Tag, E> {
+        Ok(Tag::Short(v))
+    }
+
+    fn visit_i32<E>(self, v: i32) -> core::result::Result<Tag, E> {
+        Ok(Tag::Int(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> core::result::Result<Tag, E> {
+        Ok(Tag::Long(v))
+    }
+
+    fn visit_u8<E>(self, v: u8) -> core::result::Result<Tag, E> {
+        Ok(Tag::Byte(v as i8))
+    }
+
+    fn visit_u16<E>(self, v: u16) -> core::result::Result<Tag, E> {
+        Ok(Tag::Short(v as i16))
+    }
+
+    fn visit_u32<E>(self, v: u32) -> core::result::Result<Tag, E> {
+        Ok(Tag::Int(v as i32))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> core::result::Result<Tag, E> {
+        Ok(Tag::Long(v as i64))
+    }
+
+    fn visit_f32<E>(self, v: f32) -> core::result::Result<Tag, E> {
+        Ok(Tag::Float(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> core::result::Result<Tag, E> {
+        Ok(Tag::Double(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> core::result::Result<Tag, E> {
+        Ok(Tag::String(v.into()))
+    }
+
+    fn visit_string<E>(self, v: String) -> core::result::Result<Tag, E> {
+        Ok(Tag::String(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> core::result::Result<Tag, E> {
+        Ok(Tag::ByteArray(v.iter().map(|b| *b as i8).collect()))
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> core::result::Result<Tag, A::Error> {
+        let mut list = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(tag) = seq.next_element()? {
+            list.push(tag);
+        }
+        Ok(Tag::List(list))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> core::result::Result<Tag, A::Error> {
+        let mut compound = Compound::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                BYTE_ARRAY_TOKEN => return Ok(Tag::ByteArray(map.next_value()?)),
+                INT_ARRAY_TOKEN => return Ok(Tag::IntArray(map.next_value()?)),
+                LONG_ARRAY_TOKEN => return Ok(Tag::LongArray(map.next_value()?)),
+                _ => {
+                    compound.insert(key, map.next_value::<Tag>()?);
+                }
+            }
+        }
+        Ok(Tag::Compound(compound))
+    }
+}
+
+impl<'de> Deserialize<'de> for Tag {
+    fn deserialize<D: de::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(TagVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Compound {
+    fn deserialize<D: de::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        match deserializer.deserialize_map(TagVisitor)? {
+            Tag::Compound(compound) => Ok(compound),
+            _ => Err(de::Error::custom("expected a compound")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use serde::Serialize;
+
+    use super::*;
+    use crate::{snbt, to_tag};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Kind {
+        Plains,
+        Custom { scale: f32 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Entry<'a> {
+        name: &'a str,
+        id: u8,
+        enabled: bool,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        comment: Option<String>,
+        fallback: Option<i32>,
+        heights: LongArray,
+        tags: Vec<String>,
+        kinds: Vec<Kind>,
+        extra: Compound,
+    }
+
+    #[test]
+    fn test_struct_round_trip() {
+        let mut extra = Compound::new();
+        extra.insert("anything", vec![1i32, 2]);
+        let entry = Entry {
+            name: "minecraft:plains",
+            id: 200,
+            enabled: true,
+            comment: None,
+            fallback: None,
+            heights: LongArray(vec![1, 2, 3]),
+            tags: vec!["a".into()],
+            kinds: vec![Kind::Plains, Kind::Custom { scale: 0.5 }],
+            extra,
+        };
+        let tag = to_tag(&entry).unwrap();
+        assert_eq!(
+            snbt::to_snbt(&tag),
+            "{enabled:1b,extra:{anything:[I;1,2]},heights:[L;1L,2L,3L],id:-56b,\
+             kinds:[\"plains\",{custom:{scale:0.5f}}],name:\"minecraft:plains\",tags:[\"a\"]}"
+        );
+        assert_eq!(from_tag::<Entry>(&tag).unwrap(), entry);
+    }
+
+    #[test]
+    fn test_tag_round_trip() {
+        let tag = snbt::parse("{a:[B;1B],b:[I;],c:[L;5L],d:[1b,{}],e:\"x\"}").unwrap();
+        assert_eq!(from_tag::<Tag>(&tag).unwrap(), tag);
+        assert_eq!(to_tag(&tag).unwrap(), tag);
+    }
+
+    #[test]
+    fn test_bool_from_numeric() {
+        assert_eq!(from_tag::<bool>(&Tag::Int(2)), Ok(true));
+        assert!(from_tag::<bool>(&Tag::String("true".into())).is_err());
+    }
+}
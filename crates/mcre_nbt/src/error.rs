@@ -0,0 +1,73 @@
+use core::fmt::{self, Display};
+
+use alloc::string::{String, ToString};
+
+use crate::TagType;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    UnexpectedEof,
+    InvalidTagType(u8),
+    UnexpectedTagType { expected: TagType, found: TagType },
+    InvalidMutf8,
+    StringTooLong(usize),
+    NegativeLength(i32),
+    DepthLimitExceeded,
+    HeterogeneousList,
+    InvalidGzipHeader,
+    ChecksumMismatch,
+    Decompression,
+    Snbt { position: usize, message: String },
+    Custom(String),
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl Error {
+    pub(crate) fn snbt(position: usize, message: impl ToString) -> Self {
+        Self::Snbt {
+            position,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof => f.write_str("unexpected end of input"),
+            Self::InvalidTagType(id) => write!(f, "invalid tag type id {id}"),
+            Self::UnexpectedTagType { expected, found } => {
+                write!(f, "expected {expected:?} tag, found {found:?}")
+            }
+            Self::InvalidMutf8 => f.write_str("invalid modified UTF-8 string"),
+            Self::StringTooLong(len) => {
+                write!(f, "string of {len} bytes does not fit in a u16 length")
+            }
+            Self::NegativeLength(len) => write!(f, "negative length {len}"),
+            Self::DepthLimitExceeded => f.write_str("tag nesting exceeds the depth limit"),
+            Self::HeterogeneousList => f.write_str("list elements have different types"),
+            Self::InvalidGzipHeader => f.write_str("invalid gzip header"),
+            Self::ChecksumMismatch => f.write_str("checksum mismatch"),
+            Self::Decompression => f.write_str("failed to decompress data"),
+            Self::Snbt { position, message } => {
+                write!(f, "{message} at position {position}")
+            }
+            Self::Custom(message) => f.write_str(message),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl serde::ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+impl serde::de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
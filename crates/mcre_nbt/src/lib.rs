@@ -0,0 +1,21 @@
+#![no_std]
+
+extern crate alloc;
+
+pub mod binary;
+pub mod borrow;
+mod compression;
+pub mod de;
+mod error;
+pub mod mutf8;
+pub mod ser;
+pub mod snbt;
+mod tag;
+
+pub use binary::{from_bytes, from_network_bytes, to_bytes, to_network_bytes};
+pub use compression::{Compression, from_compressed_bytes, to_compressed_bytes};
+pub use de::{from_compound, from_tag};
+pub use error::{Error, Result};
+pub use ser::{ByteArray, IntArray, LongArray, to_compound, to_tag};
+pub use snbt::{from_snbt, to_snbt};
+pub use tag::{Compound, Tag, TagType};
@@ -0,0 +1,120 @@
+//! Java's "modified UTF-8", as written by `DataOutput::writeUTF`.
+//!
+//! It differs from standard UTF-8 in two ways: `U+0000` is encoded as the two
+//! bytes `C0 80`, and supplementary characters are encoded as a surrogate pair
+//! of three-byte sequences instead of a single four-byte sequence.
+
+use alloc::{borrow::Cow, string::String, vec::Vec};
+
+use crate::{Error, Result};
+
+/// Encodes `s` as modified UTF-8, borrowing when the encodings coincide.
+pub fn encode(s: &str) -> Cow<'_, [u8]> {
+    if !s.bytes().any(|b| b == 0 || b >= 0xF0) {
+        return Cow::Borrowed(s.as_bytes());
+    }
+
+    let mut out = Vec::with_capacity(s.len() + 4);
+    for c in s.chars() {
+        match c as u32 {
+            0 => out.extend_from_slice(&[0xC0, 0x80]),
+            code if code >= 0x10000 => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    push_three_bytes(&mut out, *unit);
+                }
+            }
+            _ => {
+                let mut buf = [0u8; 4];
+                out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            }
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Length in bytes of the modified UTF-8 encoding of `s`.
+pub fn encoded_len(s: &str) -> usize {
+    s.chars()
+        .map(|c| match c as u32 {
+            0 => 2,
+            code if code >= 0x10000 => 6,
+            _ => c.len_utf8(),
+        })
+        .sum()
+}
+
+fn push_three_bytes(out: &mut Vec<u8>, unit: u16) {
+    out.push(0xE0 | (unit >> 12) as u8);
+    out.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+    out.push(0x80 | (unit & 0x3F) as u8);
+}
+
+/// Decodes modified UTF-8, borrowing when `bytes` is also valid UTF-8.
+///
+/// Unpaired surrogates cannot be represented in a Rust string and are replaced
+/// with `U+FFFD`, the same way `new String(bytes, UTF_8)` would on the Java side.
+pub fn decode(bytes: &[u8]) -> Result<Cow<'_, str>> {
+    if let Ok(s) = core::str::from_utf8(bytes) {
+        return Ok(Cow::Borrowed(s));
+    }
+
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b0 = bytes[i] as u16;
+        let (unit, len) = if b0 & 0x80 == 0 {
+            (b0, 1)
+        } else if b0 & 0xE0 == 0xC0 {
+            let b1 = continuation(bytes, i + 1)?;
+            (((b0 & 0x1F) << 6) | b1, 2)
+        } else if b0 & 0xF0 == 0xE0 {
+            let b1 = continuation(bytes, i + 1)?;
+            let b2 = continuation(bytes, i + 2)?;
+            (((b0 & 0x0F) << 12) | (b1 << 6) | b2, 3)
+        } else {
+            return Err(Error::InvalidMutf8);
+        };
+        units.push(unit);
+        i += len;
+    }
+
+    Ok(Cow::Owned(String::from_utf16_lossy(&units)))
+}
+
+fn continuation(bytes: &[u8], i: usize) -> Result<u16> {
+    match bytes.get(i) {
+        Some(b) if b & 0xC0 == 0x80 => Ok((b & 0x3F) as u16),
+        _ => Err(Error::InvalidMutf8),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_is_borrowed() {
+        assert!(matches!(encode("minecraft:stone"), Cow::Borrowed(_)));
+        assert!(matches!(decode(b"minecraft:stone"), Ok(Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn test_nul_and_supplementary_round_trip() {
+        let s = "a\0b\u{1F600}c";
+        let encoded = encode(s);
+        assert_eq!(
+            &*encoded,
+            &[
+                b'a', 0xC0, 0x80, b'b', 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80, b'c'
+            ]
+        );
+        assert_eq!(encoded.len(), encoded_len(s));
+        assert_eq!(decode(&encoded).unwrap(), s);
+    }
+
+    #[test]
+    fn test_truncated_sequence_is_rejected() {
+        assert_eq!(decode(&[b'a', 0xE0, 0x80]), Err(Error::InvalidMutf8));
+    }
+}
@@ -0,0 +1,590 @@
+//! Serializing Rust values into a [`Tag`].
+//!
+//! Integers keep their width (`u8` becomes a byte with the same bits, and so
+//! on), sequences become lists, and maps and structs become compounds. `None`
+//! struct fields and map values are left out, which is how vanilla codecs
+//! encode optional fields. Wrap a `Vec` in [`ByteArray`], [`IntArray`] or
+//! [`LongArray`] to get the array tags instead of a list.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use serde::{
+    Serialize,
+    ser::{self, Impossible},
+};
+
+use crate::{Compound, Error, Result, Tag};
+
+pub(crate) const BYTE_ARRAY_TOKEN: &str = "__mcre_nbt_byte_array";
+pub(crate) const INT_ARRAY_TOKEN: &str = "__mcre_nbt_int_array";
+pub(crate) const LONG_ARRAY_TOKEN: &str = "__mcre_nbt_long_array";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ByteArray(pub Vec<i8>);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntArray(pub Vec<i32>);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LongArray(pub Vec<i64>);
+
+macro_rules! impl_array_serialize {
+    ($($ty:ident => $token:ident),*) => {
+        $(
+            impl Serialize for $ty {
+                fn serialize<S: ser::Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+                    serializer.serialize_newtype_struct($token, &self.0)
+                }
+            }
+        )*
+    };
+}
+
+impl_array_serialize! {
+    ByteArray => BYTE_ARRAY_TOKEN,
+    IntArray => INT_ARRAY_TOKEN,
+    LongArray => LONG_ARRAY_TOKEN
+}
+
+pub fn to_tag<T: Serialize + ?Sized>(value: &T) -> Result<Tag> {
+    value
+        .serialize(Serializer)?
+        .ok_or_else(|| Error::Custom("cannot serialize None as a root tag".into()))
+}
+
+pub fn to_compound<T: Serialize + ?Sized>(value: &T) -> Result<Compound> {
+    match to_tag(value)? {
+        Tag::Compound(compound) => Ok(compound),
+        tag => Err(Error::UnexpectedTagType {
+            expected: crate::TagType::Compound,
+            found: tag.tag_type(),
+        }),
+    }
+}
+
+/// Produces `None` for values that should be omitted from their parent.
+struct Serializer;
+
+fn present(value: &(impl Serialize + ?Sized)) -> Result<Tag> {
+    value
+        .serialize(Serializer)?
+        .ok_or_else(|| Error::Custom("None is only allowed as a field or map value".into()))
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Option<Tag>;
+    type Error = Error;
+    type SerializeSeq = SerializeList;
+    type SerializeTuple = SerializeList;
+    type SerializeTupleStruct = SerializeList;
+    type SerializeTupleVariant = SerializeVariant<SerializeList>;
+    type SerializeMap = SerializeCompound;
+    type SerializeStruct = SerializeCompound;
+    type SerializeStructVariant = SerializeVariant<SerializeCompound>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok> {
+        Ok(Some(v.into()))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok> {
+        Ok(Some(Tag::Byte(v)))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok> {
+        Ok(Some(Tag::Short(v)))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok> {
+        Ok(Some(Tag::Int(v)))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok> {
+        Ok(Some(Tag::Long(v)))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok> {
+        Ok(Some(Tag::Byte(v as i8)))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok> {
+        Ok(Some(Tag::Short(v as i16)))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok> {
+        Ok(Some(Tag::Int(v as i32)))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok> {
+        Ok(Some(Tag::Long(v as i64)))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok> {
+        Ok(Some(Tag::Float(v)))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok> {
+        Ok(Some(Tag::Double(v)))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok> {
+        Ok(Some(Tag::String(v.to_string())))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok> {
+        Ok(Some(Tag::String(v.into())))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok> {
+        Ok(Some(Tag::ByteArray(v.iter().map(|b| *b as i8).collect())))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok> {
+        Ok(Some(Tag::Compound(Compound::new())))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok> {
+        let tag = present(value)?;
+        let array = |tag: Tag| -> Result<Vec<i64>> {
+            let Tag::List(list) = tag else {
+                return Err(Error::Custom(
+                    "array wrapper must contain a sequence".into(),
+                ));
+            };
+            list.iter()
+                .map(|tag| {
+                    tag.as_i64()
+                        .ok_or_else(|| Error::Custom("array elements must be integers".into()))
+                })
+                .collect()
+        };
+        Ok(Some(match name {
+            BYTE_ARRAY_TOKEN => Tag::ByteArray(array(tag)?.into_iter().map(|v| v as i8).collect()),
+            INT_ARRAY_TOKEN => Tag::IntArray(array(tag)?.into_iter().map(|v| v as i32).collect()),
+            LONG_ARRAY_TOKEN => Tag::LongArray(array(tag)?),
+            _ => tag,
+        }))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok> {
+        let mut compound = Compound::new();
+        compound.insert(variant, present(value)?);
+        Ok(Some(Tag::Compound(compound)))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(SerializeList(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Ok(SerializeVariant {
+            variant,
+            inner: SerializeList(Vec::with_capacity(len)),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(SerializeCompound {
+            compound: Compound::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+pub struct SerializeList(Vec<Tag>);
+
+impl ser::SerializeSeq for SerializeList {
+    type Ok = Option<Tag>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.0.push(present(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(Some(Tag::List(self.0)))
+    }
+}
+
+impl ser::SerializeTuple for SerializeList {
+    type Ok = Option<Tag>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeList {
+    type Ok = Option<Tag>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+pub struct SerializeCompound {
+    compound: Compound,
+    key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeCompound {
+    type Ok = Option<Tag>;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+        if let Some(tag) = value.serialize(Serializer)? {
+            self.compound.insert(key, tag);
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(Some(Tag::Compound(self.compound)))
+    }
+}
+
+impl ser::SerializeStruct for SerializeCompound {
+    type Ok = Option<Tag>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        if let Some(tag) = value.serialize(Serializer)? {
+            self.compound.insert(key, tag);
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+pub struct SerializeVariant<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl SerializeVariant<SerializeList> {
+    fn wrap(self) -> Result<Option<Tag>> {
+        let mut compound = Compound::new();
+        compound.insert(self.variant, Tag::List(self.inner.0));
+        Ok(Some(Tag::Compound(compound)))
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeList> {
+    type Ok = Option<Tag>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.wrap()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeCompound> {
+    type Ok = Option<Tag>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        let mut compound = Compound::new();
+        compound.insert(self.variant, self.inner.compound);
+        Ok(Some(Tag::Compound(compound)))
+    }
+}
+
+/// Compound keys have to be strings; anything else is rejected.
+struct KeySerializer;
+
+fn key_error() -> Error {
+    Error::Custom("compound keys must be strings".into())
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_str(self, v: &str) -> Result<String> {
+        Ok(v.into())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String> {
+        Ok(variant.into())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String> {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_none(self) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_unit(self) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String> {
+        Err(key_error())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(key_error())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(key_error())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(key_error())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(key_error())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(key_error())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(key_error())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(key_error())
+    }
+}
+
+impl Serialize for Tag {
+    fn serialize<S: ser::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        match self {
+            Tag::Byte(v) => serializer.serialize_i8(*v),
+            Tag::Short(v) => serializer.serialize_i16(*v),
+            Tag::Int(v) => serializer.serialize_i32(*v),
+            Tag::Long(v) => serializer.serialize_i64(*v),
+            Tag::Float(v) => serializer.serialize_f32(*v),
+            Tag::Double(v) => serializer.serialize_f64(*v),
+            Tag::ByteArray(v) => serializer.serialize_newtype_struct(BYTE_ARRAY_TOKEN, v),
+            Tag::String(v) => serializer.serialize_str(v),
+            Tag::List(v) => v.serialize(serializer),
+            Tag::Compound(v) => v.serialize(serializer),
+            Tag::IntArray(v) => serializer.serialize_newtype_struct(INT_ARRAY_TOKEN, v),
+            Tag::LongArray(v) => serializer.serialize_newtype_struct(LONG_ARRAY_TOKEN, v),
+        }
+    }
+}
+
+impl Serialize for Compound {
+    fn serialize<S: ser::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
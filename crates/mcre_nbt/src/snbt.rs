@@ -0,0 +1,637 @@
+//! Stringified NBT, as used by commands, `/data get` and structure text files.
+//!
+//! The printer matches vanilla's `StringTagVisitor`: compound keys are sorted,
+//! keys are only quoted when they are not simple identifiers, and strings pick
+//! whichever quote needs no escaping. The parser accepts the 1.21.5 grammar,
+//! minus the `bool(..)`/`uuid(..)` operations.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::{Compound, Error, Result, Tag, binary::MAX_DEPTH};
+
+/// Parses any SNBT value.
+pub fn parse(input: &str) -> Result<Tag> {
+    let mut parser = Parser { input, pos: 0 };
+    let tag = parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.pos != input.len() {
+        return Err(parser.error("trailing data"));
+    }
+    Ok(tag)
+}
+
+/// Parses one SNBT value at the start of `input`, returning it with the
+/// number of bytes it took, for values embedded in other syntax such as item
+/// arguments.
+pub fn parse_prefix(input: &str) -> Result<(Tag, usize)> {
+    let mut parser = Parser { input, pos: 0 };
+    let tag = parser.value(0)?;
+    Ok((tag, parser.pos))
+}
+
+/// Parses an SNBT compound, such as the argument of `/data merge`.
+pub fn from_snbt(input: &str) -> Result<Compound> {
+    match parse(input)? {
+        Tag::Compound(compound) => Ok(compound),
+        _ => Err(Error::snbt(0, "expected a compound")),
+    }
+}
+
+pub fn to_snbt(tag: &Tag) -> String {
+    let mut out = String::new();
+    write_tag(&mut out, tag);
+    out
+}
+
+fn write_tag(out: &mut String, tag: &Tag) {
+    match tag {
+        Tag::Byte(v) => write!(out, "{v}b").unwrap(),
+        Tag::Short(v) => write!(out, "{v}s").unwrap(),
+        Tag::Int(v) => write!(out, "{v}").unwrap(),
+        Tag::Long(v) => write!(out, "{v}L").unwrap(),
+        Tag::Float(v) => {
+            write_java_float(out, *v as f64, format!("{v:e}"));
+            out.push('f');
+        }
+        Tag::Double(v) => {
+            write_java_float(out, *v, format!("{v:e}"));
+            out.push('d');
+        }
+        Tag::String(s) => write_quoted(out, s),
+        Tag::List(list) => {
+            out.push('[');
+            for (i, tag) in list.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_tag(out, tag);
+            }
+            out.push(']');
+        }
+        Tag::Compound(compound) => {
+            let mut keys: Vec<&String> = compound.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                if is_simple(key) {
+                    out.push_str(key);
+                } else {
+                    write_quoted(out, key);
+                }
+                out.push(':');
+                write_tag(out, &compound[key]);
+            }
+            out.push('}');
+        }
+        Tag::ByteArray(array) => write_array(out, 'B', array, "B"),
+        Tag::IntArray(array) => write_array(out, 'I', array, ""),
+        Tag::LongArray(array) => write_array(out, 'L', array, "L"),
+    }
+}
+
+fn write_array<T: core::fmt::Display>(out: &mut String, prefix: char, array: &[T], suffix: &str) {
+    write!(out, "[{prefix};").unwrap();
+    for (i, v) in array.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write!(out, "{v}{suffix}").unwrap();
+    }
+    out.push(']');
+}
+
+/// Formats like `Double.toString`/`Float.toString`, given the shortest
+/// round-tripping scientific representation of the value.
+fn write_java_float(out: &mut String, value: f64, scientific: String) {
+    if value.is_nan() {
+        out.push_str("NaN");
+        return;
+    }
+    if value.is_infinite() {
+        out.push_str(if value > 0.0 { "Infinity" } else { "-Infinity" });
+        return;
+    }
+
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    let magnitude = value.abs();
+    if magnitude == 0.0 || (1e-3..1e7).contains(&magnitude) {
+        let (negative, digits) = match mantissa.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, mantissa),
+        };
+        let digits: String = digits.chars().filter(|c| *c != '.').collect();
+        if negative {
+            out.push('-');
+        }
+        let point = exponent + 1;
+        if magnitude == 0.0 {
+            out.push_str("0.0");
+        } else if point <= 0 {
+            out.push_str("0.");
+            for _ in 0..-point {
+                out.push('0');
+            }
+            out.push_str(&digits);
+        } else {
+            let point = point as usize;
+            if digits.len() <= point {
+                out.push_str(&digits);
+                for _ in digits.len()..point {
+                    out.push('0');
+                }
+                out.push_str(".0");
+            } else {
+                out.push_str(&digits[..point]);
+                out.push('.');
+                out.push_str(&digits[point..]);
+            }
+        }
+    } else {
+        out.push_str(mantissa);
+        if !mantissa.contains('.') {
+            out.push_str(".0");
+        }
+        write!(out, "E{exponent}").unwrap();
+    }
+}
+
+/// Vanilla's `SIMPLE_VALUE` pattern: `[A-Za-z._]+[A-Za-z0-9._+-]*`.
+fn is_simple(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '.' || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '+' | '-'))
+}
+
+/// Port of `StringTag.quoteAndEscape`.
+fn write_quoted(out: &mut String, s: &str) {
+    let start = out.len();
+    out.push(' ');
+    let mut quote = None;
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' | '\'' => {
+                let quote = *quote.get_or_insert(if c == '"' { '\'' } else { '"' });
+                if quote == c {
+                    out.push('\\');
+                }
+                out.push(c);
+            }
+            '\u{8}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if c < ' ' => write!(out, "\\x{:02X}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    let quote = quote.unwrap_or('"');
+    out.replace_range(start..start + 1, quote.encode_utf8(&mut [0; 4]));
+    out.push(quote);
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> Error {
+        Error::snbt(self.pos, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+        if self.bump() == Some(expected) {
+            Ok(())
+        } else {
+            Err(Error::snbt(self.pos, format!("expected '{expected}'")))
+        }
+    }
+
+    /// Consumes a separating comma, returning whether the sequence continues.
+    fn separator(&mut self, close: char) -> Result<bool> {
+        self.skip_whitespace();
+        match self.bump() {
+            Some(',') => {
+                self.skip_whitespace();
+                if self.peek() == Some(close) {
+                    self.bump();
+                    return Ok(false);
+                }
+                Ok(true)
+            }
+            Some(c) if c == close => Ok(false),
+            _ => Err(Error::snbt(self.pos, format!("expected ',' or '{close}'"))),
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Tag> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nesting too deep"));
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.compound(depth).map(Tag::Compound),
+            Some('[') => self.list(depth),
+            Some('"' | '\'') => self.quoted().map(Tag::String),
+            Some(_) => self.literal(),
+            None => Err(self.error("expected a value")),
+        }
+    }
+
+    fn compound(&mut self, depth: usize) -> Result<Compound> {
+        self.expect('{')?;
+        let mut compound = Compound::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.bump();
+            return Ok(compound);
+        }
+        loop {
+            self.skip_whitespace();
+            let key = match self.peek() {
+                Some('"' | '\'') => self.quoted()?,
+                _ => {
+                    let key = self.unquoted();
+                    if key.is_empty() {
+                        return Err(self.error("expected a key"));
+                    }
+                    String::from(key)
+                }
+            };
+            self.expect(':')?;
+            let value = self.value(depth + 1)?;
+            compound.insert(key, value);
+            if !self.separator('}')? {
+                return Ok(compound);
+            }
+        }
+    }
+
+    fn list(&mut self, depth: usize) -> Result<Tag> {
+        self.expect('[')?;
+        let rest = &self.input[self.pos..];
+        let array = match rest.as_bytes() {
+            [kind @ (b'B' | b'I' | b'L'), b';', ..] => Some(*kind),
+            _ => None,
+        };
+        if let Some(kind) = array {
+            self.pos += 2;
+            return self.array(kind);
+        }
+
+        let mut list = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.bump();
+            return Ok(Tag::List(list));
+        }
+        loop {
+            list.push(self.value(depth + 1)?);
+            if !self.separator(']')? {
+                return Ok(Tag::List(list));
+            }
+        }
+    }
+
+    fn array(&mut self, kind: u8) -> Result<Tag> {
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.bump();
+        } else {
+            loop {
+                self.skip_whitespace();
+                let start = self.pos;
+                let value = match self.literal()? {
+                    Tag::Byte(v) => v as i64,
+                    Tag::Short(v) => v as i64,
+                    Tag::Int(v) => v as i64,
+                    Tag::Long(v) => v,
+                    _ => return Err(Error::snbt(start, "expected an integer")),
+                };
+                values.push((start, value));
+                if !self.separator(']')? {
+                    break;
+                }
+            }
+        }
+
+        fn narrow<T: TryFrom<i64>>(values: Vec<(usize, i64)>) -> Result<Vec<T>> {
+            values
+                .into_iter()
+                .map(|(pos, v)| T::try_from(v).map_err(|_| Error::snbt(pos, "value out of range")))
+                .collect()
+        }
+
+        Ok(match kind {
+            b'B' => Tag::ByteArray(narrow(values)?),
+            b'I' => Tag::IntArray(narrow(values)?),
+            _ => Tag::LongArray(values.into_iter().map(|(_, v)| v).collect()),
+        })
+    }
+
+    fn quoted(&mut self) -> Result<String> {
+        let quote = self.bump().unwrap();
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                Some('\\') => {
+                    let c = match self.bump() {
+                        Some(c @ ('\\' | '"' | '\'')) => c,
+                        Some('b') => '\u{8}',
+                        Some('s') => ' ',
+                        Some('t') => '\t',
+                        Some('n') => '\n',
+                        Some('f') => '\u{c}',
+                        Some('r') => '\r',
+                        Some('x') => self.hex_escape(2)?,
+                        Some('u') => self.hex_escape(4)?,
+                        Some('U') => self.hex_escape(8)?,
+                        _ => return Err(self.error("invalid escape sequence")),
+                    };
+                    out.push(c);
+                }
+                Some(c) if c == quote => return Ok(out),
+                Some(c) => out.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn hex_escape(&mut self, len: usize) -> Result<char> {
+        let digits = self
+            .input
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| self.error("truncated escape sequence"))?;
+        let code =
+            u32::from_str_radix(digits, 16).map_err(|_| self.error("invalid escape sequence"))?;
+        self.pos += len;
+        char::from_u32(code).ok_or_else(|| self.error("invalid code point"))
+    }
+
+    fn unquoted(&mut self) -> &str {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+'))
+        {
+            self.bump();
+        }
+        &self.input[start..self.pos]
+    }
+
+    /// A number, boolean or unquoted string.
+    fn literal(&mut self) -> Result<Tag> {
+        let start = self.pos;
+        let word = self.unquoted();
+        if word.is_empty() {
+            return Err(self.error("expected a value"));
+        }
+        match word {
+            "true" => return Ok(Tag::Byte(1)),
+            "false" => return Ok(Tag::Byte(0)),
+            _ => {}
+        }
+        let first = word.as_bytes()[0];
+        if first.is_ascii_digit() || matches!(first, b'+' | b'-' | b'.') {
+            parse_number(word).ok_or_else(|| Error::snbt(start, "invalid number"))
+        } else {
+            Ok(Tag::String(String::from(word)))
+        }
+    }
+}
+
+fn parse_number(word: &str) -> Option<Tag> {
+    let (negative, body) = match word.as_bytes()[0] {
+        b'-' => (true, &word[1..]),
+        b'+' => (false, &word[1..]),
+        _ => (false, word),
+    };
+    if body.starts_with('_') || body.ends_with('_') {
+        return None;
+    }
+
+    let lower = body.to_ascii_lowercase();
+    let (radix, digits) = if let Some(rest) = lower.strip_prefix("0x") {
+        (16, rest)
+    } else if let Some(rest) = lower.strip_prefix("0b")
+        && rest.starts_with(['0', '1'])
+    {
+        // `0b` alone is the byte 0, not an empty binary literal
+        (2, rest)
+    } else {
+        (10, lower.as_str())
+    };
+
+    if radix == 10 && (digits.contains(['.', 'e']) || digits.ends_with(['f', 'd'])) {
+        return parse_float(negative, digits);
+    }
+
+    // integer suffix, optionally preceded by an explicit signedness
+    let (digits, signedness, size) = split_integer_suffix(digits, radix);
+    let digits: String = digits.chars().filter(|c| *c != '_').collect();
+    if digits.is_empty() {
+        return None;
+    }
+    let magnitude = u64::from_str_radix(&digits, radix).ok()?;
+    let bits = match size {
+        'b' => 8,
+        's' => 16,
+        'l' => 64,
+        _ => 32,
+    };
+    // hex and binary literals are unsigned by default, decimal ones signed
+    let unsigned = signedness.map_or(radix != 10, |s| s == 'u');
+    if unsigned && negative {
+        return None;
+    }
+    let value: i64 = if unsigned {
+        if bits < 64 && magnitude >> bits != 0 {
+            return None;
+        }
+        // reinterpret the bit pattern at the target width
+        match bits {
+            8 => magnitude as u8 as i8 as i64,
+            16 => magnitude as u16 as i16 as i64,
+            32 => magnitude as u32 as i32 as i64,
+            _ => magnitude as i64,
+        }
+    } else {
+        let limit = 1u64 << (bits - 1);
+        if magnitude > limit || (!negative && magnitude == limit) {
+            return None;
+        }
+        if negative {
+            (magnitude as i64).wrapping_neg()
+        } else {
+            magnitude as i64
+        }
+    };
+    Some(match bits {
+        8 => Tag::Byte(value as i8),
+        16 => Tag::Short(value as i16),
+        64 => Tag::Long(value),
+        _ => Tag::Int(value as i32),
+    })
+}
+
+fn split_integer_suffix(digits: &str, radix: u32) -> (&str, Option<char>, char) {
+    let bytes = digits.as_bytes();
+    let Some(&last) = bytes.last() else {
+        return (digits, None, 'i');
+    };
+    // `b` is a hex digit, so a bare `b` suffix is only a suffix outside hex
+    let size = match last {
+        b'l' | b's' | b'i' => last as char,
+        b'b' if radix != 16
+            || bytes.len() >= 2 && matches!(bytes[bytes.len() - 2], b'u' | b's') =>
+        {
+            'b'
+        }
+        _ => return (digits, None, 'i'),
+    };
+    let rest = &digits[..digits.len() - 1];
+    match rest.as_bytes().last() {
+        Some(c @ (b'u' | b's')) => (&rest[..rest.len() - 1], Some(*c as char), size),
+        _ => (rest, None, size),
+    }
+}
+
+fn parse_float(negative: bool, digits: &str) -> Option<Tag> {
+    let (digits, suffix) = match digits.as_bytes().last()? {
+        b'f' => (&digits[..digits.len() - 1], 'f'),
+        b'd' => (&digits[..digits.len() - 1], 'd'),
+        _ => (digits, 'd'),
+    };
+    let digits: String = digits.chars().filter(|c| *c != '_').collect();
+    if !digits.bytes().any(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let sign = if negative { -1.0 } else { 1.0 };
+    Some(match suffix {
+        'f' => Tag::Float(sign as f32 * digits.parse::<f32>().ok()?),
+        _ => Tag::Double(sign * digits.parse::<f64>().ok()?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn test_print_matches_vanilla() {
+        let mut compound = Compound::new();
+        compound.insert("z", 1i8);
+        compound.insert("a b", "it's");
+        compound.insert(
+            "Pos",
+            vec![Tag::Double(0.5), Tag::Double(64.0), Tag::Double(1e-5)],
+        );
+        compound.insert("f", 3.4e38f32);
+        compound.insert("longs", vec![1i64, -2]);
+        compound.insert("bytes", vec![1i8]);
+        compound.insert("quote", "say \"hi\"\n");
+        assert_eq!(
+            to_snbt(&Tag::Compound(compound)),
+            "{Pos:[0.5d,64.0d,1.0E-5d],\"a b\":\"it's\",bytes:[B;1B],f:3.4E38f,\
+             longs:[L;1L,-2L],quote:'say \"hi\"\\n',z:1b}"
+        );
+    }
+
+    #[test]
+    fn test_java_float_formatting() {
+        let cases: [(f64, &str); 7] = [
+            (0.0, "0.0"),
+            (-0.0, "-0.0"),
+            (1.0, "1.0"),
+            (0.001, "0.001"),
+            (1234567.0, "1234567.0"),
+            (1e7, "1.0E7"),
+            (-1.5e-4, "-1.5E-4"),
+        ];
+        for (value, expected) in cases {
+            let mut out = String::new();
+            write_java_float(&mut out, value, format!("{value:e}"));
+            assert_eq!(out, expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_values() {
+        let cases = [
+            ("1b", Tag::Byte(1)),
+            ("true", Tag::Byte(1)),
+            ("-32768s", Tag::Short(-32768)),
+            ("2147483647", Tag::Int(i32::MAX)),
+            ("1_000L", Tag::Long(1000)),
+            ("0xFFub", Tag::Byte(-1)),
+            ("0b101", Tag::Int(5)),
+            ("1.5f", Tag::Float(1.5)),
+            ("1e3", Tag::Double(1000.0)),
+            (".5", Tag::Double(0.5)),
+            ("3d", Tag::Double(3.0)),
+            ("minecraft.stone", Tag::String("minecraft.stone".into())),
+            ("'a\\'b'", Tag::String("a'b".into())),
+            ("\"\\u00e9\\x41\"", Tag::String("éA".into())),
+            ("[I; 1, 2,]", Tag::IntArray(vec![1, 2])),
+            (
+                "[1, \"a\"]",
+                Tag::List(vec![Tag::Int(1), Tag::String("a".into())]),
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse(input).unwrap(), expected, "{input}");
+        }
+        assert!(parse("200sb").is_err());
+        assert!(parse("128b").is_err());
+        assert!(parse("[B; 300]").is_err());
+    }
+
+    #[test]
+    fn test_parse_prefix() {
+        let input = "{a:\"]\"},!b]";
+        let (tag, len) = parse_prefix(input).unwrap();
+        assert_eq!(to_snbt(&tag), "{a:\"]\"}");
+        assert_eq!(&input[len..], ",!b]");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input =
+            "{Count:1b,id:\"minecraft:stone\",nested:{list:[{},{a:[L;]}]},\"weird key\":-1.25f}";
+        let compound = from_snbt(input).unwrap();
+        assert_eq!(to_snbt(&Tag::Compound(compound.clone())), input);
+        assert_eq!(
+            from_snbt(&to_snbt(&Tag::Compound(compound.clone()))).unwrap(),
+            compound
+        );
+    }
+}
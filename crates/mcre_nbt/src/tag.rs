@@ -0,0 +1,394 @@
+use core::{
+    fmt::{self, Display},
+    ops::Index,
+};
+
+use alloc::{string::String, vec::Vec};
+use indexmap::{IndexMap, map};
+use rustc_hash::FxBuildHasher;
+
+use crate::{Error, Result, snbt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum TagType {
+    End = 0,
+    Byte = 1,
+    Short = 2,
+    Int = 3,
+    Long = 4,
+    Float = 5,
+    Double = 6,
+    ByteArray = 7,
+    String = 8,
+    List = 9,
+    Compound = 10,
+    IntArray = 11,
+    LongArray = 12,
+}
+
+impl TagType {
+    pub fn from_id(id: u8) -> Result<Self> {
+        Ok(match id {
+            0 => Self::End,
+            1 => Self::Byte,
+            2 => Self::Short,
+            3 => Self::Int,
+            4 => Self::Long,
+            5 => Self::Float,
+            6 => Self::Double,
+            7 => Self::ByteArray,
+            8 => Self::String,
+            9 => Self::List,
+            10 => Self::Compound,
+            11 => Self::IntArray,
+            12 => Self::LongArray,
+            _ => return Err(Error::InvalidTagType(id)),
+        })
+    }
+
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+}
+
+/// An owned NBT value.
+///
+/// Lists are stored as plain `Vec<Tag>`. Vanilla (since 1.21.5) accepts lists
+/// whose elements have different types; those are written by wrapping every
+/// element in a compound with an empty key, and unwrapped again on read.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<Tag>),
+    Compound(Compound),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Tag {
+    pub fn tag_type(&self) -> TagType {
+        match self {
+            Self::Byte(_) => TagType::Byte,
+            Self::Short(_) => TagType::Short,
+            Self::Int(_) => TagType::Int,
+            Self::Long(_) => TagType::Long,
+            Self::Float(_) => TagType::Float,
+            Self::Double(_) => TagType::Double,
+            Self::ByteArray(_) => TagType::ByteArray,
+            Self::String(_) => TagType::String,
+            Self::List(_) => TagType::List,
+            Self::Compound(_) => TagType::Compound,
+            Self::IntArray(_) => TagType::IntArray,
+            Self::LongArray(_) => TagType::LongArray,
+        }
+    }
+
+    /// Numeric value of any numeric tag, the way `NumericTag::getAsLong` sees it.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Byte(v) => Some(*v as i64),
+            Self::Short(v) => Some(*v as i64),
+            Self::Int(v) => Some(*v as i64),
+            Self::Long(v) => Some(*v),
+            Self::Float(v) => Some(*v as i64),
+            Self::Double(v) => Some(*v as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Byte(v) => Some(*v as f64),
+            Self::Short(v) => Some(*v as f64),
+            Self::Int(v) => Some(*v as f64),
+            Self::Long(v) => Some(*v as f64),
+            Self::Float(v) => Some(*v as f64),
+            Self::Double(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        self.as_i64().map(|v| v != 0)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        if let Self::String(s) = self {
+            Some(s)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Tag]> {
+        if let Self::List(list) = self {
+            Some(list)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_compound(&self) -> Option<&Compound> {
+        if let Self::Compound(compound) = self {
+            Some(compound)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_compound_mut(&mut self) -> Option<&mut Compound> {
+        if let Self::Compound(compound) = self {
+            Some(compound)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_byte_array(&self) -> Option<&[i8]> {
+        if let Self::ByteArray(array) = self {
+            Some(array)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_int_array(&self) -> Option<&[i32]> {
+        if let Self::IntArray(array) = self {
+            Some(array)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_long_array(&self) -> Option<&[i64]> {
+        if let Self::LongArray(array) = self {
+            Some(array)
+        } else {
+            None
+        }
+    }
+
+    pub fn into_compound(self) -> Option<Compound> {
+        if let Self::Compound(compound) = self {
+            Some(compound)
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&snbt::to_snbt(self))
+    }
+}
+
+macro_rules! impl_from {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$ty> for Tag {
+                fn from(value: $ty) -> Self {
+                    Self::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from! {
+    i8 => Byte,
+    i16 => Short,
+    i32 => Int,
+    i64 => Long,
+    f32 => Float,
+    f64 => Double,
+    String => String,
+    &str => String,
+    Vec<Tag> => List,
+    Compound => Compound,
+    Vec<i8> => ByteArray,
+    Vec<i32> => IntArray,
+    Vec<i64> => LongArray,
+}
+
+impl From<bool> for Tag {
+    fn from(value: bool) -> Self {
+        Self::Byte(value as i8)
+    }
+}
+
+/// An ordered map of named tags.
+///
+/// Insertion order is preserved so that read → write round trips are stable,
+/// even though vanilla itself does not guarantee any order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Compound(IndexMap<String, Tag, FxBuildHasher>);
+
+impl Compound {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(IndexMap::with_capacity_and_hasher(capacity, FxBuildHasher))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Tag> {
+        self.0.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Tag> {
+        self.0.get_mut(key)
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Tag>) -> Option<Tag> {
+        self.0.insert(key.into(), value.into())
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Tag> {
+        self.0.shift_remove(key)
+    }
+
+    pub fn iter(&self) -> map::Iter<'_, String, Tag> {
+        self.0.iter()
+    }
+
+    pub fn keys(&self) -> map::Keys<'_, String, Tag> {
+        self.0.keys()
+    }
+
+    pub fn get_byte(&self, key: &str) -> Option<i8> {
+        match self.get(key)? {
+            Tag::Byte(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn get_short(&self, key: &str) -> Option<i16> {
+        match self.get(key)? {
+            Tag::Short(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn get_int(&self, key: &str) -> Option<i32> {
+        match self.get(key)? {
+            Tag::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn get_long(&self, key: &str) -> Option<i64> {
+        match self.get(key)? {
+            Tag::Long(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn get_float(&self, key: &str) -> Option<f32> {
+        match self.get(key)? {
+            Tag::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn get_double(&self, key: &str) -> Option<f64> {
+        match self.get(key)? {
+            Tag::Double(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Any numeric tag read as a boolean, matching `CompoundTag::getBoolean`.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.get(key)?.as_bool()
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key)?.as_str()
+    }
+
+    pub fn get_list(&self, key: &str) -> Option<&[Tag]> {
+        self.get(key)?.as_list()
+    }
+
+    pub fn get_compound(&self, key: &str) -> Option<&Compound> {
+        self.get(key)?.as_compound()
+    }
+
+    pub fn get_byte_array(&self, key: &str) -> Option<&[i8]> {
+        self.get(key)?.as_byte_array()
+    }
+
+    pub fn get_int_array(&self, key: &str) -> Option<&[i32]> {
+        self.get(key)?.as_int_array()
+    }
+
+    pub fn get_long_array(&self, key: &str) -> Option<&[i64]> {
+        self.get(key)?.as_long_array()
+    }
+}
+
+impl Index<&str> for Compound {
+    type Output = Tag;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        &self.0[key]
+    }
+}
+
+impl<K: Into<String>, V: Into<Tag>> FromIterator<(K, V)> for Compound {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut compound = Compound::new();
+        for (key, value) in iter {
+            compound.insert(key, value);
+        }
+        compound
+    }
+}
+
+impl<K: Into<String>, V: Into<Tag>> Extend<(K, V)> for Compound {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl IntoIterator for Compound {
+    type Item = (String, Tag);
+    type IntoIter = map::IntoIter<String, Tag>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Compound {
+    type Item = (&'a String, &'a Tag);
+    type IntoIter = map::Iter<'a, String, Tag>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
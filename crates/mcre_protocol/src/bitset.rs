@@ -0,0 +1,41 @@
+use alloc::vec::Vec;
+
+use crate::{Result, buf};
+
+/// A `java.util.BitSet` as written by `FriendlyByteBuf.writeBitSet`: a
+/// varint-prefixed array of longs, least significant bit first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitSet(Vec<u64>);
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn read(buf: &mut &[u8]) -> Result<Self> {
+        buf::read_long_array(buf).map(Self)
+    }
+
+    pub fn write(&self, out: &mut Vec<u8>) {
+        // Java trims trailing zero words
+        let len = self.0.iter().rposition(|w| *w != 0).map_or(0, |i| i + 1);
+        buf::write_varint(out, len as i32);
+        for word in &self.0[..len] {
+            out.extend_from_slice(&word.to_be_bytes());
+        }
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        self.0
+            .get(index / 64)
+            .is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+
+    pub fn set(&mut self, index: usize) {
+        let word = index / 64;
+        if word >= self.0.len() {
+            self.0.resize(word + 1, 0);
+        }
+        self.0[word] |= 1 << (index % 64);
+    }
+}
@@ -0,0 +1,280 @@
+//! Primitive readers and writers for packet bodies, matching `FriendlyByteBuf`.
+//!
+//! Readers take `&mut &[u8]` and advance the slice past what they consumed.
+
+use alloc::vec::Vec;
+use mcre_core::BlockPos;
+use mcre_nbt::{Compound, Tag};
+
+use crate::{Error, Result};
+
+/// The default limit of `readUtf()`.
+pub const MAX_STRING_CHARS: usize = 32767;
+
+pub fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if buf.len() < len {
+        return Err(Error::UnexpectedEof);
+    }
+    let (head, tail) = buf.split_at(len);
+    *buf = tail;
+    Ok(head)
+}
+
+fn read_array<const N: usize>(buf: &mut &[u8]) -> Result<[u8; N]> {
+    Ok(take(buf, N)?.try_into().unwrap())
+}
+
+pub fn read_u8(buf: &mut &[u8]) -> Result<u8> {
+    Ok(read_array::<1>(buf)?[0])
+}
+
+pub fn read_bool(buf: &mut &[u8]) -> Result<bool> {
+    Ok(read_u8(buf)? != 0)
+}
+
+pub fn read_i16(buf: &mut &[u8]) -> Result<i16> {
+    Ok(i16::from_be_bytes(read_array(buf)?))
+}
+
+pub fn read_i32(buf: &mut &[u8]) -> Result<i32> {
+    Ok(i32::from_be_bytes(read_array(buf)?))
+}
+
+pub fn read_i64(buf: &mut &[u8]) -> Result<i64> {
+    Ok(i64::from_be_bytes(read_array(buf)?))
+}
+
+pub fn read_f32(buf: &mut &[u8]) -> Result<f32> {
+    Ok(f32::from_be_bytes(read_array(buf)?))
+}
+
+pub fn read_f64(buf: &mut &[u8]) -> Result<f64> {
+    Ok(f64::from_be_bytes(read_array(buf)?))
+}
+
+/// A UUID as two longs, returned as the four ints NBT stores it as.
+pub fn read_uuid(buf: &mut &[u8]) -> Result<[i32; 4]> {
+    Ok([
+        read_i32(buf)?,
+        read_i32(buf)?,
+        read_i32(buf)?,
+        read_i32(buf)?,
+    ])
+}
+
+pub fn read_varint(buf: &mut &[u8]) -> Result<i32> {
+    let mut value = 0u32;
+    for i in 0..5 {
+        let byte = read_u8(buf)?;
+        value |= ((byte & 0x7F) as u32) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Ok(value as i32);
+        }
+    }
+    Err(Error::VarIntTooLong)
+}
+
+pub fn read_varlong(buf: &mut &[u8]) -> Result<i64> {
+    let mut value = 0u64;
+    for i in 0..10 {
+        let byte = read_u8(buf)?;
+        value |= ((byte & 0x7F) as u64) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Ok(value as i64);
+        }
+    }
+    Err(Error::VarIntTooLong)
+}
+
+/// A varint that must not be negative, used for lengths and counts.
+pub fn read_len(buf: &mut &[u8]) -> Result<usize> {
+    let len = read_varint(buf)?;
+    usize::try_from(len).map_err(|_| Error::NegativeLength(len))
+}
+
+/// A varint-prefixed byte array.
+pub fn read_byte_array<'a>(buf: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let len = read_len(buf)?;
+    take(buf, len)
+}
+
+/// A varint-prefixed UTF-8 string of at most `max_chars` UTF-16 units, as in
+/// `readUtf(int)`.
+pub fn read_string<'a>(buf: &mut &'a [u8], max_chars: usize) -> Result<&'a str> {
+    let len = read_len(buf)?;
+    if len > max_chars * 3 {
+        return Err(Error::Invalid("string too long"));
+    }
+    let s = core::str::from_utf8(take(buf, len)?).map_err(|_| Error::Invalid("UTF-8 string"))?;
+    if s.encode_utf16().count() > max_chars {
+        return Err(Error::Invalid("string too long"));
+    }
+    Ok(s)
+}
+
+/// A namespaced identifier such as `minecraft:stone`.
+pub fn read_identifier<'a>(buf: &mut &'a [u8]) -> Result<&'a str> {
+    read_string(buf, MAX_STRING_CHARS)
+}
+
+/// `len` longs with no length prefix, as in `readFixedSizeLongArray`.
+pub fn read_fixed_long_array(buf: &mut &[u8], len: usize) -> Result<Vec<u64>> {
+    let bytes = take(buf, len.checked_mul(8).ok_or(Error::UnexpectedEof)?)?;
+    Ok(bytes
+        .chunks_exact(8)
+        .map(|c| u64::from_be_bytes(c.try_into().unwrap()))
+        .collect())
+}
+
+pub fn read_long_array(buf: &mut &[u8]) -> Result<Vec<u64>> {
+    let len = read_len(buf)?;
+    read_fixed_long_array(buf, len)
+}
+
+/// A block position packed as `x << 38 | z << 12 | y`.
+pub fn read_block_pos(buf: &mut &[u8]) -> Result<BlockPos> {
+    let packed = read_i64(buf)?;
+    Ok(BlockPos::new(
+        (packed >> 38) as i32,
+        ((packed << 52) >> 52) as i32,
+        ((packed << 26) >> 38) as i32,
+    ))
+}
+
+/// A nameless network NBT tag; `None` for a lone `TAG_End`.
+pub fn read_nbt(buf: &mut &[u8]) -> Result<Option<Tag>> {
+    Ok(mcre_nbt::binary::read_nameless(buf)?)
+}
+
+pub fn read_optional_compound(buf: &mut &[u8]) -> Result<Option<Compound>> {
+    match read_nbt(buf)? {
+        Some(Tag::Compound(compound)) => Ok(Some(compound)),
+        Some(_) => Err(Error::Invalid("compound tag")),
+        None => Ok(None),
+    }
+}
+
+/// Fails if a packet body was not fully consumed.
+pub fn finish(buf: &[u8]) -> Result<()> {
+    if buf.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::TrailingBytes(buf.len()))
+    }
+}
+
+pub fn write_varint(out: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7F == 0 {
+            out.push(value as u8);
+            return;
+        }
+        out.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+pub fn write_varlong(out: &mut Vec<u8>, value: i64) {
+    let mut value = value as u64;
+    loop {
+        if value & !0x7F == 0 {
+            out.push(value as u8);
+            return;
+        }
+        out.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+pub fn write_string(out: &mut Vec<u8>, value: &str) {
+    write_varint(out, value.len() as i32);
+    out.extend_from_slice(value.as_bytes());
+}
+
+pub fn write_bool(out: &mut Vec<u8>, value: bool) {
+    out.push(value as u8);
+}
+
+pub fn write_i32(out: &mut Vec<u8>, value: i32) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+pub fn write_i64(out: &mut Vec<u8>, value: i64) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+pub fn write_f32(out: &mut Vec<u8>, value: f32) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+pub fn write_f64(out: &mut Vec<u8>, value: f64) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+pub fn write_uuid(out: &mut Vec<u8>, uuid: [i32; 4]) {
+    uuid.into_iter().for_each(|int| write_i32(out, int));
+}
+
+pub fn write_block_pos(out: &mut Vec<u8>, pos: BlockPos) {
+    let packed = ((pos.x as i64 & 0x3FF_FFFF) << 38)
+        | ((pos.z as i64 & 0x3FF_FFFF) << 12)
+        | (pos.y as i64 & 0xFFF);
+    out.extend_from_slice(&packed.to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint_round_trip() {
+        for value in [0, 1, 127, 128, 255, 25565, 2097151, i32::MAX, -1, i32::MIN] {
+            let mut out = Vec::new();
+            write_varint(&mut out, value);
+            let mut buf = out.as_slice();
+            assert_eq!(read_varint(&mut buf), Ok(value));
+            assert!(buf.is_empty());
+        }
+        for value in [0, 2147483648, i64::MAX, -1, i64::MIN] {
+            let mut out = Vec::new();
+            write_varlong(&mut out, value);
+            assert_eq!(read_varlong(&mut out.as_slice()), Ok(value));
+        }
+        assert_eq!(
+            read_varint(&mut [0xFF; 6].as_slice()),
+            Err(Error::VarIntTooLong)
+        );
+    }
+
+    #[test]
+    fn test_string_round_trip() {
+        let mut out = Vec::new();
+        write_string(&mut out, "minecraft:worldgen/biome");
+        write_string(&mut out, "§a");
+        let mut buf = out.as_slice();
+        assert_eq!(read_identifier(&mut buf), Ok("minecraft:worldgen/biome"));
+        assert_eq!(read_string(&mut buf, 2), Ok("§a"));
+        assert!(buf.is_empty());
+
+        let mut out = Vec::new();
+        write_string(&mut out, "abc");
+        assert_eq!(
+            read_string(&mut out.as_slice(), 2),
+            Err(Error::Invalid("string too long"))
+        );
+    }
+
+    #[test]
+    fn test_block_pos_round_trip() {
+        for pos in [
+            BlockPos::new(0, 0, 0),
+            BlockPos::new(-1, -64, -1),
+            BlockPos::new(33_554_431, 2047, -33_554_432),
+        ] {
+            let mut out = Vec::new();
+            write_block_pos(&mut out, pos);
+            assert_eq!(read_block_pos(&mut out.as_slice()), Ok(pos));
+        }
+    }
+}
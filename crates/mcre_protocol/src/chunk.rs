@@ -0,0 +1,620 @@
+//! Decoders for the clientbound packets that carry chunk contents.
+//!
+//! Each decoder takes a packet body (after the packet id) and produces the
+//! [`WorldEvent`]s it describes, so that a server-backed world goes through
+//! the same `World::apply` path as a file-backed one.
+
+use alloc::vec::Vec;
+use mcre_core::{BlockPos, ChunkPos};
+use mcre_world::{
+    BiomeId, BitStorage, Block, BlockEntity, BlockEntityKind, BlockState, ChunkData, ChunkSection,
+    HeightmapKind, LightUpdate, NibbleArray, PalettedContainer, SECTION_BIOMES, SECTION_BLOCKS,
+    StateIdTable, WorldEvent, WorldHeight,
+};
+
+use crate::{BitSet, Error, Result, buf};
+
+/// What the decoders need to know about the dimension the chunks belong to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkContext {
+    pub height: WorldHeight,
+    /// Number of entries in the `worldgen/biome` registry.
+    pub biome_count: usize,
+    /// How the server numbers block states, if not like the current version.
+    pub state_ids: Option<&'static StateIdTable>,
+}
+
+impl ChunkContext {
+    /// The context for a server speaking `protocol`, which numbers block
+    /// states like the current version unless a table was generated for it.
+    pub fn with_protocol(self, protocol: i32) -> Self {
+        Self {
+            state_ids: StateIdTable::for_protocol(protocol),
+            ..self
+        }
+    }
+
+    fn state_count(&self) -> usize {
+        match self.state_ids {
+            Some(table) => table.state_count as usize,
+            None => u16::from(BlockState::MAX) as usize + 1,
+        }
+    }
+}
+
+/// Bits needed to index `count` entries, as in `Mth.ceillog2`.
+fn ceil_log2(count: usize) -> u8 {
+    (usize::BITS - count.saturating_sub(1).leading_zeros()) as u8
+}
+
+/// How `PalettedContainer.Strategy` maps the bits byte to a palette.
+enum Layout {
+    Single,
+    Indirect(u8),
+    Direct(u8),
+}
+
+fn block_layout(bits: u8, ctx: &ChunkContext) -> Layout {
+    match bits {
+        0 => Layout::Single,
+        1..=4 => Layout::Indirect(4),
+        5..=8 => Layout::Indirect(bits),
+        _ => Layout::Direct(ceil_log2(ctx.state_count())),
+    }
+}
+
+fn biome_layout(bits: u8, biome_count: usize) -> Layout {
+    match bits {
+        0 => Layout::Single,
+        1..=3 => Layout::Indirect(bits),
+        _ => Layout::Direct(ceil_log2(biome_count)),
+    }
+}
+
+fn read_container<T: Copy + Eq>(
+    buf: &mut &[u8],
+    layout: Layout,
+    size: usize,
+    value: impl Fn(i32) -> Result<T>,
+) -> Result<PalettedContainer<T>> {
+    let invalid = Error::Invalid("paletted container");
+    match layout {
+        Layout::Single => {
+            let single = value(buf::read_varint(buf)?)?;
+            Ok(PalettedContainer::single(single, size))
+        }
+        Layout::Indirect(bits) => {
+            let len = buf::read_len(buf)?;
+            let palette = (0..len)
+                .map(|_| value(buf::read_varint(buf)?))
+                .collect::<Result<Vec<T>>>()?;
+            let data = buf::read_fixed_long_array(buf, BitStorage::longs_needed(bits, size))?;
+            let storage = BitStorage::from_raw(bits, size, data).ok_or(invalid.clone())?;
+            PalettedContainer::from_palette(palette, storage).ok_or(invalid)
+        }
+        Layout::Direct(bits) => {
+            let data = buf::read_fixed_long_array(buf, BitStorage::longs_needed(bits, size))?;
+            let storage = BitStorage::from_raw(bits, size, data).ok_or(invalid.clone())?;
+            let values = storage
+                .iter()
+                .map(|id| value(id as i32))
+                .collect::<Result<Vec<T>>>()?;
+            PalettedContainer::from_values(values).ok_or(invalid)
+        }
+    }
+}
+
+/// States of blocks the current version no longer has become air, so that
+/// the rest of the chunk still shows.
+fn block_state(id: i32, ctx: &ChunkContext) -> Result<BlockState> {
+    let id = u16::try_from(id)
+        .ok()
+        .filter(|id| (*id as usize) < ctx.state_count())
+        .ok_or(Error::Invalid("block state id"))?;
+    Ok(match ctx.state_ids {
+        Some(table) => table.get(id).unwrap_or(Block::AIR.default_state()),
+        None => BlockState::from(id),
+    })
+}
+
+fn biome(id: i32, biome_count: usize) -> Result<BiomeId> {
+    u16::try_from(id)
+        .ok()
+        .filter(|id| (*id as usize) < biome_count)
+        .map(BiomeId)
+        .ok_or(Error::Invalid("biome id"))
+}
+
+fn read_biomes(buf: &mut &[u8], ctx: &ChunkContext) -> Result<PalettedContainer<BiomeId>> {
+    let bits = buf::read_u8(buf)?;
+    read_container(
+        buf,
+        biome_layout(bits, ctx.biome_count),
+        SECTION_BIOMES,
+        |id| biome(id, ctx.biome_count),
+    )
+}
+
+/// Reads one `LevelChunkSection`: block count, block states and biomes.
+pub fn read_section(buf: &mut &[u8], ctx: &ChunkContext) -> Result<ChunkSection> {
+    // recomputed from the states rather than trusted
+    let _block_count = buf::read_i16(buf)?;
+    let bits = buf::read_u8(buf)?;
+    let states = read_container(buf, block_layout(bits, ctx), SECTION_BLOCKS, |id| {
+        block_state(id, ctx)
+    })?;
+    let biomes = read_biomes(buf, ctx)?;
+    ChunkSection::new(states, biomes).ok_or(Error::Invalid("chunk section"))
+}
+
+/// Reads the light data shared by the chunk and light update packets.
+pub fn read_light(buf: &mut &[u8], ctx: &ChunkContext) -> Result<LightUpdate> {
+    let sections = ctx.height.section_count() + 2;
+    let sky_mask = BitSet::read(buf)?;
+    let block_mask = BitSet::read(buf)?;
+    let empty_sky_mask = BitSet::read(buf)?;
+    let empty_block_mask = BitSet::read(buf)?;
+
+    let read_layers = |buf: &mut &[u8], mask: &BitSet, empty: &BitSet| {
+        let count = buf::read_len(buf)?;
+        let mut updates = (0..count)
+            .map(|_| {
+                NibbleArray::from_bytes(buf::read_byte_array(buf)?)
+                    .ok_or(Error::Invalid("light array"))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter();
+        let layers = (0..sections)
+            .map(|i| {
+                if mask.get(i) {
+                    updates.next().ok_or(Error::Invalid("light mask")).map(Some)
+                } else if empty.get(i) {
+                    Ok(Some(NibbleArray::new()))
+                } else {
+                    Ok(None)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if updates.next().is_some() {
+            return Err(Error::Invalid("light mask"));
+        }
+        Ok(layers)
+    };
+
+    let sky = read_layers(buf, &sky_mask, &empty_sky_mask)?;
+    let block = read_layers(buf, &block_mask, &empty_block_mask)?;
+    Ok(LightUpdate { sky, block })
+}
+
+fn read_chunk_data(buf: &mut &[u8], pos: ChunkPos, ctx: &ChunkContext) -> Result<ChunkData> {
+    let heightmap_count = buf::read_len(buf)?;
+    let heightmaps = (0..heightmap_count)
+        .map(|_| {
+            let kind = HeightmapKind::from_id(buf::read_varint(buf)? as u32)
+                .ok_or(Error::Invalid("heightmap type"))?;
+            Ok((kind, buf::read_long_array(buf)?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut data = buf::read_byte_array(buf)?;
+    let sections = (0..ctx.height.section_count())
+        .map(|_| read_section(&mut data, ctx))
+        .collect::<Result<Vec<_>>>()?;
+    // the buffer is sized generously by the server; leftovers are zero padding
+
+    let block_entity_count = buf::read_len(buf)?;
+    let block_entities = (0..block_entity_count)
+        .map(|_| {
+            let packed_xz = buf::read_u8(buf)?;
+            let y = buf::read_i16(buf)?;
+            let kind = BlockEntityKind::Id(buf::read_varint(buf)? as u32);
+            let data = buf::read_optional_compound(buf)?;
+            Ok(BlockEntity {
+                pos: BlockPos::new(
+                    pos.min_block_x() + (packed_xz >> 4) as i32,
+                    y as i32,
+                    pos.min_block_z() + (packed_xz & 15) as i32,
+                ),
+                kind,
+                data,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(ChunkData {
+        height: ctx.height,
+        sections,
+        heightmaps,
+        block_entities,
+        light: LightUpdate::default(),
+    })
+}
+
+/// `Level Chunk with Light`: a full chunk column.
+pub fn decode_level_chunk_with_light(mut body: &[u8], ctx: &ChunkContext) -> Result<WorldEvent> {
+    let buf = &mut body;
+    let pos = ChunkPos::new(buf::read_i32(buf)?, buf::read_i32(buf)?);
+    let mut chunk = read_chunk_data(buf, pos, ctx)?;
+    chunk.light = read_light(buf, ctx)?;
+    buf::finish(buf)?;
+    Ok(WorldEvent::ChunkLoaded { pos, chunk })
+}
+
+/// `Light Update`: new light for some sections of a loaded chunk.
+pub fn decode_light_update(mut body: &[u8], ctx: &ChunkContext) -> Result<WorldEvent> {
+    let buf = &mut body;
+    let pos = ChunkPos::new(buf::read_varint(buf)?, buf::read_varint(buf)?);
+    let light = read_light(buf, ctx)?;
+    buf::finish(buf)?;
+    Ok(WorldEvent::LightUpdated { pos, light })
+}
+
+/// `Chunks Biomes`: replacement biomes for several loaded chunks.
+pub fn decode_chunks_biomes(mut body: &[u8], ctx: &ChunkContext) -> Result<Vec<WorldEvent>> {
+    let buf = &mut body;
+    let count = buf::read_len(buf)?;
+    let events = (0..count)
+        .map(|_| {
+            let pos = ChunkPos::from_long(buf::read_i64(buf)?);
+            let mut data = buf::read_byte_array(buf)?;
+            let biomes = (0..ctx.height.section_count())
+                .map(|_| read_biomes(&mut data, ctx))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(WorldEvent::BiomesSet { pos, biomes })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    buf::finish(buf)?;
+    Ok(events)
+}
+
+/// `Section Blocks Update`: several block changes within one section.
+pub fn decode_section_blocks_update(
+    mut body: &[u8],
+    ctx: &ChunkContext,
+) -> Result<Vec<WorldEvent>> {
+    let buf = &mut body;
+    // x and z take 22 bits each, y the low 20
+    let section = buf::read_i64(buf)?;
+    let section_x = (section >> 42) as i32;
+    let section_y = ((section << 44) >> 44) as i32;
+    let section_z = ((section << 22) >> 42) as i32;
+
+    let count = buf::read_len(buf)?;
+    let events = (0..count)
+        .map(|_| {
+            let entry = buf::read_varlong(buf)?;
+            let local = (entry & 0xFFF) as i32;
+            let state = i32::try_from(entry >> 12).map_err(|_| Error::Invalid("block state id"))?;
+            Ok(WorldEvent::BlockSet {
+                pos: BlockPos::new(
+                    (section_x << 4) + ((local >> 8) & 15),
+                    (section_y << 4) + (local & 15),
+                    (section_z << 4) + ((local >> 4) & 15),
+                ),
+                state: block_state(state, ctx)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    buf::finish(buf)?;
+    Ok(events)
+}
+
+/// `Block Update`: a single block change.
+pub fn decode_block_update(mut body: &[u8], ctx: &ChunkContext) -> Result<WorldEvent> {
+    let buf = &mut body;
+    let pos = buf::read_block_pos(buf)?;
+    let state = block_state(buf::read_varint(buf)?, ctx)?;
+    buf::finish(buf)?;
+    Ok(WorldEvent::BlockSet { pos, state })
+}
+
+/// `Forget Level Chunk`: the server no longer tracks a chunk for us.
+pub fn decode_forget_level_chunk(mut body: &[u8]) -> Result<WorldEvent> {
+    let buf = &mut body;
+    let pos = ChunkPos::from_long(buf::read_i64(buf)?);
+    buf::finish(buf)?;
+    Ok(WorldEvent::ChunkUnloaded { pos })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use mcre_nbt::Compound;
+    use mcre_world::World;
+
+    use super::*;
+    use crate::buf::{write_block_pos, write_varint, write_varlong};
+
+    const CTX: ChunkContext = ChunkContext {
+        height: WorldHeight::OVERWORLD,
+        biome_count: 64,
+        state_ids: None,
+    };
+
+    fn stone() -> BlockState {
+        Block::STONE.default_state()
+    }
+
+    fn write_longs(out: &mut Vec<u8>, longs: &[u64]) {
+        for long in longs {
+            out.extend_from_slice(&long.to_be_bytes());
+        }
+    }
+
+    fn write_single(out: &mut Vec<u8>, id: i32) {
+        out.push(0);
+        write_varint(out, id);
+    }
+
+    /// A section of air with stone along the bottom row of x, indirect with
+    /// a 4-bit palette, and biome 3 everywhere.
+    fn write_indirect_section(out: &mut Vec<u8>) {
+        out.extend_from_slice(&16i16.to_be_bytes());
+        out.push(4);
+        write_varint(out, 2);
+        write_varint(out, 0);
+        write_varint(out, u16::from(stone()) as i32);
+        let mut storage = BitStorage::new(4, SECTION_BLOCKS);
+        for x in 0..16 {
+            storage.set(x, 1);
+        }
+        write_longs(out, storage.raw());
+        write_single(out, 3);
+    }
+
+    /// A section using the global palette for both blocks and biomes.
+    fn write_direct_section(out: &mut Vec<u8>) {
+        out.extend_from_slice(&4096i16.to_be_bytes());
+        out.push(15);
+        let mut storage = BitStorage::new(15, SECTION_BLOCKS);
+        for i in 0..SECTION_BLOCKS {
+            storage.set(i, u16::from(stone()) as u32 + (i % 2) as u32);
+        }
+        write_longs(out, storage.raw());
+        out.push(6);
+        let mut storage = BitStorage::new(6, SECTION_BIOMES);
+        for i in 0..SECTION_BIOMES {
+            storage.set(i, i as u32);
+        }
+        write_longs(out, storage.raw());
+    }
+
+    fn chunk_packet() -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&2i32.to_be_bytes());
+        out.extend_from_slice(&(-3i32).to_be_bytes());
+
+        // heightmaps
+        write_varint(&mut out, 1);
+        write_varint(&mut out, HeightmapKind::MotionBlocking as i32);
+        write_varint(&mut out, 37);
+        write_longs(&mut out, &[7; 37]);
+
+        let mut data = Vec::new();
+        write_indirect_section(&mut data);
+        write_direct_section(&mut data);
+        for _ in 2..CTX.height.section_count() {
+            data.extend_from_slice(&0i16.to_be_bytes());
+            write_single(&mut data, 0);
+            write_single(&mut data, 1);
+        }
+        write_varint(&mut out, data.len() as i32);
+        out.extend_from_slice(&data);
+
+        // one block entity at local (1, -60, 2)
+        write_varint(&mut out, 1);
+        out.push(0x12);
+        out.extend_from_slice(&(-60i16).to_be_bytes());
+        write_varint(&mut out, 7);
+        let mut compound = Compound::new();
+        compound.insert("CustomName", "x");
+        out.extend_from_slice(&mcre_nbt::to_network_bytes(&compound).unwrap());
+
+        // sky light for section index 1 (y = -64), empty block light below it
+        let mut sky = BitSet::new();
+        sky.set(1);
+        let mut empty_block = BitSet::new();
+        empty_block.set(0);
+        sky.write(&mut out);
+        BitSet::new().write(&mut out);
+        BitSet::new().write(&mut out);
+        empty_block.write(&mut out);
+        write_varint(&mut out, 1);
+        write_varint(&mut out, 2048);
+        out.extend_from_slice(&[0xFF; 2048]);
+        write_varint(&mut out, 0);
+        out
+    }
+
+    #[test]
+    fn test_level_chunk_with_light() {
+        let event = decode_level_chunk_with_light(&chunk_packet(), &CTX).unwrap();
+        let mut world = World::new();
+        world.apply(event);
+        let view = world.view();
+
+        let chunk = view.get_chunk(ChunkPos::new(2, -3)).unwrap();
+        assert_eq!(chunk.sections().len(), 24);
+        assert_eq!(chunk.sections()[0].block_count(), 16);
+        assert_eq!(chunk.sections()[1].block_count(), 4096);
+        assert!(chunk.sections()[2].is_empty());
+        assert_eq!(
+            chunk
+                .heightmap(HeightmapKind::MotionBlocking)
+                .map(|heightmap| heightmap.packed()),
+            Some(&[7; 37][..])
+        );
+
+        assert_eq!(
+            view.get_block(BlockPos::new(32 + 15, -64, -48)),
+            Some(stone())
+        );
+        assert!(
+            view.get_block(BlockPos::new(32, -63, -48))
+                .unwrap()
+                .is_air()
+        );
+        assert_eq!(
+            view.get_block(BlockPos::new(33, -48, -48)),
+            Some(BlockState::from(u16::from(stone()) + 1))
+        );
+        assert_eq!(
+            view.get_biome(BlockPos::new(32, -64, -48)),
+            Some(BiomeId(3))
+        );
+        assert_eq!(
+            view.get_biome(BlockPos::new(36, -48, -48)),
+            Some(BiomeId(1))
+        );
+        assert_eq!(view.get_biome(BlockPos::new(32, 0, -48)), Some(BiomeId(1)));
+
+        let block_entity = chunk.block_entity(BlockPos::new(33, -60, -46)).unwrap();
+        assert_eq!(block_entity.kind, BlockEntityKind::Id(7));
+        assert_eq!(
+            block_entity.data.as_ref().unwrap().get_str("CustomName"),
+            Some("x")
+        );
+
+        assert_eq!(chunk.sky_light(-4).unwrap().get(3, 5, 7), 15);
+        assert!(chunk.sky_light(-5).is_none());
+        assert_eq!(chunk.block_light(-5).unwrap().get(0, 0, 0), 0);
+        assert!(chunk.block_light(-4).is_none());
+    }
+
+    #[test]
+    fn test_block_updates() {
+        let mut world = World::new();
+        world.apply(decode_level_chunk_with_light(&chunk_packet(), &CTX).unwrap());
+
+        // section (2, -4, -3), two entries
+        let section = (2i64 << 42) | (((-3i64) & 0x3F_FFFF) << 20) | ((-4i64) & 0xF_FFFF);
+        let mut body = Vec::new();
+        body.extend_from_slice(&section.to_be_bytes());
+        write_varint(&mut body, 2);
+        write_varlong(
+            &mut body,
+            ((u16::from(stone()) as i64) << 12) | (1 << 8) | (2 << 4) | 3,
+        );
+        write_varlong(&mut body, 0);
+        let events = decode_section_blocks_update(&body, &CTX).unwrap();
+        assert_eq!(
+            events[0],
+            WorldEvent::BlockSet {
+                pos: BlockPos::new(33, -61, -46),
+                state: stone()
+            }
+        );
+        for event in events {
+            world.apply(event);
+        }
+        assert_eq!(
+            world.view().get_block(BlockPos::new(33, -61, -46)),
+            Some(stone())
+        );
+        assert!(
+            world
+                .view()
+                .get_block(BlockPos::new(32, -64, -48))
+                .unwrap()
+                .is_air()
+        );
+        assert_eq!(
+            world
+                .view()
+                .get_chunk(ChunkPos::new(2, -3))
+                .unwrap()
+                .sections()[0]
+                .block_count(),
+            16
+        );
+
+        let mut body = Vec::new();
+        write_block_pos(&mut body, BlockPos::new(33, -60, -46));
+        write_varint(&mut body, u16::from(stone()) as i32);
+        world.apply(decode_block_update(&body, &CTX).unwrap());
+        let chunk = world.view().get_chunk(ChunkPos::new(2, -3)).unwrap();
+        assert!(chunk.block_entity(BlockPos::new(33, -60, -46)).is_none());
+
+        world.apply(
+            decode_forget_level_chunk(&ChunkPos::new(2, -3).to_long().to_be_bytes()).unwrap(),
+        );
+        assert!(world.view().get_chunk(ChunkPos::new(2, -3)).is_none());
+    }
+
+    #[test]
+    fn test_chunks_biomes() {
+        let mut data = Vec::new();
+        for _ in 0..CTX.height.section_count() {
+            data.push(1);
+            write_varint(&mut data, 2);
+            write_varint(&mut data, 5);
+            write_varint(&mut data, 9);
+            let mut storage = BitStorage::new(1, SECTION_BIOMES);
+            storage.set(63, 1);
+            write_longs(&mut data, storage.raw());
+        }
+        let mut body = Vec::new();
+        write_varint(&mut body, 1);
+        body.extend_from_slice(&ChunkPos::new(2, -3).to_long().to_be_bytes());
+        write_varint(&mut body, data.len() as i32);
+        body.extend_from_slice(&data);
+
+        let mut world = World::new();
+        world.apply(decode_level_chunk_with_light(&chunk_packet(), &CTX).unwrap());
+        for event in decode_chunks_biomes(&body, &CTX).unwrap() {
+            world.apply(event);
+        }
+        assert_eq!(
+            world.view().get_biome(BlockPos::new(32, 0, -48)),
+            Some(BiomeId(5))
+        );
+        assert_eq!(
+            world.view().get_biome(BlockPos::new(47, 15, -33)),
+            Some(BiomeId(9))
+        );
+    }
+
+    #[test]
+    fn test_invalid_ids_are_rejected() {
+        let mut body = Vec::new();
+        write_block_pos(&mut body, BlockPos::ZERO);
+        write_varint(&mut body, u16::from(BlockState::MAX) as i32 + 1);
+        assert_eq!(
+            decode_block_update(&body, &CTX),
+            Err(Error::Invalid("block state id"))
+        );
+
+        let mut body = Vec::new();
+        write_block_pos(&mut body, BlockPos::ZERO);
+        write_varint(&mut body, 1);
+        body.push(0);
+        assert_eq!(
+            decode_block_update(&body, &CTX),
+            Err(Error::TrailingBytes(1))
+        );
+    }
+
+    #[test]
+    fn test_other_version_state_ids() {
+        // an older version with one block fewer between air and stone
+        static TABLE: StateIdTable = StateIdTable::new("old", 1, 3, &[[0, 0, 1], [2, 1, 1]]);
+        let ctx = ChunkContext {
+            state_ids: Some(&TABLE),
+            ..CTX
+        };
+        let update = |id: i32| {
+            let mut body = Vec::new();
+            write_block_pos(&mut body, BlockPos::ZERO);
+            write_varint(&mut body, id);
+            decode_block_update(&body, &ctx)
+        };
+        let state = |event: Result<WorldEvent>| match event.unwrap() {
+            WorldEvent::BlockSet { state, .. } => state,
+            event => panic!("unexpected {event:?}"),
+        };
+        assert_eq!(state(update(2)), BlockState::from(1));
+        assert!(state(update(1)).is_air());
+        assert_eq!(update(3), Err(Error::Invalid("block state id")));
+        assert!(matches!(block_layout(9, &ctx), Layout::Direct(2)));
+    }
+}
@@ -0,0 +1,46 @@
+use alloc::string::String;
+use core::fmt::{self, Display};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    UnexpectedEof,
+    VarIntTooLong,
+    NegativeLength(i32),
+    /// A field decoded to a value outside what the game would send.
+    Invalid(&'static str),
+    /// A packet body had bytes left over after decoding.
+    TrailingBytes(usize),
+    Nbt(mcre_nbt::Error),
+    /// A data component with no network codec here, whose value cannot be
+    /// read past or written.
+    UnsupportedComponent(String),
+    /// A registry needed to map ids to names was not given.
+    MissingRegistry(&'static str),
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl From<mcre_nbt::Error> for Error {
+    fn from(error: mcre_nbt::Error) -> Self {
+        Self::Nbt(error)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof => f.write_str("unexpected end of packet"),
+            Self::VarIntTooLong => f.write_str("variable-length integer is too long"),
+            Self::NegativeLength(len) => write!(f, "negative length {len}"),
+            Self::Invalid(what) => write!(f, "invalid {what}"),
+            Self::TrailingBytes(len) => write!(f, "{len} unread bytes at the end of the packet"),
+            Self::Nbt(error) => write!(f, "invalid NBT: {error}"),
+            Self::UnsupportedComponent(name) => {
+                write!(f, "data component `{name}` has no network codec")
+            }
+            Self::MissingRegistry(name) => write!(f, "registry `{name}` is not available"),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
@@ -0,0 +1,803 @@
+//! Item stacks on the wire, as in `ItemStack.OPTIONAL_STREAM_CODEC`: a count,
+//! the item's registry id and a `DataComponentPatch` of the components added
+//! to and removed from its defaults.
+//!
+//! Every component modelled by [`DataComponent`] has a codec here. Of the
+//! others, which are kept in their NBT form as [`DataComponent::Other`],
+//! those in [`SUPPORTED_OTHER_COMPONENTS`] are converted to and from their
+//! stream codec. Components vanilla never syncs, such as `lock` and
+//! `recipes`, never appear on the wire.
+//!
+//! Component values have no length prefix, so a stack with any other
+//! component, such as `consumable`, `equippable`, `tool` or `entity_data`,
+//! cannot be read past and fails with [`Error::UnsupportedComponent`].
+
+mod other;
+
+use alloc::{string::String, vec::Vec};
+use mcre_core::Rarity;
+use mcre_item::{
+    ComponentPatch, DataComponent, Enchantments, Filterable, ItemStack, Profile, ProfileProperty,
+    SkinPatch, WrittenBookContent, names,
+};
+use mcre_text::Component;
+
+use crate::{
+    Error, Result, buf,
+    registry::{ENCHANTMENT, Registry},
+};
+
+pub use other::SUPPORTED as SUPPORTED_OTHER_COMPONENTS;
+
+/// The most entries vanilla reads in lists such as lore and container slots.
+const MAX_LIST_LEN: usize = 256;
+
+/// The limit of lists vanilla reads without one.
+const UNBOUNDED: usize = usize::MAX;
+
+const MAX_PLAYER_NAME_CHARS: usize = 16;
+const MAX_PROFILE_PROPERTIES: usize = 16;
+const MAX_PROPERTY_NAME_CHARS: usize = 64;
+const MAX_SIGNATURE_CHARS: usize = 1024;
+
+/// The longest title a book may have.
+const MAX_TITLE_CHARS: usize = 32;
+
+/// A registry's names by network id, vanilla's `IdMap`.
+pub trait IdMap {
+    fn id(&self, name: &str) -> Option<u32>;
+    fn name(&self, id: u32) -> Option<&str>;
+}
+
+impl<T> IdMap for Registry<T> {
+    fn id(&self, name: &str) -> Option<u32> {
+        Registry::id(self, name)
+    }
+
+    fn name(&self, id: u32) -> Option<&str> {
+        Registry::name(self, id)
+    }
+}
+
+impl<const N: usize> IdMap for [&str; N] {
+    fn id(&self, name: &str) -> Option<u32> {
+        self.iter()
+            .position(|entry| *entry == name)
+            .map(|id| id as u32)
+    }
+
+    fn name(&self, id: u32) -> Option<&str> {
+        self.get(id as usize).copied()
+    }
+}
+
+/// Registries by name, such as `minecraft:potion`.
+pub trait RegistryLookup {
+    fn registry(&self, name: &str) -> Option<&dyn IdMap>;
+}
+
+/// The registries item stacks refer to by id.
+#[derive(Clone, Copy)]
+pub struct ItemContext<'a> {
+    pub items: &'a dyn IdMap,
+    pub components: &'a dyn IdMap,
+    /// The other built-in registries components refer to, such as
+    /// `minecraft:mob_effect`.
+    pub builtin: &'a dyn RegistryLookup,
+    /// The synchronised registries, such as `minecraft:enchantment`.
+    pub synced: &'a dyn RegistryLookup,
+}
+
+impl ItemContext<'_> {
+    fn registry(&self, name: &'static str) -> Result<&dyn IdMap> {
+        self.builtin
+            .registry(name)
+            .or_else(|| self.synced.registry(name))
+            .ok_or(Error::MissingRegistry(name))
+    }
+}
+
+/// A stack, or `None` for an empty slot.
+pub fn read_optional_item_stack(buf: &mut &[u8], ctx: &ItemContext) -> Result<Option<ItemStack>> {
+    let count = buf::read_varint(buf)?;
+    if count <= 0 {
+        return Ok(None);
+    }
+    let item = read_name(buf, ctx.items, "item id")?;
+    Ok(Some(ItemStack {
+        item,
+        count,
+        components: read_component_patch(buf, ctx)?,
+    }))
+}
+
+pub fn write_optional_item_stack(
+    out: &mut Vec<u8>,
+    stack: Option<&ItemStack>,
+    ctx: &ItemContext,
+) -> Result<()> {
+    let Some(stack) = stack.filter(|stack| stack.count > 0) else {
+        buf::write_varint(out, 0);
+        return Ok(());
+    };
+    buf::write_varint(out, stack.count);
+    write_id(out, ctx.items, &stack.item, "item")?;
+    write_component_patch(out, &stack.components, ctx)
+}
+
+fn read_name(buf: &mut &[u8], ids: &dyn IdMap, what: &'static str) -> Result<String> {
+    let id = buf::read_varint(buf)?;
+    u32::try_from(id)
+        .ok()
+        .and_then(|id| ids.name(id))
+        .map(String::from)
+        .ok_or(Error::Invalid(what))
+}
+
+fn write_id(out: &mut Vec<u8>, ids: &dyn IdMap, name: &str, what: &'static str) -> Result<()> {
+    let id = ids.id(name).ok_or(Error::Invalid(what))?;
+    buf::write_varint(out, id as i32);
+    Ok(())
+}
+
+/// The counts of added and removed components, then the added ones with
+/// their values and the ids of the removed ones.
+fn read_component_patch(buf: &mut &[u8], ctx: &ItemContext) -> Result<ComponentPatch> {
+    let added = buf::read_len(buf)?;
+    let removed = buf::read_len(buf)?;
+    let mut patch = ComponentPatch::new();
+    for _ in 0..added {
+        let name = read_name(buf, ctx.components, "data component type")?;
+        patch.set(read_component(buf, &name, ctx)?);
+    }
+    for _ in 0..removed {
+        patch.remove(&read_name(buf, ctx.components, "data component type")?);
+    }
+    Ok(patch)
+}
+
+fn write_component_patch(
+    out: &mut Vec<u8>,
+    patch: &ComponentPatch,
+    ctx: &ItemContext,
+) -> Result<()> {
+    buf::write_varint(out, patch.added().len() as i32);
+    buf::write_varint(out, patch.removed().len() as i32);
+    for component in patch.added() {
+        write_id(out, ctx.components, component.name(), "data component type")?;
+        write_component(out, component, ctx)?;
+    }
+    for name in patch.removed() {
+        write_id(out, ctx.components, name, "data component type")?;
+    }
+    Ok(())
+}
+
+fn read_list<T>(
+    buf: &mut &[u8],
+    max: usize,
+    mut read: impl FnMut(&mut &[u8]) -> Result<T>,
+) -> Result<Vec<T>> {
+    let len = buf::read_len(buf)?;
+    if len > max {
+        return Err(Error::Invalid("list length"));
+    }
+    (0..len).map(|_| read(buf)).collect()
+}
+
+fn write_list<T>(
+    out: &mut Vec<u8>,
+    list: &[T],
+    mut write: impl FnMut(&mut Vec<u8>, &T) -> Result<()>,
+) -> Result<()> {
+    buf::write_varint(out, list.len() as i32);
+    list.iter().try_for_each(|value| write(out, value))
+}
+
+/// A value behind a boolean saying whether it is there.
+fn read_optional<T>(
+    buf: &mut &[u8],
+    read: impl FnOnce(&mut &[u8]) -> Result<T>,
+) -> Result<Option<T>> {
+    if buf::read_bool(buf)? {
+        read(buf).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+fn write_optional<T>(
+    out: &mut Vec<u8>,
+    value: Option<&T>,
+    write: impl FnOnce(&mut Vec<u8>, &T) -> Result<()>,
+) -> Result<()> {
+    buf::write_bool(out, value.is_some());
+    value.map_or(Ok(()), |value| write(out, value))
+}
+
+fn read_identifier(buf: &mut &[u8]) -> Result<String> {
+    Ok(buf::read_identifier(buf)?.into())
+}
+
+fn write_string(out: &mut Vec<u8>, value: &impl AsRef<str>) -> Result<()> {
+    buf::write_string(out, value.as_ref());
+    Ok(())
+}
+
+fn read_text(buf: &mut &[u8]) -> Result<Component> {
+    let tag = buf::read_nbt(buf)?.ok_or(Error::Invalid("text component"))?;
+    Component::from_nbt(&tag).map_err(|_| Error::Invalid("text component"))
+}
+
+fn write_text(out: &mut Vec<u8>, text: &Component) -> Result<()> {
+    text.write_network_nbt(out);
+    Ok(())
+}
+
+/// The raw value, then the filtered one if there is one.
+fn read_filterable<T>(
+    buf: &mut &[u8],
+    read: impl Fn(&mut &[u8]) -> Result<T>,
+) -> Result<Filterable<T>> {
+    let raw = read(buf)?;
+    let filtered = if buf::read_bool(buf)? {
+        Some(read(buf)?)
+    } else {
+        None
+    };
+    Ok(Filterable { raw, filtered })
+}
+
+fn write_filterable<T>(
+    out: &mut Vec<u8>,
+    value: &Filterable<T>,
+    write: impl Fn(&mut Vec<u8>, &T) -> Result<()>,
+) -> Result<()> {
+    write(out, &value.raw)?;
+    buf::write_bool(out, value.filtered.is_some());
+    if let Some(filtered) = &value.filtered {
+        write(out, filtered)?;
+    }
+    Ok(())
+}
+
+fn read_component(buf: &mut &[u8], name: &str, ctx: &ItemContext) -> Result<DataComponent> {
+    Ok(match name {
+        names::CUSTOM_NAME => DataComponent::CustomName(read_text(buf)?),
+        names::ITEM_NAME => DataComponent::ItemName(read_text(buf)?),
+        names::LORE => DataComponent::Lore(read_list(buf, MAX_LIST_LEN, read_text)?),
+        names::DAMAGE => DataComponent::Damage(buf::read_varint(buf)?),
+        names::MAX_DAMAGE => DataComponent::MaxDamage(buf::read_varint(buf)?),
+        names::MAX_STACK_SIZE => DataComponent::MaxStackSize(buf::read_varint(buf)?),
+        names::REPAIR_COST => DataComponent::RepairCost(buf::read_varint(buf)?),
+        names::UNBREAKABLE => DataComponent::Unbreakable,
+        names::ENCHANTMENTS => DataComponent::Enchantments(read_enchantments(buf, ctx)?),
+        names::STORED_ENCHANTMENTS => {
+            DataComponent::StoredEnchantments(read_enchantments(buf, ctx)?)
+        }
+        names::DYED_COLOR => DataComponent::DyedColor(buf::read_i32(buf)? as u32),
+        names::RARITY => DataComponent::Rarity(match buf::read_varint(buf)? {
+            0 => Rarity::Common,
+            1 => Rarity::Uncommon,
+            2 => Rarity::Rare,
+            3 => Rarity::Epic,
+            _ => return Err(Error::Invalid("rarity")),
+        }),
+        names::ENCHANTMENT_GLINT_OVERRIDE => {
+            DataComponent::EnchantmentGlintOverride(buf::read_bool(buf)?)
+        }
+        names::PROFILE => DataComponent::Profile(read_profile(buf)?),
+        names::CONTAINER => DataComponent::Container(read_list(buf, MAX_LIST_LEN, |buf| {
+            read_optional_item_stack(buf, ctx)
+        })?),
+        names::WRITTEN_BOOK_CONTENT => DataComponent::WrittenBookContent(WrittenBookContent {
+            title: read_filterable(
+                buf,
+                |buf| Ok(buf::read_string(buf, MAX_TITLE_CHARS)?.into()),
+            )?,
+            author: buf::read_string(buf, buf::MAX_STRING_CHARS)?.into(),
+            generation: buf::read_varint(buf)?,
+            pages: read_list(buf, MAX_LIST_LEN, |buf| read_filterable(buf, read_text))?,
+            resolved: buf::read_bool(buf)?,
+        }),
+        _ => DataComponent::Other(name.into(), other::read(buf, name, ctx)?),
+    })
+}
+
+fn write_component(out: &mut Vec<u8>, component: &DataComponent, ctx: &ItemContext) -> Result<()> {
+    match component {
+        DataComponent::CustomName(text) | DataComponent::ItemName(text) => write_text(out, text)?,
+        DataComponent::Lore(lines) => write_list(out, lines, write_text)?,
+        DataComponent::Damage(value)
+        | DataComponent::MaxDamage(value)
+        | DataComponent::MaxStackSize(value)
+        | DataComponent::RepairCost(value) => buf::write_varint(out, *value),
+        DataComponent::Unbreakable => {}
+        DataComponent::Enchantments(enchantments)
+        | DataComponent::StoredEnchantments(enchantments) => {
+            write_enchantments(out, enchantments, ctx)?
+        }
+        DataComponent::DyedColor(color) => buf::write_i32(out, *color as i32),
+        DataComponent::Rarity(rarity) => buf::write_varint(out, *rarity as i32),
+        DataComponent::EnchantmentGlintOverride(glint) => buf::write_bool(out, *glint),
+        DataComponent::Container(slots) => write_list(out, slots, |out, stack| {
+            write_optional_item_stack(out, stack.as_ref(), ctx)
+        })?,
+        DataComponent::WrittenBookContent(book) => {
+            write_filterable(out, &book.title, |out, title| {
+                buf::write_string(out, title);
+                Ok(())
+            })?;
+            buf::write_string(out, &book.author);
+            buf::write_varint(out, book.generation);
+            write_list(out, &book.pages, |out, page| {
+                write_filterable(out, page, write_text)
+            })?;
+            buf::write_bool(out, book.resolved);
+        }
+        DataComponent::Profile(profile) => write_profile(out, profile)?,
+        DataComponent::Other(name, value) => other::write(out, name, value, ctx)?,
+    }
+    Ok(())
+}
+
+/// Either a complete game profile or a partial one to resolve, then the
+/// skin patch, as in `ResolvableProfile.STREAM_CODEC`.
+fn read_profile(buf: &mut &[u8]) -> Result<Profile> {
+    let (name, id) = if buf::read_bool(buf)? {
+        let id = buf::read_uuid(buf)?;
+        (Some(read_player_name(buf)?), Some(id))
+    } else {
+        let name = read_optional(buf, read_player_name)?;
+        (name, read_optional(buf, buf::read_uuid)?)
+    };
+    let properties = read_list(buf, MAX_PROFILE_PROPERTIES, |buf| {
+        Ok(ProfileProperty {
+            name: buf::read_string(buf, MAX_PROPERTY_NAME_CHARS)?.into(),
+            value: buf::read_string(buf, buf::MAX_STRING_CHARS)?.into(),
+            signature: read_optional(buf, |buf| {
+                Ok(buf::read_string(buf, MAX_SIGNATURE_CHARS)?.into())
+            })?,
+        })
+    })?;
+    Ok(Profile {
+        name,
+        id,
+        properties,
+        skin: SkinPatch {
+            texture: read_optional(buf, read_identifier)?,
+            cape: read_optional(buf, read_identifier)?,
+            elytra: read_optional(buf, read_identifier)?,
+            slim: read_optional(buf, buf::read_bool)?,
+        },
+    })
+}
+
+fn read_player_name(buf: &mut &[u8]) -> Result<String> {
+    Ok(buf::read_string(buf, MAX_PLAYER_NAME_CHARS)?.into())
+}
+
+/// Profiles with both a name and an id are sent as complete ones.
+fn write_profile(out: &mut Vec<u8>, profile: &Profile) -> Result<()> {
+    if let (Some(name), Some(id)) = (&profile.name, profile.id) {
+        buf::write_bool(out, true);
+        buf::write_uuid(out, id);
+        buf::write_string(out, name);
+    } else {
+        buf::write_bool(out, false);
+        write_optional(out, profile.name.as_ref(), write_string)?;
+        write_optional(out, profile.id.as_ref(), |out, id| {
+            buf::write_uuid(out, *id);
+            Ok(())
+        })?;
+    }
+    write_list(out, &profile.properties, |out, property| {
+        buf::write_string(out, &property.name);
+        buf::write_string(out, &property.value);
+        write_optional(out, property.signature.as_ref(), write_string)
+    })?;
+    let skin = &profile.skin;
+    write_optional(out, skin.texture.as_ref(), write_string)?;
+    write_optional(out, skin.cape.as_ref(), write_string)?;
+    write_optional(out, skin.elytra.as_ref(), write_string)?;
+    write_optional(out, skin.slim.as_ref(), |out, slim| {
+        buf::write_bool(out, *slim);
+        Ok(())
+    })
+}
+
+/// Enchantment ids with their levels.
+fn read_enchantments(buf: &mut &[u8], ctx: &ItemContext) -> Result<Enchantments> {
+    let enchantments = ctx.registry(ENCHANTMENT)?;
+    let len = buf::read_len(buf)?;
+    let mut levels = Vec::with_capacity(len.min(64));
+    for _ in 0..len {
+        let enchantment = read_name(buf, enchantments, "enchantment id")?;
+        levels.push((enchantment, buf::read_varint(buf)?));
+    }
+    Ok(Enchantments { levels })
+}
+
+fn write_enchantments(
+    out: &mut Vec<u8>,
+    enchantments: &Enchantments,
+    ctx: &ItemContext,
+) -> Result<()> {
+    let ids = ctx.registry(ENCHANTMENT)?;
+    buf::write_varint(out, enchantments.levels.len() as i32);
+    for (enchantment, level) in &enchantments.levels {
+        write_id(out, ids, enchantment, "enchantment")?;
+        buf::write_varint(out, *level);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+    use mcre_item::identifier;
+
+    use super::*;
+    use crate::registry::{
+        ATTRIBUTE, BANNER_PATTERN, MOB_EFFECT, POTION, Registries, RegistryData, TRIM_MATERIAL,
+        TRIM_PATTERN,
+    };
+
+    const ITEMS: [&str; 3] = ["minecraft:air", "minecraft:diamond_sword", "minecraft:book"];
+    const TYPED_COMPONENTS: [&str; 7] = [
+        "minecraft:damage",
+        "minecraft:custom_name",
+        "minecraft:enchantments",
+        "minecraft:food",
+        "minecraft:container",
+        "minecraft:profile",
+        "minecraft:consumable",
+    ];
+    const POTIONS: [&str; 1] = ["minecraft:swiftness"];
+    const MOB_EFFECTS: [&str; 2] = ["minecraft:speed", "minecraft:regeneration"];
+    const ATTRIBUTES: [&str; 2] = ["minecraft:armor", "minecraft:attack_damage"];
+
+    /// The component types above, then every other supported one.
+    struct Components;
+
+    impl IdMap for Components {
+        fn id(&self, name: &str) -> Option<u32> {
+            let mut names = TYPED_COMPONENTS.iter().chain(&SUPPORTED_OTHER_COMPONENTS);
+            names.position(|entry| *entry == name).map(|id| id as u32)
+        }
+
+        fn name(&self, id: u32) -> Option<&str> {
+            let mut names = TYPED_COMPONENTS.iter().chain(&SUPPORTED_OTHER_COMPONENTS);
+            names.nth(id as usize).copied()
+        }
+    }
+
+    struct BuiltIn;
+
+    impl RegistryLookup for BuiltIn {
+        fn registry(&self, name: &str) -> Option<&dyn IdMap> {
+            match name {
+                POTION => Some(&POTIONS),
+                MOB_EFFECT => Some(&MOB_EFFECTS),
+                ATTRIBUTE => Some(&ATTRIBUTES),
+                _ => None,
+            }
+        }
+    }
+
+    fn synced() -> Registries {
+        let mut registries = Registries::new();
+        for (registry, entries) in [
+            (
+                ENCHANTMENT,
+                &["minecraft:protection", "minecraft:sharpness"][..],
+            ),
+            (TRIM_MATERIAL, &["minecraft:gold"]),
+            (TRIM_PATTERN, &["minecraft:coast"]),
+            (BANNER_PATTERN, &["minecraft:stripe_top"]),
+        ] {
+            let entries = entries.iter().map(|name| (name.to_string(), None));
+            registries
+                .apply(RegistryData {
+                    registry: registry.into(),
+                    entries: entries.collect(),
+                })
+                .unwrap();
+        }
+        registries
+    }
+
+    fn round_trip(stack: Option<&ItemStack>, ctx: &ItemContext) -> Option<ItemStack> {
+        let mut out = Vec::new();
+        write_optional_item_stack(&mut out, stack, ctx).unwrap();
+        let mut body = out.as_slice();
+        let decoded = read_optional_item_stack(&mut body, ctx).unwrap();
+        buf::finish(body).unwrap();
+        decoded
+    }
+
+    #[test]
+    fn test_item_stack_bytes() {
+        let synced = synced();
+        let ctx = synced.item_context(&ITEMS, &Components, &BuiltIn);
+        let mut stack = ItemStack::new("diamond_sword", 1).with(DataComponent::Damage(5));
+        stack.components.remove("minecraft:food");
+        let mut out = Vec::new();
+        write_optional_item_stack(&mut out, Some(&stack), &ctx).unwrap();
+        // count, item, 1 added, 1 removed, damage = 5, food
+        assert_eq!(out, [1, 1, 1, 1, 0, 5, 3]);
+        assert_eq!(round_trip(Some(&stack), &ctx), Some(stack));
+
+        let mut out = Vec::new();
+        write_optional_item_stack(&mut out, None, &ctx).unwrap();
+        assert_eq!(out, [0]);
+        assert_eq!(round_trip(None, &ctx), None);
+    }
+
+    #[test]
+    fn test_nested_components() {
+        let synced = synced();
+        let ctx = synced.item_context(&ITEMS, &Components, &BuiltIn);
+        let sword = ItemStack::new("diamond_sword", 1)
+            .with(DataComponent::CustomName(Component::text("Excalibur")))
+            .with(DataComponent::Enchantments(Enchantments {
+                levels: vec![("minecraft:sharpness".into(), 5)],
+            }));
+        let chest = ItemStack::new("book", 3)
+            .with(DataComponent::Container(vec![None, Some(sword.clone())]));
+        assert_eq!(round_trip(Some(&chest), &ctx), Some(chest));
+    }
+
+    #[test]
+    fn test_profile() {
+        let synced = synced();
+        let ctx = synced.item_context(&ITEMS, &Components, &BuiltIn);
+        let complete = Profile {
+            name: Some("Steve".into()),
+            id: Some([0, 1, 0, 2]),
+            ..Profile::default()
+        };
+        let head = ItemStack::new("book", 1).with(DataComponent::Profile(complete));
+        let mut out = Vec::new();
+        write_optional_item_stack(&mut out, Some(&head), &ctx).unwrap();
+        #[rustfmt::skip]
+        assert_eq!(out, [
+            1, 2, 1, 0, 5,
+            // a complete profile: uuid, name and no properties
+            1,
+            0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2,
+            5, b'S', b't', b'e', b'v', b'e',
+            0,
+            // no texture, cape, elytra or model
+            0, 0, 0, 0,
+        ]);
+        assert_eq!(round_trip(Some(&head), &ctx), Some(head));
+
+        let partial = Profile {
+            name: Some("Alex".into()),
+            properties: vec![ProfileProperty {
+                name: "textures".into(),
+                value: "e30=".into(),
+                signature: Some("c2ln".into()),
+            }],
+            skin: SkinPatch {
+                texture: Some("minecraft:entity/player/slim/alex".into()),
+                slim: Some(true),
+                ..SkinPatch::default()
+            },
+            ..Profile::default()
+        };
+        let head = ItemStack::new("book", 1).with(DataComponent::Profile(partial));
+        assert_eq!(round_trip(Some(&head), &ctx), Some(head));
+
+        let too_long = ItemStack::new("book", 1).with(DataComponent::Profile(Profile {
+            name: Some("a_name_of_twenty_chars".into()),
+            id: Some([1, 2, 3, 4]),
+            ..Profile::default()
+        }));
+        let mut out = Vec::new();
+        write_optional_item_stack(&mut out, Some(&too_long), &ctx).unwrap();
+        assert!(read_optional_item_stack(&mut out.as_slice(), &ctx).is_err());
+    }
+
+    #[test]
+    fn test_other_components() {
+        let synced = synced();
+        let ctx = synced.item_context(&ITEMS, &Components, &BuiltIn);
+        let values = [
+            (
+                "attribute_modifiers",
+                r#"[{type:"minecraft:attack_damage",id:"minecraft:base_attack_damage",amount:7.0d,operation:"add_value",slot:"mainhand"},{type:"minecraft:armor",id:"minecraft:armor.helmet",amount:0.5d,operation:"add_multiplied_base",display:{type:"override",value:"Shiny"}},{type:"minecraft:armor",id:"minecraft:hidden",amount:1.0d,operation:"add_multiplied_total",display:{type:"hidden"}}]"#,
+            ),
+            (
+                "banner_patterns",
+                r#"[{pattern:"minecraft:stripe_top",color:"red"}]"#,
+            ),
+            ("base_color", r#""light_blue""#),
+            ("block_state", r#"{facing:"north",half:"top"}"#),
+            ("bucket_entity_data", "{NoAI:1b,Health:3.0f}"),
+            (
+                "bundle_contents",
+                r#"[{id:"minecraft:book",count:2},{id:"minecraft:diamond_sword",count:1,components:{"minecraft:damage":3}}]"#,
+            ),
+            ("charged_projectiles", "[]"),
+            ("creative_slot_lock", "{}"),
+            ("custom_data", r#"{custom:{level:3},tags:["a","b"]}"#),
+            (
+                "custom_model_data",
+                r#"{floats:[1.5f],flags:[B;1b,0b],strings:["red"],colors:[I;16711680]}"#,
+            ),
+            ("damage_resistant", r##"{types:"#minecraft:is_fire"}"##),
+            ("enchantable", "{value:15}"),
+            (
+                "firework_explosion",
+                r#"{shape:"star",colors:[I;255],has_twinkle:1b}"#,
+            ),
+            (
+                "fireworks",
+                r#"{flight_duration:2b,explosions:[{shape:"creeper"}]}"#,
+            ),
+            ("food", "{nutrition:4,saturation:2.4f,can_always_eat:1b}"),
+            ("glider", "{}"),
+            ("intangible_projectile", "{}"),
+            ("item_model", r#""minecraft:diamond""#),
+            (
+                "lodestone_tracker",
+                r#"{target:{dimension:"minecraft:overworld",pos:[I;1,64,-2]},tracked:0b}"#,
+            ),
+            ("map_color", "4145152"),
+            ("map_id", "7"),
+            ("map_post_processing", r#""scale""#),
+            ("note_block_sound", r#""minecraft:block.note_block.bell""#),
+            ("ominous_bottle_amplifier", "2"),
+            (
+                "pot_decorations",
+                r#"["minecraft:book","minecraft:diamond_sword","minecraft:book","minecraft:book"]"#,
+            ),
+            (
+                "potion_contents",
+                r#"{potion:"minecraft:swiftness",custom_color:255,custom_effects:[{id:"minecraft:speed",amplifier:1b,duration:600,show_icon:1b,hidden_effect:{duration:100,show_particles:0b,show_icon:0b}}],custom_name:"fast"}"#,
+            ),
+            ("potion_duration_scale", "0.25f"),
+            (
+                "provides_banner_patterns",
+                r##""#minecraft:pattern_item/globe""##,
+            ),
+            (
+                "repairable",
+                r##"{items:"#minecraft:diamond_tool_materials"}"##,
+            ),
+            (
+                "suspicious_stew_effects",
+                r#"[{id:"minecraft:speed"},{id:"minecraft:regeneration",duration:80}]"#,
+            ),
+            (
+                "tooltip_display",
+                r#"{hide_tooltip:1b,hidden_components:["minecraft:damage","minecraft:enchantments"]}"#,
+            ),
+            ("tooltip_style", r#""minecraft:fancy""#),
+            (
+                "trim",
+                r#"{material:"minecraft:gold",pattern:"minecraft:coast"}"#,
+            ),
+            (
+                "use_cooldown",
+                r#"{seconds:1.5f,cooldown_group:"minecraft:pearls"}"#,
+            ),
+            (
+                "use_remainder",
+                r#"{convert_into:{id:"minecraft:book",count:1}}"#,
+            ),
+            ("weapon", "{item_damage_per_attack:2}"),
+            (
+                "writable_book_content",
+                r#"{pages:[{raw:"hello"},{raw:"darn",filtered:"****"}]}"#,
+            ),
+        ];
+        assert_eq!(
+            values.map(|(name, _)| identifier(name)),
+            SUPPORTED_OTHER_COMPONENTS.map(String::from)
+        );
+        for (name, snbt) in values {
+            let value = mcre_nbt::snbt::parse(snbt).unwrap();
+            let stack =
+                ItemStack::new("book", 1).with(DataComponent::Other(identifier(name), value));
+            assert_eq!(round_trip(Some(&stack), &ctx), Some(stack), "{name}");
+        }
+    }
+
+    /// Writes a stack with one component given as SNBT.
+    fn write_other(name: &str, snbt: &str, ctx: &ItemContext) -> Result<Vec<u8>> {
+        let value = mcre_nbt::snbt::parse(snbt).unwrap();
+        let stack = ItemStack::new("book", 1).with(DataComponent::Other(name.into(), value));
+        let mut out = Vec::new();
+        write_optional_item_stack(&mut out, Some(&stack), ctx).map(|()| out)
+    }
+
+    #[test]
+    fn test_other_component_forms() {
+        let synced = synced();
+        let ctx = synced.item_context(&ITEMS, &Components, &BuiltIn);
+        for (name, canonical, other) in [
+            (
+                "minecraft:food",
+                "{nutrition:4,saturation:2.5f}",
+                "{nutrition:4L,saturation:2.5d,can_always_eat:0b}",
+            ),
+            (
+                "minecraft:potion_contents",
+                r#"{potion:"minecraft:swiftness"}"#,
+                r#""swiftness""#,
+            ),
+            (
+                "minecraft:repairable",
+                r#"{items:["minecraft:book"]}"#,
+                r#"{items:"book"}"#,
+            ),
+            ("minecraft:custom_data", "{a:1b}", r#""{a:1b}""#),
+            (
+                "minecraft:custom_model_data",
+                "{flags:[B;1b]}",
+                "{flags:[true]}",
+            ),
+        ] {
+            assert_eq!(
+                write_other(name, canonical, &ctx).unwrap(),
+                write_other(name, other, &ctx).unwrap(),
+                "{name}"
+            );
+        }
+        // food's id, 4 nutrition, 2.5 saturation and can_always_eat
+        assert_eq!(
+            write_other("minecraft:food", "{nutrition:4,saturation:2.5f}", &ctx).unwrap(),
+            [1, 2, 1, 0, 3, 4, 0x40, 0x20, 0, 0, 0]
+        );
+
+        assert_eq!(
+            write_other("minecraft:food", "{saturation:2.5f}", &ctx),
+            Err(Error::Invalid("nutrition"))
+        );
+        assert_eq!(
+            write_other("minecraft:base_color", r#""chartreuse""#, &ctx),
+            Err(Error::Invalid("dye color"))
+        );
+    }
+
+    #[test]
+    fn test_unsupported_components() {
+        let synced = synced();
+        let ctx = synced.item_context(&ITEMS, &Components, &BuiltIn);
+        let stack = ItemStack::new("stone", 1);
+        assert_eq!(
+            write_optional_item_stack(&mut Vec::new(), Some(&stack), &ctx),
+            Err(Error::Invalid("item"))
+        );
+
+        let consumable = Error::UnsupportedComponent("minecraft:consumable".into());
+        assert_eq!(
+            write_other("minecraft:consumable", "{}", &ctx),
+            Err(consumable.clone())
+        );
+        // consumable has no stream codec here, so the stack cannot be read
+        // past it
+        let body = [1, 1, 1, 0, 6, 4];
+        assert_eq!(
+            read_optional_item_stack(&mut &body[..], &ctx),
+            Err(consumable)
+        );
+
+        let empty = Registries::new();
+        let no_builtin = synced.item_context(&ITEMS, &Components, &empty);
+        assert_eq!(
+            write_other("minecraft:potion_contents", r#""swiftness""#, &no_builtin),
+            Err(Error::MissingRegistry(POTION))
+        );
+        // trim materials defined inline in the packet are not supported
+        let trim = Components.id("minecraft:trim").unwrap() as u8;
+        let body = [1, 1, 1, 0, trim, 0];
+        assert_eq!(
+            read_optional_item_stack(&mut &body[..], &ctx),
+            Err(Error::Invalid("inline registry entry"))
+        );
+    }
+}
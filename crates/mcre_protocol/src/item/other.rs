@@ -0,0 +1,916 @@
+//! Stream codecs of the components kept as NBT, converting between each
+//! one's wire format and the NBT its vanilla codec writes, which leaves out
+//! fields at their defaults. Writing accepts any NBT that codec reads the
+//! same way, such as numbers of another type.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use mcre_core::BlockPos;
+use mcre_item::{ItemStack, identifier};
+use mcre_nbt::{Compound, Tag};
+use mcre_text::Component;
+
+use super::{
+    ItemContext, UNBOUNDED, read_identifier, read_list, read_name, read_optional,
+    read_optional_item_stack, read_text, write_id, write_list, write_optional,
+    write_optional_item_stack,
+};
+use crate::{
+    Error, Result, buf,
+    registry::{ATTRIBUTE, BANNER_PATTERN, MOB_EFFECT, POTION, TRIM_MATERIAL, TRIM_PATTERN},
+};
+
+/// The components without a [`DataComponent`](mcre_item::DataComponent)
+/// variant that can be read and written.
+pub const SUPPORTED: [&str; 37] = [
+    "minecraft:attribute_modifiers",
+    "minecraft:banner_patterns",
+    "minecraft:base_color",
+    "minecraft:block_state",
+    "minecraft:bucket_entity_data",
+    "minecraft:bundle_contents",
+    "minecraft:charged_projectiles",
+    "minecraft:creative_slot_lock",
+    "minecraft:custom_data",
+    "minecraft:custom_model_data",
+    "minecraft:damage_resistant",
+    "minecraft:enchantable",
+    "minecraft:firework_explosion",
+    "minecraft:fireworks",
+    "minecraft:food",
+    "minecraft:glider",
+    "minecraft:intangible_projectile",
+    "minecraft:item_model",
+    "minecraft:lodestone_tracker",
+    "minecraft:map_color",
+    "minecraft:map_id",
+    "minecraft:map_post_processing",
+    "minecraft:note_block_sound",
+    "minecraft:ominous_bottle_amplifier",
+    "minecraft:pot_decorations",
+    "minecraft:potion_contents",
+    "minecraft:potion_duration_scale",
+    "minecraft:provides_banner_patterns",
+    "minecraft:repairable",
+    "minecraft:suspicious_stew_effects",
+    "minecraft:tooltip_display",
+    "minecraft:tooltip_style",
+    "minecraft:trim",
+    "minecraft:use_cooldown",
+    "minecraft:use_remainder",
+    "minecraft:weapon",
+    "minecraft:writable_book_content",
+];
+
+const DYE_COLORS: [&str; 16] = [
+    "white",
+    "orange",
+    "magenta",
+    "light_blue",
+    "yellow",
+    "lime",
+    "pink",
+    "gray",
+    "light_gray",
+    "cyan",
+    "purple",
+    "blue",
+    "brown",
+    "green",
+    "red",
+    "black",
+];
+
+const FIREWORK_SHAPES: [&str; 5] = ["small_ball", "large_ball", "star", "creeper", "burst"];
+
+const MAP_POST_PROCESSING: [&str; 2] = ["lock", "scale"];
+
+const ATTRIBUTE_OPERATIONS: [&str; 3] =
+    ["add_value", "add_multiplied_base", "add_multiplied_total"];
+
+const EQUIPMENT_SLOT_GROUPS: [&str; 11] = [
+    "any", "mainhand", "offhand", "hand", "feet", "legs", "chest", "head", "armor", "body",
+    "saddle",
+];
+
+const MODIFIER_DISPLAYS: [&str; 3] = ["default", "hidden", "override"];
+
+const MAX_FIREWORK_EXPLOSIONS: usize = 256;
+const MAX_POT_DECORATIONS: usize = 4;
+const MAX_BOOK_PAGES: usize = 100;
+const MAX_PAGE_CHARS: usize = 1024;
+
+/// How deep the hidden effects behind an effect instance may nest.
+const MAX_HIDDEN_EFFECTS: usize = 16;
+
+const DEFAULT_STEW_EFFECT_DURATION: i32 = 160;
+
+pub(super) fn read(buf: &mut &[u8], name: &str, ctx: &ItemContext) -> Result<Tag> {
+    Ok(match name {
+        "minecraft:creative_slot_lock" | "minecraft:intangible_projectile" | "minecraft:glider" => {
+            Compound::new().into()
+        }
+        "minecraft:custom_data" | "minecraft:bucket_entity_data" => {
+            buf::read_optional_compound(buf)?
+                .ok_or(Error::Invalid("custom data"))?
+                .into()
+        }
+        "minecraft:item_model" | "minecraft:tooltip_style" | "minecraft:note_block_sound" => {
+            read_identifier(buf)?.into()
+        }
+        "minecraft:provides_banner_patterns" => read_tag_key(buf)?,
+        "minecraft:damage_resistant" => fields([("types", read_tag_key(buf)?)]),
+        "minecraft:map_color" => buf::read_i32(buf)?.into(),
+        "minecraft:map_id" | "minecraft:ominous_bottle_amplifier" => buf::read_varint(buf)?.into(),
+        "minecraft:enchantable" => fields([("value", buf::read_varint(buf)?.into())]),
+        "minecraft:potion_duration_scale" => buf::read_f32(buf)?.into(),
+        "minecraft:map_post_processing" => {
+            read_enum(buf, &MAP_POST_PROCESSING, "map post processing")?
+        }
+        "minecraft:base_color" => read_enum(buf, &DYE_COLORS, "dye color")?,
+        "minecraft:food" => {
+            let mut food = Compound::new();
+            food.insert("nutrition", buf::read_varint(buf)?);
+            food.insert("saturation", buf::read_f32(buf)?);
+            put_unless(&mut food, "can_always_eat", buf::read_bool(buf)?, false);
+            food.into()
+        }
+        "minecraft:use_cooldown" => {
+            let mut cooldown = Compound::new();
+            cooldown.insert("seconds", buf::read_f32(buf)?);
+            if let Some(group) = read_optional(buf, read_identifier)? {
+                cooldown.insert("cooldown_group", group);
+            }
+            cooldown.into()
+        }
+        "minecraft:weapon" => {
+            let mut weapon = Compound::new();
+            put_unless(
+                &mut weapon,
+                "item_damage_per_attack",
+                buf::read_varint(buf)?,
+                1,
+            );
+            put_unless(
+                &mut weapon,
+                "disable_blocking_for_seconds",
+                buf::read_f32(buf)?,
+                0.0,
+            );
+            weapon.into()
+        }
+        "minecraft:tooltip_display" => {
+            let mut display = Compound::new();
+            put_unless(&mut display, "hide_tooltip", buf::read_bool(buf)?, false);
+            let hidden = read_list(buf, UNBOUNDED, |buf| {
+                Ok(Tag::from(read_name(
+                    buf,
+                    ctx.components,
+                    "data component type",
+                )?))
+            })?;
+            put_unless(&mut display, "hidden_components", hidden, Vec::new());
+            display.into()
+        }
+        "minecraft:custom_model_data" => read_custom_model_data(buf)?,
+        "minecraft:block_state" => {
+            let len = buf::read_len(buf)?;
+            let mut properties = Compound::new();
+            for _ in 0..len {
+                let key = buf::read_string(buf, buf::MAX_STRING_CHARS)?;
+                properties.insert(key, buf::read_string(buf, buf::MAX_STRING_CHARS)?);
+            }
+            properties.into()
+        }
+        "minecraft:firework_explosion" => read_explosion(buf)?,
+        "minecraft:fireworks" => {
+            let mut fireworks = Compound::new();
+            put_unless(
+                &mut fireworks,
+                "flight_duration",
+                buf::read_varint(buf)? as i8,
+                0,
+            );
+            let explosions = read_list(buf, MAX_FIREWORK_EXPLOSIONS, read_explosion)?;
+            put_unless(&mut fireworks, "explosions", explosions, Vec::new());
+            fireworks.into()
+        }
+        "minecraft:lodestone_tracker" => {
+            let mut tracker = Compound::new();
+            let target = read_optional(buf, |buf| {
+                let dimension = read_identifier(buf)?;
+                let pos = buf::read_block_pos(buf)?;
+                Ok(fields([
+                    ("dimension", dimension.into()),
+                    ("pos", vec![pos.x, pos.y, pos.z].into()),
+                ]))
+            })?;
+            if let Some(target) = target {
+                tracker.insert("target", target);
+            }
+            put_unless(&mut tracker, "tracked", buf::read_bool(buf)?, true);
+            tracker.into()
+        }
+        "minecraft:pot_decorations" => read_list(buf, MAX_POT_DECORATIONS, |buf| {
+            Ok(Tag::from(read_name(buf, ctx.items, "item id")?))
+        })?
+        .into(),
+        "minecraft:repairable" => fields([("items", read_item_set(buf, ctx)?)]),
+        "minecraft:writable_book_content" => {
+            let pages = read_list(buf, MAX_BOOK_PAGES, |buf| {
+                let mut page = Compound::new();
+                page.insert("raw", buf::read_string(buf, MAX_PAGE_CHARS)?);
+                if let Some(filtered) = read_optional(buf, |buf| {
+                    Ok(buf::read_string(buf, MAX_PAGE_CHARS)?.to_string())
+                })? {
+                    page.insert("filtered", filtered);
+                }
+                Ok(Tag::from(page))
+            })?;
+            let mut book = Compound::new();
+            put_unless(&mut book, "pages", pages, Vec::new());
+            book.into()
+        }
+        "minecraft:use_remainder" => fields([("convert_into", read_item_stack(buf, ctx)?)]),
+        "minecraft:charged_projectiles" | "minecraft:bundle_contents" => {
+            read_list(buf, UNBOUNDED, |buf| read_item_stack(buf, ctx))?.into()
+        }
+        "minecraft:potion_contents" => read_potion_contents(buf, ctx)?,
+        "minecraft:suspicious_stew_effects" => read_list(buf, UNBOUNDED, |buf| {
+            let mut effect = Compound::new();
+            effect.insert(
+                "id",
+                read_name(buf, ctx.registry(MOB_EFFECT)?, "effect id")?,
+            );
+            put_unless(
+                &mut effect,
+                "duration",
+                buf::read_varint(buf)?,
+                DEFAULT_STEW_EFFECT_DURATION,
+            );
+            Ok(Tag::from(effect))
+        })?
+        .into(),
+        "minecraft:attribute_modifiers" => {
+            read_list(buf, UNBOUNDED, |buf| read_attribute_modifier(buf, ctx))?.into()
+        }
+        "minecraft:trim" => fields([
+            (
+                "material",
+                read_reference(buf, ctx, TRIM_MATERIAL, "trim material")?,
+            ),
+            (
+                "pattern",
+                read_reference(buf, ctx, TRIM_PATTERN, "trim pattern")?,
+            ),
+        ]),
+        "minecraft:banner_patterns" => read_list(buf, UNBOUNDED, |buf| {
+            Ok(fields([
+                (
+                    "pattern",
+                    read_reference(buf, ctx, BANNER_PATTERN, "banner pattern")?,
+                ),
+                ("color", read_enum(buf, &DYE_COLORS, "dye color")?),
+            ]))
+        })?
+        .into(),
+        _ => return Err(Error::UnsupportedComponent(name.into())),
+    })
+}
+
+pub(super) fn write(out: &mut Vec<u8>, name: &str, value: &Tag, ctx: &ItemContext) -> Result<()> {
+    match name {
+        "minecraft:creative_slot_lock" | "minecraft:intangible_projectile" | "minecraft:glider" => {
+        }
+        "minecraft:custom_data" | "minecraft:bucket_entity_data" => {
+            // The codec also takes the compound as an SNBT string.
+            let parsed;
+            let data = match value {
+                Tag::String(snbt) => {
+                    parsed = mcre_nbt::snbt::parse(snbt)?;
+                    &parsed
+                }
+                value => value,
+            };
+            compound(data, "custom data")?;
+            mcre_nbt::binary::write_nameless(out, Some(data))?;
+        }
+        "minecraft:item_model" | "minecraft:tooltip_style" | "minecraft:note_block_sound" => {
+            buf::write_string(out, &identifier(string(value, "identifier")?));
+        }
+        "minecraft:provides_banner_patterns" => write_tag_key(out, value)?,
+        "minecraft:damage_resistant" => {
+            write_tag_key(
+                out,
+                required(compound(value, "damage resistant")?, "types")?,
+            )?;
+        }
+        "minecraft:map_color" => buf::write_i32(out, int(value, "map color")?),
+        "minecraft:map_id" | "minecraft:ominous_bottle_amplifier" => {
+            buf::write_varint(out, int(value, "integer")?);
+        }
+        "minecraft:enchantable" => {
+            let value = required(compound(value, "enchantable")?, "value")?;
+            buf::write_varint(out, int(value, "enchantability")?);
+        }
+        "minecraft:potion_duration_scale" => {
+            buf::write_f32(out, float(value, "potion duration scale")?);
+        }
+        "minecraft:map_post_processing" => {
+            write_enum(out, &MAP_POST_PROCESSING, value, "map post processing")?;
+        }
+        "minecraft:base_color" => write_enum(out, &DYE_COLORS, value, "dye color")?,
+        "minecraft:food" => {
+            let food = compound(value, "food")?;
+            buf::write_varint(out, int(required(food, "nutrition")?, "nutrition")?);
+            buf::write_f32(out, float(required(food, "saturation")?, "saturation")?);
+            buf::write_bool(
+                out,
+                optional(food, "can_always_eat", boolean)?.unwrap_or(false),
+            );
+        }
+        "minecraft:use_cooldown" => {
+            let cooldown = compound(value, "use cooldown")?;
+            buf::write_f32(out, float(required(cooldown, "seconds")?, "seconds")?);
+            let group = optional(cooldown, "cooldown_group", string)?;
+            write_optional(out, group.map(identifier).as_ref(), super::write_string)?;
+        }
+        "minecraft:weapon" => {
+            let weapon = compound(value, "weapon")?;
+            buf::write_varint(
+                out,
+                optional(weapon, "item_damage_per_attack", int)?.unwrap_or(1),
+            );
+            buf::write_f32(
+                out,
+                optional(weapon, "disable_blocking_for_seconds", float)?.unwrap_or(0.0),
+            );
+        }
+        "minecraft:tooltip_display" => {
+            let display = compound(value, "tooltip display")?;
+            buf::write_bool(
+                out,
+                optional(display, "hide_tooltip", boolean)?.unwrap_or(false),
+            );
+            let hidden = optional(display, "hidden_components", list)?.unwrap_or_default();
+            write_list(out, hidden, |out, component| {
+                let name = identifier(string(component, "data component type")?);
+                write_id(out, ctx.components, &name, "data component type")
+            })?;
+        }
+        "minecraft:custom_model_data" => write_custom_model_data(out, value)?,
+        "minecraft:block_state" => {
+            let properties = compound(value, "block state properties")?;
+            buf::write_varint(out, properties.len() as i32);
+            for (key, value) in properties {
+                buf::write_string(out, key);
+                buf::write_string(out, string(value, "block state property")?);
+            }
+        }
+        "minecraft:firework_explosion" => write_explosion(out, value)?,
+        "minecraft:fireworks" => {
+            let fireworks = compound(value, "fireworks")?;
+            let duration = optional(fireworks, "flight_duration", int)?.unwrap_or(0);
+            buf::write_varint(out, duration as u8 as i32);
+            let explosions = optional(fireworks, "explosions", list)?.unwrap_or_default();
+            write_list(out, explosions, |out, explosion| {
+                write_explosion(out, explosion)
+            })?;
+        }
+        "minecraft:lodestone_tracker" => {
+            let tracker = compound(value, "lodestone tracker")?;
+            let target = optional(tracker, "target", compound)?;
+            write_optional(out, target, |out, target| {
+                let dimension = string(required(target, "dimension")?, "dimension")?;
+                buf::write_string(out, &identifier(dimension));
+                match elements(required(target, "pos")?, "block position")?.as_slice() {
+                    [x, y, z] => buf::write_block_pos(
+                        out,
+                        BlockPos::new(int(x, "x")?, int(y, "y")?, int(z, "z")?),
+                    ),
+                    _ => return Err(Error::Invalid("block position")),
+                }
+                Ok(())
+            })?;
+            buf::write_bool(out, optional(tracker, "tracked", boolean)?.unwrap_or(true));
+        }
+        "minecraft:pot_decorations" => {
+            write_list(out, list(value, "pot decorations")?, |out, item| {
+                write_id(out, ctx.items, &identifier(string(item, "item")?), "item")
+            })?;
+        }
+        "minecraft:repairable" => {
+            let items = required(compound(value, "repairable")?, "items")?;
+            write_item_set(out, items, ctx)?;
+        }
+        "minecraft:writable_book_content" => {
+            let book = compound(value, "writable book content")?;
+            let pages = optional(book, "pages", list)?.unwrap_or_default();
+            write_list(out, pages, |out, page| match page {
+                Tag::Compound(page) if page.contains_key("raw") => {
+                    buf::write_string(out, string(&page["raw"], "page")?);
+                    let filtered = optional(page, "filtered", string)?;
+                    write_optional(
+                        out,
+                        filtered.map(String::from).as_ref(),
+                        super::write_string,
+                    )
+                }
+                page => {
+                    buf::write_string(out, string(page, "page")?);
+                    buf::write_bool(out, false);
+                    Ok(())
+                }
+            })?;
+        }
+        "minecraft:use_remainder" => {
+            let remainder = required(compound(value, "use remainder")?, "convert_into")?;
+            write_item_stack(out, remainder, ctx)?;
+        }
+        "minecraft:charged_projectiles" | "minecraft:bundle_contents" => {
+            write_list(out, list(value, "item stacks")?, |out, stack| {
+                write_item_stack(out, stack, ctx)
+            })?;
+        }
+        "minecraft:potion_contents" => write_potion_contents(out, value, ctx)?,
+        "minecraft:suspicious_stew_effects" => {
+            write_list(out, list(value, "stew effects")?, |out, effect| {
+                let effect = compound(effect, "stew effect")?;
+                write_holder(out, ctx, MOB_EFFECT, required(effect, "id")?, "effect")?;
+                let duration = optional(effect, "duration", int)?;
+                buf::write_varint(out, duration.unwrap_or(DEFAULT_STEW_EFFECT_DURATION));
+                Ok(())
+            })?;
+        }
+        "minecraft:attribute_modifiers" => {
+            write_list(out, list(value, "attribute modifiers")?, |out, modifier| {
+                write_attribute_modifier(out, modifier, ctx)
+            })?;
+        }
+        "minecraft:trim" => {
+            let trim = compound(value, "trim")?;
+            let material = required(trim, "material")?;
+            write_reference(out, ctx, TRIM_MATERIAL, material, "trim material")?;
+            let pattern = required(trim, "pattern")?;
+            write_reference(out, ctx, TRIM_PATTERN, pattern, "trim pattern")?;
+        }
+        "minecraft:banner_patterns" => {
+            write_list(out, list(value, "banner patterns")?, |out, layer| {
+                let layer = compound(layer, "banner pattern layer")?;
+                let pattern = required(layer, "pattern")?;
+                write_reference(out, ctx, BANNER_PATTERN, pattern, "banner pattern")?;
+                write_enum(out, &DYE_COLORS, required(layer, "color")?, "dye color")
+            })?;
+        }
+        _ => return Err(Error::UnsupportedComponent(name.into())),
+    }
+    Ok(())
+}
+
+fn fields<const N: usize>(fields: [(&str, Tag); N]) -> Tag {
+    let mut compound = Compound::new();
+    for (key, value) in fields {
+        compound.insert(key, value);
+    }
+    compound.into()
+}
+
+/// Inserts `value` unless it is the default the codec leaves out.
+fn put_unless<T: PartialEq + Into<Tag>>(compound: &mut Compound, key: &str, value: T, default: T) {
+    if value != default {
+        compound.insert(key, value);
+    }
+}
+
+fn compound<'a>(tag: &'a Tag, what: &'static str) -> Result<&'a Compound> {
+    tag.as_compound().ok_or(Error::Invalid(what))
+}
+
+fn list<'a>(tag: &'a Tag, what: &'static str) -> Result<&'a [Tag]> {
+    tag.as_list().ok_or(Error::Invalid(what))
+}
+
+/// The elements of a list or an array tag, which the codec reads alike.
+fn elements(tag: &Tag, what: &'static str) -> Result<Vec<Tag>> {
+    Ok(match tag {
+        Tag::List(list) => list.clone(),
+        Tag::ByteArray(array) => array.iter().map(|&v| v.into()).collect(),
+        Tag::IntArray(array) => array.iter().map(|&v| v.into()).collect(),
+        Tag::LongArray(array) => array.iter().map(|&v| v.into()).collect(),
+        _ => return Err(Error::Invalid(what)),
+    })
+}
+
+fn string<'a>(tag: &'a Tag, what: &'static str) -> Result<&'a str> {
+    tag.as_str().ok_or(Error::Invalid(what))
+}
+
+fn int(tag: &Tag, what: &'static str) -> Result<i32> {
+    tag.as_i64()
+        .and_then(|value| i32::try_from(value).ok())
+        .ok_or(Error::Invalid(what))
+}
+
+fn float(tag: &Tag, what: &'static str) -> Result<f32> {
+    Ok(double(tag, what)? as f32)
+}
+
+fn double(tag: &Tag, what: &'static str) -> Result<f64> {
+    tag.as_f64().ok_or(Error::Invalid(what))
+}
+
+fn boolean(tag: &Tag, what: &'static str) -> Result<bool> {
+    tag.as_bool().ok_or(Error::Invalid(what))
+}
+
+fn required<'a>(compound: &'a Compound, key: &'static str) -> Result<&'a Tag> {
+    compound.get(key).ok_or(Error::Invalid(key))
+}
+
+/// Reads `key` with `read` if it is there, naming it in errors.
+fn optional<'a, T>(
+    compound: &'a Compound,
+    key: &'static str,
+    read: impl FnOnce(&'a Tag, &'static str) -> Result<T>,
+) -> Result<Option<T>> {
+    compound.get(key).map(|value| read(value, key)).transpose()
+}
+
+fn read_enum(buf: &mut &[u8], names: &[&str], what: &'static str) -> Result<Tag> {
+    let id = buf::read_varint(buf)?;
+    usize::try_from(id)
+        .ok()
+        .and_then(|id| names.get(id))
+        .map(|&name| name.into())
+        .ok_or(Error::Invalid(what))
+}
+
+fn write_enum(out: &mut Vec<u8>, names: &[&str], value: &Tag, what: &'static str) -> Result<()> {
+    let name = string(value, what)?;
+    let id = names
+        .iter()
+        .position(|&entry| entry == name)
+        .ok_or(Error::Invalid(what))?;
+    buf::write_varint(out, id as i32);
+    Ok(())
+}
+
+/// A tag key, written `#namespace:path` in NBT.
+fn read_tag_key(buf: &mut &[u8]) -> Result<Tag> {
+    Ok(format!("#{}", buf::read_identifier(buf)?).into())
+}
+
+fn write_tag_key(out: &mut Vec<u8>, value: &Tag) -> Result<()> {
+    let key = string(value, "tag key")?;
+    let key = key.strip_prefix('#').ok_or(Error::Invalid("tag key"))?;
+    buf::write_string(out, &identifier(key));
+    Ok(())
+}
+
+/// An entry of a built-in registry, by id.
+fn write_holder(
+    out: &mut Vec<u8>,
+    ctx: &ItemContext,
+    registry: &'static str,
+    value: &Tag,
+    what: &'static str,
+) -> Result<()> {
+    let name = identifier(string(value, what)?);
+    write_id(out, ctx.registry(registry)?, &name, what)
+}
+
+/// An entry of a synchronised registry: its id plus one, or 0 followed by
+/// an inline value, which is not supported.
+fn read_reference(
+    buf: &mut &[u8],
+    ctx: &ItemContext,
+    registry: &'static str,
+    what: &'static str,
+) -> Result<Tag> {
+    let id = buf::read_varint(buf)?;
+    if id == 0 {
+        return Err(Error::Invalid("inline registry entry"));
+    }
+    let ids = ctx.registry(registry)?;
+    u32::try_from(id - 1)
+        .ok()
+        .and_then(|id| ids.name(id))
+        .map(Tag::from)
+        .ok_or(Error::Invalid(what))
+}
+
+fn write_reference(
+    out: &mut Vec<u8>,
+    ctx: &ItemContext,
+    registry: &'static str,
+    value: &Tag,
+    what: &'static str,
+) -> Result<()> {
+    let name = identifier(string(value, what)?);
+    let id = ctx
+        .registry(registry)?
+        .id(&name)
+        .ok_or(Error::Invalid(what))?;
+    buf::write_varint(out, id as i32 + 1);
+    Ok(())
+}
+
+/// A stack that must not be empty, as in `ItemStack.STREAM_CODEC`.
+fn read_item_stack(buf: &mut &[u8], ctx: &ItemContext) -> Result<Tag> {
+    let stack = read_optional_item_stack(buf, ctx)?.ok_or(Error::Invalid("empty item stack"))?;
+    Ok(stack.to_nbt())
+}
+
+fn write_item_stack(out: &mut Vec<u8>, value: &Tag, ctx: &ItemContext) -> Result<()> {
+    let stack = ItemStack::from_nbt(value).map_err(|_| Error::Invalid("item stack"))?;
+    write_optional_item_stack(out, Some(&stack), ctx)
+}
+
+/// A holder set of items: 0 followed by a tag, or the number of items plus
+/// one followed by their ids. In NBT a tag is `#name` and a single item is
+/// written without a list.
+fn read_item_set(buf: &mut &[u8], ctx: &ItemContext) -> Result<Tag> {
+    let len = buf::read_len(buf)?;
+    if len == 0 {
+        return read_tag_key(buf);
+    }
+    let mut items = Vec::new();
+    for _ in 1..len {
+        items.push(Tag::from(read_name(buf, ctx.items, "item id")?));
+    }
+    Ok(match <[Tag; 1]>::try_from(items) {
+        Ok([item]) => item,
+        Err(items) => items.into(),
+    })
+}
+
+fn write_item_set(out: &mut Vec<u8>, value: &Tag, ctx: &ItemContext) -> Result<()> {
+    if let Tag::String(name) = value
+        && name.starts_with('#')
+    {
+        buf::write_varint(out, 0);
+        return write_tag_key(out, value);
+    }
+    let items = match value {
+        Tag::List(items) => items.as_slice(),
+        item => core::slice::from_ref(item),
+    };
+    buf::write_varint(out, items.len() as i32 + 1);
+    for item in items {
+        write_id(out, ctx.items, &identifier(string(item, "item")?), "item")?;
+    }
+    Ok(())
+}
+
+/// Lists of floats, flags, strings and colours; flags and colours are
+/// arrays in NBT.
+fn read_custom_model_data(buf: &mut &[u8]) -> Result<Tag> {
+    let floats: Vec<Tag> = read_list(buf, UNBOUNDED, |buf| Ok(buf::read_f32(buf)?.into()))?;
+    let flags: Vec<i8> = read_list(buf, UNBOUNDED, |buf| Ok(buf::read_bool(buf)? as i8))?;
+    let strings: Vec<Tag> = read_list(buf, UNBOUNDED, |buf| {
+        Ok(buf::read_string(buf, buf::MAX_STRING_CHARS)?.into())
+    })?;
+    let colors: Vec<i32> = read_list(buf, UNBOUNDED, buf::read_i32)?;
+    let mut data = Compound::new();
+    put_unless(&mut data, "floats", floats, Vec::new());
+    put_unless(&mut data, "flags", flags, Vec::new());
+    put_unless(&mut data, "strings", strings, Vec::new());
+    put_unless(&mut data, "colors", colors, Vec::new());
+    Ok(data.into())
+}
+
+fn write_custom_model_data(out: &mut Vec<u8>, value: &Tag) -> Result<()> {
+    let data = compound(value, "custom model data")?;
+    let floats = optional(data, "floats", elements)?.unwrap_or_default();
+    write_list(out, &floats, |out, value| {
+        buf::write_f32(out, float(value, "float")?);
+        Ok(())
+    })?;
+    let flags = optional(data, "flags", elements)?.unwrap_or_default();
+    write_list(out, &flags, |out, value| {
+        buf::write_bool(out, boolean(value, "flag")?);
+        Ok(())
+    })?;
+    let strings = optional(data, "strings", elements)?.unwrap_or_default();
+    write_list(out, &strings, |out, value| {
+        buf::write_string(out, string(value, "string")?);
+        Ok(())
+    })?;
+    let colors = optional(data, "colors", elements)?.unwrap_or_default();
+    write_list(out, &colors, |out, value| {
+        buf::write_i32(out, int(value, "color")?);
+        Ok(())
+    })
+}
+
+fn read_explosion(buf: &mut &[u8]) -> Result<Tag> {
+    let mut explosion = Compound::new();
+    explosion.insert("shape", read_enum(buf, &FIREWORK_SHAPES, "firework shape")?);
+    let colors: Vec<i32> = read_list(buf, UNBOUNDED, buf::read_i32)?;
+    put_unless(&mut explosion, "colors", colors, Vec::new());
+    let fade_colors: Vec<i32> = read_list(buf, UNBOUNDED, buf::read_i32)?;
+    put_unless(&mut explosion, "fade_colors", fade_colors, Vec::new());
+    put_unless(&mut explosion, "has_trail", buf::read_bool(buf)?, false);
+    put_unless(&mut explosion, "has_twinkle", buf::read_bool(buf)?, false);
+    Ok(explosion.into())
+}
+
+fn write_explosion(out: &mut Vec<u8>, value: &Tag) -> Result<()> {
+    let explosion = compound(value, "firework explosion")?;
+    write_enum(
+        out,
+        &FIREWORK_SHAPES,
+        required(explosion, "shape")?,
+        "firework shape",
+    )?;
+    for key in ["colors", "fade_colors"] {
+        let colors = optional(explosion, key, elements)?.unwrap_or_default();
+        write_list(out, &colors, |out, color| {
+            buf::write_i32(out, int(color, "color")?);
+            Ok(())
+        })?;
+    }
+    buf::write_bool(
+        out,
+        optional(explosion, "has_trail", boolean)?.unwrap_or(false),
+    );
+    buf::write_bool(
+        out,
+        optional(explosion, "has_twinkle", boolean)?.unwrap_or(false),
+    );
+    Ok(())
+}
+
+/// An optional potion, an optional colour, custom effects and an optional
+/// name. NBT may also give just the potion.
+fn read_potion_contents(buf: &mut &[u8], ctx: &ItemContext) -> Result<Tag> {
+    let mut contents = Compound::new();
+    if let Some(potion) = read_optional(buf, |buf| {
+        read_name(buf, ctx.registry(POTION)?, "potion id")
+    })? {
+        contents.insert("potion", potion);
+    }
+    if let Some(color) = read_optional(buf, buf::read_i32)? {
+        contents.insert("custom_color", color);
+    }
+    let effects = read_list(buf, UNBOUNDED, |buf| {
+        let mut effect = Compound::new();
+        effect.insert(
+            "id",
+            read_name(buf, ctx.registry(MOB_EFFECT)?, "effect id")?,
+        );
+        read_effect_details(buf, &mut effect, 0)?;
+        Ok(Tag::from(effect))
+    })?;
+    put_unless(&mut contents, "custom_effects", effects, Vec::new());
+    if let Some(name) = read_optional(buf, |buf| {
+        Ok(buf::read_string(buf, buf::MAX_STRING_CHARS)?.to_string())
+    })? {
+        contents.insert("custom_name", name);
+    }
+    Ok(contents.into())
+}
+
+fn write_potion_contents(out: &mut Vec<u8>, value: &Tag, ctx: &ItemContext) -> Result<()> {
+    if let Tag::String(_) = value {
+        buf::write_bool(out, true);
+        write_holder(out, ctx, POTION, value, "potion")?;
+        // no colour, effects or name
+        out.extend_from_slice(&[0, 0, 0]);
+        return Ok(());
+    }
+    let contents = compound(value, "potion contents")?;
+    write_optional(out, contents.get("potion"), |out, potion| {
+        write_holder(out, ctx, POTION, potion, "potion")
+    })?;
+    write_optional(
+        out,
+        optional(contents, "custom_color", int)?.as_ref(),
+        |out, color| {
+            buf::write_i32(out, *color);
+            Ok(())
+        },
+    )?;
+    let effects = optional(contents, "custom_effects", list)?.unwrap_or_default();
+    write_list(out, effects, |out, effect| {
+        let effect = compound(effect, "effect")?;
+        write_holder(out, ctx, MOB_EFFECT, required(effect, "id")?, "effect")?;
+        write_effect_details(out, effect, 0)
+    })?;
+    let name = optional(contents, "custom_name", string)?.map(String::from);
+    write_optional(out, name.as_ref(), super::write_string)
+}
+
+/// The amplifier, duration and flags of an effect instance, then the weaker
+/// one it hides, if any.
+fn read_effect_details(buf: &mut &[u8], effect: &mut Compound, depth: usize) -> Result<()> {
+    if depth > MAX_HIDDEN_EFFECTS {
+        return Err(Error::Invalid("hidden effect depth"));
+    }
+    put_unless(effect, "amplifier", buf::read_varint(buf)? as i8, 0);
+    put_unless(effect, "duration", buf::read_varint(buf)?, 0);
+    put_unless(effect, "ambient", buf::read_bool(buf)?, false);
+    put_unless(effect, "show_particles", buf::read_bool(buf)?, true);
+    effect.insert("show_icon", buf::read_bool(buf)?);
+    if buf::read_bool(buf)? {
+        let mut hidden = Compound::new();
+        read_effect_details(buf, &mut hidden, depth + 1)?;
+        effect.insert("hidden_effect", hidden);
+    }
+    Ok(())
+}
+
+fn write_effect_details(out: &mut Vec<u8>, effect: &Compound, depth: usize) -> Result<()> {
+    if depth > MAX_HIDDEN_EFFECTS {
+        return Err(Error::Invalid("hidden effect depth"));
+    }
+    let amplifier = optional(effect, "amplifier", int)?.unwrap_or(0);
+    buf::write_varint(out, amplifier as u8 as i32);
+    buf::write_varint(out, optional(effect, "duration", int)?.unwrap_or(0));
+    buf::write_bool(out, optional(effect, "ambient", boolean)?.unwrap_or(false));
+    let show_particles = optional(effect, "show_particles", boolean)?.unwrap_or(true);
+    buf::write_bool(out, show_particles);
+    let show_icon = optional(effect, "show_icon", boolean)?;
+    buf::write_bool(out, show_icon.unwrap_or(show_particles));
+    write_optional(
+        out,
+        optional(effect, "hidden_effect", compound)?,
+        |out, hidden| write_effect_details(out, hidden, depth + 1),
+    )
+}
+
+/// The attribute, the modifier's id, amount and operation, the slots it
+/// applies in and how the tooltip shows it.
+fn read_attribute_modifier(buf: &mut &[u8], ctx: &ItemContext) -> Result<Tag> {
+    let mut modifier = Compound::new();
+    modifier.insert(
+        "type",
+        read_name(buf, ctx.registry(ATTRIBUTE)?, "attribute id")?,
+    );
+    modifier.insert("id", read_identifier(buf)?);
+    modifier.insert("amount", buf::read_f64(buf)?);
+    modifier.insert(
+        "operation",
+        read_enum(buf, &ATTRIBUTE_OPERATIONS, "attribute operation")?,
+    );
+    let slot = read_enum(buf, &EQUIPMENT_SLOT_GROUPS, "equipment slot group")?;
+    put_unless(&mut modifier, "slot", slot, "any".into());
+    let display = match read_enum(buf, &MODIFIER_DISPLAYS, "modifier display")? {
+        Tag::String(kind) if kind == "override" => {
+            fields([("type", kind.into()), ("value", read_text(buf)?.to_nbt())])
+        }
+        kind => fields([("type", kind)]),
+    };
+    put_unless(
+        &mut modifier,
+        "display",
+        display,
+        fields([("type", "default".into())]),
+    );
+    Ok(modifier.into())
+}
+
+fn write_attribute_modifier(out: &mut Vec<u8>, value: &Tag, ctx: &ItemContext) -> Result<()> {
+    let modifier = compound(value, "attribute modifier")?;
+    write_holder(
+        out,
+        ctx,
+        ATTRIBUTE,
+        required(modifier, "type")?,
+        "attribute",
+    )?;
+    let id = string(required(modifier, "id")?, "modifier id")?;
+    buf::write_string(out, &identifier(id));
+    buf::write_f64(out, double(required(modifier, "amount")?, "amount")?);
+    let operation = required(modifier, "operation")?;
+    write_enum(out, &ATTRIBUTE_OPERATIONS, operation, "attribute operation")?;
+    let any = Tag::from("any");
+    let slot = modifier.get("slot").unwrap_or(&any);
+    write_enum(out, &EQUIPMENT_SLOT_GROUPS, slot, "equipment slot group")?;
+    let Some(display) = optional(modifier, "display", compound)? else {
+        buf::write_varint(out, 0);
+        return Ok(());
+    };
+    let kind = required(display, "type")?;
+    write_enum(out, &MODIFIER_DISPLAYS, kind, "modifier display")?;
+    if kind.as_str() == Some("override") {
+        let text = Component::from_nbt(required(display, "value")?)
+            .map_err(|_| Error::Invalid("text component"))?;
+        text.write_network_nbt(out);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_names_are_sorted_and_unique() {
+        assert!(SUPPORTED.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
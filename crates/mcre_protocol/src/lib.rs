@@ -0,0 +1,18 @@
+//! Packet body codecs for the Minecraft protocol.
+//!
+//! Framing (length prefixes, compression and encryption) is left to the
+//! connection layer; everything here works on the body of a single packet.
+
+#![no_std]
+
+extern crate alloc;
+
+mod bitset;
+pub mod buf;
+pub mod chunk;
+mod error;
+pub mod item;
+pub mod registry;
+
+pub use bitset::BitSet;
+pub use error::{Error, Result};
@@ -0,0 +1,470 @@
+//! Dynamic registries synchronised during the configuration phase.
+//!
+//! The server sends one `registry_data` packet per registry. Entries carry
+//! their data as NBT unless the client claimed a known pack that already
+//! contains them; since we ship no vanilla data, clients should answer
+//! `select_known_packs` with an empty list so every entry arrives with data.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use hashbrown::HashMap;
+use mcre_nbt::{Compound, Tag};
+use mcre_world::{BiomeId, WorldHeight};
+use rustc_hash::FxBuildHasher;
+use serde::Deserialize;
+
+use crate::{
+    Result, buf,
+    chunk::ChunkContext,
+    item::{IdMap, ItemContext, RegistryLookup},
+};
+
+pub const DIMENSION_TYPE: &str = "minecraft:dimension_type";
+pub const BIOME: &str = "minecraft:worldgen/biome";
+pub const ENCHANTMENT: &str = "minecraft:enchantment";
+pub const TRIM_MATERIAL: &str = "minecraft:trim_material";
+pub const TRIM_PATTERN: &str = "minecraft:trim_pattern";
+pub const BANNER_PATTERN: &str = "minecraft:banner_pattern";
+
+// Built-in registries item components refer to, which are not synchronised.
+pub const POTION: &str = "minecraft:potion";
+pub const MOB_EFFECT: &str = "minecraft:mob_effect";
+pub const ATTRIBUTE: &str = "minecraft:attribute";
+
+/// A data pack the server offers to skip sending, as in `KnownPack`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownPack {
+    pub namespace: String,
+    pub id: String,
+    pub version: String,
+}
+
+/// The body of a clientbound `registry_data` packet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegistryData {
+    pub registry: String,
+    /// Entries in network id order; `None` data refers to a known pack.
+    pub entries: Vec<(String, Option<Compound>)>,
+}
+
+pub fn decode_select_known_packs(mut body: &[u8]) -> Result<Vec<KnownPack>> {
+    let count = buf::read_len(&mut body)?;
+    let mut packs = Vec::with_capacity(count.min(64));
+    for _ in 0..count {
+        packs.push(KnownPack {
+            namespace: buf::read_string(&mut body, buf::MAX_STRING_CHARS)?.into(),
+            id: buf::read_string(&mut body, buf::MAX_STRING_CHARS)?.into(),
+            version: buf::read_string(&mut body, buf::MAX_STRING_CHARS)?.into(),
+        });
+    }
+    buf::finish(body)?;
+    Ok(packs)
+}
+
+/// The serverbound reply listing the packs this client already has.
+pub fn encode_select_known_packs(packs: &[KnownPack]) -> Vec<u8> {
+    let mut out = Vec::new();
+    buf::write_varint(&mut out, packs.len() as i32);
+    for pack in packs {
+        buf::write_string(&mut out, &pack.namespace);
+        buf::write_string(&mut out, &pack.id);
+        buf::write_string(&mut out, &pack.version);
+    }
+    out
+}
+
+pub fn decode_registry_data(mut body: &[u8]) -> Result<RegistryData> {
+    let registry = buf::read_identifier(&mut body)?.to_string();
+    let count = buf::read_len(&mut body)?;
+    let mut entries = Vec::with_capacity(count.min(1024));
+    for _ in 0..count {
+        let name = buf::read_identifier(&mut body)?.to_string();
+        let data = if buf::read_bool(&mut body)? {
+            buf::read_optional_compound(&mut body)?
+        } else {
+            None
+        };
+        entries.push((name, data));
+    }
+    buf::finish(body)?;
+    Ok(RegistryData { registry, entries })
+}
+
+/// The entries of one registry, indexed by network id and by name.
+#[derive(Debug, Clone)]
+pub struct Registry<T> {
+    entries: Vec<(String, Option<T>)>,
+    ids: HashMap<String, u32, FxBuildHasher>,
+}
+
+impl<T> Default for Registry<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            ids: HashMap::default(),
+        }
+    }
+}
+
+impl<T> Registry<T> {
+    pub fn from_entries(entries: Vec<(String, Option<T>)>) -> Self {
+        let ids = entries
+            .iter()
+            .enumerate()
+            .map(|(id, (name, _))| (name.clone(), id as u32))
+            .collect();
+        Self { entries, ids }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: u32) -> Option<&str> {
+        self.entries.get(id as usize).map(|(name, _)| name.as_str())
+    }
+
+    /// The entry's data, if it was sent rather than taken from a known pack.
+    pub fn get(&self, id: u32) -> Option<&T> {
+        self.entries.get(id as usize)?.1.as_ref()
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<&T> {
+        self.get(self.id(name)?)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &str, Option<&T>)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(id, (name, value))| (id as u32, name.as_str(), value.as_ref()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DimensionType {
+    pub min_y: i32,
+    pub height: u32,
+    pub logical_height: u32,
+    #[serde(default)]
+    pub has_skylight: bool,
+    #[serde(default)]
+    pub has_ceiling: bool,
+    #[serde(default = "default_coordinate_scale")]
+    pub coordinate_scale: f64,
+    #[serde(default)]
+    pub ambient_light: f32,
+    pub fixed_time: Option<i64>,
+    /// Sky and fog rendering preset, e.g. `minecraft:the_nether`.
+    pub effects: Option<String>,
+}
+
+fn default_coordinate_scale() -> f64 {
+    1.0
+}
+
+impl DimensionType {
+    pub fn world_height(&self) -> WorldHeight {
+        WorldHeight {
+            min_y: self.min_y,
+            height: self.height,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Biome {
+    pub has_precipitation: bool,
+    pub temperature: f32,
+    #[serde(default)]
+    pub temperature_modifier: TemperatureModifier,
+    pub downfall: f32,
+    #[serde(default)]
+    pub effects: BiomeEffects,
+    /// Environment attributes, which newer versions use for sky and fog
+    /// colours instead of `effects`.
+    #[serde(default)]
+    pub attributes: Compound,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemperatureModifier {
+    #[default]
+    None,
+    Frozen,
+}
+
+/// Colours are `0xRRGGBB`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct BiomeEffects {
+    pub sky_color: Option<u32>,
+    pub fog_color: Option<u32>,
+    pub water_color: Option<u32>,
+    pub water_fog_color: Option<u32>,
+    pub foliage_color: Option<u32>,
+    pub dry_foliage_color: Option<u32>,
+    pub grass_color: Option<u32>,
+    #[serde(default)]
+    pub grass_color_modifier: GrassColorModifier,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GrassColorModifier {
+    #[default]
+    None,
+    DarkForest,
+    Swamp,
+}
+
+impl GrassColorModifier {
+    /// Applies the modifier to a grass colour. `swamp_noise` is vanilla's
+    /// `BIOME_INFO_NOISE` sampled at `(x * 0.0225, z * 0.0225)`, and is only
+    /// used by swamps.
+    pub fn apply(self, color: u32, swamp_noise: f64) -> u32 {
+        match self {
+            Self::None => color,
+            Self::DarkForest => ((color & 0xFEFEFE) + 0x28340A) >> 1,
+            Self::Swamp if swamp_noise < -0.1 => 0x4C763C,
+            Self::Swamp => 0x6A7039,
+        }
+    }
+}
+
+impl Biome {
+    pub fn sky_color(&self) -> Option<u32> {
+        self.effects
+            .sky_color
+            .or_else(|| self.attribute_color("minecraft:visual/sky_color"))
+    }
+
+    pub fn fog_color(&self) -> Option<u32> {
+        self.effects
+            .fog_color
+            .or_else(|| self.attribute_color("minecraft:visual/fog_color"))
+    }
+
+    pub fn water_fog_color(&self) -> Option<u32> {
+        self.effects
+            .water_fog_color
+            .or_else(|| self.attribute_color("minecraft:visual/water_fog_color"))
+    }
+
+    /// An attribute colour given either as an int or as `#RRGGBB`.
+    fn attribute_color(&self, key: &str) -> Option<u32> {
+        match self.attributes.get(key)? {
+            Tag::String(s) => u32::from_str_radix(s.strip_prefix('#')?, 16).ok(),
+            tag => tag.as_i64().map(|c| c as u32 & 0xFFFFFF),
+        }
+    }
+}
+
+/// Every registry the server has synchronised.
+#[derive(Debug, Clone, Default)]
+pub struct Registries {
+    pub dimension_type: Registry<DimensionType>,
+    pub biome: Registry<Biome>,
+    raw: HashMap<String, Registry<Compound>, FxBuildHasher>,
+}
+
+impl Registries {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces a registry with the contents of a `registry_data` packet.
+    pub fn apply(&mut self, data: RegistryData) -> Result<()> {
+        match data.registry.as_str() {
+            DIMENSION_TYPE => self.dimension_type = decode_entries(&data.entries)?,
+            BIOME => self.biome = decode_entries(&data.entries)?,
+            _ => {}
+        }
+        self.raw
+            .insert(data.registry, Registry::from_entries(data.entries));
+        Ok(())
+    }
+
+    /// Any synchronised registry, with its entries left as NBT.
+    pub fn get(&self, registry: &str) -> Option<&Registry<Compound>> {
+        self.raw.get(registry)
+    }
+
+    pub fn biome(&self, id: BiomeId) -> Option<&Biome> {
+        self.biome.get(u32::from(id.0))
+    }
+
+    /// The decoding context for chunks of a dimension type, given by the id
+    /// the `login` and `respawn` packets refer to it with.
+    pub fn chunk_context(&self, dimension_type: u32) -> Option<ChunkContext> {
+        Some(ChunkContext {
+            height: self.dimension_type.get(dimension_type)?.world_height(),
+            biome_count: self.biome.len(),
+            state_ids: None,
+        })
+    }
+
+    /// The context for item stacks, given the item and data component type
+    /// registries and the other built-in ones components refer to, none of
+    /// which are synchronised.
+    pub fn item_context<'a>(
+        &'a self,
+        items: &'a dyn IdMap,
+        components: &'a dyn IdMap,
+        builtin: &'a dyn RegistryLookup,
+    ) -> ItemContext<'a> {
+        ItemContext {
+            items,
+            components,
+            builtin,
+            synced: self,
+        }
+    }
+}
+
+impl RegistryLookup for Registries {
+    fn registry(&self, name: &str) -> Option<&dyn IdMap> {
+        self.get(name).map(|registry| registry as &dyn IdMap)
+    }
+}
+
+fn decode_entries<T: for<'de> Deserialize<'de>>(
+    entries: &[(String, Option<Compound>)],
+) -> Result<Registry<T>> {
+    let entries = entries
+        .iter()
+        .map(|(name, data)| {
+            let value = data.as_ref().map(mcre_nbt::from_compound).transpose()?;
+            Ok((name.clone(), value))
+        })
+        .collect::<Result<_>>()?;
+    Ok(Registry::from_entries(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    fn encode_registry_data(data: &RegistryData) -> Vec<u8> {
+        let mut out = Vec::new();
+        buf::write_string(&mut out, &data.registry);
+        buf::write_varint(&mut out, data.entries.len() as i32);
+        for (name, value) in &data.entries {
+            buf::write_string(&mut out, name);
+            buf::write_bool(&mut out, value.is_some());
+            if let Some(value) = value {
+                mcre_nbt::binary::write_nameless(&mut out, Some(&Tag::Compound(value.clone())))
+                    .unwrap();
+            }
+        }
+        out
+    }
+
+    fn snbt(s: &str) -> Option<Compound> {
+        Some(mcre_nbt::from_snbt(s).unwrap())
+    }
+
+    #[test]
+    fn test_registry_data_round_trip() {
+        let data = RegistryData {
+            registry: "minecraft:damage_type".into(),
+            entries: vec![
+                ("minecraft:arrow".into(), None),
+                (
+                    "minecraft:cactus".into(),
+                    snbt(
+                        "{exhaustion:0.1f,message_id:\"cactus\",scaling:\"when_caused_by_living_non_player\"}",
+                    ),
+                ),
+            ],
+        };
+        let body = encode_registry_data(&data);
+        assert_eq!(decode_registry_data(&body).unwrap(), data);
+
+        let mut registries = Registries::new();
+        registries.apply(data).unwrap();
+        let damage_types = registries.get("minecraft:damage_type").unwrap();
+        assert_eq!(damage_types.id("minecraft:cactus"), Some(1));
+        assert_eq!(damage_types.name(0), Some("minecraft:arrow"));
+        assert!(damage_types.get(0).is_none());
+    }
+
+    #[test]
+    fn test_dimension_types_and_biomes() {
+        let mut registries = Registries::new();
+        registries
+            .apply(RegistryData {
+                registry: DIMENSION_TYPE.into(),
+                entries: vec![
+                    ("minecraft:overworld".into(), snbt("{min_y:-64,height:384,logical_height:384,has_skylight:1b,effects:\"minecraft:overworld\"}")),
+                    ("minecraft:the_nether".into(), snbt("{min_y:0,height:256,logical_height:128,has_ceiling:1b,coordinate_scale:8.0d,ambient_light:0.1f,fixed_time:18000L}")),
+                ],
+            })
+            .unwrap();
+        registries
+            .apply(RegistryData {
+                registry: BIOME.into(),
+                entries: vec![
+                    ("minecraft:dark_forest".into(), snbt("{has_precipitation:1b,temperature:0.7f,downfall:0.8f,effects:{sky_color:7972607,water_color:4159204,grass_color_modifier:\"dark_forest\"}}")),
+                    ("minecraft:plains".into(), snbt("{has_precipitation:1b,temperature:0.8f,downfall:0.4f,effects:{water_color:4159204},attributes:{\"minecraft:visual/sky_color\":\"#78A7FF\",\"minecraft:visual/fog_color\":12638463}}")),
+                ],
+            })
+            .unwrap();
+
+        let nether = registries
+            .dimension_type
+            .get_by_name("minecraft:the_nether")
+            .unwrap();
+        assert_eq!(nether.coordinate_scale, 8.0);
+        assert_eq!(nether.fixed_time, Some(18000));
+        assert_eq!(
+            registries.chunk_context(0),
+            Some(ChunkContext {
+                height: WorldHeight::OVERWORLD,
+                biome_count: 2,
+                state_ids: None,
+            })
+        );
+        assert_eq!(registries.chunk_context(1).unwrap().height.height, 256);
+        assert_eq!(registries.chunk_context(2), None);
+
+        let dark_forest = registries.biome(BiomeId(0)).unwrap();
+        assert_eq!(dark_forest.sky_color(), Some(7972607));
+        assert_eq!(
+            dark_forest
+                .effects
+                .grass_color_modifier
+                .apply(0x79C05A, 0.0),
+            ((0x79C05A & 0xFEFEFE) + 0x28340A) >> 1
+        );
+        let plains = registries.biome(BiomeId(1)).unwrap();
+        assert_eq!(plains.sky_color(), Some(0x78A7FF));
+        assert_eq!(plains.fog_color(), Some(12638463));
+        assert_eq!(plains.water_fog_color(), None);
+    }
+
+    #[test]
+    fn test_select_known_packs_round_trip() {
+        let packs = vec![KnownPack {
+            namespace: "minecraft".into(),
+            id: "core".into(),
+            version: "26.1".into(),
+        }];
+        assert_eq!(
+            decode_select_known_packs(&encode_select_known_packs(&packs)).unwrap(),
+            packs
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use std::{fmt, io};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Nbt(mcre_nbt::Error),
+    /// A file whose extension is not one of the supported formats.
+    UnknownFormat(String),
+    /// A Sponge schematic version other than 1, 2 or 3.
+    UnsupportedVersion(i32),
+    /// NBT that is well-formed but not laid out as the format requires.
+    Invalid(&'static str),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<mcre_nbt::Error> for Error {
+    fn from(error: mcre_nbt::Error) -> Self {
+        Self::Nbt(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "I/O error: {error}"),
+            Self::Nbt(error) => write!(f, "invalid NBT: {error}"),
+            Self::UnknownFormat(extension) => {
+                write!(f, "unknown structure format `{extension}`")
+            }
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Sponge schematic version {version} is not supported")
+            }
+            Self::Invalid(what) => write!(f, "invalid structure: {what}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Nbt(error) => Some(error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Structures and schematics: vanilla structure files (`.nbt`), Sponge
+//! schematics (`.schem`) and Litematica schematics (`.litematic`), loaded into
+//! one [`Structure`] model that can be placed into a [`mcre_world::World`]
+//! with a rotation and mirror.
+//!
+//! Block states are parsed by name, and the ones this build does not know are
+//! listed in [`Structure::unknown_blocks`] rather than failing the load.
+
+mod error;
+mod litematic;
+mod sponge;
+mod structure;
+mod vanilla;
+
+use std::{fs, path::Path};
+
+pub use error::{Error, Result};
+pub use litematic::{Litematic, LitematicRegion};
+pub use structure::{PlaceSettings, Structure, StructureBlock, StructureEntity};
+
+/// Loads a structure file, picking the format from its extension. The
+/// regions of a Litematica schematic are merged.
+pub fn load(path: impl AsRef<Path>) -> Result<Structure> {
+    let path = path.as_ref();
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+    let (_, root) = mcre_nbt::from_compressed_bytes(&fs::read(path)?)?;
+    match extension {
+        "nbt" => Structure::from_vanilla(&root),
+        "schem" => Structure::from_sponge(&root),
+        "litematic" => Ok(Litematic::from_nbt(&root)?.to_structure()),
+        extension => Err(Error::UnknownFormat(extension.to_string())),
+    }
+}
@@ -0,0 +1,318 @@
+//! Litematica schematics (`.litematic`), which hold one or more named
+//! regions.
+
+use std::collections::HashMap;
+
+use mcre_core::{BlockPos, Vec3i};
+use mcre_nbt::{Compound, Tag};
+
+use crate::{
+    Error, Result, Structure, StructureBlock, StructureEntity,
+    structure::{PaletteReader, entity_pos, floor_pos},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LitematicRegion {
+    pub name: String,
+    /// The region, its [`Structure::offset`] being its minimum corner
+    /// relative to the schematic's origin.
+    pub structure: Structure,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Litematic {
+    pub name: String,
+    pub author: String,
+    pub data_version: Option<i32>,
+    pub regions: Vec<LitematicRegion>,
+}
+
+impl Litematic {
+    pub fn from_nbt(root: &Compound) -> Result<Self> {
+        let metadata = root.get_compound("Metadata");
+        let data_version = root.get_int("MinecraftDataVersion");
+        let regions = root
+            .get_compound("Regions")
+            .ok_or(Error::Invalid("missing Regions"))?
+            .iter()
+            .filter_map(|(name, region)| Some((name, region.as_compound()?)))
+            .map(|(name, region)| {
+                let mut structure = read_region(region)?;
+                structure.data_version = data_version;
+                Ok(LitematicRegion {
+                    name: name.clone(),
+                    structure,
+                })
+            })
+            .collect::<Result<_>>()?;
+        let text = |key| {
+            metadata
+                .and_then(|metadata| metadata.get_str(key))
+                .unwrap_or_default()
+                .to_string()
+        };
+        Ok(Self {
+            name: text("Name"),
+            author: text("Author"),
+            data_version,
+            regions,
+        })
+    }
+
+    /// Merges the regions into one structure spanning all of them. Where
+    /// regions overlap, later ones win when placed.
+    pub fn to_structure(&self) -> Structure {
+        let regions = || self.regions.iter().map(|region| &region.structure);
+        let min = |axis: fn(BlockPos) -> i32| regions().map(|s| axis(s.offset)).min();
+        let Some(min) = min(|pos| pos.x)
+            .zip(min(|pos| pos.y))
+            .zip(min(|pos| pos.z))
+            .map(|((x, y), z)| BlockPos::new(x, y, z))
+        else {
+            return Structure::default();
+        };
+
+        let mut merged = Structure {
+            offset: min,
+            data_version: self.data_version,
+            ..Structure::default()
+        };
+        for region in regions() {
+            let shift = BlockPos::new(
+                region.offset.x - min.x,
+                region.offset.y - min.y,
+                region.offset.z - min.z,
+            );
+            for axis in 0..3 {
+                let end = [shift.x, shift.y, shift.z][axis] + region.size[axis];
+                merged.size[axis] = merged.size[axis].max(end);
+            }
+            let base = merged.palettes[0].len() as u32;
+            merged.palettes[0].extend_from_slice(&region.palettes[0]);
+            merged
+                .blocks
+                .extend(region.blocks.iter().map(|block| StructureBlock {
+                    pos: add(block.pos, shift),
+                    state: base + block.state,
+                    nbt: block.nbt.clone(),
+                }));
+            merged.entities.extend(region.entities.iter().map(|entity| {
+                let [x, y, z] = entity.pos;
+                StructureEntity {
+                    pos: [x + shift.x as f64, y + shift.y as f64, z + shift.z as f64],
+                    block_pos: add(entity.block_pos, shift),
+                    nbt: entity.nbt.clone(),
+                }
+            }));
+            for unknown in &region.unknown_blocks {
+                if !merged.unknown_blocks.contains(unknown) {
+                    merged.unknown_blocks.push(unknown.clone());
+                }
+            }
+        }
+        merged
+    }
+}
+
+fn add(a: BlockPos, b: BlockPos) -> BlockPos {
+    BlockPos::new(a.x + b.x, a.y + b.y, a.z + b.z)
+}
+
+fn xyz(compound: Option<&Compound>) -> Option<[i32; 3]> {
+    let compound = compound?;
+    Some([
+        compound.get_int("x")?,
+        compound.get_int("y")?,
+        compound.get_int("z")?,
+    ])
+}
+
+/// Reads a region. Sizes may be negative, in which case the box extends
+/// back from `Position`. Block entities and entities are stored relative to
+/// the region's minimum corner.
+fn read_region(region: &Compound) -> Result<Structure> {
+    let position =
+        xyz(region.get_compound("Position")).ok_or(Error::Invalid("missing Position"))?;
+    let size = xyz(region.get_compound("Size")).ok_or(Error::Invalid("missing Size"))?;
+    let min = std::array::from_fn::<_, 3, _>(|axis| {
+        position[axis] + if size[axis] < 0 { size[axis] + 1 } else { 0 }
+    });
+    let size = Vec3i::new(size[0].abs(), size[1].abs(), size[2].abs());
+
+    let mut reader = PaletteReader::default();
+    let palette: Vec<_> = region
+        .get_list("BlockStatePalette")
+        .ok_or(Error::Invalid("missing BlockStatePalette"))?
+        .iter()
+        .map(|entry| reader.parse_nbt(entry.as_compound()?))
+        .collect();
+    let bits = (usize::BITS - palette.len().saturating_sub(1).leading_zeros()).max(2);
+    let states = region
+        .get_long_array("BlockStates")
+        .ok_or(Error::Invalid("missing BlockStates"))?;
+    let volume = (size[0] * size[1] * size[2]) as usize;
+    if states.len() * 64 < volume * bits as usize {
+        return Err(Error::Invalid("BlockStates is shorter than the region"));
+    }
+
+    let mut block_entities: HashMap<BlockPos, Compound> = HashMap::new();
+    for entity in region
+        .get_list("TileEntities")
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Tag::as_compound)
+    {
+        let [x, y, z] = [
+            entity.get_int("x"),
+            entity.get_int("y"),
+            entity.get_int("z"),
+        ];
+        let (Some(x), Some(y), Some(z)) = (x, y, z) else {
+            return Err(Error::Invalid("block entity without coordinates"));
+        };
+        let mut nbt = entity.clone();
+        for key in ["x", "y", "z"] {
+            nbt.remove(key);
+        }
+        block_entities.insert(BlockPos::new(x, y, z), nbt);
+    }
+
+    let blocks = (0..volume)
+        .map(|i| {
+            let (x, rest) = (i as i32 % size[0], i as i32 / size[0]);
+            let pos = BlockPos::new(x, rest / size[2], rest % size[2]);
+            StructureBlock {
+                pos,
+                state: packed_value(states, bits, i),
+                nbt: block_entities.remove(&pos),
+            }
+        })
+        .collect();
+    let entities = region
+        .get_list("Entities")
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Tag::as_compound)
+        .map(|entity| {
+            let pos = entity_pos(entity.get("Pos")).ok_or(Error::Invalid("entity pos"))?;
+            Ok(StructureEntity {
+                pos,
+                block_pos: floor_pos(pos),
+                nbt: entity.clone(),
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(Structure {
+        size,
+        offset: BlockPos::new(min[0], min[1], min[2]),
+        data_version: None,
+        palettes: vec![palette],
+        blocks,
+        entities,
+        unknown_blocks: reader.unknown,
+    })
+}
+
+/// Reads entry `index` of Litematica's bit array, whose entries run on
+/// across long boundaries unlike chunk sections'.
+fn packed_value(longs: &[i64], bits: u32, index: usize) -> u32 {
+    let mask = (1u64 << bits) - 1;
+    let start = index * bits as usize;
+    let (word, offset) = (start / 64, start % 64);
+    let mut value = longs[word] as u64 >> offset;
+    if offset + bits as usize > 64 {
+        value |= (longs[word + 1] as u64) << (64 - offset);
+    }
+    (value & mask) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_nbt::Tag;
+    use mcre_world::{Block, BlockState};
+
+    use super::*;
+
+    /// Packs values the way `LitematicaBitArray` does.
+    fn pack(values: &[u32], bits: u32) -> Vec<i64> {
+        let mut longs = vec![0u64; (values.len() * bits as usize).div_ceil(64)];
+        for (i, value) in values.iter().enumerate() {
+            let start = i * bits as usize;
+            let (word, offset) = (start / 64, start % 64);
+            longs[word] |= (*value as u64) << offset;
+            if offset + bits as usize > 64 {
+                longs[word + 1] |= (*value as u64) >> (64 - offset);
+            }
+        }
+        longs.into_iter().map(|long| long as i64).collect()
+    }
+
+    fn region(position: [i32; 3], size: [i32; 3], states: &[u32]) -> Compound {
+        let mut region = mcre_nbt::from_snbt(
+            r#"{
+                BlockStatePalette: [
+                    {Name: "minecraft:air"}, {Name: "minecraft:stone"}, {Name: "minecraft:oak_log", Properties: {axis: "z"}},
+                    {Name: "minecraft:furnace", Properties: {facing: "east", lit: "false"}}, {Name: "othermod:pipe"}
+                ],
+                TileEntities: [{x: 0, y: 0, z: 0, id: "minecraft:furnace", CookTime: 5s}],
+                Entities: [{Pos: [1.5d, 0.0d, 0.5d], id: "minecraft:cow"}]
+            }"#,
+        )
+        .unwrap();
+        let xyz = |[x, y, z]: [i32; 3]| {
+            let mut compound = Compound::new();
+            compound.insert("x", x);
+            compound.insert("y", y);
+            compound.insert("z", z);
+            Tag::Compound(compound)
+        };
+        region.insert("Position", xyz(position));
+        region.insert("Size", xyz(size));
+        region.insert("BlockStates", pack(states, 3));
+        region
+    }
+
+    #[test]
+    fn test_litematic_regions() {
+        // 22 entries of 3 bits straddle the first long boundary
+        let first: Vec<u32> = (0..22).map(|i| [3, 1, 2, 0, 4][i % 5]).collect();
+        let mut regions = Compound::new();
+        regions.insert("Main", region([0, 0, 0], [11, 1, 2], &first));
+        regions.insert("Tower", region([12, 3, 1], [-2, -3, -1], &[1; 6]));
+        let mut root = Compound::new();
+        root.insert("MinecraftDataVersion", 4440);
+        root.insert("Regions", regions);
+        let mut metadata = Compound::new();
+        metadata.insert("Name", "Base");
+        root.insert("Metadata", metadata);
+
+        let litematic = Litematic::from_nbt(&root).unwrap();
+        assert_eq!(litematic.name, "Base");
+        let main = &litematic.regions[0].structure;
+        assert_eq!(main.size, Vec3i::new(11, 1, 2));
+        let blocks: Vec<_> = main.blocks().collect();
+        // every fifth block is the unknown pipe
+        assert_eq!(blocks.len(), 18);
+        assert_eq!(blocks[0].1.block(), Block::FURNACE);
+        assert_eq!(blocks[0].2.unwrap().get_short("CookTime"), Some(5));
+        assert_eq!(
+            blocks[2].1,
+            "oak_log[axis=z]".parse::<BlockState>().unwrap()
+        );
+        assert_eq!(blocks[13].0, BlockPos::new(5, 0, 1));
+        assert_eq!(blocks[13].1, Block::STONE.default_state());
+        assert_eq!(main.unknown_blocks, ["othermod:pipe"]);
+
+        let tower = &litematic.regions[1].structure;
+        assert_eq!(tower.offset, BlockPos::new(11, 1, 1));
+        assert_eq!(tower.size, Vec3i::new(2, 3, 1));
+
+        let merged = litematic.to_structure();
+        assert_eq!(merged.offset, BlockPos::ZERO);
+        assert_eq!(merged.size, Vec3i::new(13, 4, 2));
+        assert_eq!(merged.blocks().count(), 18 + 6);
+        assert_eq!(merged.entities[1].block_pos, BlockPos::new(12, 1, 1));
+        assert_eq!(merged.unknown_blocks, ["othermod:pipe"]);
+    }
+}
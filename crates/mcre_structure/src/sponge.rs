@@ -0,0 +1,225 @@
+//! Sponge schematics (`.schem`), versions 1 to 3, as written by WorldEdit.
+
+use std::collections::HashMap;
+
+use mcre_core::{BlockPos, Vec3i};
+use mcre_nbt::{Compound, Tag};
+use mcre_world::Block;
+
+use crate::{
+    Error, Result, Structure, StructureBlock, StructureEntity,
+    structure::{PaletteReader, block_pos, entity_pos, floor_pos},
+};
+
+impl Structure {
+    /// Decodes the root compound of a Sponge schematic. Version 3 nests
+    /// everything in a `Schematic` compound; earlier versions do not.
+    pub fn from_sponge(root: &Compound) -> Result<Self> {
+        let schematic = root.get_compound("Schematic").unwrap_or(root);
+        let version = schematic
+            .get_int("Version")
+            .ok_or(Error::Invalid("missing Version"))?;
+        let (blocks, block_entities_key) = match version {
+            1 => (schematic, "TileEntities"),
+            2 => (schematic, "BlockEntities"),
+            3 => (
+                schematic
+                    .get_compound("Blocks")
+                    .ok_or(Error::Invalid("missing Blocks"))?,
+                "BlockEntities",
+            ),
+            version => return Err(Error::UnsupportedVersion(version)),
+        };
+        let dimension = |key| {
+            schematic
+                .get_short(key)
+                .map(|len| len as u16 as i32)
+                .ok_or(Error::Invalid("missing Width, Height or Length"))
+        };
+        let size = Vec3i::new(
+            dimension("Width")?,
+            dimension("Height")?,
+            dimension("Length")?,
+        );
+
+        let mut reader = PaletteReader::default();
+        let mut palette = Vec::new();
+        for (state, index) in blocks
+            .get_compound("Palette")
+            .into_iter()
+            .flat_map(Compound::iter)
+        {
+            let index = index.as_i64().ok_or(Error::Invalid("palette index"))? as usize;
+            if palette.len() <= index {
+                palette.resize(index + 1, None);
+            }
+            palette[index] = reader.parse(state);
+        }
+
+        let data = match version {
+            3 => blocks.get_byte_array("Data"),
+            _ => blocks.get_byte_array("BlockData"),
+        }
+        .ok_or(Error::Invalid("missing block data"))?;
+        let indices = read_varints(data)?;
+        let volume = (size[0] * size[1] * size[2]) as usize;
+        if indices.len() != volume {
+            return Err(Error::Invalid("block data does not match the size"));
+        }
+        let mut block_entities: HashMap<BlockPos, Compound> = blocks
+            .get_list(block_entities_key)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(Tag::as_compound)
+            .map(|entity| {
+                let pos = block_pos(entity.get("Pos")).ok_or(Error::Invalid("block entity pos"))?;
+                Ok((pos, with_id(entity, version, &["Pos"])))
+            })
+            .collect::<Result<_>>()?;
+        let void = Some(Block::STRUCTURE_VOID.default_state());
+        let structure_blocks = indices
+            .into_iter()
+            .enumerate()
+            .filter(|(_, state)| palette.get(*state as usize) != Some(&void))
+            .map(|(i, state)| {
+                let (x, rest) = (i as i32 % size[0], i as i32 / size[0]);
+                let pos = BlockPos::new(x, rest / size[2], rest % size[2]);
+                StructureBlock {
+                    pos,
+                    state,
+                    nbt: block_entities.remove(&pos),
+                }
+            })
+            .collect();
+
+        let entities = schematic
+            .get_list("Entities")
+            .unwrap_or_default()
+            .iter()
+            .filter_map(Tag::as_compound)
+            .map(|entity| {
+                let pos = entity_pos(entity.get("Pos")).ok_or(Error::Invalid("entity pos"))?;
+                Ok(StructureEntity {
+                    pos,
+                    block_pos: floor_pos(pos),
+                    nbt: with_id(entity, version, &[]),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let offset = block_pos(schematic.get("Offset")).unwrap_or(BlockPos::ZERO);
+        Ok(Self {
+            size,
+            offset,
+            data_version: schematic.get_int("DataVersion"),
+            palettes: vec![palette],
+            blocks: structure_blocks,
+            entities,
+            unknown_blocks: reader.unknown,
+        })
+    }
+}
+
+/// Rebuilds vanilla-style entity data: version 3 keeps it in `Data`, earlier
+/// versions inline it next to `Id`.
+fn with_id(entity: &Compound, version: i32, drop: &[&str]) -> Compound {
+    let mut nbt = match (version, entity.get_compound("Data")) {
+        (3, Some(data)) => data.clone(),
+        _ => {
+            let mut nbt = entity.clone();
+            for key in drop.iter().chain(&["Id"]) {
+                nbt.remove(key);
+            }
+            nbt
+        }
+    };
+    if let Some(id) = entity.get_str("Id") {
+        nbt.insert("id", id);
+    }
+    nbt
+}
+
+fn read_varints(data: &[i8]) -> Result<Vec<u32>> {
+    let mut values = Vec::with_capacity(data.len());
+    let mut value = 0u32;
+    let mut shift = 0;
+    for byte in data.iter().map(|byte| *byte as u8) {
+        value |= ((byte & 0x7F) as u32) << shift;
+        if byte & 0x80 == 0 {
+            values.push(value);
+            value = 0;
+            shift = 0;
+        } else {
+            shift += 7;
+            if shift > 28 {
+                return Err(Error::Invalid("varint too long"));
+            }
+        }
+    }
+    if shift != 0 {
+        return Err(Error::Invalid("truncated varint"));
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_world::BlockState;
+
+    use super::*;
+
+    fn check(structure: &Structure) {
+        assert_eq!(structure.size, Vec3i::new(3, 1, 2));
+        assert_eq!(structure.offset, BlockPos::new(-1, 0, 2));
+        let blocks: Vec<_> = structure.blocks().collect();
+        // one structure void is skipped and one unknown block reported
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(blocks[0].1, Block::AIR.default_state());
+        assert_eq!(blocks[2].0, BlockPos::new(1, 0, 1));
+        assert_eq!(
+            blocks[1].1,
+            "oak_log[axis=x]".parse::<BlockState>().unwrap()
+        );
+        let (pos, chest, nbt) = blocks[3];
+        assert_eq!(pos, BlockPos::new(2, 0, 1));
+        assert_eq!(chest.block().name(), "chest");
+        assert_eq!(nbt.unwrap().get_str("id"), Some("minecraft:chest"));
+        assert_eq!(nbt.unwrap().get_str("Lock"), Some("key"));
+        assert!(nbt.unwrap().get("Pos").is_none());
+        assert_eq!(structure.unknown_blocks, ["mymod:machine"]);
+        assert_eq!(
+            structure.entities[0].nbt.get_str("id"),
+            Some("minecraft:pig")
+        );
+    }
+
+    #[test]
+    fn test_sponge_v2_and_v3() {
+        // index 200 takes two varint bytes
+        let data = "[B; 0b, 1b, -56b, 1b, 3b, 1b, 4b]";
+        let palette = r#"{"minecraft:air": 0, "minecraft:oak_log[axis=x]": 1, "minecraft:structure_void": 200, "mymod:machine": 3, "minecraft:chest[facing=north]": 4}"#;
+        let v2 = mcre_nbt::from_snbt(&format!(
+            r#"{{Version: 2, DataVersion: 4440, Width: 3s, Height: 1s, Length: 2s, Offset: [I; -1, 0, 2],
+                Palette: {palette}, BlockData: {data},
+                BlockEntities: [{{Pos: [I; 2, 0, 1], Id: "minecraft:chest", Lock: "key"}}],
+                Entities: [{{Pos: [0.5d, 0.0d, 0.5d], Id: "minecraft:pig"}}]}}"#
+        ))
+        .unwrap();
+        check(&Structure::from_sponge(&v2).unwrap());
+
+        let v3 = mcre_nbt::from_snbt(&format!(
+            r#"{{Schematic: {{Version: 3, DataVersion: 4440, Width: 3s, Height: 1s, Length: 2s, Offset: [I; -1, 0, 2],
+                Blocks: {{Palette: {palette}, Data: {data},
+                    BlockEntities: [{{Pos: [I; 2, 0, 1], Id: "minecraft:chest", Data: {{Lock: "key"}}}}]}},
+                Entities: [{{Pos: [0.5d, 0.0d, 0.5d], Id: "minecraft:pig", Data: {{}}}}]}}}}"#
+        ))
+        .unwrap();
+        check(&Structure::from_sponge(&v3).unwrap());
+
+        let v4 = mcre_nbt::from_snbt("{Version: 4}").unwrap();
+        assert!(matches!(
+            Structure::from_sponge(&v4),
+            Err(Error::UnsupportedVersion(4))
+        ));
+    }
+}
@@ -0,0 +1,304 @@
+//! The format-independent structure model and its placement.
+
+use mcre_core::{BlockPos, Mirror, Rotation, Vec3i};
+use mcre_nbt::{Compound, Tag};
+use mcre_world::{BlockState, World, WorldEvent};
+
+/// A block of a structure, relative to its minimum corner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureBlock {
+    pub pos: BlockPos,
+    /// An index into each of [`Structure::palettes`].
+    pub state: u32,
+    /// Block entity data, with its `id` but without coordinates.
+    pub nbt: Option<Compound>,
+}
+
+/// An entity of a structure, relative to its minimum corner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureEntity {
+    pub pos: [f64; 3],
+    pub block_pos: BlockPos,
+    /// The entity's saved data, with its `id`.
+    pub nbt: Compound,
+}
+
+/// A box of blocks and entities loaded from a structure or schematic file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Structure {
+    pub size: Vec3i,
+    /// Where the minimum corner sat relative to the point the structure was
+    /// copied from. Zero for vanilla structures.
+    pub offset: BlockPos,
+    pub data_version: Option<i32>,
+    /// One or more palettes indexed by [`StructureBlock::state`]. Vanilla
+    /// picks one at random per placement, as for shipwrecks; the other
+    /// formats have exactly one. `None` marks states that could not be
+    /// parsed.
+    pub palettes: Vec<Vec<Option<BlockState>>>,
+    pub blocks: Vec<StructureBlock>,
+    pub entities: Vec<StructureEntity>,
+    /// The state strings that could not be parsed, each listed once. Blocks
+    /// using them are skipped.
+    pub unknown_blocks: Vec<String>,
+}
+
+impl Default for Structure {
+    fn default() -> Self {
+        Self {
+            size: Vec3i::new(0, 0, 0),
+            offset: BlockPos::ZERO,
+            data_version: None,
+            palettes: vec![Vec::new()],
+            blocks: Vec::new(),
+            entities: Vec::new(),
+            unknown_blocks: Vec::new(),
+        }
+    }
+}
+
+impl Structure {
+    pub fn volume(&self) -> usize {
+        self.size.iter().map(|len| *len as usize).product()
+    }
+
+    /// The blocks with their states from the first palette.
+    pub fn blocks(&self) -> impl Iterator<Item = (BlockPos, BlockState, Option<&Compound>)> {
+        self.blocks_with_palette(0)
+    }
+
+    pub fn blocks_with_palette(
+        &self,
+        palette: usize,
+    ) -> impl Iterator<Item = (BlockPos, BlockState, Option<&Compound>)> {
+        let palette = self.palettes.get(palette).map_or(&[][..], Vec::as_slice);
+        self.blocks.iter().filter_map(|block| {
+            let state = (*palette.get(block.state as usize)?)?;
+            Some((block.pos, state, block.nbt.as_ref()))
+        })
+    }
+
+    /// The size once placed with `settings`.
+    pub fn placed_size(&self, settings: &PlaceSettings) -> Vec3i {
+        match settings.rotation {
+            Rotation::Clockwise90 | Rotation::Counterclockwise90 => {
+                Vec3i::new(self.size[2], self.size[1], self.size[0])
+            }
+            _ => self.size,
+        }
+    }
+
+    /// The [`WorldEvent::BlockSet`]s that place the structure's blocks with
+    /// their minimum corner, before transforming, at `origin`. Block entity
+    /// data and entities are not part of the world model and are left out.
+    pub fn place_events(
+        &self,
+        origin: BlockPos,
+        settings: &PlaceSettings,
+    ) -> impl Iterator<Item = WorldEvent> {
+        self.blocks_with_palette(settings.palette)
+            .map(move |(pos, state, _)| {
+                let pos = settings.transform_pos(pos);
+                WorldEvent::BlockSet {
+                    pos: BlockPos::new(origin.x + pos.x, origin.y + pos.y, origin.z + pos.z),
+                    state: settings.transform_state(state),
+                }
+            })
+    }
+
+    /// Places the blocks into the loaded chunks of `world`, see
+    /// [`Structure::place_events`].
+    pub fn place(&self, world: &mut World, origin: BlockPos, settings: &PlaceSettings) {
+        for event in self.place_events(origin, settings) {
+            world.apply(event);
+        }
+    }
+}
+
+/// How a structure is transformed when placed, after
+/// `StructurePlaceSettings`: mirrored first, then rotated about `pivot`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlaceSettings {
+    pub mirror: Mirror,
+    pub rotation: Rotation,
+    /// The point rotations turn about, relative to the minimum corner.
+    pub pivot: BlockPos,
+    /// Which of [`Structure::palettes`] to use.
+    pub palette: usize,
+}
+
+impl PlaceSettings {
+    pub fn transform_pos(&self, pos: BlockPos) -> BlockPos {
+        self.rotation
+            .rotate_pos(self.mirror.mirror_pos(pos), self.pivot)
+    }
+
+    pub fn transform_state(&self, state: BlockState) -> BlockState {
+        state.mirror(self.mirror).rotate(self.rotation)
+    }
+
+    /// Transforms an entity position, where blocks span whole units rather
+    /// than sitting on grid points.
+    pub fn transform_entity_pos(&self, [mut x, y, mut z]: [f64; 3]) -> [f64; 3] {
+        match self.mirror {
+            Mirror::None => {}
+            Mirror::LeftRight => z = 1.0 - z,
+            Mirror::FrontBack => x = 1.0 - x,
+        }
+        let (px, pz) = (self.pivot.x as f64, self.pivot.z as f64);
+        match self.rotation {
+            Rotation::None => [x, y, z],
+            Rotation::Clockwise90 => [px + pz + 1.0 - z, y, pz - px + x],
+            Rotation::Clockwise180 => [px + px + 1.0 - x, y, pz + pz + 1.0 - z],
+            Rotation::Counterclockwise90 => [px - pz + z, y, px + pz + 1.0 - x],
+        }
+    }
+}
+
+/// Parses palette entries, remembering the ones that fail.
+#[derive(Debug, Default)]
+pub(crate) struct PaletteReader {
+    pub unknown: Vec<String>,
+}
+
+impl PaletteReader {
+    /// Parses a `name[key=value,...]` string.
+    pub fn parse(&mut self, state: &str) -> Option<BlockState> {
+        let parsed = state.parse().ok();
+        if parsed.is_none() && !self.unknown.iter().any(|unknown| unknown == state) {
+            self.unknown.push(state.to_string());
+        }
+        parsed
+    }
+
+    /// Parses a `{Name, Properties}` compound, as in chunk sections.
+    pub fn parse_nbt(&mut self, entry: &Compound) -> Option<BlockState> {
+        let mut state = entry.get_str("Name").unwrap_or_default().to_string();
+        if let Some(props) = entry.get_compound("Properties") {
+            let props: Vec<String> = props
+                .iter()
+                .map(|(key, value)| format!("{key}={}", value.as_str().unwrap_or_default()))
+                .collect();
+            state = format!("{state}[{}]", props.join(","));
+        }
+        self.parse(&state)
+    }
+}
+
+/// Reads a list or array of three ints, as positions are saved.
+pub(crate) fn block_pos(tag: Option<&Tag>) -> Option<BlockPos> {
+    let coords: Vec<i32> = match tag? {
+        Tag::IntArray(coords) => coords.clone(),
+        Tag::List(coords) => coords
+            .iter()
+            .map(|coord| coord.as_i64().map(|coord| coord as i32))
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    match coords[..] {
+        [x, y, z] => Some(BlockPos::new(x, y, z)),
+        _ => None,
+    }
+}
+
+/// Reads a list of three doubles, as entity positions are saved.
+pub(crate) fn entity_pos(tag: Option<&Tag>) -> Option<[f64; 3]> {
+    match tag?.as_list()? {
+        [x, y, z] => Some([x.as_f64()?, y.as_f64()?, z.as_f64()?]),
+        _ => None,
+    }
+}
+
+pub(crate) fn floor_pos([x, y, z]: [f64; 3]) -> BlockPos {
+    BlockPos::new(x.floor() as i32, y.floor() as i32, z.floor() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::ChunkPos;
+    use mcre_world::{BiomeId, Block, ChunkData, ChunkSection, LightUpdate, WorldHeight};
+
+    use super::*;
+
+    fn empty_chunk() -> ChunkData {
+        let height = WorldHeight::OVERWORLD;
+        ChunkData {
+            height,
+            sections: (0..height.section_count())
+                .map(|_| ChunkSection::empty(BiomeId(0)))
+                .collect(),
+            heightmaps: Vec::new(),
+            block_entities: Vec::new(),
+            light: LightUpdate::default(),
+        }
+    }
+
+    #[test]
+    fn test_place_with_transform() {
+        let stairs = "oak_stairs[facing=north]".parse().unwrap();
+        let structure = Structure {
+            size: Vec3i::new(2, 1, 1),
+            palettes: vec![vec![Some(Block::STONE.default_state()), Some(stairs), None]],
+            blocks: vec![
+                StructureBlock {
+                    pos: BlockPos::new(0, 0, 0),
+                    state: 0,
+                    nbt: None,
+                },
+                StructureBlock {
+                    pos: BlockPos::new(1, 0, 0),
+                    state: 1,
+                    nbt: None,
+                },
+                StructureBlock {
+                    pos: BlockPos::new(1, 0, 0),
+                    state: 2,
+                    nbt: None,
+                },
+            ],
+            ..Structure::default()
+        };
+        assert_eq!(structure.blocks().count(), 2);
+
+        let mut world = World::new();
+        world.apply(WorldEvent::ChunkLoaded {
+            pos: ChunkPos::new(0, 0),
+            chunk: empty_chunk(),
+        });
+        let settings = PlaceSettings {
+            rotation: Rotation::Clockwise90,
+            ..PlaceSettings::default()
+        };
+        assert_eq!(structure.placed_size(&settings), Vec3i::new(1, 1, 2));
+        let origin = BlockPos::new(4, 64, 4);
+        structure.place(&mut world, origin, &settings);
+        let view = world.view();
+        assert_eq!(view.get_block(origin), Some(Block::STONE.default_state()));
+        assert_eq!(
+            view.get_block(BlockPos::new(4, 64, 5)),
+            Some("oak_stairs[facing=east]".parse().unwrap())
+        );
+        assert_eq!(
+            settings.transform_entity_pos([0.5, 0.0, 0.5]),
+            [0.5, 0.0, 0.5]
+        );
+    }
+
+    #[test]
+    fn test_unknown_states_are_reported() {
+        let mut reader = PaletteReader::default();
+        assert!(reader.parse("minecraft:stone").is_some());
+        assert!(reader.parse("mymod:machine[on=true]").is_none());
+        assert!(reader.parse("mymod:machine[on=true]").is_none());
+        let mut entry = Compound::new();
+        entry.insert("Name", "minecraft:oak_log");
+        let mut props = Compound::new();
+        props.insert("axis", "w");
+        entry.insert("Properties", props);
+        assert!(reader.parse_nbt(&entry).is_none());
+        assert_eq!(
+            reader.unknown,
+            ["mymod:machine[on=true]", "minecraft:oak_log[axis=w]"]
+        );
+    }
+}
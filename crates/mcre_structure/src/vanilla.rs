@@ -0,0 +1,115 @@
+//! Vanilla structure files, as saved by structure blocks and shipped in
+//! `data/<namespace>/structure`.
+
+use mcre_core::Vec3i;
+use mcre_nbt::{Compound, Tag};
+
+use crate::{
+    Error, Result, Structure, StructureBlock, StructureEntity,
+    structure::{PaletteReader, block_pos, entity_pos},
+};
+
+impl Structure {
+    /// Decodes the root compound of a `.nbt` structure file.
+    pub fn from_vanilla(root: &Compound) -> Result<Self> {
+        let size = block_pos(root.get("size")).ok_or(Error::Invalid("missing size"))?;
+        let mut reader = PaletteReader::default();
+        let mut read_palette = |entries: &[Tag]| -> Vec<_> {
+            entries
+                .iter()
+                .map(|entry| reader.parse_nbt(entry.as_compound()?))
+                .collect()
+        };
+        let palettes = match root.get_list("palettes") {
+            Some(palettes) => palettes
+                .iter()
+                .map(|palette| read_palette(palette.as_list().unwrap_or_default()))
+                .collect(),
+            None => vec![read_palette(root.get_list("palette").unwrap_or_default())],
+        };
+
+        let blocks = root
+            .get_list("blocks")
+            .unwrap_or_default()
+            .iter()
+            .filter_map(Tag::as_compound)
+            .map(|block| {
+                Ok(StructureBlock {
+                    pos: block_pos(block.get("pos")).ok_or(Error::Invalid("block without pos"))?,
+                    state: block
+                        .get_int("state")
+                        .ok_or(Error::Invalid("block without state"))?
+                        as u32,
+                    nbt: block.get_compound("nbt").cloned(),
+                })
+            })
+            .collect::<Result<_>>()?;
+        let entities = root
+            .get_list("entities")
+            .unwrap_or_default()
+            .iter()
+            .filter_map(Tag::as_compound)
+            .map(|entity| {
+                let pos =
+                    entity_pos(entity.get("pos")).ok_or(Error::Invalid("entity without pos"))?;
+                Ok(StructureEntity {
+                    pos,
+                    block_pos: block_pos(entity.get("blockPos"))
+                        .unwrap_or_else(|| crate::structure::floor_pos(pos)),
+                    nbt: entity.get_compound("nbt").cloned().unwrap_or_default(),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            size: Vec3i::new(size.x, size.y, size.z),
+            offset: mcre_core::BlockPos::ZERO,
+            data_version: root.get_int("DataVersion"),
+            palettes,
+            blocks,
+            entities,
+            unknown_blocks: reader.unknown,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::BlockPos;
+    use mcre_world::BlockState;
+
+    use super::*;
+
+    #[test]
+    fn test_vanilla_structure() {
+        let root = mcre_nbt::from_snbt(
+            r#"{
+                DataVersion: 4440, size: [2, 2, 1],
+                palettes: [
+                    [{Name: "minecraft:oak_planks"}, {Name: "minecraft:chest", Properties: {facing: "west", type: "single", waterlogged: "false"}}],
+                    [{Name: "minecraft:spruce_planks"}, {Name: "mymod:crate"}]
+                ],
+                blocks: [
+                    {pos: [0, 0, 0], state: 0},
+                    {pos: [1, 1, 0], state: 1, nbt: {id: "minecraft:chest", LootTable: "minecraft:chests/shipwreck_supply"}}
+                ],
+                entities: [{pos: [0.5d, 1.0d, 0.5d], blockPos: [0, 1, 0], nbt: {id: "minecraft:armor_stand"}}]
+            }"#,
+        )
+        .unwrap();
+        let structure = Structure::from_vanilla(&root).unwrap();
+        assert_eq!(structure.size, Vec3i::new(2, 2, 1));
+        assert_eq!(structure.volume(), 4);
+        let blocks: Vec<_> = structure.blocks().collect();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(
+            blocks[1].1,
+            "chest[facing=west]".parse::<BlockState>().unwrap()
+        );
+        assert_eq!(blocks[1].0, BlockPos::new(1, 1, 0));
+        assert!(blocks[1].2.is_some());
+        assert_eq!(structure.blocks_with_palette(1).count(), 1);
+        assert_eq!(structure.unknown_blocks, ["mymod:crate"]);
+        assert_eq!(structure.entities[0].block_pos, BlockPos::new(0, 1, 0));
+    }
+}
@@ -0,0 +1,577 @@
+//! JSON and NBT codecs, following vanilla's `ComponentSerialization`.
+//!
+//! Both formats share one implementation: decoding goes through an NBT
+//! [`Tag`] tree (JSON values map onto it losslessly enough for components),
+//! and encoding goes through `serde`, which writes booleans as `true`/`false`
+//! in JSON and as bytes in NBT.
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use mcre_nbt::{Compound, IntArray, Tag};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, ser::SerializeMap};
+
+use crate::{
+    ClickEvent, Component, Content, Error, HoverEvent, NbtSource, Result, Style, TextColor,
+    style::uuid_from_str,
+};
+
+impl Component {
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("components always serialize to JSON")
+    }
+
+    pub fn from_nbt(tag: &Tag) -> Result<Self> {
+        parse_component(tag)
+    }
+
+    pub fn to_nbt(&self) -> Tag {
+        mcre_nbt::to_tag(self).expect("components always serialize to NBT")
+    }
+
+    /// Reads a component in the nameless NBT form used by the protocol,
+    /// advancing `buf` past it.
+    pub fn read_network_nbt(buf: &mut &[u8]) -> Result<Self> {
+        let tag =
+            mcre_nbt::binary::read_nameless(buf)?.ok_or_else(|| invalid("empty component tag"))?;
+        parse_component(&tag)
+    }
+
+    pub fn write_network_nbt(&self, out: &mut Vec<u8>) {
+        mcre_nbt::binary::write_nameless(out, Some(&self.to_nbt()))
+            .expect("component strings fit in NBT");
+    }
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::Invalid(message.into())
+}
+
+fn get_str<'a>(compound: &'a Compound, key: &str) -> Result<Option<&'a str>> {
+    match compound.get(key) {
+        None => Ok(None),
+        Some(Tag::String(s)) => Ok(Some(s)),
+        Some(_) => Err(invalid(format!("`{key}` must be a string"))),
+    }
+}
+
+fn require_str<'a>(compound: &'a Compound, key: &str) -> Result<&'a str> {
+    get_str(compound, key)?.ok_or_else(|| invalid(format!("missing `{key}`")))
+}
+
+fn get_bool(compound: &Compound, key: &str) -> Result<Option<bool>> {
+    match compound.get(key) {
+        None => Ok(None),
+        Some(tag) => tag
+            .as_bool()
+            .map(Some)
+            .ok_or_else(|| invalid(format!("`{key}` must be a boolean"))),
+    }
+}
+
+fn get_component(compound: &Compound, key: &str) -> Result<Option<Component>> {
+    compound.get(key).map(parse_component).transpose()
+}
+
+fn parse_component(tag: &Tag) -> Result<Component> {
+    match tag {
+        Tag::String(text) => Ok(Component::text(text.as_str())),
+        Tag::List(list) => {
+            let (first, rest) = list
+                .split_first()
+                .ok_or_else(|| invalid("empty component list"))?;
+            let mut component = parse_component(first)?;
+            for child in rest {
+                component.extra.push(parse_component(child)?);
+            }
+            Ok(component)
+        }
+        Tag::Compound(compound) => parse_compound(compound),
+        _ => Err(invalid("a component must be a string, list or compound")),
+    }
+}
+
+/// Translation arguments may also be plain numbers and booleans.
+fn parse_arg(tag: &Tag) -> Result<Component> {
+    match tag {
+        Tag::Byte(v) => Ok(Component::text(v.to_string())),
+        Tag::Short(v) => Ok(Component::text(v.to_string())),
+        Tag::Int(v) => Ok(Component::text(v.to_string())),
+        Tag::Long(v) => Ok(Component::text(v.to_string())),
+        Tag::Float(v) => Ok(Component::text(v.to_string())),
+        Tag::Double(v) => Ok(Component::text(v.to_string())),
+        tag => parse_component(tag),
+    }
+}
+
+const CONTENT_KEYS: [(&str, &str); 6] = [
+    ("text", "text"),
+    ("translatable", "translate"),
+    ("keybind", "keybind"),
+    ("score", "score"),
+    ("selector", "selector"),
+    ("nbt", "nbt"),
+];
+
+fn parse_compound(compound: &Compound) -> Result<Component> {
+    let kind = match get_str(compound, "type")? {
+        Some(kind) => {
+            CONTENT_KEYS
+                .iter()
+                .find(|(name, _)| *name == kind)
+                .ok_or_else(|| invalid(format!("unknown component type `{kind}`")))?
+                .0
+        }
+        None => {
+            CONTENT_KEYS
+                .iter()
+                .find(|(_, key)| compound.contains_key(key))
+                .ok_or_else(|| invalid("component has no content"))?
+                .0
+        }
+    };
+
+    let content = match kind {
+        "text" => Content::Text(require_str(compound, "text")?.into()),
+        "translatable" => Content::Translatable {
+            key: require_str(compound, "translate")?.into(),
+            fallback: get_str(compound, "fallback")?.map(Into::into),
+            args: match compound.get("with") {
+                None => Vec::new(),
+                Some(Tag::List(args)) => args.iter().map(parse_arg).collect::<Result<_>>()?,
+                Some(_) => return Err(invalid("`with` must be a list")),
+            },
+        },
+        "keybind" => Content::Keybind(require_str(compound, "keybind")?.into()),
+        "score" => {
+            let score = compound
+                .get_compound("score")
+                .ok_or_else(|| invalid("`score` must be a compound"))?;
+            Content::Score {
+                name: require_str(score, "name")?.into(),
+                objective: require_str(score, "objective")?.into(),
+            }
+        }
+        "selector" => Content::Selector {
+            pattern: require_str(compound, "selector")?.into(),
+            separator: get_component(compound, "separator")?.map(Box::new),
+        },
+        _ => {
+            let source = if let Some(pos) = get_str(compound, "block")? {
+                NbtSource::Block(pos.into())
+            } else if let Some(selector) = get_str(compound, "entity")? {
+                NbtSource::Entity(selector.into())
+            } else if let Some(id) = get_str(compound, "storage")? {
+                NbtSource::Storage(id.into())
+            } else {
+                return Err(invalid("nbt component has no source"));
+            };
+            Content::Nbt {
+                path: require_str(compound, "nbt")?.into(),
+                interpret: get_bool(compound, "interpret")?.unwrap_or(false),
+                separator: get_component(compound, "separator")?.map(Box::new),
+                source,
+            }
+        }
+    };
+
+    let extra = match compound.get("extra") {
+        None => Vec::new(),
+        Some(Tag::List(extra)) if !extra.is_empty() => {
+            extra.iter().map(parse_component).collect::<Result<_>>()?
+        }
+        Some(_) => return Err(invalid("`extra` must be a non-empty list")),
+    };
+
+    Ok(Component {
+        content,
+        style: parse_style(compound)?,
+        extra,
+    })
+}
+
+fn parse_style(compound: &Compound) -> Result<Style> {
+    Ok(Style {
+        color: get_str(compound, "color")?
+            .map(|color| {
+                color
+                    .parse::<TextColor>()
+                    .map_err(|_| invalid(format!("invalid color `{color}`")))
+            })
+            .transpose()?,
+        shadow_color: compound.get("shadow_color").map(parse_argb).transpose()?,
+        bold: get_bool(compound, "bold")?,
+        italic: get_bool(compound, "italic")?,
+        underlined: get_bool(compound, "underlined")?,
+        strikethrough: get_bool(compound, "strikethrough")?,
+        obfuscated: get_bool(compound, "obfuscated")?,
+        click_event: compound
+            .get("click_event")
+            .map(parse_click_event)
+            .transpose()?,
+        hover_event: compound
+            .get("hover_event")
+            .map(|tag| parse_hover_event(tag).map(Box::new))
+            .transpose()?,
+        insertion: get_str(compound, "insertion")?.map(Into::into),
+        font: get_str(compound, "font")?.map(Into::into),
+    })
+}
+
+/// An ARGB int, or a list of four floats in `[r, g, b, a]` order.
+fn parse_argb(tag: &Tag) -> Result<u32> {
+    if let Some(list) = tag.as_list() {
+        let channels = list
+            .iter()
+            .map(|c| c.as_f64().map(|c| (c.clamp(0.0, 1.0) * 255.0 + 0.5) as u32))
+            .collect::<Option<Vec<u32>>>()
+            .filter(|c| c.len() == 4)
+            .ok_or_else(|| invalid("shadow_color must be an int or four floats"))?;
+        let [r, g, b, a] = [channels[0], channels[1], channels[2], channels[3]];
+        return Ok((a << 24) | (r << 16) | (g << 8) | b);
+    }
+    match tag {
+        Tag::Byte(_) | Tag::Short(_) | Tag::Int(_) | Tag::Long(_) => {
+            Ok(tag.as_i64().unwrap() as u32)
+        }
+        _ => Err(invalid("shadow_color must be an int or four floats")),
+    }
+}
+
+fn parse_click_event(tag: &Tag) -> Result<ClickEvent> {
+    let compound = tag
+        .as_compound()
+        .ok_or_else(|| invalid("click_event must be a compound"))?;
+    let string = |key| require_str(compound, key).map(String::from);
+    Ok(match require_str(compound, "action")? {
+        "open_url" => ClickEvent::OpenUrl {
+            url: string("url")?,
+        },
+        "open_file" => ClickEvent::OpenFile {
+            path: string("path")?,
+        },
+        "run_command" => ClickEvent::RunCommand {
+            command: string("command")?,
+        },
+        "suggest_command" => ClickEvent::SuggestCommand {
+            command: string("command")?,
+        },
+        "change_page" => ClickEvent::ChangePage {
+            page: compound
+                .get("page")
+                .and_then(Tag::as_i64)
+                .ok_or_else(|| invalid("change_page needs an integer `page`"))?
+                as i32,
+        },
+        "copy_to_clipboard" => ClickEvent::CopyToClipboard {
+            value: string("value")?,
+        },
+        action => return Err(invalid(format!("unknown click action `{action}`"))),
+    })
+}
+
+fn parse_uuid(tag: &Tag) -> Result<[i32; 4]> {
+    let uuid = match tag {
+        Tag::String(s) => uuid_from_str(s),
+        Tag::IntArray(ints) => ints.as_slice().try_into().ok(),
+        Tag::List(list) => list
+            .iter()
+            .map(|i| i.as_i64().map(|i| i as i32))
+            .collect::<Option<Vec<i32>>>()
+            .and_then(|ints| ints.as_slice().try_into().ok()),
+        _ => None,
+    };
+    uuid.ok_or_else(|| invalid("invalid uuid"))
+}
+
+fn parse_hover_event(tag: &Tag) -> Result<HoverEvent> {
+    let compound = tag
+        .as_compound()
+        .ok_or_else(|| invalid("hover_event must be a compound"))?;
+    Ok(match require_str(compound, "action")? {
+        "show_text" => HoverEvent::ShowText(
+            get_component(compound, "value")?.ok_or_else(|| invalid("missing `value`"))?,
+        ),
+        "show_item" => HoverEvent::ShowItem {
+            id: require_str(compound, "id")?.into(),
+            count: compound.get("count").and_then(Tag::as_i64).unwrap_or(1) as i32,
+            components: compound.get("components").cloned(),
+        },
+        "show_entity" => HoverEvent::ShowEntity {
+            id: require_str(compound, "id")?.into(),
+            uuid: parse_uuid(
+                compound
+                    .get("uuid")
+                    .ok_or_else(|| invalid("missing `uuid`"))?,
+            )?,
+            name: get_component(compound, "name")?,
+        },
+        action => return Err(invalid(format!("unknown hover action `{action}`"))),
+    })
+}
+
+impl<'de> Deserialize<'de> for Component {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+        let tag = Tag::deserialize(deserializer)?;
+        parse_component(&tag).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for Component {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        if let (true, Content::Text(text)) = (self.is_plain_text(), &self.content) {
+            return serializer.serialize_str(text);
+        }
+
+        let mut map = serializer.serialize_map(None)?;
+        match &self.content {
+            Content::Text(text) => map.serialize_entry("text", text)?,
+            Content::Translatable {
+                key,
+                fallback,
+                args,
+            } => {
+                map.serialize_entry("translate", key)?;
+                if let Some(fallback) = fallback {
+                    map.serialize_entry("fallback", fallback)?;
+                }
+                if !args.is_empty() {
+                    map.serialize_entry("with", args)?;
+                }
+            }
+            Content::Score { name, objective } => {
+                map.serialize_entry("score", &Score { name, objective })?;
+            }
+            Content::Selector { pattern, separator } => {
+                map.serialize_entry("selector", pattern)?;
+                if let Some(separator) = separator {
+                    map.serialize_entry("separator", separator)?;
+                }
+            }
+            Content::Keybind(key) => map.serialize_entry("keybind", key)?,
+            Content::Nbt {
+                path,
+                interpret,
+                separator,
+                source,
+            } => {
+                map.serialize_entry("nbt", path)?;
+                if *interpret {
+                    map.serialize_entry("interpret", &true)?;
+                }
+                if let Some(separator) = separator {
+                    map.serialize_entry("separator", separator)?;
+                }
+                match source {
+                    NbtSource::Block(pos) => map.serialize_entry("block", pos)?,
+                    NbtSource::Entity(selector) => map.serialize_entry("entity", selector)?,
+                    NbtSource::Storage(id) => map.serialize_entry("storage", id)?,
+                }
+            }
+        }
+
+        let style = &self.style;
+        if let Some(color) = style.color {
+            map.serialize_entry("color", &color.to_string())?;
+        }
+        if let Some(shadow_color) = style.shadow_color {
+            map.serialize_entry("shadow_color", &(shadow_color as i32))?;
+        }
+        for (key, value) in [
+            ("bold", style.bold),
+            ("italic", style.italic),
+            ("underlined", style.underlined),
+            ("strikethrough", style.strikethrough),
+            ("obfuscated", style.obfuscated),
+        ] {
+            if let Some(value) = value {
+                map.serialize_entry(key, &value)?;
+            }
+        }
+        if let Some(click_event) = &style.click_event {
+            map.serialize_entry("click_event", &ClickEventRef(click_event))?;
+        }
+        if let Some(hover_event) = &style.hover_event {
+            map.serialize_entry("hover_event", &HoverEventRef(hover_event))?;
+        }
+        if let Some(insertion) = &style.insertion {
+            map.serialize_entry("insertion", insertion)?;
+        }
+        if let Some(font) = &style.font {
+            map.serialize_entry("font", font)?;
+        }
+
+        if !self.extra.is_empty() {
+            map.serialize_entry("extra", &self.extra)?;
+        }
+        map.end()
+    }
+}
+
+#[derive(Serialize)]
+struct Score<'a> {
+    name: &'a str,
+    objective: &'a str,
+}
+
+struct ClickEventRef<'a>(&'a ClickEvent);
+
+impl Serialize for ClickEventRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("action", self.0.action())?;
+        match self.0 {
+            ClickEvent::OpenUrl { url } => map.serialize_entry("url", url)?,
+            ClickEvent::OpenFile { path } => map.serialize_entry("path", path)?,
+            ClickEvent::RunCommand { command } | ClickEvent::SuggestCommand { command } => {
+                map.serialize_entry("command", command)?
+            }
+            ClickEvent::ChangePage { page } => map.serialize_entry("page", page)?,
+            ClickEvent::CopyToClipboard { value } => map.serialize_entry("value", value)?,
+        }
+        map.end()
+    }
+}
+
+struct HoverEventRef<'a>(&'a HoverEvent);
+
+impl Serialize for HoverEventRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("action", self.0.action())?;
+        match self.0 {
+            HoverEvent::ShowText(text) => map.serialize_entry("value", text)?,
+            HoverEvent::ShowItem {
+                id,
+                count,
+                components,
+            } => {
+                map.serialize_entry("id", id)?;
+                if *count != 1 {
+                    map.serialize_entry("count", count)?;
+                }
+                if let Some(components) = components {
+                    map.serialize_entry("components", components)?;
+                }
+            }
+            HoverEvent::ShowEntity { id, uuid, name } => {
+                map.serialize_entry("id", id)?;
+                map.serialize_entry("uuid", &IntArray(uuid.to_vec()))?;
+                if let Some(name) = name {
+                    map.serialize_entry("name", name)?;
+                }
+            }
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::NamedColor;
+
+    #[test]
+    fn test_plain_text_collapses_to_string() {
+        let component = Component::text("hi");
+        assert_eq!(component.to_json(), "\"hi\"");
+        assert_eq!(component.to_nbt(), Tag::String("hi".into()));
+        assert_eq!(Component::from_json("\"hi\"").unwrap(), component);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let json = r##"{"translate":"chat.type.text","with":[{"text":"Steve","color":"#12AB3F","click_event":{"action":"suggest_command","command":"/msg Steve "},"hover_event":{"action":"show_entity","id":"minecraft:player","uuid":[1,2,3,4],"name":"Steve"}},"hello"],"bold":true,"extra":[{"keybind":"key.jump","italic":false}]}"##;
+        let component = Component::from_json(json).unwrap();
+        let Content::Translatable { args, .. } = &component.content else {
+            panic!("expected a translatable component");
+        };
+        assert_eq!(args[0].style.color, Some(TextColor::Rgb(0x12AB3F)));
+        assert_eq!(
+            args[0].style.hover_event.as_deref(),
+            Some(&HoverEvent::ShowEntity {
+                id: "minecraft:player".into(),
+                uuid: [1, 2, 3, 4],
+                name: Some(Component::text("Steve")),
+            })
+        );
+        assert_eq!(component.to_json(), json);
+    }
+
+    #[test]
+    fn test_nbt_round_trip() {
+        let component = Component::text("a")
+            .with_style(
+                Style::default()
+                    .with_color(NamedColor::Gold)
+                    .with_bold(true),
+            )
+            .append(Component::translatable("item.minecraft.diamond", vec![]));
+        let tag = component.to_nbt();
+        assert_eq!(
+            mcre_nbt::to_snbt(&tag),
+            "{bold:1b,color:\"gold\",extra:[{translate:\"item.minecraft.diamond\"}],text:\"a\"}"
+        );
+        assert_eq!(Component::from_nbt(&tag).unwrap(), component);
+
+        let mut bytes = Vec::new();
+        component.write_network_nbt(&mut bytes);
+        let mut buf = bytes.as_slice();
+        assert_eq!(Component::read_network_nbt(&mut buf).unwrap(), component);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_list_and_shadow_color() {
+        let component =
+            Component::from_json(r#"[{"text":"a","shadow_color":[1.0,0.0,0.0,0.5]},"b",7]"#);
+        assert!(component.is_err());
+        let component =
+            Component::from_json(r#"[{"text":"a","shadow_color":[1.0,0.0,0.0,0.5]},"b"]"#).unwrap();
+        assert_eq!(component.style.shadow_color, Some(0x80FF0000));
+        assert_eq!(component.extra, vec![Component::text("b")]);
+    }
+
+    #[test]
+    fn test_invalid_components_are_rejected() {
+        for json in [
+            r#"{"color":"red"}"#,
+            r#"{"text":"a","color":"not_a_color"}"#,
+            r#"{"text":"a","click_event":{"action":"open_url"}}"#,
+            r#"{"type":"score","text":"a"}"#,
+            "[]",
+        ] {
+            assert!(Component::from_json(json).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn test_string_uuid() {
+        let component = Component::from_json(
+            r#"{"text":"a","hover_event":{"action":"show_entity","id":"minecraft:pig","uuid":"f81d4fae-7dec-11d0-a765-00a0c91e6bf6"}}"#,
+        )
+        .unwrap();
+        let Some(HoverEvent::ShowEntity { uuid, .. }) = component.style.hover_event.as_deref()
+        else {
+            panic!("expected show_entity");
+        };
+        assert_eq!(
+            *uuid,
+            [
+                0xf81d4faeu32 as i32,
+                0x7dec11d0,
+                0xa76500a0u32 as i32,
+                0xc91e6bf6u32 as i32
+            ]
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use crate::Style;
+
+/// A text component: some content, its style, and children that inherit it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Component {
+    pub content: Content,
+    pub style: Style,
+    pub extra: Vec<Component>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Content {
+    Text(String),
+    Translatable {
+        key: String,
+        fallback: Option<String>,
+        args: Vec<Component>,
+    },
+    Score {
+        name: String,
+        objective: String,
+    },
+    Selector {
+        pattern: String,
+        separator: Option<Box<Component>>,
+    },
+    Keybind(String),
+    Nbt {
+        path: String,
+        interpret: bool,
+        separator: Option<Box<Component>>,
+        source: NbtSource,
+    },
+}
+
+impl Default for Content {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NbtSource {
+    /// Block entity at a position, e.g. `~ ~-1 ~`.
+    Block(String),
+    /// Entities matching a selector.
+    Entity(String),
+    /// Command storage id.
+    Storage(String),
+}
+
+impl Component {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            content: Content::Text(text.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn translatable(key: impl Into<String>, args: Vec<Component>) -> Self {
+        Self {
+            content: Content::Translatable {
+                key: key.into(),
+                fallback: None,
+                args,
+            },
+            ..Self::default()
+        }
+    }
+
+    pub fn keybind(key: impl Into<String>) -> Self {
+        Self {
+            content: Content::Keybind(key.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn append(mut self, child: Component) -> Self {
+        self.extra.push(child);
+        self
+    }
+
+    /// Whether this is plain text that vanilla would encode as a bare string.
+    pub fn is_plain_text(&self) -> bool {
+        matches!(self.content, Content::Text(_)) && self.style.is_empty() && self.extra.is_empty()
+    }
+}
+
+impl From<&str> for Component {
+    fn from(text: &str) -> Self {
+        Self::text(text)
+    }
+}
+
+impl From<String> for Component {
+    fn from(text: String) -> Self {
+        Self::text(text)
+    }
+}
@@ -0,0 +1,128 @@
+//! Legacy `§` formatting codes, still found in server list MOTDs, some
+//! plugin messages and old world data.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{Component, Language, NamedColor, Style, TextColor};
+
+pub const SECTION_SIGN: char = '§';
+
+type FormatField = fn(&mut Style) -> &mut Option<bool>;
+
+const FORMATS: [(char, FormatField); 5] = [
+    ('k', |s| &mut s.obfuscated),
+    ('l', |s| &mut s.bold),
+    ('m', |s| &mut s.strikethrough),
+    ('n', |s| &mut s.underlined),
+    ('o', |s| &mut s.italic),
+];
+
+impl Component {
+    /// Parses legacy formatting codes into an unstyled root with one child
+    /// per run. As in the old renderer, a color code also resets formatting.
+    /// Unknown codes are dropped.
+    pub fn from_legacy(text: &str) -> Component {
+        let mut extra = Vec::new();
+        let mut style = Style::default();
+        let mut run = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c != SECTION_SIGN {
+                run.push(c);
+                continue;
+            }
+            let Some(code) = chars.next() else {
+                break;
+            };
+            if !run.is_empty() {
+                extra.push(Component::text(core::mem::take(&mut run)).with_style(style.clone()));
+            }
+            let code = code.to_ascii_lowercase();
+            if let Some(color) = NamedColor::from_code(code) {
+                style = Style::default().with_color(color);
+            } else if code == 'r' {
+                style = Style::default();
+            } else if let Some((_, field)) = FORMATS.iter().find(|(c, _)| *c == code) {
+                *field(&mut style) = Some(true);
+            }
+        }
+        if !run.is_empty() {
+            extra.push(Component::text(run).with_style(style));
+        }
+        Component {
+            extra,
+            ..Component::default()
+        }
+    }
+
+    /// Renders to a string with legacy codes. RGB colors have no code and
+    /// are dropped, as are events, fonts and insertions.
+    pub fn to_legacy_string(&self, language: &dyn Language) -> String {
+        let mut out = String::new();
+        let mut current = Style::default();
+        for run in self.styled_runs(language) {
+            let mut style = run.style;
+            if let Some(TextColor::Rgb(_)) = style.color {
+                style.color = None;
+            }
+            if legacy_style(&style) != legacy_style(&current) {
+                out.push(SECTION_SIGN);
+                out.push(match style.color {
+                    Some(TextColor::Named(color)) => color.code(),
+                    _ => 'r',
+                });
+                for (code, field) in FORMATS {
+                    if *field(&mut style) == Some(true) {
+                        out.push(SECTION_SIGN);
+                        out.push(code);
+                    }
+                }
+                current = style;
+            }
+            out.push_str(&run.text);
+        }
+        out
+    }
+}
+
+/// The part of a style that legacy codes can express.
+fn legacy_style(style: &Style) -> (Option<TextColor>, [bool; 5]) {
+    let mut style = style.clone();
+    (
+        style.color,
+        FORMATS.map(|(_, field)| *field(&mut style) == Some(true)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn test_from_legacy() {
+        let component = Component::from_legacy("§lA§cB§oC§rD§");
+        assert_eq!(
+            component.extra,
+            vec![
+                Component::text("A").with_style(Style::default().with_bold(true)),
+                Component::text("B").with_style(Style::default().with_color(NamedColor::Red)),
+                Component::text("C").with_style(
+                    Style::default()
+                        .with_color(NamedColor::Red)
+                        .with_italic(true)
+                ),
+                Component::text("D"),
+            ]
+        );
+        assert_eq!(component.to_plain_string(&()), "ABCD");
+    }
+
+    #[test]
+    fn test_legacy_round_trip() {
+        let legacy = "§6Gold §6§lbold§rplain";
+        let component = Component::from_legacy(legacy);
+        assert_eq!(component.to_legacy_string(&()), legacy);
+    }
+}
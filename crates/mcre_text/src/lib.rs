@@ -0,0 +1,18 @@
+//! Text components: the rich text used for chat, titles, item names, signs
+//! and disconnect reasons.
+
+#![no_std]
+
+extern crate alloc;
+
+mod codec;
+mod component;
+mod error;
+pub mod legacy;
+mod render;
+mod style;
+
+pub use component::{Component, Content, NbtSource};
+pub use error::{Error, Result};
+pub use render::{Language, StyledRun};
+pub use style::{ClickEvent, HoverEvent, NamedColor, Style, TextColor};
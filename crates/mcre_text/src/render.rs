@@ -0,0 +1,224 @@
+//! Flattening component trees into styled runs of text.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{Component, Content, Style};
+
+/// A source of translations, keyed by translation key.
+pub trait Language {
+    fn get(&self, key: &str) -> Option<&str>;
+}
+
+/// No translations: translatable components render their fallback or key.
+impl Language for () {
+    fn get(&self, _key: &str) -> Option<&str> {
+        None
+    }
+}
+
+/// A piece of text with the fully inherited style it is drawn with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyledRun {
+    pub text: String,
+    pub style: Style,
+}
+
+impl Component {
+    /// Flattens the tree into runs, merging neighbours with equal styles.
+    ///
+    /// Scores and NBT paths resolve against a live server, so they render as
+    /// nothing; selectors render their pattern.
+    pub fn styled_runs(&self, language: &dyn Language) -> Vec<StyledRun> {
+        let mut runs = Vec::new();
+        self.visit(language, &Style::default(), &mut |text, style| {
+            if text.is_empty() {
+                return;
+            }
+            match runs.last_mut() {
+                Some(StyledRun {
+                    text: last,
+                    style: last_style,
+                }) if last_style == style => last.push_str(text),
+                _ => runs.push(StyledRun {
+                    text: text.into(),
+                    style: style.clone(),
+                }),
+            }
+        });
+        runs
+    }
+
+    pub fn to_plain_string(&self, language: &dyn Language) -> String {
+        let mut out = String::new();
+        self.visit(language, &Style::default(), &mut |text, _| {
+            out.push_str(text)
+        });
+        out
+    }
+
+    fn visit(&self, language: &dyn Language, parent: &Style, f: &mut dyn FnMut(&str, &Style)) {
+        let style = self.style.inherit(parent);
+        match &self.content {
+            Content::Text(text) => f(text, &style),
+            Content::Translatable {
+                key,
+                fallback,
+                args,
+            } => {
+                let format = language
+                    .get(key)
+                    .or(fallback.as_deref())
+                    .unwrap_or(key.as_str());
+                visit_translation(format, args, language, &style, f);
+            }
+            Content::Selector { pattern, .. } => f(pattern, &style),
+            Content::Keybind(key) => f(language.get(key).unwrap_or(key), &style),
+            Content::Score { .. } | Content::Nbt { .. } => {}
+        }
+        for child in &self.extra {
+            child.visit(language, &style, f);
+        }
+    }
+}
+
+enum Part<'a> {
+    Literal(&'a str),
+    Arg(usize),
+}
+
+/// Splits a translation into literals and argument references the way
+/// `TranslatableContents.decomposeTemplate` does. Only `%s`, `%n$s` and `%%`
+/// are understood; any other `%` makes the whole format malformed.
+fn decompose(format: &str, arg_count: usize) -> Option<Vec<Part<'_>>> {
+    let mut parts = Vec::new();
+    let mut rest = format;
+    let mut next_arg = 0;
+    while let Some(start) = rest.find('%') {
+        if start > 0 {
+            parts.push(Part::Literal(&rest[..start]));
+        }
+        let spec = &rest[start + 1..];
+        let digits = spec.bytes().take_while(u8::is_ascii_digit).count();
+        let (index, spec) = match spec[digits..].strip_prefix('$') {
+            Some(conversion) if digits > 0 => (Some(spec[..digits].parse::<i32>()), conversion),
+            _ => (None, spec),
+        };
+        match (spec.chars().next(), index) {
+            (Some('%'), None) => parts.push(Part::Literal("%")),
+            (Some('s'), None) => {
+                parts.push(Part::Arg(next_arg));
+                next_arg += 1;
+            }
+            (Some('s'), Some(Ok(n))) if n >= 1 => parts.push(Part::Arg(n as usize - 1)),
+            _ => return None,
+        }
+        rest = &spec[1..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Literal(rest));
+    }
+    parts
+        .iter()
+        .all(|part| !matches!(part, Part::Arg(i) if *i >= arg_count))
+        .then_some(parts)
+}
+
+/// Renders a translation, or the raw format if it is malformed.
+fn visit_translation(
+    format: &str,
+    args: &[Component],
+    language: &dyn Language,
+    style: &Style,
+    f: &mut dyn FnMut(&str, &Style),
+) {
+    let Some(parts) = decompose(format, args.len()) else {
+        return f(format, style);
+    };
+    for part in parts {
+        match part {
+            Part::Literal(text) => f(text, style),
+            Part::Arg(i) => args[i].visit(language, style, f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::NamedColor;
+
+    struct Lang;
+
+    impl Language for Lang {
+        fn get(&self, key: &str) -> Option<&str> {
+            match key {
+                "chat.type.text" => Some("<%s> %s"),
+                "swapped" => Some("%2$s then %1$s"),
+                "key.jump" => Some("Jump"),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_translation() {
+        let message = Component::translatable(
+            "chat.type.text",
+            vec![Component::text("Steve"), Component::text("hi")],
+        );
+        assert_eq!(message.to_plain_string(&Lang), "<Steve> hi");
+        assert_eq!(message.to_plain_string(&()), "chat.type.text");
+
+        let swapped = Component::translatable("swapped", vec!["a".into(), "b".into()]);
+        assert_eq!(swapped.to_plain_string(&Lang), "b then a");
+        assert_eq!(
+            Component::keybind("key.jump").to_plain_string(&Lang),
+            "Jump"
+        );
+    }
+
+    #[test]
+    fn test_translation_formats() {
+        let render = |format: &str, args: &[&str]| {
+            let args: Vec<Component> = args.iter().map(|arg| Component::text(*arg)).collect();
+            let mut out = String::new();
+            visit_translation(format, &args, &(), &Style::default(), &mut |text, _| {
+                out.push_str(text)
+            });
+            out
+        };
+        assert_eq!(render("100%% %s", &["sure"]), "100% sure");
+        assert_eq!(render("%2$s %1$s %s", &["a", "b"]), "b a a");
+        // Malformed formats and missing arguments fall back to the raw format.
+        assert_eq!(render("100% sure", &[]), "100% sure");
+        assert_eq!(render("%s and %s", &["a"]), "%s and %s");
+        assert_eq!(render("%d", &["1"]), "%d");
+        assert_eq!(render("%0$s", &["a"]), "%0$s");
+        assert_eq!(render("trailing %", &[]), "trailing %");
+        assert_eq!(render("%1$%", &[]), "%1$%");
+    }
+
+    #[test]
+    fn test_styled_runs_inherit_and_merge() {
+        let red = Style::default().with_color(NamedColor::Red);
+        let component = Component::text("a")
+            .with_style(red.clone())
+            .append(Component::text("b"))
+            .append(Component::text("c").with_style(Style::default().with_bold(true)));
+        assert_eq!(
+            component.styled_runs(&()),
+            vec![
+                StyledRun {
+                    text: "ab".into(),
+                    style: red.clone(),
+                },
+                StyledRun {
+                    text: "c".into(),
+                    style: red.with_bold(true),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use core::{fmt, str::FromStr};
+
+use alloc::{boxed::Box, string::String};
+use mcre_nbt::Tag;
+
+use crate::Component;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum NamedColor {
+    Black = 0,
+    DarkBlue = 1,
+    DarkGreen = 2,
+    DarkAqua = 3,
+    DarkRed = 4,
+    DarkPurple = 5,
+    Gold = 6,
+    Gray = 7,
+    DarkGray = 8,
+    Blue = 9,
+    Green = 10,
+    Aqua = 11,
+    Red = 12,
+    LightPurple = 13,
+    Yellow = 14,
+    White = 15,
+}
+
+impl NamedColor {
+    pub const ALL: [NamedColor; 16] = [
+        NamedColor::Black,
+        NamedColor::DarkBlue,
+        NamedColor::DarkGreen,
+        NamedColor::DarkAqua,
+        NamedColor::DarkRed,
+        NamedColor::DarkPurple,
+        NamedColor::Gold,
+        NamedColor::Gray,
+        NamedColor::DarkGray,
+        NamedColor::Blue,
+        NamedColor::Green,
+        NamedColor::Aqua,
+        NamedColor::Red,
+        NamedColor::LightPurple,
+        NamedColor::Yellow,
+        NamedColor::White,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Black => "black",
+            Self::DarkBlue => "dark_blue",
+            Self::DarkGreen => "dark_green",
+            Self::DarkAqua => "dark_aqua",
+            Self::DarkRed => "dark_red",
+            Self::DarkPurple => "dark_purple",
+            Self::Gold => "gold",
+            Self::Gray => "gray",
+            Self::DarkGray => "dark_gray",
+            Self::Blue => "blue",
+            Self::Green => "green",
+            Self::Aqua => "aqua",
+            Self::Red => "red",
+            Self::LightPurple => "light_purple",
+            Self::Yellow => "yellow",
+            Self::White => "white",
+        }
+    }
+
+    pub fn rgb(self) -> u32 {
+        match self {
+            Self::Black => 0x000000,
+            Self::DarkBlue => 0x0000AA,
+            Self::DarkGreen => 0x00AA00,
+            Self::DarkAqua => 0x00AAAA,
+            Self::DarkRed => 0xAA0000,
+            Self::DarkPurple => 0xAA00AA,
+            Self::Gold => 0xFFAA00,
+            Self::Gray => 0xAAAAAA,
+            Self::DarkGray => 0x555555,
+            Self::Blue => 0x5555FF,
+            Self::Green => 0x55FF55,
+            Self::Aqua => 0x55FFFF,
+            Self::Red => 0xFF5555,
+            Self::LightPurple => 0xFF55FF,
+            Self::Yellow => 0xFFFF55,
+            Self::White => 0xFFFFFF,
+        }
+    }
+
+    /// The character after `§` that selects this color.
+    pub fn code(self) -> char {
+        char::from_digit(self as u32, 16).unwrap()
+    }
+
+    pub fn from_code(code: char) -> Option<Self> {
+        let index = code.to_ascii_lowercase().to_digit(16)?;
+        Some(Self::ALL[index as usize])
+    }
+}
+
+impl FromStr for NamedColor {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|color| color.as_str() == s)
+            .ok_or(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextColor {
+    Named(NamedColor),
+    Rgb(u32),
+}
+
+impl TextColor {
+    pub fn rgb(self) -> u32 {
+        match self {
+            Self::Named(color) => color.rgb(),
+            Self::Rgb(rgb) => rgb,
+        }
+    }
+}
+
+impl FromStr for TextColor {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(hex) = s.strip_prefix('#') {
+            let rgb = u32::from_str_radix(hex, 16).map_err(|_| ())?;
+            return if rgb <= 0xFFFFFF {
+                Ok(Self::Rgb(rgb))
+            } else {
+                Err(())
+            };
+        }
+        s.parse().map(Self::Named)
+    }
+}
+
+impl fmt::Display for TextColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Named(color) => f.write_str(color.as_str()),
+            Self::Rgb(rgb) => write!(f, "#{rgb:06X}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClickEvent {
+    OpenUrl { url: String },
+    OpenFile { path: String },
+    RunCommand { command: String },
+    SuggestCommand { command: String },
+    ChangePage { page: i32 },
+    CopyToClipboard { value: String },
+}
+
+impl ClickEvent {
+    pub fn action(&self) -> &'static str {
+        match self {
+            Self::OpenUrl { .. } => "open_url",
+            Self::OpenFile { .. } => "open_file",
+            Self::RunCommand { .. } => "run_command",
+            Self::SuggestCommand { .. } => "suggest_command",
+            Self::ChangePage { .. } => "change_page",
+            Self::CopyToClipboard { .. } => "copy_to_clipboard",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HoverEvent {
+    ShowText(Component),
+    ShowItem {
+        id: String,
+        count: i32,
+        /// The data component patch as NBT: `mcre_item` builds on this crate,
+        /// so the typed stack comes from `mcre_item::ItemStack::from_hover_event`.
+        components: Option<Tag>,
+    },
+    ShowEntity {
+        id: String,
+        uuid: [i32; 4],
+        name: Option<Component>,
+    },
+}
+
+impl HoverEvent {
+    pub fn action(&self) -> &'static str {
+        match self {
+            Self::ShowText(_) => "show_text",
+            Self::ShowItem { .. } => "show_item",
+            Self::ShowEntity { .. } => "show_entity",
+        }
+    }
+}
+
+/// Formatting of a component. Unset fields are inherited from the parent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Style {
+    pub color: Option<TextColor>,
+    /// ARGB colour of the text shadow.
+    pub shadow_color: Option<u32>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underlined: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub obfuscated: Option<bool>,
+    pub click_event: Option<ClickEvent>,
+    pub hover_event: Option<Box<HoverEvent>>,
+    pub insertion: Option<String>,
+    pub font: Option<String>,
+}
+
+impl Style {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Fills the unset fields of `self` from `parent`, like `Style.applyTo`.
+    pub fn inherit(&self, parent: &Style) -> Style {
+        Style {
+            color: self.color.or(parent.color),
+            shadow_color: self.shadow_color.or(parent.shadow_color),
+            bold: self.bold.or(parent.bold),
+            italic: self.italic.or(parent.italic),
+            underlined: self.underlined.or(parent.underlined),
+            strikethrough: self.strikethrough.or(parent.strikethrough),
+            obfuscated: self.obfuscated.or(parent.obfuscated),
+            click_event: self
+                .click_event
+                .clone()
+                .or_else(|| parent.click_event.clone()),
+            hover_event: self
+                .hover_event
+                .clone()
+                .or_else(|| parent.hover_event.clone()),
+            insertion: self.insertion.clone().or_else(|| parent.insertion.clone()),
+            font: self.font.clone().or_else(|| parent.font.clone()),
+        }
+    }
+
+    pub fn with_color(mut self, color: impl Into<TextColor>) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    pub fn with_bold(mut self, bold: bool) -> Self {
+        self.bold = Some(bold);
+        self
+    }
+
+    pub fn with_italic(mut self, italic: bool) -> Self {
+        self.italic = Some(italic);
+        self
+    }
+}
+
+impl From<NamedColor> for TextColor {
+    fn from(color: NamedColor) -> Self {
+        Self::Named(color)
+    }
+}
+
+/// Parses a hyphenated UUID into four big-endian ints.
+pub(crate) fn uuid_from_str(s: &str) -> Option<[i32; 4]> {
+    let hex: String = s.chars().filter(|c| *c != '-').collect();
+    if hex.len() != 32 || s.len() != 36 {
+        return None;
+    }
+    let value = u128::from_str_radix(&hex, 16).ok()?;
+    Some([
+        (value >> 96) as u32 as i32,
+        (value >> 64) as u32 as i32,
+        (value >> 32) as u32 as i32,
+        value as u32 as i32,
+    ])
+}
@@ -0,0 +1,255 @@
+//! Name and property lookups on top of the generated block data, for formats
+//! that identify states by name, like `minecraft:oak_log[axis=y]`.
+
+use core::{fmt, str::FromStr};
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use mcre_core::{Axis, Direction, Mirror, Rotation};
+
+use crate::{Block, BlockState, DoorHingeSide, PropKey, PropVal, TypePropVal};
+
+impl Block {
+    /// Looks a block up by its registry name, with or without the
+    /// `minecraft:` namespace.
+    pub fn from_name(name: &str) -> Option<Block> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        Block::all().find(|block| block.name() == name)
+    }
+
+    pub fn states(self) -> impl Iterator<Item = BlockState> {
+        (u16::from(self.min_state())..=u16::from(self.max_state())).map(BlockState::from)
+    }
+}
+
+impl BlockState {
+    /// The properties of this state, in `PropKey` order.
+    pub fn props(self) -> impl Iterator<Item = PropVal> {
+        PropKey::ALL
+            .into_iter()
+            .filter_map(move |key| self.get_prop(key))
+    }
+
+    /// The state of `block` with the given properties, taking the rest from
+    /// its default state. `None` if a property does not belong to the block.
+    pub fn from_props(block: Block, props: impl IntoIterator<Item = PropVal>) -> Option<Self> {
+        let mut wanted: Vec<PropVal> = block.default_state().props().collect();
+        for prop in props {
+            let slot = wanted.iter_mut().find(|val| val.key() == prop.key())?;
+            *slot = prop;
+        }
+        block.states().find(|state| {
+            wanted
+                .iter()
+                .all(|val| state.get_prop(val.key()) == Some(*val))
+        })
+    }
+
+    /// This state with one property changed.
+    pub fn with_prop(self, prop: PropVal) -> Option<Self> {
+        Self::from_props(self.block(), self.props().chain(core::iter::once(prop)))
+    }
+}
+
+/// Rotations and mirrors, as applied to blocks of a placed structure.
+///
+/// These work on properties alone: directions, axes, headings, connections
+/// and handedness all follow the transform. Vanilla overrides a few blocks
+/// by hand, but the shapes that differ are recomputed from neighbours once
+/// the blocks update.
+impl BlockState {
+    pub fn rotate(self, rotation: Rotation) -> Self {
+        if rotation == Rotation::None {
+            return self;
+        }
+        self.transform(
+            |direction| rotation.rotate(direction),
+            |heading| rotation.rotate_heading(heading, 16),
+            false,
+        )
+    }
+
+    pub fn mirror(self, mirror: Mirror) -> Self {
+        if mirror == Mirror::None {
+            return self;
+        }
+        self.transform(
+            |direction| mirror.mirror(direction),
+            |heading| mirror.mirror_heading(heading, 16),
+            true,
+        )
+    }
+
+    fn transform(
+        self,
+        direction: impl Fn(Direction) -> Direction,
+        heading: impl Fn(u8) -> u8,
+        flip: bool,
+    ) -> Self {
+        let props = self.props().map(|prop| match prop {
+            PropVal::Facing(facing) => PropVal::Facing(direction(facing)),
+            PropVal::Axis(axis) => PropVal::Axis(match axis {
+                Axis::Y => Axis::Y,
+                Axis::X => direction(Direction::East).axis(),
+                Axis::Z => direction(Direction::South).axis(),
+            }),
+            PropVal::Rotation(value) => PropVal::Rotation(heading(value)),
+            PropVal::Hinge(hinge) if flip => PropVal::Hinge(match hinge {
+                DoorHingeSide::Left => DoorHingeSide::Right,
+                DoorHingeSide::Right => DoorHingeSide::Left,
+            }),
+            PropVal::Type(TypePropVal::Left) if flip => PropVal::Type(TypePropVal::Right),
+            PropVal::Type(TypePropVal::Right) if flip => PropVal::Type(TypePropVal::Left),
+            PropVal::North(_) | PropVal::East(_) | PropVal::South(_) | PropVal::West(_) => {
+                let side: Direction = prop.key().as_str().parse().unwrap();
+                transform_value(prop, |value| {
+                    let key = direction(side).as_str().parse().ok()?;
+                    PropVal::parse_with_key(key, value)
+                })
+            }
+            PropVal::Shape(_) | PropVal::Orientation(_) => transform_value(prop, |value| {
+                let value = swap_handedness(value, flip);
+                let parts: Vec<&str> = value
+                    .split('_')
+                    .map(|part| part.parse().map_or(part, |d| direction(d).as_str()))
+                    .collect();
+                PropVal::parse_with_key(prop.key(), &parts.join("_")).or_else(|| {
+                    // rail curves are named north or south first
+                    let [a, b] = parts[..] else { return None };
+                    PropVal::parse_with_key(prop.key(), &format!("{b}_{a}"))
+                })
+            }),
+            prop => prop,
+        });
+        Self::from_props(self.block(), props).unwrap_or(self)
+    }
+}
+
+/// Rebuilds a property from its transformed value string, or keeps it if the
+/// new value does not exist.
+fn transform_value(prop: PropVal, f: impl FnOnce(&str) -> Option<PropVal>) -> PropVal {
+    let text = prop.to_string();
+    let (_, value) = text.split_once('=').unwrap();
+    f(value).unwrap_or(prop)
+}
+
+/// Swaps `left` and `right` in a stair shape.
+fn swap_handedness(value: &str, flip: bool) -> String {
+    match value.rsplit_once('_') {
+        Some((kind, "left")) if flip => format!("{kind}_right"),
+        Some((kind, "right")) if flip => format!("{kind}_left"),
+        _ => value.to_string(),
+    }
+}
+
+/// Parses `name[key=value,...]`, the syntax used by commands and structure
+/// files. Unlisted properties keep their default values.
+impl FromStr for BlockState {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        let (name, props) = match s.split_once('[') {
+            Some((name, rest)) => (name, rest.strip_suffix(']').ok_or(())?),
+            None => (s, ""),
+        };
+        let block = Block::from_name(name).ok_or(())?;
+        let props = props
+            .split(',')
+            .filter(|prop| !prop.is_empty())
+            .map(|prop| prop.trim().parse())
+            .collect::<Result<Vec<PropVal>, ()>>()?;
+        Self::from_props(block, props).ok_or(())
+    }
+}
+
+impl fmt::Display for BlockState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "minecraft:{}", self.block().name())?;
+        let mut props = self.props().peekable();
+        if props.peek().is_some() {
+            f.write_str("[")?;
+            for (i, prop) in props.enumerate() {
+                if i > 0 {
+                    f.write_str(",")?;
+                }
+                write!(f, "{prop}")?;
+            }
+            f.write_str("]")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_string_round_trip() {
+        for state in BlockState::all().step_by(97) {
+            let s = state.to_string();
+            assert_eq!(s.parse::<BlockState>(), Ok(state), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_parse_defaults_and_errors() {
+        let log = Block::from_name("oak_log").unwrap();
+        assert_eq!("minecraft:oak_log".parse(), Ok(log.default_state()));
+        let x = "oak_log[axis=x]".parse::<BlockState>().unwrap();
+        assert_eq!(x.axis(), Axis::X);
+        assert_eq!(x.with_prop(PropVal::Axis(Axis::Z)).unwrap().axis(), Axis::Z);
+        assert_eq!("oak_log[facing=up]".parse::<BlockState>(), Err(()));
+        assert_eq!("oak_log[axis=w]".parse::<BlockState>(), Err(()));
+        assert_eq!("minecraft:not_a_block".parse::<BlockState>(), Err(()));
+    }
+
+    #[test]
+    fn test_rotate_and_mirror() {
+        let parse = |s: &str| s.parse::<BlockState>().unwrap();
+        let stairs = parse("oak_stairs[facing=north,shape=inner_left]");
+        assert_eq!(
+            stairs.rotate(Rotation::Clockwise90),
+            parse("oak_stairs[facing=east,shape=inner_left]")
+        );
+        assert_eq!(
+            stairs.mirror(Mirror::LeftRight),
+            parse("oak_stairs[facing=south,shape=inner_right]")
+        );
+        assert_eq!(
+            parse("oak_log[axis=x]").rotate(Rotation::Counterclockwise90),
+            parse("oak_log[axis=z]")
+        );
+        assert_eq!(
+            parse("oak_sign[rotation=4]").mirror(Mirror::FrontBack),
+            parse("oak_sign[rotation=12]")
+        );
+        assert_eq!(
+            parse("oak_fence[north=true]").rotate(Rotation::Clockwise180),
+            parse("oak_fence[south=true]")
+        );
+        assert_eq!(
+            parse("rail[shape=north_east]").rotate(Rotation::Clockwise90),
+            parse("rail[shape=south_east]")
+        );
+        assert_eq!(
+            parse("oak_door[hinge=left]").mirror(Mirror::FrontBack),
+            parse("oak_door[hinge=right]")
+        );
+        assert_eq!(
+            parse("jigsaw[orientation=north_up]").rotate(Rotation::Clockwise90),
+            parse("jigsaw[orientation=east_up]")
+        );
+        for state in BlockState::all().step_by(13) {
+            let turned = Rotation::ALL
+                .into_iter()
+                .fold(state, |state, _| state.rotate(Rotation::Clockwise90));
+            assert_eq!(turned, state, "{state}");
+            let flipped = state.mirror(Mirror::LeftRight).mirror(Mirror::LeftRight);
+            assert_eq!(flipped, state, "{state}");
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use alloc::vec::Vec;
+
+use super::{BitStorage, HeightmapKind, WorldHeight};
+use crate::{BlockState, BlockTag};
+
+impl HeightmapKind {
+    /// Whether this is one of the world-generation heightmaps, which vanilla
+    /// stops maintaining once a chunk is generated.
+    pub fn is_worldgen(self) -> bool {
+        matches!(self, Self::WorldSurfaceWg | Self::OceanFloorWg)
+    }
+
+    /// Whether `state` counts towards this heightmap, vanilla's
+    /// `Heightmap.Types.isOpaque`.
+    pub fn is_opaque(self, state: BlockState) -> bool {
+        match self {
+            Self::WorldSurfaceWg | Self::WorldSurface => !state.is_air(),
+            Self::OceanFloorWg | Self::OceanFloor => state.blocks_motion(),
+            Self::MotionBlocking => state.blocks_motion() || !state.fluid_state().is_empty(),
+            Self::MotionBlockingNoLeaves => {
+                (state.blocks_motion() || !state.fluid_state().is_empty())
+                    && !state.block().is_in(BlockTag::Leaves)
+            }
+        }
+    }
+}
+
+/// For each column of a chunk, the lowest y above every block that counts
+/// towards a [`HeightmapKind`], vanilla's `Heightmap`.
+///
+/// Heights are stored relative to the bottom of the world in the same
+/// packed longs as chunk packets and region files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heightmap {
+    kind: HeightmapKind,
+    min_y: i32,
+    data: BitStorage,
+}
+
+impl Heightmap {
+    /// `ceil(log2(height + 1))`, enough for every height including the top.
+    fn bits(height: WorldHeight) -> u8 {
+        (u32::BITS - height.height.leading_zeros()) as u8
+    }
+
+    /// A heightmap with every column empty.
+    pub fn new(kind: HeightmapKind, height: WorldHeight) -> Self {
+        Self {
+            kind,
+            min_y: height.min_y,
+            data: BitStorage::new(Self::bits(height), 256),
+        }
+    }
+
+    /// Decodes packed heights, or `None` if there are not the right number
+    /// of longs for the world height.
+    pub fn from_packed(kind: HeightmapKind, height: WorldHeight, packed: Vec<u64>) -> Option<Self> {
+        Some(Self {
+            kind,
+            min_y: height.min_y,
+            data: BitStorage::from_raw(Self::bits(height), 256, packed)?,
+        })
+    }
+
+    /// Computes heightmaps from the blocks of a column, vanilla's
+    /// `primeHeightmaps`. `get_block` takes local x and z.
+    pub fn compute(
+        kinds: &[HeightmapKind],
+        height: WorldHeight,
+        get_block: impl Fn(i32, i32, i32) -> BlockState,
+    ) -> Vec<Self> {
+        let mut heightmaps: Vec<Self> = kinds.iter().map(|kind| Self::new(*kind, height)).collect();
+        for z in 0..16 {
+            for x in 0..16 {
+                let mut unset = heightmaps.len();
+                for y in (height.min_y..height.max_y()).rev() {
+                    let state = get_block(x, y, z);
+                    if state.is_air() {
+                        continue;
+                    }
+                    for heightmap in &mut heightmaps {
+                        if heightmap.get(x, z) == height.min_y && heightmap.kind.is_opaque(state) {
+                            heightmap.set(x, z, y + 1);
+                            unset -= 1;
+                        }
+                    }
+                    if unset == 0 {
+                        break;
+                    }
+                }
+            }
+        }
+        heightmaps
+    }
+
+    pub fn kind(&self) -> HeightmapKind {
+        self.kind
+    }
+
+    pub fn packed(&self) -> &[u64] {
+        self.data.raw()
+    }
+
+    /// The lowest y above every counted block of a column, the bottom of the
+    /// world if it has none. `x` and `z` are taken modulo 16.
+    pub fn get(&self, x: i32, z: i32) -> i32 {
+        self.min_y + self.data.get(Self::index(x, z)) as i32
+    }
+
+    fn set(&mut self, x: i32, z: i32, y: i32) {
+        self.data.set(Self::index(x, z), (y - self.min_y) as u32);
+    }
+
+    fn index(x: i32, z: i32) -> usize {
+        ((z & 15) << 4 | (x & 15)) as usize
+    }
+
+    /// Takes a block set at `(x, y, z)` into account, scanning down the
+    /// column with `get_block(y)` if it was the top. Returns whether the
+    /// height changed.
+    pub fn update(
+        &mut self,
+        x: i32,
+        y: i32,
+        z: i32,
+        state: BlockState,
+        get_block: impl Fn(i32) -> BlockState,
+    ) -> bool {
+        let first_available = self.get(x, z);
+        if y <= first_available - 2 {
+            return false;
+        }
+        if self.kind.is_opaque(state) {
+            if y >= first_available {
+                self.set(x, z, y + 1);
+                return true;
+            }
+        } else if first_available - 1 == y {
+            let top = (self.min_y..y)
+                .rev()
+                .find(|y| self.kind.is_opaque(get_block(*y)))
+                .map_or(self.min_y, |y| y + 1);
+            self.set(x, z, top);
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use mcre_core::BlockPos;
+
+    use super::*;
+    use crate::{BiomeId, Chunk, ChunkData, ChunkSection, LightUpdate};
+
+    fn parse(s: &str) -> BlockState {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_predicates() {
+        let leaves = parse("oak_leaves");
+        assert!(HeightmapKind::MotionBlocking.is_opaque(leaves));
+        assert!(!HeightmapKind::MotionBlockingNoLeaves.is_opaque(leaves));
+        let water = parse("water");
+        assert!(HeightmapKind::MotionBlocking.is_opaque(water));
+        assert!(!HeightmapKind::OceanFloor.is_opaque(water));
+        assert!(HeightmapKind::WorldSurface.is_opaque(water));
+        let torch = parse("torch");
+        assert!(!HeightmapKind::MotionBlocking.is_opaque(torch));
+        assert!(HeightmapKind::WorldSurface.is_opaque(torch));
+        assert!(HeightmapKind::MotionBlocking.is_opaque(parse("oak_fence")));
+        assert!(HeightmapKind::MotionBlocking.is_opaque(parse("oak_slab[waterlogged=true]")));
+        assert!(HeightmapKind::MotionBlocking.is_opaque(parse("tall_seagrass")));
+        assert!(!HeightmapKind::MotionBlocking.is_opaque(parse("oak_fence_gate[open=true]")));
+        assert!(!HeightmapKind::OceanFloor.is_opaque(parse("iron_chain[axis=x]")));
+        assert!(parse("piston_head").blocks_motion());
+        assert!(!parse("creeper_head").blocks_motion());
+    }
+
+    #[test]
+    fn test_packed_round_trip() {
+        let height = WorldHeight::OVERWORLD;
+        let mut heightmap = Heightmap::new(HeightmapKind::WorldSurface, height);
+        assert_eq!(heightmap.packed().len(), 37);
+        heightmap.set(3, 15, 319);
+        heightmap.set(15, 15, -10);
+        let decoded = Heightmap::from_packed(
+            HeightmapKind::WorldSurface,
+            height,
+            heightmap.packed().to_vec(),
+        )
+        .unwrap();
+        assert_eq!(decoded.get(3, 15), 319);
+        assert_eq!(decoded.get(-1, -1), -10);
+        assert_eq!(decoded.get(0, 0), -64);
+        assert!(Heightmap::from_packed(HeightmapKind::WorldSurface, height, vec![0; 36]).is_none());
+    }
+
+    #[test]
+    fn test_chunk_keeps_heightmaps_current() {
+        let height = WorldHeight {
+            min_y: -16,
+            height: 48,
+        };
+        let mut chunk = Chunk::from(ChunkData {
+            height,
+            sections: (0..height.section_count())
+                .map(|_| ChunkSection::empty(BiomeId(0)))
+                .collect(),
+            heightmaps: Vec::new(),
+            block_entities: Vec::new(),
+            light: LightUpdate::default(),
+        });
+        let surface = |chunk: &Chunk, kind, x, z| chunk.heightmap(kind).unwrap().get(x, z);
+        assert_eq!(surface(&chunk, HeightmapKind::MotionBlocking, 0, 0), -16);
+        assert!(chunk.heightmap(HeightmapKind::WorldSurfaceWg).is_none());
+
+        let mut seed = 0x9e37_79b9_7f4a_7c15u64;
+        let mut next = |bound: u64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % bound) as i32
+        };
+        let states = [
+            "air",
+            "air",
+            "stone",
+            "oak_leaves",
+            "water",
+            "torch",
+            "oak_slab[waterlogged=true]",
+            "short_grass",
+        ]
+        .map(parse);
+        for _ in 0..2000 {
+            let pos = BlockPos::new(next(4), next(48) - 16, next(4));
+            chunk.set_block(pos, states[next(states.len() as u64) as usize]);
+        }
+        let kinds = [
+            HeightmapKind::WorldSurface,
+            HeightmapKind::OceanFloor,
+            HeightmapKind::MotionBlocking,
+            HeightmapKind::MotionBlockingNoLeaves,
+        ];
+        let expected = Heightmap::compute(&kinds, height, |x, y, z| {
+            chunk.get_block(BlockPos::new(x, y, z))
+        });
+        for heightmap in expected {
+            assert_eq!(chunk.heightmap(heightmap.kind()), Some(&heightmap));
+        }
+        // the packed form survives a round trip through chunk data
+        let reloaded = Chunk::from(chunk.to_data());
+        assert_eq!(
+            reloaded.heightmap(HeightmapKind::OceanFloor),
+            chunk.heightmap(HeightmapKind::OceanFloor)
+        );
+    }
+}
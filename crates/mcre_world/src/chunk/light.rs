@@ -0,0 +1,64 @@
+use alloc::{boxed::Box, vec::Vec};
+
+/// One section's worth of 4-bit light levels, vanilla's `DataLayer`.
+///
+/// Entries are indexed by `y << 8 | z << 4 | x`, two per byte with the even
+/// index in the low nibble.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NibbleArray(Box<[u8; NibbleArray::BYTES]>);
+
+impl NibbleArray {
+    pub const BYTES: usize = 2048;
+
+    pub fn new() -> Self {
+        Self::filled(0)
+    }
+
+    pub fn filled(level: u8) -> Self {
+        let level = level & 0xF;
+        Self(Box::new([level | (level << 4); Self::BYTES]))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let bytes: [u8; Self::BYTES] = bytes.try_into().ok()?;
+        Some(Self(Box::new(bytes)))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; Self::BYTES] {
+        &self.0
+    }
+
+    #[inline]
+    fn index(x: usize, y: usize, z: usize) -> usize {
+        (y << 8) | (z << 4) | x
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> u8 {
+        let index = Self::index(x, y, z);
+        (self.0[index >> 1] >> ((index & 1) * 4)) & 0xF
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, z: usize, level: u8) {
+        let index = Self::index(x, y, z);
+        let shift = (index & 1) * 4;
+        let byte = &mut self.0[index >> 1];
+        *byte = (*byte & !(0xF << shift)) | ((level & 0xF) << shift);
+    }
+}
+
+impl Default for NibbleArray {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Light for a column of sections, including the sections directly below and
+/// above the world, as sent by the server.
+///
+/// Each list has one entry per section starting one below the lowest world
+/// section. `None` means "no change" in an update and "no data" in a chunk.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LightUpdate {
+    pub sky: Vec<Option<NibbleArray>>,
+    pub block: Vec<Option<NibbleArray>>,
+}
@@ -0,0 +1,411 @@
+mod heightmap;
+mod light;
+mod palette;
+mod storage;
+
+pub use heightmap::Heightmap;
+pub use light::{LightUpdate, NibbleArray};
+pub use palette::PalettedContainer;
+pub use storage::BitStorage;
+
+use alloc::{string::String, vec::Vec};
+use hashbrown::HashMap;
+use mcre_core::BlockPos;
+use mcre_nbt::Compound;
+use rustc_hash::FxBuildHasher;
+
+use crate::{Block, BlockState};
+
+/// Number of block states in a 16×16×16 section.
+pub const SECTION_BLOCKS: usize = 4096;
+/// Number of biome cells in a section, one per 4×4×4 blocks.
+pub const SECTION_BIOMES: usize = 64;
+
+/// A biome id in the biome registry of the save or connection it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BiomeId(pub u16);
+
+/// The vertical extent of a dimension, from its `dimension_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorldHeight {
+    pub min_y: i32,
+    pub height: u32,
+}
+
+impl WorldHeight {
+    pub const OVERWORLD: Self = Self {
+        min_y: -64,
+        height: 384,
+    };
+
+    pub fn min_section(self) -> i32 {
+        self.min_y >> 4
+    }
+
+    pub fn section_count(self) -> usize {
+        (self.height / 16) as usize
+    }
+
+    /// One past the highest block y.
+    pub fn max_y(self) -> i32 {
+        self.min_y + self.height as i32
+    }
+
+    pub fn contains_y(self, y: i32) -> bool {
+        (self.min_y..self.max_y()).contains(&y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum HeightmapKind {
+    WorldSurfaceWg = 0,
+    WorldSurface = 1,
+    OceanFloorWg = 2,
+    OceanFloor = 3,
+    MotionBlocking = 4,
+    MotionBlockingNoLeaves = 5,
+}
+
+impl HeightmapKind {
+    pub const ALL: [HeightmapKind; 6] = [
+        HeightmapKind::WorldSurfaceWg,
+        HeightmapKind::WorldSurface,
+        HeightmapKind::OceanFloorWg,
+        HeightmapKind::OceanFloor,
+        HeightmapKind::MotionBlocking,
+        HeightmapKind::MotionBlockingNoLeaves,
+    ];
+
+    pub fn from_id(id: u32) -> Option<Self> {
+        Self::ALL.get(id as usize).copied()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::WorldSurfaceWg => "WORLD_SURFACE_WG",
+            Self::WorldSurface => "WORLD_SURFACE",
+            Self::OceanFloorWg => "OCEAN_FLOOR_WG",
+            Self::OceanFloor => "OCEAN_FLOOR",
+            Self::MotionBlocking => "MOTION_BLOCKING",
+            Self::MotionBlockingNoLeaves => "MOTION_BLOCKING_NO_LEAVES",
+        }
+    }
+}
+
+/// How a block entity's type was identified by its source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockEntityKind {
+    /// An id in the `block_entity_type` registry, as sent over the network.
+    Id(u32),
+    /// A resource location such as `minecraft:chest`, as stored in saves.
+    Name(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockEntity {
+    pub pos: BlockPos,
+    pub kind: BlockEntityKind,
+    pub data: Option<Compound>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkSection {
+    block_count: u16,
+    states: PalettedContainer<BlockState>,
+    biomes: PalettedContainer<BiomeId>,
+}
+
+impl ChunkSection {
+    pub fn empty(biome: BiomeId) -> Self {
+        Self {
+            block_count: 0,
+            states: PalettedContainer::single(Block::AIR.default_state(), SECTION_BLOCKS),
+            biomes: PalettedContainer::single(biome, SECTION_BIOMES),
+        }
+    }
+
+    /// Returns `None` if the containers do not have section sizes.
+    pub fn new(
+        states: PalettedContainer<BlockState>,
+        biomes: PalettedContainer<BiomeId>,
+    ) -> Option<Self> {
+        if states.len() != SECTION_BLOCKS || biomes.len() != SECTION_BIOMES {
+            return None;
+        }
+        let block_count = states.iter().filter(|state| !state.is_air()).count() as u16;
+        Some(Self {
+            block_count,
+            states,
+            biomes,
+        })
+    }
+
+    #[inline]
+    fn block_index(x: usize, y: usize, z: usize) -> usize {
+        (y << 8) | (z << 4) | x
+    }
+
+    #[inline]
+    fn biome_index(x: usize, y: usize, z: usize) -> usize {
+        (y << 4) | (z << 2) | x
+    }
+
+    /// Number of non-air blocks.
+    pub fn block_count(&self) -> u16 {
+        self.block_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.block_count == 0
+    }
+
+    pub fn states(&self) -> &PalettedContainer<BlockState> {
+        &self.states
+    }
+
+    pub fn biomes(&self) -> &PalettedContainer<BiomeId> {
+        &self.biomes
+    }
+
+    pub fn get_block(&self, x: usize, y: usize, z: usize) -> BlockState {
+        self.states.get(Self::block_index(x, y, z))
+    }
+
+    /// Sets the state at section-local coordinates, returning the old one.
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, state: BlockState) -> BlockState {
+        let previous = self.states.set(Self::block_index(x, y, z), state);
+        match (previous.is_air(), state.is_air()) {
+            (true, false) => self.block_count += 1,
+            (false, true) => self.block_count -= 1,
+            _ => {}
+        }
+        previous
+    }
+
+    /// Biome at quart (4-block) coordinates within the section.
+    pub fn get_biome(&self, x: usize, y: usize, z: usize) -> BiomeId {
+        self.biomes.get(Self::biome_index(x, y, z))
+    }
+
+    pub fn set_biomes(&mut self, biomes: PalettedContainer<BiomeId>) {
+        debug_assert_eq!(biomes.len(), SECTION_BIOMES);
+        self.biomes = biomes;
+    }
+}
+
+/// A decoded chunk column, as carried by [`crate::WorldEvent::ChunkLoaded`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkData {
+    pub height: WorldHeight,
+    /// One section per 16 blocks, bottom to top.
+    pub sections: Vec<ChunkSection>,
+    /// Heightmaps in their packed form, as received.
+    pub heightmaps: Vec<(HeightmapKind, Vec<u64>)>,
+    pub block_entities: Vec<BlockEntity>,
+    pub light: LightUpdate,
+}
+
+/// A loaded chunk column owned by the [`crate::World`].
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    height: WorldHeight,
+    sections: Vec<ChunkSection>,
+    heightmaps: Vec<Heightmap>,
+    block_entities: HashMap<BlockPos, BlockEntity, FxBuildHasher>,
+    sky_light: Vec<Option<NibbleArray>>,
+    block_light: Vec<Option<NibbleArray>>,
+}
+
+impl From<ChunkData> for Chunk {
+    fn from(data: ChunkData) -> Self {
+        let light_sections = data.height.section_count() + 2;
+        let mut heightmaps: Vec<Heightmap> = data
+            .heightmaps
+            .into_iter()
+            .filter_map(|(kind, packed)| Heightmap::from_packed(kind, data.height, packed))
+            .collect();
+        let mut chunk = Self {
+            height: data.height,
+            sections: data.sections,
+            heightmaps: Vec::new(),
+            block_entities: data
+                .block_entities
+                .into_iter()
+                .map(|block_entity| (block_entity.pos, block_entity))
+                .collect(),
+            sky_light: (0..light_sections).map(|_| None).collect(),
+            block_light: (0..light_sections).map(|_| None).collect(),
+        };
+        chunk.apply_light(data.light);
+        // keep what was received and compute the maintained kinds it lacks
+        let missing: Vec<HeightmapKind> = HeightmapKind::ALL
+            .into_iter()
+            .filter(|kind| !kind.is_worldgen() && heightmaps.iter().all(|h| h.kind() != *kind))
+            .collect();
+        heightmaps.extend(Heightmap::compute(&missing, chunk.height, |x, y, z| {
+            chunk.get_block(BlockPos::new(x, y, z))
+        }));
+        heightmaps.sort_by_key(|heightmap| heightmap.kind() as u8);
+        chunk.heightmaps = heightmaps;
+        chunk
+    }
+}
+
+impl Chunk {
+    pub fn height(&self) -> WorldHeight {
+        self.height
+    }
+
+    pub fn sections(&self) -> &[ChunkSection] {
+        &self.sections
+    }
+
+    fn section_index(&self, section_y: i32) -> Option<usize> {
+        usize::try_from(section_y - self.height.min_section())
+            .ok()
+            .filter(|i| *i < self.sections.len())
+    }
+
+    /// The section at section coordinate `section_y` (block y / 16).
+    pub fn section(&self, section_y: i32) -> Option<&ChunkSection> {
+        self.sections.get(self.section_index(section_y)?)
+    }
+
+    pub fn section_mut(&mut self, section_y: i32) -> Option<&mut ChunkSection> {
+        let index = self.section_index(section_y)?;
+        self.sections.get_mut(index)
+    }
+
+    /// State at a world position in this column; air outside the world.
+    pub fn get_block(&self, pos: BlockPos) -> BlockState {
+        match self.section(pos.section_y()) {
+            Some(section) => section.get_block(
+                (pos.x & 15) as usize,
+                (pos.y & 15) as usize,
+                (pos.z & 15) as usize,
+            ),
+            None => Block::AIR.default_state(),
+        }
+    }
+
+    /// Sets the state at a world position in this column, returning the old
+    /// state, or `None` if `pos` is outside the world height.
+    pub fn set_block(&mut self, pos: BlockPos, state: BlockState) -> Option<BlockState> {
+        let section = self.section_mut(pos.section_y())?;
+        let previous = section.set_block(
+            (pos.x & 15) as usize,
+            (pos.y & 15) as usize,
+            (pos.z & 15) as usize,
+            state,
+        );
+        // vanilla drops the block entity when the block changes
+        if previous.block() != state.block() {
+            self.block_entities.remove(&pos);
+        }
+        let sections = &self.sections;
+        let min_section = self.height.min_section();
+        for heightmap in &mut self.heightmaps {
+            if !heightmap.kind().is_worldgen() {
+                heightmap.update(pos.x, pos.y, pos.z, state, |y| {
+                    sections[((y >> 4) - min_section) as usize].get_block(
+                        (pos.x & 15) as usize,
+                        (y & 15) as usize,
+                        (pos.z & 15) as usize,
+                    )
+                });
+            }
+        }
+        Some(previous)
+    }
+
+    pub fn get_biome(&self, pos: BlockPos) -> Option<BiomeId> {
+        self.section(pos.section_y()).map(|section| {
+            section.get_biome(
+                ((pos.x & 15) >> 2) as usize,
+                ((pos.y & 15) >> 2) as usize,
+                ((pos.z & 15) >> 2) as usize,
+            )
+        })
+    }
+
+    /// Replaces the biomes of every section, bottom to top.
+    pub fn set_biomes(&mut self, biomes: Vec<PalettedContainer<BiomeId>>) {
+        for (section, biomes) in self.sections.iter_mut().zip(biomes) {
+            section.set_biomes(biomes);
+        }
+    }
+
+    /// A heightmap of this column. The world-generation kinds are only
+    /// present if they were received, and are not kept up to date.
+    pub fn heightmap(&self, kind: HeightmapKind) -> Option<&Heightmap> {
+        self.heightmaps
+            .iter()
+            .find(|heightmap| heightmap.kind() == kind)
+    }
+
+    pub fn block_entity(&self, pos: BlockPos) -> Option<&BlockEntity> {
+        self.block_entities.get(&pos)
+    }
+
+    pub fn block_entities(&self) -> impl Iterator<Item = &BlockEntity> {
+        self.block_entities.values()
+    }
+
+    pub fn set_block_entity(&mut self, block_entity: BlockEntity) {
+        self.block_entities.insert(block_entity.pos, block_entity);
+    }
+
+    fn light_index(&self, section_y: i32) -> Option<usize> {
+        usize::try_from(section_y - self.height.min_section() + 1)
+            .ok()
+            .filter(|i| *i < self.sky_light.len())
+    }
+
+    /// Sky light of a section, which may be one below or above the world.
+    pub fn sky_light(&self, section_y: i32) -> Option<&NibbleArray> {
+        self.sky_light.get(self.light_index(section_y)?)?.as_ref()
+    }
+
+    pub fn block_light(&self, section_y: i32) -> Option<&NibbleArray> {
+        self.block_light.get(self.light_index(section_y)?)?.as_ref()
+    }
+
+    /// A copy of this column in the form [`crate::WorldEvent::ChunkLoaded`]
+    /// carries, with block entities ordered by position.
+    pub fn to_data(&self) -> ChunkData {
+        let mut block_entities: Vec<BlockEntity> = self.block_entities.values().cloned().collect();
+        block_entities.sort_by_key(|block_entity| {
+            let pos = block_entity.pos;
+            (pos.y, pos.z, pos.x)
+        });
+        ChunkData {
+            height: self.height,
+            sections: self.sections.clone(),
+            heightmaps: self
+                .heightmaps
+                .iter()
+                .map(|heightmap| (heightmap.kind(), heightmap.packed().to_vec()))
+                .collect(),
+            block_entities,
+            light: LightUpdate {
+                sky: self.sky_light.clone(),
+                block: self.block_light.clone(),
+            },
+        }
+    }
+
+    /// Replaces the light of every section the update has data for.
+    pub fn apply_light(&mut self, light: LightUpdate) {
+        for (slot, update) in self.sky_light.iter_mut().zip(light.sky) {
+            if update.is_some() {
+                *slot = update;
+            }
+        }
+        for (slot, update) in self.block_light.iter_mut().zip(light.block) {
+            if update.is_some() {
+                *slot = update;
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use alloc::{vec, vec::Vec};
+
+use super::BitStorage;
+
+/// A fixed-size array of values stored as indices into a palette.
+///
+/// Unlike vanilla, the in-memory container never switches to the global
+/// palette: the palette simply grows and the storage is repacked whenever it
+/// runs out of bits. The wire and disk encodings are handled by their
+/// decoders, which convert into this representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PalettedContainer<T> {
+    palette: Vec<T>,
+    storage: BitStorage,
+}
+
+fn bits_for(palette_len: usize) -> u8 {
+    if palette_len <= 1 {
+        0
+    } else {
+        (usize::BITS - (palette_len - 1).leading_zeros()) as u8
+    }
+}
+
+impl<T: Copy + Eq> PalettedContainer<T> {
+    /// A container of `size` entries that are all `value`.
+    pub fn single(value: T, size: usize) -> Self {
+        Self {
+            palette: vec![value],
+            storage: BitStorage::new(0, size),
+        }
+    }
+
+    /// Builds a container from a palette and the indices into it.
+    ///
+    /// Returns `None` if the palette is empty or any index is out of range.
+    pub fn from_palette(palette: Vec<T>, storage: BitStorage) -> Option<Self> {
+        if palette.is_empty() || storage.iter().any(|i| i as usize >= palette.len()) {
+            return None;
+        }
+        Some(Self { palette, storage })
+    }
+
+    pub fn from_values(values: impl IntoIterator<Item = T>) -> Option<Self> {
+        let mut palette = Vec::new();
+        let indices: Vec<u32> = values
+            .into_iter()
+            .map(|value| match palette.iter().position(|v| *v == value) {
+                Some(index) => index as u32,
+                None => {
+                    palette.push(value);
+                    palette.len() as u32 - 1
+                }
+            })
+            .collect();
+        if palette.is_empty() {
+            return None;
+        }
+
+        let mut storage = BitStorage::new(bits_for(palette.len()), indices.len());
+        for (i, index) in indices.into_iter().enumerate() {
+            storage.set(i, index);
+        }
+        Some(Self { palette, storage })
+    }
+
+    pub fn len(&self) -> usize {
+        self.storage.size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.storage.size() == 0
+    }
+
+    pub fn palette(&self) -> &[T] {
+        &self.palette
+    }
+
+    pub fn storage(&self) -> &BitStorage {
+        &self.storage
+    }
+
+    /// The value of every entry, if they are all the same.
+    pub fn as_single(&self) -> Option<T> {
+        match self.palette.as_slice() {
+            [value] => Some(*value),
+            _ if self.storage.bits() == 0 => Some(self.palette[0]),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, index: usize) -> T {
+        self.palette[self.storage.get(index) as usize]
+    }
+
+    /// Sets an entry, returning the previous value.
+    pub fn set(&mut self, index: usize, value: T) -> T {
+        let previous = self.get(index);
+        if previous == value {
+            return previous;
+        }
+
+        let palette_index = match self.palette.iter().position(|v| *v == value) {
+            Some(i) => i,
+            None => {
+                self.palette.push(value);
+                let bits = bits_for(self.palette.len());
+                if bits > self.storage.bits() {
+                    let mut storage = BitStorage::new(bits, self.storage.size());
+                    for (i, v) in self.storage.iter().enumerate() {
+                        storage.set(i, v);
+                    }
+                    self.storage = storage;
+                }
+                self.palette.len() - 1
+            }
+        };
+        self.storage.set(index, palette_index as u32);
+        previous
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.storage.iter().map(|i| self.palette[i as usize])
+    }
+
+    /// Drops palette entries that are no longer referenced.
+    pub fn compact(&mut self) {
+        if let Some(compacted) = Self::from_values(self.iter()) {
+            *self = compacted;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_grows_palette() {
+        let mut container = PalettedContainer::single(0u16, 4096);
+        assert_eq!(container.storage().bits(), 0);
+        for i in 0..20u16 {
+            assert_eq!(container.set(i as usize * 7, i + 1), 0);
+        }
+        assert_eq!(container.storage().bits(), 5);
+        for i in 0..20u16 {
+            assert_eq!(container.get(i as usize * 7), i + 1);
+        }
+        assert_eq!(container.get(1), 0);
+        assert_eq!(container.as_single(), None);
+    }
+
+    #[test]
+    fn test_compact_drops_unused_entries() {
+        let mut container = PalettedContainer::from_values([1u16, 2, 3, 2]).unwrap();
+        container.set(0, 2);
+        container.set(2, 2);
+        container.compact();
+        assert_eq!(container.palette(), &[2]);
+        assert_eq!(container.as_single(), Some(2));
+    }
+
+    #[test]
+    fn test_from_palette_rejects_out_of_range() {
+        let mut storage = BitStorage::new(4, 64);
+        storage.set(3, 2);
+        assert!(PalettedContainer::from_palette(vec![0u16, 1], storage.clone()).is_none());
+        assert!(PalettedContainer::from_palette(vec![0u16, 1, 5], storage).is_some());
+    }
+}
@@ -0,0 +1,88 @@
+use alloc::{vec, vec::Vec};
+
+/// Fixed-width integers packed into longs, the layout of vanilla's
+/// `SimpleBitStorage`: entries never span two longs, and the unused high bits
+/// of every long are left as zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitStorage {
+    bits: u8,
+    size: usize,
+    data: Vec<u64>,
+}
+
+impl BitStorage {
+    pub fn new(bits: u8, size: usize) -> Self {
+        Self {
+            bits,
+            size,
+            data: vec![0; Self::longs_needed(bits, size)],
+        }
+    }
+
+    /// Wraps packed longs, returning `None` if their count does not match.
+    pub fn from_raw(bits: u8, size: usize, data: Vec<u64>) -> Option<Self> {
+        (bits <= 64 && data.len() == Self::longs_needed(bits, size)).then_some(Self {
+            bits,
+            size,
+            data,
+        })
+    }
+
+    /// Number of longs needed to hold `size` entries of `bits` each.
+    pub fn longs_needed(bits: u8, size: usize) -> usize {
+        if bits == 0 {
+            0
+        } else {
+            size.div_ceil(64 / bits as usize)
+        }
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn raw(&self) -> &[u64] {
+        &self.data
+    }
+
+    fn locate(&self, index: usize) -> (usize, u32) {
+        let per_long = 64 / self.bits as usize;
+        (
+            index / per_long,
+            (index % per_long) as u32 * self.bits as u32,
+        )
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.bits as u32)
+    }
+
+    pub fn get(&self, index: usize) -> u32 {
+        assert!(index < self.size, "index {index} out of bounds");
+        if self.bits == 0 {
+            return 0;
+        }
+        let (long, shift) = self.locate(index);
+        ((self.data[long] >> shift) & self.mask()) as u32
+    }
+
+    pub fn set(&mut self, index: usize, value: u32) {
+        assert!(index < self.size, "index {index} out of bounds");
+        if self.bits == 0 {
+            debug_assert_eq!(value, 0);
+            return;
+        }
+        let (long, shift) = self.locate(index);
+        let mask = self.mask();
+        debug_assert!(value as u64 <= mask);
+        self.data[long] = (self.data[long] & !(mask << shift)) | ((value as u64 & mask) << shift);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.size).map(|i| self.get(i))
+    }
+}
@@ -0,0 +1,333 @@
+//! Moving entity boxes through the blocks of a [`WorldView`], vanilla's
+//! `Entity.collide`.
+//!
+//! Only blocks are collided with; there are no entities or world border in
+//! the world model. Unloaded blocks are passed through as air.
+
+use alloc::vec::Vec;
+use mcre_core::{Aabb, Axis, BlockPos, Vec3d};
+
+use crate::{VoxelShape, WorldView};
+
+/// The part of `movement` an entity with `bounding_box` can make before it
+/// runs into blocks.
+///
+/// An entity that is `on_ground`, or lands during the move, and is stopped
+/// sideways, steps up onto blocks up to `max_up_step` high if that gets it
+/// further.
+pub fn collide(
+    view: &WorldView,
+    bounding_box: Aabb,
+    movement: Vec3d,
+    on_ground: bool,
+    max_up_step: f32,
+) -> Vec3d {
+    if length_squared(movement) == 0.0 {
+        return movement;
+    }
+    let shapes = block_collisions(view, &bounding_box.expand_towards(movement));
+    let collided = collide_with_shapes(movement, bounding_box, &shapes);
+    let x_collided = movement[0] != collided[0];
+    let y_collided = movement[1] != collided[1];
+    let z_collided = movement[2] != collided[2];
+    let landed = y_collided && movement[1] < 0.0;
+    if max_up_step > 0.0 && (on_ground || landed) && (x_collided || z_collided) {
+        let base = if y_collided {
+            bounding_box.moved(Vec3d::new(0.0, collided[1], 0.0))
+        } else {
+            bounding_box
+        };
+        let mut area =
+            base.expand_towards(Vec3d::new(movement[0], max_up_step as f64, movement[2]));
+        if !y_collided {
+            area = area.expand_towards(Vec3d::new(0.0, -1.0e-5_f32 as f64, 0.0));
+        }
+        let shapes = block_collisions(view, &area);
+        for height in step_heights(&base, &shapes, max_up_step, collided[1] as f32) {
+            let step = Vec3d::new(movement[0], height as f64, movement[2]);
+            let stepped = collide_with_shapes(step, base, &shapes);
+            if horizontal_length_squared(stepped) > horizontal_length_squared(collided) {
+                let below = bounding_box.min[1] - base.min[1];
+                return Vec3d::new(stepped[0], stepped[1] - below, stepped[2]);
+            }
+        }
+    }
+    collided
+}
+
+/// The collision shapes, placed in the world, of the blocks an entity in
+/// `area` could touch, vanilla's `BlockCollisions`.
+pub fn block_collisions(view: &WorldView, area: &Aabb) -> Vec<VoxelShape> {
+    colliding_blocks(view, area)
+        .into_iter()
+        .map(|(_, shape)| shape)
+        .collect()
+}
+
+/// Whether no block collision shape overlaps `area`.
+pub fn no_collision(view: &WorldView, area: &Aabb) -> bool {
+    colliding_blocks(view, area).is_empty()
+}
+
+/// [`block_collisions`] along with the block each shape belongs to.
+pub(crate) fn colliding_blocks(view: &WorldView, area: &Aabb) -> Vec<(BlockPos, VoxelShape)> {
+    // one block of margin for shapes taller or wider than their block
+    let low: [i32; 3] = core::array::from_fn(|i| libm::floor(area.min[i] - 1e-7) as i32 - 1);
+    let high: [i32; 3] = core::array::from_fn(|i| libm::floor(area.max[i] + 1e-7) as i32 + 1);
+    let mut shapes = Vec::new();
+    for z in low[2]..=high[2] {
+        for y in low[1]..=high[1] {
+            for x in low[0]..=high[0] {
+                let edges = [x, y, z]
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(i, coord)| *coord == low[*i] || *coord == high[*i])
+                    .count();
+                if edges == 3 {
+                    continue;
+                }
+                let pos = BlockPos::new(x, y, z);
+                let Some(state) = view.get_block(pos) else {
+                    continue;
+                };
+                let shape = state.collision_shape(pos);
+                let reaches_out = match edges {
+                    0 => true,
+                    1 => shape
+                        .boxes()
+                        .iter()
+                        .any(|shape| (0..3).any(|i| shape.min[i] < 0.0 || shape.max[i] > 1.0)),
+                    _ => state.block().name() == "moving_piston",
+                };
+                if !reaches_out {
+                    continue;
+                }
+                let shape = shape.moved(Vec3d::new(x as f64, y as f64, z as f64));
+                if shape.boxes().iter().any(|shape| shape.intersects(area)) {
+                    shapes.push((pos, shape));
+                }
+            }
+        }
+    }
+    shapes
+}
+
+/// Moves the box one axis at a time, up or down first, then along the
+/// horizontal axis it moves the furthest on.
+fn collide_with_shapes(movement: Vec3d, bounding_box: Aabb, shapes: &[VoxelShape]) -> Vec3d {
+    if shapes.is_empty() {
+        return movement;
+    }
+    let order = if movement[0].abs() < movement[2].abs() {
+        [Axis::Y, Axis::Z, Axis::X]
+    } else {
+        [Axis::Y, Axis::X, Axis::Z]
+    };
+    let mut result = Vec3d::new(0.0, 0.0, 0.0);
+    for axis in order {
+        let distance = axis.select(movement);
+        if distance == 0.0 {
+            continue;
+        }
+        let moving = bounding_box.moved(result);
+        let allowed = shapes.iter().fold(distance, |distance, shape| {
+            if distance.abs() < 1e-7 {
+                0.0
+            } else {
+                shape.collide(axis, &moving, distance)
+            }
+        });
+        *axis.select_mut(&mut result) = allowed;
+    }
+    result
+}
+
+/// The heights, up to `max_up_step`, of the block surfaces a step could end
+/// on, lowest first.
+fn step_heights(base: &Aabb, shapes: &[VoxelShape], max_up_step: f32, collided_y: f32) -> Vec<f32> {
+    let mut heights: Vec<f32> = Vec::new();
+    for shape in shapes {
+        for coord in shape.coords(Axis::Y) {
+            let height = (coord - base.min[1]) as f32;
+            if height < 0.0 || height == collided_y {
+                continue;
+            }
+            if height > max_up_step {
+                break;
+            }
+            if !heights.contains(&height) {
+                heights.push(height);
+            }
+        }
+    }
+    heights.sort_by(f32::total_cmp);
+    heights
+}
+
+pub(crate) fn length_squared(vec: Vec3d) -> f64 {
+    vec[0] * vec[0] + vec[1] * vec[1] + vec[2] * vec[2]
+}
+
+pub(crate) fn horizontal_length_squared(vec: Vec3d) -> f64 {
+    vec[0] * vec[0] + vec[2] * vec[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::ChunkPos;
+
+    use super::*;
+    use crate::{BiomeId, ChunkData, ChunkSection, LightUpdate, World, WorldEvent, WorldHeight};
+
+    const HEIGHT: WorldHeight = WorldHeight {
+        min_y: 0,
+        height: 32,
+    };
+
+    /// A chunk with a stone floor whose top is at y = 5.
+    fn world() -> World {
+        let mut world = World::new();
+        world.apply(WorldEvent::ChunkLoaded {
+            pos: ChunkPos::new(0, 0),
+            chunk: ChunkData {
+                height: HEIGHT,
+                sections: (0..HEIGHT.section_count())
+                    .map(|_| ChunkSection::empty(BiomeId(0)))
+                    .collect(),
+                heightmaps: Vec::new(),
+                block_entities: Vec::new(),
+                light: LightUpdate::default(),
+            },
+        });
+        for x in 0..16 {
+            for z in 0..16 {
+                set(&mut world, x, 4, z, "stone");
+            }
+        }
+        world
+    }
+
+    fn set(world: &mut World, x: i32, y: i32, z: i32, state: &str) {
+        world.apply(WorldEvent::BlockSet {
+            pos: BlockPos::new(x, y, z),
+            state: state.parse().unwrap(),
+        });
+    }
+
+    /// A player's box with its feet at the given point.
+    fn player(x: f64, y: f64, z: f64) -> Aabb {
+        Aabb::new(
+            Vec3d::new(x - 0.3, y, z - 0.3),
+            Vec3d::new(x + 0.3, y + 1.8, z + 0.3),
+        )
+    }
+
+    /// Applies one movement per tick, as `Entity.move` would, and returns
+    /// the box after each.
+    fn replay(world: &World, start: Aabb, motions: &[[f64; 3]]) -> Vec<Aabb> {
+        let mut bounding_box = start;
+        let mut on_ground = false;
+        let mut boxes = Vec::new();
+        for [x, y, z] in motions {
+            let movement = Vec3d::new(*x, *y, *z);
+            let moved = collide(&world.view(), bounding_box, movement, on_ground, 0.6);
+            on_ground = moved[1] != movement[1] && movement[1] < 0.0;
+            bounding_box = bounding_box.moved(moved);
+            boxes.push(bounding_box);
+        }
+        boxes
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_falling_onto_the_floor() {
+        let world = world();
+        // a player's vertical motion while falling from rest
+        let motions = [
+            [0.0, -0.0784, 0.0],
+            [0.0, -0.155232, 0.0],
+            [0.0, -0.23052736, 0.0],
+            [0.0, -0.30431682, 0.0],
+            [0.0, -0.37663049, 0.0],
+        ];
+        let boxes = replay(&world, player(8.5, 5.5, 8.5), &motions);
+        assert_close(boxes[2].min[1], 5.5 - 0.0784 - 0.155232 - 0.23052736);
+        assert_close(boxes[3].min[1], 5.0);
+        assert_close(boxes[4].min[1], 5.0);
+    }
+
+    #[test]
+    fn test_walking_into_a_wall() {
+        let mut world = world();
+        for y in 5..7 {
+            set(&mut world, 4, y, 8, "stone");
+            set(&mut world, 4, y, 9, "stone");
+        }
+        // sliding along the wall keeps the motion along it
+        let motions = [[0.4, -0.0784, 0.1]; 4];
+        let boxes = replay(&world, player(2.5, 5.0, 8.5), &motions);
+        assert_close(boxes[0].max[0], 3.2);
+        assert_close(boxes[1].max[0], 3.6);
+        assert_close(boxes[2].max[0], 4.0);
+        assert_close(boxes[3].max[0], 4.0);
+        assert_close(boxes[3].min[2], 8.2 + 0.4);
+        assert_close(boxes[3].min[1], 5.0);
+    }
+
+    #[test]
+    fn test_stepping_up() {
+        let mut world = world();
+        set(
+            &mut world,
+            4,
+            5,
+            8,
+            "stone_slab[type=bottom,waterlogged=false]",
+        );
+        set(&mut world, 4, 5, 12, "stone");
+        let motions = [[0.3, -0.0784, 0.0]; 3];
+        // onto the slab
+        let boxes = replay(&world, player(3.2, 5.0, 8.5), &motions);
+        assert_close(boxes[0].min[1], 5.0);
+        assert_close(boxes[1].min[1], 5.5);
+        assert_close(boxes[2].min[0], 3.8);
+        // but not onto a full block
+        let boxes = replay(&world, player(3.2, 5.0, 12.5), &motions);
+        assert_close(boxes[2].min[1], 5.0);
+        assert_close(boxes[2].max[0], 4.0);
+    }
+
+    #[test]
+    fn test_collision_edges() {
+        let mut world = world();
+        // a fence is too tall to step onto, even from the side
+        set(
+            &mut world,
+            8,
+            5,
+            8,
+            "oak_fence[north=false,east=false,south=false,west=false]",
+        );
+        let start = player(7.0, 5.0, 8.5);
+        let moved = collide(&world.view(), start, Vec3d::new(2.0, 0.0, 0.0), true, 0.6);
+        assert_close(moved[0], 8.375 - 7.3);
+        assert_eq!(moved[1], 0.0);
+        // grass is walked through
+        set(&mut world, 8, 5, 8, "short_grass");
+        let moved = collide(&world.view(), start, Vec3d::new(2.0, 0.0, 0.0), true, 0.6);
+        assert_eq!(moved[0], 2.0);
+        // and unloaded chunks are fallen through
+        let outside = player(-8.5, 5.5, 8.5);
+        let moved = collide(
+            &world.view(),
+            outside,
+            Vec3d::new(0.0, -1.0, 0.0),
+            false,
+            0.6,
+        );
+        assert_eq!(moved[1], -1.0);
+    }
+}
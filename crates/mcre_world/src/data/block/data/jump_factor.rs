@@ -0,0 +1,5 @@
+static VALUES: [f32; 1168usize] =
+    unsafe { core::mem::transmute(*include_bytes!("./jump_factor.bin")) };
+pub(crate) fn get(idx: u16) -> f32 {
+    VALUES[idx as usize]
+}
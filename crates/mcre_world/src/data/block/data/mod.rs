@@ -1,6 +1,11 @@
 pub(crate) mod default_state;
 pub(crate) mod display_name;
 pub(crate) mod fields_present;
+pub(crate) mod friction;
+pub(crate) mod jump_factor;
 pub(crate) mod max_state;
 pub(crate) mod min_state;
 pub(crate) mod name;
+pub(crate) mod speed_factor;
+pub(crate) mod tags;
+pub(crate) mod translation_key;
@@ -0,0 +1,4 @@
+static VALUES: [u32; 1168usize] = unsafe { core::mem::transmute(*include_bytes!("./tags.bin")) };
+pub(crate) fn get(idx: u16) -> u32 {
+    VALUES[idx as usize]
+}
@@ -1,7 +1,9 @@
 mod consts;
 mod data;
+mod tags;
 use crate::{BlockState, FieldKey};
 use serde::{Deserialize, Serialize};
+pub use tags::BlockTag;
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Block(u16);
 impl From<u16> for Block {
@@ -27,6 +29,9 @@ impl Block {
     pub fn display_name(self) -> &'static str {
         data::display_name::get(self.0)
     }
+    pub fn translation_key(self) -> &'static str {
+        data::translation_key::get(self.0)
+    }
     pub fn default_state(self) -> BlockState {
         data::default_state::get(self.0).into()
     }
@@ -36,6 +41,23 @@ impl Block {
     pub fn max_state(self) -> BlockState {
         data::max_state::get(self.0).into()
     }
+    /// How much of its speed an entity keeps each tick on the
+    /// block, vanilla's `getFriction`.
+    pub fn friction(self) -> f32 {
+        data::friction::get(self.0)
+    }
+    /// What walking speed is multiplied by in or on the block.
+    pub fn speed_factor(self) -> f32 {
+        data::speed_factor::get(self.0)
+    }
+    /// What jump strength is multiplied by in or on the block.
+    pub fn jump_factor(self) -> f32 {
+        data::jump_factor::get(self.0)
+    }
+    /// Whether the block is in `tag`.
+    pub fn is_in(self, tag: BlockTag) -> bool {
+        (data::tags::get(self.0) >> (tag as u8)) & 1 == 1
+    }
     pub fn is_field_present(self, field: FieldKey) -> bool {
         let fields_present = data::fields_present::get(self.0);
         ((fields_present >> (field as u8)) & 1) == 1
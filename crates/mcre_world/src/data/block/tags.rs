@@ -0,0 +1,34 @@
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum BlockTag {
+    Beds = 0u8,
+    Climbable = 1u8,
+    FenceGates = 2u8,
+    Fences = 3u8,
+    Leaves = 4u8,
+    Trapdoors = 5u8,
+    Walls = 6u8,
+}
+impl BlockTag {
+    pub const ALL: [BlockTag; 7usize] = [
+        Self::Beds,
+        Self::Climbable,
+        Self::FenceGates,
+        Self::Fences,
+        Self::Leaves,
+        Self::Trapdoors,
+        Self::Walls,
+    ];
+    /// The name of the tag, without its namespace.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Beds => "beds",
+            Self::Climbable => "climbable",
+            Self::FenceGates => "fence_gates",
+            Self::Fences => "fences",
+            Self::Leaves => "leaves",
+            Self::Trapdoors => "trapdoors",
+            Self::Walls => "walls",
+        }
+    }
+}
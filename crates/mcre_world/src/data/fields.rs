@@ -109,6 +109,116 @@ pub enum FieldKey {
     StructureMode = 104u8,
     TestblockMode = 105u8,
 }
+impl FieldKey {
+    pub const ALL: [FieldKey; 106usize] = [
+        Self::IsSnowy,
+        Self::Axis,
+        Self::Stage,
+        Self::Age,
+        Self::IsHanging,
+        Self::IsWaterlogged,
+        Self::Level,
+        Self::Dusted,
+        Self::Distance,
+        Self::IsPersistent,
+        Self::Facing,
+        Self::IsTriggered,
+        Self::Instrument,
+        Self::Note,
+        Self::IsPowered,
+        Self::IsOccupied,
+        Self::Part,
+        Self::IsExtended,
+        Self::IsShort,
+        Self::IsUnstable,
+        Self::IsSlot0Occupied,
+        Self::IsSlot1Occupied,
+        Self::IsSlot2Occupied,
+        Self::IsSlot3Occupied,
+        Self::IsSlot4Occupied,
+        Self::IsSlot5Occupied,
+        Self::SideChain,
+        Self::IsEast,
+        Self::IsNorth,
+        Self::IsSouth,
+        Self::IsUp,
+        Self::IsWest,
+        Self::CreakingHeartState,
+        Self::IsNatural,
+        Self::Power,
+        Self::Moisture,
+        Self::IsLit,
+        Self::Rotation,
+        Self::Hinge,
+        Self::IsOpen,
+        Self::IsAttached,
+        Self::Face,
+        Self::Layers,
+        Self::IsHasRecord,
+        Self::Bites,
+        Self::Delay,
+        Self::IsLocked,
+        Self::IsDown,
+        Self::IsInWall,
+        Self::IsHasBottle0,
+        Self::IsHasBottle1,
+        Self::IsHasBottle2,
+        Self::IsEye,
+        Self::IsDisarmed,
+        Self::IsConditional,
+        Self::IsInverted,
+        Self::IsEnabled,
+        Self::Eggs,
+        Self::Hatch,
+        Self::Hydration,
+        Self::Pickles,
+        Self::Leaves,
+        Self::IsDrag,
+        Self::IsBottom,
+        Self::IsHasBook,
+        Self::Attachment,
+        Self::IsSignalFire,
+        Self::Orientation,
+        Self::HoneyLevel,
+        Self::Charges,
+        Self::Candles,
+        Self::SculkSensorPhase,
+        Self::IsBloom,
+        Self::IsCanSummon,
+        Self::IsShrieking,
+        Self::CopperGolemPose,
+        Self::Thickness,
+        Self::VerticalDirection,
+        Self::IsBerries,
+        Self::FlowerAmount,
+        Self::SegmentAmount,
+        Self::Tilt,
+        Self::IsCracked,
+        Self::IsCrafting,
+        Self::IsOminous,
+        Self::TrialSpawnerState,
+        Self::VaultState,
+        Self::IsTip,
+        Self::RailShape,
+        Self::StairsShape,
+        Self::DoubleblockHalf,
+        Self::Half,
+        Self::PistonType,
+        Self::ChestType,
+        Self::SlabType,
+        Self::RedstoneEast,
+        Self::WallEast,
+        Self::RedstoneNorth,
+        Self::WallNorth,
+        Self::RedstoneSouth,
+        Self::WallSouth,
+        Self::RedstoneWest,
+        Self::WallWest,
+        Self::ComparatorMode,
+        Self::StructureMode,
+        Self::TestblockMode,
+    ];
+}
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum FieldVal {
@@ -1,6 +1,7 @@
 mod block;
 mod fields;
 mod props;
+pub(crate) mod remap;
 mod state;
 pub use block::*;
 pub use fields::*;
@@ -810,6 +810,100 @@ pub enum PropFilter {
     Tip(Box<[bool]>) = 91u8,
 }
 impl PropKey {
+    pub const ALL: [PropKey; 92usize] = [
+        Self::Snowy,
+        Self::Axis,
+        Self::Stage,
+        Self::Age,
+        Self::Hanging,
+        Self::Waterlogged,
+        Self::Level,
+        Self::Dusted,
+        Self::Distance,
+        Self::Persistent,
+        Self::Facing,
+        Self::Triggered,
+        Self::Instrument,
+        Self::Note,
+        Self::Powered,
+        Self::Occupied,
+        Self::Part,
+        Self::Shape,
+        Self::Extended,
+        Self::Half,
+        Self::Short,
+        Self::Type,
+        Self::Unstable,
+        Self::Slot0Occupied,
+        Self::Slot1Occupied,
+        Self::Slot2Occupied,
+        Self::Slot3Occupied,
+        Self::Slot4Occupied,
+        Self::Slot5Occupied,
+        Self::SideChain,
+        Self::East,
+        Self::North,
+        Self::South,
+        Self::Up,
+        Self::West,
+        Self::CreakingHeartState,
+        Self::Natural,
+        Self::Power,
+        Self::Moisture,
+        Self::Lit,
+        Self::Rotation,
+        Self::Hinge,
+        Self::Open,
+        Self::Attached,
+        Self::Face,
+        Self::Layers,
+        Self::HasRecord,
+        Self::Bites,
+        Self::Delay,
+        Self::Locked,
+        Self::Down,
+        Self::InWall,
+        Self::HasBottle0,
+        Self::HasBottle1,
+        Self::HasBottle2,
+        Self::Eye,
+        Self::Disarmed,
+        Self::Conditional,
+        Self::Mode,
+        Self::Inverted,
+        Self::Enabled,
+        Self::Eggs,
+        Self::Hatch,
+        Self::Hydration,
+        Self::Pickles,
+        Self::Leaves,
+        Self::Drag,
+        Self::Bottom,
+        Self::HasBook,
+        Self::Attachment,
+        Self::SignalFire,
+        Self::Orientation,
+        Self::HoneyLevel,
+        Self::Charges,
+        Self::Candles,
+        Self::SculkSensorPhase,
+        Self::Bloom,
+        Self::CanSummon,
+        Self::Shrieking,
+        Self::CopperGolemPose,
+        Self::Thickness,
+        Self::VerticalDirection,
+        Self::Berries,
+        Self::FlowerAmount,
+        Self::SegmentAmount,
+        Self::Tilt,
+        Self::Cracked,
+        Self::Crafting,
+        Self::Ominous,
+        Self::TrialSpawnerState,
+        Self::VaultState,
+        Self::Tip,
+    ];
     pub fn as_str(self) -> &'static str {
         match self {
             Self::Snowy => "snowy",
@@ -1890,3 +1984,101 @@ impl FromStr for PropVal {
         Self::parse_with_key(key, parts[1]).ok_or(())
     }
 }
+impl core::fmt::Display for PropVal {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Snowy(v) => write!(f, "snowy={}", v),
+            Self::Axis(v) => write!(f, "axis={}", v.as_str()),
+            Self::Stage(v) => write!(f, "stage={}", v),
+            Self::Age(v) => write!(f, "age={}", v),
+            Self::Hanging(v) => write!(f, "hanging={}", v),
+            Self::Waterlogged(v) => write!(f, "waterlogged={}", v),
+            Self::Level(v) => write!(f, "level={}", v),
+            Self::Dusted(v) => write!(f, "dusted={}", v),
+            Self::Distance(v) => write!(f, "distance={}", v),
+            Self::Persistent(v) => write!(f, "persistent={}", v),
+            Self::Facing(v) => write!(f, "facing={}", v.as_str()),
+            Self::Triggered(v) => write!(f, "triggered={}", v),
+            Self::Instrument(v) => write!(f, "instrument={}", v.as_str()),
+            Self::Note(v) => write!(f, "note={}", v),
+            Self::Powered(v) => write!(f, "powered={}", v),
+            Self::Occupied(v) => write!(f, "occupied={}", v),
+            Self::Part(v) => write!(f, "part={}", v.as_str()),
+            Self::Shape(v) => write!(f, "shape={}", v.as_str()),
+            Self::Extended(v) => write!(f, "extended={}", v),
+            Self::Half(v) => write!(f, "half={}", v.as_str()),
+            Self::Short(v) => write!(f, "short={}", v),
+            Self::Type(v) => write!(f, "type={}", v.as_str()),
+            Self::Unstable(v) => write!(f, "unstable={}", v),
+            Self::Slot0Occupied(v) => write!(f, "slot_0_occupied={}", v),
+            Self::Slot1Occupied(v) => write!(f, "slot_1_occupied={}", v),
+            Self::Slot2Occupied(v) => write!(f, "slot_2_occupied={}", v),
+            Self::Slot3Occupied(v) => write!(f, "slot_3_occupied={}", v),
+            Self::Slot4Occupied(v) => write!(f, "slot_4_occupied={}", v),
+            Self::Slot5Occupied(v) => write!(f, "slot_5_occupied={}", v),
+            Self::SideChain(v) => write!(f, "side_chain={}", v.as_str()),
+            Self::East(v) => write!(f, "east={}", v.as_str()),
+            Self::North(v) => write!(f, "north={}", v.as_str()),
+            Self::South(v) => write!(f, "south={}", v.as_str()),
+            Self::Up(v) => write!(f, "up={}", v),
+            Self::West(v) => write!(f, "west={}", v.as_str()),
+            Self::CreakingHeartState(v) => write!(f, "creaking_heart_state={}", v.as_str()),
+            Self::Natural(v) => write!(f, "natural={}", v),
+            Self::Power(v) => write!(f, "power={}", v),
+            Self::Moisture(v) => write!(f, "moisture={}", v),
+            Self::Lit(v) => write!(f, "lit={}", v),
+            Self::Rotation(v) => write!(f, "rotation={}", v),
+            Self::Hinge(v) => write!(f, "hinge={}", v.as_str()),
+            Self::Open(v) => write!(f, "open={}", v),
+            Self::Attached(v) => write!(f, "attached={}", v),
+            Self::Face(v) => write!(f, "face={}", v.as_str()),
+            Self::Layers(v) => write!(f, "layers={}", v),
+            Self::HasRecord(v) => write!(f, "has_record={}", v),
+            Self::Bites(v) => write!(f, "bites={}", v),
+            Self::Delay(v) => write!(f, "delay={}", v),
+            Self::Locked(v) => write!(f, "locked={}", v),
+            Self::Down(v) => write!(f, "down={}", v),
+            Self::InWall(v) => write!(f, "in_wall={}", v),
+            Self::HasBottle0(v) => write!(f, "has_bottle_0={}", v),
+            Self::HasBottle1(v) => write!(f, "has_bottle_1={}", v),
+            Self::HasBottle2(v) => write!(f, "has_bottle_2={}", v),
+            Self::Eye(v) => write!(f, "eye={}", v),
+            Self::Disarmed(v) => write!(f, "disarmed={}", v),
+            Self::Conditional(v) => write!(f, "conditional={}", v),
+            Self::Mode(v) => write!(f, "mode={}", v.as_str()),
+            Self::Inverted(v) => write!(f, "inverted={}", v),
+            Self::Enabled(v) => write!(f, "enabled={}", v),
+            Self::Eggs(v) => write!(f, "eggs={}", v),
+            Self::Hatch(v) => write!(f, "hatch={}", v),
+            Self::Hydration(v) => write!(f, "hydration={}", v),
+            Self::Pickles(v) => write!(f, "pickles={}", v),
+            Self::Leaves(v) => write!(f, "leaves={}", v.as_str()),
+            Self::Drag(v) => write!(f, "drag={}", v),
+            Self::Bottom(v) => write!(f, "bottom={}", v),
+            Self::HasBook(v) => write!(f, "has_book={}", v),
+            Self::Attachment(v) => write!(f, "attachment={}", v.as_str()),
+            Self::SignalFire(v) => write!(f, "signal_fire={}", v),
+            Self::Orientation(v) => write!(f, "orientation={}", v.as_str()),
+            Self::HoneyLevel(v) => write!(f, "honey_level={}", v),
+            Self::Charges(v) => write!(f, "charges={}", v),
+            Self::Candles(v) => write!(f, "candles={}", v),
+            Self::SculkSensorPhase(v) => write!(f, "sculk_sensor_phase={}", v.as_str()),
+            Self::Bloom(v) => write!(f, "bloom={}", v),
+            Self::CanSummon(v) => write!(f, "can_summon={}", v),
+            Self::Shrieking(v) => write!(f, "shrieking={}", v),
+            Self::CopperGolemPose(v) => write!(f, "copper_golem_pose={}", v.as_str()),
+            Self::Thickness(v) => write!(f, "thickness={}", v.as_str()),
+            Self::VerticalDirection(v) => write!(f, "vertical_direction={}", v.as_str()),
+            Self::Berries(v) => write!(f, "berries={}", v),
+            Self::FlowerAmount(v) => write!(f, "flower_amount={}", v),
+            Self::SegmentAmount(v) => write!(f, "segment_amount={}", v),
+            Self::Tilt(v) => write!(f, "tilt={}", v.as_str()),
+            Self::Cracked(v) => write!(f, "cracked={}", v),
+            Self::Crafting(v) => write!(f, "crafting={}", v),
+            Self::Ominous(v) => write!(f, "ominous={}", v),
+            Self::TrialSpawnerState(v) => write!(f, "trial_spawner_state={}", v.as_str()),
+            Self::VaultState(v) => write!(f, "vault_state={}", v.as_str()),
+            Self::Tip(v) => write!(f, "tip={}", v),
+        }
+    }
+}
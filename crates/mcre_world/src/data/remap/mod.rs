@@ -0,0 +1,2 @@
+use crate::StateIdTable;
+pub(crate) static TABLES: &[StateIdTable] = &[];
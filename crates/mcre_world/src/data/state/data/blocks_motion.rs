@@ -0,0 +1,7 @@
+static VALUES: [u8; 3710usize] = *include_bytes!("./blocks_motion.bin");
+pub(crate) fn get(idx: u16) -> bool {
+    let byte_pos = idx / 8;
+    let bit_pos = idx % 8;
+    let byte = VALUES[byte_pos as usize];
+    ((byte >> bit_pos) & 1) == 1
+}
@@ -0,0 +1,4 @@
+static VALUES: [u8; 29673usize] = *include_bytes!("./fluid_state.bin");
+pub(crate) fn get(idx: u16) -> u8 {
+    VALUES[idx as usize]
+}
@@ -0,0 +1,67 @@
+static BOXES: [[f64; 6]; 1757usize] =
+    unsafe { core::mem::transmute(*include_bytes!("./shapes.bin")) };
+static STARTS: [u16; 646usize] = [
+    0u16, 0u16, 1u16, 2u16, 3u16, 4u16, 5u16, 6u16, 7u16, 8u16, 9u16, 10u16, 11u16, 12u16, 13u16,
+    14u16, 15u16, 17u16, 19u16, 21u16, 23u16, 25u16, 27u16, 28u16, 29u16, 32u16, 35u16, 38u16,
+    41u16, 42u16, 43u16, 44u16, 45u16, 46u16, 47u16, 49u16, 52u16, 55u16, 57u16, 59u16, 61u16,
+    64u16, 67u16, 69u16, 71u16, 73u16, 76u16, 79u16, 81u16, 83u16, 85u16, 88u16, 91u16, 93u16,
+    95u16, 97u16, 100u16, 103u16, 105u16, 108u16, 111u16, 113u16, 116u16, 119u16, 121u16, 124u16,
+    127u16, 128u16, 129u16, 130u16, 131u16, 132u16, 141u16, 149u16, 156u16, 164u16, 171u16, 177u16,
+    184u16, 190u16, 195u16, 203u16, 210u16, 216u16, 223u16, 229u16, 234u16, 240u16, 245u16, 249u16,
+    256u16, 262u16, 267u16, 273u16, 278u16, 282u16, 287u16, 291u16, 294u16, 302u16, 309u16, 315u16,
+    322u16, 328u16, 333u16, 339u16, 344u16, 348u16, 355u16, 361u16, 366u16, 372u16, 377u16, 381u16,
+    386u16, 390u16, 393u16, 399u16, 404u16, 408u16, 413u16, 417u16, 420u16, 424u16, 427u16, 429u16,
+    436u16, 442u16, 447u16, 453u16, 458u16, 462u16, 467u16, 471u16, 474u16, 480u16, 485u16, 489u16,
+    494u16, 498u16, 501u16, 505u16, 508u16, 510u16, 515u16, 519u16, 522u16, 526u16, 529u16, 531u16,
+    534u16, 536u16, 537u16, 538u16, 539u16, 540u16, 541u16, 542u16, 543u16, 544u16, 545u16, 546u16,
+    547u16, 548u16, 549u16, 550u16, 551u16, 552u16, 554u16, 556u16, 557u16, 558u16, 559u16, 560u16,
+    561u16, 562u16, 563u16, 564u16, 565u16, 566u16, 567u16, 568u16, 569u16, 570u16, 571u16, 572u16,
+    573u16, 574u16, 575u16, 576u16, 577u16, 578u16, 579u16, 580u16, 581u16, 582u16, 583u16, 584u16,
+    589u16, 594u16, 598u16, 602u16, 606u16, 610u16, 613u16, 616u16, 620u16, 624u16, 627u16, 630u16,
+    633u16, 636u16, 638u16, 640u16, 644u16, 648u16, 651u16, 654u16, 657u16, 660u16, 662u16, 664u16,
+    667u16, 670u16, 672u16, 674u16, 676u16, 678u16, 679u16, 680u16, 681u16, 682u16, 683u16, 684u16,
+    685u16, 686u16, 687u16, 688u16, 689u16, 690u16, 691u16, 696u16, 700u16, 704u16, 707u16, 711u16,
+    714u16, 717u16, 719u16, 723u16, 726u16, 729u16, 731u16, 734u16, 736u16, 738u16, 739u16, 740u16,
+    741u16, 742u16, 743u16, 744u16, 745u16, 746u16, 747u16, 748u16, 749u16, 750u16, 751u16, 752u16,
+    757u16, 761u16, 765u16, 768u16, 772u16, 775u16, 778u16, 780u16, 784u16, 787u16, 790u16, 792u16,
+    795u16, 797u16, 799u16, 800u16, 804u16, 807u16, 810u16, 812u16, 815u16, 817u16, 819u16, 820u16,
+    823u16, 825u16, 827u16, 828u16, 830u16, 831u16, 832u16, 838u16, 843u16, 848u16, 852u16, 857u16,
+    861u16, 865u16, 868u16, 873u16, 877u16, 881u16, 884u16, 888u16, 891u16, 894u16, 896u16, 901u16,
+    905u16, 909u16, 912u16, 916u16, 919u16, 922u16, 924u16, 928u16, 931u16, 934u16, 936u16, 939u16,
+    941u16, 943u16, 944u16, 945u16, 946u16, 947u16, 948u16, 949u16, 950u16, 952u16, 954u16, 956u16,
+    957u16, 958u16, 959u16, 961u16, 963u16, 966u16, 969u16, 972u16, 973u16, 974u16, 976u16, 978u16,
+    980u16, 982u16, 985u16, 988u16, 989u16, 991u16, 993u16, 995u16, 997u16, 1000u16, 1003u16,
+    1006u16, 1007u16, 1008u16, 1010u16, 1012u16, 1014u16, 1017u16, 1020u16, 1024u16, 1028u16,
+    1032u16, 1034u16, 1036u16, 1039u16, 1042u16, 1045u16, 1048u16, 1052u16, 1056u16, 1058u16,
+    1061u16, 1064u16, 1066u16, 1069u16, 1072u16, 1073u16, 1075u16, 1077u16, 1080u16, 1084u16,
+    1088u16, 1090u16, 1093u16, 1096u16, 1099u16, 1103u16, 1107u16, 1109u16, 1112u16, 1115u16,
+    1117u16, 1119u16, 1122u16, 1125u16, 1128u16, 1129u16, 1130u16, 1132u16, 1134u16, 1136u16,
+    1139u16, 1142u16, 1146u16, 1150u16, 1154u16, 1156u16, 1158u16, 1161u16, 1164u16, 1167u16,
+    1170u16, 1174u16, 1178u16, 1180u16, 1183u16, 1186u16, 1189u16, 1192u16, 1196u16, 1200u16,
+    1204u16, 1206u16, 1208u16, 1211u16, 1214u16, 1217u16, 1221u16, 1225u16, 1230u16, 1235u16,
+    1240u16, 1243u16, 1246u16, 1250u16, 1254u16, 1258u16, 1262u16, 1267u16, 1272u16, 1275u16,
+    1279u16, 1283u16, 1286u16, 1290u16, 1294u16, 1296u16, 1299u16, 1302u16, 1306u16, 1311u16,
+    1316u16, 1319u16, 1323u16, 1327u16, 1331u16, 1336u16, 1341u16, 1344u16, 1348u16, 1352u16,
+    1354u16, 1357u16, 1360u16, 1361u16, 1363u16, 1365u16, 1368u16, 1372u16, 1376u16, 1378u16,
+    1381u16, 1384u16, 1387u16, 1391u16, 1395u16, 1397u16, 1400u16, 1403u16, 1406u16, 1410u16,
+    1414u16, 1416u16, 1419u16, 1422u16, 1426u16, 1431u16, 1436u16, 1439u16, 1443u16, 1447u16,
+    1451u16, 1456u16, 1461u16, 1464u16, 1468u16, 1472u16, 1475u16, 1479u16, 1483u16, 1485u16,
+    1488u16, 1491u16, 1495u16, 1500u16, 1505u16, 1508u16, 1512u16, 1516u16, 1520u16, 1525u16,
+    1530u16, 1533u16, 1537u16, 1541u16, 1542u16, 1543u16, 1545u16, 1558u16, 1559u16, 1561u16,
+    1562u16, 1563u16, 1564u16, 1565u16, 1566u16, 1567u16, 1568u16, 1569u16, 1570u16, 1571u16,
+    1572u16, 1573u16, 1574u16, 1575u16, 1576u16, 1577u16, 1578u16, 1579u16, 1580u16, 1581u16,
+    1582u16, 1583u16, 1584u16, 1585u16, 1589u16, 1593u16, 1600u16, 1607u16, 1614u16, 1621u16,
+    1628u16, 1629u16, 1630u16, 1631u16, 1632u16, 1633u16, 1634u16, 1635u16, 1636u16, 1637u16,
+    1638u16, 1639u16, 1640u16, 1641u16, 1642u16, 1643u16, 1644u16, 1645u16, 1646u16, 1647u16,
+    1648u16, 1649u16, 1650u16, 1651u16, 1656u16, 1659u16, 1661u16, 1663u16, 1665u16, 1666u16,
+    1667u16, 1668u16, 1669u16, 1670u16, 1675u16, 1680u16, 1685u16, 1690u16, 1695u16, 1700u16,
+    1705u16, 1710u16, 1715u16, 1716u16, 1717u16, 1718u16, 1720u16, 1721u16, 1722u16, 1723u16,
+    1724u16, 1725u16, 1726u16, 1727u16, 1728u16, 1729u16, 1730u16, 1731u16, 1732u16, 1733u16,
+    1734u16, 1735u16, 1736u16, 1737u16, 1738u16, 1739u16, 1740u16, 1741u16, 1742u16, 1743u16,
+    1744u16, 1745u16, 1746u16, 1747u16, 1749u16, 1751u16, 1752u16, 1754u16, 1755u16, 1756u16,
+    1757u16,
+];
+pub(crate) fn get(shape: u16) -> &'static [[f64; 6]] {
+    let shape = shape as usize;
+    &BOXES[STARTS[shape] as usize..STARTS[shape + 1] as usize]
+}
@@ -2,7 +2,7 @@ mod data;
 mod enums;
 use crate::{Block, FieldKey, FieldVal, PropKey, PropVal};
 pub use enums::*;
-use mcre_core::OffsetType;
+use mcre_core::{FluidState, OffsetType};
 use serde::{Deserialize, Serialize};
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct BlockState(u16);
@@ -60,6 +60,11 @@ impl BlockState {
     pub fn destroy_speed(self) -> f32 {
         data::destroy_speed::get(self.0)
     }
+    /// Whether entities are stopped by the state, vanilla's
+    /// `blocksMotion`.
+    pub fn blocks_motion(self) -> bool {
+        data::blocks_motion::get(self.0)
+    }
     pub fn offset_type(self) -> OffsetType {
         unsafe { core::mem::transmute::<u8, OffsetType>(data::offset_type::get(self.0)) }
     }
@@ -69,6 +74,21 @@ impl BlockState {
     pub fn max_vertical_offset(self) -> f32 {
         data::max_vertical_offset::get(self.0)
     }
+    pub fn fluid_state(self) -> FluidState {
+        FluidState::from_u8(data::fluid_state::get(self.0))
+    }
+    /// The boxes of the outline shape, before the random offset.
+    pub(crate) fn outline_boxes(self) -> &'static [[f64; 6]] {
+        data::shapes::get(data::outline_shape::get(self.0))
+    }
+    /// The boxes of the collision shape, before the random offset.
+    pub(crate) fn collision_boxes(self) -> &'static [[f64; 6]] {
+        data::shapes::get(data::collision_shape::get(self.0))
+    }
+    /// The boxes of the shape that blocks light on its faces.
+    pub(crate) fn occlusion_boxes(self) -> &'static [[f64; 6]] {
+        data::shapes::get(data::occlusion_shape::get(self.0))
+    }
     pub fn get_field(self, field: FieldKey) -> Option<FieldVal> {
         if !self.block().is_field_present(field) {
             return None;
@@ -994,7 +1014,11 @@ impl Iterator for BlockStateIter {
         }
     }
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = (self.end - self.current) as usize;
+        let remaining = if self.current > self.end {
+            0
+        } else {
+            (self.end - self.current + 1) as usize
+        };
         (remaining, Some(remaining))
     }
 }
@@ -0,0 +1,288 @@
+//! The shape of fluid surfaces, after vanilla's `LiquidBlockRenderer` and
+//! `FlowingFluid.getFlow`.
+//!
+//! Unloaded blocks are read as air, as on the edge of the loaded world.
+
+use mcre_core::{BlockPos, Direction, Fluid, FluidState, Vec3f};
+
+use crate::{Block, BlockState, WorldView};
+
+/// The surface height of a fluid at the four top corners of its block, from
+/// 0 to 1.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FluidCorners {
+    pub north_east: f32,
+    pub north_west: f32,
+    pub south_east: f32,
+    pub south_west: f32,
+}
+
+/// The corner heights of the fluid at `pos`, or `None` if the block holds no
+/// fluid.
+pub fn fluid_corners(view: &WorldView, pos: BlockPos) -> Option<FluidCorners> {
+    let fluid = block_at(view, pos).fluid_state().fluid;
+    if fluid == Fluid::Empty {
+        return None;
+    }
+    let height = fluid_height(view, fluid, pos);
+    if height >= 1.0 {
+        return Some(FluidCorners {
+            north_east: 1.0,
+            north_west: 1.0,
+            south_east: 1.0,
+            south_west: 1.0,
+        });
+    }
+    let side = |direction| fluid_height(view, fluid, pos.relative(direction));
+    let [north, south, east, west] = [
+        Direction::North,
+        Direction::South,
+        Direction::East,
+        Direction::West,
+    ]
+    .map(side);
+    let corner = |a: f32, b: f32, x: Direction, z: Direction| {
+        average_height(view, fluid, height, a, b, pos.relative(z).relative(x))
+    };
+    Some(FluidCorners {
+        north_east: corner(north, east, Direction::East, Direction::North),
+        north_west: corner(north, west, Direction::West, Direction::North),
+        south_east: corner(south, east, Direction::East, Direction::South),
+        south_west: corner(south, west, Direction::West, Direction::South),
+    })
+}
+
+/// The normalized direction the fluid at `pos` pushes entities and scrolls
+/// its texture, or zero where it is still.
+pub fn fluid_flow(view: &WorldView, pos: BlockPos) -> Vec3f {
+    let state = block_at(view, pos).fluid_state();
+    if state.is_empty() {
+        return Vec3f::new(0.0, 0.0, 0.0);
+    }
+    let affects_flow = |other: FluidState| other.is_empty() || other.fluid.is_same(state.fluid);
+    let (mut dx, mut dz) = (0.0, 0.0);
+    for direction in HORIZONTAL {
+        let neighbour = pos.relative(direction);
+        let neighbour_block = block_at(view, neighbour);
+        let fluid = neighbour_block.fluid_state();
+        if !affects_flow(fluid) {
+            continue;
+        }
+        let mut diff = 0.0;
+        if fluid.own_height() == 0.0 {
+            if !neighbour_block.blocks_motion() {
+                let below = block_at(view, neighbour.relative(Direction::Down)).fluid_state();
+                if affects_flow(below) && below.own_height() > 0.0 {
+                    diff = state.own_height() - (below.own_height() - 0.8888889);
+                }
+            }
+        } else {
+            diff = state.own_height() - fluid.own_height();
+        }
+        let step = direction.step();
+        dx += step[0] as f32 * diff;
+        dz += step[2] as f32 * diff;
+    }
+    let mut flow = [dx, 0.0, dz];
+    if state.falling {
+        let wall = HORIZONTAL.into_iter().any(|direction| {
+            let neighbour = pos.relative(direction);
+            is_solid_face(view, state.fluid, neighbour)
+                || is_solid_face(view, state.fluid, neighbour.relative(Direction::Up))
+        });
+        if wall {
+            flow = normalize(flow);
+            flow[1] -= 6.0;
+        }
+    }
+    let [x, y, z] = normalize(flow);
+    Vec3f::new(x, y, z)
+}
+
+const HORIZONTAL: [Direction; 4] = [
+    Direction::North,
+    Direction::East,
+    Direction::South,
+    Direction::West,
+];
+
+fn block_at(view: &WorldView, pos: BlockPos) -> BlockState {
+    view.get_block(pos).unwrap_or(Block::AIR.default_state())
+}
+
+/// The height `fluid` reaches at `pos`: full when more of it lies above,
+/// 0 for an open block and -1 for a solid one.
+fn fluid_height(view: &WorldView, fluid: Fluid, pos: BlockPos) -> f32 {
+    let state = block_at(view, pos);
+    let fluid_state = state.fluid_state();
+    if fluid_state.fluid.is_same(fluid) {
+        let above = block_at(view, pos.relative(Direction::Up)).fluid_state();
+        if above.fluid.is_same(fluid) {
+            1.0
+        } else {
+            fluid_state.own_height()
+        }
+    } else if !state.blocks_motion() {
+        0.0
+    } else {
+        -1.0
+    }
+}
+
+/// The height of a corner shared by the block, its two sides `a` and `b`
+/// and the diagonal block at `corner`, weighting near-full heights tenfold.
+fn average_height(
+    view: &WorldView,
+    fluid: Fluid,
+    height: f32,
+    a: f32,
+    b: f32,
+    corner: BlockPos,
+) -> f32 {
+    if a >= 1.0 || b >= 1.0 {
+        return 1.0;
+    }
+    let (mut sum, mut weight) = (0.0, 0.0);
+    let mut add = |height: f32| {
+        if height >= 0.8 {
+            sum += height * 10.0;
+            weight += 10.0;
+        } else if height >= 0.0 {
+            sum += height;
+            weight += 1.0;
+        }
+    };
+    if a > 0.0 || b > 0.0 {
+        let diagonal = fluid_height(view, fluid, corner);
+        if diagonal >= 1.0 {
+            return 1.0;
+        }
+        add(diagonal);
+    }
+    add(height);
+    add(b);
+    add(a);
+    sum / weight
+}
+
+/// Whether the block at `pos` walls in falling `fluid`.
+fn is_solid_face(view: &WorldView, fluid: Fluid, pos: BlockPos) -> bool {
+    let state = block_at(view, pos);
+    if state.fluid_state().fluid.is_same(fluid) {
+        return false;
+    }
+    // the tables carry no face shapes, so full opaque blocks stand in for
+    // sturdy faces
+    !matches!(state.block().name(), "ice" | "frosted_ice") && state.solid_render()
+}
+
+fn normalize([x, y, z]: [f32; 3]) -> [f32; 3] {
+    let length = libm::sqrtf(x * x + y * y + z * z);
+    if length < 1e-5 {
+        [0.0; 3]
+    } else {
+        [x / length, y / length, z / length]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use mcre_core::ChunkPos;
+
+    use super::*;
+    use crate::{BiomeId, ChunkData, ChunkSection, LightUpdate, World, WorldEvent, WorldHeight};
+
+    const HEIGHT: WorldHeight = WorldHeight {
+        min_y: 0,
+        height: 32,
+    };
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.apply(WorldEvent::ChunkLoaded {
+            pos: ChunkPos::new(0, 0),
+            chunk: ChunkData {
+                height: HEIGHT,
+                sections: (0..HEIGHT.section_count())
+                    .map(|_| ChunkSection::empty(BiomeId(0)))
+                    .collect(),
+                heightmaps: Vec::new(),
+                block_entities: Vec::new(),
+                light: LightUpdate::default(),
+            },
+        });
+        world
+    }
+
+    fn set(world: &mut World, x: i32, y: i32, z: i32, state: &str) {
+        world.apply(WorldEvent::BlockSet {
+            pos: BlockPos::new(x, y, z),
+            state: state.parse().unwrap(),
+        });
+    }
+
+    fn fluid(s: &str) -> FluidState {
+        s.parse::<BlockState>().unwrap().fluid_state()
+    }
+
+    #[test]
+    fn test_fluid_states() {
+        let source = fluid("water[level=0]");
+        assert_eq!(source.fluid, Fluid::Water);
+        assert_eq!(source.amount, 8);
+        let flowing = fluid("lava[level=3]");
+        assert_eq!(flowing.fluid, Fluid::FlowingLava);
+        assert_eq!(flowing.amount, 5);
+        assert!(!flowing.falling);
+        assert!(fluid("water[level=9]").falling);
+        assert!(fluid("oak_slab[type=bottom,waterlogged=true]").is_source());
+        assert!(fluid("oak_slab[type=bottom,waterlogged=false]").is_empty());
+        assert!(fluid("kelp[age=3]").fluid.is_water());
+        assert!(fluid("stone").is_empty());
+    }
+
+    #[test]
+    fn test_fluid_corners() {
+        let mut world = world();
+        set(&mut world, 8, 8, 8, "water[level=0]");
+        let corners = fluid_corners(&world.view(), BlockPos::new(8, 8, 8)).unwrap();
+        // a lone source next to open air on every side
+        let expected = (8.0 / 9.0 * 10.0) / 12.0;
+        assert!((corners.north_east - expected).abs() < 1e-6);
+        assert_eq!(corners.north_east, corners.south_west);
+
+        set(&mut world, 8, 9, 8, "water[level=0]");
+        let corners = fluid_corners(&world.view(), BlockPos::new(8, 8, 8)).unwrap();
+        assert_eq!(corners.north_west, 1.0);
+
+        // flowing water next to its source slopes down away from it
+        set(&mut world, 9, 9, 8, "water[level=2]");
+        let corners = fluid_corners(&world.view(), BlockPos::new(9, 9, 8)).unwrap();
+        assert!(corners.north_west > corners.north_east);
+        assert!(fluid_corners(&world.view(), BlockPos::new(0, 0, 0)).is_none());
+    }
+
+    #[test]
+    fn test_fluid_flow() {
+        let mut world = world();
+        set(&mut world, 8, 8, 8, "water[level=0]");
+        set(&mut world, 9, 8, 8, "water[level=1]");
+        set(&mut world, 10, 8, 8, "water[level=2]");
+        for z in [7, 9] {
+            for x in 8..=10 {
+                set(&mut world, x, 8, z, "stone");
+            }
+        }
+        set(&mut world, 11, 8, 8, "stone");
+        let flow = fluid_flow(&world.view(), BlockPos::new(9, 8, 8));
+        assert!((flow[0] - 1.0).abs() < 1e-6 && flow[2] == 0.0);
+
+        set(&mut world, 8, 8, 8, "stone");
+        set(&mut world, 10, 8, 8, "stone");
+        set(&mut world, 9, 8, 8, "water[level=8]");
+        let flow = fluid_flow(&world.view(), BlockPos::new(9, 8, 8));
+        assert_eq!(*flow, [0.0, -1.0, 0.0]);
+        assert_eq!(*fluid_flow(&world.view(), BlockPos::new(0, 0, 0)), [0.0; 3]);
+    }
+}
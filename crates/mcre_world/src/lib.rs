@@ -2,5 +2,25 @@
 
 extern crate alloc;
 
+mod blockstate;
+mod chunk;
+mod collision;
 pub mod data;
+mod fluid;
+mod light;
+mod player;
+mod raycast;
+mod shape;
+mod state_ids;
+mod world;
+
+pub use chunk::*;
+pub use collision::*;
 pub use data::*;
+pub use fluid::*;
+pub use light::*;
+pub use player::*;
+pub use raycast::*;
+pub use shape::VoxelShape;
+pub use state_ids::*;
+pub use world::*;
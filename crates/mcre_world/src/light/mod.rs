@@ -0,0 +1,805 @@
+//! Client-side block and sky light, after vanilla's `LevelLightEngine`.
+//!
+//! The engine keeps no light of its own: it watches the world's events as a
+//! [`WorldConsumer`] and, when [`LightEngine::run`] is called, reads the
+//! current light from the world and returns the sections it changed as
+//! [`WorldEvent::LightUpdated`]s to apply back. Chunks that arrive without
+//! any light, as when loading a save the server never lit, are lit from
+//! scratch; [`WorldEvent::BlockSet`]s are re-lit incrementally, removing the
+//! old light before spreading the new.
+
+mod occlusion;
+
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use hashbrown::{HashMap, HashSet};
+use mcre_core::{BlockPos, ChunkPos, Direction};
+use rustc_hash::FxBuildHasher;
+
+use crate::{
+    Block, BlockState, Chunk, LightUpdate, NibbleArray, WorldConsumer, WorldEvent, WorldView,
+};
+use occlusion::shape_occludes;
+
+/// One bit per [`Direction`], by its ordinal.
+type Directions = u8;
+
+const ALL_DIRECTIONS: Directions = 0b11_1111;
+
+/// Sky sources spread everywhere but up, where everything is a source.
+const SKY_SOURCE_DIRECTIONS: Directions = ALL_DIRECTIONS & !only(Direction::Up);
+
+const fn only(direction: Direction) -> Directions {
+    1 << direction as u8
+}
+
+const fn all_but(direction: Direction) -> Directions {
+    ALL_DIRECTIONS & !only(direction)
+}
+
+/// Relights the world as blocks change and unlit chunks load.
+///
+/// ```ignore
+/// world.apply(event.clone());
+/// engine.on_event(&event, &world.view());
+/// for update in engine.run(&world.view()) {
+///     world.apply(update);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LightEngine {
+    has_sky: bool,
+    pending_chunks: HashSet<ChunkPos, FxBuildHasher>,
+    pending_blocks: Vec<BlockPos>,
+    /// The lowest sky source of each column of each loaded chunk, as of the
+    /// light the world last held. See [`sky_sources`].
+    sky_sources: HashMap<ChunkPos, Vec<i32>, FxBuildHasher>,
+}
+
+impl LightEngine {
+    /// An engine for a dimension with or without sky light, per its
+    /// `dimension_type`'s `has_skylight`.
+    pub fn new(has_sky: bool) -> Self {
+        Self {
+            has_sky,
+            ..Self::default()
+        }
+    }
+
+    pub fn has_pending(&self) -> bool {
+        !self.pending_chunks.is_empty() || !self.pending_blocks.is_empty()
+    }
+
+    /// Lights the chunks and blocks changed since the last run, returning the
+    /// changed sections grouped by chunk.
+    pub fn run(&mut self, view: &WorldView) -> Vec<WorldEvent> {
+        let mut block = Propagator::new(*view, false);
+        let mut sky = self.has_sky.then(|| Propagator::new(*view, true));
+
+        let mut chunks: Vec<ChunkPos> = self
+            .pending_chunks
+            .drain()
+            .filter(|pos| view.get_chunk(*pos).is_some())
+            .collect();
+        chunks.sort_by_key(|pos| (pos.x, pos.z));
+        // every unlit chunk starts dark, so none of them pulls in the light a
+        // server never sent
+        for pos in &chunks {
+            block.reset(*pos);
+            if let Some(sky) = &mut sky {
+                sky.reset(*pos);
+            }
+        }
+        for pos in chunks {
+            let chunk = view.get_chunk(pos).unwrap();
+            let sources = sky_sources(chunk);
+            block.light_emitters(pos, chunk);
+            block.pull_in(pos);
+            if let Some(sky) = &mut sky {
+                sky.light_sky_sources(pos, chunk, &sources);
+                sky.pull_in(pos);
+            }
+            self.sky_sources.insert(pos, sources);
+        }
+
+        let mut seen = HashSet::with_hasher(FxBuildHasher);
+        for pos in core::mem::take(&mut self.pending_blocks) {
+            let Some(chunk) = view.get_chunk(pos.chunk()) else {
+                continue;
+            };
+            if !chunk.height().contains_y(pos.y) || !seen.insert(pos) {
+                continue;
+            }
+            block.check_block(pos);
+            if let Some(sky) = &mut sky {
+                let sources = self
+                    .sky_sources
+                    .entry(pos.chunk())
+                    .or_insert_with(|| sky_sources(chunk));
+                let column = column_index(pos.x, pos.z);
+                let old = sources[column];
+                sources[column] = lowest_sky_source(chunk, pos.x, pos.z);
+                sky.check_sky(pos, old, sources[column]);
+            }
+        }
+
+        let mut updates: HashMap<ChunkPos, LightUpdate, FxBuildHasher> = HashMap::default();
+        block.propagate();
+        block.finish(&mut updates);
+        if let Some(mut sky) = sky {
+            sky.propagate();
+            sky.finish(&mut updates);
+        }
+        let mut updates: Vec<_> = updates.into_iter().collect();
+        updates.sort_by_key(|(pos, _)| (pos.x, pos.z));
+        updates
+            .into_iter()
+            .map(|(pos, light)| WorldEvent::LightUpdated { pos, light })
+            .collect()
+    }
+}
+
+impl WorldConsumer for LightEngine {
+    fn on_event(&mut self, event: &WorldEvent, view: &WorldView) {
+        match event {
+            WorldEvent::ChunkLoaded { pos, chunk } => {
+                let unlit = chunk
+                    .light
+                    .sky
+                    .iter()
+                    .chain(&chunk.light.block)
+                    .all(Option::is_none);
+                if unlit {
+                    self.pending_chunks.insert(*pos);
+                } else if let Some(chunk) = view.get_chunk(*pos) {
+                    self.pending_chunks.remove(pos);
+                    self.sky_sources.insert(*pos, sky_sources(chunk));
+                }
+            }
+            WorldEvent::ChunkUnloaded { pos } => {
+                self.pending_chunks.remove(pos);
+                self.sky_sources.remove(pos);
+            }
+            WorldEvent::BlockSet { pos, .. } => self.pending_blocks.push(*pos),
+            WorldEvent::BiomesSet { .. } | WorldEvent::LightUpdated { .. } => {}
+        }
+    }
+}
+
+fn column_index(x: i32, z: i32) -> usize {
+    ((z & 15) << 4 | (x & 15)) as usize
+}
+
+/// The lowest sky source of every column of `chunk`, indexed by `z << 4 | x`.
+fn sky_sources(chunk: &Chunk) -> Vec<i32> {
+    (0..256)
+        .map(|i| lowest_sky_source(chunk, i & 15, i >> 4))
+        .collect()
+}
+
+/// The lowest y of a column from which every block up is lit straight from
+/// the sky, after `ChunkSkyLightSources`. Sky light passes down until a
+/// block with any `light_block` or faces that close the gap between two
+/// blocks.
+fn lowest_sky_source(chunk: &Chunk, x: i32, z: i32) -> i32 {
+    let height = chunk.height();
+    let mut above = Block::AIR.default_state();
+    for y in (height.min_y..height.max_y()).rev() {
+        let state = chunk.get_block(BlockPos::new(x, y, z));
+        if state.light_block() != 0 || shape_occludes(above, state, Direction::Down) {
+            return y + 1;
+        }
+        above = state;
+    }
+    height.min_y
+}
+
+/// A queued propagation step, vanilla's `LightEngine.QueueEntry`.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    pos: BlockPos,
+    level: u8,
+    directions: Directions,
+    /// Raises the stored level to `level` first, for emitters.
+    from_emission: bool,
+}
+
+impl Entry {
+    fn directions(self) -> impl Iterator<Item = Direction> {
+        Direction::ALL
+            .into_iter()
+            .filter(move |direction| self.directions & only(*direction) != 0)
+    }
+}
+
+/// One channel's light during a run, read from the world and written to
+/// copies of the sections it touches.
+struct Propagator<'a> {
+    view: WorldView<'a>,
+    sky: bool,
+    light: HashMap<(ChunkPos, usize), NibbleArray, FxBuildHasher>,
+    decreases: VecDeque<Entry>,
+    increases: VecDeque<Entry>,
+}
+
+impl<'a> Propagator<'a> {
+    fn new(view: WorldView<'a>, sky: bool) -> Self {
+        Self {
+            view,
+            sky,
+            light: HashMap::default(),
+            decreases: VecDeque::new(),
+            increases: VecDeque::new(),
+        }
+    }
+
+    /// The chunk and light section index holding `pos`, if it is loaded and
+    /// within the light sections, which reach one past the world each way.
+    fn slot(&self, pos: BlockPos) -> Option<(ChunkPos, usize)> {
+        let chunk = self.view.get_chunk(pos.chunk())?;
+        let height = chunk.height();
+        let index = usize::try_from(pos.section_y() - height.min_section() + 1).ok()?;
+        (index < height.section_count() + 2).then_some((pos.chunk(), index))
+    }
+
+    /// The light the world holds for a section.
+    fn stored(&self, chunk: &'a Chunk, index: usize) -> Option<&'a NibbleArray> {
+        let section_y = index as i32 + chunk.height().min_section() - 1;
+        match self.sky {
+            true => chunk.sky_light(section_y),
+            false => chunk.block_light(section_y),
+        }
+    }
+
+    /// The level of a section the world has no light for, after
+    /// `SkyLightSectionStorage.getLightValue`: sky light carries on from the
+    /// bottom of the next section up that has some, and is full if none has.
+    fn missing(&self, chunk: &'a Chunk, index: usize, x: usize, z: usize) -> u8 {
+        if !self.sky {
+            return 0;
+        }
+        (index + 1..chunk.height().section_count() + 2)
+            .find_map(|index| self.stored(chunk, index))
+            .map_or(15, |above| above.get(x, 0, z))
+    }
+
+    fn get(&self, pos: BlockPos) -> Option<u8> {
+        let (chunk_pos, index) = self.slot(pos)?;
+        let (x, y, z) = (
+            (pos.x & 15) as usize,
+            (pos.y & 15) as usize,
+            (pos.z & 15) as usize,
+        );
+        if let Some(light) = self.light.get(&(chunk_pos, index)) {
+            return Some(light.get(x, y, z));
+        }
+        let chunk = self.view.get_chunk(chunk_pos)?;
+        Some(match self.stored(chunk, index) {
+            Some(light) => light.get(x, y, z),
+            None => self.missing(chunk, index, x, z),
+        })
+    }
+
+    fn set(&mut self, pos: BlockPos, level: u8) {
+        let Some((chunk_pos, index)) = self.slot(pos) else {
+            return;
+        };
+        if !self.light.contains_key(&(chunk_pos, index)) {
+            let chunk = self.view.get_chunk(chunk_pos).unwrap();
+            let light = match self.stored(chunk, index) {
+                Some(light) => light.clone(),
+                None => {
+                    let mut light = NibbleArray::new();
+                    for z in 0..16 {
+                        for x in 0..16 {
+                            let level = self.missing(chunk, index, x, z);
+                            for y in 0..16 {
+                                light.set(x, y, z, level);
+                            }
+                        }
+                    }
+                    light
+                }
+            };
+            self.light.insert((chunk_pos, index), light);
+        }
+        let light = self.light.get_mut(&(chunk_pos, index)).unwrap();
+        light.set(
+            (pos.x & 15) as usize,
+            (pos.y & 15) as usize,
+            (pos.z & 15) as usize,
+            level,
+        );
+    }
+
+    fn block(&self, pos: BlockPos) -> BlockState {
+        self.view
+            .get_block(pos)
+            .unwrap_or(Block::AIR.default_state())
+    }
+
+    fn emission(&self, pos: BlockPos) -> u8 {
+        match self.sky {
+            true => 0,
+            false => self.block(pos).light_emission(),
+        }
+    }
+
+    fn decrease(&mut self, pos: BlockPos, level: u8, directions: Directions) {
+        self.decreases.push_back(Entry {
+            pos,
+            level,
+            directions,
+            from_emission: false,
+        });
+    }
+
+    fn increase(&mut self, pos: BlockPos, level: u8, directions: Directions) {
+        self.increases.push_back(Entry {
+            pos,
+            level,
+            directions,
+            from_emission: false,
+        });
+    }
+
+    fn increase_from_emission(&mut self, pos: BlockPos, level: u8) {
+        self.increases.push_back(Entry {
+            pos,
+            level,
+            directions: ALL_DIRECTIONS,
+            from_emission: true,
+        });
+    }
+
+    /// Darkens every light section of a chunk.
+    fn reset(&mut self, chunk_pos: ChunkPos) {
+        let Some(chunk) = self.view.get_chunk(chunk_pos) else {
+            return;
+        };
+        for index in 0..chunk.height().section_count() + 2 {
+            self.light.insert((chunk_pos, index), NibbleArray::new());
+        }
+    }
+
+    fn light_emitters(&mut self, chunk_pos: ChunkPos, chunk: &Chunk) {
+        let min_y = chunk.height().min_section() * 16;
+        for (section_index, section) in chunk.sections().iter().enumerate() {
+            let states = section.states();
+            if states
+                .palette()
+                .iter()
+                .all(|state| state.light_emission() == 0)
+            {
+                continue;
+            }
+            for (i, state) in states.iter().enumerate() {
+                let emission = state.light_emission();
+                if emission > 0 {
+                    let pos = BlockPos::new(
+                        chunk_pos.min_block_x() + (i & 15) as i32,
+                        min_y + (section_index * 16 + (i >> 8)) as i32,
+                        chunk_pos.min_block_z() + ((i >> 4) & 15) as i32,
+                    );
+                    self.increase_from_emission(pos, emission);
+                }
+            }
+        }
+    }
+
+    /// Fills every column of a reset chunk with full light from its lowest
+    /// source up, queueing the sources that have something darker beside or
+    /// below them.
+    fn light_sky_sources(&mut self, chunk_pos: ChunkPos, chunk: &Chunk, sources: &[i32]) {
+        let height = chunk.height();
+        let top = (height.min_section() + height.section_count() as i32 + 1) * 16;
+        for z in 0..16 {
+            for x in 0..16 {
+                let lowest = sources[column_index(x, z)];
+                let edge = x == 0 || x == 15 || z == 0 || z == 15;
+                let reach = match edge {
+                    true => top,
+                    false => [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                        .map(|(dx, dz)| sources[column_index(x + dx, z + dz)])
+                        .into_iter()
+                        .max()
+                        .unwrap(),
+                };
+                for y in lowest..top {
+                    let pos =
+                        BlockPos::new(chunk_pos.min_block_x() + x, y, chunk_pos.min_block_z() + z);
+                    self.set(pos, 15);
+                    if y == lowest || y < reach {
+                        self.increase(pos, 15, SKY_SOURCE_DIRECTIONS);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Spreads the light of loaded neighbours into a chunk lit from scratch.
+    fn pull_in(&mut self, chunk_pos: ChunkPos) {
+        let Some(chunk) = self.view.get_chunk(chunk_pos) else {
+            return;
+        };
+        let height = chunk.height();
+        let bottom = (height.min_section() - 1) * 16;
+        let top = (height.min_section() + height.section_count() as i32 + 1) * 16;
+        let (min_x, min_z) = (chunk_pos.min_block_x(), chunk_pos.min_block_z());
+        for direction in [
+            Direction::North,
+            Direction::South,
+            Direction::West,
+            Direction::East,
+        ] {
+            for i in 0..16 {
+                // the neighbour's cell across the border, towards `direction`
+                let (x, z) = match direction {
+                    Direction::North => (min_x + i, min_z - 1),
+                    Direction::South => (min_x + i, min_z + 16),
+                    Direction::West => (min_x - 1, min_z + i),
+                    _ => (min_x + 16, min_z + i),
+                };
+                for y in bottom..top {
+                    let pos = BlockPos::new(x, y, z);
+                    match self.get(pos) {
+                        Some(level) if level > 1 => {
+                            self.increase(pos, level, only(direction.opposite()))
+                        }
+                        Some(_) => {}
+                        None => break,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Relights a changed block, after `BlockLightEngine.checkNode`.
+    fn check_block(&mut self, pos: BlockPos) {
+        let Some(level) = self.get(pos) else {
+            return;
+        };
+        let emission = self.emission(pos);
+        if emission < level {
+            self.set(pos, 0);
+            self.decrease(pos, level, ALL_DIRECTIONS);
+        } else {
+            // let the neighbours light it again
+            self.decrease(pos, 0, ALL_DIRECTIONS);
+        }
+        if emission > 0 {
+            self.increase_from_emission(pos, emission);
+        }
+    }
+
+    /// Relights a changed block whose column's lowest sky source moved from
+    /// `old` to `new`, after `SkyLightEngine.checkNode`.
+    fn check_sky(&mut self, pos: BlockPos, old: i32, new: i32) {
+        for y in old..new {
+            let source = BlockPos::new(pos.x, y, pos.z);
+            if self.get(source).is_some_and(|level| level > 0) {
+                self.set(source, 0);
+                self.decrease(source, 15, ALL_DIRECTIONS);
+            }
+        }
+        for y in new..old {
+            let source = BlockPos::new(pos.x, y, pos.z);
+            self.set(source, 15);
+            self.increase(source, 15, SKY_SOURCE_DIRECTIONS);
+        }
+        if pos.y >= new {
+            self.decrease(pos, 15, ALL_DIRECTIONS);
+            self.increase(pos, 15, SKY_SOURCE_DIRECTIONS);
+        } else {
+            match self.get(pos) {
+                Some(level) if level > 0 => {
+                    self.set(pos, 0);
+                    self.decrease(pos, level, ALL_DIRECTIONS);
+                }
+                _ => self.decrease(pos, 0, ALL_DIRECTIONS),
+            }
+        }
+    }
+
+    /// Runs every removal, then every addition.
+    fn propagate(&mut self) {
+        while let Some(entry) = self.decreases.pop_front() {
+            self.propagate_decrease(entry);
+        }
+        while let Some(entry) = self.increases.pop_front() {
+            let Some(mut level) = self.get(entry.pos) else {
+                continue;
+            };
+            if entry.from_emission && level < entry.level {
+                self.set(entry.pos, entry.level);
+                level = entry.level;
+            }
+            // a later change may have superseded the entry
+            if level == entry.level {
+                self.propagate_increase(entry);
+            }
+        }
+    }
+
+    /// Clears the light that could have come from `entry`, queueing the
+    /// neighbours lit some other way to spread back in.
+    fn propagate_decrease(&mut self, entry: Entry) {
+        for direction in entry.directions() {
+            let to = entry.pos.relative(direction);
+            let Some(to_level) = self.get(to) else {
+                continue;
+            };
+            if to_level == 0 {
+                continue;
+            }
+            if to_level < entry.level {
+                self.set(to, 0);
+                let emission = self.emission(to);
+                if emission < to_level {
+                    self.decrease(to, to_level, all_but(direction.opposite()));
+                }
+                if emission > 0 {
+                    self.increase_from_emission(to, emission);
+                }
+            } else {
+                self.increase(to, to_level, only(direction.opposite()));
+            }
+        }
+    }
+
+    fn propagate_increase(&mut self, entry: Entry) {
+        let from = self.block(entry.pos);
+        for direction in entry.directions() {
+            let to = entry.pos.relative(direction);
+            let Some(to_level) = self.get(to) else {
+                continue;
+            };
+            if entry.level.saturating_sub(1) <= to_level {
+                continue;
+            }
+            let state = self.block(to);
+            if shape_occludes(from, state, direction) {
+                continue;
+            }
+            let level = entry.level.saturating_sub(state.light_block().max(1));
+            if level > to_level {
+                self.set(to, level);
+                if level > 1 {
+                    self.increase(to, level, all_but(direction.opposite()));
+                }
+            }
+        }
+    }
+
+    /// Adds the sections whose light changed to `updates`.
+    fn finish(self, updates: &mut HashMap<ChunkPos, LightUpdate, FxBuildHasher>) {
+        for ((chunk_pos, index), light) in &self.light {
+            let Some(chunk) = self.view.get_chunk(*chunk_pos) else {
+                continue;
+            };
+            if self.stored(chunk, *index) == Some(light) {
+                continue;
+            }
+            let sections = chunk.height().section_count() + 2;
+            let update = updates.entry(*chunk_pos).or_insert_with(|| LightUpdate {
+                sky: vec![None; sections],
+                block: vec![None; sections],
+            });
+            let channel = match self.sky {
+                true => &mut update.sky,
+                false => &mut update.block,
+            };
+            channel[*index] = Some(light.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BiomeId, ChunkData, ChunkSection, World, WorldHeight};
+
+    use super::*;
+
+    const HEIGHT: WorldHeight = WorldHeight {
+        min_y: 0,
+        height: 32,
+    };
+
+    fn unlit_chunk() -> ChunkData {
+        ChunkData {
+            height: HEIGHT,
+            sections: (0..HEIGHT.section_count())
+                .map(|_| ChunkSection::empty(BiomeId(0)))
+                .collect(),
+            heightmaps: Vec::new(),
+            block_entities: Vec::new(),
+            light: LightUpdate::default(),
+        }
+    }
+
+    struct Harness {
+        world: World,
+        engine: LightEngine,
+    }
+
+    impl Harness {
+        /// Three by three unlit chunks around the origin chunk.
+        fn new() -> Self {
+            let mut harness = Self {
+                world: World::new(),
+                engine: LightEngine::new(true),
+            };
+            for x in -1..=1 {
+                for z in -1..=1 {
+                    harness.apply(WorldEvent::ChunkLoaded {
+                        pos: ChunkPos::new(x, z),
+                        chunk: unlit_chunk(),
+                    });
+                }
+            }
+            harness.run();
+            harness
+        }
+
+        fn apply(&mut self, event: WorldEvent) {
+            self.world.apply(event.clone());
+            self.engine.on_event(&event, &self.world.view());
+        }
+
+        fn set(&mut self, x: i32, y: i32, z: i32, state: &str) {
+            self.apply(WorldEvent::BlockSet {
+                pos: BlockPos::new(x, y, z),
+                state: state.parse().unwrap(),
+            });
+        }
+
+        fn run(&mut self) {
+            for event in self.engine.run(&self.world.view()) {
+                self.world.apply(event);
+            }
+            assert!(!self.engine.has_pending());
+        }
+
+        fn block(&self, x: i32, y: i32, z: i32) -> u8 {
+            let pos = BlockPos::new(x, y, z);
+            let chunk = self.world.view().get_chunk(pos.chunk()).unwrap();
+            let light = chunk.block_light(pos.section_y()).unwrap();
+            light.get((x & 15) as usize, (y & 15) as usize, (z & 15) as usize)
+        }
+
+        fn sky(&self, x: i32, y: i32, z: i32) -> u8 {
+            let pos = BlockPos::new(x, y, z);
+            let chunk = self.world.view().get_chunk(pos.chunk()).unwrap();
+            let light = chunk.sky_light(pos.section_y()).unwrap();
+            light.get((x & 15) as usize, (y & 15) as usize, (z & 15) as usize)
+        }
+    }
+
+    #[test]
+    fn test_block_light_spreads_and_clears() {
+        let mut harness = Harness::new();
+        assert_eq!(harness.block(8, 8, 8), 0);
+        harness.set(8, 8, 8, "glowstone");
+        harness.run();
+        assert_eq!(harness.block(8, 8, 8), 15);
+        assert_eq!(harness.block(9, 8, 8), 14);
+        assert_eq!(harness.block(8, 16, 8), 7);
+        // across the chunk border
+        assert_eq!(harness.block(-1, 8, 8), 6);
+        assert_eq!(harness.block(-1, 7, 7), 4);
+
+        harness.set(7, 8, 8, "stone");
+        harness.run();
+        assert_eq!(harness.block(7, 8, 8), 0);
+        // around the stone rather than through it
+        assert_eq!(harness.block(6, 8, 8), 11);
+
+        harness.set(8, 8, 8, "air");
+        harness.run();
+        assert_eq!(harness.block(9, 8, 8), 0);
+        assert_eq!(harness.block(-1, 8, 8), 0);
+    }
+
+    #[test]
+    fn test_sky_light_under_a_roof() {
+        let mut harness = Harness::new();
+        assert_eq!(harness.sky(3, 0, 3), 15);
+        for x in 0..8 {
+            for z in 0..8 {
+                harness.set(x, 10, z, "stone");
+            }
+        }
+        harness.run();
+        assert_eq!(harness.sky(3, 11, 3), 15);
+        assert_eq!(harness.sky(8, 9, 3), 15);
+        assert_eq!(harness.sky(7, 9, 3), 14);
+        assert_eq!(harness.sky(3, 9, 3), 11);
+        // leaves dim the light below without stopping it
+        harness.set(12, 10, 12, "oak_leaves");
+        harness.run();
+        assert_eq!(harness.sky(12, 10, 12), 14);
+        assert_eq!(harness.sky(12, 9, 12), 14);
+
+        for x in 0..8 {
+            for z in 0..8 {
+                harness.set(x, 10, z, "air");
+            }
+        }
+        harness.run();
+        assert_eq!(harness.sky(3, 9, 3), 15);
+    }
+
+    #[test]
+    fn test_shape_occlusion() {
+        let mut harness = Harness::new();
+        harness.set(8, 8, 8, "glowstone");
+        harness.set(9, 8, 8, "stone_slab[type=bottom]");
+        harness.set(10, 8, 8, "stone_slab[type=top]");
+        harness.run();
+        assert_eq!(harness.block(9, 8, 8), 14);
+        // not through the slab pair but up from below
+        assert_eq!(harness.block(10, 8, 8), 11);
+        assert_eq!(harness.block(10, 9, 8), 12);
+    }
+
+    #[test]
+    fn test_incremental_matches_full_light() {
+        let states = [
+            "air",
+            "air",
+            "stone",
+            "glowstone",
+            "torch",
+            "glass",
+            "oak_leaves",
+            "water",
+            "stone_slab[type=bottom]",
+            "stone_slab[type=top]",
+            "oak_stairs[facing=east,half=top]",
+            "snow[layers=4]",
+        ];
+        let mut harness = Harness::new();
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let mut next = |bound: u64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % bound) as i32
+        };
+        for _ in 0..20 {
+            for _ in 0..40 {
+                let (x, y, z) = (next(24) - 4, next(32), next(24) - 4);
+                let state = states[next(states.len() as u64) as usize];
+                harness.set(x, y, z, state);
+            }
+            harness.run();
+        }
+
+        let mut fresh = World::new();
+        let mut engine = LightEngine::new(true);
+        for (pos, chunk) in harness.world.view().chunks() {
+            let mut data = chunk.to_data();
+            data.light = LightUpdate::default();
+            let event = WorldEvent::ChunkLoaded { pos, chunk: data };
+            fresh.apply(event.clone());
+            engine.on_event(&event, &fresh.view());
+        }
+        for event in engine.run(&fresh.view()) {
+            fresh.apply(event);
+        }
+        for (pos, chunk) in harness.world.view().chunks() {
+            let relit = fresh.view().get_chunk(pos).unwrap();
+            for section_y in -1..=HEIGHT.section_count() as i32 {
+                assert_eq!(
+                    chunk.block_light(section_y),
+                    relit.block_light(section_y),
+                    "block light of {pos:?} section {section_y}"
+                );
+                assert_eq!(
+                    chunk.sky_light(section_y),
+                    relit.sky_light(section_y),
+                    "sky light of {pos:?} section {section_y}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Face occlusion for the blocks with `use_shape_for_light_occlusion`, after
+//! `LightEngine.shapeOccludes`, against the generated occlusion shapes.
+
+use alloc::vec::Vec;
+use mcre_core::{Axis, Direction};
+
+use crate::BlockState;
+
+/// A rectangle `[u0, v0, u1, v1]` on a face, in the face's own axes.
+type FaceRect = [f64; 4];
+
+/// Whether light cannot pass from `from` to its neighbour `to` in
+/// `direction` because their touching faces together cover the whole face.
+pub(crate) fn shape_occludes(from: BlockState, to: BlockState, direction: Direction) -> bool {
+    if is_empty_shape(from) && is_empty_shape(to) {
+        return false;
+    }
+    let mut rects = face_rects(from, direction);
+    rects.extend(face_rects(to, direction.opposite()));
+    covers_face(&rects)
+}
+
+/// Whether a block takes no part in shape occlusion, leaving it to
+/// `light_block` alone.
+fn is_empty_shape(state: BlockState) -> bool {
+    !state.can_occlude() || !state.use_shape_for_light_occlusion()
+}
+
+/// The parts of the face towards `direction` that the boxes of the
+/// occlusion shape reaching it cover, vanilla's `getFaceShape`.
+fn face_rects(state: BlockState, direction: Direction) -> Vec<FaceRect> {
+    if is_empty_shape(state) {
+        return Vec::new();
+    }
+    let (normal, u, v) = match direction.axis() {
+        Axis::X => (0, 2, 1),
+        Axis::Y => (1, 0, 2),
+        Axis::Z => (2, 0, 1),
+    };
+    state
+        .occlusion_boxes()
+        .iter()
+        .filter(|bounds| {
+            if direction.is_positive() {
+                bounds[normal + 3] >= 1.0
+            } else {
+                bounds[normal] <= 0.0
+            }
+        })
+        .map(|bounds| [bounds[u], bounds[v], bounds[u + 3], bounds[v + 3]])
+        .collect()
+}
+
+/// Whether the rectangles together cover the whole face, checked cell by
+/// cell on the grid their edges make.
+fn covers_face(rects: &[FaceRect]) -> bool {
+    let edges = |low: usize| {
+        let mut edges: Vec<f64> = rects
+            .iter()
+            .flat_map(|rect| [rect[low], rect[low + 2]])
+            .filter(|edge| (0.0..=1.0).contains(edge))
+            .chain([0.0, 1.0])
+            .collect();
+        edges.sort_by(f64::total_cmp);
+        edges.dedup();
+        edges
+    };
+    let (us, vs) = (edges(0), edges(1));
+    us.windows(2).all(|u| {
+        let u = (u[0] + u[1]) / 2.0;
+        vs.windows(2).all(|v| {
+            let v = (v[0] + v[1]) / 2.0;
+            rects
+                .iter()
+                .any(|rect| rect[0] <= u && u <= rect[2] && rect[1] <= v && v <= rect[3])
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> BlockState {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_face_occlusion() {
+        let bottom = parse("stone_slab[type=bottom]");
+        let top = parse("stone_slab[type=top]");
+        let air = parse("air");
+        assert!(shape_occludes(bottom, top, Direction::East));
+        assert!(!shape_occludes(bottom, bottom, Direction::East));
+        assert!(shape_occludes(bottom, air, Direction::Down));
+        assert!(!shape_occludes(bottom, air, Direction::Up));
+        assert!(!shape_occludes(air, air, Direction::Up));
+
+        // the tall back of a stair covers its facing side
+        let stairs = parse("oak_stairs[facing=east,half=bottom,shape=straight]");
+        assert!(shape_occludes(stairs, air, Direction::East));
+        assert!(!shape_occludes(stairs, air, Direction::West));
+        assert!(shape_occludes(stairs, top, Direction::West));
+        let outer = parse("oak_stairs[facing=north,half=bottom,shape=outer_left]");
+        assert!(!shape_occludes(outer, air, Direction::North));
+        assert!(!shape_occludes(outer, air, Direction::West));
+
+        let piston = parse("piston[facing=up,extended=true]");
+        assert!(shape_occludes(piston, air, Direction::Down));
+        assert!(!shape_occludes(piston, air, Direction::Up));
+        let head = parse("piston_head[facing=up]");
+        assert!(shape_occludes(head, air, Direction::Up));
+        assert!(!shape_occludes(head, piston, Direction::Down));
+    }
+}
@@ -0,0 +1,1429 @@
+//! The movement of the local player, after vanilla's `LocalPlayer`, `Player`
+//! and `LivingEntity` ticking.
+//!
+//! Only what moves a survival player around the world is simulated: there
+//! are no status effects, enchantments, creative flight, vehicles, entity
+//! collisions or blocks that slow entities inside them, and the player
+//! always has the food to sprint. Unloaded blocks are read as air, though
+//! like vanilla the player sinks slowly through unloaded chunks instead of
+//! falling.
+
+use alloc::vec::Vec;
+use mcre_core::{Aabb, BlockPos, Fluid, Vec3d};
+
+use crate::collision::{colliding_blocks, horizontal_length_squared, length_squared};
+use crate::{Block, BlockState, BlockTag, WorldView, collide, fluid_flow, no_collision};
+
+const GRAVITY: f64 = 0.08;
+const WALKING_SPEED: f32 = 0.1;
+const SPRINTING_SPEED_BONUS: f64 = 0.3;
+const SNEAKING_SPEED: f32 = 0.3;
+const JUMP_STRENGTH: f32 = 0.42;
+const MAX_UP_STEP: f32 = 0.6;
+const WATER_PUSH: f64 = 0.014;
+const LAVA_PUSH: f64 = 0.0023333333333333335;
+const DEGREES: f32 = core::f32::consts::PI / 180.0;
+
+/// The movement keys held during a tick, vanilla's `Input`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct PlayerInput {
+    pub forward: bool,
+    pub backward: bool,
+    pub left: bool,
+    pub right: bool,
+    pub jump: bool,
+    pub sneak: bool,
+    pub sprint: bool,
+}
+
+/// The posture of the player, which sets the size of its bounding box.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum PlayerPose {
+    #[default]
+    Standing,
+    Crouching,
+    /// Swimming, or crawling through a one block gap.
+    Swimming,
+    FallFlying,
+}
+
+impl PlayerPose {
+    pub fn height(self) -> f32 {
+        match self {
+            PlayerPose::Standing => 1.8,
+            PlayerPose::Crouching => 1.5,
+            PlayerPose::Swimming | PlayerPose::FallFlying => 0.6,
+        }
+    }
+
+    pub fn eye_height(self) -> f32 {
+        match self {
+            PlayerPose::Standing => 1.62,
+            PlayerPose::Crouching => 1.27,
+            PlayerPose::Swimming | PlayerPose::FallFlying => 0.4,
+        }
+    }
+
+    /// The player's box in this pose with its feet at `position`.
+    pub fn bounding_box(self, position: Vec3d) -> Aabb {
+        let radius = (0.6f32 / 2.0) as f64;
+        let height = self.height() as f64;
+        Aabb::new(
+            Vec3d::new(position[0] - radius, position[1], position[2] - radius),
+            Vec3d::new(
+                position[0] + radius,
+                position[1] + height,
+                position[2] + radius,
+            ),
+        )
+    }
+}
+
+/// A `ServerboundMovePlayerPacket`, carrying whatever changed since the last
+/// one was sent.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MovePacket {
+    PosRot {
+        position: Vec3d,
+        yaw: f32,
+        pitch: f32,
+        on_ground: bool,
+        horizontal_collision: bool,
+    },
+    Pos {
+        position: Vec3d,
+        on_ground: bool,
+        horizontal_collision: bool,
+    },
+    Rot {
+        yaw: f32,
+        pitch: f32,
+        on_ground: bool,
+        horizontal_collision: bool,
+    },
+    StatusOnly {
+        on_ground: bool,
+        horizontal_collision: bool,
+    },
+}
+
+/// A `ServerboundPlayerCommandPacket` action caused by movement.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PlayerCommand {
+    StartSprinting,
+    StopSprinting,
+    StartFallFlying,
+}
+
+/// The packets a tick leaves to be sent, in the order vanilla sends them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerTick {
+    pub commands: Vec<PlayerCommand>,
+    /// The keys held, if they changed, for a `ServerboundPlayerInputPacket`.
+    pub input: Option<PlayerInput>,
+    pub movement: Option<MovePacket>,
+}
+
+/// What the server was last told.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Sent {
+    position: Vec3d,
+    yaw: f32,
+    pitch: f32,
+    on_ground: bool,
+    horizontal_collision: bool,
+    sprinting: bool,
+    input: PlayerInput,
+    /// Ticks since the position was last sent; it is sent at least once a
+    /// second.
+    position_reminder: u32,
+}
+
+/// The movement state of the local player.
+///
+/// Rotation is set by the caller as the mouse moves; everything else is
+/// advanced by [`PlayerPhysics::tick`], once per game tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerPhysics {
+    /// The point between the player's feet.
+    pub position: Vec3d,
+    /// Blocks per tick, vanilla's `deltaMovement`.
+    pub velocity: Vec3d,
+    /// Degrees clockwise from south.
+    pub yaw: f32,
+    /// Degrees downwards from level.
+    pub pitch: f32,
+    /// Whether an elytra is worn.
+    pub elytra: bool,
+    pub on_ground: bool,
+    pub horizontal_collision: bool,
+    pub sprinting: bool,
+    pub fall_flying: bool,
+    pub pose: PlayerPose,
+    pub fall_distance: f32,
+    minor_horizontal_collision: bool,
+    /// The walking input, vanilla's `xxa` and `zza`.
+    impulse: Vec3d,
+    swimming: bool,
+    in_water: bool,
+    under_water: bool,
+    eye_in_water: bool,
+    water_height: f64,
+    lava_height: f64,
+    supporting_block: Option<BlockPos>,
+    on_ground_no_blocks: bool,
+    no_jump_delay: u8,
+    last_input: PlayerInput,
+    sent: Sent,
+}
+
+impl PlayerPhysics {
+    /// A player standing still at `position`, as placed by the server.
+    pub fn new(position: Vec3d, yaw: f32, pitch: f32) -> Self {
+        Self {
+            position,
+            velocity: Vec3d::new(0.0, 0.0, 0.0),
+            yaw,
+            pitch,
+            elytra: false,
+            on_ground: false,
+            horizontal_collision: false,
+            sprinting: false,
+            fall_flying: false,
+            pose: PlayerPose::Standing,
+            fall_distance: 0.0,
+            minor_horizontal_collision: false,
+            impulse: Vec3d::new(0.0, 0.0, 0.0),
+            swimming: false,
+            in_water: false,
+            under_water: false,
+            eye_in_water: false,
+            water_height: 0.0,
+            lava_height: 0.0,
+            supporting_block: None,
+            on_ground_no_blocks: false,
+            no_jump_delay: 0,
+            last_input: PlayerInput::default(),
+            sent: Sent {
+                position,
+                yaw,
+                pitch,
+                on_ground: false,
+                horizontal_collision: false,
+                sprinting: false,
+                input: PlayerInput::default(),
+                position_reminder: 0,
+            },
+        }
+    }
+
+    pub fn bounding_box(&self) -> Aabb {
+        self.pose.bounding_box(self.position)
+    }
+
+    pub fn eye_position(&self) -> Vec3d {
+        let [x, y, z] = *self.position;
+        Vec3d::new(x, y + self.pose.eye_height() as f64, z)
+    }
+
+    pub fn is_in_water(&self) -> bool {
+        self.in_water
+    }
+
+    pub fn is_in_lava(&self) -> bool {
+        self.lava_height > 0.0
+    }
+
+    pub fn is_swimming(&self) -> bool {
+        self.swimming
+    }
+
+    /// Advances the player by one tick of `input`, and returns the packets
+    /// that tell the server about it.
+    pub fn tick(&mut self, input: &PlayerInput, view: &WorldView) -> PlayerTick {
+        let mut commands = Vec::new();
+        // Player.tick, then Entity.baseTick
+        self.under_water = self.eye_in_water;
+        self.update_fluids(view);
+        self.update_swimming(view);
+        if self.is_in_lava() {
+            self.fall_distance *= 0.5;
+        }
+        self.local_ai_step(input, view, &mut commands);
+        self.update_pose(input, view);
+        self.last_input = *input;
+
+        let input = (self.sent.input != *input).then(|| {
+            self.sent.input = *input;
+            *input
+        });
+        let movement = self.send_position(&mut commands);
+        PlayerTick {
+            commands,
+            input,
+            movement,
+        }
+    }
+
+    /// `LocalPlayer.aiStep`: turns keys into impulses and sprinting, before
+    /// the movement shared by all living entities.
+    fn local_ai_step(
+        &mut self,
+        input: &PlayerInput,
+        view: &WorldView,
+        commands: &mut Vec<PlayerCommand>,
+    ) {
+        let crouching = !self.swimming
+            && self.can_fit(view, PlayerPose::Crouching)
+            && (input.sneak || !self.can_fit(view, PlayerPose::Standing));
+        let moving_slowly = crouching || (self.pose == PlayerPose::Swimming && !self.in_water);
+        let mut left = impulse(input.left, input.right);
+        let mut forward = impulse(input.forward, input.backward);
+        if moving_slowly {
+            left *= SNEAKING_SPEED;
+            forward *= SNEAKING_SPEED;
+        }
+
+        let has_forward_impulse = forward > 1.0e-5;
+        let enough_impulse = if self.under_water {
+            has_forward_impulse
+        } else {
+            forward as f64 >= 0.8
+        };
+        let can_start_sprinting = !self.sprinting
+            && enough_impulse
+            && !self.fall_flying
+            && (!moving_slowly || self.under_water);
+        if (!self.in_water || self.under_water) && can_start_sprinting && input.sprint {
+            self.sprinting = true;
+        }
+        if self.sprinting {
+            let stop = if self.swimming {
+                !self.in_water || !has_forward_impulse && !self.on_ground && !input.sneak
+            } else {
+                !has_forward_impulse
+                    || self.fall_flying
+                    || moving_slowly && !self.under_water
+                    || self.horizontal_collision && !self.minor_horizontal_collision
+                    || self.in_water && !self.under_water
+            };
+            if stop {
+                self.sprinting = false;
+            }
+        }
+
+        if input.jump
+            && !self.last_input.jump
+            && !self.on_ground
+            && !self.fall_flying
+            && !self.in_water
+            && self.elytra
+            && !self.on_climbable(view)
+        {
+            self.fall_flying = true;
+            commands.push(PlayerCommand::StartFallFlying);
+        }
+        if self.in_water && input.sneak {
+            self.velocity[1] -= 0.04f32 as f64;
+        }
+
+        self.ai_step(left, forward, input, view);
+    }
+
+    /// `LivingEntity.aiStep`: jumping and travelling.
+    fn ai_step(&mut self, left: f32, forward: f32, input: &PlayerInput, view: &WorldView) {
+        self.no_jump_delay = self.no_jump_delay.saturating_sub(1);
+        if horizontal_length_squared(self.velocity) < 9.0e-6 {
+            self.velocity[0] = 0.0;
+            self.velocity[2] = 0.0;
+        }
+        if self.velocity[1].abs() < 0.003 {
+            self.velocity[1] = 0.0;
+        }
+
+        if input.jump {
+            let height = if self.is_in_lava() {
+                self.lava_height
+            } else {
+                self.water_height
+            };
+            let in_water = self.in_water && height > 0.0;
+            let threshold = self.fluid_jump_threshold();
+            if (in_water || self.is_in_lava()) && (!self.on_ground || height > threshold) {
+                // swimming up
+                self.velocity[1] += 0.04f32 as f64;
+            } else if (self.on_ground || in_water && height <= threshold) && self.no_jump_delay == 0
+            {
+                self.jump_from_ground(view);
+                self.no_jump_delay = 10;
+            }
+        } else {
+            self.no_jump_delay = 0;
+        }
+
+        let xxa = left * 0.98;
+        let zza = forward * 0.98;
+        self.impulse = Vec3d::new(xxa as f64, 0.0, zza as f64);
+        // the server ends a glide once the player lands or loses the elytra
+        if self.fall_flying && (self.on_ground || !self.elytra) {
+            self.fall_flying = false;
+        }
+        self.travel(self.impulse, input, view);
+    }
+
+    fn jump_from_ground(&mut self, view: &WorldView) {
+        let power = JUMP_STRENGTH * self.block_jump_factor(view);
+        if power <= 1.0e-5 {
+            return;
+        }
+        self.velocity[1] = (power as f64).max(self.velocity[1]);
+        if self.sprinting {
+            let yaw = self.yaw * DEGREES;
+            self.velocity[0] += -sin(yaw as f64) as f64 * 0.2;
+            self.velocity[2] += cos(yaw as f64) as f64 * 0.2;
+        }
+    }
+
+    fn fluid_jump_threshold(&self) -> f64 {
+        if self.pose.eye_height() < 0.4 {
+            0.0
+        } else {
+            0.4
+        }
+    }
+
+    /// `Player.travel`, then `LivingEntity.travel`.
+    fn travel(&mut self, movement: Vec3d, input: &PlayerInput, view: &WorldView) {
+        if self.swimming {
+            let look = self.look_angle()[1];
+            let rate = if look < -0.2 { 0.085 } else { 0.06 };
+            let [x, y, z] = *self.position;
+            let head = containing(x, y + 1.0 - 0.1, z);
+            if look <= 0.0 || input.jump || !block(view, head).fluid_state().is_empty() {
+                self.velocity[1] += (look - self.velocity[1]) * rate;
+            }
+        }
+
+        if self.in_water || self.is_in_lava() {
+            self.travel_in_fluid(movement, input, view);
+        } else if self.fall_flying {
+            self.velocity = self.fall_flying_movement(self.velocity);
+            self.move_by(self.velocity, input, view);
+        } else {
+            self.travel_in_air(movement, input, view);
+        }
+    }
+
+    fn travel_in_air(&mut self, movement: Vec3d, input: &PlayerInput, view: &WorldView) {
+        let below = self.block_pos_below_that_affects_movement(view);
+        let friction = if self.on_ground {
+            block(view, below).block().friction()
+        } else {
+            1.0
+        };
+        let inertia = friction * 0.91;
+
+        let speed = if self.on_ground {
+            self.speed() * (0.21600002 / (friction * friction * friction))
+        } else if self.sprinting {
+            0.025999999
+        } else {
+            0.02
+        };
+        self.move_relative(speed, movement);
+        self.velocity = self.handle_on_climbable(self.velocity, input, view);
+        self.move_by(self.velocity, input, view);
+        let mut velocity = self.velocity;
+        if (self.horizontal_collision || input.jump) && self.on_climbable(view) {
+            velocity[1] = 0.2;
+        }
+
+        let y = if view.get_block(below).is_none() {
+            -0.1
+        } else {
+            velocity[1] - GRAVITY
+        };
+        self.velocity = Vec3d::new(
+            velocity[0] * inertia as f64,
+            y * 0.98f32 as f64,
+            velocity[2] * inertia as f64,
+        );
+    }
+
+    fn travel_in_fluid(&mut self, movement: Vec3d, input: &PlayerInput, view: &WorldView) {
+        let falling = self.velocity[1] <= 0.0;
+        let start_y = self.position[1];
+        if self.in_water {
+            let slow_down = if self.sprinting { 0.9f32 } else { 0.8 };
+            self.move_relative(0.02, movement);
+            self.move_by(self.velocity, input, view);
+            let mut velocity = self.velocity;
+            if self.horizontal_collision && self.on_climbable(view) {
+                velocity[1] = 0.2;
+            }
+            velocity[0] *= slow_down as f64;
+            velocity[1] *= 0.8f32 as f64;
+            velocity[2] *= slow_down as f64;
+            self.velocity = self.fluid_falling_adjusted(falling, velocity);
+        } else {
+            self.move_relative(0.02, movement);
+            self.move_by(self.velocity, input, view);
+            if self.lava_height <= self.fluid_jump_threshold() {
+                self.velocity[0] *= 0.5;
+                self.velocity[1] *= 0.8f32 as f64;
+                self.velocity[2] *= 0.5;
+                self.velocity = self.fluid_falling_adjusted(falling, self.velocity);
+            } else {
+                self.velocity = Vec3d::new(
+                    self.velocity[0] * 0.5,
+                    self.velocity[1] * 0.5,
+                    self.velocity[2] * 0.5,
+                );
+            }
+            self.velocity[1] -= GRAVITY / 4.0;
+        }
+
+        // climbing out onto a ledge
+        let [x, y, z] = *self.velocity;
+        let lift = y + 0.6f32 as f64 - self.position[1] + start_y;
+        if self.horizontal_collision && self.is_free(view, Vec3d::new(x, lift, z)) {
+            self.velocity[1] = 0.3f32 as f64;
+        }
+    }
+
+    /// `LivingEntity.getFluidFallingAdjustedMovement`: fluids pull down a
+    /// sixteenth of gravity, and hold the player still when barely sinking.
+    fn fluid_falling_adjusted(&self, falling: bool, velocity: Vec3d) -> Vec3d {
+        if self.sprinting {
+            return velocity;
+        }
+        let [x, y, z] = *velocity;
+        let y = if falling && (y - 0.005).abs() >= 0.003 && (y - GRAVITY / 16.0).abs() < 0.003 {
+            -0.003
+        } else {
+            y - GRAVITY / 16.0
+        };
+        Vec3d::new(x, y, z)
+    }
+
+    /// `LivingEntity.updateFallFlyingMovement`: trading height for speed in
+    /// the direction the player looks.
+    fn fall_flying_movement(&self, velocity: Vec3d) -> Vec3d {
+        let look = self.look_angle();
+        let pitch = self.pitch * DEGREES;
+        let look_horizontal = libm::sqrt(look[0] * look[0] + look[2] * look[2]);
+        let speed = libm::sqrt(horizontal_length_squared(velocity));
+        let lift = libm::cos(pitch as f64);
+        let lift = lift * lift;
+        let [mut x, mut y, mut z] = *velocity;
+        y += GRAVITY * (-1.0 + lift * 0.75);
+        if y < 0.0 && look_horizontal > 0.0 {
+            let dive = y * -0.1 * lift;
+            x += look[0] * dive / look_horizontal;
+            y += dive;
+            z += look[2] * dive / look_horizontal;
+        }
+        if pitch < 0.0 && look_horizontal > 0.0 {
+            let climb = speed * -sin(pitch as f64) as f64 * 0.04;
+            x += -look[0] * climb / look_horizontal;
+            y += climb * 3.2;
+            z += -look[2] * climb / look_horizontal;
+        }
+        if look_horizontal > 0.0 {
+            x += (look[0] / look_horizontal * speed - x) * 0.1;
+            z += (look[2] / look_horizontal * speed - z) * 0.1;
+        }
+        Vec3d::new(x * 0.99f32 as f64, y * 0.98f32 as f64, z * 0.99f32 as f64)
+    }
+
+    /// `Entity.moveRelative`: accelerates by `speed` along the input,
+    /// turned to face where the player does.
+    fn move_relative(&mut self, speed: f32, movement: Vec3d) {
+        let length = length_squared(movement);
+        if length < 1.0e-7 {
+            return;
+        }
+        let movement = if length > 1.0 {
+            let length = libm::sqrt(length);
+            Vec3d::from(movement.map(|coord| coord / length))
+        } else {
+            movement
+        };
+        let [x, y, z] = movement.map(|coord| coord * speed as f64);
+        let yaw = (self.yaw * DEGREES) as f64;
+        let (sin, cos) = (sin(yaw) as f64, cos(yaw) as f64);
+        self.velocity[0] += x * cos - z * sin;
+        self.velocity[1] += y;
+        self.velocity[2] += z * cos + x * sin;
+    }
+
+    fn handle_on_climbable(
+        &mut self,
+        velocity: Vec3d,
+        input: &PlayerInput,
+        view: &WorldView,
+    ) -> Vec3d {
+        if !self.on_climbable(view) {
+            return velocity;
+        }
+        self.fall_distance = 0.0;
+        let limit = 0.15f32 as f64;
+        let [x, y, z] = *velocity;
+        let mut y = y.max(-limit);
+        if y < 0.0
+            && input.sneak
+            && block(view, self.block_position()).block() != Block::SCAFFOLDING
+        {
+            y = 0.0;
+        }
+        Vec3d::new(x.clamp(-limit, limit), y, z.clamp(-limit, limit))
+    }
+
+    /// `Entity.move`: moves as far as blocks allow, then reacts to what was
+    /// hit and stood on.
+    fn move_by(&mut self, movement: Vec3d, input: &PlayerInput, view: &WorldView) {
+        let movement = self.back_off_from_edge(movement, input, view);
+        let moved = collide(
+            view,
+            self.bounding_box(),
+            movement,
+            self.on_ground,
+            MAX_UP_STEP,
+        );
+        let length = length_squared(moved);
+        if length > 1.0e-7 || length_squared(movement) - length < 1.0e-7 {
+            self.position = self.position + moved;
+        }
+
+        let x_collided = (movement[0] - moved[0]).abs() >= 1.0e-5f32 as f64;
+        let z_collided = (movement[2] - moved[2]).abs() >= 1.0e-5f32 as f64;
+        self.horizontal_collision = x_collided || z_collided;
+        self.minor_horizontal_collision =
+            self.horizontal_collision && self.is_horizontal_collision_minor(moved);
+        let vertical_collision = movement[1] != moved[1];
+        self.on_ground = vertical_collision && movement[1] < 0.0;
+        self.check_supporting_block(moved, view);
+
+        let on_pos = self.on_pos(0.2, view);
+        let on = block(view, on_pos).block();
+        if self.on_ground {
+            self.fall_distance = 0.0;
+        } else if moved[1] < 0.0 {
+            self.fall_distance -= moved[1] as f32;
+        }
+        if x_collided {
+            self.velocity[0] = 0.0;
+        }
+        if z_collided {
+            self.velocity[2] = 0.0;
+        }
+
+        if vertical_collision {
+            // Block.updateEntityMovementAfterFallOn
+            let y = self.velocity[1];
+            let bounce = if on == Block::SLIME_BLOCK {
+                Some(1.0)
+            } else if on.is_in(BlockTag::Beds) {
+                Some(0.66f32 as f64)
+            } else {
+                None
+            };
+            self.velocity[1] = match bounce {
+                Some(bounce) if !input.sneak => {
+                    if y < 0.0 {
+                        -y * bounce
+                    } else {
+                        y
+                    }
+                }
+                _ => 0.0,
+            };
+        }
+        if self.on_ground && on == Block::SLIME_BLOCK && !input.sneak {
+            let y = self.velocity[1].abs();
+            if y < 0.1 {
+                let slow = 0.4 + y * 0.2;
+                self.velocity[0] *= slow;
+                self.velocity[2] *= slow;
+            }
+        }
+
+        let speed = self.block_speed_factor(view) as f64;
+        self.velocity[0] *= speed;
+        self.velocity[2] *= speed;
+    }
+
+    /// `Player.maybeBackOffFromEdge`: a sneaking player on the ground does
+    /// not walk off any edge higher than a step.
+    fn back_off_from_edge(&self, movement: Vec3d, input: &PlayerInput, view: &WorldView) -> Vec3d {
+        if movement[1] > 0.0 || !input.sneak || !self.is_above_ground(view) {
+            return movement;
+        }
+        let step = 0.05;
+        let [mut x, y, mut z] = *movement;
+        let (step_x, step_z) = (signum(x) * step, signum(z) * step);
+        let falls = |x: f64, z: f64| self.can_fall_at_least(x, z, MAX_UP_STEP, view);
+        while x != 0.0 && falls(x, 0.0) {
+            if x.abs() <= step {
+                x = 0.0;
+                break;
+            }
+            x -= step_x;
+        }
+        while z != 0.0 && falls(0.0, z) {
+            if z.abs() <= step {
+                z = 0.0;
+                break;
+            }
+            z -= step_z;
+        }
+        while x != 0.0 && z != 0.0 && falls(x, z) {
+            if x.abs() <= step {
+                x = 0.0;
+            } else {
+                x -= step_x;
+            }
+            if z.abs() <= step {
+                z = 0.0;
+            } else {
+                z -= step_z;
+            }
+        }
+        Vec3d::new(x, y, z)
+    }
+
+    fn is_above_ground(&self, view: &WorldView) -> bool {
+        self.on_ground
+            || self.fall_distance < MAX_UP_STEP
+                && !self.can_fall_at_least(0.0, 0.0, MAX_UP_STEP - self.fall_distance, view)
+    }
+
+    /// Whether the player, moved sideways by `x` and `z`, has nothing under
+    /// it for `distance` blocks.
+    fn can_fall_at_least(&self, x: f64, z: f64, distance: f32, view: &WorldView) -> bool {
+        let bounds = self.bounding_box();
+        let area = Aabb::new(
+            Vec3d::new(
+                bounds.min[0] + x,
+                bounds.min[1] - distance as f64 - 1.0e-5f32 as f64,
+                bounds.min[2] + z,
+            ),
+            Vec3d::new(bounds.max[0] + x, bounds.min[1], bounds.max[2] + z),
+        );
+        no_collision(view, &area)
+    }
+
+    /// `Player.isHorizontalCollisionMinor`: whether the wall was only
+    /// grazed, at under 8 degrees from the direction of the input.
+    fn is_horizontal_collision_minor(&self, moved: Vec3d) -> bool {
+        let yaw = (self.yaw * DEGREES) as f64;
+        let (sin, cos) = (sin(yaw) as f64, cos(yaw) as f64);
+        let [left, _, forward] = *self.impulse;
+        let x = left * cos - forward * sin;
+        let z = forward * cos + left * sin;
+        let wanted = x * x + z * z;
+        let actual = horizontal_length_squared(moved);
+        if wanted < 1.0e-5 || actual < 1.0e-5 {
+            return false;
+        }
+        let dot = x * moved[0] + z * moved[2];
+        let angle = libm::acos(dot / libm::sqrt(wanted * actual));
+        angle < 0.13962634f32 as f64
+    }
+
+    /// `Entity.checkSupportingBlock`: the block the player stands on, when
+    /// on the ground.
+    fn check_supporting_block(&mut self, moved: Vec3d, view: &WorldView) {
+        if !self.on_ground {
+            self.on_ground_no_blocks = false;
+            self.supporting_block = None;
+            return;
+        }
+        let bounds = self.bounding_box();
+        let below = Aabb::new(
+            Vec3d::new(bounds.min[0], bounds.min[1] - 1.0e-6, bounds.min[2]),
+            Vec3d::new(bounds.max[0], bounds.min[1], bounds.max[2]),
+        );
+        let mut supporting = self.find_supporting_block(&below, view);
+        if supporting.is_none() && !self.on_ground_no_blocks {
+            let back = Vec3d::new(-moved[0], 0.0, -moved[2]);
+            supporting = self.find_supporting_block(&below.moved(back), view);
+        }
+        self.supporting_block = supporting;
+        self.on_ground_no_blocks = supporting.is_none();
+    }
+
+    /// The colliding block nearest the player's position, the highest, then
+    /// most southern and eastern one on ties.
+    fn find_supporting_block(&self, area: &Aabb, view: &WorldView) -> Option<BlockPos> {
+        let mut nearest: Option<(BlockPos, f64)> = None;
+        for (pos, _) in colliding_blocks(view, area) {
+            let center = Vec3d::new(pos.x as f64 + 0.5, pos.y as f64 + 0.5, pos.z as f64 + 0.5);
+            let distance = length_squared(center - self.position);
+            let later = |best: BlockPos| (pos.y, pos.z, pos.x) > (best.y, best.z, best.x);
+            if nearest.is_none_or(|(best, best_distance)| {
+                distance < best_distance || distance == best_distance && later(best)
+            }) {
+                nearest = Some((pos, distance));
+            }
+        }
+        nearest.map(|(pos, _)| pos)
+    }
+
+    /// `Entity.getOnPos`: the block `offset` below the feet, counting the
+    /// supporting block's column, and a fence or wall under a short drop.
+    fn on_pos(&self, offset: f32, view: &WorldView) -> BlockPos {
+        let [x, y, z] = *self.position;
+        let Some(pos) = self.supporting_block else {
+            return containing(x, y - offset as f64, z);
+        };
+        if offset <= 1.0e-5 {
+            return pos;
+        }
+        let on = block(view, pos).block();
+        let tall = [BlockTag::Fences, BlockTag::Walls, BlockTag::FenceGates]
+            .into_iter()
+            .any(|tag| on.is_in(tag));
+        if offset <= 0.5 && tall {
+            pos
+        } else {
+            BlockPos::new(pos.x, libm::floor(y - offset as f64) as i32, pos.z)
+        }
+    }
+
+    fn block_pos_below_that_affects_movement(&self, view: &WorldView) -> BlockPos {
+        self.on_pos(0.500001, view)
+    }
+
+    fn block_position(&self) -> BlockPos {
+        let [x, y, z] = *self.position;
+        containing(x, y, z)
+    }
+
+    fn block_speed_factor(&self, view: &WorldView) -> f32 {
+        let here = block(view, self.block_position()).block();
+        if here == Block::WATER || here == Block::BUBBLE_COLUMN {
+            return here.speed_factor();
+        }
+        match here.speed_factor() {
+            1.0 => block(view, self.block_pos_below_that_affects_movement(view))
+                .block()
+                .speed_factor(),
+            factor => factor,
+        }
+    }
+
+    fn block_jump_factor(&self, view: &WorldView) -> f32 {
+        match block(view, self.block_position()).block().jump_factor() {
+            1.0 => block(view, self.block_pos_below_that_affects_movement(view))
+                .block()
+                .jump_factor(),
+            factor => factor,
+        }
+    }
+
+    /// The movement speed attribute, raised by sprinting.
+    fn speed(&self) -> f32 {
+        if self.sprinting {
+            (WALKING_SPEED as f64 * (1.0 + SPRINTING_SPEED_BONUS)) as f32
+        } else {
+            WALKING_SPEED
+        }
+    }
+
+    /// The unit vector the player looks along, `Entity.calculateViewVector`.
+    fn look_angle(&self) -> Vec3d {
+        let pitch = (self.pitch * DEGREES) as f64;
+        let yaw = (-self.yaw * DEGREES) as f64;
+        let (cos_yaw, sin_yaw) = (cos(yaw), sin(yaw));
+        let (cos_pitch, sin_pitch) = (cos(pitch), sin(pitch));
+        Vec3d::new(
+            (sin_yaw * cos_pitch) as f64,
+            -sin_pitch as f64,
+            (cos_yaw * cos_pitch) as f64,
+        )
+    }
+
+    /// Whether the player is in a climbable block, or an open trapdoor on
+    /// top of a ladder facing the same way.
+    fn on_climbable(&self, view: &WorldView) -> bool {
+        let pos = self.block_position();
+        let state = block(view, pos);
+        if state.block().is_in(BlockTag::Climbable) {
+            return true;
+        }
+        if state.block().is_in(BlockTag::Trapdoors) && state.is_open() {
+            let below = block(view, BlockPos::new(pos.x, pos.y - 1, pos.z));
+            return below.block() == Block::LADDER && below.facing() == state.facing();
+        }
+        false
+    }
+
+    /// Whether the player's box, moved by `offset`, touches neither blocks
+    /// nor fluids.
+    fn is_free(&self, view: &WorldView, offset: Vec3d) -> bool {
+        let area = self.bounding_box().moved(offset);
+        no_collision(view, &area) && !contains_any_liquid(view, &area)
+    }
+
+    /// Whether the player fits in `pose` where it stands.
+    fn can_fit(&self, view: &WorldView, pose: PlayerPose) -> bool {
+        let area = pose
+            .bounding_box(self.position)
+            .deflate(1.0e-7, 1.0e-7, 1.0e-7);
+        no_collision(view, &area)
+    }
+
+    /// `Entity.updateInWaterStateAndDoFluidPushing` and
+    /// `updateFluidOnEyes`.
+    fn update_fluids(&mut self, view: &WorldView) {
+        match self.fluid_pushing(Fluid::Water, WATER_PUSH, view) {
+            Some(height) => {
+                self.water_height = height;
+                self.in_water = true;
+                self.fall_distance = 0.0;
+            }
+            None => {
+                self.water_height = 0.0;
+                self.in_water = false;
+            }
+        }
+        self.lava_height = self
+            .fluid_pushing(Fluid::Lava, LAVA_PUSH, view)
+            .unwrap_or(0.0);
+
+        let [x, y, z] = *self.eye_position();
+        let eye = y - 0.11111111f32 as f64;
+        let pos = containing(x, eye, z);
+        let fluid = block(view, pos).fluid_state().fluid;
+        self.eye_in_water = fluid.is_water() && pos.y as f64 + fluid_height(view, pos) as f64 > eye;
+    }
+
+    /// `Entity.updateFluidHeightAndDoFluidPushing`: pushes the player along
+    /// the flow of `fluid` where it touches it, and returns how deep the
+    /// player is in it.
+    fn fluid_pushing(&mut self, fluid: Fluid, scale: f64, view: &WorldView) -> Option<f64> {
+        let area = self.bounding_box().deflate(0.001, 0.001, 0.001);
+        let low = area.min.map(|coord| libm::floor(coord) as i32);
+        let high = area.max.map(|coord| libm::ceil(coord) as i32);
+        let mut depth = 0.0;
+        let mut found = false;
+        let mut push = Vec3d::new(0.0, 0.0, 0.0);
+        let mut count = 0;
+        for x in low[0]..high[0] {
+            for y in low[1]..high[1] {
+                for z in low[2]..high[2] {
+                    let pos = BlockPos::new(x, y, z);
+                    if !block(view, pos).fluid_state().fluid.is_same(fluid) {
+                        continue;
+                    }
+                    let surface = y as f64 + fluid_height(view, pos) as f64;
+                    if surface < area.min[1] {
+                        continue;
+                    }
+                    found = true;
+                    depth = (surface - area.min[1]).max(depth);
+                    let scale = if depth < 0.4 { depth } else { 1.0 };
+                    let flow = fluid_flow(view, pos).map(|coord| coord as f64 * scale);
+                    push = push + Vec3d::from(flow);
+                    count += 1;
+                }
+            }
+        }
+        let length = libm::sqrt(length_squared(push));
+        if length > 0.0 {
+            let mut push = Vec3d::from(push.map(|coord| coord / count as f64 * scale));
+            let length = libm::sqrt(length_squared(push));
+            if self.velocity[0].abs() < 0.003
+                && self.velocity[2].abs() < 0.003
+                && length < 0.0045000000000000005
+            {
+                push = Vec3d::from(push.map(|coord| coord / length * 0.0045000000000000005));
+            }
+            self.velocity = self.velocity + push;
+        }
+        found.then_some(depth)
+    }
+
+    /// `Entity.updateSwimming`: sprinting under water starts swimming, and
+    /// it lasts while sprinting in water.
+    fn update_swimming(&mut self, view: &WorldView) {
+        self.swimming = if self.swimming {
+            self.sprinting && self.in_water
+        } else {
+            self.sprinting
+                && self.under_water
+                && block(view, self.block_position())
+                    .fluid_state()
+                    .fluid
+                    .is_water()
+        };
+    }
+
+    /// `Player.updatePlayerPose`: the pose the player wants, or the largest
+    /// that fits.
+    fn update_pose(&mut self, input: &PlayerInput, view: &WorldView) {
+        if !self.can_fit(view, PlayerPose::Swimming) {
+            return;
+        }
+        let pose = if self.fall_flying {
+            PlayerPose::FallFlying
+        } else if self.swimming {
+            PlayerPose::Swimming
+        } else if input.sneak {
+            PlayerPose::Crouching
+        } else {
+            PlayerPose::Standing
+        };
+        self.pose = if self.can_fit(view, pose) {
+            pose
+        } else if self.can_fit(view, PlayerPose::Crouching) {
+            PlayerPose::Crouching
+        } else {
+            PlayerPose::Swimming
+        };
+    }
+
+    /// `LocalPlayer.sendPosition`: the sprint command and move packet due,
+    /// if anything changed enough to be worth sending.
+    fn send_position(&mut self, commands: &mut Vec<PlayerCommand>) -> Option<MovePacket> {
+        if self.sprinting != self.sent.sprinting {
+            commands.push(if self.sprinting {
+                PlayerCommand::StartSprinting
+            } else {
+                PlayerCommand::StopSprinting
+            });
+            self.sent.sprinting = self.sprinting;
+        }
+
+        self.sent.position_reminder += 1;
+        let moved = length_squared(self.position - self.sent.position) > 2.0e-4 * 2.0e-4
+            || self.sent.position_reminder >= 20;
+        let turned = self.yaw != self.sent.yaw || self.pitch != self.sent.pitch;
+        let (position, on_ground, horizontal_collision) =
+            (self.position, self.on_ground, self.horizontal_collision);
+        let (yaw, pitch) = (self.yaw, self.pitch);
+        let packet = if moved && turned {
+            Some(MovePacket::PosRot {
+                position,
+                yaw,
+                pitch,
+                on_ground,
+                horizontal_collision,
+            })
+        } else if moved {
+            Some(MovePacket::Pos {
+                position,
+                on_ground,
+                horizontal_collision,
+            })
+        } else if turned {
+            Some(MovePacket::Rot {
+                yaw,
+                pitch,
+                on_ground,
+                horizontal_collision,
+            })
+        } else if self.sent.on_ground != on_ground
+            || self.sent.horizontal_collision != horizontal_collision
+        {
+            Some(MovePacket::StatusOnly {
+                on_ground,
+                horizontal_collision,
+            })
+        } else {
+            None
+        };
+
+        if moved {
+            self.sent.position = position;
+            self.sent.position_reminder = 0;
+        }
+        if turned {
+            self.sent.yaw = yaw;
+            self.sent.pitch = pitch;
+        }
+        self.sent.on_ground = on_ground;
+        self.sent.horizontal_collision = horizontal_collision;
+        packet
+    }
+}
+
+/// 1, -1 or 0 for a pair of opposing keys.
+fn impulse(positive: bool, negative: bool) -> f32 {
+    match (positive, negative) {
+        (true, false) => 1.0,
+        (false, true) => -1.0,
+        _ => 0.0,
+    }
+}
+
+fn signum(value: f64) -> f64 {
+    if value > 0.0 {
+        1.0
+    } else if value < 0.0 {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+/// `Mth.sin`, read from a table of 65536 samples as vanilla does.
+fn sin(radians: f64) -> f32 {
+    table_sin((radians * 10430.378350470453) as i64)
+}
+
+/// `Mth.cos`, a quarter turn along the same table.
+fn cos(radians: f64) -> f32 {
+    table_sin((radians * 10430.378350470453 + 16384.0) as i64)
+}
+
+fn table_sin(index: i64) -> f32 {
+    libm::sin((index & 0xffff) as f64 * core::f64::consts::PI * 2.0 / 65536.0) as f32
+}
+
+fn containing(x: f64, y: f64, z: f64) -> BlockPos {
+    BlockPos::new(
+        libm::floor(x) as i32,
+        libm::floor(y) as i32,
+        libm::floor(z) as i32,
+    )
+}
+
+fn block(view: &WorldView, pos: BlockPos) -> BlockState {
+    view.get_block(pos).unwrap_or(Block::AIR.default_state())
+}
+
+/// `FluidState.getHeight`: full under more of the same fluid.
+fn fluid_height(view: &WorldView, pos: BlockPos) -> f32 {
+    let fluid = block(view, pos).fluid_state();
+    let above = block(view, BlockPos::new(pos.x, pos.y + 1, pos.z)).fluid_state();
+    if !fluid.is_empty() && above.fluid.is_same(fluid.fluid) {
+        1.0
+    } else {
+        fluid.own_height()
+    }
+}
+
+fn contains_any_liquid(view: &WorldView, area: &Aabb) -> bool {
+    let low = area.min.map(|coord| libm::floor(coord) as i32);
+    let high = area.max.map(|coord| libm::ceil(coord) as i32);
+    (low[0]..high[0]).any(|x| {
+        (low[1]..high[1]).any(|y| {
+            (low[2]..high[2]).any(|z| !block(view, BlockPos::new(x, y, z)).fluid_state().is_empty())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::ChunkPos;
+
+    use super::*;
+    use crate::{BiomeId, ChunkData, ChunkSection, LightUpdate, World, WorldEvent, WorldHeight};
+
+    const HEIGHT: WorldHeight = WorldHeight {
+        min_y: 0,
+        height: 32,
+    };
+
+    const IDLE: PlayerInput = PlayerInput {
+        forward: false,
+        backward: false,
+        left: false,
+        right: false,
+        jump: false,
+        sneak: false,
+        sprint: false,
+    };
+
+    /// A chunk with a floor of `floor` whose top is at y = 5.
+    fn world(floor: &str) -> World {
+        let mut world = World::new();
+        world.apply(WorldEvent::ChunkLoaded {
+            pos: ChunkPos::new(0, 0),
+            chunk: ChunkData {
+                height: HEIGHT,
+                sections: (0..HEIGHT.section_count())
+                    .map(|_| ChunkSection::empty(BiomeId(0)))
+                    .collect(),
+                heightmaps: Vec::new(),
+                block_entities: Vec::new(),
+                light: LightUpdate::default(),
+            },
+        });
+        for x in 0..16 {
+            for z in 0..16 {
+                set(&mut world, x, 4, z, floor);
+            }
+        }
+        world
+    }
+
+    fn set(world: &mut World, x: i32, y: i32, z: i32, state: &str) {
+        world.apply(WorldEvent::BlockSet {
+            pos: BlockPos::new(x, y, z),
+            state: state.parse().unwrap(),
+        });
+    }
+
+    /// A player that has settled on the floor, facing south.
+    fn standing(world: &World, x: f64, z: f64) -> PlayerPhysics {
+        let mut player = PlayerPhysics::new(Vec3d::new(x, 5.0, z), 0.0, 0.0);
+        for _ in 0..4 {
+            player.tick(&IDLE, &world.view());
+        }
+        assert!(player.on_ground);
+        player
+    }
+
+    /// Runs `ticks` ticks of `input` and returns the distance moved in the
+    /// last one.
+    fn run(player: &mut PlayerPhysics, world: &World, input: PlayerInput, ticks: usize) -> Vec3d {
+        let mut last = player.position;
+        for _ in 0..ticks {
+            last = player.position;
+            player.tick(&input, &world.view());
+        }
+        player.position - last
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_jump_trajectory() {
+        let world = world("stone");
+        let mut player = standing(&world, 8.5, 8.5);
+        let jump = PlayerInput { jump: true, ..IDLE };
+        // heights above the floor after each tick of a standing jump
+        let heights = [
+            0.41999998688697815,
+            0.7531999805212015,
+            1.001335979112147,
+            1.1661092609382138,
+            1.249187078744681,
+            1.2522033402537236,
+            1.1767592750642368,
+            1.0244240882136797,
+            0.7967356006686916,
+            0.4952008770059111,
+            0.12129684053918943,
+            0.0,
+        ];
+        for (tick, height) in heights.into_iter().enumerate() {
+            player.tick(if tick == 0 { &jump } else { &IDLE }, &world.view());
+            assert_close(player.position[1] - 5.0, height);
+        }
+        assert!(player.on_ground);
+    }
+
+    #[test]
+    fn test_walking_speeds() {
+        let world = world("stone");
+        let forward = PlayerInput {
+            forward: true,
+            ..IDLE
+        };
+        // 4.317, 5.612 and 1.295 blocks per second at full speed
+        let mut player = standing(&world, 8.5, 0.5);
+        let moved = run(&mut player, &world, forward, 30);
+        assert!((moved[2] - 0.2158590).abs() < 1e-6);
+        let sprint = PlayerInput {
+            sprint: true,
+            ..forward
+        };
+        let moved = run(&mut player, &world, sprint, 20);
+        assert!(player.sprinting);
+        assert!((moved[2] - 0.2806168).abs() < 1e-6);
+        let sneak = PlayerInput {
+            sneak: true,
+            ..forward
+        };
+        let moved = run(&mut player, &world, sneak, 25);
+        assert!(!player.sprinting);
+        assert_eq!(player.pose, PlayerPose::Crouching);
+        assert!((moved[2] - 0.0647577).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_block_friction() {
+        // the player slides further on ice, and barely at all on soul sand
+        let coast = |floor: &str| {
+            let world = world(floor);
+            let mut player = standing(&world, 8.5, 0.5);
+            let forward = PlayerInput {
+                forward: true,
+                ..IDLE
+            };
+            run(&mut player, &world, forward, 10);
+            let start = player.position[2];
+            run(&mut player, &world, IDLE, 20);
+            player.position[2] - start
+        };
+        let stone = coast("stone");
+        assert!(coast("ice") > stone * 4.0);
+        assert!(coast("soul_sand") < stone / 2.0);
+
+        // and honey halves the jump
+        let world = world("honey_block");
+        let mut player = standing(&world, 8.5, 8.5);
+        let jump = PlayerInput { jump: true, ..IDLE };
+        let top = (0..10)
+            .map(|tick| {
+                player.tick(if tick == 0 { &jump } else { &IDLE }, &world.view());
+                player.position[1]
+            })
+            .fold(0.0, f64::max);
+        assert!(top - 5.0 < 0.5);
+    }
+
+    #[test]
+    fn test_sneaking_at_an_edge() {
+        let world = world("stone");
+        let mut player = standing(&world, 8.5, 14.5);
+        let sneak = PlayerInput {
+            forward: true,
+            sneak: true,
+            ..IDLE
+        };
+        run(&mut player, &world, sneak, 40);
+        assert!(player.on_ground);
+        assert_eq!(player.position[1], 5.0);
+        // hanging over the edge, but not past it
+        let bounds = player.bounding_box();
+        assert!(bounds.max[2] > 16.0 && bounds.min[2] < 16.0);
+
+        let walk = PlayerInput {
+            forward: true,
+            ..IDLE
+        };
+        run(&mut player, &world, walk, 10);
+        assert!(player.position[1] < 5.0);
+    }
+
+    #[test]
+    fn test_climbing_a_ladder() {
+        let mut world = world("stone");
+        for y in 5..12 {
+            set(&mut world, 8, y, 9, "stone");
+            set(
+                &mut world,
+                8,
+                y,
+                8,
+                "ladder[facing=north,waterlogged=false]",
+            );
+        }
+        let mut player = standing(&world, 8.5, 8.5);
+        let forward = PlayerInput {
+            forward: true,
+            ..IDLE
+        };
+        // 2.35 blocks per second upwards
+        let moved = run(&mut player, &world, forward, 20);
+        assert_close(moved[1], 0.11760000228881837);
+        // holding sneak stops sliding down
+        let sneak = PlayerInput {
+            sneak: true,
+            ..IDLE
+        };
+        run(&mut player, &world, sneak, 10);
+        let height = player.position[1];
+        run(&mut player, &world, sneak, 10);
+        assert_eq!(player.position[1], height);
+        run(&mut player, &world, IDLE, 10);
+        assert!(player.position[1] < height - 1.0);
+    }
+
+    #[test]
+    fn test_water() {
+        let mut world = world("stone");
+        for x in 0..16 {
+            for z in 0..16 {
+                for y in 5..10 {
+                    set(&mut world, x, y, z, "water[level=0]");
+                }
+            }
+        }
+        let mut player = PlayerPhysics::new(Vec3d::new(8.5, 7.0, 8.5), 0.0, 0.0);
+        let sinking = run(&mut player, &world, IDLE, 10);
+        assert!(player.is_in_water());
+        assert!(sinking[1] < 0.0 && sinking[1] > -0.05);
+        let jump = PlayerInput { jump: true, ..IDLE };
+        let rising = run(&mut player, &world, jump, 10);
+        assert!(rising[1] > 0.0);
+
+        // sprinting under water swims, in the swimming pose
+        let mut player = PlayerPhysics::new(Vec3d::new(8.5, 5.0, 0.5), 0.0, 0.0);
+        let sprint = PlayerInput {
+            forward: true,
+            sprint: true,
+            ..IDLE
+        };
+        run(&mut player, &world, sprint, 5);
+        assert!(player.is_swimming());
+        assert_eq!(player.pose, PlayerPose::Swimming);
+    }
+
+    #[test]
+    fn test_elytra() {
+        let world = world("stone");
+        let mut player = PlayerPhysics::new(Vec3d::new(8.5, 12.0, 0.5), 0.0, 0.0);
+        player.elytra = true;
+        run(&mut player, &world, IDLE, 3);
+        let jump = PlayerInput { jump: true, ..IDLE };
+        let tick = player.tick(&jump, &world.view());
+        assert_eq!(tick.commands, [PlayerCommand::StartFallFlying]);
+        assert!(player.fall_flying);
+        assert_eq!(player.pose, PlayerPose::FallFlying);
+        // gliding level turns the fall into forward speed
+        let moved = run(&mut player, &world, IDLE, 30);
+        assert!(moved[2] > 0.4);
+        assert!(moved[1] > -0.2);
+        run(&mut player, &world, IDLE, 20);
+        assert!(!player.fall_flying);
+    }
+
+    #[test]
+    fn test_movement_packets() {
+        let world = world("stone");
+        let view = world.view();
+        let mut player = PlayerPhysics::new(Vec3d::new(8.5, 5.0, 8.5), 0.0, 0.0);
+        // landing on the first tick of falling
+        assert_eq!(player.tick(&IDLE, &view), PlayerTick::default());
+        let tick = player.tick(&IDLE, &view);
+        assert_eq!(
+            tick.movement,
+            Some(MovePacket::StatusOnly {
+                on_ground: true,
+                horizontal_collision: false,
+            })
+        );
+        // standing still, the position is only repeated once a second
+        let sent = (1..40)
+            .filter(|_| player.tick(&IDLE, &view).movement.is_some())
+            .count();
+        assert_eq!(sent, 2);
+
+        player.yaw = 90.0;
+        let tick = player.tick(&IDLE, &view);
+        assert!(matches!(
+            tick.movement,
+            Some(MovePacket::Rot { yaw: 90.0, .. })
+        ));
+
+        let forward = PlayerInput {
+            forward: true,
+            ..IDLE
+        };
+        player.yaw = 0.0;
+        let tick = player.tick(&forward, &view);
+        assert_eq!(tick.input, Some(forward));
+        assert!(matches!(tick.movement, Some(MovePacket::PosRot { .. })));
+        let tick = player.tick(&forward, &view);
+        assert_eq!(tick.input, None);
+        assert!(matches!(tick.movement, Some(MovePacket::Pos { .. })));
+    }
+}
@@ -0,0 +1,339 @@
+//! Raycasting against the blocks of a [`WorldView`], vanilla's
+//! `BlockGetter.clip` with a `ClipContext`.
+
+use mcre_core::{Aabb, BlockPos, Direction, FluidState, Vec3d};
+
+use crate::{Block, VoxelShape, WorldView};
+
+/// Which shape of each block a ray is tested against, vanilla's
+/// `ClipContext.Block`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClipBlock {
+    /// The shape entities collide with.
+    Collider,
+    /// The shape selected by looking at the block.
+    Outline,
+}
+
+/// Which fluids a ray stops at, vanilla's `ClipContext.Fluid`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClipFluid {
+    None,
+    SourceOnly,
+    Any,
+}
+
+impl ClipFluid {
+    fn can_pick(self, fluid: FluidState) -> bool {
+        match self {
+            Self::None => false,
+            Self::SourceOnly => fluid.is_source(),
+            Self::Any => !fluid.is_empty(),
+        }
+    }
+}
+
+/// A ray from `from` to `to`, and what it stops at.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ClipContext {
+    pub from: Vec3d,
+    pub to: Vec3d,
+    pub block: ClipBlock,
+    pub fluid: ClipFluid,
+}
+
+impl ClipContext {
+    pub fn new(from: Vec3d, to: Vec3d, block: ClipBlock, fluid: ClipFluid) -> Self {
+        Self {
+            from,
+            to,
+            block,
+            fluid,
+        }
+    }
+}
+
+/// Where a ray hit a block.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BlockHitResult {
+    pub pos: BlockPos,
+    /// The face of the block that was hit.
+    pub face: Direction,
+    pub location: Vec3d,
+    /// Whether the ray started inside the block's shape.
+    pub inside: bool,
+}
+
+impl WorldView<'_> {
+    /// The first block along the ray, or `None` if it reaches its end
+    /// without hitting one. Unloaded blocks are passed through as air.
+    ///
+    /// Blocks are visited in the order the ray crosses them, so a hit on a
+    /// shape that reaches into the next block is only found once the ray
+    /// gets there, as in vanilla.
+    pub fn clip(&self, context: &ClipContext) -> Option<BlockHitResult> {
+        let ClipContext { from, to, .. } = *context;
+        if from == to {
+            return None;
+        }
+        // nudged outwards so a ray starting or ending on a block boundary
+        // still visits the blocks on both sides
+        let lerp = |delta: f64, start: f64, end: f64| start + delta * (end - start);
+        let end: [f64; 3] = core::array::from_fn(|i| lerp(-1e-7, to[i], from[i]));
+        let start: [f64; 3] = core::array::from_fn(|i| lerp(-1e-7, from[i], to[i]));
+        let mut pos = start.map(|coord| libm::floor(coord) as i32);
+        if let Some(hit) = self.clip_block(context, BlockPos::new(pos[0], pos[1], pos[2])) {
+            return Some(hit);
+        }
+
+        let delta: [f64; 3] = core::array::from_fn(|i| end[i] - start[i]);
+        let sign = delta.map(|delta| {
+            if delta > 0.0 {
+                1
+            } else if delta < 0.0 {
+                -1
+            } else {
+                0
+            }
+        });
+        // the fraction of the ray it takes to cross a whole block, and to
+        // reach the next block boundary, on each axis
+        let step: [f64; 3] = core::array::from_fn(|i| {
+            if sign[i] == 0 {
+                f64::MAX
+            } else {
+                sign[i] as f64 / delta[i]
+            }
+        });
+        let mut next: [f64; 3] = core::array::from_fn(|i| {
+            let frac = start[i] - libm::floor(start[i]);
+            step[i] * if sign[i] > 0 { 1.0 - frac } else { frac }
+        });
+        while next.iter().any(|next| *next <= 1.0) {
+            let axis = if next[0] < next[1] {
+                if next[0] < next[2] { 0 } else { 2 }
+            } else if next[1] < next[2] {
+                1
+            } else {
+                2
+            };
+            pos[axis] += sign[axis];
+            next[axis] += step[axis];
+            if let Some(hit) = self.clip_block(context, BlockPos::new(pos[0], pos[1], pos[2])) {
+                return Some(hit);
+            }
+        }
+        None
+    }
+
+    /// The hit on the block at `pos` or its fluid, whichever the ray meets
+    /// first, preferring the block.
+    fn clip_block(&self, context: &ClipContext, pos: BlockPos) -> Option<BlockHitResult> {
+        let state = self.get_block(pos).unwrap_or(Block::AIR.default_state());
+        let shape = match context.block {
+            ClipBlock::Collider => state.collision_shape(pos),
+            ClipBlock::Outline => state.outline_shape(pos),
+        };
+        let block_hit = shape.clip(context.from, context.to, pos);
+        let fluid = state.fluid_state();
+        let fluid_hit = if context.fluid.can_pick(fluid) {
+            self.fluid_shape(fluid, pos)
+                .clip(context.from, context.to, pos)
+        } else {
+            None
+        };
+        let distance = |hit: &Option<BlockHitResult>| {
+            hit.map_or(f64::MAX, |hit| {
+                let offset = hit.location - context.from;
+                offset[0] * offset[0] + offset[1] * offset[1] + offset[2] * offset[2]
+            })
+        };
+        if distance(&block_hit) <= distance(&fluid_hit) {
+            block_hit
+        } else {
+            fluid_hit
+        }
+    }
+
+    /// The box a fluid fills, full height under more of the same fluid.
+    fn fluid_shape(&self, fluid: FluidState, pos: BlockPos) -> VoxelShape {
+        let above = self
+            .get_block(pos.relative(Direction::Up))
+            .map(|state| state.fluid_state().fluid);
+        let height = if above.is_some_and(|above| above.is_same(fluid.fluid)) {
+            1.0
+        } else {
+            fluid.own_height() as f64
+        };
+        VoxelShape::new(alloc::vec![Aabb::new(
+            Vec3d::new(0.0, 0.0, 0.0),
+            Vec3d::new(1.0, height, 1.0),
+        )])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use mcre_core::ChunkPos;
+
+    use super::*;
+    use crate::{BiomeId, ChunkData, ChunkSection, LightUpdate, World, WorldEvent, WorldHeight};
+
+    const HEIGHT: WorldHeight = WorldHeight {
+        min_y: 0,
+        height: 32,
+    };
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.apply(WorldEvent::ChunkLoaded {
+            pos: ChunkPos::new(0, 0),
+            chunk: ChunkData {
+                height: HEIGHT,
+                sections: (0..HEIGHT.section_count())
+                    .map(|_| ChunkSection::empty(BiomeId(0)))
+                    .collect(),
+                heightmaps: Vec::new(),
+                block_entities: Vec::new(),
+                light: LightUpdate::default(),
+            },
+        });
+        world
+    }
+
+    fn set(world: &mut World, x: i32, y: i32, z: i32, state: &str) {
+        world.apply(WorldEvent::BlockSet {
+            pos: BlockPos::new(x, y, z),
+            state: state.parse().unwrap(),
+        });
+    }
+
+    fn clip(
+        world: &World,
+        from: [f64; 3],
+        to: [f64; 3],
+        fluid: ClipFluid,
+    ) -> Option<BlockHitResult> {
+        let [x0, y0, z0] = from;
+        let [x1, y1, z1] = to;
+        world.view().clip(&ClipContext::new(
+            Vec3d::new(x0, y0, z0),
+            Vec3d::new(x1, y1, z1),
+            ClipBlock::Outline,
+            fluid,
+        ))
+    }
+
+    #[test]
+    fn test_clip_faces() {
+        let mut world = world();
+        set(&mut world, 8, 5, 8, "stone");
+        let hit = clip(&world, [8.5, 10.5, 8.5], [8.5, 0.5, 8.5], ClipFluid::None).unwrap();
+        assert_eq!(hit.pos, BlockPos::new(8, 5, 8));
+        assert_eq!(hit.face, Direction::Up);
+        assert_eq!(*hit.location, [8.5, 6.0, 8.5]);
+        assert!(!hit.inside);
+
+        let hit = clip(
+            &world,
+            [2.0, 5.25, 8.75],
+            [12.0, 5.25, 8.75],
+            ClipFluid::None,
+        )
+        .unwrap();
+        assert_eq!(hit.face, Direction::West);
+        assert_eq!(*hit.location, [8.0, 5.25, 8.75]);
+
+        // too short to reach it
+        assert_eq!(
+            clip(&world, [8.5, 10.5, 8.5], [8.5, 6.5, 8.5], ClipFluid::None),
+            None
+        );
+        assert_eq!(
+            clip(&world, [8.5, 10.5, 8.5], [8.5, 10.5, 8.5], ClipFluid::None),
+            None
+        );
+        // unloaded blocks are air
+        assert_eq!(
+            clip(&world, [-8.5, 10.5, 8.5], [-8.5, 0.5, 8.5], ClipFluid::None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_clip_edges() {
+        let mut world = world();
+        set(&mut world, 8, 5, 8, "stone");
+        // grazing the top face does not hit it
+        assert_eq!(
+            clip(&world, [6.0, 6.0, 8.5], [12.0, 6.0, 8.5], ClipFluid::None),
+            None
+        );
+        // straight through the vertical edge between the west and north
+        // faces, which counts as the west face
+        let hit = clip(&world, [7.0, 5.5, 7.0], [9.0, 5.5, 9.0], ClipFluid::None).unwrap();
+        assert_eq!(hit.pos, BlockPos::new(8, 5, 8));
+        assert_eq!(hit.face, Direction::West);
+        assert_eq!(*hit.location, [8.0, 5.5, 8.0]);
+    }
+
+    #[test]
+    fn test_clip_inside() {
+        let mut world = world();
+        set(&mut world, 8, 5, 8, "stone");
+        let hit = clip(&world, [8.5, 5.5, 8.5], [8.5, 5.5, 12.5], ClipFluid::None).unwrap();
+        assert!(hit.inside);
+        assert_eq!(hit.pos, BlockPos::new(8, 5, 8));
+        assert_eq!(hit.face, Direction::North);
+        assert!((hit.location[2] - 8.504).abs() < 1e-9);
+
+        // starting on the face, heading out, is not inside
+        assert_eq!(
+            clip(&world, [8.5, 6.0, 8.5], [8.5, 9.0, 8.5], ClipFluid::None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_clip_shapes_and_fluids() {
+        let mut world = world();
+        set(
+            &mut world,
+            8,
+            5,
+            8,
+            "stone_slab[type=bottom,waterlogged=false]",
+        );
+        let hit = clip(&world, [8.5, 10.5, 8.5], [8.5, 0.5, 8.5], ClipFluid::None).unwrap();
+        assert_eq!(*hit.location, [8.5, 5.5, 8.5]);
+
+        set(&mut world, 8, 6, 8, "short_grass");
+        let hit = clip(&world, [8.5, 10.5, 8.5], [8.5, 0.5, 8.5], ClipFluid::None).unwrap();
+        assert_eq!(hit.pos, BlockPos::new(8, 6, 8));
+        let hit = world
+            .view()
+            .clip(&ClipContext::new(
+                Vec3d::new(8.5, 10.5, 8.5),
+                Vec3d::new(8.5, 0.5, 8.5),
+                ClipBlock::Collider,
+                ClipFluid::None,
+            ))
+            .unwrap();
+        assert_eq!(hit.pos, BlockPos::new(8, 5, 8));
+
+        set(&mut world, 8, 6, 8, "water[level=0]");
+        let down = ([8.5, 10.5, 8.5], [8.5, 0.5, 8.5]);
+        let hit = clip(&world, down.0, down.1, ClipFluid::None).unwrap();
+        assert_eq!(hit.pos, BlockPos::new(8, 5, 8));
+        let hit = clip(&world, down.0, down.1, ClipFluid::SourceOnly).unwrap();
+        assert_eq!(hit.pos, BlockPos::new(8, 6, 8));
+        assert!((hit.location[1] - (6.0 + 8.0 / 9.0)).abs() < 1e-6);
+
+        set(&mut world, 8, 6, 8, "water[level=3]");
+        let hit = clip(&world, down.0, down.1, ClipFluid::SourceOnly).unwrap();
+        assert_eq!(hit.pos, BlockPos::new(8, 5, 8));
+        let hit = clip(&world, down.0, down.1, ClipFluid::Any).unwrap();
+        assert_eq!(hit.pos, BlockPos::new(8, 6, 8));
+    }
+}
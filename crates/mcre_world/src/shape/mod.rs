@@ -0,0 +1,249 @@
+//! Block outline, collision and occlusion shapes, vanilla's `VoxelShape`,
+//! read from the generated tables.
+
+use alloc::{vec, vec::Vec};
+use mcre_core::{Aabb, Axis, BlockPos, Direction, Vec3d};
+
+use crate::{BlockHitResult, BlockState};
+
+/// A shape made of boxes, in block units relative to the block's origin.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VoxelShape {
+    boxes: Vec<Aabb>,
+}
+
+impl VoxelShape {
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// The full cube from 0 to 1.
+    pub fn block() -> Self {
+        Self::new(vec![Aabb::of_block(BlockPos::ZERO)])
+    }
+
+    /// A shape from boxes, which may overlap.
+    pub fn new(boxes: Vec<Aabb>) -> Self {
+        Self { boxes }
+    }
+
+    fn from_bounds(boxes: &[[f64; 6]]) -> Self {
+        Self::new(
+            boxes
+                .iter()
+                .map(|&[x0, y0, z0, x1, y1, z1]| {
+                    Aabb::new(Vec3d::new(x0, y0, z0), Vec3d::new(x1, y1, z1))
+                })
+                .collect(),
+        )
+    }
+
+    pub fn boxes(&self) -> &[Aabb] {
+        &self.boxes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    pub fn moved(mut self, offset: Vec3d) -> Self {
+        for shape in &mut self.boxes {
+            *shape = shape.moved(offset);
+        }
+        self
+    }
+
+    /// Whether a point relative to the block's origin lies within the
+    /// shape, counting the low faces of each box but not the high ones.
+    pub fn contains(&self, point: Vec3d) -> bool {
+        self.boxes.iter().any(|shape| shape.contains(point))
+    }
+
+    /// The sorted, distinct bounds of the shape's boxes along `axis`,
+    /// vanilla's `getCoords`.
+    pub fn coords(&self, axis: Axis) -> Vec<f64> {
+        let mut coords: Vec<f64> = self
+            .boxes
+            .iter()
+            .flat_map(|shape| [axis.select(shape.min), axis.select(shape.max)])
+            .collect();
+        coords.sort_by(f64::total_cmp);
+        coords.dedup();
+        coords
+    }
+
+    /// How far `moving` can go along `axis`, up to `distance`, before it
+    /// runs into the shape.
+    pub fn collide(&self, axis: Axis, moving: &Aabb, distance: f64) -> f64 {
+        if distance.abs() < 1e-7 {
+            return 0.0;
+        }
+        self.boxes.iter().fold(distance, |distance, shape| {
+            shape.collide(axis, moving, distance)
+        })
+    }
+
+    /// The first point where the segment from `from` to `to` enters this
+    /// shape placed at `pos`, vanilla's `VoxelShape.clip`.
+    ///
+    /// A segment that starts inside the shape hits it just past `from`, on
+    /// the face it is heading away from, with `inside` set.
+    pub fn clip(&self, from: Vec3d, to: Vec3d, pos: BlockPos) -> Option<BlockHitResult> {
+        if self.is_empty() {
+            return None;
+        }
+        let delta = to - from;
+        if length_squared(delta) < 1e-7 {
+            return None;
+        }
+        let start = Vec3d::new(
+            from[0] + delta[0] * 0.001,
+            from[1] + delta[1] * 0.001,
+            from[2] + delta[2] * 0.001,
+        );
+        let origin = Vec3d::new(pos.x as f64, pos.y as f64, pos.z as f64);
+        if self.contains(start - origin) {
+            return Some(BlockHitResult {
+                pos,
+                face: nearest_direction(delta).opposite(),
+                location: start,
+                inside: true,
+            });
+        }
+        // the nearest box, the first listed on a tie
+        let mut nearest: Option<(Direction, f64)> = None;
+        for shape in &self.boxes {
+            if let Some((face, t)) = shape.moved(origin).clip_face(from, to)
+                && nearest.is_none_or(|(_, nearest)| t < nearest)
+            {
+                nearest = Some((face, t));
+            }
+        }
+        nearest.map(|(face, t)| BlockHitResult {
+            pos,
+            face,
+            location: Vec3d::new(
+                from[0] + delta[0] * t,
+                from[1] + delta[1] * t,
+                from[2] + delta[2] * t,
+            ),
+            inside: false,
+        })
+    }
+}
+
+impl BlockState {
+    /// The shape selected by looking at the block, including its random
+    /// offset at `pos`.
+    pub fn outline_shape(self, pos: BlockPos) -> VoxelShape {
+        self.offset_shape(self.outline_boxes(), pos)
+    }
+
+    /// The shape entities collide with, including its random offset at
+    /// `pos`.
+    pub fn collision_shape(self, pos: BlockPos) -> VoxelShape {
+        self.offset_shape(self.collision_boxes(), pos)
+    }
+
+    /// The shape whose faces block light, vanilla's `getOcclusionShape`,
+    /// which is never offset.
+    pub fn occlusion_shape(self) -> VoxelShape {
+        VoxelShape::from_bounds(self.occlusion_boxes())
+    }
+
+    fn offset_shape(self, boxes: &[[f64; 6]], pos: BlockPos) -> VoxelShape {
+        let (x, y, z) = self.offset_type().offset(
+            pos,
+            self.max_horizontal_offset(),
+            self.max_vertical_offset(),
+        );
+        VoxelShape::from_bounds(boxes).moved(Vec3d::new(x, y, z))
+    }
+}
+
+fn length_squared(vec: Vec3d) -> f64 {
+    vec[0] * vec[0] + vec[1] * vec[1] + vec[2] * vec[2]
+}
+
+/// The direction closest to `vec`, vanilla's `Direction.getApproximateNearest`.
+pub(crate) fn nearest_direction(vec: Vec3d) -> Direction {
+    let mut nearest = Direction::North;
+    let mut max = 0.0;
+    for direction in Direction::ALL {
+        let step = direction.step();
+        let dot = vec[0] * step[0] as f64 + vec[1] * step[1] as f64 + vec[2] * step[2] as f64;
+        if dot > max {
+            max = dot;
+            nearest = direction;
+        }
+    }
+    nearest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> BlockState {
+        s.parse().unwrap()
+    }
+
+    fn aabb(min: [f64; 3], max: [f64; 3]) -> Aabb {
+        Aabb::new(
+            Vec3d::new(min[0], min[1], min[2]),
+            Vec3d::new(max[0], max[1], max[2]),
+        )
+    }
+
+    #[test]
+    fn test_block_shapes() {
+        let origin = BlockPos::ZERO;
+        assert_eq!(parse("stone").outline_shape(origin), VoxelShape::block());
+        assert!(parse("air").outline_shape(origin).is_empty());
+        assert_eq!(
+            parse("stone_slab[type=top]").collision_shape(origin),
+            VoxelShape::new(vec![aabb([0.0, 0.5, 0.0], [1.0, 1.0, 1.0])])
+        );
+        // grass is selectable but has nothing to stand on
+        let grass = parse("short_grass");
+        assert!(!grass.outline_shape(origin).is_empty());
+        assert!(grass.collision_shape(origin).is_empty());
+        // snow collides one layer below its outline
+        let snow = parse("snow[layers=3]");
+        assert_eq!(snow.outline_shape(origin).boxes()[0].max[1], 6.0 / 16.0);
+        assert_eq!(snow.collision_shape(origin).boxes()[0].max[1], 4.0 / 16.0);
+        // fences are jumped over at a block and a half
+        let fence = parse("oak_fence[north=true,east=false,south=false,west=false]");
+        assert_eq!(fence.outline_shape(origin).boxes().len(), 2);
+        assert_eq!(fence.collision_shape(origin).boxes()[0].max[1], 1.5);
+        let door = parse("oak_door[facing=north,open=false,hinge=left,half=lower]");
+        assert_eq!(
+            door.outline_shape(origin),
+            VoxelShape::new(vec![aabb([0.0, 0.0, 13.0 / 16.0], [1.0, 1.0, 1.0])])
+        );
+    }
+
+    #[test]
+    fn test_offset_shapes() {
+        let flower = parse("poppy");
+        let shape = flower.outline_shape(BlockPos::new(3, 0, 7));
+        let flower_box = shape.boxes()[0];
+        assert_eq!(flower_box.size(Axis::X), 6.0 / 16.0);
+        assert_eq!(flower_box.size(Axis::Z), 6.0 / 16.0);
+        assert!(flower_box.min[0] >= 0.0 && flower_box.max[2] <= 1.0);
+        // the same flower anywhere in the column is offset the same way
+        assert_eq!(flower.outline_shape(BlockPos::new(3, 40, 7)), shape);
+
+        // dripstone narrows from its base to its tip
+        let width = |thickness: &str| {
+            let state = parse(&alloc::format!(
+                "pointed_dripstone[thickness={thickness},vertical_direction=up,waterlogged=false]"
+            ));
+            state.outline_shape(BlockPos::ZERO).boxes()[0].size(Axis::X) * 16.0
+        };
+        assert_eq!(width("base"), 12.0);
+        assert_eq!(width("middle"), 10.0);
+        assert_eq!(width("frustum"), 8.0);
+        assert_eq!(width("tip"), 6.0);
+    }
+}
@@ -0,0 +1,72 @@
+//! Block state ids of other versions of the game, for servers that number
+//! states differently from the version the data was generated for.
+
+use crate::{BlockState, data::remap};
+
+/// How one other version numbers block states, as runs of ids that map onto
+/// consecutive [`BlockState`]s. Generated from that version's data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateIdTable {
+    /// The name of the version, such as `1.21.11`.
+    pub version: &'static str,
+    pub protocol: i32,
+    /// How many states the version has, which sizes its global palette.
+    pub state_count: u16,
+    /// `[from, to, len]`, sorted by `from`.
+    runs: &'static [[u16; 3]],
+}
+
+impl StateIdTable {
+    pub const fn new(
+        version: &'static str,
+        protocol: i32,
+        state_count: u16,
+        runs: &'static [[u16; 3]],
+    ) -> Self {
+        Self {
+            version,
+            protocol,
+            state_count,
+            runs,
+        }
+    }
+
+    /// Every generated table, by protocol version.
+    pub fn all() -> &'static [Self] {
+        remap::TABLES
+    }
+
+    /// The table for a protocol version, or `None` if it numbers states like
+    /// the current one or no table was generated for it.
+    pub fn for_protocol(protocol: i32) -> Option<&'static Self> {
+        Self::all().iter().find(|table| table.protocol == protocol)
+    }
+
+    /// The state `id` stands for in this version. `None` if the id is out of
+    /// range or its block no longer exists.
+    pub fn get(&self, id: u16) -> Option<BlockState> {
+        let index = self
+            .runs
+            .partition_point(|[from, ..]| *from <= id)
+            .checked_sub(1)?;
+        let [from, to, len] = self.runs[index];
+        (id - from < len).then(|| BlockState::from(to + (id - from)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        static RUNS: [[u16; 3]; 2] = [[0, 0, 2], [3, 10, 4]];
+        let table = StateIdTable::new("test", 1, 8, &RUNS);
+        assert_eq!(table.get(1), Some(BlockState::from(1)));
+        assert_eq!(table.get(2), None);
+        assert_eq!(table.get(3), Some(BlockState::from(10)));
+        assert_eq!(table.get(6), Some(BlockState::from(13)));
+        assert_eq!(table.get(7), None);
+        assert_eq!(StateIdTable::for_protocol(-1), None);
+    }
+}
@@ -0,0 +1,111 @@
+//! The event-driven world model described in `docs/world-model.md`.
+
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+use mcre_core::{BlockPos, ChunkPos};
+use rustc_hash::FxBuildHasher;
+
+use crate::{BiomeId, BlockState, Chunk, ChunkData, LightUpdate, PalettedContainer};
+
+/// A fact about the world changing. Events are the only way [`World`] mutates.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldEvent {
+    ChunkLoaded {
+        pos: ChunkPos,
+        chunk: ChunkData,
+    },
+    ChunkUnloaded {
+        pos: ChunkPos,
+    },
+    BlockSet {
+        pos: BlockPos,
+        state: BlockState,
+    },
+    /// New biomes for every section of a loaded chunk, bottom to top.
+    BiomesSet {
+        pos: ChunkPos,
+        biomes: Vec<PalettedContainer<BiomeId>>,
+    },
+    LightUpdated {
+        pos: ChunkPos,
+        light: LightUpdate,
+    },
+}
+
+/// The authoritative world state.
+#[derive(Debug, Clone, Default)]
+pub struct World {
+    chunks: HashMap<ChunkPos, Chunk, FxBuildHasher>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies an event. Events about chunks that are not loaded are ignored.
+    pub fn apply(&mut self, event: WorldEvent) {
+        match event {
+            WorldEvent::ChunkLoaded { pos, chunk } => {
+                self.chunks.insert(pos, Chunk::from(chunk));
+            }
+            WorldEvent::ChunkUnloaded { pos } => {
+                self.chunks.remove(&pos);
+            }
+            WorldEvent::BlockSet { pos, state } => {
+                if let Some(chunk) = self.chunks.get_mut(&pos.chunk()) {
+                    chunk.set_block(pos, state);
+                }
+            }
+            WorldEvent::BiomesSet { pos, biomes } => {
+                if let Some(chunk) = self.chunks.get_mut(&pos) {
+                    chunk.set_biomes(biomes);
+                }
+            }
+            WorldEvent::LightUpdated { pos, light } => {
+                if let Some(chunk) = self.chunks.get_mut(&pos) {
+                    chunk.apply_light(light);
+                }
+            }
+        }
+    }
+
+    pub fn view(&self) -> WorldView<'_> {
+        WorldView { world: self }
+    }
+}
+
+/// Read-only access to a [`World`].
+#[derive(Debug, Clone, Copy)]
+pub struct WorldView<'a> {
+    world: &'a World,
+}
+
+impl<'a> WorldView<'a> {
+    pub fn get_chunk(&self, pos: ChunkPos) -> Option<&'a Chunk> {
+        self.world.chunks.get(&pos)
+    }
+
+    pub fn get_block(&self, pos: BlockPos) -> Option<BlockState> {
+        self.get_chunk(pos.chunk())
+            .map(|chunk| chunk.get_block(pos))
+    }
+
+    pub fn get_biome(&self, pos: BlockPos) -> Option<BiomeId> {
+        self.get_chunk(pos.chunk())?.get_biome(pos)
+    }
+
+    pub fn chunks(&self) -> impl Iterator<Item = (ChunkPos, &'a Chunk)> {
+        self.world.chunks.iter().map(|(pos, chunk)| (*pos, chunk))
+    }
+}
+
+/// Produces [`WorldEvent`]s, such as a region file reader or a connection.
+pub trait WorldSource {
+    fn poll_event(&mut self) -> Option<WorldEvent>;
+}
+
+/// Reacts to [`WorldEvent`]s after they have been applied.
+pub trait WorldConsumer {
+    fn on_event(&mut self, event: &WorldEvent, view: &WorldView);
+}
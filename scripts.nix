@@ -11,6 +11,7 @@ pkgs: {
       cargo ck
       cargo test --workspace --all-features
       cargo lint -- -D warnings
+      cargo r -r -p parity
       git diff --exit-code
     '';
   };
@@ -66,6 +67,7 @@ pkgs: {
         rm -rf crates/mcre_world/src/data
         cargo r -r -p world_data_gen
         cargo fmt
+        cargo r -r -p parity
       fi
     '';
   };
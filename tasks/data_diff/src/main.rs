@@ -0,0 +1,75 @@
+//! Reports what changed in the block data from an older version of the game
+//! to the current one, and writes the remap of its state ids.
+//!
+//! ```text
+//! cargo r -p data_diff -- <old dir> [<new dir>] [--rename <old>=<new>]... [--remap <file>]
+//! ```
+//!
+//! The directories hold `blocks.json` and `block_states.json`; the new one
+//! defaults to the data of the current version.
+
+use std::{env, fs, process};
+
+use mcre_data::diff::{DataSet, VersionDiff};
+
+const USAGE: &str =
+    "usage: data_diff <old dir> [<new dir>] [--rename <old>=<new>]... [--remap <file>]";
+
+fn main() {
+    let mut dirs = Vec::new();
+    let mut renames = Vec::new();
+    let mut remap_path = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rename" => {
+                let rename = args.next().unwrap_or_else(|| usage());
+                let Some((from, to)) = rename.split_once('=') else {
+                    usage();
+                };
+                renames.push((from.to_owned(), to.to_owned()));
+            }
+            "--remap" => remap_path = Some(args.next().unwrap_or_else(|| usage())),
+            "-h" | "--help" => usage(),
+            _ => dirs.push(arg),
+        }
+    }
+
+    let (old, new) = match dirs.as_slice() {
+        [old] => (DataSet::load(old), DataSet::current()),
+        [old, new] => (DataSet::load(old), DataSet::load(new)),
+        _ => usage(),
+    };
+    let (old, new) = match (old, new) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(error), _) | (_, Err(error)) => {
+            eprintln!("failed to load block data: {error}");
+            process::exit(1);
+        }
+    };
+
+    let diff = VersionDiff::new(&old, &new, &renames);
+    print!("{diff}");
+
+    let remap = diff.remap(&old, &new);
+    println!(
+        "\n{} of {} old states remapped in {} runs",
+        remap.mapped(),
+        old.states.len(),
+        remap.runs().len()
+    );
+    if let Some(path) = remap_path {
+        let json = serde_json::to_string_pretty(&remap).unwrap();
+        if let Err(error) = fs::write(&path, json) {
+            eprintln!("failed to write `{path}`: {error}");
+            process::exit(1);
+        }
+        println!("remap saved to `{path}`");
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    process::exit(2);
+}
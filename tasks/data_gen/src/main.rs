@@ -1,69 +1,73 @@
 use std::{fs, path::PathBuf};
 
 use indexmap::IndexMap;
-use jni::{
-    JNIEnv,
-    objects::{JObject, JString, JValueGen},
+use jni::{JNIEnv, objects::JObject};
+use mcje::{
+    Iterable, JAabb, JBlock, JBlockItem, JBlockPos, JBlockState, JBoolean, JBuiltInRegistries,
+    JDataComponentMap, JDataComponentType, JDataComponents, JEmptyBlockGetter, JEnum,
+    JFlowingFluid, JFoodProperties, JInteger, JIntegerProperty, JItem, JMapEntry, JProperty,
+    JRegistry, JTool, JVoxelShape, JavaClass, Result,
 };
-use mcje::{get_registry, iterate};
-use mcre_core::OffsetType;
+use mcre_core::{Fluid, FluidState, FoodProperties, OffsetType, Rarity, Tool};
 use mcre_data::{
     block::{Block, BlockStateField, BlockStateFieldValues},
+    component::DataComponentType,
+    item::{Item, ItemComponents},
     state::{BlockState, StateValue},
 };
 
 const BLOCK_DATA_PATH: &str = "crates/mcre_data/blocks.json";
 const BLOCK_STATE_DATA_PATH: &str = "crates/mcre_data/block_states.json";
+const ITEM_DATA_PATH: &str = "crates/mcre_data/items.json";
+const COMPONENT_DATA_PATH: &str = "crates/mcre_data/data_components.json";
+
+/// The block tags the generated code can test blocks for.
+const BLOCK_TAGS: &[&str] = &[
+    "beds",
+    "climbable",
+    "fence_gates",
+    "fences",
+    "leaves",
+    "trapdoors",
+    "walls",
+];
 
 #[mcje::main]
-async fn main(env: &mut JNIEnv<'_>) {
-    generate_block_data(env);
-    generate_block_state_data(env);
+async fn main(env: &mut JNIEnv<'_>) -> Result<()> {
+    generate_block_data(env)?;
+    generate_block_state_data(env)?;
+    generate_item_data(env)?;
+    generate_component_data(env)
 }
 
-fn generate_block_data(env: &mut JNIEnv) {
+fn generate_block_data(env: &mut JNIEnv) -> Result<()> {
     println!("[DEBUG] Generating block data");
-    let block_registry = get_registry(env, "BLOCK", "DefaultedRegistry");
-
-    let block_state_registry = env
-        .get_static_field(
-            "net/minecraft/world/level/block/Block",
-            "BLOCK_STATE_REGISTRY",
-            "Lnet/minecraft/core/IdMapper;",
-        )
-        .unwrap()
-        .l()
-        .unwrap();
+    let block_registry = JBuiltInRegistries::block(env)?;
+    let item_registry = JBuiltInRegistries::item(env)?;
+    let block_state_registry = JBlock::state_registry(env)?;
+
+    let mut tags = Vec::new();
+    for &tag in BLOCK_TAGS {
+        let blocks = mcje::block_tag(env, tag)?
+            .unwrap_or_else(|| panic!("the game has no block tag `{tag}`"));
+        tags.push((tag, blocks));
+    }
 
     let mut blocks = Vec::new();
 
     let mut block_state_id_counter = 0u16;
 
-    iterate(&block_registry, env, |i, block, env| {
-        let display_name = get_block_display_name(env, &block);
-        let name = get_block_name(&block, &block_registry, env);
-        let default_state = env
-            .get_field(
-                &block,
-                "defaultBlockState",
-                "Lnet/minecraft/world/level/block/state/BlockState;",
-            )
-            .unwrap()
-            .l()
-            .unwrap();
-
-        let default_state_id = env
-            .call_static_method(
-                "net/minecraft/world/level/block/Block",
-                "getId",
-                "(Lnet/minecraft/world/level/block/state/BlockState;)I",
-                &[JValueGen::Object(&default_state)],
-            )
-            .unwrap()
-            .i()
-            .unwrap() as u16;
-
-        let states = get_block_states(&block, env);
+    let mut registered = block_registry.iter::<JBlock>(env)?;
+    while let Some(block) = registered.next(env)? {
+        let display_name = block.name(env)?.string(env)?;
+        let translation_key = block.description_id(env)?;
+        let name = get_block_name(&block, &block_registry, env)?;
+        let default_state = block.default_block_state(env)?;
+        let default_state_id = JBlock::state_id(env, &default_state)? as u16;
+
+        let states = get_block_states(&block, env)?;
+        let item = block.as_item(env)?;
+        let item = Some(item_registry.key(env, &item)?.path(env)?).filter(|item| item != "air");
 
         let min_state_id = block_state_id_counter;
 
@@ -71,40 +75,37 @@ fn generate_block_data(env: &mut JNIEnv) {
 
         while owner == name {
             block_state_id_counter += 1;
-            let state = env
-                .call_method(
-                    &block_state_registry,
-                    "byId",
-                    "(I)Ljava/lang/Object;",
-                    &[JValueGen::Int(block_state_id_counter as i32)],
-                )
-                .unwrap()
-                .l()
-                .unwrap();
-            if state.is_null() {
+            let Some(state) = block_state_registry.by_id(env, block_state_id_counter.into())?
+            else {
                 break;
-            }
-            let state_owner = env
-                .get_field(state, "owner", "Ljava/lang/Object;")
-                .unwrap()
-                .l()
-                .unwrap();
-            let state_owner_name = get_block_name(&state_owner, &block_registry, env);
-            owner = state_owner_name;
+            };
+            let state_owner = state.cast::<JBlockState>().owner(env)?;
+            owner = get_block_name(&state_owner, &block_registry, env)?;
         }
 
         let max_state_id = block_state_id_counter - 1;
+        let block_tags = tags
+            .iter()
+            .filter(|(_, blocks)| blocks.contains(&name))
+            .map(|(tag, _)| tag.to_string())
+            .collect();
 
         blocks.push(Block {
-            id: i as u16,
+            id: blocks.len() as u16,
             name,
             display_name,
+            translation_key,
             default_state_id,
             min_state_id,
             max_state_id,
             states,
+            friction: block.friction(env)?,
+            speed_factor: block.speed_factor(env)?,
+            jump_factor: block.jump_factor(env)?,
+            item,
+            tags: block_tags,
         });
-    });
+    }
     println!("[DEBUG] Done!");
 
     let json_string = serde_json::to_string_pretty(&blocks).unwrap();
@@ -114,51 +115,29 @@ fn generate_block_data(env: &mut JNIEnv) {
     fs::write(&data_path, json_string).unwrap();
 
     println!("[DEBUG] Block data saved to `{}`", BLOCK_DATA_PATH);
+    Ok(())
 }
 
-fn generate_block_state_data(env: &mut JNIEnv) {
+fn generate_block_state_data(env: &mut JNIEnv) -> Result<()> {
     println!("[DEBUG] Generating block state data");
-    let block_registry = get_registry(env, "BLOCK", "DefaultedRegistry");
-
-    let block_state_registry = env
-        .get_static_field(
-            "net/minecraft/world/level/block/Block",
-            "BLOCK_STATE_REGISTRY",
-            "Lnet/minecraft/core/IdMapper;",
-        )
-        .unwrap()
-        .l()
-        .unwrap();
-
-    let mut block_state = env
-        .call_method(
-            &block_state_registry,
-            "byId",
-            "(I)Ljava/lang/Object;",
-            &[JValueGen::Int(0)],
-        )
-        .unwrap()
-        .l()
-        .unwrap();
+    let block_registry = JBuiltInRegistries::block(env)?;
+    let fluid_registry = JBuiltInRegistries::fluid(env)?;
+    let block_state_registry = JBlock::state_registry(env)?;
 
     let mut block_state_id = 0u16;
 
     let mut states = Vec::new();
 
-    while !block_state.is_null() {
-        let state = process_block_state(&block_registry, block_state_id, &block_state, env);
+    while let Some(block_state) = block_state_registry.by_id(env, block_state_id.into())? {
+        let state = process_block_state(
+            &block_registry,
+            &fluid_registry,
+            block_state_id,
+            &block_state.cast(),
+            env,
+        )?;
         states.push(state);
         block_state_id += 1;
-        block_state = env
-            .call_method(
-                &block_state_registry,
-                "byId",
-                "(I)Ljava/lang/Object;",
-                &[JValueGen::Int(block_state_id.into())],
-            )
-            .unwrap()
-            .l()
-            .unwrap();
     }
 
     println!("[DEBUG] Done!");
@@ -173,250 +152,302 @@ fn generate_block_state_data(env: &mut JNIEnv) {
         "[DEBUG] Block state data saved to `{}`",
         BLOCK_STATE_DATA_PATH
     );
+    Ok(())
+}
+
+fn generate_item_data(env: &mut JNIEnv) -> Result<()> {
+    println!("[DEBUG] Generating item data");
+    let item_registry = JBuiltInRegistries::item(env)?;
+    let block_registry = JBuiltInRegistries::block(env)?;
+
+    let mut items = Vec::new();
+
+    let mut registered = item_registry.iter::<JItem>(env)?;
+    while let Some(item) = registered.next(env)? {
+        let name = item_registry.key(env, &item)?.path(env)?;
+        let display_name = item.name(env)?.string(env)?;
+        let translation_key = item.description_id(env)?;
+        let components = item.components(env)?;
+
+        let block = if item.is::<JBlockItem>(env)? {
+            let block = item.cast::<JBlockItem>().block(env)?;
+            Some(get_block_name(&block, &block_registry, env)?)
+        } else {
+            None
+        };
+
+        let max_stack_size = JDataComponents::max_stack_size(env)?;
+        let max_stack_size = get_int_component(&components, &max_stack_size, env)?;
+        let max_damage = JDataComponents::max_damage(env)?;
+        let max_damage = get_int_component(&components, &max_damage, env)?;
+
+        items.push(Item {
+            id: items.len() as u16,
+            name,
+            display_name,
+            translation_key,
+            max_stack_size: max_stack_size.unwrap_or(1).try_into().unwrap(),
+            block,
+            components: ItemComponents {
+                max_damage: max_damage.map(|max_damage| max_damage.try_into().unwrap()),
+                food: get_food(&components, env)?,
+                tool: get_tool(&components, env)?,
+                rarity: get_rarity(&components, env)?,
+            },
+        });
+    }
+    println!("[DEBUG] Done!");
+
+    let json_string = serde_json::to_string_pretty(&items).unwrap();
+    let root = env!("CARGO_MANIFEST_DIR");
+    let root = PathBuf::from(root);
+    let data_path = root.join("../../").join(ITEM_DATA_PATH);
+    fs::write(&data_path, json_string).unwrap();
+
+    println!("[DEBUG] Item data saved to `{}`", ITEM_DATA_PATH);
+    Ok(())
+}
+
+fn generate_component_data(env: &mut JNIEnv) -> Result<()> {
+    println!("[DEBUG] Generating data component data");
+    let component_registry = JBuiltInRegistries::data_component_type(env)?;
+
+    let mut components = Vec::new();
+
+    let mut registered = component_registry.iter::<JDataComponentType>(env)?;
+    while let Some(component) = registered.next(env)? {
+        components.push(DataComponentType {
+            id: components.len() as u16,
+            name: component_registry.key(env, &component)?.path(env)?,
+            persistent: !component.is_transient(env)?,
+        });
+    }
+    println!("[DEBUG] Done!");
+
+    let json_string = serde_json::to_string_pretty(&components).unwrap();
+    let root = env!("CARGO_MANIFEST_DIR");
+    let root = PathBuf::from(root);
+    let data_path = root.join("../../").join(COMPONENT_DATA_PATH);
+    fs::write(&data_path, json_string).unwrap();
+
+    println!(
+        "[DEBUG] Data component data saved to `{}`",
+        COMPONENT_DATA_PATH
+    );
+    Ok(())
+}
+
+fn get_int_component<'local>(
+    components: &JDataComponentMap<'local>,
+    component: &JDataComponentType<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<Option<i32>> {
+    let Some(value) = components.get(env, component)? else {
+        return Ok(None);
+    };
+    Ok(Some(value.cast::<JInteger>().int_value(env)?))
 }
 
-fn process_block_state(
-    block_registry: &JObject,
+fn get_food<'local>(
+    components: &JDataComponentMap<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<Option<FoodProperties>> {
+    let component = JDataComponents::food(env)?;
+    let Some(food) = components.get(env, &component)? else {
+        return Ok(None);
+    };
+    let food = food.cast::<JFoodProperties>();
+    Ok(Some(FoodProperties {
+        nutrition: food.nutrition(env)?.try_into().unwrap(),
+        saturation: food.saturation(env)?,
+        can_always_eat: food.can_always_eat(env)?,
+    }))
+}
+
+fn get_tool<'local>(
+    components: &JDataComponentMap<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<Option<Tool>> {
+    let component = JDataComponents::tool(env)?;
+    let Some(tool) = components.get(env, &component)? else {
+        return Ok(None);
+    };
+    let tool = tool.cast::<JTool>();
+    Ok(Some(Tool {
+        default_mining_speed: tool.default_mining_speed(env)?,
+        damage_per_block: tool.damage_per_block(env)?.try_into().unwrap(),
+        can_destroy_blocks_in_creative: tool.can_destroy_blocks_in_creative(env)?,
+    }))
+}
+
+fn get_rarity<'local>(
+    components: &JDataComponentMap<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<Rarity> {
+    let component = JDataComponents::rarity(env)?;
+    let Some(rarity) = components.get(env, &component)? else {
+        return Ok(Rarity::Common);
+    };
+    Ok(match rarity.cast::<JEnum>().ordinal(env)? {
+        0 => Rarity::Common,
+        1 => Rarity::Uncommon,
+        2 => Rarity::Rare,
+        3 => Rarity::Epic,
+        ordinal => panic!("unknown rarity {ordinal}"),
+    })
+}
+
+fn process_block_state<'local>(
+    block_registry: &JRegistry<'local>,
+    fluid_registry: &JRegistry<'local>,
     id: u16,
-    block_state: &JObject,
-    env: &mut JNIEnv,
-) -> BlockState {
-    let block = env
-        .get_field(block_state, "owner", "Ljava/lang/Object;")
-        .unwrap()
-        .l()
-        .unwrap();
-
-    let block_id: u16 = env
-        .call_method(
-            block_registry,
-            "getId",
-            "(Ljava/lang/Object;)I",
-            &[JValueGen::Object(&block)],
-        )
-        .unwrap()
-        .i()
-        .unwrap()
-        .try_into()
-        .unwrap();
-
-    let block_name = get_block_name(&block, block_registry, env);
-
-    let light_emission: u8 = env
-        .get_field(block_state, "lightEmission", "I")
-        .unwrap()
-        .i()
-        .unwrap()
-        .try_into()
-        .unwrap();
-
-    let use_shape_for_light_occlusion = env
-        .get_field(block_state, "useShapeForLightOcclusion", "Z")
-        .unwrap()
-        .z()
-        .unwrap();
-
-    let propagates_skylight_down = env
-        .get_field(block_state, "propagatesSkylightDown", "Z")
-        .unwrap()
-        .z()
-        .unwrap();
-
-    let light_block: u8 = env
-        .get_field(block_state, "lightBlock", "I")
-        .unwrap()
-        .i()
-        .unwrap()
-        .try_into()
-        .unwrap();
-
-    let solid_render = env
-        .get_field(block_state, "solidRender", "Z")
-        .unwrap()
-        .z()
-        .unwrap();
-
-    let is_air = env
-        .get_field(block_state, "isAir", "Z")
-        .unwrap()
-        .z()
-        .unwrap();
-
-    let ignited_by_lava = env
-        .get_field(block_state, "ignitedByLava", "Z")
-        .unwrap()
-        .z()
-        .unwrap();
-
-    let can_occlude = env
-        .get_field(block_state, "canOcclude", "Z")
-        .unwrap()
-        .z()
-        .unwrap();
-
-    let is_randomly_ticking = env
-        .get_field(block_state, "isRandomlyTicking", "Z")
-        .unwrap()
-        .z()
-        .unwrap();
-
-    let replaceable = env
-        .get_field(block_state, "replaceable", "Z")
-        .unwrap()
-        .z()
-        .unwrap();
-
-    let spawn_terrain_particles = env
-        .get_field(block_state, "spawnTerrainParticles", "Z")
-        .unwrap()
-        .z()
-        .unwrap();
-
-    let requires_correct_tool_for_drops = env
-        .get_field(block_state, "requiresCorrectToolForDrops", "Z")
-        .unwrap()
-        .z()
-        .unwrap();
-
-    let destroy_speed = env
-        .get_field(block_state, "destroySpeed", "F")
-        .unwrap()
-        .f()
-        .unwrap();
-
-    let offset_type = determine_offset_type(block_state, env);
-
-    let max_horizontal_offset = env
-        .call_method(&block, "getMaxHorizontalOffset", "()F", &[])
-        .unwrap()
-        .f()
-        .unwrap();
-
-    let max_vertical_offset = env
-        .call_method(&block, "getMaxVerticalOffset", "()F", &[])
-        .unwrap()
-        .f()
-        .unwrap();
-
-    let state_values = get_state_values(block_state, env);
-
-    BlockState {
+    block_state: &JBlockState<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<BlockState> {
+    let block = block_state.owner(env)?;
+    let level = JEmptyBlockGetter::instance(env)?;
+    let origin = JBlockPos::zero(env)?;
+    let offset = get_origin_offset(block_state, env)?;
+    let outline_shape = block_state.shape(env, &level, &origin)?;
+    let collision_shape = block_state.collision_shape(env, &level, &origin)?;
+    let occlusion_shape = block_state.occlusion_shape(env)?;
+
+    Ok(BlockState {
         id,
-        block_id,
-        block_name,
-        light_emission,
-        use_shape_for_light_occlusion,
-        propagates_skylight_down,
-        light_block,
-        solid_render,
-        is_air,
-        ignited_by_lava,
-        can_occlude,
-        is_randomly_ticking,
-        replaceable,
-        spawn_terrain_particles,
-        requires_correct_tool_for_drops,
-        destroy_speed,
-        offset_type,
-        max_horizontal_offset,
-        max_vertical_offset,
-        state_values,
-    }
+        block_id: block_registry.id(env, &block)?.try_into().unwrap(),
+        block_name: get_block_name(&block, block_registry, env)?,
+        light_emission: block_state.light_emission(env)?.try_into().unwrap(),
+        use_shape_for_light_occlusion: block_state.use_shape_for_light_occlusion(env)?,
+        propagates_skylight_down: block_state.propagates_skylight_down(env)?,
+        light_block: block_state.light_block(env)?.try_into().unwrap(),
+        solid_render: block_state.solid_render(env)?,
+        is_air: block_state.is_air(env)?,
+        ignited_by_lava: block_state.ignited_by_lava(env)?,
+        can_occlude: block_state.can_occlude(env)?,
+        is_randomly_ticking: block_state.is_randomly_ticking(env)?,
+        replaceable: block_state.replaceable(env)?,
+        spawn_terrain_particles: block_state.spawn_terrain_particles(env)?,
+        requires_correct_tool_for_drops: block_state.requires_correct_tool_for_drops(env)?,
+        destroy_speed: block_state.destroy_speed(env)?,
+        blocks_motion: block_state.blocks_motion(env)?,
+        offset_type: determine_offset_type(block_state, env)?,
+        max_horizontal_offset: block.max_horizontal_offset(env)?,
+        max_vertical_offset: block.max_vertical_offset(env)?,
+        fluid_state: get_fluid_state(fluid_registry, block_state, env)?,
+        outline_shape: get_shape_boxes(&outline_shape, offset, env)?,
+        collision_shape: get_shape_boxes(&collision_shape, offset, env)?,
+        // cached once for the state, so kept as it is
+        occlusion_shape: get_shape_boxes(&occlusion_shape, [0.0; 3], env)?,
+        state_values: get_state_values(block_state, env)?,
+    })
 }
 
-pub fn determine_offset_type(block_state: &JObject, env: &mut JNIEnv) -> OffsetType {
-    let offset_function = env
-        .get_field(
-            block_state,
-            "offsetFunction",
-            "Lnet/minecraft/world/level/block/state/BlockBehaviour$OffsetFunction;",
-        )
-        .unwrap()
-        .l()
-        .unwrap();
-
-    if offset_function.is_null() {
-        return OffsetType::None;
+fn get_fluid_state<'local>(
+    fluid_registry: &JRegistry<'local>,
+    block_state: &JBlockState<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<FluidState> {
+    let fluid_state = block_state.fluid_state(env)?;
+    let fluid_type = fluid_state.fluid(env)?;
+
+    let fluid = match fluid_registry.id(env, &fluid_type)? {
+        0 => Fluid::Empty,
+        1 => Fluid::FlowingWater,
+        2 => Fluid::Water,
+        3 => Fluid::FlowingLava,
+        4 => Fluid::Lava,
+        id => panic!("unknown fluid id {id}"),
+    };
+
+    if fluid == Fluid::Empty {
+        return Ok(FluidState::EMPTY);
     }
 
-    let block_pos_class = "net/minecraft/core/BlockPos";
+    let amount = fluid_state.amount(env)?.try_into().unwrap();
+    let falling_property = JFlowingFluid::falling(env)?;
+    let falling = fluid_state
+        .value(env, &falling_property)?
+        .cast::<JBoolean>()
+        .boolean_value(env)?;
+
+    Ok(FluidState {
+        fluid,
+        amount,
+        falling,
+    })
+}
+
+pub fn determine_offset_type<'local>(
+    block_state: &JBlockState<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<OffsetType> {
+    let Some(offset_function) = block_state.offset_function(env)? else {
+        return Ok(OffsetType::None);
+    };
 
     for i in 0..10 {
-        // BlockPos(i, i, i)
-        let pos_obj = env
-            .new_object(
-                block_pos_class,
-                "(III)V",
-                &[JValueGen::Int(i), JValueGen::Int(i), JValueGen::Int(i)],
-            )
-            .unwrap();
-
-        // Call offsetFunction.evaluate(state, pos)
-        // Signature: (LBlockState;LBlockPos;)LVec3;
-        let vec3_obj = env.call_method(
-            &offset_function,
-            "evaluate",
-            "(Lnet/minecraft/world/level/block/state/BlockState;Lnet/minecraft/core/BlockPos;)Lnet/minecraft/world/phys/Vec3;",
-            &[JValueGen::Object(block_state), JValueGen::Object(&pos_obj)]
-        ).unwrap().l().unwrap();
-
-        let y_val = env.get_field(&vec3_obj, "y", "D").unwrap().d().unwrap();
-
-        if y_val.abs() > 0.00001 {
-            return OffsetType::XYZ;
+        let pos = JBlockPos::new(env, i, i, i)?;
+        let offset = offset_function.evaluate(env, block_state, &pos)?;
+
+        if offset.y(env)?.abs() > 0.00001 {
+            return Ok(OffsetType::XYZ);
         }
     }
 
-    OffsetType::XZ
+    Ok(OffsetType::XZ)
+}
+
+/// The random offset of the state at the origin, which the shapes read there
+/// are moved by.
+fn get_origin_offset<'local>(
+    block_state: &JBlockState<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<[f64; 3]> {
+    let Some(offset_function) = block_state.offset_function(env)? else {
+        return Ok([0.0; 3]);
+    };
+    let origin = JBlockPos::zero(env)?;
+    let offset = offset_function.evaluate(env, block_state, &origin)?;
+    Ok([offset.x(env)?, offset.y(env)?, offset.z(env)?])
 }
 
-fn get_state_values(block_state: &JObject, env: &mut JNIEnv) -> IndexMap<String, StateValue> {
+/// The boxes of a shape with `offset` taken back out. Box bounds are
+/// fractions of a sixteenth, so they are snapped to a fine grid to drop the
+/// rounding error of moving there and back.
+fn get_shape_boxes<'local>(
+    shape: &JVoxelShape<'local>,
+    offset: [f64; 3],
+    env: &mut JNIEnv<'local>,
+) -> Result<Vec<[f64; 6]>> {
+    let snap = |bound: f64, offset: f64| ((bound - offset) * 4096.0).round() / 4096.0;
+    let mut boxes = Vec::new();
+    let mut aabbs = shape.to_aabbs(env)?.iter::<JAabb>(env)?;
+    while let Some(aabb) = aabbs.next(env)? {
+        boxes.push([
+            snap(aabb.min_x(env)?, offset[0]),
+            snap(aabb.min_y(env)?, offset[1]),
+            snap(aabb.min_z(env)?, offset[2]),
+            snap(aabb.max_x(env)?, offset[0]),
+            snap(aabb.max_y(env)?, offset[1]),
+            snap(aabb.max_z(env)?, offset[2]),
+        ]);
+    }
+    Ok(boxes)
+}
+
+fn get_state_values<'local>(
+    block_state: &JBlockState<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<IndexMap<String, StateValue>> {
     let mut values = IndexMap::new();
 
-    let properties = env
-        .call_method(
-            block_state,
-            "getProperties",
-            "()Ljava/util/Collection;",
-            &[],
-        )
-        .unwrap()
-        .l()
-        .unwrap();
-
-    iterate(&properties, env, |_i, property, env| {
-        let property_clazz = env
-            .get_field(&property, "clazz", "Ljava/lang/Class;")
-            .unwrap()
-            .l()
-            .unwrap();
-
-        let property_clazz_name = env
-            .call_method(property_clazz, "getSimpleName", "()Ljava/lang/String;", &[])
-            .unwrap()
-            .l()
-            .unwrap();
-
-        let property_clazz_name = obj_to_str(property_clazz_name, env);
-
-        let value = env.call_method(block_state, "getValue", "(Lnet/minecraft/world/level/block/state/properties/Property;)Ljava/lang/Comparable;", &[JValueGen::Object(&property)]).unwrap().l().unwrap();
-        let key_obj = env
-            .get_field(&property, "name", "Ljava/lang/String;")
-            .unwrap()
-            .l()
-            .unwrap();
-        let key = obj_to_str(key_obj, env);
-        let value_obj = env
-            .call_method(
-                &property,
-                "getName",
-                "(Ljava/lang/Comparable;)Ljava/lang/String;",
-                &[JValueGen::Object(&value)],
-            )
-            .unwrap()
-            .l()
-            .unwrap();
-        let value_string = obj_to_str(value_obj, env);
+    let mut properties = block_state.properties(env)?.iter::<JProperty>(env)?;
+    while let Some(property) = properties.next(env)? {
+        let property_clazz_name = property.clazz(env)?.simple_name(env)?;
+
+        let value = block_state.value(env, &property)?;
+        let key = property.name(env)?;
+        let value_string = property.value_name(env, &value)?;
 
         let value = match property_clazz_name.as_str() {
             "Integer" => StateValue::Int(value_string.parse().unwrap()),
@@ -427,176 +458,52 @@ fn get_state_values(block_state: &JObject, env: &mut JNIEnv) -> IndexMap<String,
             _ => StateValue::String(value_string),
         };
         values.insert(key, value);
-    });
-
-    values
-}
-
-fn get_block_display_name(env: &mut JNIEnv, block: &JObject) -> String {
-    let display_name_component = env
-        .call_method(
-            block,
-            "getName",
-            "()Lnet/minecraft/network/chat/MutableComponent;",
-            &[],
-        )
-        .unwrap()
-        .l()
-        .unwrap();
-
-    let display_name_obj = env
-        .call_method(
-            display_name_component,
-            "getString",
-            "()Ljava/lang/String;",
-            &[],
-        )
-        .unwrap()
-        .l()
-        .unwrap();
-
-    obj_to_str(display_name_obj, env)
-}
-
-fn get_block_name(block: &JObject, block_registry: &JObject, env: &mut JNIEnv) -> String {
-    let block_identifier = env
-        .call_method(
-            block_registry,
-            "getKey",
-            "(Ljava/lang/Object;)Lnet/minecraft/resources/Identifier;",
-            &[JValueGen::Object(block)],
-        )
-        .unwrap()
-        .l()
-        .unwrap();
-    get_identifier_path(&block_identifier, env)
-}
+    }
 
-fn get_identifier_path(identifier: &JObject, env: &mut JNIEnv) -> String {
-    let path = env
-        .get_field(identifier, "path", "Ljava/lang/String;")
-        .unwrap()
-        .l()
-        .unwrap();
-    obj_to_str(path, env)
+    Ok(values)
 }
 
-fn obj_to_str(obj: JObject, env: &mut JNIEnv) -> String {
-    let jstr = JString::from(obj);
-    env.get_string(&jstr).unwrap().into()
+fn get_block_name<'local>(
+    block: &JObject<'local>,
+    block_registry: &JRegistry<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<String> {
+    block_registry.key(env, block)?.path(env)
 }
 
-fn get_block_states(block: &JObject, env: &mut JNIEnv) -> Vec<BlockStateField> {
-    let state_definition = env
-        .get_field(
-            block,
-            "stateDefinition",
-            "Lnet/minecraft/world/level/block/state/StateDefinition;",
-        )
-        .unwrap()
-        .l()
-        .unwrap();
-
-    let properties_map = env
-        .get_field(
-            state_definition,
-            "propertiesByName",
-            "Lcom/google/common/collect/ImmutableSortedMap;",
-        )
-        .unwrap()
-        .l()
-        .unwrap();
-
-    let properties_map_entry_set = env
-        .call_method(
-            properties_map,
-            "entrySet",
-            "()Lcom/google/common/collect/ImmutableSet;",
-            &[],
-        )
-        .unwrap()
-        .l()
-        .unwrap();
+fn get_block_states<'local>(
+    block: &JBlock<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<Vec<BlockStateField>> {
+    let properties_map = block.state_definition(env)?.properties_by_name(env)?;
 
     let mut states = Vec::new();
 
-    iterate(&properties_map_entry_set, env, |_i, entry, env| {
-        let name_obj = env
-            .call_method(&entry, "getKey", "()Ljava/lang/Object;", &[])
-            .unwrap()
-            .l()
-            .unwrap();
-
-        let name = obj_to_str(name_obj, env);
-
-        let property = env
-            .call_method(entry, "getValue", "()Ljava/lang/Object;", &[])
-            .unwrap()
-            .l()
-            .unwrap();
-
-        let property_clazz = env
-            .get_field(&property, "clazz", "Ljava/lang/Class;")
-            .unwrap()
-            .l()
-            .unwrap();
-
-        let property_clazz_name = env
-            .call_method(property_clazz, "getSimpleName", "()Ljava/lang/String;", &[])
-            .unwrap()
-            .l()
-            .unwrap();
-
-        let property_clazz_name = obj_to_str(property_clazz_name, env);
+    let mut entries = properties_map.entry_set(env)?.iter::<JMapEntry>(env)?;
+    while let Some(entry) = entries.next(env)? {
+        let property = entry.value(env)?.cast::<JProperty>();
+        let name = property.name(env)?;
+        let property_clazz_name = property.clazz(env)?.simple_name(env)?;
 
         let values = match property_clazz_name.as_str() {
             "Integer" => {
-                let min: u8 = env
-                    .get_field(&property, "min", "I")
-                    .unwrap()
-                    .i()
-                    .unwrap()
-                    .try_into()
-                    .unwrap();
-                let max: u8 = env
-                    .get_field(&property, "max", "I")
-                    .unwrap()
-                    .i()
-                    .unwrap()
-                    .try_into()
-                    .unwrap();
-
-                BlockStateFieldValues::Int { min, max }
+                let property = property.cast::<JIntegerProperty>();
+                BlockStateFieldValues::Int {
+                    min: property.min(env)?.try_into().unwrap(),
+                    max: property.max(env)?.try_into().unwrap(),
+                }
             }
             "Boolean" => BlockStateFieldValues::Bool,
             "String" => {
                 panic!("No schema");
             }
             _ => {
-                let possible_values = env
-                    .call_method(&property, "getPossibleValues", "()Ljava/util/List;", &[])
-                    .unwrap()
-                    .l()
-                    .unwrap();
-
                 let mut values = Vec::new();
 
-                iterate(&possible_values, env, |_i, value, env| {
-                    let value_name_obj = env
-                        .call_method(
-                            &property,
-                            "getName",
-                            "(Ljava/lang/Comparable;)Ljava/lang/String;",
-                            &[JValueGen::Object(&value)],
-                        )
-                        .unwrap()
-                        .l()
-                        .unwrap();
-
-                    let value_name = obj_to_str(value_name_obj, env);
-
-                    values.push(value_name);
-                });
+                let mut possible_values = property.possible_values(env)?.iter::<JObject>(env)?;
+                while let Some(value) = possible_values.next(env)? {
+                    values.push(property.value_name(env, &value)?);
+                }
 
                 BlockStateFieldValues::Enum {
                     enum_name: property_clazz_name,
@@ -606,7 +513,7 @@ fn get_block_states(block: &JObject, env: &mut JNIEnv) -> Vec<BlockStateField> {
         };
 
         states.push(BlockStateField { name, values })
-    });
+    }
 
-    states
+    Ok(states)
 }
@@ -0,0 +1,434 @@
+//! Compares the generated block and block state data against the game it was
+//! generated from, printing every difference at once. Exits with an error if
+//! there is one, so that a bump of `mc-version` checks itself.
+
+mod report;
+
+use std::{collections::BTreeMap, fmt, process};
+
+use jni::{JNIEnv, objects::JObject};
+use mcje::{
+    Iterable, JAabb, JBlock, JBlockPos, JBlockState, JBoolean, JBuiltInRegistries,
+    JEmptyBlockGetter, JFlowingFluid, JProperty, JVoxelShape, JavaClass, Result,
+};
+use mcre_core::{BlockPos, OffsetType};
+use mcre_world::{Block, BlockState, BlockTag, FieldKey, PropKey, PropVal, VoxelShape};
+
+use crate::report::Report;
+
+#[mcje::main]
+async fn main(env: &mut JNIEnv<'_>) -> Result<()> {
+    let mut report = Report::new();
+    check_blocks(&mut report, env)?;
+    check_block_states(&mut report, env)?;
+
+    println!("{report}");
+    if !report.is_clean() {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// A state as the report names it, with its id in case the name is wrong.
+struct StateLabel(BlockState);
+
+impl fmt::Display for StateLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{} {}", u16::from(self.0), self.0)
+    }
+}
+
+fn check_blocks(report: &mut Report, env: &mut JNIEnv) -> Result<()> {
+    let block_registry = JBuiltInRegistries::block(env)?;
+    let mut count = 0;
+    let mut tags = Vec::new();
+    for tag in BlockTag::ALL {
+        let Some(names) = mcje::block_tag(env, tag.as_str())? else {
+            report.missing(
+                &format_args!("#minecraft:{}", tag.as_str()),
+                "block_tag",
+                "",
+            );
+            continue;
+        };
+        tags.push((tag, names));
+    }
+
+    let mut blocks = block_registry.iter::<JBlock>(env)?;
+    while let Some(java) = blocks.next(env)? {
+        let id = count;
+        count += 1;
+        let name = block_registry.key(env, &java)?.path(env)?;
+        // blocks added since the data was generated have no entry to read
+        if id > u16::from(Block::MAX) {
+            report.missing(&format_args!("block #{id}"), "block", name);
+            continue;
+        }
+        let block = Block::from(id);
+        let subject = &format_args!("minecraft:{}", block.name());
+
+        report.compare(subject, "block.name", block.name(), name.as_str());
+        let display_name = java.name(env)?.string(env)?;
+        report.compare(
+            subject,
+            "block.display_name",
+            block.display_name(),
+            display_name.as_str(),
+        );
+        let translation_key = java.description_id(env)?;
+        report.compare(
+            subject,
+            "block.translation_key",
+            block.translation_key(),
+            translation_key.as_str(),
+        );
+        let default_state = java.default_block_state(env)?;
+        let default_state = JBlock::state_id(env, &default_state)?;
+        report.compare(
+            subject,
+            "block.default_state",
+            u16::from(block.default_state()).into(),
+            default_state,
+        );
+        let friction = java.friction(env)?;
+        report.compare(subject, "block.friction", block.friction(), friction);
+        let speed_factor = java.speed_factor(env)?;
+        report.compare(
+            subject,
+            "block.speed_factor",
+            block.speed_factor(),
+            speed_factor,
+        );
+        let jump_factor = java.jump_factor(env)?;
+        report.compare(
+            subject,
+            "block.jump_factor",
+            block.jump_factor(),
+            jump_factor,
+        );
+        for (tag, names) in &tags {
+            report.compare(
+                subject,
+                "block.tags",
+                (tag.as_str(), block.is_in(*tag)),
+                (tag.as_str(), names.contains(&name)),
+            );
+        }
+    }
+
+    report.compare(&"blocks", "block.count", Block::all().count(), count.into());
+    Ok(())
+}
+
+fn check_block_states(report: &mut Report, env: &mut JNIEnv) -> Result<()> {
+    let block_registry = JBuiltInRegistries::block(env)?;
+    let fluid_registry = JBuiltInRegistries::fluid(env)?;
+    let state_registry = JBlock::state_registry(env)?;
+    let falling = JFlowingFluid::falling(env)?;
+    let mut count = 0u16;
+    // the first and last state of each block
+    let mut ranges = BTreeMap::new();
+
+    while let Some(java) = state_registry.by_id(env, count.into())? {
+        let java = java.cast::<JBlockState>();
+        let id = count;
+        count += 1;
+        let block = java.owner(env)?;
+        let block_id = block_registry.id(env, &block)?;
+        if id > u16::from(BlockState::MAX) {
+            let name = block_registry.key(env, &block)?.path(env)?;
+            let props: Vec<String> = java_props(&java, env)?
+                .into_iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect();
+            report.missing(
+                &format_args!("block state #{id}"),
+                "block_state",
+                format_args!("minecraft:{name}[{}]", props.join(",")),
+            );
+            continue;
+        }
+        let state = BlockState::from(id);
+        let subject = &StateLabel(state);
+
+        report.compare(subject, "block", u16::from(state.block()).into(), block_id);
+        ranges
+            .entry(block_id)
+            .and_modify(|(_, max)| *max = state)
+            .or_insert((state, state));
+
+        macro_rules! compare_fields {
+            ($($field:ident),*) => {$(
+                let value = java.$field(env)?;
+                report.compare(subject, stringify!($field), state.$field().into(), value);
+            )*};
+        }
+        compare_fields!(
+            light_emission,
+            use_shape_for_light_occlusion,
+            propagates_skylight_down,
+            light_block,
+            solid_render,
+            is_air,
+            ignited_by_lava,
+            can_occlude,
+            is_randomly_ticking,
+            replaceable,
+            spawn_terrain_particles,
+            requires_correct_tool_for_drops,
+            destroy_speed,
+            blocks_motion
+        );
+
+        let max_horizontal_offset = block.max_horizontal_offset(env)?;
+        report.compare(
+            subject,
+            "max_horizontal_offset",
+            state.max_horizontal_offset(),
+            max_horizontal_offset,
+        );
+        let max_vertical_offset = block.max_vertical_offset(env)?;
+        report.compare(
+            subject,
+            "max_vertical_offset",
+            state.max_vertical_offset(),
+            max_vertical_offset,
+        );
+        check_offsets(report, subject, state, &java, env)?;
+        check_shapes(report, subject, state, &java, env)?;
+
+        let fluid_state = java.fluid_state(env)?;
+        let fluid = fluid_state.fluid(env)?;
+        let fluid = fluid_registry.id(env, &fluid)?;
+        let rust_fluid = state.fluid_state();
+        report.compare(subject, "fluid_state.fluid", rust_fluid.fluid as i32, fluid);
+        if fluid != 0 {
+            let amount = fluid_state.amount(env)?;
+            report.compare(
+                subject,
+                "fluid_state.amount",
+                rust_fluid.amount.into(),
+                amount,
+            );
+            let is_falling = fluid_state
+                .value(env, &falling)?
+                .cast::<JBoolean>()
+                .boolean_value(env)?;
+            report.compare(
+                subject,
+                "fluid_state.falling",
+                rust_fluid.falling,
+                is_falling,
+            );
+        }
+
+        let props = java_props(&java, env)?;
+        check_props(report, subject, state, &props);
+    }
+
+    for (block_id, (min, max)) in ranges {
+        let block_id = u16::try_from(block_id).unwrap();
+        if block_id > u16::from(Block::MAX) {
+            continue;
+        }
+        let block = Block::from(block_id);
+        let subject = &format_args!("minecraft:{}", block.name());
+        report.compare(subject, "block.min_state", block.min_state(), min);
+        report.compare(subject, "block.max_state", block.max_state(), max);
+    }
+    report.compare(
+        &"block states",
+        "block_state.count",
+        u16::from(BlockState::MAX) + 1,
+        count,
+    );
+    Ok(())
+}
+
+/// Compares whether the state is offset and where the offset puts it at a
+/// few positions, which tells `XZ` from `XYZ`.
+fn check_offsets<'local>(
+    report: &mut Report,
+    subject: &StateLabel,
+    state: BlockState,
+    java: &JBlockState<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<()> {
+    let offset_function = java.offset_function(env)?;
+    report.compare(
+        subject,
+        "offset_type",
+        state.offset_type() != OffsetType::None,
+        offset_function.is_some(),
+    );
+    let Some(offset_function) = offset_function else {
+        return Ok(());
+    };
+    if state.offset_type() == OffsetType::None {
+        return Ok(());
+    }
+
+    for i in 0..10 {
+        let pos = BlockPos::new(i, i * 7, -i * 3);
+        let java_pos = JBlockPos::new(env, pos.x, pos.y, pos.z)?;
+        let offset = offset_function.evaluate(env, java, &java_pos)?;
+        let (x, y, z) = state.offset_type().offset(
+            pos,
+            state.max_horizontal_offset(),
+            state.max_vertical_offset(),
+        );
+        report.compare_approx(subject, "offset.x", x, offset.x(env)?);
+        report.compare_approx(subject, "offset.y", y, offset.y(env)?);
+        report.compare_approx(subject, "offset.z", z, offset.z(env)?);
+    }
+    Ok(())
+}
+
+/// Compares the outline and collision shapes at the origin, random offset
+/// included, and the occlusion shape, box by box.
+fn check_shapes<'local>(
+    report: &mut Report,
+    subject: &StateLabel,
+    state: BlockState,
+    java: &JBlockState<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<()> {
+    let level = JEmptyBlockGetter::instance(env)?;
+    let origin = JBlockPos::zero(env)?;
+    let outline = java.shape(env, &level, &origin)?;
+    report.compare(
+        subject,
+        "outline_shape",
+        boxes(&state.outline_shape(BlockPos::ZERO)),
+        java_boxes(&outline, env)?,
+    );
+    let collision = java.collision_shape(env, &level, &origin)?;
+    report.compare(
+        subject,
+        "collision_shape",
+        boxes(&state.collision_shape(BlockPos::ZERO)),
+        java_boxes(&collision, env)?,
+    );
+    let occlusion = java.occlusion_shape(env)?;
+    report.compare(
+        subject,
+        "occlusion_shape",
+        boxes(&state.occlusion_shape()),
+        java_boxes(&occlusion, env)?,
+    );
+    Ok(())
+}
+
+/// Bounds are snapped to a 4096th of a block, as in the generated data, so
+/// that rounding in the random offset does not count.
+fn snap(bound: f64) -> f64 {
+    (bound * 4096.0).round() / 4096.0
+}
+
+fn boxes(shape: &VoxelShape) -> Vec<[f64; 6]> {
+    shape
+        .boxes()
+        .iter()
+        .map(|aabb| {
+            [
+                aabb.min[0],
+                aabb.min[1],
+                aabb.min[2],
+                aabb.max[0],
+                aabb.max[1],
+                aabb.max[2],
+            ]
+            .map(snap)
+        })
+        .collect()
+}
+
+fn java_boxes<'local>(
+    shape: &JVoxelShape<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<Vec<[f64; 6]>> {
+    let mut boxes = Vec::new();
+    let mut aabbs = shape.to_aabbs(env)?.iter::<JAabb>(env)?;
+    while let Some(aabb) = aabbs.next(env)? {
+        let bounds = [
+            aabb.min_x(env)?,
+            aabb.min_y(env)?,
+            aabb.min_z(env)?,
+            aabb.max_x(env)?,
+            aabb.max_y(env)?,
+            aabb.max_z(env)?,
+        ];
+        boxes.push(bounds.map(snap));
+    }
+    Ok(boxes)
+}
+
+/// Compares the properties of a state through [`BlockState::props`],
+/// [`BlockState::get_prop`] for every key, and [`BlockState::get_field`] for
+/// every field, which reads each typed getter.
+fn check_props(
+    report: &mut Report,
+    subject: &StateLabel,
+    state: BlockState,
+    java: &BTreeMap<String, String>,
+) {
+    let mut rust_props: Vec<String> = state.props().map(|prop| prop.to_string()).collect();
+    rust_props.sort();
+    let java_props: Vec<String> = java
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect();
+    report.compare(subject, "props", rust_props, java_props);
+
+    for name in java.keys() {
+        if name.parse::<PropKey>().is_err() {
+            report.missing(subject, "prop_key", name);
+        }
+    }
+    for key in PropKey::ALL {
+        let value = java.get(key.as_str());
+        report.compare(
+            subject,
+            "get_prop",
+            state.get_prop(key).map(|prop| prop.to_string()),
+            value.map(|value| format!("{}={value}", key.as_str())),
+        );
+
+        // exactly one field stands for each property of the block
+        let fields: Vec<FieldKey> = FieldKey::ALL
+            .into_iter()
+            .filter(|&field| PropKey::from(field) == key && state.get_field(field).is_some())
+            .collect();
+        report.compare(
+            subject,
+            "get_field.count",
+            fields.len(),
+            usize::from(value.is_some()),
+        );
+        for field in fields {
+            let rust = state.get_field(field).map(PropVal::from);
+            report.compare(
+                subject,
+                "get_field",
+                rust.map(|prop| prop.to_string()),
+                value.map(|value| format!("{}={value}", key.as_str())),
+            );
+        }
+    }
+}
+
+/// The properties of a state by name.
+fn java_props<'local>(
+    java: &JBlockState<'local>,
+    env: &mut JNIEnv<'local>,
+) -> Result<BTreeMap<String, String>> {
+    let mut props = BTreeMap::new();
+    let mut properties = java.properties(env)?.iter::<JProperty>(env)?;
+    while let Some(property) = properties.next(env)? {
+        let value: JObject = java.value(env, &property)?;
+        let name = property.name(env)?;
+        let value = property.value_name(env, &value)?;
+        props.insert(name, value);
+    }
+    Ok(props)
+}
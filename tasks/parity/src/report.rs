@@ -0,0 +1,145 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug, Display},
+};
+
+/// How many mismatches of one property are listed; the rest are counted.
+const EXAMPLES: usize = 8;
+
+/// Every difference found between the generated data and the game, by
+/// property, so that one run shows all of them.
+#[derive(Debug, Default)]
+pub struct Report {
+    comparisons: usize,
+    mismatches: BTreeMap<&'static str, Vec<Mismatch>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// What was compared, such as `minecraft:oak_log[axis=x]`.
+    pub subject: String,
+    pub rust: String,
+    pub java: String,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records whether `property` of `subject` is the same in Rust and Java.
+    pub fn compare<T: PartialEq + Debug>(
+        &mut self,
+        subject: &dyn Display,
+        property: &'static str,
+        rust: T,
+        java: T,
+    ) {
+        self.comparisons += 1;
+        if rust != java {
+            self.mismatch(subject, property, rust, java);
+        }
+    }
+
+    /// Like [`Report::compare`], for values computed in floating point.
+    pub fn compare_approx(
+        &mut self,
+        subject: &dyn Display,
+        property: &'static str,
+        rust: f64,
+        java: f64,
+    ) {
+        self.comparisons += 1;
+        if (rust - java).abs() >= 1e-6 {
+            self.mismatch(subject, property, rust, java);
+        }
+    }
+
+    /// Records something the game has that the generated data does not,
+    /// such as a block added since the data was generated.
+    pub fn missing(&mut self, subject: &dyn Display, property: &'static str, java: impl Debug) {
+        self.comparisons += 1;
+        self.mismatch(subject, property, format_args!("missing"), java);
+    }
+
+    fn mismatch(
+        &mut self,
+        subject: &dyn Display,
+        property: &'static str,
+        rust: impl Debug,
+        java: impl Debug,
+    ) {
+        self.mismatches.entry(property).or_default().push(Mismatch {
+            subject: subject.to_string(),
+            rust: format!("{rust:?}"),
+            java: format!("{java:?}"),
+        });
+    }
+
+    pub fn mismatch_count(&self) -> usize {
+        self.mismatches.values().map(Vec::len).sum()
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} comparisons, {} mismatches",
+            self.comparisons,
+            self.mismatch_count()
+        )?;
+        for (property, mismatches) in &self.mismatches {
+            writeln!(f, "\n{property} ({}):", mismatches.len())?;
+            for mismatch in mismatches.iter().take(EXAMPLES) {
+                writeln!(
+                    f,
+                    "  {}: rust {}, java {}",
+                    mismatch.subject, mismatch.rust, mismatch.java
+                )?;
+            }
+            if mismatches.len() > EXAMPLES {
+                writeln!(f, "  and {} more", mismatches.len() - EXAMPLES)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_collects_every_mismatch() {
+        let mut report = Report::new();
+        report.compare(&"minecraft:stone", "light_emission", 0, 0);
+        assert!(report.is_clean());
+        for i in 0..10 {
+            report.compare(&format_args!("minecraft:torch{i}"), "light_emission", 0, 14);
+        }
+        report.compare_approx(&"minecraft:grass", "offset_x", 0.25, 0.25 + 1e-9);
+        report.compare_approx(&"minecraft:grass", "offset_y", 0.0, 0.2);
+        report.missing(&"block #1168", "block.name", "copper_torch");
+        assert_eq!(report.mismatch_count(), 12);
+        assert_eq!(
+            report.mismatches["offset_y"],
+            [Mismatch {
+                subject: "minecraft:grass".into(),
+                rust: "0.0".into(),
+                java: "0.2".into(),
+            }]
+        );
+
+        let text = report.to_string();
+        assert!(text.starts_with("14 comparisons, 12 mismatches\n"));
+        assert!(text.contains("block #1168: rust missing, java \"copper_torch\"\n"));
+        assert!(text.contains("light_emission (10):\n  minecraft:torch0: rust 0, java 14\n"));
+        assert!(text.contains("  and 2 more\n"));
+        assert!(!text.contains("offset_x"));
+    }
+}
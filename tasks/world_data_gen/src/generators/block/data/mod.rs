@@ -2,19 +2,33 @@ use crate::{
     analyzer::{Analysis, FieldSchema},
     generators::{
         Scope, ScopeGen, Unit, UnitGen,
+        block::tags::tag_names,
         common::{MultiByteGen, StringGen},
     },
 };
 
-use mcre_data::block::{Block, BlockStateFieldValues};
+use std::collections::HashMap;
+
+use mcre_data::{
+    block::{Block, BlockStateFieldValues},
+    item::Item,
+};
 use quote::quote;
 
 pub struct BlockDataScope<'a> {
     pub blocks: &'a [Block],
+    pub items: &'a [Item],
 }
 
 impl<'a> ScopeGen<'a> for BlockDataScope<'a> {
     fn generate(&self, _analysis: &Analysis) -> Scope<'a> {
+        let item_ids: HashMap<String, u16> = self
+            .items
+            .iter()
+            .map(|item| (item.name.clone(), item.id))
+            .collect();
+        let tags = tag_names(self.blocks);
+
         Scope {
             name: "data".to_string(),
             units: Box::new([
@@ -29,6 +43,11 @@ impl<'a> ScopeGen<'a> for BlockDataScope<'a> {
                     list: self.blocks,
                     mapping_fn: Box::new(|block| &block.display_name),
                 }),
+                Box::new(StringGen {
+                    name: "translation_key".to_string(),
+                    list: self.blocks,
+                    mapping_fn: Box::new(|block| &block.translation_key),
+                }),
                 Box::new(MultiByteGen {
                     name: "default_state".to_string(),
                     list: self.blocks,
@@ -44,6 +63,39 @@ impl<'a> ScopeGen<'a> for BlockDataScope<'a> {
                     list: self.blocks,
                     mapping_fn: Box::new(|block, _analysis: &Analysis<'_>| block.max_state_id),
                 }),
+                Box::new(MultiByteGen {
+                    name: "friction".to_string(),
+                    list: self.blocks,
+                    mapping_fn: Box::new(|block, _analysis: &Analysis<'_>| block.friction),
+                }),
+                Box::new(MultiByteGen {
+                    name: "speed_factor".to_string(),
+                    list: self.blocks,
+                    mapping_fn: Box::new(|block, _analysis: &Analysis<'_>| block.speed_factor),
+                }),
+                Box::new(MultiByteGen {
+                    name: "jump_factor".to_string(),
+                    list: self.blocks,
+                    mapping_fn: Box::new(|block, _analysis: &Analysis<'_>| block.jump_factor),
+                }),
+                // 0 is air, which vanilla gives for blocks without an item
+                Box::new(MultiByteGen {
+                    name: "item".to_string(),
+                    list: self.blocks,
+                    mapping_fn: Box::new(move |block, _analysis: &Analysis<'_>| {
+                        block.item.as_ref().map_or(0, |item| item_ids[item])
+                    }),
+                }),
+                // a bit per tag, in the order of `BlockTag`
+                Box::new(MultiByteGen {
+                    name: "tags".to_string(),
+                    list: self.blocks,
+                    mapping_fn: Box::new(move |block, _analysis: &Analysis<'_>| {
+                        block.tags.iter().fold(0u32, |mask, tag| {
+                            mask | 1 << tags.iter().position(|other| other == tag).unwrap()
+                        })
+                    }),
+                }),
                 Box::new(MultiByteGen {
                     name: "fields_present".to_string(),
                     list: self.blocks,
@@ -106,10 +158,16 @@ impl UnitGen for BlockDataRootUnit {
         let code = quote! {
             pub(crate) mod default_state;
             pub(crate) mod display_name;
+            pub(crate) mod friction;
             pub(crate) mod max_state;
             pub(crate) mod min_state;
             pub(crate) mod name;
             pub(crate) mod fields_present;
+            pub(crate) mod item;
+            pub(crate) mod jump_factor;
+            pub(crate) mod speed_factor;
+            pub(crate) mod tags;
+            pub(crate) mod translation_key;
         };
 
         Unit {
@@ -119,3 +177,44 @@ impl UnitGen for BlockDataRootUnit {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::{
+        analyzer::analyze,
+        generators::fixtures::{blocks, items, u16s, u32s, units},
+    };
+
+    #[test]
+    fn test_block_items() {
+        let blocks = blocks();
+        let items = items();
+        let analysis = analyze(&blocks, IndexMap::new());
+        let scope = BlockDataScope {
+            blocks: &blocks,
+            items: &items,
+        };
+        let units = units(&scope, &analysis);
+
+        // fire has no item, stored as 0 so `as_item` returns `None` for it
+        assert_eq!(u16s(&units["data/item"]), [0, 1, 0]);
+    }
+
+    #[test]
+    fn test_block_tags() {
+        let blocks = blocks();
+        let items = items();
+        let analysis = analyze(&blocks, IndexMap::new());
+        let scope = BlockDataScope {
+            blocks: &blocks,
+            items: &items,
+        };
+        let units = units(&scope, &analysis);
+
+        // a bit per tag, numbered in name order
+        assert_eq!(u32s(&units["data/tags"]), [0, 0b01, 0b10]);
+    }
+}
@@ -1,15 +1,20 @@
 mod data;
+mod tags;
 
 use crate::{
     analyzer::Analysis,
-    generators::{Scope, ScopeGen, Unit, UnitGen, block::data::BlockDataScope},
+    generators::{
+        Scope, ScopeGen, Unit, UnitGen,
+        block::{data::BlockDataScope, tags::BlockTagsUnit},
+    },
 };
 
-use mcre_data::block::Block;
+use mcre_data::{block::Block, item::Item};
 use quote::{format_ident, quote};
 
 pub struct BlockScope<'a> {
     pub blocks: &'a [Block],
+    pub items: &'a [Item],
 }
 
 impl<'a> ScopeGen<'a> for BlockScope<'a> {
@@ -23,9 +28,13 @@ impl<'a> ScopeGen<'a> for BlockScope<'a> {
                 Box::new(BlockConstsUnit {
                     blocks: self.blocks,
                 }),
+                Box::new(BlockTagsUnit {
+                    blocks: self.blocks,
+                }),
             ]),
             sub_scopes: Box::new([Box::new(BlockDataScope {
                 blocks: self.blocks,
+                items: self.items,
             })]),
         }
     }
@@ -41,8 +50,11 @@ impl UnitGen for BlockRootUnit<'_> {
         let code = quote! {
             mod data;
             mod consts;
+            mod tags;
 
-            use crate::{BlockState, FieldKey};
+            use crate::{BlockState, FieldKey, Item};
+
+            pub use tags::BlockTag;
             use serde::{Serialize, Deserialize};
 
             #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -77,6 +89,10 @@ impl UnitGen for BlockRootUnit<'_> {
                     data::display_name::get(self.0)
                 }
 
+                pub fn translation_key(self) -> &'static str {
+                    data::translation_key::get(self.0)
+                }
+
                 pub fn default_state(self) -> BlockState {
                     data::default_state::get(self.0).into()
                 }
@@ -89,6 +105,34 @@ impl UnitGen for BlockRootUnit<'_> {
                     data::max_state::get(self.0).into()
                 }
 
+                /// How much of its speed an entity keeps each tick on the
+                /// block, vanilla's `getFriction`.
+                pub fn friction(self) -> f32 {
+                    data::friction::get(self.0)
+                }
+
+                /// What walking speed is multiplied by in or on the block.
+                pub fn speed_factor(self) -> f32 {
+                    data::speed_factor::get(self.0)
+                }
+
+                /// What jump strength is multiplied by in or on the block.
+                pub fn jump_factor(self) -> f32 {
+                    data::jump_factor::get(self.0)
+                }
+
+                /// The item standing for the block, `None` for blocks like
+                /// fire that have none.
+                pub fn as_item(self) -> Option<Item> {
+                    let item = data::item::get(self.0);
+                    (item != 0).then(|| Item::from(item))
+                }
+
+                /// Whether the block is in `tag`.
+                pub fn is_in(self, tag: BlockTag) -> bool {
+                    (data::tags::get(self.0) >> (tag as u8)) & 1 == 1
+                }
+
                 pub fn is_field_present(self, field: FieldKey) -> bool {
                     let fields_present = data::fields_present::get(self.0);
                     ((fields_present >> (field as u8)) & 1) == 1
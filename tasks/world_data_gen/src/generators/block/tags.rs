@@ -0,0 +1,60 @@
+use std::collections::BTreeSet;
+
+use convert_case::ccase;
+use mcre_data::block::Block;
+use quote::{format_ident, quote};
+
+use crate::{
+    analyzer::Analysis,
+    generators::{Unit, UnitGen},
+};
+
+/// The tags the blocks are in, by name, which numbers their bits.
+pub fn tag_names(blocks: &[Block]) -> Vec<String> {
+    let names: BTreeSet<&String> = blocks.iter().flat_map(|block| &block.tags).collect();
+    assert!(names.len() <= 32, "too many block tags");
+    names.into_iter().cloned().collect()
+}
+
+pub struct BlockTagsUnit<'a> {
+    pub blocks: &'a [Block],
+}
+
+impl UnitGen for BlockTagsUnit<'_> {
+    fn generate(&self, _analysis: &Analysis) -> Unit {
+        let names = tag_names(self.blocks);
+        let idents = names
+            .iter()
+            .map(|name| format_ident!("{}", ccase!(pascal, name)))
+            .collect::<Vec<_>>();
+        let indices = (0..names.len() as u8).collect::<Vec<_>>();
+        let count = names.len();
+
+        let code = quote! {
+            #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+            #[repr(u8)]
+            pub enum BlockTag {
+                #( #idents = #indices, )*
+            }
+
+            impl BlockTag {
+                pub const ALL: [BlockTag; #count] = [
+                    #( Self::#idents, )*
+                ];
+
+                /// The name of the tag, without its namespace.
+                pub fn as_str(self) -> &'static str {
+                    match self {
+                        #( Self::#idents => #names, )*
+                    }
+                }
+            }
+        };
+
+        Unit {
+            name: "tags".to_string(),
+            code,
+            data: None,
+        }
+    }
+}
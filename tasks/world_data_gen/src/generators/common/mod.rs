@@ -1,7 +1,9 @@
 mod multi_byte;
+mod palette;
 mod string;
 mod sub_byte;
 
-pub use multi_byte::MultiByteGen;
+pub use multi_byte::{MultiByteGen, box_t_to_box_u8};
+pub use palette::PaletteGen;
 pub use string::StringGen;
 pub use sub_byte::SubByteGen;
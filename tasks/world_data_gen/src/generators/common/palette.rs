@@ -0,0 +1,63 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{
+    analyzer::Analysis,
+    generators::{Unit, UnitGen},
+};
+
+type MappingFn<'a, T, V> = Box<dyn Fn(&'a T) -> Option<V>>;
+
+/// An optional value that few elements have and fewer differ in, stored as a
+/// byte per element indexing a palette of the distinct values, with 0 for
+/// none. The palette is written out as code.
+pub struct PaletteGen<'a, T, V> {
+    pub name: String,
+    /// The type of the values, in scope of the generated code.
+    pub type_path: TokenStream,
+    pub list: &'a [T],
+    pub mapping_fn: MappingFn<'a, T, V>,
+    pub to_tokens: Box<dyn Fn(&V) -> TokenStream>,
+}
+
+impl<'a, T, V: PartialEq> UnitGen for PaletteGen<'a, T, V> {
+    fn generate(&self, _analysis: &Analysis) -> Unit {
+        let mut palette: Vec<V> = Vec::new();
+        let mut data = Vec::with_capacity(self.list.len());
+        for el in self.list {
+            let index = match (self.mapping_fn)(el) {
+                None => 0,
+                Some(value) => match palette.iter().position(|other| *other == value) {
+                    Some(index) => index + 1,
+                    None => {
+                        palette.push(value);
+                        palette.len()
+                    }
+                },
+            };
+            data.push(u8::try_from(index).expect("palette too large"));
+        }
+
+        let len = self.list.len();
+        let palette_len = palette.len();
+        let palette = palette.iter().map(&self.to_tokens);
+        let type_path = &self.type_path;
+        let data_path = format!("./{}.bin", self.name);
+
+        let code = quote! {
+            static PALETTE: [#type_path; #palette_len] = [#(#palette),*];
+            static VALUES: [u8; #len] = *include_bytes!(#data_path);
+
+            pub(crate) fn get(idx: u16) -> Option<#type_path> {
+                let index = VALUES[idx as usize].checked_sub(1)?;
+                Some(PALETTE[index as usize])
+            }
+        };
+
+        Unit {
+            name: self.name.clone(),
+            code,
+            data: Some(data.into_boxed_slice()),
+        }
+    }
+}
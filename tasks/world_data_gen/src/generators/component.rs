@@ -0,0 +1,103 @@
+use mcre_data::component::DataComponentType;
+use quote::{format_ident, quote};
+
+use crate::{
+    analyzer::Analysis,
+    generators::{Scope, ScopeGen, Unit, UnitGen},
+};
+
+/// The data component type registry, small enough to keep its names inline.
+pub struct ComponentScope<'a> {
+    pub components: &'a [DataComponentType],
+}
+
+impl<'a> ScopeGen<'a> for ComponentScope<'a> {
+    fn generate(&self, _analysis: &Analysis) -> Scope<'a> {
+        Scope {
+            name: "component".to_string(),
+            units: Box::new([Box::new(ComponentRootUnit {
+                components: self.components,
+            })]),
+            sub_scopes: Box::new([]),
+        }
+    }
+}
+
+pub struct ComponentRootUnit<'a> {
+    components: &'a [DataComponentType],
+}
+
+impl UnitGen for ComponentRootUnit<'_> {
+    fn generate(&self, _analysis: &Analysis) -> Unit {
+        let max = self.components.last().unwrap().id;
+        let count = self.components.len();
+        let names = self.components.iter().map(|component| &component.name);
+        let persistent = self.components.iter().map(|component| component.persistent);
+        let consts = self.components.iter().map(|component| {
+            let name = format_ident!("{}", component.name.to_uppercase().replace('/', "_"));
+            let id = component.id;
+            quote! {
+                pub const #name: Self = Self(#id);
+            }
+        });
+
+        let code = quote! {
+            use serde::{Serialize, Deserialize};
+
+            static NAMES: [&str; #count] = [#(#names),*];
+            static PERSISTENT: [bool; #count] = [#(#persistent),*];
+
+            /// A data component type, by its id in the registry.
+            #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+            pub struct DataComponentType(u16);
+
+            impl From<u16> for DataComponentType {
+                fn from(id: u16) -> Self {
+                    Self(id)
+                }
+            }
+
+            impl From<DataComponentType> for u16 {
+                fn from(id: DataComponentType) -> Self {
+                    id.0
+                }
+            }
+
+            impl DataComponentType {
+                pub const MAX: Self = Self(#max);
+
+                #( #consts )*
+
+                pub fn name(self) -> &'static str {
+                    NAMES[self.0 as usize]
+                }
+
+                /// Whether stacks save the component; transient ones only
+                /// live in memory.
+                pub fn is_persistent(self) -> bool {
+                    PERSISTENT[self.0 as usize]
+                }
+
+                /// Looks a component type up by its registry name, with or
+                /// without the `minecraft:` namespace.
+                pub fn from_name(name: &str) -> Option<Self> {
+                    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+                    NAMES
+                        .iter()
+                        .position(|entry| *entry == name)
+                        .map(|id| Self(id as u16))
+                }
+
+                pub fn all() -> impl ExactSizeIterator<Item = Self> {
+                    (0..=Self::MAX.0).map(Self)
+                }
+            }
+        };
+
+        Unit {
+            name: "mod".to_string(),
+            code,
+            data: None,
+        }
+    }
+}
@@ -45,6 +45,7 @@ impl UnitGen for FieldsUnit {
             })
             .collect::<Vec<_>>();
         let fields_indices = (0..(fields_idents.len() as u8)).collect::<Vec<_>>();
+        let fields_count = fields_idents.len();
 
         let fields_def = quote! {
             #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -53,6 +54,12 @@ impl UnitGen for FieldsUnit {
                 #( #fields_idents = #fields_indices, )*
             }
 
+            impl FieldKey {
+                pub const ALL: [FieldKey; #fields_count] = [
+                    #( Self::#fields_idents, )*
+                ];
+            }
+
             #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
             #[repr(u8)]
             pub enum FieldVal {
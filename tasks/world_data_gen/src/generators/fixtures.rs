@@ -0,0 +1,66 @@
+//! A few blocks and items to run generators on in tests.
+
+use std::collections::HashMap;
+
+use mcre_data::{block::Block, item::Item};
+
+use crate::{
+    analyzer::Analysis,
+    generators::{ScopeGen, Unit},
+};
+
+pub fn blocks() -> Vec<Block> {
+    serde_json::from_str(
+        r#"[
+            {"id":0,"name":"air","display_name":"Air","translation_key":"block.minecraft.air","default_state_id":0,"min_state_id":0,"max_state_id":0,"states":[],"friction":0.6,"speed_factor":1.0,"jump_factor":1.0,"item":"air","tags":[]},
+            {"id":1,"name":"stone","display_name":"Stone","translation_key":"block.minecraft.stone","default_state_id":1,"min_state_id":1,"max_state_id":1,"states":[],"friction":0.6,"speed_factor":1.0,"jump_factor":1.0,"item":"stone","tags":["base_stone_overworld"]},
+            {"id":2,"name":"fire","display_name":"Fire","translation_key":"block.minecraft.fire","default_state_id":2,"min_state_id":2,"max_state_id":2,"states":[],"friction":0.6,"speed_factor":1.0,"jump_factor":1.0,"tags":["fire"]}
+        ]"#,
+    )
+    .unwrap()
+}
+
+pub fn items() -> Vec<Item> {
+    serde_json::from_str(
+        r#"[
+            {"id":0,"name":"air","display_name":"Air","translation_key":"block.minecraft.air","max_stack_size":64,"block":"air","components":{"rarity":"common"}},
+            {"id":1,"name":"stone","display_name":"Stone","translation_key":"block.minecraft.stone","max_stack_size":64,"block":"stone","components":{"rarity":"common"}},
+            {"id":2,"name":"diamond_sword","display_name":"Diamond Sword","translation_key":"item.minecraft.diamond_sword","max_stack_size":1,"components":{"max_damage":1561,"rarity":"rare"}}
+        ]"#,
+    )
+    .unwrap()
+}
+
+/// Every unit of a scope and its sub-scopes by path, checking that the code
+/// parses, as the factory does before writing it.
+pub fn units(scope: &dyn ScopeGen, analysis: &Analysis) -> HashMap<String, Unit> {
+    let scope = scope.generate(analysis);
+    let mut all = HashMap::new();
+    for unit in &scope.units {
+        let unit = unit.generate(analysis);
+        syn::parse2::<syn::File>(unit.code.clone()).unwrap();
+        all.insert(format!("{}/{}", scope.name, unit.name), unit);
+    }
+    for sub_scope in &scope.sub_scopes {
+        for (path, unit) in units(sub_scope.as_ref(), analysis) {
+            all.insert(format!("{}/{path}", scope.name), unit);
+        }
+    }
+    all
+}
+
+/// The values of a `MultiByteGen` unit of `u16`s.
+pub fn u16s(unit: &Unit) -> Vec<u16> {
+    let data = unit.data.as_deref().unwrap();
+    data.chunks_exact(2)
+        .map(|bytes| u16::from_ne_bytes([bytes[0], bytes[1]]))
+        .collect()
+}
+
+/// The values of a `MultiByteGen` unit of `u32`s.
+pub fn u32s(unit: &Unit) -> Vec<u32> {
+    let data = unit.data.as_deref().unwrap();
+    data.chunks_exact(4)
+        .map(|bytes| u32::from_ne_bytes(bytes.try_into().unwrap()))
+        .collect()
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use crate::{
+    analyzer::Analysis,
+    generators::{
+        Scope, ScopeGen, Unit, UnitGen,
+        common::{MultiByteGen, PaletteGen, StringGen, SubByteGen},
+    },
+};
+
+use mcre_data::{block::Block, item::Item};
+use quote::quote;
+
+pub struct ItemDataScope<'a> {
+    pub items: &'a [Item],
+    pub blocks: &'a [Block],
+}
+
+impl<'a> ScopeGen<'a> for ItemDataScope<'a> {
+    fn generate(&self, _analysis: &Analysis) -> Scope<'a> {
+        let block_ids: HashMap<String, u16> = self
+            .blocks
+            .iter()
+            .map(|block| (block.name.clone(), block.id))
+            .collect();
+
+        Scope {
+            name: "data".to_string(),
+            units: Box::new([
+                Box::new(ItemDataRootUnit),
+                Box::new(StringGen {
+                    name: "name".to_string(),
+                    list: self.items,
+                    mapping_fn: Box::new(|item| &item.name),
+                }),
+                Box::new(StringGen {
+                    name: "display_name".to_string(),
+                    list: self.items,
+                    mapping_fn: Box::new(|item| &item.display_name),
+                }),
+                Box::new(StringGen {
+                    name: "translation_key".to_string(),
+                    list: self.items,
+                    mapping_fn: Box::new(|item| &item.translation_key),
+                }),
+                Box::new(SubByteGen {
+                    name: "max_stack_size".to_string(),
+                    is_bool: false,
+                    min: 1,
+                    max: 99,
+                    list: self.items,
+                    mapping_fn: Box::new(|item, _analysis: &Analysis<'_>| item.max_stack_size),
+                }),
+                Box::new(MultiByteGen {
+                    name: "max_damage".to_string(),
+                    list: self.items,
+                    mapping_fn: Box::new(|item, _analysis: &Analysis<'_>| {
+                        item.components.max_damage.unwrap_or(0)
+                    }),
+                }),
+                Box::new(MultiByteGen {
+                    name: "block".to_string(),
+                    list: self.items,
+                    mapping_fn: Box::new(move |item, _analysis: &Analysis<'_>| {
+                        item.block
+                            .as_ref()
+                            .map_or(u16::MAX, |block| block_ids[block])
+                    }),
+                }),
+                Box::new(PaletteGen {
+                    name: "food".to_string(),
+                    type_path: quote!(mcre_core::FoodProperties),
+                    list: self.items,
+                    mapping_fn: Box::new(|item| item.components.food),
+                    to_tokens: Box::new(|food| {
+                        let nutrition = food.nutrition;
+                        let saturation = food.saturation;
+                        let can_always_eat = food.can_always_eat;
+                        quote! {
+                            mcre_core::FoodProperties {
+                                nutrition: #nutrition,
+                                saturation: #saturation,
+                                can_always_eat: #can_always_eat,
+                            }
+                        }
+                    }),
+                }),
+                Box::new(PaletteGen {
+                    name: "tool".to_string(),
+                    type_path: quote!(mcre_core::Tool),
+                    list: self.items,
+                    mapping_fn: Box::new(|item| item.components.tool),
+                    to_tokens: Box::new(|tool| {
+                        let default_mining_speed = tool.default_mining_speed;
+                        let damage_per_block = tool.damage_per_block;
+                        let can_destroy_blocks_in_creative = tool.can_destroy_blocks_in_creative;
+                        quote! {
+                            mcre_core::Tool {
+                                default_mining_speed: #default_mining_speed,
+                                damage_per_block: #damage_per_block,
+                                can_destroy_blocks_in_creative: #can_destroy_blocks_in_creative,
+                            }
+                        }
+                    }),
+                }),
+                Box::new(SubByteGen {
+                    name: "rarity".to_string(),
+                    is_bool: false,
+                    min: 0,
+                    max: 3,
+                    list: self.items,
+                    mapping_fn: Box::new(|item, _analysis: &Analysis<'_>| {
+                        item.components.rarity as u8
+                    }),
+                }),
+            ]),
+            sub_scopes: Box::new([]),
+        }
+    }
+}
+
+pub struct ItemDataRootUnit;
+
+impl UnitGen for ItemDataRootUnit {
+    fn generate(&self, _analysis: &Analysis) -> Unit {
+        let code = quote! {
+            pub(crate) mod block;
+            pub(crate) mod display_name;
+            pub(crate) mod food;
+            pub(crate) mod max_damage;
+            pub(crate) mod max_stack_size;
+            pub(crate) mod name;
+            pub(crate) mod rarity;
+            pub(crate) mod tool;
+            pub(crate) mod translation_key;
+        };
+
+        Unit {
+            name: "mod".to_string(),
+            code,
+            data: None,
+        }
+    }
+}
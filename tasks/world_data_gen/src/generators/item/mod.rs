@@ -0,0 +1,227 @@
+mod data;
+
+use crate::{
+    analyzer::Analysis,
+    generators::{Scope, ScopeGen, Unit, UnitGen, item::data::ItemDataScope},
+};
+
+use mcre_data::{block::Block, item::Item};
+use quote::{format_ident, quote};
+
+pub struct ItemScope<'a> {
+    pub items: &'a [Item],
+    pub blocks: &'a [Block],
+}
+
+impl<'a> ScopeGen<'a> for ItemScope<'a> {
+    fn generate(&self, _analysis: &Analysis) -> Scope<'a> {
+        Scope {
+            name: "item".to_string(),
+            units: Box::new([
+                Box::new(ItemRootUnit { items: self.items }),
+                Box::new(ItemConstsUnit { items: self.items }),
+            ]),
+            sub_scopes: Box::new([Box::new(ItemDataScope {
+                items: self.items,
+                blocks: self.blocks,
+            })]),
+        }
+    }
+}
+
+pub struct ItemRootUnit<'a> {
+    items: &'a [Item],
+}
+
+impl UnitGen for ItemRootUnit<'_> {
+    fn generate(&self, _analysis: &Analysis) -> Unit {
+        let max = self.items.last().unwrap().id;
+        let code = quote! {
+            mod data;
+            mod consts;
+
+            use crate::Block;
+            use mcre_core::{FoodProperties, Rarity, Tool};
+            use serde::{Serialize, Deserialize};
+
+            #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+            pub struct Item(u16);
+
+            impl From<u16> for Item {
+                fn from(id: u16) -> Self {
+                    Self(id)
+                }
+            }
+
+            impl From<Item> for u16 {
+                fn from(id: Item) -> Self {
+                    id.0
+                }
+            }
+
+            impl Item {
+                pub const MAX: Self = Self(#max);
+
+                pub fn name(self) -> &'static str {
+                    data::name::get(self.0)
+                }
+
+                pub fn display_name(self) -> &'static str {
+                    data::display_name::get(self.0)
+                }
+
+                pub fn translation_key(self) -> &'static str {
+                    data::translation_key::get(self.0)
+                }
+
+                pub fn max_stack_size(self) -> u8 {
+                    data::max_stack_size::get(self.0)
+                }
+
+                /// How much damage a stack takes before it breaks, `None`
+                /// for items that cannot be damaged.
+                pub fn max_damage(self) -> Option<u16> {
+                    let max_damage = data::max_damage::get(self.0);
+                    (max_damage != 0).then_some(max_damage)
+                }
+
+                /// The block the item places, for block items.
+                pub fn block(self) -> Option<Block> {
+                    let block = data::block::get(self.0);
+                    (block != u16::MAX).then(|| Block::from(block))
+                }
+
+                pub fn food(self) -> Option<FoodProperties> {
+                    data::food::get(self.0)
+                }
+
+                pub fn tool(self) -> Option<Tool> {
+                    data::tool::get(self.0)
+                }
+
+                pub fn rarity(self) -> Rarity {
+                    unsafe { core::mem::transmute::<u8, Rarity>(data::rarity::get(self.0)) }
+                }
+
+                /// Looks an item up by its registry name, with or without the
+                /// `minecraft:` namespace.
+                pub fn from_name(name: &str) -> Option<Item> {
+                    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+                    Item::all().find(|item| item.name() == name)
+                }
+
+                pub fn all() -> impl Iterator<Item = Self> {
+                    ItemIter::new(Item(0), Self::MAX)
+                }
+            }
+
+            pub struct ItemIter {
+                current: u16,
+                end: u16,
+            }
+
+            impl ItemIter {
+                // inclusive range
+                pub fn new(start: Item, end: Item) -> Self {
+                    Self {
+                        current: start.0,
+                        end: end.0,
+                    }
+                }
+            }
+
+            impl Iterator for ItemIter {
+                type Item = Item;
+
+                fn next(&mut self) -> Option<Self::Item> {
+                    if self.current > self.end {
+                        None
+                    } else {
+                        let id = self.current;
+                        self.current += 1;
+                        Some(Item(id))
+                    }
+                }
+
+                fn size_hint(&self) -> (usize, Option<usize>) {
+                    let remaining = if self.current > self.end {
+                        0
+                    } else {
+                        (self.end - self.current + 1) as usize
+                    };
+                    (remaining, Some(remaining))
+                }
+            }
+
+            impl ExactSizeIterator for ItemIter {}
+        };
+
+        Unit {
+            name: "mod".to_string(),
+            code,
+            data: None,
+        }
+    }
+}
+
+pub struct ItemConstsUnit<'a> {
+    items: &'a [Item],
+}
+
+impl UnitGen for ItemConstsUnit<'_> {
+    fn generate(&self, _analysis: &Analysis) -> Unit {
+        let consts = self.items.iter().map(|item| {
+            let name = format_ident!("{}", item.name.to_uppercase());
+            let id = item.id;
+            quote! {
+                pub const #name: Self = Self(#id);
+            }
+        });
+        let code = quote! {
+            use super::Item;
+
+            impl Item {
+                #( #consts )*
+            }
+        };
+
+        Unit {
+            name: "consts".to_string(),
+            code,
+            data: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::{
+        analyzer::analyze,
+        generators::fixtures::{blocks, items, u16s, units},
+    };
+
+    #[test]
+    fn test_item_scope() {
+        let blocks = blocks();
+        let items = items();
+        let analysis = analyze(&blocks, IndexMap::new());
+        let scope = ItemScope {
+            items: &items,
+            blocks: &blocks,
+        };
+        let units = units(&scope, &analysis);
+
+        let consts = units["item/consts"].code.to_string();
+        assert!(consts.contains("pub const DIAMOND_SWORD : Self = Self (2u16)"));
+        assert_eq!(u16s(&units["item/data/block"]), [0, 1, u16::MAX]);
+        assert_eq!(u16s(&units["item/data/max_damage"]), [0, 0, 1561]);
+        // 1 to 99 takes a whole byte, which is stored as is
+        assert_eq!(
+            units["item/data/max_stack_size"].data.as_deref(),
+            Some(&[64, 64, 1][..])
+        );
+    }
+}
@@ -7,8 +7,13 @@ use crate::analyzer::Analysis;
 
 mod block;
 pub mod common;
+mod component;
 mod fields;
+#[cfg(test)]
+mod fixtures;
+mod item;
 mod props;
+mod remap;
 mod root;
 mod state;
 
@@ -120,8 +120,22 @@ impl UnitGen for PropsUnit {
                 None
             }
         });
+        let props_value_displays = analysis
+            .prop_schema
+            .values()
+            .map(|schema| match schema {
+                PropSchema::Bool | PropSchema::Int(_, _) => quote! { v },
+                PropSchema::Enums { .. } => quote! { v.as_str() },
+            })
+            .collect::<Vec<_>>();
         let props_literals = analysis.prop_schema.keys().collect::<Vec<_>>();
+        let props_display_formats = analysis
+            .prop_schema
+            .keys()
+            .map(|key| format!("{key}={{}}"))
+            .collect::<Vec<_>>();
         let props_indices = (0..(props_idents.len() as u8)).collect::<Vec<_>>();
+        let props_count = props_idents.len();
 
         let prop_def = quote! {
             #( #extra_enums )*
@@ -145,6 +159,10 @@ impl UnitGen for PropsUnit {
             }
 
             impl PropKey {
+                pub const ALL: [PropKey; #props_count] = [
+                    #( Self::#props_idents, )*
+                ];
+
                 pub fn as_str(self) -> &'static str {
                     match self {
                         #( Self::#props_idents => #props_literals, )*
@@ -214,6 +232,14 @@ impl UnitGen for PropsUnit {
                     Self::parse_with_key(key, parts[1]).ok_or(())
                 }
             }
+
+            impl core::fmt::Display for PropVal {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    match self {
+                        #( Self::#props_idents(v) => write!(f, #props_display_formats, #props_value_displays), )*
+                    }
+                }
+            }
         };
 
         let code = quote! {
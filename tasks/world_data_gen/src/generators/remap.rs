@@ -0,0 +1,102 @@
+use mcre_data::{remap::StateIdRemap, versions::OtherVersion};
+use quote::{format_ident, quote};
+
+use crate::{
+    analyzer::Analysis,
+    generators::{Scope, ScopeGen, Unit, UnitGen, common::box_t_to_box_u8},
+};
+
+/// The state id tables of the other versions, each mapping their ids onto the
+/// current ones.
+pub struct RemapScope<'a> {
+    pub versions: &'a [(OtherVersion, StateIdRemap)],
+}
+
+impl<'a> ScopeGen<'a> for RemapScope<'a> {
+    fn generate(&self, _analysis: &Analysis) -> Scope<'a> {
+        let mut units: Vec<Box<dyn UnitGen + 'a>> = vec![Box::new(RemapRootUnit {
+            versions: self.versions,
+        })];
+        for (version, remap) in self.versions {
+            units.push(Box::new(RemapTableUnit {
+                protocol: version.protocol,
+                remap,
+            }));
+        }
+        Scope {
+            name: "remap".to_string(),
+            units: units.into_boxed_slice(),
+            sub_scopes: Box::new([]),
+        }
+    }
+}
+
+fn module_name(protocol: i32) -> String {
+    format!("v{protocol}")
+}
+
+pub struct RemapRootUnit<'a> {
+    versions: &'a [(OtherVersion, StateIdRemap)],
+}
+
+impl UnitGen for RemapRootUnit<'_> {
+    fn generate(&self, _analysis: &Analysis) -> Unit {
+        let modules = self
+            .versions
+            .iter()
+            .map(|(version, _)| format_ident!("{}", module_name(version.protocol)));
+        let tables = self.versions.iter().map(|(version, _)| {
+            let module = format_ident!("{}", module_name(version.protocol));
+            let name = &version.name;
+            let protocol = version.protocol;
+            let state_count = version.data.states.len() as u16;
+            quote! {
+                StateIdTable::new(#name, #protocol, #state_count, &#module::RUNS)
+            }
+        });
+
+        let code = quote! {
+            #(mod #modules;)*
+
+            use crate::StateIdTable;
+
+            pub(crate) static TABLES: &[StateIdTable] = &[#(#tables),*];
+        };
+
+        Unit {
+            name: "mod".to_string(),
+            code,
+            data: None,
+        }
+    }
+}
+
+pub struct RemapTableUnit<'a> {
+    protocol: i32,
+    remap: &'a StateIdRemap,
+}
+
+impl UnitGen for RemapTableUnit<'_> {
+    fn generate(&self, _analysis: &Analysis) -> Unit {
+        let runs: Box<[[u16; 3]]> = self
+            .remap
+            .runs()
+            .iter()
+            .map(|run| [run.from, run.to, run.len])
+            .collect();
+        let len = runs.len();
+        let name = module_name(self.protocol);
+        let data_path = format!("./{name}.bin");
+
+        let code = quote! {
+            pub(super) static RUNS: [[u16; 3]; #len] =
+                unsafe { core::mem::transmute(*include_bytes!(#data_path)) };
+        };
+
+        Unit {
+            name,
+            code,
+            data: Some(box_t_to_box_u8(runs)),
+        }
+    }
+}
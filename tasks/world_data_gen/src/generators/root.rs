@@ -1,17 +1,24 @@
 use crate::{
     analyzer::Analysis,
     generators::{
-        Scope, ScopeGen, Unit, UnitGen, block::BlockScope, fields::FieldsUnit, props::PropsUnit,
+        Scope, ScopeGen, Unit, UnitGen, block::BlockScope, component::ComponentScope,
+        fields::FieldsUnit, item::ItemScope, props::PropsUnit, remap::RemapScope,
         state::StateScope,
     },
 };
 
-use mcre_data::{block::Block, state::BlockState};
+use mcre_data::{
+    block::Block, component::DataComponentType, item::Item, remap::StateIdRemap, state::BlockState,
+    versions::OtherVersion,
+};
 use quote::quote;
 
 pub struct RootScope<'a> {
     pub blocks: &'a [Block],
     pub states: &'a [BlockState],
+    pub items: &'a [Item],
+    pub components: &'a [DataComponentType],
+    pub versions: &'a [(OtherVersion, StateIdRemap)],
 }
 
 impl<'a> ScopeGen<'a> for RootScope<'a> {
@@ -26,10 +33,21 @@ impl<'a> ScopeGen<'a> for RootScope<'a> {
             sub_scopes: Box::new([
                 Box::new(BlockScope {
                     blocks: self.blocks,
+                    items: self.items,
                 }),
                 Box::new(StateScope {
                     states: self.states,
                 }),
+                Box::new(ItemScope {
+                    items: self.items,
+                    blocks: self.blocks,
+                }),
+                Box::new(ComponentScope {
+                    components: self.components,
+                }),
+                Box::new(RemapScope {
+                    versions: self.versions,
+                }),
             ]),
         }
     }
@@ -41,11 +59,16 @@ impl UnitGen for RootUnit {
     fn generate(&self, _analysis: &Analysis) -> Unit {
         let code = quote! {
             mod block;
+            mod item;
+            mod component;
             mod state;
             mod props;
             mod fields;
+            pub(crate) mod remap;
 
             pub use block::*;
+            pub use item::*;
+            pub use component::*;
             pub use state::*;
             pub use props::*;
             pub use fields::*;
@@ -1,4 +1,7 @@
 mod fields;
+mod shapes;
+
+use std::rc::Rc;
 
 use mcre_data::state::BlockState;
 use quote::quote;
@@ -8,7 +11,10 @@ use crate::{
     generators::{
         Scope, ScopeGen, Unit, UnitGen,
         common::{MultiByteGen, SubByteGen},
-        state::data::fields::StateFieldsDataScope,
+        state::data::{
+            fields::StateFieldsDataScope,
+            shapes::{ShapePalette, ShapesUnit},
+        },
     },
 };
 
@@ -18,6 +24,17 @@ pub struct StateDataScope<'a> {
 
 impl<'a> ScopeGen<'a> for StateDataScope<'a> {
     fn generate(&self, _analysis: &Analysis) -> Scope<'a> {
+        let shapes = Rc::new(ShapePalette::new(self.states.iter().flat_map(|state| {
+            [
+                state.outline_shape.as_slice(),
+                state.collision_shape.as_slice(),
+                state.occlusion_shape.as_slice(),
+            ]
+        })));
+        let outline_shapes = shapes.clone();
+        let collision_shapes = shapes.clone();
+        let occlusion_shapes = shapes.clone();
+
         Scope {
             name: "data".to_string(),
             units: Box::new([
@@ -137,6 +154,16 @@ impl<'a> ScopeGen<'a> for StateDataScope<'a> {
                         state.requires_correct_tool_for_drops as u8
                     }),
                 }),
+                Box::new(SubByteGen {
+                    name: "blocks_motion".to_string(),
+                    is_bool: true,
+                    min: 0,
+                    max: 1,
+                    list: self.states,
+                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
+                        state.blocks_motion as u8
+                    }),
+                }),
                 Box::new(MultiByteGen {
                     name: "destroy_speed".to_string(),
                     list: self.states,
@@ -164,6 +191,38 @@ impl<'a> ScopeGen<'a> for StateDataScope<'a> {
                         state.max_vertical_offset
                     }),
                 }),
+                Box::new(SubByteGen {
+                    name: "fluid_state".to_string(),
+                    is_bool: false,
+                    min: 0,
+                    max: u8::MAX,
+                    list: self.states,
+                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
+                        state.fluid_state.to_u8()
+                    }),
+                }),
+                Box::new(ShapesUnit { palette: shapes }),
+                Box::new(MultiByteGen {
+                    name: "outline_shape".to_string(),
+                    list: self.states,
+                    mapping_fn: Box::new(move |state, _analysis: &Analysis<'_>| {
+                        outline_shapes.id(&state.outline_shape)
+                    }),
+                }),
+                Box::new(MultiByteGen {
+                    name: "collision_shape".to_string(),
+                    list: self.states,
+                    mapping_fn: Box::new(move |state, _analysis: &Analysis<'_>| {
+                        collision_shapes.id(&state.collision_shape)
+                    }),
+                }),
+                Box::new(MultiByteGen {
+                    name: "occlusion_shape".to_string(),
+                    list: self.states,
+                    mapping_fn: Box::new(move |state, _analysis: &Analysis<'_>| {
+                        occlusion_shapes.id(&state.occlusion_shape)
+                    }),
+                }),
             ]),
             sub_scopes: Box::new([Box::new(StateFieldsDataScope {
                 states: self.states,
@@ -178,8 +237,11 @@ impl UnitGen for StateDataRootUnit {
     fn generate(&self, _analysis: &Analysis) -> Unit {
         let code = quote! {
             pub(crate) mod block;
+            pub(crate) mod blocks_motion;
             pub(crate) mod can_occlude;
+            pub(crate) mod collision_shape;
             pub(crate) mod destroy_speed;
+            pub(crate) mod fluid_state;
             pub(crate) mod ignited_by_lava;
             pub(crate) mod is_air;
             pub(crate) mod is_randomly_ticking;
@@ -187,10 +249,13 @@ impl UnitGen for StateDataRootUnit {
             pub(crate) mod light_emission;
             pub(crate) mod max_horizontal_offset;
             pub(crate) mod max_vertical_offset;
+            pub(crate) mod occlusion_shape;
             pub(crate) mod offset_type;
+            pub(crate) mod outline_shape;
             pub(crate) mod propagates_skylight_down;
             pub(crate) mod replaceable;
             pub(crate) mod requires_correct_tool_for_drops;
+            pub(crate) mod shapes;
             pub(crate) mod solid_render;
             pub(crate) mod spawn_terrain_particles;
             pub(crate) mod use_shape_for_light_occlusion;
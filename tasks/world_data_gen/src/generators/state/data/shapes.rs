@@ -0,0 +1,119 @@
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    rc::Rc,
+};
+
+use quote::quote;
+
+use crate::{
+    analyzer::Analysis,
+    generators::{Unit, UnitGen, common::box_t_to_box_u8},
+};
+
+type Box6 = [f64; 6];
+
+/// The distinct shapes of the states, numbered in the order they are first
+/// met, with the empty shape as 0.
+pub struct ShapePalette {
+    shapes: Vec<Vec<Box6>>,
+    ids: HashMap<Vec<[u64; 6]>, u16>,
+}
+
+impl ShapePalette {
+    pub fn new<'s>(shapes: impl IntoIterator<Item = &'s [Box6]>) -> Self {
+        let mut palette = Self {
+            shapes: Vec::new(),
+            ids: HashMap::new(),
+        };
+        palette.insert(&[]);
+        for shape in shapes {
+            palette.insert(shape);
+        }
+        palette
+    }
+
+    fn insert(&mut self, shape: &[Box6]) {
+        let id = u16::try_from(self.shapes.len()).expect("too many shapes");
+        if let Entry::Vacant(entry) = self.ids.entry(key(shape)) {
+            entry.insert(id);
+            self.shapes.push(shape.to_vec());
+        }
+    }
+
+    pub fn id(&self, shape: &[Box6]) -> u16 {
+        self.ids[&key(shape)]
+    }
+}
+
+fn key(shape: &[Box6]) -> Vec<[u64; 6]> {
+    shape
+        .iter()
+        .map(|bounds| bounds.map(f64::to_bits))
+        .collect()
+}
+
+/// The boxes of every shape in a [`ShapePalette`], one after the other, and
+/// where each shape starts among them.
+pub struct ShapesUnit {
+    pub palette: Rc<ShapePalette>,
+}
+
+impl UnitGen for ShapesUnit {
+    fn generate(&self, _analysis: &Analysis) -> Unit {
+        let mut boxes = Vec::new();
+        let mut starts = vec![0u16];
+        for shape in &self.palette.shapes {
+            boxes.extend_from_slice(shape);
+            starts.push(u16::try_from(boxes.len()).expect("too many boxes"));
+        }
+
+        let len = boxes.len();
+        let starts_len = starts.len();
+        let code = quote! {
+            static BOXES: [[f64; 6]; #len] =
+                unsafe { core::mem::transmute(*include_bytes!("./shapes.bin")) };
+            static STARTS: [u16; #starts_len] = [#(#starts),*];
+
+            pub(crate) fn get(shape: u16) -> &'static [[f64; 6]] {
+                let shape = shape as usize;
+                &BOXES[STARTS[shape] as usize..STARTS[shape + 1] as usize]
+            }
+        };
+
+        Unit {
+            name: "shapes".to_string(),
+            code,
+            data: Some(box_t_to_box_u8(boxes.into_boxed_slice())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::{analyzer::analyze, generators::fixtures::blocks};
+
+    #[test]
+    fn test_shape_palette() {
+        let full = [[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]];
+        let slab = [[0.0, 0.0, 0.0, 1.0, 0.5, 1.0]];
+        let palette = Rc::new(ShapePalette::new([full.as_slice(), &slab, &full, &[]]));
+        assert_eq!(palette.id(&[]), 0);
+        assert_eq!(palette.id(&full), 1);
+        assert_eq!(palette.id(&slab), 2);
+
+        let blocks = blocks();
+        let analysis = analyze(&blocks, IndexMap::new());
+        let unit = ShapesUnit { palette }.generate(&analysis);
+        syn::parse2::<syn::File>(unit.code).unwrap();
+        let bounds: Vec<f64> = unit
+            .data
+            .unwrap()
+            .chunks_exact(8)
+            .map(|bytes| f64::from_ne_bytes(bytes.try_into().unwrap()))
+            .collect();
+        assert_eq!(bounds, [full[0], slab[0]].concat());
+    }
+}
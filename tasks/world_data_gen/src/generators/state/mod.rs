@@ -137,7 +137,7 @@ impl UnitGen for StateRootUnit<'_> {
 
             use crate::{Block, FieldKey, FieldVal, PropKey, PropVal};
 
-            use mcre_core::OffsetType;
+            use mcre_core::{FluidState, OffsetType};
             use serde::{Serialize, Deserialize};
 
             pub use enums::*;
@@ -216,6 +216,12 @@ impl UnitGen for StateRootUnit<'_> {
                     data::destroy_speed::get(self.0)
                 }
 
+                /// Whether entities are stopped by the state, vanilla's
+                /// `blocksMotion`.
+                pub fn blocks_motion(self) -> bool {
+                    data::blocks_motion::get(self.0)
+                }
+
                 pub fn offset_type(self) -> OffsetType {
                     unsafe { core::mem::transmute::<u8, OffsetType>(data::offset_type::get(self.0)) }
                 }
@@ -228,6 +234,25 @@ impl UnitGen for StateRootUnit<'_> {
                     data::max_vertical_offset::get(self.0)
                 }
 
+                pub fn fluid_state(self) -> FluidState {
+                    FluidState::from_u8(data::fluid_state::get(self.0))
+                }
+
+                /// The boxes of the outline shape, before the random offset.
+                pub(crate) fn outline_boxes(self) -> &'static [[f64; 6]] {
+                    data::shapes::get(data::outline_shape::get(self.0))
+                }
+
+                /// The boxes of the collision shape, before the random offset.
+                pub(crate) fn collision_boxes(self) -> &'static [[f64; 6]] {
+                    data::shapes::get(data::collision_shape::get(self.0))
+                }
+
+                /// The boxes of the shape that blocks light on its faces.
+                pub(crate) fn occlusion_boxes(self) -> &'static [[f64; 6]] {
+                    data::shapes::get(data::occlusion_shape::get(self.0))
+                }
+
                 pub fn get_field(self, field: FieldKey) -> Option<FieldVal> {
                     if !self.block().is_field_present(field) {
                         return None;
@@ -279,7 +304,11 @@ impl UnitGen for StateRootUnit<'_> {
                 }
 
                 fn size_hint(&self) -> (usize, Option<usize>) {
-                    let remaining = (self.end - self.current) as usize;
+                    let remaining = if self.current > self.end {
+                        0
+                    } else {
+                        (self.end - self.current + 1) as usize
+                    };
                     (remaining, Some(remaining))
                 }
             }
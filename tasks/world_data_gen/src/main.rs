@@ -4,7 +4,10 @@ mod generators;
 use std::path::PathBuf;
 
 use indexmap::IndexMap;
-use mcre_data::{block::Block, state::BlockState};
+use mcre_data::{
+    block::Block, component::DataComponentType, diff::DataSet, item::Item, state::BlockState,
+    versions::OtherVersion,
+};
 
 use crate::{
     analyzer::analyze,
@@ -13,8 +16,26 @@ use crate::{
 
 #[tokio::main]
 async fn main() {
-    let blocks = Block::all().await.unwrap();
-    let block_states = BlockState::all().await.unwrap();
+    let current = DataSet {
+        blocks: Block::all().await.unwrap(),
+        states: BlockState::all().await.unwrap(),
+    };
+    // written by data_gen, like the block data, but missing from data
+    // generated before items were
+    let items = Item::all()
+        .await
+        .expect("no item data; run data_gen to write crates/mcre_data/items.json");
+    let components = DataComponentType::all().await.expect(
+        "no data component data; run data_gen to write crates/mcre_data/data_components.json",
+    );
+    let versions: Vec<_> = OtherVersion::all()
+        .unwrap()
+        .into_iter()
+        .map(|version| {
+            let remap = version.remap(&current);
+            (version, remap)
+        })
+        .collect();
 
     let mut foreign_enums: IndexMap<&str, Box<[&str]>> = IndexMap::new();
 
@@ -24,15 +45,18 @@ async fn main() {
     );
     foreign_enums.insert("Axis", Box::new(["x", "y", "z"]));
 
-    let analysis = analyze(&blocks, foreign_enums);
+    let analysis = analyze(&current.blocks, foreign_enums);
 
     let mut factory = Factory::new(
         PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../crates/mcre_world/src/data"),
     );
 
     factory.add_scope(RootScope {
-        blocks: &blocks,
-        states: &block_states,
+        blocks: &current.blocks,
+        states: &current.states,
+        items: &items,
+        components: &components,
+        versions: &versions,
     });
 
     factory.generate(&analysis).await;